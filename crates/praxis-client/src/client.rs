@@ -0,0 +1,283 @@
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::error::{ApiError, Problem};
+use praxis_graph::wire::{self, StreamEvent};
+use crate::sse::{parse_frames, IdentifiedEvent};
+use crate::types::{CreateThreadRequest, HydratePage, MessageList, SendMessageRequest, Thread, ThreadList};
+
+/// Header naming the caller's tenant (see the API's `auth` module)
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Reconnects tried in a row before a dropped message stream fails
+const DEFAULT_RECONNECT_ATTEMPTS: u32 = 3;
+
+/// Wait before the first reconnect; each later one waits one step more
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// Events of one message stream
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// Client of the Praxis REST API
+#[derive(Debug, Clone)]
+pub struct PraxisClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    tenant: Option<String>,
+    idle_timeout: Option<Duration>,
+    reconnect_attempts: u32,
+}
+
+impl PraxisClient {
+    /// `api_key` is sent as a bearer token; empty sends none
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        let api_key = api_key.into();
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: (!api_key.is_empty()).then_some(api_key),
+            tenant: None,
+            idle_timeout: None,
+            reconnect_attempts: DEFAULT_RECONNECT_ATTEMPTS,
+        }
+    }
+
+    /// Send every request on behalf of `tenant`
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// End message streams with `StreamStalled` once they send nothing for
+    /// `timeout`
+    ///
+    /// Runs can be silent for a long time (queued, or inside a slow tool),
+    /// so set it above the server's `[stream] keep_alive_secs`.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Reconnect a dropped message stream up to `attempts` times in a row
+    /// (3 by default; 0 turns reconnecting off)
+    ///
+    /// The count starts over whenever an event arrives, so a long run
+    /// survives several drops.
+    pub fn with_reconnect(mut self, attempts: u32) -> Self {
+        self.reconnect_attempts = attempts;
+        self
+    }
+
+    /// Use a preconfigured `reqwest` client (proxies, TLS, connect timeout)
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub async fn create_thread(&self, request: &CreateThreadRequest) -> Result<Thread> {
+        let response = self.send(self.request(Method::POST, "/threads").json(request)).await?;
+        json(response).await
+    }
+
+    pub async fn get_thread(&self, thread_id: &str) -> Result<Thread> {
+        let response = self.send(self.request(Method::GET, &format!("/threads/{}", thread_id))).await?;
+        json(response).await
+    }
+
+    /// Most recently updated threads of `user_id` first
+    pub async fn list_threads(&self, user_id: &str, limit: Option<u32>) -> Result<ThreadList> {
+        let mut request = self.request(Method::GET, "/threads").query(&[("user_id", user_id)]);
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        json(self.send(request).await?).await
+    }
+
+    /// Summarize a thread's history now (needs an admin key)
+    pub async fn summarize_thread(&self, thread_id: &str) -> Result<Thread> {
+        let response = self.send(self.request(Method::POST, &format!("/threads/{}/summarize", thread_id))).await?;
+        json(response).await
+    }
+
+    pub async fn delete_thread(&self, thread_id: &str) -> Result<()> {
+        self.send(self.request(Method::DELETE, &format!("/threads/{}", thread_id))).await?;
+        Ok(())
+    }
+
+    /// Stored messages, newest first; `before` pages back from a message id
+    pub async fn list_messages(&self, thread_id: &str, limit: Option<u32>, before: Option<&str>) -> Result<MessageList> {
+        let mut request = self.request(Method::GET, &format!("/threads/{}/messages", thread_id));
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        if let Some(before) = before {
+            request = request.query(&[("before", before)]);
+        }
+        json(self.send(request).await?).await
+    }
+
+    /// Thread history as stream events, oldest run first; pass the previous
+    /// page's `next_cursor` for the next one
+    pub async fn hydrate_thread(&self, thread_id: &str, cursor: Option<&str>) -> Result<HydratePage> {
+        let mut request = self.request(Method::GET, &format!("/threads/{}/hydrate", thread_id));
+        if let Some(cursor) = cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        json(self.send(request).await?).await
+    }
+
+    /// Send a message and stream the run it starts
+    ///
+    /// Fails before streaming if the API rejects the request. The run
+    /// doesn't depend on the connection: if it drops or stalls before the
+    /// run ends, the stream reconnects with `Last-Event-ID` and goes on from
+    /// the next event (see `with_reconnect`). Sending again would start
+    /// another run.
+    pub async fn send_message_stream(&self, thread_id: &str, request: &SendMessageRequest) -> Result<EventStream> {
+        let request = self
+            .request(Method::POST, &format!("/threads/{}/messages", thread_id))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(request);
+        let response = self.send(request).await?;
+        Ok(self.resumable(response))
+    }
+
+    /// Send a message to the thread of the external `conversation_key`
+    /// (e.g. a Slack channel id), creating the thread on first use, and
+    /// stream the run it starts
+    ///
+    /// Otherwise the same as `send_message_stream`. A key already used by
+    /// another user's thread fails with a 409.
+    pub async fn send_conversation_message_stream(
+        &self,
+        conversation_key: &str,
+        request: &SendMessageRequest,
+    ) -> Result<EventStream> {
+        let request = self
+            .request(Method::POST, &format!("/conversations/{}/messages", path_segment(conversation_key)))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(request);
+        let response = self.send(request).await?;
+        Ok(self.resumable(response))
+    }
+
+    /// Events of a message stream, reconnecting through `/streams/{id}`
+    /// until the run's terminal `info` or `error` event arrives
+    ///
+    /// Without event ids (an older server) a drop ends the stream as before.
+    fn resumable(&self, response: Response) -> EventStream {
+        let client = self.clone();
+        Box::pin(async_stream::stream! {
+            let mut frames = parse_frames(response.bytes_stream(), client.idle_timeout);
+            let mut last_id: Option<String> = None;
+            let mut ended = false;
+            let mut attempts = 0;
+            loop {
+                let mut dropped = None;
+                while let Some(frame) = frames.next().await {
+                    match frame {
+                        Ok(IdentifiedEvent { id, event }) => {
+                            if id.is_some() {
+                                last_id = id;
+                                attempts = 0;
+                            }
+                            ended |= matches!(event, Ok(StreamEvent::Info {} | StreamEvent::Error(_)));
+                            yield event;
+                        }
+                        Err(e) => dropped = Some(e),
+                    }
+                }
+                let resume_from = last_id.as_deref().filter(|_| !ended).and_then(wire::parse_event_id);
+                let Some((stream_id, _)) = resume_from else {
+                    if let Some(e) = dropped {
+                        yield Err(e);
+                    }
+                    break;
+                };
+                let (stream_id, after) = (stream_id.to_string(), last_id.clone().unwrap_or_default());
+                let resumed = loop {
+                    if attempts >= client.reconnect_attempts {
+                        break Err(dropped.take().unwrap_or_else(|| anyhow::anyhow!("Message stream ended before the run")));
+                    }
+                    attempts += 1;
+                    tokio::time::sleep(RECONNECT_DELAY * attempts).await;
+                    match client.resume(&stream_id, &after).await {
+                        Ok(response) => break Ok(response),
+                        // The stream is gone (expired, or another server)
+                        Err(e) if e.downcast_ref::<ApiError>().is_some() => break Err(e),
+                        Err(e) => dropped = Some(e),
+                    }
+                };
+                match resumed {
+                    Ok(response) => frames = parse_frames(response.bytes_stream(), client.idle_timeout),
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Reopen the stream `stream_id` after the event `last_event_id`
+    async fn resume(&self, stream_id: &str, last_event_id: &str) -> Result<Response> {
+        let request = self
+            .request(Method::GET, &format!("/streams/{}", path_segment(stream_id)))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .header("last-event-id", last_event_id);
+        self.send(request).await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self.http.request(method, format!("{}{}", self.base_url, path));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        if let Some(tenant) = &self.tenant {
+            request = request.header(TENANT_HEADER, tenant);
+        }
+        request
+    }
+
+    /// Send `request`, turning error statuses into `ApiError`
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await.context("Failed to reach the Praxis API")?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let problem = response.json::<Problem>().await.ok();
+        Err(ApiError { status: status.as_u16(), problem }.into())
+    }
+}
+
+async fn json<T: DeserializeOwned>(response: Response) -> Result<T> {
+    response.json().await.context("Invalid response body")
+}
+
+/// `value` percent-encoded for use as one path segment
+fn path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversation_keys_are_one_path_segment() {
+        assert_eq!(path_segment("C024BE91L"), "C024BE91L");
+        assert_eq!(path_segment("team/chan 1"), "team%2Fchan%201");
+    }
+}
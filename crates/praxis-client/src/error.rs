@@ -0,0 +1,41 @@
+//! Failures reported by the API
+//!
+//! These travel inside `anyhow::Error`; recover them with `downcast_ref`.
+
+use std::fmt;
+use std::time::Duration;
+
+pub use praxis_graph::wire::Problem;
+
+/// The API answered with an error status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    /// `None` when the body wasn't problem details (e.g. a proxy error page)
+    pub problem: Option<Problem>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            Some(problem) => write!(f, "API error {}: {}", self.status, problem),
+            None => write!(f, "API error {}", self.status),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// The message stream sent nothing, not even a keep-alive, for `after`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamStalled {
+    pub after: Duration,
+}
+
+impl fmt::Display for StreamStalled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Message stream sent nothing for {:?}", self.after)
+    }
+}
+
+impl std::error::Error for StreamStalled {}
@@ -0,0 +1,39 @@
+//! Typed Rust client for the Praxis REST API
+//!
+//! [`PraxisClient`] covers the thread and message endpoints and parses the
+//! SSE message stream into [`StreamEvent`]s, so services calling the API
+//! don't each write their own SSE reader.
+//!
+//! ```rust,no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use futures::StreamExt;
+//! use praxis_client::{CreateThreadRequest, PraxisClient, SendMessageRequest, StreamEvent};
+//!
+//! let client = PraxisClient::new("http://localhost:8000", "");
+//! let thread = client.create_thread(&CreateThreadRequest::new("user-1")).await?;
+//!
+//! let request = SendMessageRequest::new("user-1", "What's the weather in Lisbon?", "gpt-4o-mini");
+//! let mut events = client.send_message_stream(&thread.thread_id, &request).await?;
+//! while let Some(event) = events.next().await {
+//!     if let StreamEvent::Message { content, .. } = event? {
+//!         print!("{}", content);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod error;
+mod sse;
+mod types;
+
+pub use client::{EventStream, PraxisClient, TENANT_HEADER};
+pub use error::{ApiError, Problem, StreamStalled};
+pub use praxis_graph::wire::{Provenance, RunInfo, StreamEvent, ThreadInfo};
+pub use praxis_llm::buffer_utils::{SseFrame, SseFrameDecoder};
+pub use sse::parse_event_stream;
+pub use types::{
+    CreateThreadRequest, HydratePage, HydratedItem, LlmConfig, Message, MessageList, SendMessageRequest, Thread,
+    ThreadList, ThreadSummary,
+};
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use futures::{Stream, StreamExt};
+use praxis_llm::buffer_utils::SseFrameDecoder;
+use praxis_llm::CircularLineBuffer;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::error::StreamStalled;
+use praxis_graph::wire::StreamEvent;
+
+/// Typed events of an SSE body
+///
+/// With `idle_timeout`, the stream ends with `StreamStalled` once the body
+/// sends nothing for that long; keep-alive comments count as activity.
+pub fn parse_event_stream<S, B, E>(
+    body: S,
+    idle_timeout: Option<Duration>,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send,
+    E: std::fmt::Display + Send,
+{
+    Box::pin(parse_frames(body, idle_timeout).map(|frame| frame.and_then(|frame| frame.event)))
+}
+
+/// One event of an SSE body, with the SSE `id` it was sent with
+pub(crate) struct IdentifiedEvent {
+    pub id: Option<String>,
+    /// `Err` for an event that doesn't parse; the body goes on
+    pub event: Result<StreamEvent>,
+}
+
+/// Events of an SSE body with their ids; an `Err` item (transport error
+/// or `StreamStalled`) is the body's last
+pub(crate) fn parse_frames<S, B, E>(
+    body: S,
+    idle_timeout: Option<Duration>,
+) -> Pin<Box<dyn Stream<Item = Result<IdentifiedEvent>> + Send>>
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send,
+    E: std::fmt::Display + Send,
+{
+    Box::pin(async_stream::stream! {
+        let mut body = Box::pin(body);
+        let mut buffer = CircularLineBuffer::with_capacity(4096);
+        let mut decoder = SseFrameDecoder::default();
+
+        loop {
+            let chunk = match idle_timeout {
+                Some(after) => match tokio::time::timeout(after, body.next()).await {
+                    Ok(chunk) => chunk,
+                    Err(_) => {
+                        yield Err(StreamStalled { after }.into());
+                        break;
+                    }
+                },
+                None => body.next().await,
+            };
+            let bytes = match chunk {
+                Some(Ok(bytes)) => bytes,
+                Some(Err(e)) => {
+                    yield Err(anyhow::anyhow!("Stream error: {}", e));
+                    break;
+                }
+                None => break,
+            };
+
+            buffer.extend(bytes.as_ref());
+            while let Some(line) = buffer.next_line() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        yield Ok(IdentifiedEvent { id: None, event: Err(e) });
+                        continue;
+                    }
+                };
+                if let Some(frame) = decoder.push_line(&line) {
+                    let event = StreamEvent::parse(&frame.event, &frame.data).map_err(|e| {
+                        anyhow::anyhow!("Invalid '{}' event: {}", frame.event, e)
+                    });
+                    yield Ok(IdentifiedEvent { id: frame.id, event });
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use praxis_llm::buffer_utils::SseFrame;
+
+    fn frames(text: &str) -> Vec<SseFrame> {
+        let mut decoder = SseFrameDecoder::default();
+        text.split('\n').filter_map(|line| decoder.push_line(line)).collect()
+    }
+
+    fn body(chunks: &[&'static str]) -> impl Stream<Item = std::result::Result<&'static [u8], String>> {
+        futures::stream::iter(chunks.iter().map(|chunk| Ok(chunk.as_bytes())).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_frames_are_dispatched_on_blank_lines() {
+        let frames = frames("event: message\ndata: {\"a\": 1}\nid: 7\n\n: keep-alive\n\ndata:line 1\ndata: line 2\n\n");
+
+        assert_eq!(
+            frames,
+            vec![
+                SseFrame { event: "message".to_string(), data: "{\"a\": 1}".to_string(), id: Some("7".to_string()) },
+                SseFrame { event: "message".to_string(), data: "line 1\nline 2".to_string(), id: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_without_data_is_dropped() {
+        assert!(frames("event: info\n\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_events_split_across_chunks() {
+        let events: Vec<_> = parse_event_stream(
+            body(&["event: mess", "age\r\ndata: {\"content\": \"Hi\"}\r", "\n\r\nevent: done\ndata: {\"status\": \"completed\"}\n\n"]),
+            None,
+        )
+        .collect()
+        .await;
+
+        let events: Vec<StreamEvent> = events.into_iter().map(Result::unwrap).collect();
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent::Message { content: "Hi".to_string(), agent: None, provenance: None },
+                StreamEvent::Done { status: "completed".to_string(), finish_reason: None, cost_usd: None },
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_stream_ends_with_stalled() {
+        let body = body(&["event: queued\ndata: {\"position\": 1}\n\n"]).chain(futures::stream::pending());
+        let mut events = parse_event_stream(body, Some(Duration::from_secs(30)));
+
+        assert_eq!(events.next().await.unwrap().unwrap(), StreamEvent::Queued { position: 1 });
+        let error = events.next().await.unwrap().unwrap_err();
+        assert_eq!(error.downcast_ref::<StreamStalled>(), Some(&StreamStalled { after: Duration::from_secs(30) }));
+        assert!(events.next().await.is_none());
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use praxis_llm::ToolChoice;
+use praxis_persist::{Citation, MessageRole, MessageType, SummaryTrigger, ThreadMetadata, ToolVerification};
+use serde::{Deserialize, Serialize};
+
+use praxis_graph::wire::StreamEvent;
+
+/// Body of `POST /threads`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateThreadRequest {
+    pub user_id: String,
+    pub metadata: ThreadMetadata,
+}
+
+impl CreateThreadRequest {
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self { user_id: user_id.into(), metadata: ThreadMetadata::default() }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.metadata.title = Some(title.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Thread {
+    pub thread_id: String,
+    pub user_id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub metadata: ThreadMetadata,
+    #[serde(default)]
+    pub summary: Option<ThreadSummary>,
+    /// Set once the server archived the idle thread
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadSummary {
+    pub text: String,
+    pub generated_at: DateTime<Utc>,
+    pub total_tokens_before_summary: usize,
+    pub messages_count: usize,
+    #[serde(default)]
+    pub trigger: SummaryTrigger,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadList {
+    pub threads: Vec<Thread>,
+    pub has_more: bool,
+}
+
+/// Stored message of a thread
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub message_id: String,
+    pub thread_id: String,
+    pub role: MessageRole,
+    pub message_type: MessageType,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub content_sha256: Option<String>,
+    #[serde(default)]
+    pub token_count: Option<u32>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Tool results: MCP server that ran the call (`_local` for a local tool)
+    #[serde(default)]
+    pub tool_server: Option<String>,
+    /// Tool results: read call that checked a write tool's effect
+    #[serde(default)]
+    pub verification: Option<ToolVerification>,
+    /// Answers: tool results cited by `[n]` markers in `content`
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageList {
+    pub messages: Vec<Message>,
+    pub has_more: bool,
+}
+
+/// Body of `POST /threads/:id/messages`
+#[derive(Debug, Clone, Serialize)]
+pub struct SendMessageRequest {
+    pub user_id: String,
+    pub content: String,
+    pub llm_config: LlmConfig,
+}
+
+impl SendMessageRequest {
+    pub fn new(user_id: impl Into<String>, content: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            content: content.into(),
+            llm_config: LlmConfig { model: model.into(), ..Default::default() },
+        }
+    }
+}
+
+/// Requested LLM settings; unset fields take the server defaults, and the
+/// server's routing may override any of them
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LlmConfig {
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Tool choice for the run's first LLM call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+}
+
+/// Page of `GET /threads/:id/hydrate`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HydratePage {
+    pub thread_id: String,
+    pub items: Vec<HydratedItem>,
+    /// Pass to `hydrate_thread` for the next page; `None` on the last one
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// Stored message as the event the live stream sent for it
+#[derive(Debug, Clone, Deserialize)]
+pub struct HydratedItem {
+    pub message_id: String,
+    pub created_at: DateTime<Utc>,
+    /// `completed`, or `incomplete` for a tool call without a result
+    pub status: String,
+    #[serde(flatten, deserialize_with = "deserialize_event")]
+    pub event: StreamEvent,
+}
+
+/// `event` and `data` fields, parsed like a live event
+fn deserialize_event<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<StreamEvent, D::Error> {
+    #[derive(Deserialize)]
+    struct Raw {
+        event: String,
+        #[serde(default)]
+        data: serde_json::Value,
+    }
+
+    let raw = Raw::deserialize(deserializer)?;
+    StreamEvent::from_parts(&raw.event, raw.data).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hydrated_items_parse_as_stream_events() {
+        let page: HydratePage = serde_json::from_value(serde_json::json!({
+            "thread_id": "t-1",
+            "items": [
+                {
+                    "message_id": "m-1",
+                    "created_at": "2025-01-01T00:00:00Z",
+                    "status": "completed",
+                    "event": "user_message",
+                    "data": { "content": "Hi" }
+                },
+                {
+                    "message_id": "m-2",
+                    "created_at": "2025-01-01T00:00:01Z",
+                    "status": "completed",
+                    "event": "citation",
+                    "data": { "url": "https://example.com" }
+                }
+            ],
+            "next_cursor": null
+        }))
+        .unwrap();
+
+        assert_eq!(page.items[0].event, StreamEvent::UserMessage { content: "Hi".to_string() });
+        assert!(matches!(&page.items[1].event, StreamEvent::Unknown { event, .. } if event == "citation"));
+        assert!(page.next_cursor.is_none());
+    }
+}
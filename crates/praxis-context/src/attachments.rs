@@ -0,0 +1,229 @@
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use praxis_mcp::{LocalTool, ToolResponse};
+use praxis_persist::{AttachmentRef, PersistenceClient};
+
+/// Per-thread tool reading the files uploaded to the thread
+///
+/// Text attachments are read by line: the whole file, or an inclusive
+/// 1-based `range` of lines. Output past `max_bytes` is cut at a line
+/// boundary and ends with a note telling the model which line to continue
+/// from. Binary attachments can't be read.
+pub struct ReadAttachmentTool {
+    thread_id: String,
+    persist_client: Arc<dyn PersistenceClient>,
+    attachments: Vec<AttachmentRef>,
+    max_bytes: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadArgs {
+    name: String,
+    #[serde(default)]
+    range: Option<LineRange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LineRange {
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+}
+
+impl ReadAttachmentTool {
+    pub const NAME: &'static str = "read_attachment";
+
+    /// Tool over `attachments`, the thread's files as listed when the run starts
+    pub fn new(
+        thread_id: impl Into<String>,
+        persist_client: Arc<dyn PersistenceClient>,
+        attachments: Vec<AttachmentRef>,
+    ) -> Self {
+        Self {
+            thread_id: thread_id.into(),
+            persist_client,
+            attachments,
+            max_bytes: 32 * 1024,
+        }
+    }
+
+    /// The tool for `thread_id`, or None if the thread has no attachments
+    pub async fn for_thread(thread_id: &str, persist_client: Arc<dyn PersistenceClient>) -> Result<Option<Self>> {
+        let attachments = persist_client.list_attachments(thread_id).await?;
+        if attachments.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(thread_id, persist_client, attachments)))
+    }
+
+    /// Maximum bytes of content returned per call
+    pub fn with_max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = max;
+        self
+    }
+
+    fn attachment(&self, name: &str) -> Result<&AttachmentRef> {
+        self.attachments.iter().find(|a| a.name == name).ok_or_else(|| {
+            let names: Vec<&str> = self.attachments.iter().map(|a| a.name.as_str()).collect();
+            anyhow!("No attachment named '{}'; available: {}", name, names.join(", "))
+        })
+    }
+}
+
+#[async_trait]
+impl LocalTool for ReadAttachmentTool {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn description(&self) -> String {
+        let files = self
+            .attachments
+            .iter()
+            .map(|a| format!("- {} ({}, {} bytes)", a.name, a.mime_type, a.size))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "Read a file the user attached to this conversation, optionally only a range of \
+             lines (1-based, inclusive). At most {} bytes are returned per call. Attached files:\n{}",
+            self.max_bytes, files
+        )
+    }
+
+    fn input_schema(&self) -> Value {
+        let names: Vec<&str> = self.attachments.iter().map(|a| a.name.as_str()).collect();
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "enum": names,
+                    "description": "Attachment name"
+                },
+                "range": {
+                    "type": "object",
+                    "properties": {
+                        "start_line": { "type": "integer", "minimum": 1 },
+                        "end_line": { "type": "integer", "minimum": 1 }
+                    },
+                    "additionalProperties": false,
+                    "description": "Lines to read (default: the whole file)"
+                }
+            },
+            "required": ["name"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Vec<ToolResponse>> {
+        let args: ReadArgs = serde_json::from_value(arguments).map_err(|e| anyhow!("Invalid arguments: {}", e))?;
+        let attachment = self.attachment(&args.name)?;
+
+        let bytes = self
+            .persist_client
+            .load_attachment(&self.thread_id, &attachment.name)
+            .await?
+            .ok_or_else(|| anyhow!("Attachment '{}' no longer exists", attachment.name))?;
+        let text = std::str::from_utf8(&bytes).map_err(|_| {
+            anyhow!(
+                "'{}' is binary ({}, {} bytes) and can't be read as text",
+                attachment.name,
+                attachment.mime_type,
+                bytes.len()
+            )
+        })?;
+
+        let (start, end) = match &args.range {
+            Some(range) => (range.start_line, range.end_line),
+            None => (None, None),
+        };
+        let text = read_lines(text, start, end, self.max_bytes)?;
+        Ok(vec![ToolResponse::Text { text }])
+    }
+}
+
+/// Lines `start..=end` (1-based, `end` clamped to the last line) of `text`,
+/// stopping before the line that would exceed `max_bytes`
+fn read_lines(text: &str, start: Option<usize>, end: Option<usize>, max_bytes: usize) -> Result<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let total = lines.len();
+    let start = start.unwrap_or(1);
+    let end = end.unwrap_or(total).min(total);
+    if start == 0 {
+        return Err(anyhow!("Lines are numbered from 1"));
+    }
+    if total == 0 {
+        return Ok(String::new());
+    }
+    if start > total {
+        return Err(anyhow!("start_line {} is past the last line ({})", start, total));
+    }
+    if start > end {
+        return Err(anyhow!("start_line {} is after end_line {}", start, end));
+    }
+
+    let mut out = String::new();
+    for (offset, line) in lines[start - 1..end].iter().enumerate() {
+        let number = start + offset;
+        if !out.is_empty() && out.len() + line.len() + 1 > max_bytes {
+            out.push_str(&format!(
+                "\n[truncated: lines {}-{} not shown, read from start_line {} to continue]",
+                number, end, number
+            ));
+            return Ok(out);
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&truncate_at_char(line, max_bytes));
+    }
+    Ok(out)
+}
+
+/// `line` cut to at most `max_bytes`, on a char boundary
+fn truncate_at_char(line: &str, max_bytes: usize) -> String {
+    if line.len() <= max_bytes {
+        return line.to_string();
+    }
+    let mut cut = max_bytes;
+    while !line.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}…", &line[..cut])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "one\ntwo\r\nthree\nfour\nfive\n";
+
+    #[test]
+    fn test_whole_file_and_ranges() {
+        assert_eq!(read_lines(TEXT, None, None, 1024).unwrap(), "one\ntwo\nthree\nfour\nfive");
+        assert_eq!(read_lines(TEXT, Some(2), Some(3), 1024).unwrap(), "two\nthree");
+        assert_eq!(read_lines(TEXT, Some(4), None, 1024).unwrap(), "four\nfive");
+        // The end is clamped to the last line
+        assert_eq!(read_lines(TEXT, Some(5), Some(99), 1024).unwrap(), "five");
+    }
+
+    #[test]
+    fn test_invalid_ranges_are_errors() {
+        assert!(read_lines(TEXT, Some(0), None, 1024).is_err());
+        assert!(read_lines(TEXT, Some(6), None, 1024).is_err());
+        assert!(read_lines(TEXT, Some(3), Some(2), 1024).is_err());
+        assert_eq!(read_lines("", None, None, 1024).unwrap(), "");
+    }
+
+    #[test]
+    fn test_output_stops_at_max_bytes_with_a_continuation_note() {
+        let out = read_lines(TEXT, None, None, 10).unwrap();
+        assert_eq!(out, "one\ntwo\n[truncated: lines 3-5 not shown, read from start_line 3 to continue]");
+
+        // A single line longer than the cap is cut on a char boundary
+        assert_eq!(read_lines("ééééé", None, None, 5).unwrap(), "éé…");
+    }
+}
@@ -0,0 +1,332 @@
+//! Context built by an ordered pipeline of stages
+//!
+//! Each stage adds to a draft window (system prompt sections, history
+//! messages, tools) within a token budget: its share of `max_tokens` plus
+//! whatever the stages before it left unused. Stage order is precedence:
+//! earlier stages pick first and later ones get their leftovers.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use praxis_mcp::LocalTool;
+use praxis_persist::{ContentTokenizer, DBMessage, MessageRole, PersistenceClient, Thread, ThreadSummary};
+use crate::default::DefaultContextStrategy;
+use crate::normalize::normalize_for_model;
+use crate::stages::{PinnedStage, RecencyStage, RetrievalStage, SummaryStage};
+use crate::strategy::{ContextStrategy, ContextWindow};
+use crate::tokenizer::TiktokenTokenizer;
+
+/// What stages read during one `get_context_window` call
+pub struct StageContext<'a> {
+    pub thread_id: &'a str,
+    pub thread: &'a Thread,
+    /// The thread's messages, oldest first, without the message of the turn
+    /// being started
+    pub messages: &'a [DBMessage],
+    /// Text of the turn being started (the excluded message), or else of
+    /// the last user message
+    pub query: Option<&'a str>,
+    pub model: &'a str,
+    pub persist_client: &'a Arc<dyn PersistenceClient>,
+    /// Set with `CompositeContextStrategy::with_summarizer`
+    pub summarizer: Option<&'a DefaultContextStrategy>,
+}
+
+impl StageContext<'_> {
+    /// Tokens of `messages[position]`, preferring the count stored at write time
+    pub fn message_tokens(&self, position: usize) -> usize {
+        let message = &self.messages[position];
+        match message.token_count {
+            Some(count) => count as usize,
+            None => self.text_tokens(&message.content),
+        }
+    }
+
+    /// Tokens of `text` for the run's model
+    pub fn text_tokens(&self, text: &str) -> usize {
+        TiktokenTokenizer.count(self.model, text)
+    }
+}
+
+/// The window as stages build it
+#[derive(Default)]
+pub struct ContextDraft {
+    /// Added to the system prompt in this order
+    pub sections: Vec<String>,
+    /// Positions (in `StageContext::messages`) of the messages included;
+    /// the window sends them in thread order, whichever stage added them
+    pub included: BTreeSet<usize>,
+    /// History up to this time is covered by a summary in `sections`
+    pub summarized_until: Option<DateTime<Utc>>,
+    pub tools: Vec<Arc<dyn LocalTool>>,
+}
+
+/// One step of a `CompositeContextStrategy`
+#[async_trait]
+pub trait ContextStage: Send + Sync {
+    /// Shown in `StageUsage`
+    fn name(&self) -> &str;
+
+    /// Add to `draft` using at most `budget` tokens and return the tokens used
+    ///
+    /// What is left of `budget` goes to the next stage.
+    async fn apply(&self, ctx: &StageContext<'_>, draft: &mut ContextDraft, budget: usize) -> Result<usize>;
+}
+
+/// Tokens a stage was given and used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StageUsage {
+    pub stage: String,
+    /// Its share plus what earlier stages left
+    pub budget: usize,
+    pub used: usize,
+}
+
+/// A stage and the percentage of the budget it is allocated
+#[derive(Clone)]
+struct Step {
+    stage: Arc<dyn ContextStage>,
+    share_percent: u8,
+}
+
+/// Context from an ordered pipeline of `ContextStage`s, e.g. pinned
+/// messages, then the summary, then retrieval, then the most recent messages
+///
+/// `max_tokens` covers the system prompt and the history; the base system
+/// prompt and the thread's developer prompt are taken off first and the
+/// rest split by the stages' shares.
+/// Shares may add up to less than 100, leaving headroom unused.
+#[derive(Clone)]
+pub struct CompositeContextStrategy {
+    max_tokens: usize,
+    system_prompt: String,
+    steps: Vec<Step>,
+    summarizer: Option<Arc<DefaultContextStrategy>>,
+}
+
+impl CompositeContextStrategy {
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            system_prompt: "You are a helpful assistant.".to_string(),
+            steps: Vec::new(),
+            summarizer: None,
+        }
+    }
+
+    /// Build the pipeline `stages` describe, in their order
+    pub fn from_config(max_tokens: usize, stages: &[ContextStageConfig]) -> Self {
+        stages
+            .iter()
+            .fold(Self::new(max_tokens), |strategy, config| strategy.with_stage(config.build(), config.share_percent()))
+    }
+
+    /// Base system prompt; stage sections follow it
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = prompt.into();
+        self
+    }
+
+    /// Run `stage` after the ones added so far, with `share_percent` of the budget
+    pub fn with_stage(mut self, stage: Arc<dyn ContextStage>, share_percent: u8) -> Self {
+        self.steps.push(Step { stage, share_percent });
+        self
+    }
+
+    /// Strategy whose triggers summarize the thread (see `SummaryStage`)
+    /// and which `summarize_now` uses
+    pub fn with_summarizer(mut self, summarizer: Arc<DefaultContextStrategy>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+
+    /// Fails when the stage shares add up to more than 100%
+    pub fn validate(&self) -> Result<()> {
+        let total: u32 = self.steps.iter().map(|step| u32::from(step.share_percent)).sum();
+        if total > 100 {
+            bail!("Context stage shares add up to {}%, more than 100%", total);
+        }
+        Ok(())
+    }
+
+    /// Build the window, with how much of the budget each stage used
+    pub async fn compose(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
+    ) -> Result<(ContextWindow, Vec<StageUsage>)> {
+        self.validate()?;
+        let thread = persist_client.get_thread(thread_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Thread {} not found - should be created before sending messages", thread_id))?;
+
+        let mut messages = persist_client.get_messages(thread_id).await?;
+        let excluded = exclude_message_id
+            .and_then(|excluded| messages.iter().position(|msg| msg.id == excluded))
+            .map(|position| messages.remove(position));
+        let query = excluded.as_ref().map(|msg| msg.content.clone()).or_else(|| {
+            messages
+                .iter()
+                .rev()
+                .find(|msg| msg.role == MessageRole::User)
+                .map(|msg| msg.content.clone())
+        });
+
+        let ctx = StageContext {
+            thread_id,
+            thread: &thread,
+            messages: &messages,
+            query: query.as_deref(),
+            model,
+            persist_client,
+            summarizer: self.summarizer.as_deref(),
+        };
+
+        let developer_prompt = thread.metadata.developer_prompt.clone();
+        let instructions = ctx.text_tokens(&self.system_prompt)
+            + developer_prompt.as_deref().map_or(0, |prompt| ctx.text_tokens(prompt));
+        let available = self.max_tokens.saturating_sub(instructions);
+        let mut draft = ContextDraft::default();
+        let mut usage = Vec::with_capacity(self.steps.len());
+        let mut spill = 0;
+        for step in &self.steps {
+            let budget = share(available, step.share_percent) + spill;
+            let used = step.stage.apply(&ctx, &mut draft, budget).await?;
+            if used > budget {
+                bail!("Context stage {} used {} tokens of a {} token budget", step.stage.name(), used, budget);
+            }
+            spill = budget - used;
+            usage.push(StageUsage { stage: step.stage.name().to_string(), budget, used });
+        }
+
+        let system_prompt = std::iter::once(self.system_prompt.as_str())
+            .chain(draft.sections.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let window: Vec<DBMessage> = draft.included.iter().map(|&position| messages[position].clone()).collect();
+        let (llm_messages, normalizations) = normalize_for_model(window, model);
+
+        Ok((
+            ContextWindow {
+                system_prompt,
+                developer_prompt,
+                messages: llm_messages,
+                tools: draft.tools,
+                normalizations,
+            },
+            usage,
+        ))
+    }
+}
+
+/// `share_percent` of `available` tokens
+fn share(available: usize, share_percent: u8) -> usize {
+    available * usize::from(share_percent) / 100
+}
+
+#[async_trait]
+impl ContextStrategy for CompositeContextStrategy {
+    async fn get_context_window(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
+    ) -> Result<ContextWindow> {
+        let (window, _) = self.compose(thread_id, persist_client, exclude_message_id, model).await?;
+        Ok(window)
+    }
+
+    async fn summarize_now(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+    ) -> Result<Option<ThreadSummary>> {
+        match &self.summarizer {
+            Some(summarizer) => summarizer.summarize_now(thread_id, persist_client).await,
+            None => bail!("This context strategy has no summarizer"),
+        }
+    }
+}
+
+/// A stage of a `CompositeContextStrategy` in configuration files
+///
+/// ```toml
+/// [[context.stages]]
+/// kind = "recency"
+/// share_percent = 50
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContextStageConfig {
+    /// See `PinnedStage`
+    Pinned {
+        share_percent: u8,
+        #[serde(default = "default_first_messages")]
+        first_messages: usize,
+    },
+    /// See `SummaryStage`
+    Summary { share_percent: u8 },
+    /// See `RetrievalStage`
+    Retrieval {
+        share_percent: u8,
+        #[serde(default = "default_max_messages")]
+        max_messages: usize,
+    },
+    /// See `RecencyStage`
+    Recency { share_percent: u8 },
+}
+
+fn default_first_messages() -> usize {
+    1
+}
+
+fn default_max_messages() -> usize {
+    5
+}
+
+impl ContextStageConfig {
+    pub fn share_percent(&self) -> u8 {
+        match self {
+            Self::Pinned { share_percent, .. }
+            | Self::Summary { share_percent }
+            | Self::Retrieval { share_percent, .. }
+            | Self::Recency { share_percent } => *share_percent,
+        }
+    }
+
+    pub fn build(&self) -> Arc<dyn ContextStage> {
+        match self {
+            Self::Pinned { first_messages, .. } => Arc::new(PinnedStage::new(*first_messages)),
+            Self::Summary { .. } => Arc::new(SummaryStage::new()),
+            Self::Retrieval { max_messages, .. } => Arc::new(RetrievalStage::new(*max_messages)),
+            Self::Recency { .. } => Arc::new(RecencyStage::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shares_round_down_and_must_fit() {
+        assert_eq!(share(999, 50), 499);
+        assert_eq!(share(1000, 0), 0);
+
+        let stages: Vec<ContextStageConfig> = serde_json::from_value(serde_json::json!([
+            { "kind": "pinned", "share_percent": 10 },
+            { "kind": "recency", "share_percent": 95 },
+        ]))
+        .unwrap();
+        assert_eq!(stages[0], ContextStageConfig::Pinned { share_percent: 10, first_messages: 1 });
+        assert!(CompositeContextStrategy::from_config(1000, &stages).validate().is_err());
+        assert!(CompositeContextStrategy::from_config(1000, &stages[..1]).validate().is_ok());
+    }
+}
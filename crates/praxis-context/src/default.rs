@@ -1,19 +1,26 @@
 use std::sync::Arc;
 use anyhow::Result;
 use async_trait::async_trait;
-use tiktoken_rs::cl100k_base;
-use chrono::Utc;
 
-use praxis_llm::{ChatClient, Message, Content};
-use praxis_persist::{PersistenceClient, DBMessage};
+use praxis_core::{Clock, SystemClock};
+use praxis_llm::{ChatClient, Message, TokenCounter};
+use praxis_persist::{PersistenceClient, DBMessage, SummaryTrigger, ThreadSummarizer, ThreadSummary};
+use crate::normalize::normalize_for_model;
 use crate::strategy::{ContextStrategy, ContextWindow};
+use crate::tokenizer::TiktokenTokenizer;
 use crate::templates::{DEFAULT_SYSTEM_PROMPT_TEMPLATE, DEFAULT_SUMMARIZATION_PROMPT};
+use crate::trigger::{OverflowTrigger, SummarizationTrigger, SummaryWindow};
 
+#[derive(Clone)]
 pub struct DefaultContextStrategy {
     max_tokens: usize,
     llm_client: Arc<dyn ChatClient>,
     system_prompt_template: String,
     summarization_template: String,
+    /// Checked after the `max_tokens` backstop, in order
+    triggers: Vec<Arc<dyn SummarizationTrigger>>,
+    clock: Arc<dyn Clock>,
+    token_counter: Arc<dyn TokenCounter>,
 }
 
 impl DefaultContextStrategy {
@@ -26,6 +33,9 @@ impl DefaultContextStrategy {
             llm_client,
             system_prompt_template: DEFAULT_SYSTEM_PROMPT_TEMPLATE.to_string(),
             summarization_template: DEFAULT_SUMMARIZATION_PROMPT.to_string(),
+            triggers: Vec::new(),
+            clock: Arc::new(SystemClock),
+            token_counter: Arc::new(TiktokenTokenizer),
         }
     }
     
@@ -40,20 +50,64 @@ impl DefaultContextStrategy {
             llm_client,
             system_prompt_template,
             summarization_template,
+            triggers: Vec::new(),
+            clock: Arc::new(SystemClock),
+            token_counter: Arc::new(TiktokenTokenizer),
         }
     }
     
-    /// Count tokens in messages using tiktoken
-    fn count_tokens(&self, messages: &[DBMessage]) -> Result<usize> {
-        let bpe = cl100k_base().map_err(|e| anyhow::anyhow!("Tokenizer error: {}", e))?;
-        
-        let mut total_tokens = 0;
-        for msg in messages {
-            let tokens = bpe.encode_with_special_tokens(&msg.content);
-            total_tokens += tokens.len();
+    /// Also summarize when `trigger` fires (e.g. a `CadenceTrigger`)
+    ///
+    /// Exceeding `max_tokens` still summarizes, whatever the added triggers
+    /// decide.
+    pub fn with_trigger(mut self, trigger: Arc<dyn SummarizationTrigger>) -> Self {
+        self.triggers.push(trigger);
+        self
+    }
+    
+    /// Clock the cadence triggers measure idle time with (default: system clock)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Counter for messages saved without a `token_count` (default:
+    /// `TiktokenTokenizer`)
+    pub fn with_token_counter(mut self, token_counter: Arc<dyn TokenCounter>) -> Self {
+        self.token_counter = token_counter;
+        self
+    }
+    
+    /// Why `window` should be summarized now, if it should
+    fn due_trigger(&self, window: &SummaryWindow<'_>) -> Option<SummaryTrigger> {
+        let overflow = OverflowTrigger::new(self.max_tokens);
+        if overflow.should_summarize(window) {
+            return Some(overflow.kind());
         }
-        
-        Ok(total_tokens)
+        self.triggers
+            .iter()
+            .find(|trigger| trigger.should_summarize(window))
+            .map(|trigger| trigger.kind())
+    }
+    
+    /// Count tokens in messages for `model`, preferring counts stored at
+    /// write time
+    ///
+    /// Only messages saved without a `token_count` go through the token
+    /// counter, together. Backends decompress on read, so `content` is the
+    /// text the model sees even for messages stored with
+    /// `ContentEncoding::Zstd`.
+    fn count_tokens(&self, messages: &[DBMessage], model: &str) -> Result<usize> {
+        let stored: usize = messages.iter().filter_map(|msg| msg.token_count).map(|count| count as usize).sum();
+        let uncounted: Vec<Message> = messages
+            .iter()
+            .filter(|msg| msg.token_count.is_none())
+            .map(|msg| Message::try_from(msg.clone()).unwrap_or_else(|_| Message::ai(msg.content.clone())))
+            .collect();
+        if uncounted.is_empty() {
+            return Ok(stored);
+        }
+        Ok(stored + self.token_counter.count_messages(model, &uncounted, &[]))
     }
     
     /// Build conversation text from messages
@@ -82,10 +136,7 @@ impl DefaultContextStrategy {
         
         let request = praxis_llm::ChatRequest::new(
             "gpt-4o-mini".to_string(),
-            vec![Message::Human {
-                content: Content::text(summary_prompt),
-                name: None,
-            }],
+            vec![Message::human(summary_prompt)],
         );
         
         let response = self.llm_client.chat(request).await?;
@@ -95,6 +146,71 @@ impl DefaultContextStrategy {
         Ok(summary)
     }
     
+    /// Summarize `messages` on top of `previous_summary` and save the result
+    ///
+    /// The summary covers up to the newest of `messages`, so anything saved
+    /// while it was generated stays in the next window.
+    async fn summarize(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        messages: &[DBMessage],
+        previous_summary: Option<&str>,
+        trigger: SummaryTrigger,
+    ) -> Result<()> {
+        let Some(covered_until) = messages.iter().map(|m| m.created_at).max() else {
+            return Ok(());
+        };
+        let summary_text = self.generate_summary(messages, previous_summary).await?;
+        persist_client
+            .save_thread_summary(thread_id, summary_text, covered_until, trigger)
+            .await?;
+        Ok(())
+    }
+    
+    /// Summarize `messages` (the thread's unsummarized history) in the
+    /// background if a trigger fires for them, with tokens counted for
+    /// `model`
+    ///
+    /// The summary shows up from a later turn on. Shared with `SummaryStage`.
+    pub(crate) fn spawn_due_summary(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        messages: &[DBMessage],
+        existing_summary: Option<&str>,
+        model: &str,
+    ) -> Result<()> {
+        let window = SummaryWindow {
+            messages,
+            tokens: self.count_tokens(messages, model)?,
+            now: self.clock.now(),
+        };
+        let Some(trigger) = self.due_trigger(&window) else {
+            return Ok(());
+        };
+        
+        // Clone everything needed for fire-and-forget task
+        let messages_clone = messages.to_vec();
+        let previous_summary = existing_summary.map(|s| s.to_string());
+        let persist_client_clone = Arc::clone(persist_client);
+        let thread_id_owned = thread_id.to_string();
+        let strategy = self.clone();
+        
+        tokio::spawn(async move {
+            let _ = strategy
+                .summarize(
+                    &thread_id_owned,
+                    &persist_client_clone,
+                    &messages_clone,
+                    previous_summary.as_deref(),
+                    trigger,
+                )
+                .await;
+        });
+        Ok(())
+    }
+    
     /// Build system prompt.
     fn build_system_prompt(&self, summary: Option<&str>) -> String {
         let summary_text = summary.unwrap_or("Não temos resumo ainda.");
@@ -107,71 +223,81 @@ impl ContextStrategy for DefaultContextStrategy {
     async fn get_context_window(
         &self,
         thread_id: &str,
-        persist_client: Arc<dyn PersistenceClient>,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
     ) -> Result<ContextWindow> {
         // 1. Get thread
         let thread = persist_client.get_thread(thread_id).await?
             .ok_or_else(|| anyhow::anyhow!("Thread {} not found - should be created before sending messages", thread_id))?;
         
         // 2. Fetch messages after last_summary_update
-        let messages_to_evaluate = persist_client
+        let mut messages_to_evaluate = persist_client
             .get_messages_after(thread_id, thread.last_summary_update)
             .await?;
+        if let Some(excluded) = exclude_message_id {
+            messages_to_evaluate.retain(|msg| msg.id != excluded);
+        }
         
         let existing_summary = thread.summary.as_ref().map(|s| s.text.as_str());
         if messages_to_evaluate.is_empty() {
             return Ok(ContextWindow {
                 system_prompt: self.build_system_prompt(existing_summary),
+                developer_prompt: thread.metadata.developer_prompt,
                 messages: vec![],
+                tools: vec![],
+                normalizations: vec![],
             });
         }
         
-        // 3. Count tokens of CURRENT WINDOW
-        let current_window_tokens = self.count_tokens(&messages_to_evaluate)?;
-        
-        // 4. If current window exceeds max_tokens, spawn async summary generation
-        if current_window_tokens > self.max_tokens {
-            // Clone everything needed for fire-and-forget task
-            let messages_clone = messages_to_evaluate.clone();
-            let previous_summary = existing_summary.map(|s| s.to_string());
-            let persist_client_clone = Arc::clone(&persist_client);
-            let thread_id_owned = thread_id.to_string();
-            
-            // Clone strategy fields to recreate context in async task
-            let strategy = Self {
-                max_tokens: self.max_tokens,
-                llm_client: self.llm_client.clone(),
-                system_prompt_template: self.system_prompt_template.clone(),
-                summarization_template: self.summarization_template.clone(),
-            };
-            
-            tokio::spawn(async move {
-                if let Ok(summary_text) = strategy
-                    .generate_summary(&messages_clone, previous_summary.as_deref())
-                    .await {
-                        let summary_time = Utc::now();
-                        let _ = persist_client_clone.save_thread_summary(
-                            &thread_id_owned,
-                            summary_text,
-                            summary_time
-                        ).await;
-                }
-            });
-        }
+        // 3. Summarize in the background if a trigger fires (the window
+        //    exceeds max_tokens, or a cadence came due)
+        self.spawn_due_summary(thread_id, persist_client, &messages_to_evaluate, existing_summary, model)?;
         
-        // 6. Build system prompt with existing summary (if any)
+        // 4. Build system prompt with existing summary (if any)
         let system_prompt = self.build_system_prompt(existing_summary);
         
-        // 7. Convert DBMessage → praxis_llm::Message
-        let llm_messages = messages_to_evaluate
-            .into_iter()
-            .filter_map(|msg| msg.try_into().ok())
-            .collect();
+        // 5. Convert DBMessage → praxis_llm::Message, in the form `model` takes
+        let (llm_messages, normalizations) = normalize_for_model(messages_to_evaluate, model);
         
         Ok(ContextWindow {
             system_prompt,
+            developer_prompt: thread.metadata.developer_prompt,
             messages: llm_messages,
+            tools: vec![],
+            normalizations,
         })
     }
+    
+    async fn summarize_now(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+    ) -> Result<Option<ThreadSummary>> {
+        let thread = persist_client.get_thread(thread_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Thread {} not found", thread_id))?;
+        let messages = persist_client
+            .get_messages_after(thread_id, thread.last_summary_update)
+            .await?;
+        if messages.is_empty() {
+            return Ok(None);
+        }
+        
+        let previous_summary = thread.summary.as_ref().map(|s| s.text.as_str());
+        self.summarize(thread_id, persist_client, &messages, previous_summary, SummaryTrigger::Manual)
+            .await?;
+        Ok(persist_client.get_thread(thread_id).await?.and_then(|thread| thread.summary))
+    }
 }
 
+/// Final summaries of idle threads for hygiene runs, written by `summarize_now`
+#[async_trait]
+impl ThreadSummarizer for DefaultContextStrategy {
+    async fn summarize_thread(
+        &self,
+        thread_id: &str,
+        persist: &Arc<dyn PersistenceClient>,
+    ) -> Result<Option<ThreadSummary>> {
+        self.summarize_now(thread_id, persist).await
+    }
+}
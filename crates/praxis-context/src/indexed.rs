@@ -0,0 +1,325 @@
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use praxis_mcp::{LocalTool, ToolResponse};
+use praxis_persist::{DBMessage, HistoryIndex, HistoryIndexEntry, MessageRole, MessageType, PersistenceClient};
+use crate::normalize::normalize_for_model;
+use crate::strategy::{ContextStrategy, ContextWindow};
+use crate::templates::DEFAULT_INDEXED_HISTORY_PROMPT;
+
+/// Sparse history: a one-line index of older messages plus the last K in full
+///
+/// The index is cached on the thread (`Thread::history_index`) and only
+/// extended with messages added since the last call. The returned window
+/// carries a `fetch_messages` tool so the model can pull specific older
+/// messages by their index number.
+pub struct IndexedHistoryStrategy {
+    recent_messages: usize,
+    line_max_chars: usize,
+    system_prompt_template: String,
+}
+
+impl IndexedHistoryStrategy {
+    /// Keep the last `recent_messages` messages in full
+    pub fn new(recent_messages: usize) -> Self {
+        Self {
+            recent_messages,
+            line_max_chars: 80,
+            system_prompt_template: DEFAULT_INDEXED_HISTORY_PROMPT.to_string(),
+        }
+    }
+
+    /// Maximum characters of message content shown per index line
+    pub fn with_line_max_chars(mut self, max: usize) -> Self {
+        self.line_max_chars = max;
+        self
+    }
+
+    /// System prompt template; `<index>` is replaced with the index lines
+    pub fn with_system_prompt_template(mut self, template: impl Into<String>) -> Self {
+        self.system_prompt_template = template.into();
+        self
+    }
+
+    /// Append entries for messages not yet covered by the index
+    ///
+    /// Returns true if the index changed. Falls back to a rebuild only when
+    /// the cached index no longer matches the thread (e.g. messages deleted).
+    fn refresh_index(&self, index: &mut HistoryIndex, messages: &[DBMessage]) -> bool {
+        let stale = index.indexed_count > messages.len()
+            || index
+                .entries
+                .last()
+                .is_some_and(|entry| messages.get(entry.seq - 1).map(|m| m.id.as_str()) != Some(entry.message_id.as_str()));
+        if stale {
+            *index = HistoryIndex::default();
+        }
+
+        if index.indexed_count == messages.len() {
+            return stale;
+        }
+
+        for (offset, message) in messages.iter().enumerate().skip(index.indexed_count) {
+            let seq = offset + 1;
+            if let Some(line) = index_line(seq, message, self.line_max_chars) {
+                index.entries.push(HistoryIndexEntry {
+                    seq,
+                    message_id: message.id.clone(),
+                    line,
+                });
+            }
+        }
+        index.indexed_count = messages.len();
+        true
+    }
+
+    /// Index (0-based) of the first message sent in full
+    ///
+    /// Never starts on a tool result, so its tool call is always included.
+    fn window_start(&self, messages: &[DBMessage]) -> usize {
+        let mut start = messages.len().saturating_sub(self.recent_messages);
+        while start > 0 && messages[start].message_type == MessageType::ToolResult {
+            start -= 1;
+        }
+        start
+    }
+}
+
+#[async_trait]
+impl ContextStrategy for IndexedHistoryStrategy {
+    async fn get_context_window(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
+    ) -> Result<ContextWindow> {
+        let thread = persist_client.get_thread(thread_id).await?
+            .ok_or_else(|| anyhow!("Thread {} not found - should be created before sending messages", thread_id))?;
+
+        let mut messages = persist_client.get_messages(thread_id).await?;
+        if let Some(excluded) = exclude_message_id {
+            messages.retain(|msg| msg.id != excluded);
+        }
+
+        let mut index = thread.history_index.unwrap_or_default();
+        if self.refresh_index(&mut index, &messages) {
+            persist_client.save_thread_index(thread_id, index.clone()).await?;
+        }
+
+        let start = self.window_start(&messages);
+        let index_text = index
+            .entries
+            .iter()
+            .take_while(|entry| entry.seq <= start)
+            .map(|entry| entry.line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let tools: Vec<Arc<dyn LocalTool>> = if index_text.is_empty() {
+            vec![]
+        } else {
+            vec![Arc::new(FetchMessagesTool::new(thread_id, Arc::clone(persist_client)))]
+        };
+
+        let index_text = if index_text.is_empty() { "(no earlier messages)".to_string() } else { index_text };
+        let system_prompt = self.system_prompt_template.replace("<index>", &index_text);
+
+        let window: Vec<DBMessage> = messages.into_iter().skip(start).collect();
+        let (llm_messages, normalizations) = normalize_for_model(window, model);
+
+        Ok(ContextWindow {
+            system_prompt,
+            developer_prompt: thread.metadata.developer_prompt,
+            messages: llm_messages,
+            tools,
+            normalizations,
+        })
+    }
+}
+
+/// Who produced a message and its text, as shown to the model
+fn describe(message: &DBMessage) -> Option<(String, String)> {
+    let tool_name = message.tool_name.as_deref().unwrap_or("tool");
+    match (message.role, message.message_type) {
+        (_, MessageType::Reasoning) => None,
+        (_, MessageType::ToolCall) => Some((
+            format!("assistant called {}", tool_name),
+            message.arguments.as_ref().map(Value::to_string).unwrap_or_default(),
+        )),
+        (_, MessageType::ToolResult) => Some((format!("{} result", tool_name), message.content.clone())),
+        (MessageRole::User, MessageType::Message) => Some(("user".to_string(), message.content.clone())),
+        (MessageRole::Assistant, MessageType::Message) => Some(("assistant".to_string(), message.content.clone())),
+    }
+}
+
+/// One index line: `#<seq> <who>: <first max_chars characters>`
+fn index_line(seq: usize, message: &DBMessage, max_chars: usize) -> Option<String> {
+    let (who, text) = describe(message)?;
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let snippet = match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    };
+
+    Some(format!("#{} {}: {}", seq, who, snippet))
+}
+
+/// `fetch_messages` tool: returns full messages of a thread by index number
+pub struct FetchMessagesTool {
+    thread_id: String,
+    persist_client: Arc<dyn PersistenceClient>,
+    max_ids: usize,
+}
+
+impl FetchMessagesTool {
+    pub const NAME: &'static str = "fetch_messages";
+
+    pub fn new(thread_id: impl Into<String>, persist_client: Arc<dyn PersistenceClient>) -> Self {
+        Self {
+            thread_id: thread_id.into(),
+            persist_client,
+            max_ids: 20,
+        }
+    }
+
+    /// Maximum number of messages returned per call
+    pub fn with_max_ids(mut self, max: usize) -> Self {
+        self.max_ids = max;
+        self
+    }
+}
+
+#[async_trait]
+impl LocalTool for FetchMessagesTool {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Fetch the full content of earlier messages in this conversation by their index \
+             number (the #N shown in the conversation index). Up to {} messages per call.",
+            self.max_ids
+        )
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "ids": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 1 },
+                    "maxItems": self.max_ids,
+                    "description": "Message index numbers to fetch"
+                }
+            },
+            "required": ["ids"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Vec<ToolResponse>> {
+        let ids: Vec<usize> = arguments
+            .get("ids")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Missing required array argument 'ids'"))?
+            .iter()
+            .filter_map(|id| id.as_u64().map(|id| id as usize))
+            .collect();
+        if ids.is_empty() {
+            return Err(anyhow!("'ids' must contain at least one message number"));
+        }
+        if ids.len() > self.max_ids {
+            return Err(anyhow!("At most {} messages can be fetched per call", self.max_ids));
+        }
+
+        let messages = self.persist_client.get_messages(&self.thread_id).await?;
+
+        let text = ids
+            .iter()
+            .map(|&seq| {
+                match seq.checked_sub(1).and_then(|i| messages.get(i)).and_then(describe) {
+                    Some((who, text)) => format!("#{} {}: {}", seq, who, text),
+                    None => format!("#{}: not found", seq),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(vec![ToolResponse::Text { text }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, message_type: MessageType, content: &str) -> DBMessage {
+        DBMessage {
+            role,
+            message_type,
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_index_line_collapses_and_truncates() {
+        let msg = message(MessageRole::User, MessageType::Message, "what  about\npricing for teams?");
+        assert_eq!(index_line(42, &msg, 11).unwrap(), "#42 user: what about…");
+        assert_eq!(index_line(1, &msg, 100).unwrap(), "#1 user: what about pricing for teams?");
+        assert!(index_line(1, &message(MessageRole::Assistant, MessageType::Reasoning, "x"), 10).is_none());
+    }
+
+    #[test]
+    fn test_refresh_appends_without_rebuilding() {
+        let strategy = IndexedHistoryStrategy::new(2);
+        let mut messages = vec![
+            message(MessageRole::User, MessageType::Message, "one"),
+            message(MessageRole::Assistant, MessageType::Message, "two"),
+        ];
+
+        let mut index = HistoryIndex::default();
+        assert!(strategy.refresh_index(&mut index, &messages));
+        index.entries[0].line = "cached".to_string();
+
+        messages.push(message(MessageRole::User, MessageType::Message, "three"));
+        assert!(strategy.refresh_index(&mut index, &messages));
+        assert!(!strategy.refresh_index(&mut index, &messages));
+
+        let lines: Vec<_> = index.entries.iter().map(|e| e.line.as_str()).collect();
+        assert_eq!(lines, ["cached", "#2 assistant: two", "#3 user: three"]);
+        assert_eq!(index.indexed_count, 3);
+    }
+
+    #[test]
+    fn test_refresh_rebuilds_when_thread_changed() {
+        let strategy = IndexedHistoryStrategy::new(2);
+        let messages = vec![message(MessageRole::User, MessageType::Message, "one")];
+
+        let mut index = HistoryIndex::default();
+        strategy.refresh_index(&mut index, &messages);
+        index.entries[0].line = "cached".to_string();
+
+        let replaced = vec![message(MessageRole::User, MessageType::Message, "other")];
+        assert!(strategy.refresh_index(&mut index, &replaced));
+        assert_eq!(index.entries[0].line, "#1 user: other");
+    }
+
+    #[test]
+    fn test_window_does_not_start_on_tool_result() {
+        let strategy = IndexedHistoryStrategy::new(2);
+        let messages = vec![
+            message(MessageRole::User, MessageType::Message, "q"),
+            message(MessageRole::Assistant, MessageType::ToolCall, ""),
+            message(MessageRole::Assistant, MessageType::ToolResult, "r"),
+            message(MessageRole::Assistant, MessageType::Message, "a"),
+        ];
+        assert_eq!(strategy.window_start(&messages), 1);
+    }
+}
@@ -1,7 +1,25 @@
 mod strategy;
+mod attachments;
+mod composite;
 mod default;
+mod indexed;
+mod normalize;
+mod stages;
 mod templates;
+mod tokenizer;
+mod trigger;
 
 pub use strategy::{ContextStrategy, ContextWindow};
 pub use default::DefaultContextStrategy;
-pub use templates::{DEFAULT_SYSTEM_PROMPT_TEMPLATE, DEFAULT_SUMMARIZATION_PROMPT};
+pub use composite::{
+    CompositeContextStrategy, ContextDraft, ContextStage, ContextStageConfig, StageContext, StageUsage,
+};
+pub use stages::{PinnedStage, RecencyStage, RetrievalStage, SummaryStage};
+pub use indexed::{IndexedHistoryStrategy, FetchMessagesTool};
+pub use attachments::ReadAttachmentTool;
+pub use normalize::{normalize_for_model, ContextNormalization};
+pub use tokenizer::TiktokenTokenizer;
+pub use trigger::{CadenceTrigger, OverflowTrigger, SummarizationTrigger, SummaryWindow};
+pub use templates::{
+    DEFAULT_SYSTEM_PROMPT_TEMPLATE, DEFAULT_SUMMARIZATION_PROMPT, DEFAULT_INDEXED_HISTORY_PROMPT,
+};
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use praxis_llm::{Message, ModelCapabilities};
+use praxis_persist::{DBMessage, MessageType};
+use serde::{Deserialize, Serialize};
+
+/// A rewrite applied to stored history so it fits the model of this run
+///
+/// A thread keeps the items its earlier models produced. When the next
+/// turn goes to a model that can't take some of them, the strategy
+/// rewrites them and lists what it did in `ContextWindow::normalizations`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContextNormalization {
+    /// Reasoning rows left out: the model doesn't reason
+    ReasoningDropped { count: usize },
+    /// Tool calls rewritten as assistant text (with their results): the
+    /// model has no function calling
+    ToolCallsFlattened { count: usize },
+}
+
+/// Convert stored messages into the messages `model` can take
+///
+/// Rows that don't convert (reasoning for a reasoning model, malformed
+/// tool rows) are skipped as before; only rewrites driven by the model's
+/// capabilities are reported.
+pub fn normalize_for_model(messages: Vec<DBMessage>, model: &str) -> (Vec<Message>, Vec<ContextNormalization>) {
+    let capabilities = ModelCapabilities::for_model(model);
+    let mut applied = Vec::new();
+
+    let reasoning = messages.iter().filter(|m| m.message_type == MessageType::Reasoning).count();
+    if !capabilities.reasoning && reasoning > 0 {
+        applied.push(ContextNormalization::ReasoningDropped { count: reasoning });
+    }
+
+    if capabilities.tools {
+        let converted = messages.into_iter().filter_map(|msg| msg.try_into().ok()).collect();
+        return (converted, applied);
+    }
+
+    let results: HashMap<String, String> = messages
+        .iter()
+        .filter(|m| m.message_type == MessageType::ToolResult)
+        .filter_map(|m| Some((m.tool_call_id.clone()?, m.content.clone())))
+        .collect();
+
+    let mut flattened = 0;
+    let mut converted = Vec::with_capacity(messages.len());
+    for msg in messages {
+        match msg.message_type {
+            MessageType::ToolCall => {
+                flattened += 1;
+                converted.push(Message::ai(describe_call(&msg, &results)));
+            }
+            // Folded into the text of its call
+            MessageType::ToolResult => {}
+            _ => converted.extend(Message::try_from(msg).ok()),
+        }
+    }
+    if flattened > 0 {
+        applied.push(ContextNormalization::ToolCallsFlattened { count: flattened });
+    }
+    (converted, applied)
+}
+
+fn describe_call(call: &DBMessage, results: &HashMap<String, String>) -> String {
+    let name = call.tool_name.as_deref().unwrap_or("tool");
+    let arguments = call.arguments.as_ref().map(|a| a.to_string()).unwrap_or_default();
+    let result = call.tool_call_id.as_ref().and_then(|id| results.get(id));
+    match result {
+        Some(result) => format!("[called {}({}), which returned: {}]", name, arguments, result),
+        None => format!("[called {}({}), no result recorded]", name, arguments),
+    }
+}
@@ -0,0 +1,236 @@
+//! Stages for `CompositeContextStrategy`
+//!
+//! Pinned and retrieval stages only pick user and assistant messages, so a
+//! tool result never reaches the window without the call it answers.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use praxis_persist::{DBMessage, MessageType};
+
+use crate::composite::{ContextDraft, ContextStage, StageContext};
+
+/// A user question or an assistant answer (not a tool row or reasoning)
+fn is_conversation(message: &DBMessage) -> bool {
+    message.message_type == MessageType::Message
+}
+
+/// Include `position` if it fits in what is left of `budget`; returns whether it did
+fn include(ctx: &StageContext<'_>, draft: &mut ContextDraft, position: usize, budget: usize, used: &mut usize) -> bool {
+    let tokens = ctx.message_tokens(position);
+    if *used + tokens > budget {
+        return false;
+    }
+    draft.included.insert(position);
+    *used += tokens;
+    true
+}
+
+/// The thread's opening messages, which often state the task
+///
+/// Takes the first `first_messages` user and assistant messages, as many
+/// as fit.
+pub struct PinnedStage {
+    first_messages: usize,
+}
+
+impl PinnedStage {
+    pub fn new(first_messages: usize) -> Self {
+        Self { first_messages }
+    }
+}
+
+#[async_trait]
+impl ContextStage for PinnedStage {
+    fn name(&self) -> &str {
+        "pinned"
+    }
+
+    async fn apply(&self, ctx: &StageContext<'_>, draft: &mut ContextDraft, budget: usize) -> Result<usize> {
+        let mut used = 0;
+        let opening: Vec<usize> = (0..ctx.messages.len())
+            .filter(|&position| is_conversation(&ctx.messages[position]))
+            .take(self.first_messages)
+            .collect();
+        for position in opening {
+            if !draft.included.contains(&position) && !include(ctx, draft, position, budget, &mut used) {
+                break;
+            }
+        }
+        Ok(used)
+    }
+}
+
+/// The thread's summary, as a system prompt section
+///
+/// Later stages leave out the history it covers. A summary that doesn't
+/// fit is skipped, and the history stays available to them. With a
+/// summarizer (`CompositeContextStrategy::with_summarizer`) its triggers
+/// also run here, summarizing in the background as `DefaultContextStrategy`
+/// does.
+#[derive(Default)]
+pub struct SummaryStage;
+
+impl SummaryStage {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ContextStage for SummaryStage {
+    fn name(&self) -> &str {
+        "summary"
+    }
+
+    async fn apply(&self, ctx: &StageContext<'_>, draft: &mut ContextDraft, budget: usize) -> Result<usize> {
+        let existing_summary = ctx.thread.summary.as_ref().map(|summary| summary.text.as_str());
+        if let Some(summarizer) = ctx.summarizer {
+            let unsummarized: Vec<DBMessage> = ctx
+                .messages
+                .iter()
+                .filter(|msg| msg.created_at > ctx.thread.last_summary_update)
+                .cloned()
+                .collect();
+            if !unsummarized.is_empty() {
+                summarizer.spawn_due_summary(
+                    ctx.thread_id,
+                    ctx.persist_client,
+                    &unsummarized,
+                    existing_summary,
+                    ctx.model,
+                )?;
+            }
+        }
+
+        let Some(text) = existing_summary else {
+            return Ok(0);
+        };
+        let section = format!("Summary of the conversation so far:\n{}", text);
+        let tokens = ctx.text_tokens(&section);
+        if tokens > budget {
+            return Ok(0);
+        }
+        draft.sections.push(section);
+        draft.summarized_until = Some(ctx.thread.last_summary_update);
+        Ok(tokens)
+    }
+}
+
+/// Older messages that share the most words with the turn's question
+///
+/// Lexical: messages score by the distinct words (three letters or more)
+/// they share with `StageContext::query`. Takes up to `max_messages`,
+/// best first, as many as fit.
+pub struct RetrievalStage {
+    max_messages: usize,
+}
+
+impl RetrievalStage {
+    pub fn new(max_messages: usize) -> Self {
+        Self { max_messages }
+    }
+}
+
+/// Lowercased words of three characters or more
+fn terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[async_trait]
+impl ContextStage for RetrievalStage {
+    fn name(&self) -> &str {
+        "retrieval"
+    }
+
+    async fn apply(&self, ctx: &StageContext<'_>, draft: &mut ContextDraft, budget: usize) -> Result<usize> {
+        let Some(query) = ctx.query.map(terms).filter(|terms| !terms.is_empty()) else {
+            return Ok(0);
+        };
+
+        // Best score first, newer first among equals
+        let mut candidates: Vec<(usize, usize)> = (0..ctx.messages.len())
+            .filter(|position| !draft.included.contains(position))
+            .filter(|&position| is_conversation(&ctx.messages[position]))
+            .filter_map(|position| {
+                let score = terms(&ctx.messages[position].content).intersection(&query).count();
+                (score > 0).then_some((score, position))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.cmp(a));
+
+        let mut used = 0;
+        let mut taken = 0;
+        for (_, position) in candidates {
+            if taken == self.max_messages {
+                break;
+            }
+            if include(ctx, draft, position, budget, &mut used) {
+                taken += 1;
+            }
+        }
+        Ok(used)
+    }
+}
+
+/// The most recent messages, newest first, as many as fit
+///
+/// Stops at the first message that doesn't fit, at a message already
+/// included or at the history a summary covers, and never starts the run
+/// of messages on a tool result.
+#[derive(Default)]
+pub struct RecencyStage;
+
+impl RecencyStage {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ContextStage for RecencyStage {
+    fn name(&self) -> &str {
+        "recency"
+    }
+
+    async fn apply(&self, ctx: &StageContext<'_>, draft: &mut ContextDraft, budget: usize) -> Result<usize> {
+        let mut used = 0;
+        let mut start = ctx.messages.len();
+        while start > 0 {
+            let position = start - 1;
+            let message = &ctx.messages[position];
+            let summarized = draft.summarized_until.is_some_and(|until| message.created_at <= until);
+            let tokens = ctx.message_tokens(position);
+            if summarized || draft.included.contains(&position) || used + tokens > budget {
+                break;
+            }
+            used += tokens;
+            start = position;
+        }
+
+        let end = ctx.messages.len();
+        while start < end && ctx.messages[start].message_type == MessageType::ToolResult {
+            used -= ctx.message_tokens(start);
+            start += 1;
+        }
+        draft.included.extend(start..end);
+        Ok(used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms_ignore_case_punctuation_and_short_words() {
+        let terms = terms("What's the Weather in Lisbon? lisbon!");
+        let mut terms: Vec<_> = terms.into_iter().collect();
+        terms.sort();
+        assert_eq!(terms, ["lisbon", "the", "weather", "what"]);
+    }
+}
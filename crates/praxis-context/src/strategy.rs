@@ -2,23 +2,81 @@ use std::sync::Arc;
 use anyhow::Result;
 use praxis_llm::Message;
 use async_trait::async_trait;
-use praxis_persist::PersistenceClient;
+use praxis_persist::{PersistenceClient, ThreadSummary};
+use praxis_mcp::LocalTool;
+
+use crate::normalize::ContextNormalization;
 
 /// Result of context retrieval
 #[derive(Debug, Clone)]
 pub struct ContextWindow {
     pub system_prompt: String,
+    /// The thread's `ThreadMetadata::developer_prompt`, to send as a
+    /// developer message after the system prompt
+    pub developer_prompt: Option<String>,
     pub messages: Vec<Message>,
+    /// Tools the strategy needs during the run (register via `GraphInput::with_run_tools`)
+    pub tools: Vec<Arc<dyn LocalTool>>,
+    /// Rewrites applied so the stored history fits the run's model
+    pub normalizations: Vec<ContextNormalization>,
 }
 
 /// Strategy for building context window from conversation history
+///
+/// Shared as `Arc<dyn ContextStrategy>` by request handlers. The persistence
+/// client is borrowed; strategies clone the `Arc` only for what outlives the
+/// call (background summaries, run tools).
 #[async_trait]
 pub trait ContextStrategy: Send + Sync {
     /// Get context window for a conversation
+    ///
+    /// `exclude_message_id` names the message of the turn being started
+    /// (typically the user message just saved), which the caller sends
+    /// itself. It is left out whether or not the read already sees it, so
+    /// the window never depends on write visibility.
+    ///
+    /// `model` is the model the turn goes to. History written by another
+    /// model is normalized for it (see `normalize_for_model`).
     async fn get_context_window(
         &self,
         thread_id: &str,
-        persist_client: Arc<dyn PersistenceClient>,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
     ) -> Result<ContextWindow>;
+    
+    /// Summarize the thread's unsummarized history now, whatever the
+    /// strategy's triggers say, and return the saved summary
+    ///
+    /// `None` when nothing was said since the last summary. Strategies
+    /// that keep no summary fail.
+    async fn summarize_now(
+        &self,
+        _thread_id: &str,
+        _persist_client: &Arc<dyn PersistenceClient>,
+    ) -> Result<Option<ThreadSummary>> {
+        anyhow::bail!("This context strategy doesn't keep thread summaries")
+    }
+}
+
+#[async_trait]
+impl<T: ContextStrategy + ?Sized> ContextStrategy for Arc<T> {
+    async fn get_context_window(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
+    ) -> Result<ContextWindow> {
+        (**self).get_context_window(thread_id, persist_client, exclude_message_id, model).await
+    }
+    
+    async fn summarize_now(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+    ) -> Result<Option<ThreadSummary>> {
+        (**self).summarize_now(thread_id, persist_client).await
+    }
 }
 
@@ -4,3 +4,6 @@ pub const DEFAULT_SYSTEM_PROMPT_TEMPLATE: &str =
 pub const DEFAULT_SUMMARIZATION_PROMPT: &str = 
     include_str!("templates/default_summarization.txt");
 
+
+pub const DEFAULT_INDEXED_HISTORY_PROMPT: &str = 
+    include_str!("templates/indexed_history_prompt.txt");
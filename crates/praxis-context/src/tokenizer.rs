@@ -0,0 +1,174 @@
+use praxis_llm::{Content, ContentPart, Message, TokenCounter, Tool};
+use praxis_persist::ContentTokenizer;
+use serde_json::Value;
+use tiktoken_rs::{cl100k_base_singleton, o200k_base_singleton, CoreBPE};
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+
+/// Tokens every message is wrapped in (`<|start|>{role}\n ... <|end|>\n`)
+const TOKENS_PER_MESSAGE: usize = 3;
+/// A message's `name` takes one token on top of its text
+const TOKENS_PER_NAME: usize = 1;
+/// Every reply is primed with `<|start|>assistant<|message|>`
+const REPLY_PRIMING_TOKENS: usize = 3;
+
+/// Overhead of tool definitions in the prompt, which the model sees as a
+/// TypeScript-like namespace
+struct ToolOverhead {
+    per_function: usize,
+    properties: usize,
+    per_property: usize,
+    /// Negative: the enum replaces part of the property's type
+    enum_start: isize,
+    per_enum_item: usize,
+    end: usize,
+}
+
+impl ToolOverhead {
+    fn for_encoding(encoding: Tokenizer) -> Self {
+        let per_function = match encoding {
+            Tokenizer::O200kBase => 7,
+            _ => 10,
+        };
+        Self { per_function, properties: 3, per_property: 3, enum_start: -3, per_enum_item: 3, end: 12 }
+    }
+}
+
+/// `ContentTokenizer` and `TokenCounter` using the tiktoken encoding of the
+/// model
+///
+/// Models tiktoken does not list fall back to o200k_base for the gpt-5 and
+/// o-series families and to cl100k_base for everything else, as do the
+/// legacy p50k/r50k encodings. Message and tool overhead follows the
+/// OpenAI cookbook's `num_tokens_from_messages` and `num_tokens_for_tools`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TiktokenTokenizer;
+
+impl TiktokenTokenizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn encoding(model: &str) -> Tokenizer {
+        get_tokenizer(model).unwrap_or_else(|| {
+            if ["gpt-5", "o1", "o3", "o4"].iter().any(|family| model.starts_with(family)) {
+                Tokenizer::O200kBase
+            } else {
+                Tokenizer::Cl100kBase
+            }
+        })
+    }
+
+    /// Run `count` with the encoding of `encoding`, locked once for all its
+    /// texts
+    fn with_bpe<R>(encoding: Tokenizer, count: impl FnOnce(&CoreBPE) -> R) -> R {
+        let bpe = match encoding {
+            Tokenizer::O200kBase => o200k_base_singleton(),
+            _ => cl100k_base_singleton(),
+        };
+        let guard = bpe.lock();
+        count(&guard)
+    }
+
+    fn message_tokens(bpe: &CoreBPE, message: &Message) -> usize {
+        let tokens = |text: &str| bpe.encode_with_special_tokens(text).len();
+        let mut count = TOKENS_PER_MESSAGE + tokens(message.role());
+        let name = match message {
+            Message::System { name, .. }
+            | Message::Developer { name, .. }
+            | Message::Human { name, .. }
+            | Message::AI { name, .. } => name.as_deref(),
+            Message::Tool { .. } => None,
+        };
+        if let Some(name) = name {
+            count += tokens(name) + TOKENS_PER_NAME;
+        }
+        match message {
+            Message::System { content, .. }
+            | Message::Developer { content, .. }
+            | Message::Human { content, .. }
+            | Message::Tool { content, .. } => {
+                count += content_tokens(content, &tokens);
+            }
+            Message::AI { content, tool_calls, .. } => {
+                count += content.as_ref().map_or(0, |content| content_tokens(content, &tokens));
+                for call in tool_calls.iter().flatten() {
+                    count += tokens(&call.function.name) + tokens(&call.function.arguments);
+                }
+            }
+        }
+        count
+    }
+
+    fn tool_tokens(bpe: &CoreBPE, overhead: &ToolOverhead, tools: &[Tool]) -> usize {
+        let tokens = |text: &str| bpe.encode_with_special_tokens(text).len() as isize;
+        let mut count = 0isize;
+        for tool in tools {
+            let function = &tool.function;
+            let description = function.description.as_deref().unwrap_or_default();
+            count += overhead.per_function as isize;
+            count += tokens(&format!("{}:{}", function.name, description.trim_end_matches('.')));
+
+            let properties = function.parameters.get("properties").and_then(Value::as_object);
+            let Some(properties) = properties.filter(|properties| !properties.is_empty()) else {
+                continue;
+            };
+            count += overhead.properties as isize;
+            for (name, property) in properties {
+                count += overhead.per_property as isize;
+                if let Some(items) = property.get("enum").and_then(Value::as_array) {
+                    count += overhead.enum_start;
+                    for item in items {
+                        count += overhead.per_enum_item as isize;
+                        count += tokens(&item.as_str().map_or_else(|| item.to_string(), str::to_string));
+                    }
+                }
+                let kind = property.get("type").and_then(Value::as_str).unwrap_or_default();
+                let description = property.get("description").and_then(Value::as_str).unwrap_or_default();
+                count += tokens(&format!("{}:{}:{}", name, kind, description.trim_end_matches('.')));
+            }
+        }
+        if !tools.is_empty() {
+            count += overhead.end as isize;
+        }
+        count.max(0) as usize
+    }
+}
+
+/// Tokens of the text parts of `content`; images are left out
+fn content_tokens(content: &Content, tokens: &impl Fn(&str) -> usize) -> usize {
+    match content {
+        Content::Text(text) => tokens(text),
+        Content::Parts(parts) => parts.iter().filter_map(ContentPart::as_text).map(tokens).sum(),
+    }
+}
+
+impl ContentTokenizer for TiktokenTokenizer {
+    fn count(&self, model: &str, text: &str) -> usize {
+        Self::with_bpe(Self::encoding(model), |bpe| bpe.encode_with_special_tokens(text).len())
+    }
+}
+
+impl TokenCounter for TiktokenTokenizer {
+    fn count_messages(&self, model: &str, messages: &[Message], tools: &[Tool]) -> usize {
+        let encoding = Self::encoding(model);
+        let overhead = ToolOverhead::for_encoding(encoding);
+        Self::with_bpe(encoding, |bpe| {
+            let messages: usize = messages.iter().map(|message| Self::message_tokens(bpe, message)).sum();
+            messages + REPLY_PRIMING_TOKENS + Self::tool_tokens(bpe, &overhead, tools)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding_follows_model() {
+        assert_eq!(TiktokenTokenizer::encoding("gpt-4o-mini"), Tokenizer::O200kBase);
+        assert_eq!(TiktokenTokenizer::encoding("gpt-5"), Tokenizer::O200kBase);
+        assert_eq!(TiktokenTokenizer::encoding("gpt-4"), Tokenizer::Cl100kBase);
+        assert_eq!(TiktokenTokenizer::encoding("some-local-model"), Tokenizer::Cl100kBase);
+        assert_eq!(TiktokenTokenizer.count("gpt-4o", "hello world"), 2);
+    }
+}
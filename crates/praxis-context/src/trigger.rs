@@ -0,0 +1,110 @@
+//! When `DefaultContextStrategy` summarizes a thread
+
+use chrono::{DateTime, Duration, Utc};
+use praxis_persist::{DBMessage, MessageRole, MessageType, SummaryTrigger};
+
+/// The unsummarized part of a thread at the start of a turn
+pub struct SummaryWindow<'a> {
+    /// Messages after the last summary, oldest first, without the message
+    /// of the turn being started
+    pub messages: &'a [DBMessage],
+    /// Tokens in `messages`
+    pub tokens: usize,
+    /// When the turn started
+    pub now: DateTime<Utc>,
+}
+
+impl SummaryWindow<'_> {
+    /// Assistant answers in the window (tool calls and reasoning don't count)
+    pub fn assistant_turns(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|m| m.role == MessageRole::Assistant && m.message_type == MessageType::Message)
+            .count()
+    }
+
+    /// Time since the newest message in the window
+    pub fn idle_for(&self) -> Option<Duration> {
+        self.messages.iter().map(|m| m.created_at).max().map(|last| self.now - last)
+    }
+}
+
+/// Decides, at the start of each turn, whether to summarize the window
+///
+/// Checked before the turn's context is returned; when it fires the summary
+/// is generated in the background and shows up from a later turn on.
+pub trait SummarizationTrigger: Send + Sync {
+    fn should_summarize(&self, window: &SummaryWindow<'_>) -> bool;
+
+    /// Recorded on the summary as its cause
+    fn kind(&self) -> SummaryTrigger {
+        SummaryTrigger::Cadence
+    }
+}
+
+/// Fires once the window holds more than `max_tokens`
+///
+/// `DefaultContextStrategy` always checks this one first, whatever other
+/// triggers it has.
+#[derive(Debug, Clone, Copy)]
+pub struct OverflowTrigger {
+    pub max_tokens: usize,
+}
+
+impl OverflowTrigger {
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+}
+
+impl SummarizationTrigger for OverflowTrigger {
+    fn should_summarize(&self, window: &SummaryWindow<'_>) -> bool {
+        window.tokens > self.max_tokens
+    }
+
+    fn kind(&self) -> SummaryTrigger {
+        SummaryTrigger::Overflow
+    }
+}
+
+/// Fires every `every_turns` assistant answers, or when the user comes back
+/// after `after_idle` without activity
+///
+/// Either condition alone is enough; neither fires on a window without an
+/// assistant answer, since there is nothing to summarize yet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CadenceTrigger {
+    pub every_turns: Option<usize>,
+    pub after_idle: Option<Duration>,
+}
+
+impl CadenceTrigger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_every_turns(mut self, turns: usize) -> Self {
+        self.every_turns = Some(turns.max(1));
+        self
+    }
+
+    pub fn with_after_idle(mut self, idle: Duration) -> Self {
+        self.after_idle = Some(idle);
+        self
+    }
+}
+
+impl SummarizationTrigger for CadenceTrigger {
+    fn should_summarize(&self, window: &SummaryWindow<'_>) -> bool {
+        let turns = window.assistant_turns();
+        if turns == 0 {
+            return false;
+        }
+        let turns_due = self.every_turns.is_some_and(|every| turns >= every);
+        let idle_due = match (self.after_idle, window.idle_for()) {
+            (Some(after), Some(idle)) => idle >= after,
+            _ => false,
+        };
+        turns_due || idle_due
+    }
+}
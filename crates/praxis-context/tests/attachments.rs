@@ -0,0 +1,148 @@
+//! End-to-end: the model reads a file the user attached to the thread
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use praxis_context::ReadAttachmentTool;
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{
+    ChatClient, ChatRequest, ChatResponse, Content, LLMClient, Message, ReasoningClient,
+    ResponseOutput, ResponseRequest,
+};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use praxis_persist::{InMemoryPersistenceClient, PersistenceClient, ThreadMetadata};
+use serde_json::json;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+const REPORT: &str = "Quarterly report\nRevenue: 1.2M\nChurn: 3%\nHeadcount: 42\n";
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>;
+
+/// Turn 1: read lines 2-3 of report.txt. Turn 2: answer with the tool result.
+#[derive(Default)]
+struct ReadingLLM {
+    turn: Mutex<usize>,
+    tools: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl ChatClient for ReadingLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let turn = {
+            let mut turn = self.turn.lock().unwrap();
+            *turn += 1;
+            *turn
+        };
+        if let Some(tools) = &request.options.tools {
+            *self.tools.lock().unwrap() = tools.iter().map(|t| t.function.name.clone()).collect();
+        }
+
+        let events = if turn == 1 {
+            vec![
+                praxis_llm::StreamEvent::ToolCall {
+                    index: 0,
+                    id: Some("call_1".to_string()),
+                    name: Some("read_attachment".to_string()),
+                    arguments: Some(r#"{"name":"report.txt","range":{"start_line":2,"end_line":3}}"#.to_string()),
+                },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+            ]
+        } else {
+            let read = request
+                .messages
+                .iter()
+                .rev()
+                .find_map(|m| match m {
+                    Message::Tool { content: Content::Text(text), .. } => Some(text.clone()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            vec![
+                praxis_llm::StreamEvent::Message { content: format!("The report says: {}", read), choice_index: None, logprobs: None },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+            ]
+        };
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for ReadingLLM {
+    async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(&self, _request: ResponseRequest) -> Result<EventStream> {
+        anyhow::bail!("not scripted")
+    }
+}
+
+impl LLMClient for ReadingLLM {}
+
+async fn thread_with_attachments() -> (Arc<dyn PersistenceClient>, String) {
+    let persist: Arc<dyn PersistenceClient> = Arc::new(InMemoryPersistenceClient::new());
+    let thread = persist.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+    persist.save_attachment(&thread.id, "report.txt", "text/plain", REPORT.as_bytes().to_vec()).await.unwrap();
+    persist.save_attachment(&thread.id, "logo.png", "image/png", vec![0x89, b'P', b'N', b'G', 0xff]).await.unwrap();
+    (persist, thread.id)
+}
+
+#[tokio::test]
+async fn test_model_reads_an_attachment_range() {
+    let (persist, thread_id) = thread_with_attachments().await;
+    let tool = ReadAttachmentTool::for_thread(&thread_id, persist).await.unwrap().expect("thread has attachments");
+
+    let llm = Arc::new(ReadingLLM::default());
+    let graph = Graph::new(llm.clone(), Arc::new(MCPToolExecutor::new()), GraphConfig::default());
+    let tools: Vec<Arc<dyn LocalTool>> = vec![Arc::new(tool)];
+    let input = GraphInput::new(thread_id, vec![Message::human("What's the churn?")], LLMConfig::new("mock-model"))
+        .with_run_tools(tools);
+
+    let mut events = graph.spawn_run(input, None);
+    let mut read = None;
+    let mut answer = String::new();
+    while let Some(event) = events.recv().await {
+        match event {
+            StreamEvent::ToolResult { result, is_error, .. } => read = Some((result, is_error)),
+            StreamEvent::Message { content, .. } => answer.push_str(&content),
+            StreamEvent::EndStream { .. } => break,
+            StreamEvent::Error { message, .. } => panic!("graph error: {}", message),
+            _ => {}
+        }
+    }
+
+    assert_eq!(*llm.tools.lock().unwrap(), vec!["read_attachment".to_string()]);
+    let (result, is_error) = read.expect("read_attachment should be called");
+    assert!(!is_error, "{}", result);
+    assert_eq!(result, "Revenue: 1.2M\nChurn: 3%");
+    assert!(answer.contains("Churn: 3%"), "{}", answer);
+}
+
+#[tokio::test]
+async fn test_tool_lists_files_and_refuses_binary_or_unknown_names() {
+    let (persist, thread_id) = thread_with_attachments().await;
+    let tool = ReadAttachmentTool::for_thread(&thread_id, persist).await.unwrap().unwrap();
+
+    assert!(tool.description().contains("- report.txt (text/plain, 55 bytes)"));
+    assert_eq!(tool.input_schema()["properties"]["name"]["enum"], json!(["report.txt", "logo.png"]));
+
+    let binary = tool.call(json!({ "name": "logo.png" })).await.unwrap_err();
+    assert!(binary.to_string().contains("binary (image/png, 5 bytes)"), "{}", binary);
+    let unknown = tool.call(json!({ "name": "secrets.txt" })).await.unwrap_err();
+    assert!(unknown.to_string().contains("available: report.txt, logo.png"), "{}", unknown);
+
+    let whole = tool.call(json!({ "name": "report.txt" })).await.unwrap();
+    assert!(matches!(&whole[..], [ToolResponse::Text { text }] if text == REPORT.trim_end()));
+}
+
+#[tokio::test]
+async fn test_no_tool_without_attachments() {
+    let persist: Arc<dyn PersistenceClient> = Arc::new(InMemoryPersistenceClient::new());
+    let thread = persist.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+    assert!(ReadAttachmentTool::for_thread(&thread.id, persist).await.unwrap().is_none());
+}
@@ -0,0 +1,170 @@
+//! Composite strategies split the token budget between their stages in
+//! order, spilling what a stage leaves to the ones after it
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use praxis_context::{
+    CompositeContextStrategy, ContextStageConfig, RecencyStage, RetrievalStage, StageUsage, TiktokenTokenizer,
+};
+use praxis_llm::Message;
+use praxis_persist::{
+    ContentTokenizer, DBMessage, InMemoryPersistenceClient, MessageRole, MessageType, PersistenceClient,
+    SummaryTrigger, ThreadMetadata,
+};
+use std::sync::Arc;
+
+const MODEL: &str = "gpt-4o";
+const MESSAGE_TOKENS: usize = 20;
+const SUMMARY: &str = "The user is planning a trip to Portugal.";
+
+const HISTORY: [&str; 10] = [
+    "Plan a trip to Lisbon",
+    "Lisbon in May is mild",
+    "And Porto?",
+    "Porto is rainier",
+    "Trains between them?",
+    "Hourly trains",
+    "Prices?",
+    "About 30 euros",
+    "Thanks",
+    "You're welcome",
+];
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap()
+}
+
+/// Tokens the default base system prompt takes off the budget
+fn base_prompt_tokens() -> usize {
+    TiktokenTokenizer.count(MODEL, "You are a helpful assistant.")
+}
+
+struct Fixture {
+    persist: Arc<dyn PersistenceClient>,
+    thread_id: String,
+}
+
+impl Fixture {
+    /// `HISTORY` as alternating user and assistant messages of
+    /// `MESSAGE_TOKENS` each, one minute apart, then the turn's question
+    async fn new() -> Self {
+        let persist: Arc<dyn PersistenceClient> = Arc::new(InMemoryPersistenceClient::new());
+        let thread = persist.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+        let fixture = Self { persist, thread_id: thread.id };
+
+        for (i, content) in HISTORY.iter().enumerate() {
+            let role = if i % 2 == 0 { MessageRole::User } else { MessageRole::Assistant };
+            fixture.save(&format!("m-{}", i), role, content, i as i64).await;
+        }
+        fixture.save("question", MessageRole::User, "Hotels in Lisbon?", HISTORY.len() as i64).await;
+        fixture
+    }
+
+    async fn save(&self, id: &str, role: MessageRole, content: &str, minute: i64) {
+        self.persist
+            .save_message(DBMessage {
+                id: id.to_string(),
+                thread_id: self.thread_id.clone(),
+                user_id: "user-1".to_string(),
+                role,
+                message_type: MessageType::Message,
+                content: content.to_string(),
+                created_at: start() + Duration::minutes(minute),
+                token_count: Some(MESSAGE_TOKENS as u32),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn compose(&self, strategy: &CompositeContextStrategy) -> (Vec<String>, String, Vec<StageUsage>) {
+        let (window, usage) = strategy
+            .compose(&self.thread_id, &self.persist, Some("question"), MODEL)
+            .await
+            .unwrap();
+        let texts = window.messages.iter().map(text).collect();
+        (texts, window.system_prompt, usage)
+    }
+}
+
+fn text(message: &Message) -> String {
+    match message {
+        Message::Human { content, .. } => content.as_text().unwrap_or_default().to_string(),
+        Message::AI { content, .. } => content.as_ref().and_then(|c| c.as_text()).unwrap_or_default().to_string(),
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+fn stages(json: serde_json::Value) -> Vec<ContextStageConfig> {
+    serde_json::from_value(json).unwrap()
+}
+
+#[tokio::test]
+async fn test_budget_accounting_adds_up() {
+    let fixture = Fixture::new().await;
+    // Covers the first four messages
+    fixture
+        .persist
+        .save_thread_summary(&fixture.thread_id, SUMMARY.to_string(), start() + Duration::minutes(3), SummaryTrigger::Manual)
+        .await
+        .unwrap();
+
+    let available = 200;
+    let strategy = CompositeContextStrategy::from_config(
+        available + base_prompt_tokens(),
+        &stages(serde_json::json!([
+            { "kind": "pinned", "share_percent": 10 },
+            { "kind": "summary", "share_percent": 20 },
+            { "kind": "retrieval", "share_percent": 20 },
+            { "kind": "recency", "share_percent": 50 },
+        ])),
+    );
+    let (texts, system_prompt, usage) = fixture.compose(&strategy).await;
+
+    let names: Vec<&str> = usage.iter().map(|u| u.stage.as_str()).collect();
+    assert_eq!(names, ["pinned", "summary", "retrieval", "recency"]);
+
+    // Each stage gets its share plus what the previous one left
+    let mut spill = 0;
+    for (stage, percent) in usage.iter().zip([10, 20, 20, 50]) {
+        assert_eq!(stage.budget, available * percent / 100 + spill, "budget of {}", stage.stage);
+        assert!(stage.used <= stage.budget);
+        spill = stage.budget - stage.used;
+    }
+
+    // What the stages used is exactly what the window holds
+    let summary_tokens = TiktokenTokenizer.count(MODEL, &format!("Summary of the conversation so far:\n{}", SUMMARY));
+    let used: usize = usage.iter().map(|u| u.used).sum();
+    assert_eq!(usage[1].used, summary_tokens);
+    assert_eq!(used, summary_tokens + texts.len() * MESSAGE_TOKENS);
+    assert!(used <= available);
+    assert!(system_prompt.ends_with(SUMMARY));
+
+    // Pinned opening, retrieved answer, then the history after the summary
+    assert_eq!(
+        texts,
+        [HISTORY[0], HISTORY[1], HISTORY[4], HISTORY[5], HISTORY[6], HISTORY[7], HISTORY[8], HISTORY[9]]
+    );
+}
+
+#[tokio::test]
+async fn test_stage_order_changes_the_window() {
+    let fixture = Fixture::new().await;
+    let max_tokens = 100 + base_prompt_tokens();
+
+    // Retrieval first: room for one of the two Lisbon messages, the rest
+    // spills over to four recent ones
+    let retrieval_first = CompositeContextStrategy::new(max_tokens)
+        .with_stage(Arc::new(RetrievalStage::new(5)), 30)
+        .with_stage(Arc::new(RecencyStage::new()), 70);
+    let (texts, _, usage) = fixture.compose(&retrieval_first).await;
+    assert_eq!(texts, [HISTORY[1], HISTORY[6], HISTORY[7], HISTORY[8], HISTORY[9]]);
+    assert_eq!(usage.iter().map(|u| (u.budget, u.used)).collect::<Vec<_>>(), [(30, 20), (80, 80)]);
+
+    // Recency first: three recent ones, and both Lisbon messages with what is left
+    let recency_first = CompositeContextStrategy::new(max_tokens)
+        .with_stage(Arc::new(RecencyStage::new()), 70)
+        .with_stage(Arc::new(RetrievalStage::new(5)), 30);
+    let (texts, _, usage) = fixture.compose(&recency_first).await;
+    assert_eq!(texts, [HISTORY[0], HISTORY[1], HISTORY[7], HISTORY[8], HISTORY[9]]);
+    assert_eq!(usage.iter().map(|u| (u.budget, u.used)).collect::<Vec<_>>(), [(70, 60), (40, 40)]);
+}
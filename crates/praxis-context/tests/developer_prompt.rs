@@ -0,0 +1,53 @@
+//! The thread's developer prompt comes with the window, apart from the
+//! system prompt
+
+use praxis_context::{CompositeContextStrategy, ContextStrategy, DefaultContextStrategy, RecencyStage};
+use praxis_llm::testkit::MockLLMClient;
+use praxis_persist::{
+    DBMessage, InMemoryPersistenceClient, MessageRole, MessageType, PersistenceClient, ThreadMetadata,
+};
+use std::sync::Arc;
+
+const DEVELOPER_PROMPT: &str = "Answer in French.";
+
+async fn thread(developer_prompt: Option<&str>) -> (Arc<dyn PersistenceClient>, String) {
+    let persist: Arc<dyn PersistenceClient> = Arc::new(InMemoryPersistenceClient::new());
+    let metadata = ThreadMetadata { developer_prompt: developer_prompt.map(str::to_string), ..Default::default() };
+    let thread = persist.create_thread("user-1", metadata).await.unwrap();
+    persist
+        .save_message(DBMessage {
+            thread_id: thread.id.clone(),
+            user_id: "user-1".to_string(),
+            role: MessageRole::User,
+            message_type: MessageType::Message,
+            content: "Hello".to_string(),
+            token_count: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    (persist, thread.id)
+}
+
+#[tokio::test]
+async fn test_default_strategy_carries_the_developer_prompt() {
+    let (persist, thread_id) = thread(Some(DEVELOPER_PROMPT)).await;
+    let strategy = DefaultContextStrategy::new(100_000, Arc::new(MockLLMClient::new()));
+
+    let window = strategy.get_context_window(&thread_id, &persist, None, "gpt-5").await.unwrap();
+    assert_eq!(window.developer_prompt.as_deref(), Some(DEVELOPER_PROMPT));
+    assert!(!window.system_prompt.contains(DEVELOPER_PROMPT));
+}
+
+#[tokio::test]
+async fn test_composite_strategy_carries_the_developer_prompt() {
+    let strategy = CompositeContextStrategy::new(1_000).with_stage(Arc::new(RecencyStage::new()), 100);
+
+    let (persist, thread_id) = thread(Some(DEVELOPER_PROMPT)).await;
+    let window = strategy.get_context_window(&thread_id, &persist, None, "gpt-4o").await.unwrap();
+    assert_eq!(window.developer_prompt.as_deref(), Some(DEVELOPER_PROMPT));
+
+    let (persist, thread_id) = thread(None).await;
+    let window = strategy.get_context_window(&thread_id, &persist, None, "gpt-4o").await.unwrap();
+    assert_eq!(window.developer_prompt, None);
+}
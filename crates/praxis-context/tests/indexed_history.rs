@@ -0,0 +1,275 @@
+//! End-to-end: the model reads the index, fetches an old message and answers from it
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use praxis_context::{ContextStrategy, IndexedHistoryStrategy};
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{
+    ChatClient, ChatRequest, ChatResponse, Content, LLMClient, Message, ReasoningClient,
+    ResponseOutput, ResponseRequest,
+};
+use praxis_mcp::MCPToolExecutor;
+use praxis_persist::{
+    DBMessage, HistoryIndex, MessageRole, MessageType, PersistenceClient, SummaryTrigger, Thread, ThreadMetadata,
+};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+const SECRET: &str = "ACCT-4242-7788";
+const THREAD_ID: &str = "thread-1";
+
+/// Single-thread in-memory persistence
+#[derive(Default)]
+struct MemoryPersistence {
+    messages: Mutex<Vec<DBMessage>>,
+    index: Mutex<Option<HistoryIndex>>,
+    index_saves: Mutex<usize>,
+}
+
+impl MemoryPersistence {
+    fn push(&self, role: MessageRole, content: &str) {
+        let mut messages = self.messages.lock().unwrap();
+        let id = format!("msg-{}", messages.len() + 1);
+        messages.push(DBMessage {
+            id,
+            thread_id: THREAD_ID.to_string(),
+            role,
+            message_type: MessageType::Message,
+            content: content.to_string(),
+            ..Default::default()
+        });
+    }
+}
+
+#[async_trait]
+impl PersistenceClient for MemoryPersistence {
+    async fn save_message(&self, message: DBMessage) -> praxis_persist::Result<()> {
+        self.messages.lock().unwrap().push(message);
+        Ok(())
+    }
+
+    async fn get_messages(&self, _thread_id: &str) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self.messages.lock().unwrap().clone())
+    }
+
+    async fn get_messages_after(
+        &self,
+        thread_id: &str,
+        after: DateTime<Utc>,
+    ) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self
+            .get_messages(thread_id)
+            .await?
+            .into_iter()
+            .filter(|m| m.created_at > after)
+            .collect())
+    }
+
+    async fn create_thread(&self, _user_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<Thread> {
+        unimplemented!("threads are pre-created in these tests")
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> praxis_persist::Result<Option<Thread>> {
+        let now = Utc::now();
+        Ok(Some(Thread {
+            id: thread_id.to_string(),
+            user_id: "user-1".to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata: ThreadMetadata::default(),
+            last_summary_update: now,
+            summary: None,
+            history_index: self.index.lock().unwrap().clone(),
+            archived_at: None,
+            conversation_key: None,
+        }))
+    }
+
+    async fn save_thread_summary(
+        &self,
+        _thread_id: &str,
+        _summary: String,
+        _generated_at: DateTime<Utc>,
+        _trigger: SummaryTrigger,
+    ) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn update_thread_metadata(&self, _thread_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn save_thread_index(&self, _thread_id: &str, index: HistoryIndex) -> praxis_persist::Result<()> {
+        *self.index.lock().unwrap() = Some(index);
+        *self.index_saves.lock().unwrap() += 1;
+        Ok(())
+    }
+
+    async fn delete_thread(&self, _thread_id: &str, _user_id: &str) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn list_threads(
+        &self,
+        _user_id: &str,
+        _limit: Option<i64>,
+        _skip: Option<i64>,
+    ) -> praxis_persist::Result<Vec<Thread>> {
+        Ok(Vec::new())
+    }
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>;
+
+/// Turn 1: fetch message #2. Turn 2: answer with whatever the tool returned.
+#[derive(Default)]
+struct FetchingLLM {
+    turn: Mutex<usize>,
+}
+
+#[async_trait]
+impl ChatClient for FetchingLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let turn = {
+            let mut turn = self.turn.lock().unwrap();
+            *turn += 1;
+            *turn
+        };
+
+        let events = if turn == 1 {
+            vec![
+                praxis_llm::StreamEvent::ToolCall {
+                    index: 0,
+                    id: Some("call_1".to_string()),
+                    name: Some("fetch_messages".to_string()),
+                    arguments: Some(r#"{"ids":[2]}"#.to_string()),
+                },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+            ]
+        } else {
+            let fetched = request
+                .messages
+                .iter()
+                .rev()
+                .find_map(|m| match m {
+                    Message::Tool { content: Content::Text(text), .. } => Some(text.clone()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            vec![
+                praxis_llm::StreamEvent::Message { content: format!("From earlier: {}", fetched), choice_index: None, logprobs: None },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+            ]
+        };
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for FetchingLLM {
+    async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(&self, _request: ResponseRequest) -> Result<EventStream> {
+        anyhow::bail!("not scripted")
+    }
+}
+
+impl LLMClient for FetchingLLM {}
+
+fn long_thread() -> Arc<MemoryPersistence> {
+    let persist = Arc::new(MemoryPersistence::default());
+    persist.push(MessageRole::User, "Hi, I need help with my billing account.");
+    persist.push(
+        MessageRole::User,
+        &format!(
+            "For reference, here are all my account details so you have them later on: \
+             the number is {}.",
+            SECRET
+        ),
+    );
+    for i in 0..8 {
+        persist.push(MessageRole::Assistant, &format!("Answer number {}", i));
+        persist.push(MessageRole::User, &format!("Follow-up question {}", i));
+    }
+    persist
+}
+
+#[tokio::test]
+async fn test_model_fetches_message_outside_window() {
+    let persist = long_thread();
+    let strategy = IndexedHistoryStrategy::new(4).with_line_max_chars(40);
+
+    let window = strategy
+        .get_context_window(THREAD_ID, &(persist.clone() as Arc<dyn PersistenceClient>), None, "gpt-4o")
+        .await
+        .unwrap();
+
+    // Only the index line for #2 is visible, not the secret itself
+    assert_eq!(window.messages.len(), 4);
+    assert!(window.system_prompt.contains("#2 user: For reference"));
+    assert!(!window.system_prompt.contains(SECRET));
+    assert_eq!(window.tools.len(), 1);
+
+    let mut messages = vec![Message::system(window.system_prompt.clone())];
+    messages.extend(window.messages.clone());
+    messages.push(Message::human("What was my account number?"));
+
+    let graph = Graph::new(
+        Arc::new(FetchingLLM::default()),
+        Arc::new(MCPToolExecutor::new()),
+        GraphConfig::default(),
+    );
+    let input = GraphInput::new(THREAD_ID, messages, LLMConfig::new("mock-model")).with_run_tools(window.tools);
+
+    let mut events = graph.spawn_run(input, None);
+    let mut fetched = None;
+    let mut answer = String::new();
+    while let Some(event) = events.recv().await {
+        match event {
+            StreamEvent::ToolResult { result, is_error, .. } => fetched = Some((result, is_error)),
+            StreamEvent::Message { content, .. } => answer.push_str(&content),
+            StreamEvent::EndStream { .. } => break,
+            StreamEvent::Error { message, .. } => panic!("graph error: {}", message),
+            _ => {}
+        }
+    }
+
+    let (result, is_error) = fetched.expect("fetch_messages should be called");
+    assert!(!is_error, "{}", result);
+    assert!(result.starts_with("#2 user:"), "{}", result);
+    assert!(answer.contains(SECRET), "{}", answer);
+}
+
+#[tokio::test]
+async fn test_index_is_cached_and_extended() {
+    let persist = long_thread();
+    let strategy = IndexedHistoryStrategy::new(4);
+    let client = persist.clone() as Arc<dyn PersistenceClient>;
+
+    strategy.get_context_window(THREAD_ID, &client, None, "gpt-4o").await.unwrap();
+    assert_eq!(*persist.index_saves.lock().unwrap(), 1);
+
+    // Unchanged thread: nothing to save
+    strategy.get_context_window(THREAD_ID, &client, None, "gpt-4o").await.unwrap();
+    assert_eq!(*persist.index_saves.lock().unwrap(), 1);
+
+    // Mark a cached line; an incremental refresh keeps it
+    persist.index.lock().unwrap().as_mut().unwrap().entries[0].line = "#1 cached".to_string();
+    persist.push(MessageRole::Assistant, "A brand new answer");
+
+    let window = strategy.get_context_window(THREAD_ID, &client, None, "gpt-4o").await.unwrap();
+    let index = persist.index.lock().unwrap().clone().unwrap();
+
+    assert_eq!(*persist.index_saves.lock().unwrap(), 2);
+    assert_eq!(index.indexed_count, 19);
+    assert_eq!(index.entries.last().unwrap().line, "#19 assistant: A brand new answer");
+    assert!(window.system_prompt.contains("#1 cached"));
+}
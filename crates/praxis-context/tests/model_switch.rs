@@ -0,0 +1,208 @@
+//! History written by one model is normalized for the model the next turn
+//! goes to, and the window lists what was rewritten
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::Stream;
+use praxis_context::{ContextNormalization, ContextStrategy, ContextWindow, DefaultContextStrategy};
+use praxis_llm::{ChatClient, ChatRequest, ChatResponse, Content, Message};
+use praxis_persist::{
+    DBMessage, HistoryIndex, MessageRole, MessageType, PersistenceClient, SummaryTrigger, Thread, ThreadMetadata,
+    ThreadSummary,
+};
+use serde_json::json;
+use std::pin::Pin;
+use std::sync::Arc;
+
+const THREAD_ID: &str = "thread-1";
+const SUMMARY: &str = "The user is planning a trip to Lisbon.";
+
+/// A summarized thread whose last turns came from a reasoning model that
+/// called a tool, followed by a turn from a chat model
+struct FixtureThread {
+    messages: Vec<DBMessage>,
+    summarized_at: DateTime<Utc>,
+}
+
+impl FixtureThread {
+    fn new() -> Self {
+        let summarized_at = Utc::now() - Duration::hours(1);
+        let at = |minutes: i64| summarized_at + Duration::minutes(minutes);
+        let row = |minutes: i64, role: MessageRole, message_type: MessageType, content: &str| DBMessage {
+            id: format!("m-{}", minutes),
+            thread_id: THREAD_ID.to_string(),
+            role,
+            message_type,
+            content: content.to_string(),
+            created_at: at(minutes),
+            ..Default::default()
+        };
+
+        let mut reasoning = row(2, MessageRole::Assistant, MessageType::Reasoning, "Need the forecast first.");
+        reasoning.reasoning_id = Some("rs_1".to_string());
+        reasoning.summary = Some("Checking the forecast".to_string());
+        let mut call = row(3, MessageRole::Assistant, MessageType::ToolCall, "");
+        call.tool_call_id = Some("call_1".to_string());
+        call.tool_name = Some("weather".to_string());
+        call.arguments = Some(json!({ "city": "Lisbon" }));
+        let mut result = row(4, MessageRole::Assistant, MessageType::ToolResult, "22C, sunny");
+        result.tool_call_id = Some("call_1".to_string());
+
+        Self {
+            messages: vec![
+                row(1, MessageRole::User, MessageType::Message, "Will it rain in Lisbon?"),
+                reasoning,
+                call,
+                result,
+                row(5, MessageRole::Assistant, MessageType::Message, "No, 22C and sunny."),
+                row(6, MessageRole::User, MessageType::Message, "Thanks!"),
+                row(7, MessageRole::Assistant, MessageType::Message, "Enjoy the trip."),
+            ],
+            summarized_at,
+        }
+    }
+}
+
+#[async_trait]
+impl PersistenceClient for FixtureThread {
+    async fn save_message(&self, _message: DBMessage) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn get_messages(&self, _thread_id: &str) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self.messages.clone())
+    }
+
+    async fn get_messages_after(
+        &self,
+        _thread_id: &str,
+        after: DateTime<Utc>,
+    ) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self.messages.iter().filter(|m| m.created_at > after).cloned().collect())
+    }
+
+    async fn create_thread(&self, _user_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<Thread> {
+        unimplemented!("the fixture thread already exists")
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> praxis_persist::Result<Option<Thread>> {
+        Ok(Some(Thread {
+            id: thread_id.to_string(),
+            user_id: "user-1".to_string(),
+            created_at: self.summarized_at - Duration::days(1),
+            updated_at: self.summarized_at,
+            metadata: ThreadMetadata::default(),
+            last_summary_update: self.summarized_at,
+            summary: Some(ThreadSummary {
+                text: SUMMARY.to_string(),
+                generated_at: self.summarized_at,
+                total_tokens_before_summary: 4000,
+                messages_count: 12,
+                trigger: SummaryTrigger::Overflow,
+            }),
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        }))
+    }
+
+    async fn save_thread_summary(
+        &self,
+        _thread_id: &str,
+        _summary: String,
+        _generated_at: DateTime<Utc>,
+        _trigger: SummaryTrigger,
+    ) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn update_thread_metadata(&self, _thread_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn save_thread_index(&self, _thread_id: &str, _index: HistoryIndex) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn delete_thread(&self, _thread_id: &str, _user_id: &str) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn list_threads(
+        &self,
+        _user_id: &str,
+        _limit: Option<i64>,
+        _skip: Option<i64>,
+    ) -> praxis_persist::Result<Vec<Thread>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Never called: the fixture stays far below the summarization threshold
+struct UnusedLLM;
+
+#[async_trait]
+impl ChatClient for UnusedLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        anyhow::bail!("summarization should not run")
+    }
+
+    async fn chat_stream(
+        &self,
+        _request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
+        anyhow::bail!("summarization should not run")
+    }
+}
+
+async fn window_for(model: &str) -> ContextWindow {
+    let strategy = DefaultContextStrategy::new(100_000, Arc::new(UnusedLLM));
+    let persist: Arc<dyn PersistenceClient> = Arc::new(FixtureThread::new());
+    let window = strategy
+        .get_context_window(THREAD_ID, &persist, None, model)
+        .await
+        .unwrap();
+    assert!(window.system_prompt.contains(SUMMARY), "summary lost for {}", model);
+    window
+}
+
+fn has_tool_structures(messages: &[Message]) -> bool {
+    messages.iter().any(|m| matches!(m, Message::Tool { .. } | Message::AI { tool_calls: Some(_), .. }))
+}
+
+#[tokio::test]
+async fn test_reasoning_history_to_chat_model() {
+    let window = window_for("gpt-4o").await;
+
+    assert_eq!(window.normalizations, vec![ContextNormalization::ReasoningDropped { count: 1 }]);
+    // Tool calls are kept as structures: the chat model calls functions too
+    assert_eq!(window.messages.len(), 6);
+    assert!(has_tool_structures(&window.messages));
+}
+
+#[tokio::test]
+async fn test_chat_history_to_reasoning_model() {
+    let window = window_for("gpt-5").await;
+
+    assert!(window.normalizations.is_empty(), "{:?}", window.normalizations);
+    assert_eq!(window.messages.len(), 6);
+    assert!(has_tool_structures(&window.messages));
+}
+
+#[tokio::test]
+async fn test_tool_calls_flattened_for_model_without_functions() {
+    let window = window_for("o1-mini").await;
+
+    assert_eq!(window.normalizations, vec![ContextNormalization::ToolCallsFlattened { count: 1 }]);
+    assert!(!has_tool_structures(&window.messages));
+    let flattened = window.messages.iter().find_map(|m| match m {
+        Message::AI { content: Some(Content::Text(text)), .. } if text.starts_with("[called") => Some(text.clone()),
+        _ => None,
+    });
+    assert_eq!(
+        flattened.as_deref(),
+        Some(r#"[called weather({"city":"Lisbon"}), which returned: 22C, sunny]"#)
+    );
+    assert_eq!(window.messages.len(), 5);
+}
@@ -0,0 +1,259 @@
+//! The context window of a turn never duplicates or drops its user message,
+//! even when saves become visible only after a delay
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::Stream;
+use praxis_context::{ContextStrategy, DefaultContextStrategy};
+use praxis_llm::{ChatClient, ChatRequest, ChatResponse, Content, Message};
+use praxis_persist::{
+    DBMessage, HistoryIndex, MessageRole, MessageType, PersistenceClient, SummaryTrigger, Thread, ThreadMetadata,
+};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// In-memory persistence whose saves land after an artificial delay,
+/// like a write-behind worker; `flush` waits for the queue to drain
+struct DelayedWritePersistence {
+    messages: Arc<Mutex<HashMap<String, Vec<DBMessage>>>>,
+    /// Writes still in flight, per thread
+    pending: Mutex<HashMap<String, watch::Sender<usize>>>,
+    writes: AtomicU64,
+}
+
+impl DelayedWritePersistence {
+    fn new() -> Self {
+        Self {
+            messages: Arc::default(),
+            pending: Mutex::default(),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    fn pending(&self, thread_id: &str) -> watch::Sender<usize> {
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(thread_id.to_string())
+            .or_insert_with(|| watch::channel(0).0)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl PersistenceClient for DelayedWritePersistence {
+    async fn save_message(&self, message: DBMessage) -> praxis_persist::Result<()> {
+        // 0-4 ms, varied per write so visibility races both ways
+        let delay = self.writes.fetch_add(1, Ordering::SeqCst) * 7 % 5;
+        let messages = Arc::clone(&self.messages);
+        let pending = self.pending(&message.thread_id);
+        pending.send_modify(|n| *n += 1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            messages.lock().unwrap().entry(message.thread_id.clone()).or_default().push(message);
+            pending.send_modify(|n| *n -= 1);
+        });
+        Ok(())
+    }
+
+    async fn flush(&self, thread_id: &str) -> praxis_persist::Result<()> {
+        let mut pending = self.pending(thread_id).subscribe();
+        let _ = pending.wait_for(|n| *n == 0).await;
+        Ok(())
+    }
+
+    async fn get_messages(&self, thread_id: &str) -> praxis_persist::Result<Vec<DBMessage>> {
+        let mut messages = self.messages.lock().unwrap().get(thread_id).cloned().unwrap_or_default();
+        messages.sort_by_key(|m| m.created_at);
+        Ok(messages)
+    }
+
+    async fn get_messages_after(
+        &self,
+        thread_id: &str,
+        after: DateTime<Utc>,
+    ) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self
+            .get_messages(thread_id)
+            .await?
+            .into_iter()
+            .filter(|m| m.created_at > after)
+            .collect())
+    }
+
+    async fn create_thread(&self, _user_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<Thread> {
+        unimplemented!("threads are implicit in these tests")
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> praxis_persist::Result<Option<Thread>> {
+        let long_ago = Utc::now() - Duration::days(1);
+        Ok(Some(Thread {
+            id: thread_id.to_string(),
+            user_id: "user-1".to_string(),
+            created_at: long_ago,
+            updated_at: long_ago,
+            metadata: ThreadMetadata::default(),
+            last_summary_update: long_ago,
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        }))
+    }
+
+    async fn save_thread_summary(
+        &self,
+        _thread_id: &str,
+        _summary: String,
+        _generated_at: DateTime<Utc>,
+        _trigger: SummaryTrigger,
+    ) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn update_thread_metadata(&self, _thread_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn save_thread_index(&self, _thread_id: &str, _index: HistoryIndex) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn delete_thread(&self, _thread_id: &str, _user_id: &str) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn list_threads(
+        &self,
+        _user_id: &str,
+        _limit: Option<i64>,
+        _skip: Option<i64>,
+    ) -> praxis_persist::Result<Vec<Thread>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Never called: the windows stay far below the summarization threshold
+struct UnusedLLM;
+
+#[async_trait]
+impl ChatClient for UnusedLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        anyhow::bail!("summarization should not run")
+    }
+
+    async fn chat_stream(
+        &self,
+        _request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
+        anyhow::bail!("summarization should not run")
+    }
+}
+
+fn message(thread_id: &str, role: MessageRole, content: String) -> DBMessage {
+    DBMessage {
+        id: format!("{}:{}", thread_id, content),
+        thread_id: thread_id.to_string(),
+        role,
+        message_type: MessageType::Message,
+        content,
+        created_at: Utc::now(),
+        ..Default::default()
+    }
+}
+
+fn texts(messages: &[Message]) -> Vec<String> {
+    messages
+        .iter()
+        .filter_map(|m| match m {
+            Message::Human { content: Content::Text(text), .. }
+            | Message::AI { content: Some(Content::Text(text)), .. } => Some(text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run `turns` turns the way the API handler does, returning each turn's
+/// outgoing messages (window plus the appended user message)
+async fn run_conversation(
+    persist: Arc<DelayedWritePersistence>,
+    strategy: Arc<DefaultContextStrategy>,
+    thread_id: String,
+    turns: usize,
+    flush: bool,
+) -> Vec<Vec<String>> {
+    let client = persist.clone() as Arc<dyn PersistenceClient>;
+    let mut sent = Vec::new();
+    for turn in 0..turns {
+        let user = message(&thread_id, MessageRole::User, format!("question {}", turn));
+        let user_id = user.id.clone();
+        let user_text = user.content.clone();
+        client.save_message(user).await.unwrap();
+        if flush {
+            client.flush(&thread_id).await.unwrap();
+        }
+
+        let window = strategy
+            .get_context_window(&thread_id, &client, Some(&user_id), "gpt-4o")
+            .await
+            .unwrap();
+        let mut outgoing = texts(&window.messages);
+        outgoing.push(user_text);
+        sent.push(outgoing);
+
+        let answer = message(&thread_id, MessageRole::Assistant, format!("answer {}", turn));
+        client.save_message(answer).await.unwrap();
+    }
+    sent
+}
+
+fn strategy() -> Arc<DefaultContextStrategy> {
+    Arc::new(DefaultContextStrategy::new(1_000_000, Arc::new(UnusedLLM)))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_window_never_duplicates_or_drops_newest_user_message() {
+    let persist = Arc::new(DelayedWritePersistence::new());
+    let turns = 12;
+
+    let conversations = futures::future::join_all((0..8).map(|t| {
+        tokio::spawn(run_conversation(persist.clone(), strategy(), format!("thread-{}", t), turns, true))
+    }))
+    .await;
+
+    for sent in conversations {
+        for (turn, outgoing) in sent.unwrap().iter().enumerate() {
+            // Every earlier exchange, then this turn's question exactly once
+            let expected: Vec<String> = (0..turn)
+                .flat_map(|i| [format!("question {}", i), format!("answer {}", i)])
+                .chain([format!("question {}", turn)])
+                .collect();
+            assert_eq!(outgoing, &expected);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_excluded_user_message_not_duplicated_without_flush() {
+    let persist = Arc::new(DelayedWritePersistence::new());
+
+    let conversations = futures::future::join_all((0..8).map(|t| {
+        tokio::spawn(run_conversation(persist.clone(), strategy(), format!("thread-{}", t), 12, false))
+    }))
+    .await;
+
+    // Without a flush earlier writes may still be in flight, but the new
+    // question is never sent twice, whether or not its save was visible
+    for sent in conversations {
+        for (turn, outgoing) in sent.unwrap().iter().enumerate() {
+            let question = format!("question {}", turn);
+            assert_eq!(outgoing.iter().filter(|text| **text == question).count(), 1);
+            assert_eq!(outgoing.last(), Some(&question));
+        }
+    }
+}
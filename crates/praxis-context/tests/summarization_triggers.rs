@@ -0,0 +1,217 @@
+//! Each summarization trigger fires when due and records itself on the
+//! summary, with time taken from a fixed clock
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use praxis_context::{CadenceTrigger, ContextStrategy, DefaultContextStrategy};
+use praxis_core::testkit::FixedClock;
+use praxis_core::Clock;
+use praxis_llm::testkit::MockLLMClient;
+use praxis_llm::{Message, TokenCounter, Tool};
+use praxis_persist::{
+    DBMessage, HygienePolicy, HygieneRunner, InMemoryPersistenceClient, MessageRole, MessageType, PersistenceClient,
+    SummaryTrigger, ThreadMetadata, ThreadSummary,
+};
+use std::sync::{Arc, Mutex};
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap()
+}
+
+struct Fixture {
+    clock: Arc<FixedClock>,
+    llm: Arc<MockLLMClient>,
+    persist: Arc<dyn PersistenceClient>,
+    thread_id: String,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let clock = Arc::new(FixedClock::at(start()));
+        let persist: Arc<dyn PersistenceClient> =
+            Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+        let thread = persist.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+        Self {
+            clock,
+            llm: Arc::new(MockLLMClient::new().then_message("They talked about Lisbon.")),
+            persist,
+            thread_id: thread.id,
+        }
+    }
+
+    fn strategy(&self, max_tokens: usize) -> DefaultContextStrategy {
+        DefaultContextStrategy::new(max_tokens, self.llm.clone()).with_clock(self.clock.clone())
+    }
+
+    /// Save a user question and its answer, each `tokens` long, one minute apart
+    async fn exchange(&self, tokens: u32) {
+        self.exchange_counted(Some(tokens)).await;
+    }
+
+    /// Save a user question and its answer with `token_count` stored
+    async fn exchange_counted(&self, token_count: Option<u32>) {
+        for role in [MessageRole::User, MessageRole::Assistant] {
+            self.clock.advance(Duration::minutes(1));
+            self.persist
+                .save_message(DBMessage {
+                    thread_id: self.thread_id.clone(),
+                    user_id: "user-1".to_string(),
+                    role,
+                    message_type: MessageType::Message,
+                    content: "Lisbon".to_string(),
+                    created_at: self.clock.now(),
+                    token_count,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    /// Start a turn and let the summary it may spawn finish
+    ///
+    /// The mock LLM and in-memory store never wait, so on the test's
+    /// single-threaded runtime a few yields run the background task through.
+    async fn turn(&self, strategy: &DefaultContextStrategy) -> Option<ThreadSummary> {
+        strategy.get_context_window(&self.thread_id, &self.persist, None, "gpt-4o").await.unwrap();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        self.persist.get_thread(&self.thread_id).await.unwrap().unwrap().summary
+    }
+}
+
+#[tokio::test]
+async fn test_overflow_summarizes_past_max_tokens() {
+    let fixture = Fixture::new().await;
+    let strategy = fixture.strategy(100);
+
+    fixture.exchange(40).await;
+    assert!(fixture.turn(&strategy).await.is_none());
+
+    fixture.exchange(40).await;
+    let summary = fixture.turn(&strategy).await.expect("a summary");
+    assert_eq!(summary.trigger, SummaryTrigger::Overflow);
+    assert_eq!(summary.text, "They talked about Lisbon.");
+}
+
+/// Counts 30 tokens a message and keeps the models it counted for
+#[derive(Default)]
+struct FlatCounter {
+    models: Mutex<Vec<String>>,
+}
+
+impl TokenCounter for FlatCounter {
+    fn count_messages(&self, model: &str, messages: &[Message], _tools: &[Tool]) -> usize {
+        self.models.lock().unwrap().push(model.to_string());
+        30 * messages.len()
+    }
+}
+
+#[tokio::test]
+async fn test_overflow_counts_uncounted_messages_with_the_token_counter() {
+    let fixture = Fixture::new().await;
+    let counter = Arc::new(FlatCounter::default());
+    let strategy = fixture.strategy(100).with_token_counter(counter.clone());
+
+    fixture.exchange_counted(None).await;
+    assert!(fixture.turn(&strategy).await.is_none());
+
+    // 60 stored + 4 × 30 counted
+    fixture.exchange(30).await;
+    fixture.exchange_counted(None).await;
+    let summary = fixture.turn(&strategy).await.expect("a summary");
+    assert_eq!(summary.trigger, SummaryTrigger::Overflow);
+    assert_eq!(*counter.models.lock().unwrap(), ["gpt-4o", "gpt-4o"]);
+}
+
+#[tokio::test]
+async fn test_cadence_summarizes_every_n_assistant_turns() {
+    let fixture = Fixture::new().await;
+    let strategy = fixture
+        .strategy(100_000)
+        .with_trigger(Arc::new(CadenceTrigger::new().with_every_turns(2)));
+
+    fixture.exchange(10).await;
+    assert!(fixture.turn(&strategy).await.is_none());
+
+    fixture.exchange(10).await;
+    let summary = fixture.turn(&strategy).await.expect("a summary");
+    assert_eq!(summary.trigger, SummaryTrigger::Cadence);
+    // The summary covers up to the last answer, not the time it was written
+    let thread = fixture.persist.get_thread(&fixture.thread_id).await.unwrap().unwrap();
+    assert_eq!(thread.last_summary_update, start() + Duration::minutes(4));
+}
+
+#[tokio::test]
+async fn test_cadence_summarizes_after_an_idle_gap() {
+    let fixture = Fixture::new().await;
+    let strategy = fixture
+        .strategy(100_000)
+        .with_trigger(Arc::new(CadenceTrigger::new().with_after_idle(Duration::minutes(30))));
+
+    fixture.exchange(10).await;
+    fixture.clock.advance(Duration::minutes(29));
+    assert!(fixture.turn(&strategy).await.is_none());
+
+    fixture.clock.advance(Duration::minutes(1));
+    let summary = fixture.turn(&strategy).await.expect("a summary");
+    assert_eq!(summary.trigger, SummaryTrigger::Cadence);
+}
+
+#[tokio::test]
+async fn test_overflow_stays_the_backstop_under_a_cadence() {
+    let fixture = Fixture::new().await;
+    let strategy = fixture
+        .strategy(100)
+        .with_trigger(Arc::new(CadenceTrigger::new().with_every_turns(10)));
+
+    fixture.exchange(60).await;
+    let summary = fixture.turn(&strategy).await.expect("a summary");
+    assert_eq!(summary.trigger, SummaryTrigger::Overflow);
+}
+
+#[tokio::test]
+async fn test_summarize_now_is_manual_and_moves_the_window() {
+    let fixture = Fixture::new().await;
+    let strategy = fixture.strategy(100_000);
+
+    fixture.exchange(10).await;
+    let summary = strategy
+        .summarize_now(&fixture.thread_id, &fixture.persist)
+        .await
+        .unwrap()
+        .expect("a summary");
+    assert_eq!(summary.trigger, SummaryTrigger::Manual);
+
+    let window = strategy.get_context_window(&fixture.thread_id, &fixture.persist, None, "gpt-4o").await.unwrap();
+    assert!(window.messages.is_empty());
+    assert!(window.system_prompt.contains("They talked about Lisbon."));
+    // Nothing new since: no call to the LLM, no summary
+    assert!(strategy.summarize_now(&fixture.thread_id, &fixture.persist).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_hygiene_writes_a_final_summary_before_archiving() {
+    let clock = Arc::new(FixedClock::at(start()));
+    let store = Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+    let persist: Arc<dyn PersistenceClient> = store.clone();
+    let thread = persist.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+    let fixture = Fixture {
+        clock: clock.clone(),
+        llm: Arc::new(MockLLMClient::new().then_message("They talked about Lisbon.")),
+        persist,
+        thread_id: thread.id,
+    };
+    fixture.exchange(10).await;
+    clock.advance(Duration::days(91));
+
+    let runner = HygieneRunner::new(store, "replica-a")
+        .with_clock(clock.clone())
+        .with_summarizer(Arc::new(fixture.strategy(100_000)));
+    let report = runner.run_hygiene(&HygienePolicy::default()).await.unwrap().unwrap();
+    assert_eq!((report.summarized, report.archived), (1, 1));
+
+    let thread = fixture.persist.get_thread(&fixture.thread_id).await.unwrap().unwrap();
+    assert_eq!(thread.summary.unwrap().text, "They talked about Lisbon.");
+    assert_eq!(thread.archived_at, Some(clock.now()));
+}
@@ -0,0 +1,74 @@
+//! `TiktokenTokenizer` counts prompts as OpenAI bills them: golden counts
+//! from the OpenAI cookbook's "How to count tokens with tiktoken"
+
+use praxis_context::TiktokenTokenizer;
+use praxis_llm::{Message, TokenCounter, Tool};
+use serde_json::json;
+
+fn named_system(name: &str, content: &str) -> Message {
+    Message::System { content: content.into(), name: Some(name.to_string()), cache_hint: None }
+}
+
+/// The cookbook's corporate-jargon few-shot prompt
+fn example_messages() -> Vec<Message> {
+    vec![
+        Message::system(
+            "You are a helpful, pattern-following assistant that translates corporate jargon into plain English.",
+        ),
+        named_system("example_user", "New synergies will help drive top-line growth."),
+        named_system("example_assistant", "Things working well together will increase revenue."),
+        named_system(
+            "example_user",
+            "Let's circle back when we have more time to touch base on opportunities for increased leverage.",
+        ),
+        named_system("example_assistant", "Let's talk later when we're less busy about how to do better."),
+        Message::human("This late pivot means we don't have time to boil the ocean for the client deliverable."),
+    ]
+}
+
+/// The cookbook's weather prompt and tool
+fn weather_request() -> (Vec<Message>, Vec<Tool>) {
+    let messages = vec![
+        Message::system("You are a helpful assistant that can answer to questions about the weather."),
+        Message::human("What's the weather like in San Francisco?"),
+    ];
+    let tool = Tool::new(
+        "get_current_weather",
+        "Get the current weather in a given location",
+        json!({
+            "type": "object",
+            "properties": {
+                "location": { "type": "string", "description": "The city and state, e.g. San Francisco, CA" },
+                "unit": { "type": "string", "description": "The unit of temperature to return", "enum": ["celsius", "fahrenheit"] }
+            },
+            "required": ["location"]
+        }),
+    );
+    (messages, vec![tool])
+}
+
+#[test]
+fn test_messages_match_the_cookbook_counts() {
+    let counter = TiktokenTokenizer::new();
+    let messages = example_messages();
+
+    assert_eq!(counter.count_messages("gpt-4", &messages, &[]), 129);
+    assert_eq!(counter.count_messages("gpt-3.5-turbo", &messages, &[]), 129);
+    assert_eq!(counter.count_messages("gpt-4o", &messages, &[]), 124);
+    assert_eq!(counter.count_messages("gpt-4o-mini", &messages, &[]), 124);
+}
+
+#[test]
+fn test_tools_match_the_cookbook_counts() {
+    let counter = TiktokenTokenizer::new();
+    let (messages, tools) = weather_request();
+
+    assert_eq!(counter.count_messages("gpt-4", &messages, &tools), 105);
+    assert_eq!(counter.count_messages("gpt-4o", &messages, &tools), 101);
+    assert_eq!(counter.count_messages("gpt-4o-mini", &messages, &tools), 101);
+}
+
+#[test]
+fn test_empty_prompt_is_the_reply_priming() {
+    assert_eq!(TiktokenTokenizer.count_messages("gpt-4o", &[], &[]), 3);
+}
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+/// Source of wall-clock timestamps
+pub trait Clock: Send + Sync + Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
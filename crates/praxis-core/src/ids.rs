@@ -0,0 +1,16 @@
+use std::fmt::Debug;
+
+/// Source of unique ids
+pub trait IdGen: Send + Sync + Debug {
+    fn next_id(&self) -> String;
+}
+
+/// Random v4 UUIDs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidIds;
+
+impl IdGen for UuidIds {
+    fn next_id(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
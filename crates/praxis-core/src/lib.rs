@@ -0,0 +1,12 @@
+//! Clock and id generator shared by the Praxis crates
+//!
+//! Every place that stamps a timestamp or mints an id (run ids, message
+//! ids, trace ids) goes through a `Clock` or an `IdGen`, so tests can swap
+//! in the fixed implementations from `testkit`.
+
+mod clock;
+mod ids;
+pub mod testkit;
+
+pub use clock::{Clock, SystemClock};
+pub use ids::{IdGen, UuidIds};
@@ -0,0 +1,82 @@
+//! Deterministic `Clock` and `IdGen` for tests
+
+use crate::{Clock, IdGen};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn at(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Sequential ids: `id-0001`, `id-0002`, ...
+///
+/// Zero-padded so ids sort in the order they were minted.
+#[derive(Debug)]
+pub struct SeqIds {
+    prefix: String,
+    next: AtomicU64,
+}
+
+impl SeqIds {
+    pub fn new() -> Self {
+        Self::with_prefix("id")
+    }
+
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl Default for SeqIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdGen for SeqIds {
+    fn next_id(&self) -> String {
+        let n = self.next.fetch_add(1, Ordering::SeqCst);
+        format!("{}-{:04}", self.prefix, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock_and_seq_ids() {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let clock = FixedClock::at(start);
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::seconds(2));
+        assert_eq!(clock.now(), start + Duration::seconds(2));
+
+        let ids = SeqIds::new();
+        assert_eq!(ids.next_id(), "id-0001");
+        assert_eq!(ids.next_id(), "id-0002");
+        assert_eq!(SeqIds::with_prefix("run").next_id(), "run-0001");
+    }
+}
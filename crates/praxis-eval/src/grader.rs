@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use praxis_llm::{ChatClient, ChatOptions, ChatRequest, Message};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How the final answer of a case is judged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Grader {
+    /// Answer equals `expected`, ignoring surrounding whitespace
+    Exact { expected: String },
+    /// Answer matches `pattern` somewhere
+    Regex { pattern: String },
+    /// Answer is JSON (optionally in a code fence) containing `expected`:
+    /// objects may have extra keys, arrays and scalars must match exactly
+    JsonSubset { expected: Value },
+    /// A judge model reads the answer and the rubric and decides
+    LlmJudge { rubric: String },
+}
+
+/// Outcome of grading one answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grade {
+    pub passed: bool,
+    /// Why the answer failed, or the judge's reasoning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Grade {
+    fn pass() -> Self {
+        Self { passed: true, reason: None }
+    }
+
+    fn fail(reason: impl Into<String>) -> Self {
+        Self { passed: false, reason: Some(reason.into()) }
+    }
+}
+
+/// Model that grades `llm_judge` cases
+#[derive(Clone)]
+pub struct Judge {
+    pub client: Arc<dyn ChatClient>,
+    pub model: String,
+}
+
+impl Grader {
+    /// Reject graders that can't run (e.g. an invalid regex) before any case starts
+    pub(crate) fn validate(&self) -> Result<()> {
+        if let Self::Regex { pattern } = self {
+            Regex::new(pattern)?;
+        }
+        Ok(())
+    }
+
+    pub async fn grade(&self, answer: &str, judge: Option<&Judge>) -> Result<Grade> {
+        let grade = match self {
+            Self::Exact { expected } if answer.trim() == expected.trim() => Grade::pass(),
+            Self::Exact { expected } => Grade::fail(format!("expected {:?}, got {:?}", expected.trim(), answer.trim())),
+            Self::Regex { pattern } if Regex::new(pattern)?.is_match(answer) => Grade::pass(),
+            Self::Regex { pattern } => Grade::fail(format!("no match for /{}/", pattern)),
+            Self::JsonSubset { expected } => match serde_json::from_str::<Value>(strip_code_fence(answer)) {
+                Ok(actual) if json_contains(&actual, expected) => Grade::pass(),
+                Ok(actual) => Grade::fail(format!("{} does not contain {}", actual, expected)),
+                Err(e) => Grade::fail(format!("answer is not JSON: {}", e)),
+            },
+            Self::LlmJudge { rubric } => {
+                let judge = judge.ok_or_else(|| anyhow::anyhow!("llm_judge grader needs a judge model"))?;
+                judge_answer(judge, rubric, answer).await?
+            }
+        };
+        Ok(grade)
+    }
+}
+
+/// Whether `actual` contains everything in `expected`
+pub fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| json_contains(a, value))),
+        _ => actual == expected,
+    }
+}
+
+fn strip_code_fence(answer: &str) -> &str {
+    let trimmed = answer.trim();
+    trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(str::trim)
+        .unwrap_or(trimmed)
+}
+
+const JUDGE_PROMPT: &str = "You grade answers of an AI assistant against a rubric. \
+    Reply with PASS or FAIL on the first line, then one sentence explaining why.";
+
+async fn judge_answer(judge: &Judge, rubric: &str, answer: &str) -> Result<Grade> {
+    let request = ChatRequest::new(
+        judge.model.clone(),
+        vec![
+            Message::system(JUDGE_PROMPT),
+            Message::human(format!("Rubric:\n{}\n\nAnswer:\n{}", rubric, answer)),
+        ],
+    )
+    .with_options(ChatOptions::new().temperature(0.0));
+    let reply = judge.client.chat(request).await?.content.unwrap_or_default();
+
+    let (verdict, reason) = reply.trim().split_once('\n').unwrap_or((reply.trim(), ""));
+    let reason = Some(reason.trim().to_string()).filter(|r| !r.is_empty());
+    match verdict.trim().to_ascii_uppercase().as_str() {
+        v if v.starts_with("PASS") => Ok(Grade { passed: true, reason }),
+        v if v.starts_with("FAIL") => Ok(Grade { passed: false, reason }),
+        _ => Ok(Grade::fail(format!("judge gave no verdict: {}", reply.trim()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_deterministic_graders() {
+        let exact = Grader::Exact { expected: "Paris".to_string() };
+        assert!(exact.grade(" Paris\n", None).await.unwrap().passed);
+        assert!(!exact.grade("paris", None).await.unwrap().passed);
+
+        let regex = Grader::Regex { pattern: r"^\d{4}-\d{2}-\d{2}$".to_string() };
+        assert!(regex.grade("2025-01-08", None).await.unwrap().passed);
+        assert!(!regex.grade("Jan 8", None).await.unwrap().passed);
+
+        let subset = Grader::JsonSubset { expected: json!({ "city": "Lisbon", "tags": ["a"] }) };
+        let fenced = "```json\n{\"city\": \"Lisbon\", \"tags\": [\"a\"], \"temp\": 22}\n```";
+        assert!(subset.grade(fenced, None).await.unwrap().passed);
+        assert!(!subset.grade(r#"{"city": "Lisbon", "tags": ["a", "b"]}"#, None).await.unwrap().passed);
+        assert_eq!(
+            subset.grade("Lisbon", None).await.unwrap().reason.map(|r| r.starts_with("answer is not JSON")),
+            Some(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_judge_required_for_llm_grader() {
+        let grader = Grader::LlmJudge { rubric: "Polite".to_string() };
+        assert!(grader.grade("Hi", None).await.is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! Regression evals for Praxis agents
+//!
+//! An [`EvalSuite`] lists conversations, the tool calls each should make
+//! and a [`Grader`] for the final answer. [`EvalRunner`] sends every case
+//! through a configured `Graph`, with the suite's mock tools standing in
+//! for real ones, and returns an [`EvalReport`] with pass rates and
+//! latency per case.
+//!
+//! ```rust,no_run
+//! # async fn run(graph: std::sync::Arc<praxis_graph::Graph>) -> anyhow::Result<()> {
+//! use praxis_eval::{EvalRunner, EvalSuite};
+//!
+//! let suite = EvalSuite::load("suites/tool_selection.yaml")?;
+//! let report = EvalRunner::new(graph, "gpt-4o-mini").with_concurrency(8).run(&suite).await;
+//! println!("{}", report);
+//! # Ok(())
+//! # }
+//! ```
+
+mod grader;
+mod report;
+mod runner;
+mod suite;
+
+pub use grader::{json_contains, Grade, Grader, Judge};
+pub use report::{CaseReport, EvalReport, ObservedToolCall};
+pub use runner::{EvalRunner, DEFAULT_CONCURRENCY};
+pub use suite::{EvalCase, EvalSuite, ExpectedToolCall, InputMessage, InputRole, MockTool};
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Results of one suite run, cases in suite order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub suite: String,
+    pub model: String,
+    pub passed: usize,
+    pub total: usize,
+    /// `passed / total` (0 for an empty suite)
+    pub pass_rate: f64,
+    /// Sum of case latencies, not wall time: cases run concurrently
+    pub total_latency_ms: u64,
+    pub cases: Vec<CaseReport>,
+}
+
+impl EvalReport {
+    pub(crate) fn new(suite: String, model: String, cases: Vec<CaseReport>) -> Self {
+        let passed = cases.iter().filter(|case| case.passed).count();
+        let total = cases.len();
+        Self {
+            suite,
+            model,
+            passed,
+            total,
+            pass_rate: if total == 0 { 0.0 } else { passed as f64 / total as f64 },
+            total_latency_ms: cases.iter().map(|case| case.latency_ms).sum(),
+            cases,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseReport {
+    pub id: String,
+    pub passed: bool,
+    /// From starting the run to its last event
+    pub latency_ms: u64,
+    pub tool_calls: Vec<ObservedToolCall>,
+    /// Text of the last assistant message
+    pub answer: String,
+    /// Why the case failed (empty when it passed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+    /// Reasoning of an `llm_judge` grader
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObservedToolCall {
+    pub name: String,
+    /// Parsed arguments, or the raw string when they aren't JSON
+    pub arguments: Value,
+}
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({})", self.suite, self.model)?;
+        let width = self.cases.iter().map(|case| case.id.len()).max().unwrap_or(0);
+        for case in &self.cases {
+            let status = if case.passed { "PASS" } else { "FAIL" };
+            writeln!(f, "  {}  {:<width$}  {:>6} ms", status, case.id, case.latency_ms, width = width)?;
+            for failure in &case.failures {
+                writeln!(f, "        {}", failure)?;
+            }
+        }
+        write!(
+            f,
+            "{}/{} passed ({:.1}%), {} ms total",
+            self.passed,
+            self.total,
+            self.pass_rate * 100.0,
+            self.total_latency_ms
+        )
+    }
+}
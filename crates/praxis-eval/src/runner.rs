@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::StreamExt;
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{ChatClient, Message};
+use praxis_mcp::LocalTool;
+
+use crate::grader::{json_contains, Judge};
+use crate::report::{CaseReport, EvalReport, ObservedToolCall};
+use crate::suite::{EvalCase, EvalSuite, ExpectedToolCall};
+
+/// Cases in flight at once unless `with_concurrency` says otherwise
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Runs suites through a graph and grades the outcomes
+///
+/// The graph is used as configured (LLM client, MCP servers, limits); the
+/// suite's mock tools are added to each run as run-only tools.
+pub struct EvalRunner {
+    graph: Arc<Graph>,
+    model: String,
+    concurrency: usize,
+    judge: Option<Judge>,
+}
+
+impl EvalRunner {
+    /// `model` is used for suites that don't name one
+    pub fn new(graph: Arc<Graph>, model: impl Into<String>) -> Self {
+        Self {
+            graph,
+            model: model.into(),
+            concurrency: DEFAULT_CONCURRENCY,
+            judge: None,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Model that grades `llm_judge` cases (without one, they fail)
+    pub fn with_judge(mut self, client: Arc<dyn ChatClient>, model: impl Into<String>) -> Self {
+        self.judge = Some(Judge { client, model: model.into() });
+        self
+    }
+
+    /// Run every case, at most `concurrency` at a time
+    ///
+    /// The report lists cases in suite order whatever order they finish in.
+    pub async fn run(&self, suite: &EvalSuite) -> EvalReport {
+        let model = suite.model.clone().unwrap_or_else(|| self.model.clone());
+        let tools: Vec<Arc<dyn LocalTool>> = suite.tools.iter().cloned().map(|tool| tool.into_local_tool()).collect();
+
+        let cases = futures::stream::iter(&suite.cases)
+            .map(|case| self.run_case(&suite.name, &model, &tools, case))
+            .buffered(self.concurrency)
+            .collect()
+            .await;
+        EvalReport::new(suite.name.clone(), model, cases)
+    }
+
+    async fn run_case(&self, suite: &str, model: &str, tools: &[Arc<dyn LocalTool>], case: &EvalCase) -> CaseReport {
+        let input = GraphInput::new(
+            format!("eval-{}-{}", suite, case.id),
+            case.input.iter().map(Message::from).collect(),
+            LLMConfig::new(model),
+        )
+        .with_run_tools(tools.iter().cloned());
+
+        let started = Instant::now();
+        let outcome = collect(self.graph.spawn_run(input, None)).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let mut failures = Vec::new();
+        if let Some(error) = outcome.error {
+            failures.push(format!("run failed: {}", error));
+        }
+        if let Some(expected) = &case.expected_tool_calls {
+            failures.extend(check_tool_calls(expected, &outcome.tool_calls));
+        }
+        let mut judge_reason = None;
+        match case.grader.grade(&outcome.answer, self.judge.as_ref()).await {
+            Ok(grade) if grade.passed => judge_reason = grade.reason,
+            Ok(grade) => failures.push(grade.reason.unwrap_or_else(|| "answer rejected".to_string())),
+            Err(e) => failures.push(format!("grading failed: {}", e)),
+        }
+
+        CaseReport {
+            id: case.id.clone(),
+            passed: failures.is_empty(),
+            latency_ms,
+            tool_calls: outcome.tool_calls,
+            answer: outcome.answer,
+            failures,
+            judge_reason,
+        }
+    }
+}
+
+struct RunOutcome {
+    tool_calls: Vec<ObservedToolCall>,
+    answer: String,
+    error: Option<String>,
+}
+
+/// Assemble tool calls and the final answer from a run's events
+async fn collect(mut events: tokio::sync::mpsc::Receiver<StreamEvent>) -> RunOutcome {
+    // Tool call deltas, keyed by (iteration, index) so they stay in call order
+    let mut calls: BTreeMap<(u32, u32), (String, String)> = BTreeMap::new();
+    let mut answer = String::new();
+    let mut answer_iteration = None;
+    let mut error = None;
+
+    while let Some(event) = events.recv().await {
+        let iteration = event.provenance().map_or(0, |p| p.iteration);
+        match event {
+            StreamEvent::ToolCall { index, name, arguments, .. } => {
+                let (call_name, call_arguments) = calls.entry((iteration, index)).or_default();
+                call_name.push_str(name.as_deref().unwrap_or_default());
+                call_arguments.push_str(arguments.as_deref().unwrap_or_default());
+            }
+            StreamEvent::Message { content, .. } => {
+                // Only the last assistant turn counts as the answer
+                if answer_iteration != Some(iteration) {
+                    answer.clear();
+                    answer_iteration = Some(iteration);
+                }
+                answer.push_str(&content);
+            }
+            StreamEvent::Error { message, .. } => error = Some(message),
+            StreamEvent::EndStream { .. } => break,
+            _ => {}
+        }
+    }
+
+    let tool_calls = calls
+        .into_values()
+        .map(|(name, arguments)| ObservedToolCall {
+            name,
+            arguments: serde_json::from_str(&arguments).unwrap_or(serde_json::Value::String(arguments)),
+        })
+        .collect();
+    RunOutcome { tool_calls, answer, error }
+}
+
+fn check_tool_calls(expected: &[ExpectedToolCall], actual: &[ObservedToolCall]) -> Vec<String> {
+    let names = |calls: Vec<&str>| format!("[{}]", calls.join(", "));
+    if expected.len() != actual.len() {
+        return vec![format!(
+            "expected tool calls {}, got {}",
+            names(expected.iter().map(|c| c.name.as_str()).collect()),
+            names(actual.iter().map(|c| c.name.as_str()).collect()),
+        )];
+    }
+    expected
+        .iter()
+        .zip(actual)
+        .enumerate()
+        .filter_map(|(position, (expected, actual))| {
+            if expected.name != actual.name {
+                return Some(format!("tool call {}: expected {}, got {}", position, expected.name, actual.name));
+            }
+            let arguments = expected.arguments.as_ref()?;
+            (!json_contains(&actual.arguments, arguments)).then(|| {
+                format!("tool call {} ({}): arguments {} do not contain {}", position, actual.name, actual.arguments, arguments)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use praxis_llm::{Content, Message};
+use praxis_mcp::{LocalTool, ToolResponse};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::grader::Grader;
+
+/// A named set of cases run against the same model and mock tools
+///
+/// Read from YAML or JSON with [`EvalSuite::load`]:
+///
+/// ```yaml
+/// name: tool-selection
+/// model: gpt-4o-mini
+/// tools:
+///   - name: get_weather
+///     description: Current weather for a city
+///     result: "22C, sunny"
+/// cases:
+///   - id: weather-lisbon
+///     input:
+///       - role: user
+///         content: What's the weather in Lisbon?
+///     expected_tool_calls:
+///       - name: get_weather
+///         arguments: { city: Lisbon }
+///     grader: { type: regex, pattern: "22" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSuite {
+    pub name: String,
+    /// Model for every case (None = the runner's default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tools offered to the model; they return canned results
+    #[serde(default)]
+    pub tools: Vec<MockTool>,
+    pub cases: Vec<EvalCase>,
+}
+
+impl EvalSuite {
+    /// Parse a suite file (YAML, which also accepts JSON)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let suite: Self = serde_yaml::from_str(text)?;
+        suite.validate()?;
+        Ok(suite)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut ids = std::collections::HashSet::new();
+        for case in &self.cases {
+            anyhow::ensure!(ids.insert(case.id.as_str()), "duplicate case id '{}'", case.id);
+            anyhow::ensure!(!case.input.is_empty(), "case '{}' has no input messages", case.id);
+            case.grader.validate().with_context(|| format!("case '{}'", case.id))?;
+        }
+        Ok(())
+    }
+}
+
+/// One conversation and what its outcome must satisfy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    pub id: String,
+    /// Messages sent to the graph, oldest first
+    pub input: Vec<InputMessage>,
+    /// Tool calls the run must make, in order (None = not checked, empty =
+    /// no tool may be called)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_tool_calls: Option<Vec<ExpectedToolCall>>,
+    /// How the final answer is graded
+    pub grader: Grader,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputRole {
+    System,
+    Developer,
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputMessage {
+    pub role: InputRole,
+    pub content: String,
+}
+
+impl From<&InputMessage> for Message {
+    fn from(message: &InputMessage) -> Self {
+        let content = Content::text(message.content.clone());
+        match message.role {
+            InputRole::System => Message::System { content, name: None, cache_hint: None },
+            InputRole::Developer => Message::Developer { content, name: None, cache_hint: None },
+            InputRole::User => Message::Human { content, name: None, cache_hint: None },
+            InputRole::Assistant => Message::AI { content: Some(content), tool_calls: None, name: None },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedToolCall {
+    pub name: String,
+    /// Arguments the call must include (a subset: extra keys are fine)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+}
+
+/// A tool that always returns the same result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockTool {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema of the arguments (default: any object)
+    #[serde(default = "any_object")]
+    pub parameters: Value,
+    pub result: String,
+}
+
+fn any_object() -> Value {
+    serde_json::json!({ "type": "object" })
+}
+
+impl MockTool {
+    pub fn into_local_tool(self) -> Arc<dyn LocalTool> {
+        Arc::new(self)
+    }
+}
+
+#[async_trait]
+impl LocalTool for MockTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn input_schema(&self) -> Value {
+        self.parameters.clone()
+    }
+
+    async fn call(&self, _arguments: Value) -> Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: self.result.clone() }])
+    }
+}
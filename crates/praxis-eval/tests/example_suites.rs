@@ -0,0 +1,180 @@
+//! The shipped suites run end to end against a stub agent whose answers
+//! are known, so the report contents can be checked exactly
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use praxis_eval::{EvalReport, EvalRunner, EvalSuite};
+use praxis_graph::{Graph, GraphConfig};
+use praxis_llm::{
+    ChatClient, ChatRequest, ChatResponse, LLMClient, Message, ReasoningClient, ResponseOutput, ResponseRequest,
+    StreamEvent,
+};
+use praxis_mcp::MCPToolExecutor;
+use serde_json::json;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// Answers from the last message of the request, so concurrent cases
+/// can share it
+struct StubAgent;
+
+fn say(text: &str) -> Vec<StreamEvent> {
+    vec![
+        StreamEvent::Message { content: text.to_string(), choice_index: None, logprobs: None },
+        StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+fn call(name: &str, arguments: serde_json::Value) -> Vec<StreamEvent> {
+    let arguments = arguments.to_string();
+    let (head, tail) = arguments.split_at(arguments.len() / 2);
+    vec![
+        StreamEvent::ToolCall {
+            index: 0,
+            id: Some(format!("call_{}", name)),
+            name: Some(name.to_string()),
+            arguments: Some(head.to_string()),
+        },
+        StreamEvent::ToolCall { index: 0, id: None, name: None, arguments: Some(tail.to_string()) },
+        StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+    ]
+}
+
+impl StubAgent {
+    fn reply(messages: &[Message]) -> (Vec<StreamEvent>, Duration) {
+        let text = |content: &praxis_llm::Content| content.as_text().unwrap_or_default().to_string();
+        let question = match messages.last() {
+            Some(Message::Tool { content, .. }) => {
+                return (say(&format!("Looked it up: {}.", text(content))), Duration::ZERO)
+            }
+            Some(Message::Human { content, .. }) => text(content),
+            _ => return (say("?"), Duration::ZERO),
+        };
+        let turn = if question.contains("weather") {
+            call("get_weather", json!({ "city": "Lisbon" }))
+        } else if question.contains("USD") {
+            call("convert_currency", json!({ "amount": 100, "from": "USD", "to": "EUR" }))
+        } else if question.contains("Who are you") {
+            say("Hello! I'm a helpful assistant.")
+        } else if question.contains("Apollo") {
+            // Finishes last, though it is the first case of its suite
+            return (say("1969-07-20"), Duration::from_millis(100));
+        } else if question.contains("Eiffel") {
+            say("```json\n{\"city\": \"Paris\", \"country\": \"France\", \"height_m\": 330}\n```")
+        } else {
+            // The suite asks for no punctuation
+            say("Tokyo.")
+        };
+        (turn, Duration::ZERO)
+    }
+}
+
+#[async_trait]
+impl ChatClient for StubAgent {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        // Judge: greetings pass
+        let rubric = request.messages.last().and_then(|m| match m {
+            Message::Human { content, .. } => content.as_text().map(str::to_string),
+            _ => None,
+        });
+        let verdict = match rubric {
+            Some(text) if text.contains("Hello!") => "PASS\nIt greets and introduces itself.",
+            _ => "FAIL\nNot a greeting.",
+        };
+        Ok(ChatResponse {
+            content: Some(verdict.to_string()),
+            tool_calls: None,
+            usage: None,
+            finish_reason: Some("stop".to_string()),
+            logprobs: None,
+            choices: Vec::new(),
+            warnings: Vec::new(),
+            raw: serde_json::Value::Null,
+            served_by: None,
+            metadata: None,
+        })
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let (events, delay) = Self::reply(&request.messages);
+        tokio::time::sleep(delay).await;
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for StubAgent {
+    async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(&self, _request: ResponseRequest) -> Result<EventStream> {
+        anyhow::bail!("not scripted")
+    }
+}
+
+impl LLMClient for StubAgent {}
+
+async fn run_suite(file: &str) -> EvalReport {
+    let suite = EvalSuite::load(format!("{}/suites/{}", env!("CARGO_MANIFEST_DIR"), file)).unwrap();
+    let graph = Graph::new(Arc::new(StubAgent), Arc::new(MCPToolExecutor::new()), GraphConfig::default());
+    EvalRunner::new(Arc::new(graph), "stub-model")
+        .with_concurrency(3)
+        .with_judge(Arc::new(StubAgent), "stub-judge")
+        .run(&suite)
+        .await
+}
+
+#[tokio::test]
+async fn test_tool_selection_suite() {
+    let report = run_suite("tool_selection.yaml").await;
+
+    assert_eq!((report.passed, report.total), (3, 3), "{}", report);
+    let weather = &report.cases[0];
+    assert_eq!(weather.tool_calls.len(), 1);
+    assert_eq!(weather.tool_calls[0].name, "get_weather");
+    assert_eq!(weather.tool_calls[0].arguments, json!({ "city": "Lisbon" }));
+    assert_eq!(weather.answer, "Looked it up: 22C, sunny.");
+    assert_eq!(
+        report.cases[2].judge_reason.as_deref(),
+        Some("It greets and introduces itself.")
+    );
+}
+
+#[tokio::test]
+async fn test_answer_format_suite_reports_in_suite_order() {
+    let report = run_suite("answer_format.yaml").await;
+
+    let ids: Vec<&str> = report.cases.iter().map(|case| case.id.as_str()).collect();
+    assert_eq!(ids, ["iso-date", "json-object", "single-word"]);
+    assert_eq!((report.passed, report.total), (2, 3), "{}", report);
+    assert!(!report.cases[2].passed);
+    assert_eq!(report.cases[2].failures, ["expected \"Tokyo\", got \"Tokyo.\""]);
+    assert!((report.pass_rate - 2.0 / 3.0).abs() < 1e-9);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["cases"][0]["id"], "iso-date");
+    assert!(report.to_string().contains("FAIL  single-word"), "{}", report);
+}
+
+#[test]
+fn test_suite_validation() {
+    let duplicate = r#"
+name: broken
+cases:
+  - { id: a, input: [{ role: user, content: hi }], grader: { type: exact, expected: hi } }
+  - { id: a, input: [{ role: user, content: hi }], grader: { type: exact, expected: hi } }
+"#;
+    assert!(EvalSuite::parse(duplicate).unwrap_err().to_string().contains("duplicate case id"));
+
+    let bad_regex = r#"
+name: broken
+cases:
+  - { id: a, input: [{ role: user, content: hi }], grader: { type: regex, pattern: "(" } }
+"#;
+    assert!(EvalSuite::parse(bad_regex).is_err());
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
-use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, StreamEvent};
-use praxis_llm::{Content, Message, OpenAIClient};
+use praxis_graph::{ClientFactory, Graph, GraphConfig, GraphInput, LLMConfig, Provider, StreamEvent, ThinkingHeartbeatConfig};
+use praxis_llm::{LLMClient, Message, OpenAIClient};
 use praxis_mcp::{MCPClient, MCPToolExecutor};
 use std::io::{self, Write};
 use std::sync::Arc;
@@ -19,16 +19,30 @@ async fn main() -> Result<()> {
     println!();
     println!("Prerequisites:");
     println!("  1. Set OPENAI_API_KEY: export OPENAI_API_KEY=your_key");
+    println!("     (or, for Claude: export PRAXIS_PROVIDER=anthropic ANTHROPIC_API_KEY=your_key)");
+    println!("     (or, for Gemini: export PRAXIS_PROVIDER=gemini GEMINI_API_KEY=your_key)");
+    println!("     (or, for Ollama: export OPENAI_BASE_URL=http://localhost:11434/v1 PRAXIS_MODEL=llama3.2)");
     println!("  2. Set MCP_SERVERS: export MCP_SERVERS=\"http://localhost:8000/mcp,http://localhost:8001/mcp\"");
     println!("  3. Start MCP servers: cd mcp_servers/weather && uv run python weather.py");
     println!();
     println!("Type 'exit' to quit");
     println!();
 
-    // Get API key from environment
-    let api_key = std::env::var("OPENAI_API_KEY").expect(
-        "OPENAI_API_KEY must be set in environment. Run: export OPENAI_API_KEY=your_key_here"
-    );
+    // Pick the provider and its API key from the environment
+    let (provider, key_var, model) = match std::env::var("PRAXIS_PROVIDER").as_deref() {
+        Ok("anthropic") => (Provider::Anthropic, "ANTHROPIC_API_KEY", "claude-sonnet-4-5"),
+        Ok("gemini") => (Provider::Gemini, "GEMINI_API_KEY", "gemini-2.5-flash"),
+        _ => (Provider::OpenAI, "OPENAI_API_KEY", "gpt-4"),
+    };
+    // An OpenAI-compatible server (e.g. Ollama) may not need a key
+    let base_url = std::env::var("OPENAI_BASE_URL").ok().filter(|url| !url.is_empty());
+    let local = base_url.is_some() && provider == Provider::OpenAI;
+    let model = std::env::var("PRAXIS_MODEL").unwrap_or_else(|_| model.to_string());
+    let api_key = match std::env::var(key_var) {
+        Ok(key) => key,
+        Err(_) if local => String::new(),
+        Err(_) => panic!("{} must be set in environment. Run: export {}=your_key_here", key_var, key_var),
+    };
 
     // Parse MCP servers from environment
     let mcp_servers = std::env::var("MCP_SERVERS")
@@ -63,10 +77,16 @@ async fn main() -> Result<()> {
     println!();
 
     // Create LLM client
-    let llm_client = Arc::new(OpenAIClient::new(api_key)?);
+    let provider_config = LLMConfig::new(model).with_provider(provider);
+    let llm_client: Arc<dyn LLMClient> = match base_url.filter(|_| local) {
+        Some(base_url) => Arc::new(OpenAIClient::with_base_url(&api_key, base_url)?),
+        None => ClientFactory::create_client(&provider_config, &api_key)?,
+    };
 
-    // Create graph config
-    let config = GraphConfig::default();
+    // Create graph config (heartbeats drive the thinking spinner)
+    let config = GraphConfig::default()
+        .with_thinking_heartbeat(ThinkingHeartbeatConfig { first_after_ms: 1000, interval_ms: 1000 });
+    let primary_agent = config.agent_name.clone();
 
     // Create graph
     let graph = Graph::new(llm_client, mcp_executor, config);
@@ -94,16 +114,13 @@ async fn main() -> Result<()> {
         }
 
         // Create user message
-        let user_message = Message::Human {
-            content: Content::text(input),
-            name: None,
-        };
+        let user_message = Message::human(input);
         
         // Add to conversation history
         conversation_history.push(user_message);
 
         // Create graph input with full conversation history
-        let llm_config = LLMConfig::new("gpt-4")
+        let llm_config = provider_config.clone()
             .with_temperature(0.7)
             .with_max_tokens(4096);
 
@@ -122,16 +139,30 @@ async fn main() -> Result<()> {
 
         let mut in_reasoning = false;
         let mut in_message = false;
+        let mut thinking_beats = 0;
         let mut assistant_response = String::new();
 
         // Process events
         while let Some(event) = event_rx.recv().await {
+            // Label output from agents other than the primary one (subagents)
+            if let Some(agent) = event.agent() {
+                if agent != primary_agent && matches!(event, StreamEvent::Message { .. } | StreamEvent::ToolCall { name: Some(_), .. }) {
+                    print!("\n\x1b[1;35m[{}]\x1b[0m ", agent);
+                }
+            }
+
+            // Replace the thinking spinner once output starts
+            if thinking_beats > 0 && !matches!(event, StreamEvent::Thinking { .. }) {
+                print!("\r\x1b[2K\x1b[1;32mAssistant:\x1b[0m ");
+                thinking_beats = 0;
+            }
+
             match event {
                 StreamEvent::InitStream { .. } => {
                     // Silent - just track
                 }
 
-                StreamEvent::Reasoning { content } => {
+                StreamEvent::Reasoning { content, .. } => {
                     if !in_reasoning {
                         print!("\n\x1b[2;3mReasoning: ");
                         in_reasoning = true;
@@ -141,7 +172,11 @@ async fn main() -> Result<()> {
                     io::stdout().flush()?;
                 }
 
-                StreamEvent::Message { content } => {
+                StreamEvent::ReasoningSummary { .. } => {
+                    // Silent - the full reasoning was already streamed
+                }
+
+                StreamEvent::Message { content, .. } => {
                     if !in_message {
                         if in_reasoning {
                             print!("\x1b[0m\n\n");
@@ -155,32 +190,30 @@ async fn main() -> Result<()> {
                     io::stdout().flush()?;
                 }
 
-                StreamEvent::ToolCall {
-                    index: _,
-                    id: _,
-                    name,
-                    arguments,
-                } => {
+                StreamEvent::ToolCall { .. } => {
                     if in_reasoning {
                         print!("\x1b[0m\n");
                     }
-                    if let Some(name) = name {
-                        if let Some(args) = arguments {
-                            print!("\n\x1b[1;33mCalling tool: {} ({})\x1b[0m", name, args);
-                        } else {
-                            print!("\n\x1b[1;33mCalling tool: {}\x1b[0m", name);
-                        }
-                        io::stdout().flush()?;
-                    }
                     in_reasoning = false;
                     in_message = false;
                 }
 
+                StreamEvent::ToolCallComplete { name, arguments, .. } => {
+                    print!("\n\x1b[1;33mCalling tool: {} ({})\x1b[0m", name, arguments);
+                    io::stdout().flush()?;
+                }
+
+                StreamEvent::Activity { text, .. } => {
+                    print!("\n\x1b[2m{}…\x1b[0m", text);
+                    io::stdout().flush()?;
+                }
+
                 StreamEvent::ToolResult {
                     tool_call_id: _,
                     result,
                     is_error,
                     duration_ms,
+                    ..
                 } => {
                     if is_error {
                         print!(
@@ -204,10 +237,67 @@ async fn main() -> Result<()> {
                     in_message = false;
                 }
 
-                StreamEvent::Done { finish_reason: _ } => {
+                StreamEvent::Citations { citations, .. } => {
+                    for citation in citations {
+                        print!("\n\x1b[2m[{}] {} ({})\x1b[0m", citation.marker, citation.tool_name, citation.tool_call_id);
+                    }
+                    io::stdout().flush()?;
+                }
+
+                StreamEvent::Handoff { from, to, reason } => {
+                    print!("\n\x1b[1;35m{} → {}\x1b[0m", from, to);
+                    if let Some(reason) = reason {
+                        print!(" \x1b[2m({})\x1b[0m", reason);
+                    }
+                    io::stdout().flush()?;
+                    in_reasoning = false;
+                    in_message = false;
+                }
+
+                StreamEvent::OptionsAdjusted { adjustments } => {
+                    for adjusted in adjustments {
+                        println!("\n\x1b[33m⚠ {}: {}\x1b[0m", adjusted.option, adjusted.reason);
+                    }
+                }
+
+                StreamEvent::Retrying { in_seconds, .. } => {
+                    println!("\n\x1b[33mHigh demand, retrying in {}s\x1b[0m", in_seconds);
+                }
+
+                StreamEvent::Thinking { elapsed_ms } => {
+                    // Reasoning text already shows progress
+                    if !in_reasoning && !in_message {
+                        const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+                        print!(
+                            "\r\x1b[2K\x1b[1;32mAssistant:\x1b[0m \x1b[2m{} thinking for {}s…\x1b[0m",
+                            SPINNER[thinking_beats % SPINNER.len()],
+                            elapsed_ms / 1000
+                        );
+                        io::stdout().flush()?;
+                        thinking_beats += 1;
+                    }
+                }
+
+                StreamEvent::Usage { .. } | StreamEvent::ResponseMetadata { .. } => {
+                    // Token counts and request ids are for billing and traces, not the transcript
+                }
+
+                StreamEvent::Audio { .. } => {
+                    // A terminal can't play it; the transcript arrives as Message text
+                }
+
+                StreamEvent::Done { .. } => {
                     // LLM stream done, continue to next node
                 }
 
+                StreamEvent::Warning { message, .. } => {
+                    println!("\n\x1b[33m⚠ {}\x1b[0m", message);
+                }
+
+                StreamEvent::ModerationWarning { stage, categories } => {
+                    println!("\n\x1b[33m⚠ Moderation flagged the {}: {}\x1b[0m", stage, categories.join(", "));
+                }
+
                 StreamEvent::Error { message, .. } => {
                     print!("\n\n\x1b[1;31mError: {}\x1b[0m", message);
                     io::stdout().flush()?;
@@ -215,8 +305,7 @@ async fn main() -> Result<()> {
                 }
 
                 StreamEvent::EndStream {
-                    status: _,
-                    total_duration_ms,
+                    total_duration_ms, ..
                 } => {
                     print!("\n\n\x1b[2m[Completed in {}ms]\x1b[0m", total_duration_ms);
                     io::stdout().flush()?;
@@ -227,11 +316,7 @@ async fn main() -> Result<()> {
         
         // Add assistant response to conversation history
         if !assistant_response.is_empty() {
-            conversation_history.push(Message::AI {
-                content: Some(Content::text(assistant_response)),
-                tool_calls: None,
-                name: None,
-            });
+            conversation_history.push(Message::ai(assistant_response));
         }
 
         println!(); // Final newline
@@ -0,0 +1,138 @@
+//! Human-readable activity lines for tool calls
+//!
+//! Once a tool call's arguments are complete the graph turns the call into a
+//! line like "Searching for 'rust async channels'" and emits it as
+//! `StreamEvent::Activity`, next to the structured `ToolCall` events, so a UI
+//! can show what the agent is doing without rendering raw JSON.
+//!
+//! `TemplateDescriber` fills per-tool templates from the arguments
+//! (`GraphConfig::activity`); any other `ToolCallDescriber` can be plugged in
+//! with `GraphBuilder::with_tool_call_describer`. Calls no describer handles
+//! read "Using {tool_name}".
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Longest argument value spliced into a template, in characters
+const MAX_VALUE_CHARS: usize = 80;
+
+/// Turns a tool call into an activity line
+pub trait ToolCallDescriber: Send + Sync {
+    /// Describe a call of `tool_name` with `arguments`, or None to fall back
+    /// to the generic line
+    ///
+    /// `arguments` is whatever the model sent: fields may be missing or of
+    /// an unexpected type, and it is `Value::Null` when they weren't JSON.
+    fn describe(&self, tool_name: &str, arguments: &Value) -> Option<String>;
+}
+
+/// Describer filling per-tool templates such as `"Searching for '{query}'"`
+///
+/// A `{field}` placeholder takes the top-level argument of that name
+/// (`{a.b}` reaches into objects). Strings are inserted as-is, other values
+/// as JSON. A template whose placeholders the arguments can't fill doesn't
+/// match.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateDescriber {
+    templates: BTreeMap<String, String>,
+}
+
+impl TemplateDescriber {
+    pub fn new(templates: BTreeMap<String, String>) -> Self {
+        Self { templates }
+    }
+
+    /// Add or replace the template for `tool_name`
+    pub fn with_template(mut self, tool_name: impl Into<String>, template: impl Into<String>) -> Self {
+        self.templates.insert(tool_name.into(), template.into());
+        self
+    }
+}
+
+impl ToolCallDescriber for TemplateDescriber {
+    fn describe(&self, tool_name: &str, arguments: &Value) -> Option<String> {
+        render(self.templates.get(tool_name)?, arguments)
+    }
+}
+
+/// The activity line for a call: the describer's, else "Using {tool_name}"
+pub(crate) fn activity_text(describer: &dyn ToolCallDescriber, tool_name: &str, raw_arguments: &str) -> String {
+    let arguments = serde_json::from_str(raw_arguments).unwrap_or(Value::Null);
+    describer
+        .describe(tool_name, &arguments)
+        .filter(|text| !text.trim().is_empty())
+        .unwrap_or_else(|| format!("Using {}", tool_name))
+}
+
+fn render(template: &str, arguments: &Value) -> Option<String> {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let close = open + rest[open..].find('}')?;
+        text.push_str(&rest[..open]);
+        let value = rest[open + 1..close]
+            .split('.')
+            .try_fold(arguments, |value, key| value.get(key.trim()))?;
+        text.push_str(&shorten(value));
+        rest = &rest[close + 1..];
+    }
+    text.push_str(rest);
+    Some(text)
+}
+
+fn shorten(value: &Value) -> String {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() <= MAX_VALUE_CHARS {
+        return text;
+    }
+    let mut short: String = text.chars().take(MAX_VALUE_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn describer() -> TemplateDescriber {
+        TemplateDescriber::default()
+            .with_template("search", "Searching for '{query}'")
+            .with_template("read_file", "Reading file {path}")
+            .with_template("fetch", "Fetching {request.url} ({request.method})")
+    }
+
+    #[test]
+    fn test_templates_fill_from_arguments() {
+        let describer = describer();
+        assert_eq!(
+            activity_text(&describer, "search", r#"{"query": "rust async channels"}"#),
+            "Searching for 'rust async channels'"
+        );
+        assert_eq!(
+            activity_text(&describer, "fetch", r#"{"request": {"url": "https://x.dev", "method": "GET"}}"#),
+            "Fetching https://x.dev (GET)"
+        );
+        assert_eq!(activity_text(&describer, "read_file", r#"{"path": 42}"#), "Reading file 42");
+    }
+
+    #[test]
+    fn test_unfillable_templates_fall_back_to_generic_line() {
+        let describer = describer();
+        assert_eq!(activity_text(&describer, "search", r#"{"q": "typo"}"#), "Using search");
+        assert_eq!(activity_text(&describer, "search", r#"{"query": "trunc"#), "Using search");
+        assert_eq!(activity_text(&describer, "fetch", r#"{"request": "https://x.dev"}"#), "Using fetch");
+        assert_eq!(activity_text(&describer, "calculator", "{}"), "Using calculator");
+    }
+
+    #[test]
+    fn test_long_values_are_shortened() {
+        let query = "a".repeat(200);
+        let text = describer().describe("search", &json!({ "query": query })).unwrap();
+        assert_eq!(text.chars().count(), "Searching for ''".len() + MAX_VALUE_CHARS);
+        assert!(text.ends_with("…'"));
+    }
+}
@@ -0,0 +1,247 @@
+//! Per-call authorization of tool execution
+//!
+//! A `ToolAuthorizer` is asked before every tool call of a run. A denial is
+//! returned to the model as an error tool result carrying the policy reason,
+//! so it can pick another approach instead of failing the run.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Who a run is executing for
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunContext {
+    pub run_id: String,
+    pub conversation_id: String,
+    /// From `PersistenceContext::user_id`, when the run is persisted
+    pub user_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub roles: Vec<String>,
+}
+
+impl RunContext {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Outcome of a `ToolAuthorizer` check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    /// Not executed; `reason` is shown to the model
+    Deny { reason: String },
+}
+
+impl Decision {
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::Deny { reason: reason.into() }
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allow)
+    }
+}
+
+/// Decides whether a tool call may run
+pub trait ToolAuthorizer: Send + Sync {
+    fn authorize(&self, ctx: &RunContext, tool_name: &str, arguments: &Value) -> Decision;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleEffect {
+    #[default]
+    Allow,
+    Deny,
+}
+
+/// One rule of a `RulesAuthorizer`
+///
+/// Empty `tenants`/`roles` match anyone; otherwise the run needs one of the
+/// listed tenants and at least one of the listed roles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizationRule {
+    /// Tool name glob (`*` any run of characters, `?` one character)
+    pub tool: String,
+    #[serde(default)]
+    pub tenants: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    pub effect: RuleEffect,
+    /// Shown to the model on deny
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl AuthorizationRule {
+    fn matches(&self, ctx: &RunContext, tool_name: &str) -> bool {
+        glob_match(&self.tool, tool_name)
+            && (self.tenants.is_empty()
+                || ctx.tenant_id.as_ref().is_some_and(|tenant| self.tenants.contains(tenant)))
+            && (self.roles.is_empty() || self.roles.iter().any(|role| ctx.has_role(role)))
+    }
+}
+
+/// First matching rule wins; calls matching no rule get `default`
+///
+/// Deserializes from config, e.g.:
+///
+/// ```toml
+/// default = "allow"
+///
+/// [[rules]]
+/// tool = "delete_record"
+/// roles = ["admin"]
+/// effect = "allow"
+///
+/// [[rules]]
+/// tool = "delete_record"
+/// effect = "deny"
+/// reason = "delete_record is restricted to admins"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RulesAuthorizer {
+    #[serde(default)]
+    pub default: RuleEffect,
+    #[serde(default)]
+    pub rules: Vec<AuthorizationRule>,
+}
+
+impl RulesAuthorizer {
+    pub fn new(default: RuleEffect) -> Self {
+        Self { default, rules: Vec::new() }
+    }
+
+    pub fn with_rule(mut self, rule: AuthorizationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+impl ToolAuthorizer for RulesAuthorizer {
+    fn authorize(&self, ctx: &RunContext, tool_name: &str, _arguments: &Value) -> Decision {
+        let rule = self.rules.iter().find(|rule| rule.matches(ctx, tool_name));
+        match rule.map_or(self.default, |rule| rule.effect) {
+            RuleEffect::Allow => Decision::Allow,
+            RuleEffect::Deny => Decision::deny(
+                rule.and_then(|rule| rule.reason.clone())
+                    .unwrap_or_else(|| format!("tool '{}' is not permitted for this user", tool_name)),
+            ),
+        }
+    }
+}
+
+/// Glob match where `*` matches any run of characters and `?` exactly one
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*`, and where in `name` it started matching
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ctx(tenant: Option<&str>, roles: &[&str]) -> RunContext {
+        RunContext {
+            tenant_id: tenant.map(String::from),
+            roles: roles.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn rule(tool: &str, effect: RuleEffect) -> AuthorizationRule {
+        AuthorizationRule {
+            tool: tool.to_string(),
+            tenants: vec![],
+            roles: vec![],
+            effect,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("http_*", "http_get"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("*_record", "delete_record"));
+        assert!(glob_match("get_?", "get_a"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("http_*", "fetch_http"));
+        assert!(!glob_match("get_?", "get_ab"));
+        assert!(!glob_match("delete_record", "delete_records"));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let authorizer = RulesAuthorizer::default()
+            .with_rule(AuthorizationRule { roles: vec!["admin".to_string()], ..rule("delete_record", RuleEffect::Allow) })
+            .with_rule(AuthorizationRule {
+                reason: Some("admins only".to_string()),
+                ..rule("delete_record", RuleEffect::Deny)
+            });
+
+        assert_eq!(authorizer.authorize(&ctx(None, &["admin"]), "delete_record", &json!({})), Decision::Allow);
+        assert_eq!(
+            authorizer.authorize(&ctx(None, &["viewer"]), "delete_record", &json!({})),
+            Decision::deny("admins only")
+        );
+        // Unmatched tools fall through to the default
+        assert!(authorizer.authorize(&ctx(None, &[]), "search", &json!({})).is_allowed());
+    }
+
+    #[test]
+    fn test_tenant_rule_and_deny_default() {
+        let authorizer = RulesAuthorizer::new(RuleEffect::Deny)
+            .with_rule(AuthorizationRule { tenants: vec!["tenant-x".to_string()], ..rule("http_*", RuleEffect::Deny) })
+            .with_rule(rule("*", RuleEffect::Allow));
+
+        assert!(!authorizer.authorize(&ctx(Some("tenant-x"), &[]), "http_get", &json!({})).is_allowed());
+        assert!(authorizer.authorize(&ctx(Some("tenant-y"), &[]), "http_get", &json!({})).is_allowed());
+        // A tenant rule never matches runs without a tenant
+        assert!(authorizer.authorize(&ctx(None, &[]), "http_get", &json!({})).is_allowed());
+
+        let deny_all = RulesAuthorizer::new(RuleEffect::Deny);
+        match deny_all.authorize(&ctx(None, &[]), "search", &json!({})) {
+            Decision::Deny { reason } => assert!(reason.contains("search")),
+            Decision::Allow => panic!("expected deny"),
+        }
+    }
+
+    #[test]
+    fn test_rules_from_config() {
+        let authorizer: RulesAuthorizer = serde_json::from_value(json!({
+            "default": "deny",
+            "rules": [{ "tool": "search", "effect": "allow" }]
+        }))
+        .unwrap();
+
+        assert_eq!(authorizer.default, RuleEffect::Deny);
+        assert_eq!(authorizer.rules, vec![rule("search", RuleEffect::Allow)]);
+    }
+}
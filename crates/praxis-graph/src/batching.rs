@@ -0,0 +1,238 @@
+//! Batching of message and reasoning deltas on the graph event channel
+//!
+//! With `GraphConfig::streaming.batch` set, deltas are held and each run of
+//! consecutive `Message` or `Reasoning` deltas from the same agent and node
+//! goes out as one event when the `BatchPolicy` says so. Any other event
+//! sends what is held first and is forwarded at once, so tool calls,
+//! `Done` and errors are never delayed and the order of events is kept.
+
+use crate::node::EventSender;
+use crate::types::{BatchPolicy, StreamEvent};
+use praxis_llm::buffer_utils::{AdaptiveEventBatcher, EventBatcher};
+use praxis_llm::ReasoningKind;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Adaptive window bounds, in milliseconds
+const ADAPTIVE_BASE_MS: u64 = 50;
+const ADAPTIVE_MIN_MS: u64 = 20;
+const ADAPTIVE_MAX_MS: u64 = 200;
+
+/// Put a batcher in front of `output`; events sent to the returned sender reach `output` batched
+pub(crate) fn spawn_batcher(policy: BatchPolicy, output: EventSender, capacity: usize) -> EventSender {
+    let (tx, rx) = mpsc::channel(capacity);
+    tokio::spawn(Batcher::new(policy, output).run(rx));
+    tx
+}
+
+/// Deltas waiting to go out, and when they should
+enum Held {
+    Interval(EventBatcher<StreamEvent>),
+    Adaptive(AdaptiveEventBatcher<StreamEvent>),
+    Chars { events: Vec<StreamEvent>, chars: usize, min_chars: usize },
+}
+
+impl Held {
+    fn new(policy: BatchPolicy) -> Self {
+        match policy {
+            BatchPolicy::FixedInterval(ms) => {
+                let mut batcher = EventBatcher::new(ms.max(1));
+                // The first tick would be immediate
+                batcher.ticker().reset();
+                Self::Interval(batcher)
+            }
+            BatchPolicy::Adaptive => {
+                let mut batcher = AdaptiveEventBatcher::new(ADAPTIVE_BASE_MS, ADAPTIVE_MIN_MS, ADAPTIVE_MAX_MS);
+                batcher.ticker().reset();
+                Self::Adaptive(batcher)
+            }
+            BatchPolicy::MinChars(min_chars) => Self::Chars { events: Vec::new(), chars: 0, min_chars },
+        }
+    }
+
+    fn push(&mut self, delta: StreamEvent) {
+        match self {
+            Self::Interval(batcher) => batcher.push(delta),
+            Self::Adaptive(batcher) => batcher.push(delta),
+            Self::Chars { events, chars, .. } => {
+                *chars += delta_text(&delta).chars().count();
+                events.push(delta);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Interval(batcher) => batcher.is_empty(),
+            Self::Adaptive(batcher) => batcher.is_empty(),
+            Self::Chars { events, .. } => events.is_empty(),
+        }
+    }
+
+    /// Whether `delta` can't join the held text and what is held should go
+    /// out first (`MinChars` counts the characters of one run of deltas)
+    fn breaks_run(&self, delta: &StreamEvent) -> bool {
+        match self {
+            Self::Chars { events, .. } => events.last().is_some_and(|last| !same_run(last, delta)),
+            _ => false,
+        }
+    }
+
+    /// Whether the held text is enough to send without waiting for a tick
+    fn is_full(&self) -> bool {
+        matches!(self, Self::Chars { chars, min_chars, .. } if chars >= min_chars)
+    }
+
+    fn take(&mut self) -> Vec<StreamEvent> {
+        match self {
+            Self::Interval(batcher) => batcher.take(),
+            Self::Adaptive(batcher) => batcher.take(),
+            Self::Chars { events, chars, .. } => {
+                *chars = 0;
+                std::mem::take(events)
+            }
+        }
+    }
+
+    /// Next interval tick; never for `MinChars`
+    async fn tick(&mut self) {
+        match self {
+            Self::Interval(batcher) => {
+                batcher.ticker().tick().await;
+            }
+            Self::Adaptive(batcher) => {
+                batcher.ticker().tick().await;
+            }
+            Self::Chars { .. } => std::future::pending().await,
+        }
+    }
+
+    /// How long the consumer took to accept a batch (adaptive windows grow with it)
+    fn record_send(&mut self, elapsed: std::time::Duration) {
+        if let Self::Adaptive(batcher) = self {
+            batcher.record_latency(elapsed);
+        }
+    }
+}
+
+struct Batcher {
+    held: Held,
+    output: EventSender,
+}
+
+impl Batcher {
+    fn new(policy: BatchPolicy, output: EventSender) -> Self {
+        Self { held: Held::new(policy), output }
+    }
+
+    async fn run(mut self, mut input: mpsc::Receiver<StreamEvent>) {
+        loop {
+            tokio::select! {
+                biased;
+                event = input.recv() => match event {
+                    Some(event) if is_delta(&event) => {
+                        if self.held.breaks_run(&event) && self.flush().await.is_err() {
+                            return;
+                        }
+                        self.held.push(event);
+                        if self.held.is_full() && self.flush().await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(event) => {
+                        if self.flush().await.is_err() || self.output.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => {
+                        let _ = self.flush().await;
+                        return;
+                    }
+                },
+                _ = self.held.tick() => {
+                    if self.flush().await.is_err() {
+                        return;
+                    }
+                }
+                // Receiver gone: drop our input so the run sees the cancellation
+                _ = self.output.closed() => return,
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), mpsc::error::SendError<StreamEvent>> {
+        if self.held.is_empty() {
+            return Ok(());
+        }
+        let started = Instant::now();
+        for event in merge_deltas(self.held.take()) {
+            self.output.send(event).await?;
+        }
+        self.held.record_send(started.elapsed());
+        Ok(())
+    }
+}
+
+fn is_delta(event: &StreamEvent) -> bool {
+    matches!(event, StreamEvent::Message { .. } | StreamEvent::Reasoning { .. })
+}
+
+fn delta_text(event: &StreamEvent) -> &str {
+    match event {
+        StreamEvent::Message { content, .. } | StreamEvent::Reasoning { content, .. } => content,
+        _ => "",
+    }
+}
+
+/// Whether `next` continues the delta `last`: same kind, agent and provenance
+fn same_run(last: &StreamEvent, next: &StreamEvent) -> bool {
+    std::mem::discriminant(last) == std::mem::discriminant(next)
+        && reasoning_kind(last) == reasoning_kind(next)
+        && last.agent() == next.agent()
+        && last.provenance() == next.provenance()
+}
+
+fn reasoning_kind(event: &StreamEvent) -> Option<ReasoningKind> {
+    match event {
+        StreamEvent::Reasoning { kind, .. } => Some(*kind),
+        _ => None,
+    }
+}
+
+/// Join consecutive deltas of the same kind, agent and provenance
+fn merge_deltas(deltas: Vec<StreamEvent>) -> Vec<StreamEvent> {
+    let mut merged: Vec<StreamEvent> = Vec::new();
+    for delta in deltas {
+        let joins = merged.last().is_some_and(|last| same_run(last, &delta));
+        match merged.last_mut() {
+            Some(StreamEvent::Message { content, .. } | StreamEvent::Reasoning { content, .. }) if joins => {
+                content.push_str(delta_text(&delta))
+            }
+            _ => merged.push(delta),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str, agent: Option<&str>) -> StreamEvent {
+        StreamEvent::Message { content: content.to_string(), agent: agent.map(str::to_string), provenance: None }
+    }
+
+    #[test]
+    fn test_merge_keeps_kinds_and_agents_apart() {
+        let merged = merge_deltas(vec![
+            message("Hel", None),
+            message("lo", None),
+            StreamEvent::Reasoning { content: "hm".to_string(), kind: ReasoningKind::Raw, agent: None, provenance: None },
+            StreamEvent::Reasoning { content: "Thinking".to_string(), kind: ReasoningKind::Summary, agent: None, provenance: None },
+            message(" there", None),
+            message("!", Some("researcher")),
+        ]);
+        let texts: Vec<&str> = merged.iter().map(delta_text).collect();
+        assert_eq!(texts, ["Hello", "hm", "Thinking", " there", "!"]);
+    }
+}
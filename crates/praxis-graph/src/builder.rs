@@ -1,15 +1,26 @@
+use std::path::Path;
 use std::sync::Arc;
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 
-use praxis_llm::LLMClient;
+use praxis_core::{Clock, IdGen, SystemClock, UuidIds};
+use praxis_llm::{CapabilityRegistry, CostCalculator, LLMClient, ModerationClient};
 use praxis_mcp::MCPToolExecutor;
-use crate::types::GraphConfig;
+use praxis_persist::{ContentTokenizer, PersistencePolicy};
+use crate::types::{ContextPolicy, GraphConfig, StreamingConfig};
+use crate::authorization::ToolAuthorizer;
+use crate::activity::ToolCallDescriber;
+use crate::moderation::{ModerationPolicy, Moderator};
+use crate::router::{BudgetRouter, Router};
+use crate::spec::{GraphSpec, NodeRegistry};
 
-use crate::graph::Graph;
+use crate::graph::{Graph, RunDeps, Topology};
 
 /// Configuration for optional persistence
 pub struct PersistenceConfig {
     pub client: Arc<dyn praxis_persist::PersistenceClient>,
+    pub policy: PersistencePolicy,
+    /// Counts tokens of saved messages (None = hash only)
+    pub tokenizer: Option<Arc<dyn ContentTokenizer>>,
 }
 
 /// Configuration for optional observability
@@ -25,6 +36,16 @@ pub struct GraphBuilder {
     mcp_executor: Option<Arc<MCPToolExecutor>>,
     config: GraphConfig,
     persistence_config: Option<PersistenceConfig>,
+    persistence_policy: PersistencePolicy,
+    content_tokenizer: Option<Arc<dyn ContentTokenizer>>,
+    tool_authorizer: Option<Arc<dyn ToolAuthorizer>>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGen>,
+    topology: Topology,
+    /// Applied over `config` at build time
+    streaming: Option<StreamingConfig>,
+    /// Budgets and guardrails applied over `config` at build time
+    spec: Option<GraphSpec>,
     #[cfg(feature = "observability")]
     observer_config: Option<ObserverConfig>,
 }
@@ -37,10 +58,45 @@ impl GraphBuilder {
             mcp_executor: None,
             config: GraphConfig::default(),
             persistence_config: None,
+            persistence_policy: PersistencePolicy::default(),
+            content_tokenizer: None,
+            tool_authorizer: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidIds),
+            topology: Topology::default(),
+            streaming: None,
+            spec: None,
             #[cfg(feature = "observability")]
             observer_config: None,
         }
     }
+
+    /// A builder shaped by the spec file at `path` (see `crate::spec`)
+    ///
+    /// The spec is validated against `registry`. Its budgets and guardrails
+    /// override the matching `GraphConfig` fields when `build` is called.
+    pub fn from_spec(path: impl AsRef<Path>, registry: &NodeRegistry) -> Result<Self> {
+        let path = path.as_ref();
+        let spec = GraphSpec::load(path)?;
+        spec.validate(registry)
+            .with_context(|| format!("invalid graph spec {}", path.display()))?;
+
+        let mut builder = Self::new();
+        builder.topology.custom_nodes = spec.custom_nodes(registry)?;
+        if let Some(max_tool_rounds) = spec.router.max_tool_rounds {
+            builder = builder.with_router(Arc::new(BudgetRouter { max_tool_rounds }));
+        }
+        if let Some(prompt) = &spec.system_prompt {
+            let prompt = std::fs::read_to_string(prompt)
+                .with_context(|| format!("system_prompt: cannot read {}", prompt.display()))?;
+            builder = builder.with_system_prompt(prompt);
+        }
+        if let Some(policy) = &spec.context_policy {
+            builder = builder.with_context_policy(policy.clone());
+        }
+        builder.spec = Some(spec);
+        Ok(builder)
+    }
     
     /// Set the LLM client
     pub fn llm_client(mut self, client: Arc<dyn LLMClient>) -> Self {
@@ -65,13 +121,109 @@ impl GraphBuilder {
         self.config = config;
         self
     }
+
+    /// Set the graph configuration (e.g. loaded from a config file)
+    ///
+    /// The configuration is validated when `build` is called.
+    pub fn with_config(self, config: GraphConfig) -> Self {
+        self.config(config)
+    }
     
     /// Enable persistence with a PersistenceClient
     pub fn with_persistence(mut self, client: Arc<dyn praxis_persist::PersistenceClient>) -> Self {
-        self.persistence_config = Some(PersistenceConfig { client });
+        self.persistence_config = Some(PersistenceConfig {
+            client,
+            policy: PersistencePolicy::default(),
+            tokenizer: None,
+        });
+        self
+    }
+    
+    /// Choose which message kinds are persisted (defaults to everything)
+    pub fn with_persistence_policy(mut self, policy: PersistencePolicy) -> Self {
+        self.persistence_policy = policy;
+        self
+    }
+    
+    /// Record a token count on every saved message, using the run's model
+    pub fn with_content_tokenizer(mut self, tokenizer: Arc<dyn ContentTokenizer>) -> Self {
+        self.content_tokenizer = Some(tokenizer);
+        self
+    }
+    
+    /// Check every tool call against `authorizer` before it runs
+    pub fn with_tool_authorizer(mut self, authorizer: Arc<dyn ToolAuthorizer>) -> Self {
+        self.tool_authorizer = Some(authorizer);
+        self
+    }
+    
+    /// Take timestamps from `clock` (e.g. a `FixedClock` in tests)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Mint run and message ids with `ids` (e.g. `SeqIds` in tests)
+    pub fn with_id_gen(mut self, ids: Arc<dyn IdGen>) -> Self {
+        self.ids = ids;
         self
     }
     
+    /// Decide the next node and tool availability with `router` (default: `SimpleRouter`)
+    pub fn with_router(mut self, router: Arc<dyn Router>) -> Self {
+        self.topology.router = router;
+        self
+    }
+
+    /// System prompt for runs whose input doesn't start with one
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.topology.system_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Send the model only the part of the history `policy` keeps
+    pub fn with_context_policy(mut self, policy: ContextPolicy) -> Self {
+        self.topology.context_policy = Some(policy);
+        self
+    }
+
+    /// Describe tool calls with `describer` instead of the `GraphConfig::activity` templates
+    pub fn with_tool_call_describer(mut self, describer: Arc<dyn ToolCallDescriber>) -> Self {
+        self.topology.tool_call_describer = Some(describer);
+        self
+    }
+
+    /// Price each LLM call's usage with `calculator`, reporting the run's
+    /// total as `EndStream::cost_usd` and each call's cost to the observer
+    pub fn with_cost_calculator(mut self, calculator: CostCalculator) -> Self {
+        self.topology.cost_calculator = Some(Arc::new(calculator));
+        self
+    }
+
+    /// Decide which models are reasoning models, and what options each
+    /// takes, from `registry`; on-prem and Azure deployments declare their
+    /// model names here. Give the provider client the same registry (e.g.
+    /// `OpenAIConfig::with_capabilities`) so both drop the same options.
+    pub fn with_capabilities(mut self, registry: CapabilityRegistry) -> Self {
+        self.topology.capabilities = Some(Arc::new(registry));
+        self
+    }
+
+    /// Screen the latest human message before the run's first LLM call, and
+    /// each answer after it streams, with `client`; `policy` decides whether
+    /// a flag ends the run or only warns
+    pub fn with_moderation(mut self, client: Arc<dyn ModerationClient>, policy: ModerationPolicy) -> Self {
+        self.topology.moderator = Some(Moderator::new(client, policy));
+        self
+    }
+
+    /// Deliver events as `streaming` says (e.g. batched deltas), whatever
+    /// the graph configuration
+    pub fn with_streaming(mut self, streaming: StreamingConfig) -> Self {
+        self.streaming = Some(streaming);
+        self
+    }
+
     /// Enable observability with an Observer
     #[cfg(feature = "observability")]
     pub fn with_observer(mut self, observer: Arc<dyn praxis_observability::Observer>) -> Self {
@@ -85,16 +237,36 @@ impl GraphBuilder {
             .ok_or_else(|| anyhow!("LLM client is required"))?;
         let mcp_executor = self.mcp_executor
             .ok_or_else(|| anyhow!("MCP executor is required"))?;
+        let mut config = match &self.spec {
+            Some(spec) => spec.apply_to(self.config),
+            None => self.config,
+        };
+        if let Some(streaming) = self.streaming {
+            config.streaming = streaming;
+        }
+        config
+            .validate()
+            .map_err(|e| anyhow!("Invalid graph config: {}", e))?;
         
-        Ok(Graph::new_with_config(
+        Ok(Graph::new_with_deps(RunDeps {
             llm_client,
-            self.reasoning_client,
+            reasoning_client: self.reasoning_client,
             mcp_executor,
-            self.config,
-            self.persistence_config,
+            config,
+            persistence: self.persistence_config.map(|persistence| {
+                Arc::new(PersistenceConfig {
+                    policy: self.persistence_policy,
+                    tokenizer: self.content_tokenizer,
+                    ..persistence
+                })
+            }),
+            tool_authorizer: self.tool_authorizer,
+            clock: self.clock,
+            ids: self.ids,
+            topology: self.topology,
             #[cfg(feature = "observability")]
-            self.observer_config,
-        ))
+            observer: self.observer_config.map(Arc::new),
+        }))
     }
 }
 
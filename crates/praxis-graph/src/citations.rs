@@ -0,0 +1,157 @@
+//! Citations from tool results
+//!
+//! With `GraphConfig::cite_sources` set, each LLM call that follows tool
+//! results gets a system addendum listing those results as numbered sources
+//! and asking the model to mark the facts it takes from them with `[n]`.
+//! When the call turns out to be the final answer (no tool calls), the
+//! markers are read back into `Citation`s linking each number to its tool
+//! call.
+//!
+//! The markers stay in the answer text. A model that ignores the request
+//! leaves no markers and the answer gets no citations; numbers that match no
+//! source are dropped.
+
+use praxis_llm::Message;
+use praxis_persist::Citation;
+
+/// A tool result the model may cite, numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Source {
+    pub(crate) marker: u32,
+    pub(crate) tool_call_id: String,
+    pub(crate) tool_name: String,
+}
+
+/// Tool results since the last user message, in the order they arrived
+pub(crate) fn sources(messages: &[Message]) -> Vec<Source> {
+    let turn_start = messages
+        .iter()
+        .rposition(|message| matches!(message, Message::Human { .. }))
+        .map_or(0, |i| i + 1);
+
+    let mut sources = Vec::new();
+    for (i, message) in messages[turn_start..].iter().enumerate() {
+        let Message::Tool { tool_call_id, .. } = message else {
+            continue;
+        };
+        sources.push(Source {
+            marker: sources.len() as u32 + 1,
+            tool_call_id: tool_call_id.clone(),
+            tool_name: tool_name(&messages[turn_start..turn_start + i], tool_call_id).unwrap_or_default(),
+        });
+    }
+    sources
+}
+
+/// Name of the call `tool_call_id` answers, from the latest AI message that made it
+fn tool_name(messages: &[Message], tool_call_id: &str) -> Option<String> {
+    messages.iter().rev().find_map(|message| match message {
+        Message::AI { tool_calls: Some(calls), .. } => calls
+            .iter()
+            .find(|call| call.id == tool_call_id)
+            .map(|call| call.function.name.clone()),
+        _ => None,
+    })
+}
+
+/// System addendum asking for `[n]` markers that refer to `sources`
+pub(crate) fn instruction(sources: &[Source]) -> String {
+    let list = sources
+        .iter()
+        .map(|source| format!("[{}] {} result (call {})", source.marker, source.tool_name, source.tool_call_id))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "The tool results in this conversation are numbered sources:\n{}\n\
+         When your answer states something taken from a source, put its number in square brackets \
+         right after it, e.g. \"It is sunny in Lisbon [1].\" Use only these numbers and don't add a \
+         list of sources at the end.",
+        list
+    )
+}
+
+/// Citations for the markers in `content`, in order of first appearance
+pub(crate) fn extract(content: &str, sources: &[Source]) -> Vec<Citation> {
+    let mut citations: Vec<Citation> = Vec::new();
+    for marker in markers(content) {
+        if citations.iter().any(|citation| citation.marker == marker) {
+            continue;
+        }
+        if let Some(source) = sources.iter().find(|source| source.marker == marker) {
+            citations.push(Citation {
+                marker,
+                tool_call_id: source.tool_call_id.clone(),
+                tool_name: source.tool_name.clone(),
+            });
+        }
+    }
+    citations
+}
+
+/// Numbers written as `[n]` in `content`
+fn markers(content: &str) -> Vec<u32> {
+    content
+        .split('[')
+        .skip(1)
+        .filter_map(|rest| {
+            let (number, _) = rest.split_once(']')?;
+            if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            number.parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use praxis_llm::types::FunctionCall;
+    use praxis_llm::ToolCall;
+
+    fn call(id: &str, name: &str) -> ToolCall {
+        ToolCall {
+            id: id.to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall { name: name.to_string(), arguments: "{}".to_string() },
+        }
+    }
+
+    fn result(id: &str, text: &str) -> Message {
+        Message::tool(id, text)
+    }
+
+    #[test]
+    fn test_sources_cover_the_current_turn() {
+        let messages = vec![
+            Message::human("weather?"),
+            Message::ai_with_tools(vec![call("old", "get_weather")]),
+            result("old", "rainy"),
+            Message::human("and now?"),
+            Message::ai_with_tools(vec![call("call_1", "get_weather"), call("call_2", "get_time")]),
+            result("call_1", "sunny"),
+            result("call_2", "10:00"),
+        ];
+
+        let sources = sources(&messages);
+        assert_eq!(sources.len(), 2);
+        assert_eq!((sources[0].marker, sources[0].tool_call_id.as_str(), sources[0].tool_name.as_str()), (1, "call_1", "get_weather"));
+        assert_eq!((sources[1].marker, sources[1].tool_call_id.as_str(), sources[1].tool_name.as_str()), (2, "call_2", "get_time"));
+        assert!(instruction(&sources).contains("[2] get_time result (call call_2)"));
+    }
+
+    #[test]
+    fn test_extract_keeps_known_markers_once() {
+        let sources = vec![
+            Source { marker: 1, tool_call_id: "call_1".to_string(), tool_name: "get_weather".to_string() },
+            Source { marker: 2, tool_call_id: "call_2".to_string(), tool_name: "get_time".to_string() },
+        ];
+
+        let citations = extract("It's 10:00 [2] and sunny [1][7], as said [2]. See [x] or [].", &sources);
+        let markers: Vec<u32> = citations.iter().map(|citation| citation.marker).collect();
+        assert_eq!(markers, vec![2, 1]);
+        assert_eq!(citations[0].tool_call_id, "call_2");
+
+        assert!(extract("It's sunny.", &sources).is_empty());
+    }
+}
@@ -1,7 +1,80 @@
 use crate::types::{LLMConfig, Provider};
 use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use praxis_llm::{LLMClient, ReasoningClient};
+use praxis_llm::{
+    AnthropicClient, AnthropicConfig, CapabilityRegistry, FallbackClient, FallbackProvider, GeminiClient, GeminiConfig, LLMClient, OpenAIClient,
+    OpenAIConfig, ReasoningClient, RecordingClient,
+};
+
+/// One provider of a fallback chain (see `ClientFactory::create_fallback_client`)
+#[derive(Clone, Deserialize)]
+pub struct ProviderConfig {
+    pub provider: Provider,
+    pub api_key: String,
+    /// `OpenAI-Organization` header (OpenAI only; default: `OPENAI_ORG_ID`)
+    #[serde(default)]
+    pub organization: Option<String>,
+    /// `OpenAI-Project` header (OpenAI only; default: `OPENAI_PROJECT_ID`)
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Request model name -> this provider's name for it
+    #[serde(default)]
+    pub models: HashMap<String, String>,
+    /// `reqwest::Client` to send requests through, so clients made from
+    /// several configs share one connection pool (see
+    /// `praxis_llm::ConnectionOptions::build_client`)
+    #[serde(skip)]
+    pub http_client: Option<reqwest::Client>,
+}
+
+impl ProviderConfig {
+    pub fn new(provider: Provider, api_key: impl Into<String>) -> Self {
+        Self {
+            provider,
+            api_key: api_key.into(),
+            organization: None,
+            project: None,
+            models: HashMap::new(),
+            http_client: None,
+        }
+    }
+
+    pub fn with_organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>, provider_model: impl Into<String>) -> Self {
+        self.models.insert(model.into(), provider_model.into());
+        self
+    }
+
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl std::fmt::Debug for ProviderConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderConfig")
+            .field("provider", &self.provider)
+            .field("api_key", &"[redacted]")
+            .field("organization", &self.organization)
+            .field("project", &self.project)
+            .field("models", &self.models)
+            .field("shared_http_client", &self.http_client.is_some())
+            .finish()
+    }
+}
 
 /// Factory Pattern: Centralized logic for client creation and configuration
 /// 
@@ -12,9 +85,11 @@ pub struct ClientFactory;
 impl ClientFactory {
     /// Check if a model supports reasoning capabilities
     /// 
-    /// Reasoning models (gpt-5, o1-*) require special handling and use the Responses API
-    pub fn supports_reasoning(model: &str) -> bool {
-        model.starts_with("gpt-5") || model.starts_with("o1")
+    /// Reasoning models (gpt-5, o-series) require special handling and use the Responses API.
+    /// Pass the registry the graph was built with so registered models get
+    /// the same answer here as in LLMNode.
+    pub fn supports_reasoning(model: &str, capabilities: &CapabilityRegistry) -> bool {
+        capabilities.get(model).reasoning
     }
     
     /// Validate that the given LLM configuration is supported
@@ -22,11 +97,9 @@ impl ClientFactory {
         match config.provider {
             Provider::OpenAI => Ok(()),
             Provider::Azure => {
-                Err(anyhow!("Azure provider not yet implemented. Use Provider::OpenAI for now."))
-            }
-            Provider::Anthropic => {
-                Err(anyhow!("Anthropic provider not yet implemented. Use Provider::OpenAI for now."))
+                Err(anyhow!("Azure provider not yet implemented. Use Provider::OpenAI, Provider::Anthropic or Provider::Gemini for now."))
             }
+            Provider::Anthropic | Provider::Gemini => Ok(()),
         }
     }
     
@@ -35,20 +108,74 @@ impl ClientFactory {
     /// This is a runtime check to see if we should attempt to use the Reasoning API
     pub fn should_use_reasoning_api(
         config: &LLMConfig,
+        capabilities: &CapabilityRegistry,
         reasoning_client: &Option<Arc<dyn ReasoningClient>>,
     ) -> bool {
-        Self::supports_reasoning(&config.model) && reasoning_client.is_some()
+        Self::supports_reasoning(&config.model, capabilities) && reasoning_client.is_some()
     }
     
-    /// Future: Create an LLM client from configuration
+    /// Create the client for `config.provider`
     /// 
-    /// Currently, clients are created at the application level and passed to the graph.
-    /// This method is reserved for future use when we might want to create clients
-    /// dynamically at runtime.
-    #[allow(dead_code)]
-    pub fn create_client(_config: &LLMConfig, _api_key: &str) -> Result<Arc<dyn LLMClient>> {
-        // Future implementation
-        Err(anyhow!("Dynamic client creation not yet implemented. Create clients at application level and pass to GraphBuilder."))
+    /// Applications that need client options (payload capture, retries,
+    /// ...) build clients themselves and pass them to GraphBuilder.
+    pub fn create_client(config: &LLMConfig, api_key: &str) -> Result<Arc<dyn LLMClient>> {
+        Self::create_provider_client(&ProviderConfig::new(config.provider.clone(), api_key))
+    }
+    
+    /// Create a client trying `providers` in order, falling back on rate
+    /// limits, 5xx and timeouts (see `praxis_llm::FallbackClient`)
+    ///
+    /// Each provider is named after its kind (`openai`, `anthropic`, ...) in
+    /// the responses' `served_by`.
+    pub fn create_fallback_client(providers: &[ProviderConfig]) -> Result<Arc<dyn LLMClient>> {
+        anyhow::ensure!(!providers.is_empty(), "A fallback client needs at least one provider");
+        let providers = providers
+            .iter()
+            .map(|config| {
+                let name = serde_json::to_value(&config.provider)?.as_str().unwrap_or_default().to_string();
+                let provider = FallbackProvider::new(name, Self::create_provider_client(config)?);
+                Ok(config.models.iter().fold(provider, |provider, (model, provider_model)| {
+                    provider.with_model(model.clone(), provider_model.clone())
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(FallbackClient::new(providers)))
+    }
+    
+    /// Create the client `config` describes, with its OpenAI organization
+    /// and project and its shared `reqwest::Client`, if any
+    pub fn create_provider_client(config: &ProviderConfig) -> Result<Arc<dyn LLMClient>> {
+        let api_key = config.api_key.as_str();
+        let http_client = config.http_client.clone();
+        match config.provider {
+            Provider::OpenAI => {
+                let mut openai = OpenAIConfig::new(api_key);
+                openai.organization = config.organization.clone();
+                openai.project = config.project.clone();
+                openai.http_client = http_client;
+                Ok(Arc::new(OpenAIClient::from_config(openai)?))
+            }
+            Provider::Anthropic => {
+                let mut anthropic = AnthropicConfig::new(api_key);
+                anthropic.http_client = http_client;
+                Ok(Arc::new(AnthropicClient::from_config(anthropic)?))
+            }
+            Provider::Gemini => {
+                let mut gemini = GeminiConfig::new(api_key);
+                gemini.http_client = http_client;
+                Ok(Arc::new(GeminiClient::from_config(gemini)?))
+            }
+            Provider::Azure => Err(anyhow!("Azure provider not yet implemented. Use Provider::OpenAI, Provider::Anthropic or Provider::Gemini for now.")),
+        }
+    }
+    
+    /// Record every call `client` makes to `record_dir`, one JSONL file per
+    /// run (see `praxis_llm::recording`); `None` returns `client` unchanged
+    pub fn with_record_dir(client: Arc<dyn LLMClient>, record_dir: Option<&Path>) -> Arc<dyn LLMClient> {
+        match record_dir {
+            Some(dir) => Arc::new(RecordingClient::new(client, dir)),
+            None => client,
+        }
     }
 }
 
@@ -59,14 +186,29 @@ mod tests {
     
     #[test]
     fn test_supports_reasoning() {
-        assert!(ClientFactory::supports_reasoning("gpt-5"));
-        assert!(ClientFactory::supports_reasoning("gpt-5-turbo"));
-        assert!(ClientFactory::supports_reasoning("o1-preview"));
-        assert!(ClientFactory::supports_reasoning("o1-mini"));
+        let registry = CapabilityRegistry::new();
+        assert!(ClientFactory::supports_reasoning("gpt-5", &registry));
+        assert!(ClientFactory::supports_reasoning("gpt-5-turbo", &registry));
+        assert!(ClientFactory::supports_reasoning("o1-preview", &registry));
+        assert!(ClientFactory::supports_reasoning("o1-mini", &registry));
+        
+        assert!(!ClientFactory::supports_reasoning("gpt-4o", &registry));
+        assert!(!ClientFactory::supports_reasoning("gpt-4o-mini", &registry));
+        assert!(!ClientFactory::supports_reasoning("gpt-3.5-turbo", &registry));
+    }
+    
+    #[test]
+    fn test_supports_reasoning_follows_registered_models() {
+        let registry = CapabilityRegistry::new()
+            .with_model("acme-reasoner", praxis_llm::ModelCapabilities::reasoning(200_000))
+            .with_model("gpt-5-chat", praxis_llm::ModelCapabilities::chat(128_000));
+        assert!(ClientFactory::supports_reasoning("acme-reasoner-v2", &registry));
+        assert!(!ClientFactory::supports_reasoning("gpt-5-chat-latest", &registry));
         
-        assert!(!ClientFactory::supports_reasoning("gpt-4o"));
-        assert!(!ClientFactory::supports_reasoning("gpt-4o-mini"));
-        assert!(!ClientFactory::supports_reasoning("gpt-3.5-turbo"));
+        let config = LLMConfig::new("acme-reasoner-v2");
+        let client: Arc<dyn ReasoningClient> = Arc::new(OpenAIClient::new("sk-test").unwrap());
+        assert!(ClientFactory::should_use_reasoning_api(&config, &registry, &Some(client)));
+        assert!(!ClientFactory::should_use_reasoning_api(&config, &registry, &None));
     }
     
     #[test]
@@ -78,7 +220,63 @@ mod tests {
         assert!(ClientFactory::validate_config(&azure_config).is_err());
         
         let anthropic_config = LLMConfig::new("claude-3").with_provider(Provider::Anthropic);
-        assert!(ClientFactory::validate_config(&anthropic_config).is_err());
+        assert!(ClientFactory::validate_config(&anthropic_config).is_ok());
+        
+        let gemini_config = LLMConfig::new("gemini-2.5-flash").with_provider(Provider::Gemini);
+        assert!(ClientFactory::validate_config(&gemini_config).is_ok());
+    }
+    
+    #[test]
+    fn test_create_client() {
+        let anthropic_config = LLMConfig::new("claude-sonnet-4-5").with_provider(Provider::Anthropic);
+        assert!(ClientFactory::create_client(&anthropic_config, "sk-ant-test").is_ok());
+        
+        let gemini_config = LLMConfig::new("gemini-2.5-flash").with_provider(Provider::Gemini);
+        assert!(ClientFactory::create_client(&gemini_config, "AIza-test").is_ok());
+        
+        let azure_config = LLMConfig::new("gpt-4o").with_provider(Provider::Azure);
+        assert!(ClientFactory::create_client(&azure_config, "key").is_err());
+    }
+    
+    #[test]
+    fn test_create_fallback_client() {
+        let providers: Vec<ProviderConfig> = serde_json::from_value(serde_json::json!([
+            { "provider": "openai", "api_key": "sk-test", "organization": "org-123", "project": "proj_abc" },
+            { "provider": "anthropic", "api_key": "sk-ant-test", "models": { "gpt-4o": "claude-sonnet-4-5" } },
+        ]))
+        .unwrap();
+        assert!(ClientFactory::create_fallback_client(&providers).is_ok());
+        assert!(!format!("{:?}", providers[0]).contains("sk-test"));
+        assert_eq!(providers[0].project.as_deref(), Some("proj_abc"));
+        assert_eq!(providers[1].organization, None);
+        
+        assert!(ClientFactory::create_fallback_client(&[]).is_err());
+        assert!(ClientFactory::create_fallback_client(&[ProviderConfig::new(Provider::Azure, "key")]).is_err());
+    }
+    
+    #[test]
+    fn test_provider_client_takes_the_openai_scoping() {
+        let scoped = ProviderConfig::new(Provider::OpenAI, "sk-test").with_organization("org-123").with_project("proj_abc");
+        assert!(ClientFactory::create_provider_client(&scoped).is_ok());
+        
+        // Only a header-safe project reaches the client
+        let invalid = ProviderConfig::new(Provider::OpenAI, "sk-test").with_project("proj\nabc");
+        let error = ClientFactory::create_provider_client(&invalid).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid OpenAI project id");
+    }
+    
+    #[test]
+    fn test_provider_clients_can_share_an_http_client() {
+        let shared = praxis_llm::ConnectionOptions::new()
+            .with_pool_max_idle_per_host(64)
+            .build_client(praxis_llm::http::DEFAULT_CONNECT_TIMEOUT)
+            .unwrap();
+        let providers = [
+            ProviderConfig::new(Provider::OpenAI, "sk-one").with_http_client(shared.clone()),
+            ProviderConfig::new(Provider::OpenAI, "sk-two").with_project("proj_abc").with_http_client(shared.clone()),
+            ProviderConfig::new(Provider::Anthropic, "sk-ant-test").with_http_client(shared),
+        ];
+        assert!(ClientFactory::create_fallback_client(&providers).is_ok());
+        assert!(format!("{:?}", providers[0]).contains("shared_http_client: true"));
     }
 }
-
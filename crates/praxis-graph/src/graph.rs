@@ -1,32 +1,86 @@
-use crate::node::{Node, NodeType};
+use crate::node::{EventSender, Node, NodeType};
 use crate::nodes::{LLMNode, ToolNode};
 use crate::router::{NextNode, Router, SimpleRouter};
+use crate::spec::CustomNodes;
 use crate::builder::PersistenceConfig;
+use crate::authorization::{RunContext, ToolAuthorizer};
+use crate::activity::{TemplateDescriber, ToolCallDescriber};
+use crate::moderation::{self, ModerationStage, Moderator};
 use praxis_llm::ReasoningClient;
+use praxis_core::{Clock, IdGen, SystemClock, UuidIds};
 #[cfg(feature = "observability")]
 use crate::builder::ObserverConfig;
 use anyhow::Result;
-use praxis_llm::LLMClient;
+use praxis_llm::{CapabilityRegistry, CostCalculator, LLMClient};
 use praxis_mcp::MCPToolExecutor;
-use crate::types::{GraphConfig, GraphInput, GraphState, StreamEvent};
+use crate::types::{ContextPolicy, GraphConfig, GraphErrorKind, GraphInput, GraphState, Provenance, StreamEvent};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 /// Context for persistence operations
+///
+/// `thread_id` is the stored thread the run's messages are saved to; the
+/// run's `GraphInput::conversation_id` is the caller's own identifier and
+/// may differ (e.g. an external conversation key mapped to the thread).
 pub struct PersistenceContext {
     pub thread_id: String,
     pub user_id: String,
 }
 
-pub struct Graph {
-    llm_client: Arc<dyn LLMClient>,
-    reasoning_client: Option<Arc<dyn praxis_llm::ReasoningClient>>,
-    mcp_executor: Arc<MCPToolExecutor>,
-    config: GraphConfig,
-    persistence: Option<Arc<PersistenceConfig>>,
+/// Routing and the nodes around the built-in LLM/tool loop
+#[derive(Clone)]
+pub(crate) struct Topology {
+    pub router: Arc<dyn Router>,
+    pub custom_nodes: CustomNodes,
+    /// Prepended when the input has no system message
+    pub system_prompt: Option<String>,
+    pub context_policy: Option<ContextPolicy>,
+    /// Describes tool calls in place of `GraphConfig::activity` templates
+    pub tool_call_describer: Option<Arc<dyn ToolCallDescriber>>,
+    /// Prices LLM calls for `EndStream::cost_usd` and the observer
+    pub cost_calculator: Option<Arc<CostCalculator>>,
+    /// Model capabilities, when some were declared on the builder
+    pub capabilities: Option<Arc<CapabilityRegistry>>,
+    /// Screens the input and each answer
+    pub moderator: Option<Moderator>,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self {
+            router: Arc::new(SimpleRouter),
+            custom_nodes: CustomNodes::default(),
+            system_prompt: None,
+            context_policy: None,
+            tool_call_describer: None,
+            cost_calculator: None,
+            capabilities: None,
+            moderator: None,
+        }
+    }
+}
+
+/// What every run of a graph shares: clients, configuration and the
+/// services around the loop
+#[derive(Clone)]
+pub(crate) struct RunDeps {
+    pub llm_client: Arc<dyn LLMClient>,
+    pub reasoning_client: Option<Arc<dyn ReasoningClient>>,
+    pub mcp_executor: Arc<MCPToolExecutor>,
+    pub config: GraphConfig,
+    pub persistence: Option<Arc<PersistenceConfig>>,
+    pub tool_authorizer: Option<Arc<dyn ToolAuthorizer>>,
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGen>,
+    pub topology: Topology,
     #[cfg(feature = "observability")]
-    observer: Option<Arc<ObserverConfig>>,
+    pub observer: Option<Arc<ObserverConfig>>,
+}
+
+pub struct Graph {
+    deps: RunDeps,
 }
 
 impl Graph {
@@ -35,35 +89,23 @@ impl Graph {
         mcp_executor: Arc<MCPToolExecutor>,
         config: GraphConfig,
     ) -> Self {
-        Self {
+        Self::new_with_deps(RunDeps {
             llm_client,
             reasoning_client: None,
             mcp_executor,
             config,
             persistence: None,
+            tool_authorizer: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidIds),
+            topology: Topology::default(),
             #[cfg(feature = "observability")]
             observer: None,
-        }
+        })
     }
     
-    pub(crate) fn new_with_config(
-        llm_client: Arc<dyn LLMClient>,
-        reasoning_client: Option<Arc<dyn praxis_llm::ReasoningClient>>,
-        mcp_executor: Arc<MCPToolExecutor>,
-        config: GraphConfig,
-        persistence: Option<PersistenceConfig>,
-        #[cfg(feature = "observability")]
-        observer: Option<ObserverConfig>,
-    ) -> Self {
-        Self {
-            llm_client,
-            reasoning_client,
-            mcp_executor,
-            config,
-            persistence: persistence.map(Arc::new),
-            #[cfg(feature = "observability")]
-            observer: observer.map(Arc::new),
-        }
+    pub(crate) fn new_with_deps(deps: RunDeps) -> Self {
+        Self { deps }
     }
     
     /// Create a builder for fluent construction
@@ -77,34 +119,28 @@ impl Graph {
         input: GraphInput,
         persistence_ctx: Option<PersistenceContext>,
     ) -> mpsc::Receiver<StreamEvent> {
-        let (tx, rx) = mpsc::channel(1000);
-
-        // Clone what we need for the spawned task
-        let llm_client = Arc::clone(&self.llm_client);
-        let reasoning_client = self.reasoning_client.clone();
-        let mcp_executor = Arc::clone(&self.mcp_executor);
-        let config = self.config.clone();
-        let persistence = self.persistence.clone();
-        #[cfg(feature = "observability")]
-        let observer = self.observer.clone();
+        let capacity = self.deps.config.event_channel_capacity.max(1);
+        let (tx, rx) = mpsc::channel(capacity);
+        // Batching sits closest to the consumer, so it sees the paced deltas
+        let tx = match self.deps.config.streaming.batch {
+            Some(policy) => crate::batching::spawn_batcher(policy, tx, capacity),
+            None => tx,
+        };
+        let tx = match &self.deps.config.smoothing {
+            Some(smoothing) => crate::smoothing::spawn_pacer(smoothing.clone(), tx, capacity),
+            None => tx,
+        };
+
+        let deps = self.deps.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = Self::execute_loop(
-                input,
-                tx.clone(),
-                llm_client,
-                reasoning_client,
-                mcp_executor,
-                config,
-                persistence,
-                #[cfg(feature = "observability")]
-                observer,
-                persistence_ctx,
-            ).await {
+            if let Err(e) = Self::execute_loop(input, tx.clone(), deps, persistence_ctx).await {
                 let _ = tx
                     .send(StreamEvent::Error {
                         message: e.to_string(),
                         node_id: None,
+                        kind: GraphErrorKind::of(&e),
+                        error_code: praxis_llm::LLMError::of(&e).map(|error| error.code().to_string()),
                     })
                     .await;
             }
@@ -116,23 +152,49 @@ impl Graph {
     async fn execute_loop(
         input: GraphInput,
         event_tx: mpsc::Sender<StreamEvent>,
-        llm_client: Arc<dyn LLMClient>,
-        reasoning_client: Option<Arc<dyn ReasoningClient>>,
-        mcp_executor: Arc<MCPToolExecutor>,
-        config: GraphConfig,
-        persistence: Option<Arc<PersistenceConfig>>,
-        #[cfg(feature = "observability")]
-        observer: Option<Arc<ObserverConfig>>,
+        deps: RunDeps,
         ctx: Option<PersistenceContext>,
     ) -> Result<()> {
+        let RunDeps {
+            llm_client,
+            reasoning_client,
+            mcp_executor,
+            config,
+            persistence,
+            tool_authorizer,
+            clock,
+            ids,
+            topology,
+            #[cfg(feature = "observability")]
+            observer,
+        } = deps;
         let start_time = Instant::now();
 
+        // Run-only tools and pinned tool sets get their own executor so
+        // concurrent runs don't see them
+        let mcp_executor = match (&input.pinned_tools, input.run_tools.is_empty()) {
+            (None, true) => mcp_executor,
+            (None, false) => Arc::new(mcp_executor.scoped(input.run_tools.clone()).await),
+            (Some(pinned), _) => Arc::new(
+                mcp_executor
+                    .scoped(input.run_tools.clone())
+                    .await
+                    .with_pinned_tools(pinned.clone()),
+            ),
+        };
+
         // Build initial state
-        let mut state = GraphState::from_input(input);
+        let (tenant_id, roles) = (input.tenant_id.clone(), input.roles.clone());
+        let mut state = GraphState::from_input_with(input, clock, ids);
+        if let Some(prompt) = &topology.system_prompt {
+            if !matches!(state.messages.first(), Some(praxis_llm::Message::System { .. })) {
+                state.messages.insert(0, praxis_llm::Message::system(prompt.clone()));
+            }
+        }
 
         // Initialize tracing if observer is configured
         #[cfg(feature = "observability")]
-        if let Some(ref obs) = observer {
+        let trace_started = observer.as_ref().map(|obs| {
             let obs_clone = Arc::clone(&obs.observer);
             let run_id = state.run_id.clone();
             let conversation_id = state.conversation_id.clone();
@@ -140,35 +202,127 @@ impl Graph {
                 if let Err(e) = obs_clone.trace_start(run_id, conversation_id).await {
                     tracing::error!("Failed to start trace: {}", e);
                 }
-            });
+            })
+        });
+
+        // Create nodes
+        let mut llm_node = LLMNode::new(llm_client.clone(), mcp_executor.clone())
+            .with_agent(config.agent_name.clone())
+            .with_rate_limit_retries(config.rate_limit_retries)
+            .with_context_overflow_truncation(config.truncate_on_context_overflow)
+            .with_deadline(start_time + config.execution_timeout)
+            .with_cancellation(config.enable_cancellation);
+        
+        if let Some(reasoning_client) = reasoning_client.clone() {
+            llm_node = llm_node.with_reasoning_client(reasoning_client);
+        }
+        if let Some(context) = &ctx {
+            llm_node = llm_node.with_end_user_id(config.end_user_id(&context.user_id));
+        }
+        if let Some(model) = &config.reasoning_summary_model {
+            llm_node = llm_node.with_reasoning_summary_model(model.clone());
+        }
+        if let Some(heartbeat) = &config.thinking_heartbeat {
+            llm_node = llm_node.with_thinking_heartbeat(heartbeat.clone());
+        }
+        if let Some(isolation) = &config.tool_content_isolation {
+            llm_node = llm_node.with_tool_content_isolation(isolation.clone());
+        }
+        if let Some(choice) = &config.tool_choice {
+            llm_node = llm_node.with_tool_choice(choice.clone());
         }
+        if let Some(parallel) = config.parallel_tool_calls {
+            llm_node = llm_node.with_parallel_tool_calls(parallel);
+        }
+        if config.cite_sources {
+            llm_node = llm_node.with_citations();
+        }
+        if let Some(policy) = &topology.context_policy {
+            llm_node = llm_node.with_context_policy(policy.clone());
+        }
+        if let Some(calculator) = &topology.cost_calculator {
+            llm_node = llm_node.with_cost_calculator(Arc::clone(calculator));
+        }
+        if let Some(registry) = &topology.capabilities {
+            llm_node = llm_node.with_capabilities(Arc::clone(registry));
+        }
+        let templates = config.activity.as_ref().map(|activity| {
+            Arc::new(TemplateDescriber::new(activity.templates.clone())) as Arc<dyn ToolCallDescriber>
+        });
+        if let Some(describer) = topology.tool_call_describer.clone().or(templates) {
+            let persist = config.activity.as_ref().is_some_and(|activity| activity.persist);
+            llm_node = llm_node.with_tool_call_describer(describer, persist);
+        }
+        llm_node = llm_node.with_tool_prefetch();
 
         // Emit init event
+        let option_adjustments = llm_node.option_adjustments(&state).await.unwrap_or_else(|e| {
+            tracing::warn!("Could not resolve option adjustments: {}", e);
+            Vec::new()
+        });
         let init_event = StreamEvent::InitStream {
             run_id: state.run_id.clone(),
             conversation_id: state.conversation_id.clone(),
-            timestamp: chrono::Utc::now().timestamp_millis(),
+            timestamp: state.clock.now().timestamp_millis(),
+            llm_config: Some(state.llm_config.clone()),
+            variables: state.variables.clone(),
+            option_adjustments,
         };
         event_tx.send(init_event.clone()).await?;
 
-        // Create nodes
-        let mut llm_node = LLMNode::new(llm_client.clone(), mcp_executor.clone());
-        
-        if let Some(reasoning_client) = reasoning_client.clone() {
-            llm_node = llm_node.with_reasoning_client(reasoning_client);
+        // Record the user message once the trace exists
+        #[cfg(feature = "observability")]
+        if let (Some(obs), Some(trace_started)) = (&observer, trace_started) {
+            let user_input = state.messages.iter().rev().find_map(|message| match message {
+                praxis_llm::Message::Human { content, .. } => Some(content.as_text().unwrap_or("").to_string()),
+                _ => None,
+            });
+            if let Some(content) = user_input {
+                let obs_clone = Arc::clone(&obs.observer);
+                let run_id = state.run_id.clone();
+                let conversation_id = state.conversation_id.clone();
+                let mut metadata = state.variables.clone();
+                if let Some(context) = &ctx {
+                    metadata.insert("thread_id".to_string(), serde_json::json!(context.thread_id));
+                }
+                tokio::spawn(async move {
+                    let _ = trace_started.await;
+                    if let Err(e) = obs_clone.trace_user_input(run_id, conversation_id, content, metadata).await {
+                        tracing::error!("Failed to trace user input: {}", e);
+                    }
+                });
+            }
+        }
+        let mut tool_node = ToolNode::new(mcp_executor).with_agent(config.agent_name.clone());
+        if let Some(authorizer) = tool_authorizer {
+            let run_context = RunContext {
+                run_id: state.run_id.clone(),
+                conversation_id: state.conversation_id.clone(),
+                user_id: ctx.as_ref().map(|context| context.user_id.clone()),
+                tenant_id,
+                roles,
+            };
+            tool_node = tool_node.with_authorizer(authorizer, run_context);
         }
-        let tool_node = ToolNode::new(mcp_executor);
-        let router = SimpleRouter;
+        if let Some(moderator) = &topology.moderator {
+            if let Some(input) = moderation::latest_input(&state.messages) {
+                moderator.check(ModerationStage::Input, &input, &event_tx).await?;
+            }
+        }
+        let router = Arc::clone(&topology.router);
+        let CustomNodes { before_llm, after_llm, after_tool } = &topology.custom_nodes;
 
         let mut current_node = NodeType::LLM;
-        let mut iteration = 0;
+        let mut iteration: u32 = 0;
 
         loop {
             // Guardrail: max iterations
-            if iteration >= config.max_iterations {
+            if iteration as usize >= config.max_iterations {
                 let error_event = StreamEvent::Error {
                     message: format!("Max iterations ({}) reached", config.max_iterations),
                     node_id: None,
+                    kind: GraphErrorKind::MaxIterations,
+                    error_code: None,
                 };
                 event_tx.send(error_event.clone()).await?;
                 break;
@@ -179,14 +333,36 @@ impl Graph {
             // Store state snapshot before execution for observation
             let messages_before = state.messages.len();
 
-            // Execute current node (this emits events via event_tx)
-            match current_node {
+            // Execute current node (this emits events via event_tx, stamped
+            // with the node and iteration on the way out)
+            let provenance = Provenance { node: current_node, iteration };
+            let (node_tx, forwarder) = Self::stamped_sender(&event_tx, provenance);
+            let executed = match current_node {
                 NodeType::LLM => {
-                    llm_node.execute(&mut state, event_tx.clone()).await?;
+                    state.tools_enabled = router.allow_tools(&state);
+                    // Files the node's LLM calls under this run when recording
+                    let run_id = state.run_id.clone();
+                    praxis_llm::recording::with_run_id(
+                        run_id,
+                        Self::execute_stage(&mut state, &node_tx, before_llm, &llm_node, after_llm),
+                    )
+                    .await
                 }
                 NodeType::Tool => {
-                    tool_node.execute(&mut state, event_tx.clone()).await?;
+                    let executed = Self::execute_stage(&mut state, &node_tx, &[], &tool_node, after_tool).await;
+                    state.tool_rounds += 1;
+                    executed
                 }
+            };
+            drop(node_tx);
+            // Everything the node sent is forwarded before anything that follows
+            let _ = forwarder.await;
+            executed?;
+
+            // Before the answer is saved, so a blocked one never is
+            if let (NodeType::LLM, Some(moderator)) = (current_node, &topology.moderator) {
+                let answer = moderation::answer_text(state.messages.get(messages_before..).unwrap_or_default());
+                moderator.check(ModerationStage::Output, &answer, &event_tx).await?;
             }
 
             let node_duration = node_start.elapsed().as_millis() as u64;
@@ -195,15 +371,26 @@ impl Graph {
             Self::handle_post_node_execution(
                 &state,
                 current_node,
+                iteration,
                 node_start,
                 node_duration,
                 messages_before,
+                &config.agent_name,
                 &persistence,
                 #[cfg(feature = "observability")]
                 &observer,
                 &ctx,
             ).await;
 
+            // Tool results the model can no longer be sent are saved by now;
+            // keep only a reference to them for the rest of the run
+            if let Some(policy) = &topology.context_policy {
+                let compacted = policy.compact(&mut state.messages);
+                if compacted > 0 {
+                    tracing::debug!(run_id = %state.run_id, compacted, "Compacted tool results outside the context window");
+                }
+            }
+
             // Route to next node
             let next = router.next(&state, current_node);
 
@@ -221,6 +408,8 @@ impl Graph {
         let end_event = StreamEvent::EndStream {
             status: "success".to_string(),
             total_duration_ms: total_duration,
+            finish_reason: state.last_finish_reason.clone(),
+            cost_usd: state.cost.map(|cost| cost.total_usd),
         };
         event_tx.send(end_event.clone()).await?;
         
@@ -239,14 +428,61 @@ impl Graph {
         Ok(())
     }
 
+    /// Run a built-in node with the custom nodes placed around it
+    async fn execute_stage(
+        state: &mut GraphState,
+        node_tx: &EventSender,
+        before: &[Arc<dyn Node>],
+        node: &dyn Node,
+        after: &[Arc<dyn Node>],
+    ) -> Result<()> {
+        for custom in before {
+            custom.execute(state, node_tx.clone()).await?;
+        }
+        node.execute(state, node_tx.clone()).await?;
+        for custom in after {
+            custom.execute(state, node_tx.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// A sender for one node execution that stamps content events with
+    /// `provenance` and forwards them to `event_tx`
+    ///
+    /// The returned task ends once the node drops its sender. It closes the
+    /// node's channel when `event_tx` closes, so cancellation still reaches
+    /// nodes that wait on `EventSender::closed`.
+    fn stamped_sender(event_tx: &EventSender, provenance: Provenance) -> (EventSender, JoinHandle<()>) {
+        let (node_tx, mut node_rx) = mpsc::channel::<StreamEvent>(event_tx.max_capacity());
+        let event_tx = event_tx.clone();
+        let forwarder = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = node_rx.recv() => match event {
+                        Some(event) => {
+                            if event_tx.send(event.with_provenance(provenance)).await.is_err() {
+                                return;
+                            }
+                        }
+                        None => return,
+                    },
+                    _ = event_tx.closed() => return,
+                }
+            }
+        });
+        (node_tx, forwarder)
+    }
+
     /// Handle post-node execution: persistence and observability
     async fn handle_post_node_execution(
         state: &GraphState,
         node_type: NodeType,
+        iteration: u32,
         node_start: Instant,
         #[allow(unused_variables)]
         node_duration: u64,
         messages_before: usize,
+        agent: &str,
         persistence: &Option<Arc<PersistenceConfig>>,
         #[cfg(feature = "observability")]
         observer: &Option<Arc<ObserverConfig>>,
@@ -267,18 +503,16 @@ impl Graph {
                 if let Some(outputs) = &state.last_outputs {
                     for output in outputs {
                         let db_message = Self::convert_output_to_db(
+                            state,
                             output,
                             &context.thread_id,
                             &context.user_id,
+                            agent,
+                            iteration,
                         );
                         
                         if let Some(db_msg) = db_message {
-                            let client = Arc::clone(&persist.client);
-                            tokio::spawn(async move {
-                                if let Err(e) = client.save_message(db_msg).await {
-                                    tracing::error!("Failed to save output to database: {}", e);
-                                }
-                            });
+                            Self::spawn_save(persist, db_msg, &state.llm_config.model);
                         }
                     }
                 }
@@ -286,19 +520,17 @@ impl Graph {
                 // Fallback: Save messages directly (for Tool nodes or old LLM nodes)
                 for msg in new_messages {
                     let db_message = Self::convert_message_to_db(
+                        state,
                         msg,
                         &context.thread_id,
                         &context.user_id,
+                        agent,
                         node_type,
+                        iteration,
                     );
                     
                     if let Some(db_msg) = db_message {
-                        let client = Arc::clone(&persist.client);
-                        tokio::spawn(async move {
-                            if let Err(e) = client.save_message(db_msg).await {
-                                tracing::error!("Failed to save message: {}", e);
-                            }
-                        });
+                        Self::spawn_save(persist, db_msg, &state.llm_config.model);
                     }
                 }
             }
@@ -315,7 +547,11 @@ impl Graph {
                 new_messages,
             );
 
-            if let Some(obs_data) = observation {
+            if let Some(mut obs_data) = observation {
+                // Traces are keyed by conversation_id; keep the stored thread too
+                if let Some(context) = ctx {
+                    obs_data.metadata.insert("thread_id".to_string(), serde_json::json!(context.thread_id));
+                }
                 let obs_clone = Arc::clone(&obs.observer);
                 tokio::spawn(async move {
                     let result = match obs_data.node_type.as_str() {
@@ -332,19 +568,39 @@ impl Graph {
         }
     }
 
+    /// Save a message in the background, enforcing the persistence policy
+    ///
+    /// Hash and token count are taken after the policy truncates content.
+    fn spawn_save(persist: &PersistenceConfig, db_msg: praxis_persist::DBMessage, model: &str) {
+        let Some(mut db_msg) = persist.policy.apply(db_msg) else {
+            return;
+        };
+        db_msg.fingerprint(persist.tokenizer.as_deref(), model);
+
+        let client = Arc::clone(&persist.client);
+        tokio::spawn(async move {
+            if let Err(e) = client.save_message(db_msg).await {
+                tracing::error!("Failed to save message: {}", e);
+            }
+        });
+    }
+
     /// Convert GraphOutput to DBMessage
     fn convert_output_to_db(
+        state: &GraphState,
         output: &crate::types::GraphOutput,
         thread_id: &str,
         user_id: &str,
+        agent: &str,
+        iteration: u32,
     ) -> Option<praxis_persist::DBMessage> {
         use crate::types::GraphOutput;
         use praxis_persist::{MessageRole, MessageType};
 
         match output {
-            GraphOutput::Reasoning { id, content } => {
+            GraphOutput::Reasoning { id, content, summary } => {
                 Some(praxis_persist::DBMessage {
-                    id: uuid::Uuid::new_v4().to_string(),
+                    id: state.ids.next_id(),
                     thread_id: thread_id.to_string(),
                     user_id: user_id.to_string(),
                     role: MessageRole::Assistant,
@@ -352,18 +608,28 @@ impl Graph {
                     content: content.clone(),
                     tool_call_id: None,
                     tool_name: None,
+                    tool_server: None,
+                    verification: None,
                     arguments: None,
                     reasoning_id: Some(id.clone()),
-                    created_at: chrono::Utc::now(),
+                    created_at: state.clock.now(),
                     duration_ms: None,
+                    agent: Some(agent.to_string()),
+                    content_encoding: praxis_persist::ContentEncoding::None,
+                    content_sha256: None,
+                    token_count: None,
+                    summary: summary.clone(),
+                    iteration: Some(iteration),
+                    usage: None,
+                    citations: Vec::new(),
                 })
             }
-            GraphOutput::Message { id, content, tool_calls } => {
+            GraphOutput::Message { id, content, tool_calls, citations } => {
                 if let Some(calls) = tool_calls {
                     // Save first tool call (expand to handle all in production)
                     if let Some(first_call) = calls.first() {
                         Some(praxis_persist::DBMessage {
-                            id: uuid::Uuid::new_v4().to_string(),
+                            id: state.ids.next_id(),
                             thread_id: thread_id.to_string(),
                             user_id: user_id.to_string(),
                             role: MessageRole::Assistant,
@@ -371,17 +637,27 @@ impl Graph {
                             content: String::new(),
                             tool_call_id: Some(first_call.id.clone()),
                             tool_name: Some(first_call.function.name.clone()),
+                            tool_server: None,
+                            verification: None,
                             arguments: serde_json::from_str(&first_call.function.arguments).ok(),
                             reasoning_id: Some(id.clone()),
-                            created_at: chrono::Utc::now(),
+                            created_at: state.clock.now(),
                             duration_ms: None,
+                        agent: Some(agent.to_string()),
+                        content_encoding: praxis_persist::ContentEncoding::None,
+                        content_sha256: None,
+                        token_count: None,
+                        summary: state.tool_activity.get(&first_call.id).cloned(),
+                        iteration: Some(iteration),
+                        usage: state.last_usage.clone(),
+                        citations: Vec::new(),
                         })
                     } else {
                         None
                     }
                 } else if !content.is_empty() {
                     Some(praxis_persist::DBMessage {
-                        id: uuid::Uuid::new_v4().to_string(),
+                        id: state.ids.next_id(),
                         thread_id: thread_id.to_string(),
                         user_id: user_id.to_string(),
                         role: MessageRole::Assistant,
@@ -389,10 +665,20 @@ impl Graph {
                         content: content.clone(),
                         tool_call_id: None,
                         tool_name: None,
+                        tool_server: None,
+                        verification: None,
                         arguments: None,
                         reasoning_id: Some(id.clone()),
-                        created_at: chrono::Utc::now(),
+                        created_at: state.clock.now(),
                         duration_ms: None,
+                        agent: Some(agent.to_string()),
+                        content_encoding: praxis_persist::ContentEncoding::None,
+                        content_sha256: None,
+                        token_count: None,
+                        summary: None,
+                        iteration: Some(iteration),
+                        usage: state.last_usage.clone(),
+                        citations: citations.clone(),
                     })
                 } else {
                     None
@@ -403,10 +689,13 @@ impl Graph {
     
     /// Convert praxis-llm Message to praxis-persist DBMessage
     fn convert_message_to_db(
+        state: &GraphState,
         msg: &praxis_llm::Message,
         thread_id: &str,
         user_id: &str,
+        agent: &str,
         _node_type: NodeType,
+        iteration: u32,
     ) -> Option<praxis_persist::DBMessage> {
         use praxis_llm::Message;
         use praxis_persist::{MessageRole, MessageType};
@@ -419,7 +708,7 @@ impl Graph {
                     // In production, you might want to handle all tool calls
                     if let Some(first_call) = calls.first() {
                         Some(praxis_persist::DBMessage {
-                            id: uuid::Uuid::new_v4().to_string(),
+                            id: state.ids.next_id(),
                             thread_id: thread_id.to_string(),
                             user_id: user_id.to_string(),
                             role: MessageRole::Assistant,
@@ -427,17 +716,27 @@ impl Graph {
                             content: String::new(),
                             tool_call_id: Some(first_call.id.clone()),
                             tool_name: Some(first_call.function.name.clone()),
+                            tool_server: None,
+                            verification: None,
                             arguments: serde_json::from_str(&first_call.function.arguments).ok(),
                             reasoning_id: None,
-                            created_at: chrono::Utc::now(),
+                            created_at: state.clock.now(),
                             duration_ms: None,
+                        agent: Some(agent.to_string()),
+                        content_encoding: praxis_persist::ContentEncoding::None,
+                        content_sha256: None,
+                        token_count: None,
+                        summary: state.tool_activity.get(&first_call.id).cloned(),
+                        iteration: Some(iteration),
+                        usage: state.last_usage.clone(),
+                        citations: Vec::new(),
                         })
                     } else {
                         None
                     }
                 } else if let Some(content) = content {
                     Some(praxis_persist::DBMessage {
-                        id: uuid::Uuid::new_v4().to_string(),
+                        id: state.ids.next_id(),
                         thread_id: thread_id.to_string(),
                         user_id: user_id.to_string(),
                         role: MessageRole::Assistant,
@@ -445,10 +744,20 @@ impl Graph {
                         content: content.as_text().unwrap_or("").to_string(),
                         tool_call_id: None,
                         tool_name: None,
+                        tool_server: None,
+                        verification: None,
                         arguments: None,
                         reasoning_id: None,
-                        created_at: chrono::Utc::now(),
+                        created_at: state.clock.now(),
                         duration_ms: None,
+                        agent: Some(agent.to_string()),
+                        content_encoding: praxis_persist::ContentEncoding::None,
+                        content_sha256: None,
+                        token_count: None,
+                        summary: None,
+                        iteration: Some(iteration),
+                        usage: state.last_usage.clone(),
+                        citations: Vec::new(),
                     })
                 } else {
                     None
@@ -456,7 +765,7 @@ impl Graph {
             }
             Message::Tool { tool_call_id, content } => {
                 Some(praxis_persist::DBMessage {
-                    id: uuid::Uuid::new_v4().to_string(),
+                    id: state.ids.next_id(),
                     thread_id: thread_id.to_string(),
                     user_id: user_id.to_string(),
                     role: MessageRole::Assistant,
@@ -464,10 +773,20 @@ impl Graph {
                     content: content.as_text().unwrap_or("").to_string(),
                     tool_call_id: Some(tool_call_id.clone()),
                     tool_name: None,
+                    tool_server: state.tool_servers.get(tool_call_id).cloned(),
+                    verification: state.tool_verifications.get(tool_call_id).cloned(),
                     arguments: None,
                     reasoning_id: None,
-                    created_at: chrono::Utc::now(),
+                    created_at: state.clock.now(),
                     duration_ms: None,
+                    agent: Some(agent.to_string()),
+                    content_encoding: praxis_persist::ContentEncoding::None,
+                    content_sha256: None,
+                    token_count: None,
+                    summary: None,
+                    iteration: Some(iteration),
+                    usage: None,
+                    citations: Vec::new(),
                 })
             }
             _ => None,
@@ -486,8 +805,8 @@ impl Graph {
         use praxis_observability::{NodeObservation, NodeObservationData, NodeOutput, LangfuseMessage, ToolCallInfo, ToolResultInfo};
         use crate::types::GraphOutput;
 
-        let span_id = uuid::Uuid::new_v4().to_string();
-        let started_at = chrono::Utc::now() - chrono::Duration::milliseconds(node_duration as i64);
+        let span_id = state.ids.next_id();
+        let started_at = state.clock.now() - chrono::Duration::milliseconds(node_duration as i64);
 
         match node_type {
             NodeType::LLM => {
@@ -509,13 +828,13 @@ impl Graph {
                 let outputs = if let Some(ref last_outputs) = state.last_outputs {
                     last_outputs.iter().map(|output| {
                         match output {
-                            GraphOutput::Reasoning { id, content } => {
+                            GraphOutput::Reasoning { id, content, .. } => {
                                 NodeOutput::Reasoning {
                                     id: id.clone(),
                                     content: content.clone(),
                                 }
                             }
-                            GraphOutput::Message { id, content, tool_calls } => {
+                            GraphOutput::Message { id, content, tool_calls, .. } => {
                                 if tool_calls.is_some() {
                                     NodeOutput::ToolCalls {
                                         calls: tool_calls.as_ref().unwrap().iter().map(|call| {
@@ -563,9 +882,16 @@ impl Graph {
                         input_messages,
                         outputs,
                         model: state.llm_config.model.clone(),
-                        usage: None,
+                        usage: state.last_usage.clone(),
+                        cost: state.last_cost,
                     },
-                    metadata: std::collections::HashMap::new(),
+                    metadata: state
+                        .last_response_metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.request_id.clone())
+                        .map(|request_id| ("request_id".to_string(), serde_json::json!(request_id)))
+                        .into_iter()
+                        .collect(),
                 })
             }
             NodeType::Tool => {
@@ -596,6 +922,7 @@ impl Graph {
                                 result: content.as_text().unwrap_or("").to_string(),
                                 is_error: false,
                                 duration_ms: 0, // TODO: track individual tool duration
+                                tool_server: state.tool_servers.get(tool_call_id).cloned(),
                             })
                         }
                         _ => None,
@@ -608,6 +935,16 @@ impl Graph {
                     tool_results.len()
                 );
 
+                // Which server ran each call, for auditing third-party tools
+                let tool_servers: serde_json::Map<String, serde_json::Value> = tool_results
+                    .iter()
+                    .filter_map(|result| Some((result.tool_call_id.clone(), result.tool_server.clone()?.into())))
+                    .collect();
+                let mut metadata = std::collections::HashMap::new();
+                if !tool_servers.is_empty() {
+                    metadata.insert("tool_servers".to_string(), serde_json::Value::Object(tool_servers));
+                }
+
                 Some(NodeObservation {
                     span_id,
                     run_id: state.run_id.clone(),
@@ -619,7 +956,7 @@ impl Graph {
                         tool_calls,
                         tool_results,
                     },
-                    metadata: std::collections::HashMap::new(),
+                    metadata,
                 })
             }
         }
@@ -631,8 +968,8 @@ impl Graph {
         use praxis_observability::{LangfuseMessage, ToolCallInfo};
 
         match msg {
-            praxis_llm::Message::System { content, .. } => Some(LangfuseMessage {
-                role: "system".to_string(),
+            praxis_llm::Message::System { content, .. } | praxis_llm::Message::Developer { content, .. } => Some(LangfuseMessage {
+                role: msg.role().to_string(),
                 content: content.as_text().unwrap_or("").to_string(),
                 name: None,
                 tool_call_id: None,
@@ -1,20 +1,67 @@
+#[cfg(feature = "runtime")]
 pub mod types;
+#[cfg(feature = "runtime")]
 pub mod node;
+#[cfg(feature = "runtime")]
 pub mod router;
+#[cfg(feature = "runtime")]
 pub mod nodes;
+#[cfg(feature = "runtime")]
 pub mod graph;
+#[cfg(feature = "runtime")]
 pub mod builder;
+#[cfg(feature = "runtime")]
 pub mod client_factory;
+#[cfg(feature = "runtime")]
 pub mod streaming;
+#[cfg(feature = "runtime")]
+pub mod authorization;
+#[cfg(feature = "runtime")]
+pub mod spec;
+#[cfg(feature = "runtime")]
+pub mod activity;
+#[cfg(feature = "runtime")]
+pub mod moderation;
+#[cfg(feature = "runtime")]
+mod smoothing;
+#[cfg(feature = "runtime")]
+mod batching;
+#[cfg(feature = "runtime")]
+mod isolation;
+#[cfg(feature = "runtime")]
+mod citations;
+#[cfg(feature = "wire")]
+pub mod wire;
 
+#[cfg(feature = "runtime")]
 pub use node::{Node, NodeType, EventSender};
-pub use router::{Router, NextNode, SimpleRouter};
+#[cfg(feature = "runtime")]
+pub use router::{Router, NextNode, SimpleRouter, BudgetRouter};
+#[cfg(feature = "runtime")]
+pub use spec::{GraphSpec, NodeRegistry};
+#[cfg(feature = "runtime")]
+pub use activity::{TemplateDescriber, ToolCallDescriber};
+#[cfg(feature = "runtime")]
+pub use moderation::{ModerationPolicy, ModerationStage};
+#[cfg(feature = "runtime")]
 pub use graph::{Graph, PersistenceContext};
+#[cfg(feature = "runtime")]
 pub use builder::{GraphBuilder, PersistenceConfig};
-pub use client_factory::ClientFactory;
+#[cfg(feature = "runtime")]
+pub use client_factory::{ClientFactory, ProviderConfig};
+#[cfg(feature = "runtime")]
 pub use streaming::{StreamAdapter, OpenAIStreamAdapter};
+#[cfg(feature = "runtime")]
+pub use authorization::{
+    AuthorizationRule, Decision, RuleEffect, RulesAuthorizer, RunContext, ToolAuthorizer,
+};
 
+#[cfg(feature = "runtime")]
 pub use types::{
-    GraphState, GraphInput, GraphConfig, LLMConfig, ContextPolicy, StreamEvent, Provider, GraphOutput,
+    GraphState, GraphInput, GraphConfig, LLMConfig, ContextPolicy, StreamEvent, Provenance, Provider, GraphOutput,
+    SmoothingConfig, StreamingConfig, BatchPolicy, SuspiciousContentAction, ThinkingHeartbeatConfig, ToolContentIsolationConfig,
+    ActivityConfig, GraphError, GraphErrorKind,
 };
+#[cfg(feature = "runtime")]
+pub use praxis_persist::Citation;
 
@@ -0,0 +1,118 @@
+//! Moderation of run input and model answers
+//!
+//! Given a `ModerationClient`, the graph screens the latest human message
+//! before the run's first LLM call and each call's answer once it has
+//! streamed. The `ModerationPolicy` decides what a flag does: fail the run,
+//! or tell the caller and carry on.
+
+use crate::node::EventSender;
+use crate::types::{GraphError, GraphErrorKind, StreamEvent};
+use anyhow::{Context, Result};
+use praxis_llm::{Content, ContentPart, Message, ModerationClient};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// What the graph does with flagged text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationPolicy {
+    /// End the run with a `moderation_blocked` error; a flagged answer is
+    /// not saved
+    #[default]
+    Block,
+    /// Send a `ModerationWarning` and go on with the run
+    Annotate,
+}
+
+/// Which side of an LLM call was moderated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStage {
+    /// The latest human message, before the first LLM call
+    Input,
+    /// An LLM call's answer
+    Output,
+}
+
+impl fmt::Display for ModerationStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Input => "input",
+            Self::Output => "answer",
+        })
+    }
+}
+
+/// A moderation client with the policy to apply to its flags
+#[derive(Clone)]
+pub(crate) struct Moderator {
+    client: Arc<dyn ModerationClient>,
+    policy: ModerationPolicy,
+}
+
+impl Moderator {
+    pub fn new(client: Arc<dyn ModerationClient>, policy: ModerationPolicy) -> Self {
+        Self { client, policy }
+    }
+
+    /// Moderate `text`, failing with a `ModerationBlocked` error or sending a
+    /// warning if it is flagged
+    ///
+    /// A failed moderation call fails the run, so unscreened text never goes
+    /// through.
+    pub async fn check(&self, stage: ModerationStage, text: &str, event_tx: &EventSender) -> Result<()> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        let moderation = self.client.moderate(text).await.context("Moderation check failed")?;
+        if !moderation.flagged {
+            return Ok(());
+        }
+
+        let categories = moderation.categories;
+        tracing::warn!(?stage, ?categories, policy = ?self.policy, "Moderation flagged the {}", stage);
+        match self.policy {
+            ModerationPolicy::Block => {
+                let message = if categories.is_empty() {
+                    format!("The {} was flagged by moderation", stage)
+                } else {
+                    format!("The {} was flagged by moderation: {}", stage, categories.join(", "))
+                };
+                Err(GraphError::new(GraphErrorKind::ModerationBlocked, message).into())
+            }
+            ModerationPolicy::Annotate => {
+                event_tx.send(StreamEvent::ModerationWarning { stage, categories }).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Text of the latest human message
+pub(crate) fn latest_input(messages: &[Message]) -> Option<String> {
+    messages.iter().rev().find_map(|message| match message {
+        Message::Human { content, .. } => Some(text_of(content)),
+        _ => None,
+    })
+}
+
+/// Text of the answers among `messages`
+pub(crate) fn answer_text(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::AI { content: Some(content), .. } => Some(text_of(content)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// All text parts of `content`, images left out
+fn text_of(content: &Content) -> String {
+    match content {
+        Content::Text(text) => text.clone(),
+        Content::Parts(parts) => parts.iter().filter_map(ContentPart::as_text).collect::<Vec<_>>().join("\n"),
+    }
+}
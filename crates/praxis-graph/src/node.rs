@@ -1,5 +1,6 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use crate::types::{GraphState, StreamEvent};
 use tokio::sync::mpsc;
 
@@ -15,7 +16,8 @@ pub trait Node: Send + Sync {
     fn node_type(&self) -> NodeType;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NodeType {
     LLM,
     Tool,
@@ -1,20 +1,69 @@
 use crate::node::{EventSender, Node, NodeType};
-use crate::types::GraphOutput;
+use crate::activity::{activity_text, ToolCallDescriber};
+use crate::citations;
+use crate::isolation::ToolContentIsolation;
+use crate::types::{ContextPolicy, GraphError, GraphErrorKind, GraphOutput, ThinkingHeartbeatConfig, ToolContentIsolationConfig};
 use anyhow::Result;
 use async_trait::async_trait;
 use futures::StreamExt;
-use praxis_llm::{ChatClient, ReasoningClient, ChatOptions, ChatRequest, ResponseRequest, ReasoningConfig, Message, ToolChoice};
+use praxis_llm::{ChatClient, CostBreakdown, CostCalculator, LLMError, RateLimited, ReasoningClient, ChatOptions, ChatRequest, CapabilityRegistry, OptionAdjusted, ResponseOptions, ResponseRequest, ReasoningConfig, ReasoningEffort, ReasoningKind, ResponseMetadata, SummaryMode, Message, TokenUsage, Tool, ToolChoice};
+use praxis_core::IdGen;
 use praxis_mcp::MCPToolExecutor;
 use crate::types::GraphState;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+type LLMStream = Pin<Box<dyn futures::Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>;
 
 pub struct LLMNode {
     client: Arc<dyn ChatClient>,
     reasoning_client: Option<Arc<dyn ReasoningClient>>,
     mcp_executor: Arc<MCPToolExecutor>,
+    agent: Option<String>,
+    end_user_id: Option<String>,
+    reasoning_summary_model: Option<String>,
+    rate_limit_retries: u32,
+    /// Retry a call once with older messages dropped when it overflows the context window
+    truncate_on_context_overflow: bool,
+    /// Rate-limit waits never run past this
+    deadline: Option<Instant>,
+    /// Stop waiting when the event receiver is dropped
+    cancellable: bool,
+    thinking_heartbeat: Option<ThinkingHeartbeatConfig>,
+    tool_content_isolation: Option<ToolContentIsolation>,
+    /// Trims the history sent to the provider
+    context_policy: Option<ContextPolicy>,
+    /// Emits an activity line per completed tool call
+    tool_call_describer: Option<Arc<dyn ToolCallDescriber>>,
+    /// Keep activity lines in `GraphState::tool_activity` for persistence
+    persist_activity: bool,
+    /// Tool choice for the run's first call (see `GraphConfig::tool_choice`)
+    tool_choice: Option<ToolChoice>,
+    /// Ask for `[n]` markers on tool facts and report them as citations
+    cite_sources: bool,
+    /// See `GraphConfig::parallel_tool_calls`
+    parallel_tool_calls: Option<bool>,
+    /// Prices each call's usage into `GraphState::cost`
+    cost_calculator: Option<Arc<CostCalculator>>,
+    /// Decides which models use the Reasoning API and what options they take
+    capabilities: Arc<CapabilityRegistry>,
+    /// Tool list for the first chat request, fetched while the run starts
+    tool_prefetch: tokio::sync::Mutex<Option<ToolPrefetch>>,
+}
+
+enum ToolPrefetch {
+    Running(tokio::task::JoinHandle<Result<Vec<Tool>>>),
+    Ready(Vec<Tool>),
 }
 
+const REASONING_SUMMARY_PROMPT: &str = "Summarize the following reasoning in one or two short sentences, \
+written as what was considered. Reply with the summary only.";
+
+/// Text kept (and persisted) for a spoken answer that came without a
+/// transcript; the audio itself is only streamed
+const AUDIO_PLACEHOLDER: &str = "[audio]";
+
 impl LLMNode {
     pub fn new(client: Arc<dyn ChatClient>, mcp_executor: Arc<MCPToolExecutor>) -> Self {
         let reasoning_client = None; // We'll set this from client if it implements both traits
@@ -22,6 +71,24 @@ impl LLMNode {
             client,
             reasoning_client,
             mcp_executor,
+            agent: None,
+            end_user_id: None,
+            reasoning_summary_model: None,
+            rate_limit_retries: 0,
+            truncate_on_context_overflow: false,
+            deadline: None,
+            cancellable: true,
+            thinking_heartbeat: None,
+            tool_content_isolation: None,
+            context_policy: None,
+            tool_call_describer: None,
+            persist_activity: false,
+            tool_choice: None,
+            cite_sources: false,
+            parallel_tool_calls: None,
+            cost_calculator: None,
+            capabilities: Arc::new(CapabilityRegistry::new()),
+            tool_prefetch: tokio::sync::Mutex::new(None),
         }
     }
 
@@ -30,24 +97,276 @@ impl LLMNode {
         self
     }
 
+    /// Attribute emitted events to the given agent
+    pub fn with_agent(mut self, agent: impl Into<String>) -> Self {
+        self.agent = Some(agent.into());
+        self
+    }
+
+    /// Identify the end user to the provider (sent as the `user` field)
+    pub fn with_end_user_id(mut self, id: impl Into<String>) -> Self {
+        self.end_user_id = Some(id.into());
+        self
+    }
+
+    /// Price each call's token usage with `calculator`
+    pub fn with_cost_calculator(mut self, calculator: Arc<CostCalculator>) -> Self {
+        self.cost_calculator = Some(calculator);
+        self
+    }
+
+    /// Look models up in `registry` instead of the built-in table
+    pub fn with_capabilities(mut self, registry: Arc<CapabilityRegistry>) -> Self {
+        self.capabilities = registry;
+        self
+    }
+
+    /// Summarize reasoning that arrives without a provider summary using `model`
+    pub fn with_reasoning_summary_model(mut self, model: impl Into<String>) -> Self {
+        self.reasoning_summary_model = Some(model.into());
+        self
+    }
+
     /// Convert praxis_llm::StreamEvent to Graph StreamEvent
-    /// Uses automatic From trait conversion
-    fn convert_event(event: praxis_llm::StreamEvent) -> crate::types::StreamEvent {
-        event.into()
+    /// Uses automatic From trait conversion, then attributes the agent
+    /// Retry a rate-limited call up to `retries` times, waiting as the provider asks
+    pub fn with_rate_limit_retries(mut self, retries: u32) -> Self {
+        self.rate_limit_retries = retries;
+        self
+    }
+
+    /// Retry a call the provider rejects as too long for the context window
+    /// once, with the oldest messages after the system prompt dropped
+    pub fn with_context_overflow_truncation(mut self, enabled: bool) -> Self {
+        self.truncate_on_context_overflow = enabled;
+        self
+    }
+
+    /// Fail instead of waiting out a rate limit past `deadline`
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Whether a dropped event receiver cuts a rate-limit wait short
+    pub fn with_cancellation(mut self, enabled: bool) -> Self {
+        self.cancellable = enabled;
+        self
+    }
+
+    /// Send `StreamEvent::Thinking` while the model hasn't started answering
+    pub fn with_thinking_heartbeat(mut self, heartbeat: ThinkingHeartbeatConfig) -> Self {
+        self.thinking_heartbeat = Some(heartbeat);
+        self
     }
 
-    /// Check if model should use Reasoning API
-    fn is_reasoning_model(model: &str) -> bool {
-        model.starts_with("gpt-5") || model.starts_with("o")
+    /// Ask for `choice` on the run's first call instead of auto
+    pub fn with_tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.tool_choice = Some(choice);
+        self
+    }
+
+    /// Allow or forbid several tool calls in one model turn
+    pub fn with_parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = Some(enabled);
+        self
+    }
+
+    /// Wrap tool results sent to the provider; `state.messages` stays raw
+    pub fn with_tool_content_isolation(mut self, config: ToolContentIsolationConfig) -> Self {
+        self.tool_content_isolation = Some(ToolContentIsolation::new(config));
+        self
+    }
+
+    /// Send only the part of the history `policy` keeps
+    pub fn with_context_policy(mut self, policy: ContextPolicy) -> Self {
+        self.context_policy = Some(policy);
+        self
+    }
+
+    /// Send `StreamEvent::Activity` for each tool call, described by
+    /// `describer`; with `persist` the lines are also saved on the tool calls
+    pub fn with_tool_call_describer(mut self, describer: Arc<dyn ToolCallDescriber>, persist: bool) -> Self {
+        self.tool_call_describer = Some(describer);
+        self.persist_activity = persist;
+        self
+    }
+
+    /// Ask the model to cite tool results and send `StreamEvent::Citations`
+    /// for the markers in its final answer
+    pub fn with_citations(mut self) -> Self {
+        self.cite_sources = true;
+        self
+    }
+
+    /// Start listing the tools for the first chat request in the background
+    ///
+    /// Option adjustments and the first request share the result instead of
+    /// listing the MCP servers twice; later requests list them afresh.
+    pub fn with_tool_prefetch(self) -> Self {
+        let executor = Arc::clone(&self.mcp_executor);
+        let fetch = tokio::spawn(async move { executor.get_llm_tools().await });
+        Self { tool_prefetch: tokio::sync::Mutex::new(Some(ToolPrefetch::Running(fetch))), ..self }
+    }
+
+    /// Tools to offer the model; `keep` leaves a prefetched list for the next caller
+    async fn llm_tools(&self, keep: bool) -> Result<Vec<Tool>> {
+        let mut prefetch = self.tool_prefetch.lock().await;
+        let tools = match prefetch.take() {
+            Some(ToolPrefetch::Running(fetch)) => fetch.await?,
+            Some(ToolPrefetch::Ready(tools)) => Ok(tools),
+            None => return self.mcp_executor.get_llm_tools().await,
+        };
+        if let (true, Ok(tools)) = (keep, &tools) {
+            *prefetch = Some(ToolPrefetch::Ready(tools.clone()));
+        }
+        tools
+    }
+
+    /// History as sent to the provider
+    ///
+    /// Messages are borrowed from `state` until the final request is built,
+    /// so each kept message is copied once per request.
+    fn request_messages(&self, state: &GraphState) -> Vec<Message> {
+        let window = match &self.context_policy {
+            Some(policy) => policy.window(&state.messages),
+            None => state.messages.iter().collect(),
+        };
+        let mut messages: Vec<Message> = match &self.tool_content_isolation {
+            Some(isolation) => isolation.apply(&window),
+            None => window.into_iter().cloned().collect(),
+        };
+        if self.cite_sources {
+            let sources = citations::sources(&state.messages);
+            if !sources.is_empty() {
+                messages.push(Message::system(citations::instruction(&sources)));
+            }
+        }
+        messages
+    }
+
+    /// What a call to `model` that used `usage` cost, when calls are priced
+    fn price(&self, model: &str, usage: Option<&TokenUsage>) -> Option<CostBreakdown> {
+        self.cost_calculator.as_ref().zip(usage).and_then(|(calculator, usage)| calculator.cost(model, usage))
+    }
+
+    fn convert_event(&self, event: praxis_llm::StreamEvent) -> crate::types::StreamEvent {
+        let event: crate::types::StreamEvent = event.into();
+        match &self.agent {
+            Some(agent) => event.with_agent(agent.clone()),
+            None => event,
+        }
+    }
+
+    /// Whether the model goes through the Reasoning (Responses) API
+    pub(crate) fn use_reasoning_api(&self, model: &str) -> bool {
+        self.capabilities.get(model).reasoning && self.reasoning_client.is_some()
+    }
+    
+    /// Options the provider client will drop for this state's model
+    ///
+    /// Computed the same way as the requests themselves, so the run can
+    /// report them before the first LLM call.
+    pub async fn option_adjustments(&self, state: &GraphState) -> Result<Vec<OptionAdjusted>> {
+        let model = &state.llm_config.model;
+        let capabilities = self.capabilities.get(model);
+        if self.use_reasoning_api(model) {
+            let tools = self.llm_tools(true).await?;
+            let request = self.response_request(state, self.request_messages(state), tools);
+            let (_, _, adjusted) =
+                capabilities.adjust_response_options(model, request.reasoning.as_ref(), &request.options)?;
+            Ok(adjusted)
+        } else {
+            let tools = self.llm_tools(true).await?;
+            let (_, adjusted) = capabilities.adjust_chat_options(model, &self.chat_options(state, tools))?;
+            Ok(adjusted)
+        }
     }
     
     /// Template Method: Create stream based on model configuration
+    /// Create the stream, waiting out provider rate limits
+    ///
+    /// Each wait is announced with `StreamEvent::Retrying`. Once the retries
+    /// are used up, the last rate limit fails the run as a provider error.
+    /// With context overflow truncation, a call that overflows the context
+    /// window is retried once with fewer messages.
+    async fn open_stream(&self, state: &GraphState, event_tx: &EventSender) -> Result<LLMStream> {
+        let mut messages = self.request_messages(state);
+        let mut attempt = 0;
+        let mut truncated = false;
+        loop {
+            let error = match self.create_stream(state, messages.clone()).await {
+                Ok(stream) => return Ok(stream),
+                Err(error) => error,
+            };
+            if let Some(LLMError::ContextLengthExceeded { max, requested, .. }) = LLMError::of(&error) {
+                if !self.truncate_on_context_overflow || truncated {
+                    return Err(error);
+                }
+                let dropped = drop_oldest_messages(&mut messages, *max, *requested);
+                if dropped == 0 {
+                    return Err(error);
+                }
+                truncated = true;
+                event_tx
+                    .send(crate::types::StreamEvent::Warning {
+                        code: "context_truncated".to_string(),
+                        message: format!(
+                            "The conversation was too long for the model; the oldest {} message{} were left out",
+                            dropped,
+                            if dropped == 1 { "" } else { "s" }
+                        ),
+                        node_id: Some("llm".to_string()),
+                    })
+                    .await?;
+                tracing::warn!("LLM_NODE: context window exceeded, retrying without the oldest {} messages", dropped);
+                continue;
+            }
+            let Some(limited) = RateLimited::of(&error) else {
+                return Err(error);
+            };
+            if attempt >= self.rate_limit_retries {
+                return Err(GraphError::provider(error));
+            }
+            attempt += 1;
+
+            // Without a hint, back off 1s, 2s, 4s, ...
+            let wait = limited
+                .retry_after
+                .unwrap_or_else(|| Duration::from_secs(1 << (attempt - 1).min(5)));
+            if self.deadline.is_some_and(|deadline| Instant::now() + wait > deadline) {
+                return Err(GraphError::new(
+                    GraphErrorKind::ExecutionTimeout,
+                    format!("Rate limited, and retrying in {:?} would exceed the run deadline", wait),
+                )
+                .into());
+            }
+
+            event_tx
+                .send(crate::types::StreamEvent::Retrying {
+                    reason: "rate_limited".to_string(),
+                    in_seconds: wait.as_millis().div_ceil(1000) as u64,
+                    attempt,
+                })
+                .await?;
+            tracing::warn!("LLM_NODE: rate limited, retry {} in {:?}", attempt, wait);
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = event_tx.closed(), if self.cancellable => {
+                    return Err(GraphError::new(GraphErrorKind::Cancelled, "Run cancelled while rate limited").into());
+                }
+            }
+        }
+    }
+
     async fn create_stream(
         &self,
         state: &GraphState,
-    ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
+        messages: Vec<Message>,
+    ) -> Result<LLMStream> {
         let model = &state.llm_config.model;
-        let use_reasoning_api = Self::is_reasoning_model(model) && self.reasoning_client.is_some();
+        let use_reasoning_api = self.use_reasoning_api(model);
         
         tracing::info!(
             "LLM_NODE: Creating stream with model={}, use_reasoning_api={}",
@@ -56,50 +375,58 @@ impl LLMNode {
         );
         
         if use_reasoning_api {
-            self.create_reasoning_stream(state).await
+            self.create_reasoning_stream(state, messages).await
         } else {
-            self.create_chat_stream(state).await
+            self.create_chat_stream(state, messages).await
         }
     }
     
-    async fn create_reasoning_stream(
-        &self,
-        state: &GraphState,
-    ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
-        let reasoning_config = state.llm_config.reasoning_effort
-            .as_ref()
-            .map(|effort| match effort.as_str() {
-                "low" => ReasoningConfig::low(),
-                "high" => ReasoningConfig::high(),
-                _ => ReasoningConfig::medium(),
-            });
+    fn response_request(&self, state: &GraphState, messages: Vec<Message>, tools: Vec<Tool>) -> ResponseRequest {
+        let config = &state.llm_config;
+        let reasoning_config = (config.reasoning_effort.is_some() || config.reasoning_summary.is_some()).then(|| {
+            ReasoningConfig::new(
+                config.reasoning_effort.unwrap_or(ReasoningEffort::Medium),
+                config.reasoning_summary.unwrap_or(SummaryMode::Auto),
+            )
+        });
 
-        let request = ResponseRequest::new(
-            state.llm_config.model.clone(),
-            state.messages.clone()
-        );
+        let request = ResponseRequest::new(state.llm_config.model.clone(), messages);
         let request = if let Some(config) = reasoning_config {
             request.with_reasoning(config)
         } else {
             request
         };
-
+        let mut options = ResponseOptions::new().tools(tools).tool_choice(self.tool_choice(state));
+        if let Some(parallel) = self.parallel_tool_calls {
+            options = options.parallel_tool_calls(parallel);
+        }
+        if let Some(id) = &self.end_user_id {
+            options = options.end_user_id(id.clone());
+        }
+        if let Some(extra) = &config.extra_body {
+            options = options.extra_body(extra.clone());
+        }
+        request.with_options(options)
+    }
+    
+    async fn create_reasoning_stream(
+        &self,
+        state: &GraphState,
+        messages: Vec<Message>,
+    ) -> Result<LLMStream> {
+        let tools = self.llm_tools(false).await?;
+        let request = self.response_request(state, messages, tools);
+        request.validate().map_err(|error| provider_error(LLMError::from(error).into()))?;
         self.reasoning_client
             .as_ref()
             .unwrap()
             .reason_stream(request)
             .await
+            .map_err(provider_error)
     }
     
-    async fn create_chat_stream(
-        &self,
-        state: &GraphState,
-    ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
-        let tools = self.mcp_executor.get_llm_tools().await?;
-        
-        let mut options = ChatOptions::new()
-            .tools(tools)
-            .tool_choice(ToolChoice::auto());
+    fn chat_options(&self, state: &GraphState, tools: Vec<Tool>) -> ChatOptions {
+        let mut options = ChatOptions::new().tools(tools).tool_choice(self.tool_choice(state));
 
         if let Some(temp) = state.llm_config.temperature {
             options = options.temperature(temp);
@@ -107,41 +434,157 @@ impl LLMNode {
         if let Some(max_tokens) = state.llm_config.max_tokens {
             options = options.max_tokens(max_tokens);
         }
+        if let Some(top_p) = state.llm_config.top_p {
+            options = options.top_p(top_p);
+        }
+        if let Some(seed) = state.llm_config.seed {
+            options = options.seed(seed);
+        }
+        if let Some(stop) = &state.llm_config.stop {
+            options = options.stop(stop.iter().cloned());
+        }
+        if let Some(audio) = &state.llm_config.audio {
+            options = options.audio(audio.clone());
+        }
+        if let Some(extra) = &state.llm_config.extra_body {
+            options = options.extra_body(extra.clone());
+        }
+        if let Some(parallel) = self.parallel_tool_calls {
+            options = options.parallel_tool_calls(parallel);
+        }
+        if let Some(id) = &self.end_user_id {
+            options = options.end_user_id(id.clone());
+        }
+        options
+    }
+    
+    /// Choice for this call: none once the router disables tools, the
+    /// configured choice before the first tool round, auto after it (a
+    /// forced call would otherwise repeat every turn)
+    fn tool_choice(&self, state: &GraphState) -> ToolChoice {
+        if !state.tools_enabled {
+            return ToolChoice::none();
+        }
+        let configured = state.llm_config.tool_choice.as_ref().or(self.tool_choice.as_ref());
+        match configured {
+            Some(choice) if state.tool_rounds == 0 => choice.clone(),
+            _ => ToolChoice::auto(),
+        }
+    }
+    
+    async fn create_chat_stream(
+        &self,
+        state: &GraphState,
+        messages: Vec<Message>,
+    ) -> Result<LLMStream> {
+        let tools = self.llm_tools(false).await?;
+        let options = self.chat_options(state, tools);
 
-        let request = ChatRequest::new(
-            state.llm_config.model.clone(),
-            state.messages.clone()
-        ).with_options(options);
+        let request = ChatRequest::new(state.llm_config.model.clone(), messages).with_options(options);
+        // Fail the run on a malformed request without calling the provider
+        request.validate().map_err(|error| provider_error(LLMError::from(error).into()))?;
 
-        self.client.chat_stream(request).await
+        self.client.chat_stream(request).await.map_err(provider_error)
     }
     
-    /// Template Method: Process stream and return structured outputs
+    /// Template Method: Process stream and return structured outputs, with
+    /// the usage and finish reason the provider reported
+    ///
+    /// `Done` carries `run_cost` plus this call's cost, so the run's last
+    /// `Done` reports what the whole run cost.
     async fn process_stream(
         &self,
-        mut stream: Pin<Box<dyn futures::Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>,
+        mut stream: LLMStream,
         event_tx: EventSender,
-    ) -> Result<Vec<GraphOutput>> {
+        ids: &dyn IdGen,
+        model: &str,
+        run_cost: Option<CostBreakdown>,
+    ) -> Result<(Vec<GraphOutput>, Option<TokenUsage>, Option<String>, Option<ResponseMetadata>)> {
         let mut reasoning_content = String::new();
+        let mut summary_deltas = String::new();
+        // Provider id of the reasoning item, from its deltas
+        let mut reasoning_item_id: Option<String> = None;
+        // Provider summary: (reasoning item id, text)
+        let mut reasoning_summary: Option<(String, String)> = None;
         let mut message_content = String::new();
-        let mut tool_call_buffers: std::collections::HashMap<u32, (Option<String>, Option<String>, String)> = std::collections::HashMap::new();
+        let mut spoken = false;
+        let mut usage = None;
+        let mut finish_reason = None;
+        let mut metadata = None;
+        let mut tool_call_buffers: std::collections::BTreeMap<u32, (Option<String>, Option<String>, String)> = std::collections::BTreeMap::new();
+
+        // Heartbeats run until the answer starts; the first one waits long
+        // enough that fast models never send any
+        let started = tokio::time::Instant::now();
+        let mut next_heartbeat = self
+            .thinking_heartbeat
+            .as_ref()
+            .map(|heartbeat| started + Duration::from_millis(heartbeat.first_after_ms));
 
         // Forward events and accumulate content separately
-        while let Some(event_result) = stream.next().await {
-            let llm_event = event_result?;
+        loop {
+            let event_result = tokio::select! {
+                event = stream.next() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(next_heartbeat.unwrap_or(started)), if next_heartbeat.is_some() => {
+                    let now = tokio::time::Instant::now();
+                    let elapsed_ms = now.duration_since(started).as_millis() as u64;
+                    event_tx.send(crate::types::StreamEvent::Thinking { elapsed_ms }).await?;
+                    let interval = self.thinking_heartbeat.as_ref().map_or(0, |heartbeat| heartbeat.interval_ms);
+                    next_heartbeat = Some(now + Duration::from_millis(interval.max(1)));
+                    continue;
+                }
+            };
+            let llm_event = event_result.map_err(GraphError::provider)?;
+            // The graph answers with the first choice; requests for more are
+            // made by callers using the client directly
+            if matches!(
+                llm_event,
+                praxis_llm::StreamEvent::Message { choice_index: Some(index), .. }
+                    | praxis_llm::StreamEvent::Done { choice_index: Some(index), .. } if index != 0
+            ) {
+                continue;
+            }
+            if matches!(
+                llm_event,
+                praxis_llm::StreamEvent::Message { .. }
+                    | praxis_llm::StreamEvent::Audio { .. }
+                    | praxis_llm::StreamEvent::ToolCall { .. }
+            ) {
+                next_heartbeat = None;
+            }
 
             // Convert and forward to client
-            let graph_event = Self::convert_event(llm_event.clone());
+            let mut graph_event = self.convert_event(llm_event.clone());
+            if let crate::types::StreamEvent::Done { cost_usd, .. } = &mut graph_event {
+                *cost_usd = add_costs(run_cost, self.price(model, usage.as_ref())).map(|cost| cost.total_usd);
+            }
             event_tx.send(graph_event).await?;
 
             // Accumulate based on event type (keep reasoning and message separate)
             match llm_event {
-                praxis_llm::StreamEvent::Reasoning { content } => {
-                    reasoning_content.push_str(&content);
+                praxis_llm::StreamEvent::Reasoning { content, kind, item_id } => {
+                    if reasoning_item_id.is_none() {
+                        reasoning_item_id = item_id;
+                    }
+                    match kind {
+                        ReasoningKind::Raw => reasoning_content.push_str(&content),
+                        ReasoningKind::Summary => summary_deltas.push_str(&content),
+                    }
                 }
-                praxis_llm::StreamEvent::Message { content } => {
+                praxis_llm::StreamEvent::ReasoningSummary { item_id, text } => match &mut reasoning_summary {
+                    Some((_, summary)) => {
+                        summary.push_str("\n\n");
+                        summary.push_str(&text);
+                    }
+                    None => reasoning_summary = Some((item_id, text)),
+                },
+                praxis_llm::StreamEvent::Message { content, .. } => {
                     message_content.push_str(&content);
                 }
+                praxis_llm::StreamEvent::Audio { .. } => spoken = true,
                 praxis_llm::StreamEvent::ToolCall { index, id, name, arguments } => {
                 let entry = tool_call_buffers.entry(index).or_insert((None, None, String::new()));
                 
@@ -155,6 +598,15 @@ impl LLMNode {
                     entry.2.push_str(&args);
                 }
             }
+                praxis_llm::StreamEvent::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens } => {
+                    usage = Some(TokenUsage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens });
+                }
+                praxis_llm::StreamEvent::Done { finish_reason: reason, .. } => {
+                    finish_reason = reason;
+                }
+                praxis_llm::StreamEvent::ResponseMetadata { metadata: sent } => {
+                    metadata = Some(sent);
+                }
                 _ => {}
             }
         }
@@ -162,15 +614,24 @@ impl LLMNode {
         // Build output items
         let mut outputs = Vec::new();
         
-        // Add reasoning output if present
-        if !reasoning_content.is_empty() {
-            outputs.push(GraphOutput::reasoning(
-                format!("rs_{}", uuid::Uuid::new_v4()),
-                reasoning_content,
-            ));
+        // Add reasoning output if present, under the provider's item id when known
+        if !reasoning_content.is_empty() || reasoning_summary.is_some() || !summary_deltas.is_empty() {
+            let (id, summary) = match reasoning_summary {
+                Some((id, summary)) => (id, Some(summary)),
+                // Streamed summary text stands in for a summary event the provider didn't send
+                None => (
+                    reasoning_item_id.unwrap_or_else(|| format!("rs_{}", ids.next_id())),
+                    Some(summary_deltas).filter(|text| !text.is_empty()),
+                ),
+            };
+            let output = GraphOutput::reasoning(id, reasoning_content);
+            outputs.push(match summary {
+                Some(summary) => output.with_summary(summary),
+                None => output,
+            });
         }
         
-        // Build tool calls
+        // Build tool calls, in index order
         let tool_calls: Vec<praxis_llm::ToolCall> = tool_call_buffers
             .into_iter()
             .filter_map(|(_, (id, name, arguments))| {
@@ -189,23 +650,145 @@ impl LLMNode {
             })
             .collect();
         
+        if spoken && message_content.is_empty() {
+            message_content = AUDIO_PLACEHOLDER.to_string();
+        }
+
         // Add message output if present
         if !message_content.is_empty() || !tool_calls.is_empty() {
             if tool_calls.is_empty() {
                 outputs.push(GraphOutput::message(
-                    format!("msg_{}", uuid::Uuid::new_v4()),
+                    format!("msg_{}", ids.next_id()),
                     message_content,
                 ));
             } else {
                 outputs.push(GraphOutput::message_with_tools(
-                    format!("msg_{}", uuid::Uuid::new_v4()),
+                    format!("msg_{}", ids.next_id()),
                     message_content,
                     tool_calls,
                 ));
             }
         }
         
-        Ok(outputs)
+        Ok((outputs, usage, finish_reason, metadata))
+    }
+    
+    /// Summarize reasoning the provider returned without a summary
+    ///
+    /// Only runs with a `reasoning_summary_model`; a failed summary is
+    /// logged and leaves the output unchanged.
+    async fn summarize_reasoning(&self, outputs: &mut [GraphOutput], event_tx: &EventSender) -> Result<()> {
+        let Some(model) = &self.reasoning_summary_model else {
+            return Ok(());
+        };
+        for output in outputs.iter_mut() {
+            let GraphOutput::Reasoning { id, content, summary } = output else {
+                continue;
+            };
+            if summary.is_some() || content.is_empty() {
+                continue;
+            }
+
+            let request = ChatRequest::new(
+                model.clone(),
+                vec![Message::system(REASONING_SUMMARY_PROMPT), Message::human(content.clone())],
+            )
+            .with_options(ChatOptions::new().max_tokens(120));
+            match self.client.chat(request).await {
+                Ok(response) => {
+                    let Some(text) = response.content.map(|text| text.trim().to_string()).filter(|t| !t.is_empty()) else {
+                        continue;
+                    };
+                    *summary = Some(text.clone());
+                    let event = praxis_llm::StreamEvent::ReasoningSummary { item_id: id.clone(), text };
+                    event_tx.send(self.convert_event(event)).await?;
+                }
+                Err(e) => tracing::warn!("Failed to summarize reasoning {}: {}", id, e),
+            }
+        }
+        Ok(())
+    }
+    
+    /// Emit each tool call with its assembled arguments
+    async fn complete_tool_calls(&self, outputs: &[GraphOutput], event_tx: &EventSender) -> Result<()> {
+        for call in tool_calls(outputs) {
+            let event = crate::types::StreamEvent::ToolCallComplete {
+                id: call.id.clone(),
+                name: call.function.name.clone(),
+                arguments: call.function.arguments.clone(),
+                agent: self.agent.clone(),
+                provenance: None,
+            };
+            event_tx.send(event).await?;
+        }
+        Ok(())
+    }
+    
+    /// Emit an activity line for each tool call
+    async fn describe_tool_calls(&self, state: &mut GraphState, outputs: &[GraphOutput], event_tx: &EventSender) -> Result<()> {
+        let Some(describer) = &self.tool_call_describer else {
+            return Ok(());
+        };
+        for call in tool_calls(outputs) {
+            let text = activity_text(describer.as_ref(), &call.function.name, &call.function.arguments);
+            if self.persist_activity {
+                state.tool_activity.insert(call.id.clone(), text.clone());
+            }
+            let event = crate::types::StreamEvent::Activity {
+                tool_call_id: call.id.clone(),
+                text,
+                agent: self.agent.clone(),
+                provenance: None,
+            };
+            event_tx.send(event).await?;
+        }
+        Ok(())
+    }
+    
+    /// Read the citation markers of a final answer into its output
+    async fn cite_sources(&self, state: &GraphState, outputs: &mut [GraphOutput], event_tx: &EventSender) -> Result<()> {
+        if !self.cite_sources {
+            return Ok(());
+        }
+        let sources = citations::sources(&state.messages);
+        for output in outputs.iter_mut() {
+            let GraphOutput::Message { content, tool_calls: None, citations: cited, .. } = output else {
+                continue;
+            };
+            *cited = citations::extract(content, &sources);
+            if cited.is_empty() {
+                continue;
+            }
+            let event = crate::types::StreamEvent::Citations {
+                citations: cited.clone(),
+                agent: self.agent.clone(),
+                provenance: None,
+            };
+            event_tx.send(event).await?;
+        }
+        Ok(())
+    }
+    
+    /// Tell the caller when the answer didn't end normally: a warning for
+    /// `length`, an error for `content_filter`
+    async fn report_finish_reason(&self, finish_reason: Option<&str>, event_tx: &EventSender) -> Result<()> {
+        let event = match finish_reason {
+            Some("length") => crate::types::StreamEvent::Warning {
+                code: "truncated".to_string(),
+                message: "The answer was cut off at the output token limit".to_string(),
+                node_id: Some("llm".to_string()),
+            },
+            Some("content_filter") => crate::types::StreamEvent::Error {
+                message: "The provider's content filter stopped the answer".to_string(),
+                node_id: Some("llm".to_string()),
+                kind: GraphErrorKind::ContentFiltered,
+                error_code: None,
+            },
+            _ => return Ok(()),
+        };
+        tracing::warn!("LLM_NODE: stream finished with finish_reason={:?}", finish_reason);
+        event_tx.send(event).await?;
+        Ok(())
     }
     
     /// Template Method: Save outputs to state
@@ -253,15 +836,99 @@ impl LLMNode {
     }
 }
 
+/// Tag a provider failure, keeping rate limits recognizable for retry
+fn provider_error(error: anyhow::Error) -> anyhow::Error {
+    if RateLimited::of(&error).is_some() {
+        error
+    } else {
+        GraphError::provider(error)
+    }
+}
+
+/// Drop the oldest messages after the leading instructions so the request
+/// fits in `max` tokens, returning how many went
+///
+/// Without the provider's token counts, half of them go. System messages
+/// stay, tool results never outlive the call that asked for them, and the
+/// latest message is always kept.
+fn drop_oldest_messages(messages: &mut Vec<Message>, max: Option<u32>, requested: Option<u32>) -> usize {
+    let is_instruction = |message: &Message| matches!(message, Message::System { .. } | Message::Developer { .. });
+    let leading = messages.iter().take_while(|message| is_instruction(message)).count();
+    let Some(latest) = messages.len().checked_sub(1).filter(|latest| *latest > leading) else {
+        return 0;
+    };
+
+    let droppable = latest - leading;
+    let share = match (max, requested) {
+        // The excess, plus a tenth of the window for the answer and estimation error
+        (Some(max), Some(requested)) if requested > max => {
+            (requested - max + max / 10) as f64 / requested as f64
+        }
+        _ => 0.5,
+    };
+    let mut cutoff = leading + ((droppable as f64 * share).ceil() as usize).clamp(1, droppable);
+    // Drop the rest of a cut-off tool round, or keep it whole if it reaches the latest message
+    while cutoff < latest && matches!(messages[cutoff], Message::Tool { .. }) {
+        cutoff += 1;
+    }
+    while cutoff > leading && matches!(messages[cutoff], Message::Tool { .. }) {
+        cutoff -= 1;
+    }
+
+    let before = messages.len();
+    let mut index = 0;
+    messages.retain(|message| {
+        let keep = index < leading || index >= cutoff || is_instruction(message);
+        index += 1;
+        keep
+    });
+    before - messages.len()
+}
+
+/// `total` with `cost` added, either of which may be unknown
+fn add_costs(total: Option<CostBreakdown>, cost: Option<CostBreakdown>) -> Option<CostBreakdown> {
+    match (total, cost) {
+        (Some(mut total), Some(cost)) => {
+            total += cost;
+            Some(total)
+        }
+        (total, cost) => total.or(cost),
+    }
+}
+
+/// Tool calls among `outputs`, in the order the model made them
+fn tool_calls(outputs: &[GraphOutput]) -> impl Iterator<Item = &praxis_llm::ToolCall> {
+    outputs
+        .iter()
+        .filter_map(|output| match output {
+            GraphOutput::Message { tool_calls, .. } => tool_calls.as_deref(),
+            GraphOutput::Reasoning { .. } => None,
+        })
+        .flatten()
+}
+
 #[async_trait]
 impl Node for LLMNode {
     /// Template Method Pattern: Execute node with structured steps
     async fn execute(&self, state: &mut GraphState, event_tx: EventSender) -> Result<()> {
         // Step 1: Create stream (Chat or Reasoning API)
-        let stream = self.create_stream(state).await?;
+        let stream = self.open_stream(state, &event_tx).await?;
         
         // Step 2: Process stream and get structured outputs
-        let outputs = self.process_stream(stream, event_tx).await?;
+        let ids = Arc::clone(&state.ids);
+        let (mut outputs, usage, finish_reason, metadata) = self
+            .process_stream(stream, event_tx.clone(), ids.as_ref(), &state.llm_config.model, state.cost)
+            .await?;
+        state.last_response_metadata = metadata;
+        state.last_cost = self.price(&state.llm_config.model, usage.as_ref());
+        state.cost = add_costs(state.cost, state.last_cost);
+        state.last_usage = usage;
+        self.report_finish_reason(finish_reason.as_deref(), &event_tx).await?;
+        state.last_finish_reason = finish_reason;
+        self.summarize_reasoning(&mut outputs, &event_tx).await?;
+        self.cite_sources(state, &mut outputs, &event_tx).await?;
+        self.complete_tool_calls(&outputs, &event_tx).await?;
+        self.describe_tool_calls(state, &outputs, &event_tx).await?;
         
         // Step 3: Save outputs to state
         self.save_outputs(state, &outputs)?;
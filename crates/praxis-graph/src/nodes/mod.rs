@@ -1,5 +1,6 @@
 pub mod llm_node;
 pub mod tool_node;
+mod tool_args;
 
 pub use llm_node::LLMNode;
 pub use tool_node::ToolNode;
@@ -0,0 +1,128 @@
+//! Completeness checks for streamed tool call arguments
+//!
+//! Arguments arrive as string deltas; if the stream ends early (provider bug,
+//! dropped delta) the buffer can be a truncated fragment. Such calls must be
+//! bounced back to the model, never executed with whatever happens to parse.
+
+use serde_json::{Map, Value};
+
+/// Parse `raw` as a complete JSON object and check it against `schema`
+///
+/// Only the parts of JSON Schema that catch truncation are enforced: the
+/// top-level object, `required` properties and primitive property `type`s.
+pub(crate) fn validate_arguments(raw: &str, schema: Option<&Value>) -> Result<Value, String> {
+    // An empty buffer means "no arguments", which providers send for
+    // parameterless tools
+    let raw = if raw.trim().is_empty() { "{}" } else { raw };
+
+    let value: Value = serde_json::from_str(raw).map_err(|e| format!("arguments are not complete JSON ({})", e))?;
+    let Value::Object(arguments) = &value else {
+        return Err("arguments must be a JSON object".to_string());
+    };
+
+    if let Some(schema) = schema {
+        check_required(arguments, schema)?;
+        check_property_types(arguments, schema)?;
+    }
+    Ok(value)
+}
+
+fn check_required(arguments: &Map<String, Value>, schema: &Value) -> Result<(), String> {
+    let missing: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|name| !arguments.contains_key(*name))
+        .collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("missing required argument(s): {}", missing.join(", ")))
+    }
+}
+
+fn check_property_types(arguments: &Map<String, Value>, schema: &Value) -> Result<(), String> {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Ok(());
+    };
+
+    for (name, value) in arguments {
+        let Some(expected) = properties.get(name).and_then(|p| p.get("type")) else {
+            continue;
+        };
+        // `type` may be a single name or a list of allowed names
+        let allowed: Vec<&str> = match expected {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => continue,
+        };
+        if !allowed.iter().any(|kind| matches_type(value, kind)) {
+            return Err(format!("argument '{}' should be of type {}", name, allowed.join(" or ")));
+        }
+    }
+    Ok(())
+}
+
+fn matches_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // Unknown type names are not ours to reject
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer" }
+            },
+            "required": ["query"]
+        })
+    }
+
+    #[test]
+    fn test_truncated_arguments_are_rejected() {
+        let full = r#"{"query": "SF weather", "limit": 5}"#;
+        for cut in 1..full.len() {
+            let fragment = &full[..cut];
+            assert!(
+                validate_arguments(fragment, Some(&schema())).is_err(),
+                "fragment accepted: {}",
+                fragment
+            );
+        }
+        assert!(validate_arguments(full, Some(&schema())).is_ok());
+    }
+
+    #[test]
+    fn test_schema_catches_parseable_but_incomplete_arguments() {
+        assert!(validate_arguments(r#"{}"#, Some(&schema())).unwrap_err().contains("query"));
+        assert!(validate_arguments(r#"{"query": 12}"#, Some(&schema())).unwrap_err().contains("string"));
+        assert!(validate_arguments(r#"{"query": "SF", "limit": 2.5}"#, Some(&schema())).is_err());
+        assert!(validate_arguments(r#"{"query": "SF", "extra": true}"#, Some(&schema())).is_ok());
+    }
+
+    #[test]
+    fn test_without_schema_only_strict_json_object_is_required() {
+        assert!(validate_arguments("", None).is_ok());
+        assert!(validate_arguments("[1, 2]", None).is_err());
+        assert!(validate_arguments(r#"{"query": "SF""#, None).is_err());
+        assert!(validate_arguments(r#"{"query": "SF"}"#, None).is_ok());
+    }
+}
@@ -1,18 +1,120 @@
+use crate::authorization::{Decision, RunContext, ToolAuthorizer};
 use crate::node::{EventSender, Node, NodeType};
+use crate::nodes::tool_args::validate_arguments;
 use anyhow::Result;
 use async_trait::async_trait;
 use praxis_mcp::{MCPToolExecutor, ToolResponse};
+use praxis_persist::ToolVerification;
 use crate::types::{GraphState, StreamEvent};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
 pub struct ToolNode {
     mcp_executor: Arc<MCPToolExecutor>,
+    agent: Option<String>,
+    authorizer: Option<(Arc<dyn ToolAuthorizer>, RunContext)>,
 }
 
 impl ToolNode {
     pub fn new(mcp_executor: Arc<MCPToolExecutor>) -> Self {
-        Self { mcp_executor }
+        Self { mcp_executor, agent: None, authorizer: None }
+    }
+
+    /// Ask `authorizer` before each call, on behalf of `ctx`
+    pub fn with_authorizer(mut self, authorizer: Arc<dyn ToolAuthorizer>, ctx: RunContext) -> Self {
+        self.authorizer = Some((authorizer, ctx));
+        self
+    }
+
+    /// Run the authorizer (if any) and write the audit record
+    fn authorize(&self, tool_name: &str, tool_call_id: &str, args: &serde_json::Value) -> Decision {
+        let Some((authorizer, ctx)) = &self.authorizer else {
+            return Decision::Allow;
+        };
+
+        let decision = authorizer.authorize(ctx, tool_name, args);
+        let reason = match &decision {
+            Decision::Allow => "",
+            Decision::Deny { reason } => reason.as_str(),
+        };
+        tracing::info!(
+            target: "praxis::audit",
+            event = "ToolCallAuthorization",
+            allowed = decision.is_allowed(),
+            tool = %tool_name,
+            tool_call_id = %tool_call_id,
+            run_id = %ctx.run_id,
+            conversation_id = %ctx.conversation_id,
+            user_id = ctx.user_id.as_deref().unwrap_or(""),
+            tenant_id = ctx.tenant_id.as_deref().unwrap_or(""),
+            reason = %reason,
+        );
+        decision
+    }
+
+    /// Write the audit record of a call `server` ran
+    fn audit_execution(&self, state: &GraphState, tool_name: &str, tool_call_id: &str, server: &str) {
+        let ctx = self.authorizer.as_ref().map(|(_, ctx)| ctx);
+        tracing::info!(
+            target: "praxis::audit",
+            event = "ToolCallExecuted",
+            tool = %tool_name,
+            tool_call_id = %tool_call_id,
+            server = %server,
+            run_id = %state.run_id,
+            conversation_id = %state.conversation_id,
+            user_id = ctx.and_then(|ctx| ctx.user_id.as_deref()).unwrap_or(""),
+            tenant_id = ctx.and_then(|ctx| ctx.tenant_id.as_deref()).unwrap_or(""),
+        );
+    }
+
+    /// Check the effect of a successful call of `tool_name` with the read
+    /// tool its `verify_with` names (None when it has none)
+    async fn verify(
+        &self,
+        state: &GraphState,
+        tool_name: &str,
+        tool_call_id: &str,
+        arguments: &serde_json::Value,
+    ) -> Option<ToolVerification> {
+        let config = self.mcp_executor.tool_config(tool_name).await?;
+        let read_tool = config.verify_with.clone()?;
+
+        let (read_arguments, result, reason) = match config.verification_arguments(arguments) {
+            Err(reason) => (serde_json::Value::Null, String::new(), Some(reason)),
+            Ok(read_arguments) => match self.mcp_executor.call_tool(&read_tool, read_arguments.clone()).await {
+                Ok(output) => {
+                    self.audit_execution(state, &read_tool, tool_call_id, &output.server);
+                    let result = ToolResponse::join_responses(&output.responses);
+                    let reason = config.check(arguments, &result).err();
+                    (read_arguments, result, reason)
+                }
+                Err(e) => (read_arguments, format!("Tool execution failed: {}", e), Some("the read call failed".to_string())),
+            },
+        };
+
+        tracing::info!(
+            event = "ToolCallVerified",
+            tool = %tool_name,
+            tool_call_id = %tool_call_id,
+            verify_with = %read_tool,
+            passed = reason.is_none(),
+            reason = reason.as_deref().unwrap_or(""),
+        );
+        Some(ToolVerification {
+            tool_name: read_tool,
+            arguments: read_arguments,
+            result,
+            passed: reason.is_none(),
+            reason,
+        })
+    }
+
+    /// Attribute emitted tool results to the given agent
+    pub fn with_agent(mut self, agent: impl Into<String>) -> Self {
+        self.agent = Some(agent.into());
+        self
     }
 }
 
@@ -26,29 +128,118 @@ impl Node for ToolNode {
             return Ok(());
         }
 
+        // Argument schemas by tool name; without them only strict JSON is checked
+        let schemas: HashMap<String, serde_json::Value> = match self.mcp_executor.get_llm_tools().await {
+            Ok(tools) => tools
+                .into_iter()
+                .map(|tool| (tool.function.name, tool.function.parameters))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Could not load tool schemas, validating JSON only: {}", e);
+                HashMap::new()
+            }
+        };
+
         // Execute each tool call
         for tool_call in tool_calls {
             let start = Instant::now();
 
-            // Parse arguments from string to Value
-            let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)?;
+            // Never execute truncated or malformed arguments: the model retries instead
+            let raw_arguments = &tool_call.function.arguments;
+            let args = match validate_arguments(raw_arguments, schemas.get(&tool_call.function.name)) {
+                Ok(args) => args,
+                Err(reason) => {
+                    tracing::warn!(
+                        event = "ToolCallInvalid",
+                        tool = %tool_call.function.name,
+                        tool_call_id = %tool_call.id,
+                        raw_arguments = %raw_arguments,
+                        "Not executing tool call: {}",
+                        reason
+                    );
+                    let error_msg = format!(
+                        "Invalid arguments for tool '{}': {}. Nothing was executed; call the tool again with complete arguments.",
+                        tool_call.function.name, reason
+                    );
+
+                    event_tx
+                        .send(StreamEvent::ToolResult {
+                            tool_call_id: tool_call.id.clone(),
+                            result: error_msg.clone(),
+                            is_error: true,
+                            duration_ms: start.elapsed().as_millis() as u64,
+                            tool_server: None,
+                            verification: None,
+                            agent: self.agent.clone(),
+                            provenance: None,
+                        })
+                        .await?;
+
+                    state.add_tool_result(tool_call.id, error_msg);
+                    continue;
+                }
+            };
+
+            if let Decision::Deny { reason } = self.authorize(&tool_call.function.name, &tool_call.id, &args) {
+                let error_msg = format!(
+                    "Tool '{}' was not executed: {}",
+                    tool_call.function.name, reason
+                );
+
+                event_tx
+                    .send(StreamEvent::ToolResult {
+                        tool_call_id: tool_call.id.clone(),
+                        result: error_msg.clone(),
+                        is_error: true,
+                        duration_ms: start.elapsed().as_millis() as u64,
+                        tool_server: None,
+                        verification: None,
+                        agent: self.agent.clone(),
+                        provenance: None,
+                    })
+                    .await?;
+
+                state.add_tool_result(tool_call.id, error_msg);
+                continue;
+            }
             
             match self
                 .mcp_executor
-                .execute_tool(&tool_call.function.name, args)
+                .call_tool(&tool_call.function.name, args.clone())
                 .await
             {
-                Ok(responses) => {
+                Ok(output) => {
                     // Join all responses into a single result string
-                    let result = ToolResponse::join_responses(&responses);
+                    let mut result = ToolResponse::join_responses(&output.responses);
+                    self.audit_execution(state, &tool_call.function.name, &tool_call.id, &output.server);
+                    state.tool_servers.insert(tool_call.id.clone(), output.server.clone());
+
+                    // Write tools: the model only sees a success once a read confirms it
+                    let verification = self.verify(state, &tool_call.function.name, &tool_call.id, &args).await;
+                    let failed = verification.as_ref().is_some_and(|verification| !verification.passed);
+                    if let Some(verification) = &verification {
+                        if !verification.passed {
+                            result = format!(
+                                "{}\n\nVerification with '{}' failed: {}. The change may not have taken effect; check it and retry, or tell the user it could not be confirmed.",
+                                result,
+                                verification.tool_name,
+                                verification.reason.as_deref().unwrap_or("unknown reason")
+                            );
+                        }
+                        state.tool_verifications.insert(tool_call.id.clone(), verification.clone());
+                    }
                     
-                    // Success: emit result event
+                    // Emit result event
                     event_tx
                         .send(StreamEvent::ToolResult {
                             tool_call_id: tool_call.id.clone(),
                             result: result.clone(),
-                            is_error: false,
+                            is_error: failed,
                             duration_ms: start.elapsed().as_millis() as u64,
+                            tool_server: Some(output.server),
+                            verification,
+                            agent: self.agent.clone(),
+                            provenance: None,
                         })
                         .await?;
 
@@ -65,6 +256,10 @@ impl Node for ToolNode {
                             result: error_msg.clone(),
                             is_error: true,
                             duration_ms: start.elapsed().as_millis() as u64,
+                            tool_server: None,
+                            verification: None,
+                            agent: self.agent.clone(),
+                            provenance: None,
                         })
                         .await?;
 
@@ -4,6 +4,11 @@ use crate::types::GraphState;
 /// Decides which node to execute next based on current state
 pub trait Router: Send + Sync {
     fn next(&self, state: &GraphState, current: NodeType) -> NextNode;
+
+    /// Whether the next LLM call may request tools
+    fn allow_tools(&self, _state: &GraphState) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -36,3 +41,23 @@ impl Router for SimpleRouter {
     }
 }
 
+/// `SimpleRouter` with a cap on tool rounds
+///
+/// Once `max_tool_rounds` rounds have run, the model is asked to answer
+/// without tools; if it still calls one, the run ends there.
+pub struct BudgetRouter {
+    pub max_tool_rounds: u32,
+}
+
+impl Router for BudgetRouter {
+    fn next(&self, state: &GraphState, current: NodeType) -> NextNode {
+        match SimpleRouter.next(state, current) {
+            NextNode::Tool if !self.allow_tools(state) => NextNode::End,
+            next => next,
+        }
+    }
+
+    fn allow_tools(&self, state: &GraphState) -> bool {
+        state.tool_rounds < self.max_tool_rounds
+    }
+}
@@ -0,0 +1,138 @@
+//! Pacing of bursty message deltas on the graph event channel
+//!
+//! Some deployments deliver several kilobytes of text at once after a long
+//! gap. With `GraphConfig::smoothing` set, large `Message` deltas are split
+//! into chunks of about `target_chars_per_event` characters and released on
+//! a timer. No chunk is held longer than `max_delay_ms` after its delta
+//! arrived. Any other event flushes the pending chunks and is forwarded at
+//! once, so tool calls, `Done` and errors are never delayed.
+//!
+//! Smoothing works on the events the graph emits. Batching of events for
+//! transport comes after it (see `crate::batching`).
+
+use crate::node::EventSender;
+use crate::types::config::SmoothingConfig;
+use crate::types::{Provenance, StreamEvent};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Put a pacer in front of `output`; events sent to the returned sender reach `output` smoothed
+pub(crate) fn spawn_pacer(config: SmoothingConfig, output: EventSender, capacity: usize) -> EventSender {
+    let (tx, rx) = mpsc::channel(capacity);
+    tokio::spawn(Pacer::new(config, output).run(rx));
+    tx
+}
+
+struct Chunk {
+    event: StreamEvent,
+    release_at: Instant,
+}
+
+struct Pacer {
+    config: SmoothingConfig,
+    output: EventSender,
+    pending: VecDeque<Chunk>,
+}
+
+impl Pacer {
+    fn new(config: SmoothingConfig, output: EventSender) -> Self {
+        Self {
+            config,
+            output,
+            pending: VecDeque::new(),
+        }
+    }
+
+    async fn run(mut self, mut input: mpsc::Receiver<StreamEvent>) {
+        loop {
+            let next_release = self.pending.front().map(|chunk| chunk.release_at);
+            tokio::select! {
+                event = input.recv() => match event {
+                    Some(StreamEvent::Message { content, agent, provenance }) => self.schedule(content, agent, provenance),
+                    Some(event) => {
+                        if self.flush().await.is_err() || self.output.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => {
+                        let _ = self.flush().await;
+                        return;
+                    }
+                },
+                _ = tokio::time::sleep_until(next_release.unwrap_or_else(Instant::now)), if next_release.is_some() => {
+                    if self.release_due().await.is_err() {
+                        return;
+                    }
+                }
+                // Receiver gone: drop our input so the run sees the cancellation
+                _ = self.output.closed() => return,
+            }
+        }
+    }
+
+    /// Split a delta into chunks spread evenly over the delay budget
+    fn schedule(&mut self, content: String, agent: Option<String>, provenance: Option<Provenance>) {
+        let now = Instant::now();
+        let chunks = split_chars(&content, self.config.target_chars_per_event);
+        let budget = Duration::from_millis(self.config.max_delay_ms);
+        let step = budget / chunks.len().max(1) as u32;
+        // Chunks go out in order, so none may be scheduled before the last queued one
+        let mut not_before = self.pending.back().map_or(now, |chunk| chunk.release_at);
+
+        for (i, text) in chunks.into_iter().enumerate() {
+            let release_at = (now + step * i as u32).max(not_before);
+            not_before = release_at;
+            self.pending.push_back(Chunk {
+                event: StreamEvent::Message { content: text, agent: agent.clone(), provenance },
+                release_at,
+            });
+        }
+    }
+
+    async fn release_due(&mut self) -> Result<(), mpsc::error::SendError<StreamEvent>> {
+        let now = Instant::now();
+        while self.pending.front().is_some_and(|chunk| chunk.release_at <= now) {
+            let chunk = self.pending.pop_front().unwrap();
+            self.output.send(chunk.event).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), mpsc::error::SendError<StreamEvent>> {
+        while let Some(chunk) = self.pending.pop_front() {
+            self.output.send(chunk.event).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Split `text` into pieces of at most `max_chars` characters
+fn split_chars(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let cut = rest.char_indices().nth(max_chars).map_or(rest.len(), |(index, _)| index);
+        pieces.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+    if pieces.is_empty() {
+        pieces.push(String::new());
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_respects_char_boundaries() {
+        assert_eq!(split_chars("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(split_chars("héllo wörld", 4), vec!["héll", "o wö", "rld"]);
+        assert_eq!(split_chars("", 4), vec![""]);
+        assert_eq!(split_chars("ab", 10), vec!["ab"]);
+    }
+}
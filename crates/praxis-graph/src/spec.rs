@@ -0,0 +1,246 @@
+//! Declarative graph definitions
+//!
+//! A spec file (TOML, or YAML for `.yaml`/`.yml`) describes the shape of a
+//! run so topology can change without a rebuild:
+//!
+//! ```toml
+//! # Built-in `llm` and `tool`, plus custom nodes from the `NodeRegistry`.
+//! # Custom nodes before `llm` run before every LLM call, those between
+//! # `llm` and `tool` after it, and those after `tool` after every tool round.
+//! pipeline = ["redact_pii", "llm", "tool", "audit"]
+//! # Read relative to the spec file; used when the input has no system message
+//! system_prompt = "prompts/agent.md"
+//!
+//! [router]
+//! max_tool_rounds = 4   # then the model must answer without tools
+//!
+//! [budgets]
+//! max_iterations = 20
+//! execution_timeout_secs = 120
+//!
+//! [context_policy]
+//! type = "last_k"
+//! k = 20
+//!
+//! [guardrails.tool_content_isolation]
+//! suspicious_content = "redact"
+//! ```
+//!
+//! Custom node implementations stay in code: the registry maps the names a
+//! spec may use to factories. `GraphBuilder::from_spec` loads, validates
+//! and applies a spec; validation errors name the offending key.
+
+use crate::node::Node;
+use crate::types::config::{ContextPolicy, GraphConfig, ToolContentIsolationConfig};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Name of the built-in LLM node in `GraphSpec::pipeline`
+pub const LLM_NODE: &str = "llm";
+/// Name of the built-in tool node in `GraphSpec::pipeline`
+pub const TOOL_NODE: &str = "tool";
+
+type NodeFactory = Arc<dyn Fn() -> Arc<dyn Node> + Send + Sync>;
+
+/// Custom nodes a spec may name, keyed by name
+#[derive(Clone, Default)]
+pub struct NodeRegistry {
+    factories: BTreeMap<String, NodeFactory>,
+}
+
+impl NodeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `name` available to specs; `factory` is called once per graph
+    pub fn register(
+        mut self,
+        name: impl Into<String>,
+        factory: impl Fn() -> Arc<dyn Node> + Send + Sync + 'static,
+    ) -> Self {
+        self.factories.insert(name.into(), Arc::new(factory));
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Registered names, sorted
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    pub(crate) fn create(&self, name: &str) -> Option<Arc<dyn Node>> {
+        self.factories.get(name).map(|factory| factory())
+    }
+}
+
+/// Graph topology, routing and budgets as written in a spec file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GraphSpec {
+    /// Node order around the LLM/tool loop (default: `["llm", "tool"]`)
+    pub pipeline: Vec<String>,
+    /// Prompt file, relative to the spec file when loaded with `load`
+    pub system_prompt: Option<PathBuf>,
+    pub router: RouterSpec,
+    pub budgets: BudgetSpec,
+    /// Trim the history sent to the model (unset: send it all)
+    pub context_policy: Option<ContextPolicy>,
+    pub guardrails: GuardrailSpec,
+}
+
+/// Routing rules on top of the ReAct loop
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RouterSpec {
+    /// Tool rounds before the model has to answer without tools (unset: no limit)
+    pub max_tool_rounds: Option<u32>,
+}
+
+/// Run limits; unset fields keep the `GraphConfig` value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetSpec {
+    pub max_iterations: Option<usize>,
+    pub execution_timeout_secs: Option<u64>,
+    pub rate_limit_retries: Option<u32>,
+}
+
+/// Guardrails applied to every run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuardrailSpec {
+    pub tool_content_isolation: Option<ToolContentIsolationConfig>,
+}
+
+impl Default for GraphSpec {
+    fn default() -> Self {
+        Self {
+            pipeline: vec![LLM_NODE.to_string(), TOOL_NODE.to_string()],
+            system_prompt: None,
+            router: RouterSpec::default(),
+            budgets: BudgetSpec::default(),
+            context_policy: None,
+            guardrails: GuardrailSpec::default(),
+        }
+    }
+}
+
+impl GraphSpec {
+    /// Parse a spec file (YAML for `.yaml`/`.yml`, TOML otherwise)
+    ///
+    /// `system_prompt` is resolved against the file's directory. The spec
+    /// is not validated; see `validate`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let yaml = matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml"));
+        let mut spec = if yaml { Self::from_yaml(&text) } else { Self::from_toml(&text) }
+            .with_context(|| format!("parsing {}", path.display()))?;
+        if let (Some(prompt), Some(dir)) = (&spec.system_prompt, path.parent()) {
+            spec.system_prompt = Some(dir.join(prompt));
+        }
+        Ok(spec)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(text)?)
+    }
+
+    /// Check the spec against the custom nodes in `registry`
+    pub fn validate(&self, registry: &NodeRegistry) -> Result<()> {
+        let position = |name: &str| self.pipeline.iter().position(|node| node == name);
+        let (Some(llm), Some(tool)) = (position(LLM_NODE), position(TOOL_NODE)) else {
+            bail!("pipeline must contain `{}` and `{}`", LLM_NODE, TOOL_NODE);
+        };
+        if tool < llm {
+            bail!("pipeline: `{}` must come before `{}`", LLM_NODE, TOOL_NODE);
+        }
+        let mut seen = HashSet::new();
+        for (i, name) in self.pipeline.iter().enumerate() {
+            if !seen.insert(name.as_str()) {
+                bail!("pipeline[{}]: `{}` is listed more than once", i, name);
+            }
+            if name != LLM_NODE && name != TOOL_NODE && !registry.contains(name) {
+                let registered: Vec<&str> = registry.names().collect();
+                bail!(
+                    "pipeline[{}]: unknown node `{}` (registered: {})",
+                    i,
+                    name,
+                    if registered.is_empty() { "none".to_string() } else { registered.join(", ") }
+                );
+            }
+        }
+
+        if self.router.max_tool_rounds == Some(0) {
+            bail!("router.max_tool_rounds must be greater than 0");
+        }
+        if self.budgets.max_iterations == Some(0) {
+            bail!("budgets.max_iterations must be greater than 0");
+        }
+        if self.budgets.execution_timeout_secs == Some(0) {
+            bail!("budgets.execution_timeout_secs must be greater than 0");
+        }
+        if let Some(ContextPolicy::LastK { k: 0 }) = self.context_policy {
+            bail!("context_policy.k must be greater than 0");
+        }
+        if let Some(path) = &self.system_prompt {
+            std::fs::metadata(path).with_context(|| format!("system_prompt: cannot read {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// `config` with the spec's budgets and guardrails applied
+    pub fn apply_to(&self, mut config: GraphConfig) -> GraphConfig {
+        if let Some(max) = self.budgets.max_iterations {
+            config.max_iterations = max;
+        }
+        if let Some(secs) = self.budgets.execution_timeout_secs {
+            config.execution_timeout = Duration::from_secs(secs);
+        }
+        if let Some(retries) = self.budgets.rate_limit_retries {
+            config.rate_limit_retries = retries;
+        }
+        if let Some(isolation) = &self.guardrails.tool_content_isolation {
+            config.tool_content_isolation = Some(isolation.clone());
+        }
+        config
+    }
+
+    /// Custom nodes around the built-ins, created from `registry`
+    pub(crate) fn custom_nodes(&self, registry: &NodeRegistry) -> Result<CustomNodes> {
+        let mut nodes = CustomNodes::default();
+        let mut slot = &mut nodes.before_llm;
+        for name in &self.pipeline {
+            match name.as_str() {
+                LLM_NODE => slot = &mut nodes.after_llm,
+                TOOL_NODE => slot = &mut nodes.after_tool,
+                _ => slot.push(
+                    registry
+                        .create(name)
+                        .with_context(|| format!("pipeline: unknown node `{}`", name))?,
+                ),
+            }
+        }
+        Ok(nodes)
+    }
+}
+
+/// Custom nodes by where they run in the loop
+#[derive(Clone, Default)]
+pub(crate) struct CustomNodes {
+    pub before_llm: Vec<Arc<dyn Node>>,
+    pub after_llm: Vec<Arc<dyn Node>>,
+    pub after_tool: Vec<Arc<dyn Node>>,
+}
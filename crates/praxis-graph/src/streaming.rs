@@ -68,7 +68,7 @@ pub struct AnthropicStreamAdapter;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use praxis_llm::StreamEvent as LLMEvent;
+    use praxis_llm::{ReasoningKind, StreamEvent as LLMEvent};
     use crate::types::StreamEvent as GraphEvent;
     
     #[test]
@@ -76,13 +76,15 @@ mod tests {
         let adapter = OpenAIStreamAdapter;
         let llm_event = LLMEvent::Message {
             content: "Hello".to_string(),
+            choice_index: None,
+            logprobs: None,
         };
         
         let graph_event = adapter.adapt(llm_event);
         assert!(graph_event.is_some());
         
         match graph_event.unwrap() {
-            GraphEvent::Message { content } => {
+            GraphEvent::Message { content, .. } => {
                 assert_eq!(content, "Hello");
             }
             _ => panic!("Expected Message event"),
@@ -94,14 +96,17 @@ mod tests {
         let adapter = OpenAIStreamAdapter;
         let llm_event = LLMEvent::Reasoning {
             content: "Thinking...".to_string(),
+            kind: ReasoningKind::Summary,
+            item_id: None,
         };
         
         let graph_event = adapter.adapt(llm_event);
         assert!(graph_event.is_some());
         
         match graph_event.unwrap() {
-            GraphEvent::Reasoning { content } => {
+            GraphEvent::Reasoning { content, kind, .. } => {
                 assert_eq!(content, "Thinking...");
+                assert_eq!(kind, ReasoningKind::Summary);
             }
             _ => panic!("Expected Reasoning event"),
         }
@@ -1,4 +1,7 @@
+use anyhow::{bail, Result};
+use praxis_llm::{AudioOptions, ReasoningEffort, SummaryMode, ToolChoice};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -7,6 +10,7 @@ pub enum Provider {
     OpenAI,
     Azure,
     Anthropic,
+    Gemini,
 }
 
 impl Default for Provider {
@@ -15,11 +19,154 @@ impl Default for Provider {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Graph execution settings
+///
+/// Every field has a default, so partial configs (e.g. a `[graph]` TOML
+/// section with only `max_iterations`) deserialize cleanly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GraphConfig {
     pub max_iterations: usize,
+    /// Serialized as whole seconds (`execution_timeout_secs`)
+    #[serde(rename = "execution_timeout_secs", with = "duration_secs")]
     pub execution_timeout: Duration,
     pub enable_cancellation: bool,
+    /// Capacity of the event channel returned by `Graph::spawn_run`
+    pub event_channel_capacity: usize,
+    /// Name of the primary agent, used to attribute events and messages
+    pub agent_name: String,
+    /// Send `PersistenceContext::user_id` to the provider as-is instead of
+    /// its SHA-256 hash (as the `user` end-user identifier)
+    pub send_raw_end_user_id: bool,
+    /// Cheap model that summarizes reasoning the provider returned without
+    /// a summary (`None`: no post-hoc summaries)
+    pub reasoning_summary_model: Option<String>,
+    /// Times an LLM call the provider rate-limited is retried before the run fails
+    pub rate_limit_retries: u32,
+    /// When the provider rejects a call as longer than the model's context
+    /// window, drop the oldest messages after the system prompt and retry
+    /// it once, sending a `context_truncated` warning (default: the run fails)
+    pub truncate_on_context_overflow: bool,
+    /// Split large message deltas into paced events (`None`: forward as received)
+    pub smoothing: Option<SmoothingConfig>,
+    /// How events are delivered on the run's channel (default: one event
+    /// per delta)
+    pub streaming: StreamingConfig,
+    /// Emit `StreamEvent::Thinking` while the model works before answering
+    /// (`None`: no heartbeats)
+    pub thinking_heartbeat: Option<ThinkingHeartbeatConfig>,
+    /// Wrap tool results sent to the provider in per-run delimited blocks
+    /// the model is told to treat as data (`None`: sent as returned)
+    pub tool_content_isolation: Option<ToolContentIsolationConfig>,
+    /// Emit `StreamEvent::Activity` lines for tool calls (`None`: no
+    /// activity lines unless a describer is set on the builder)
+    pub activity: Option<ActivityConfig>,
+    /// Tool choice for each run's first LLM call, e.g.
+    /// `ToolChoice::function("search_knowledge_base")` to look something up
+    /// before answering; later calls use auto (`None`: auto throughout).
+    /// `LLMConfig::tool_choice` overrides it per run
+    pub tool_choice: Option<ToolChoice>,
+    /// Ask the model to mark facts from tool results with `[n]` and report
+    /// them as `StreamEvent::Citations` on the final answer
+    pub cite_sources: bool,
+    /// Whether the model may ask for several tool calls in one turn
+    /// (`None`: the provider's default). `Some(false)` suits stateful tools
+    /// that must see each other's results
+    pub parallel_tool_calls: Option<bool>,
+}
+
+/// Pacing of bursty message deltas (see `GraphConfig::smoothing`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmoothingConfig {
+    /// Size, in characters, that large deltas are split into
+    pub target_chars_per_event: usize,
+    /// Longest any piece of text is held back
+    pub max_delay_ms: u64,
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        Self {
+            target_chars_per_event: 64,
+            max_delay_ms: 250,
+        }
+    }
+}
+
+/// Delivery of events to the run's consumer (see `GraphConfig::streaming`)
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamingConfig {
+    /// Join consecutive message and reasoning deltas into fewer events
+    /// (`None`: one event per delta)
+    pub batch: Option<BatchPolicy>,
+}
+
+/// When held deltas are sent as one event (see `StreamingConfig::batch`)
+///
+/// Any other event sends what is held first, whatever the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchPolicy {
+    /// Every this many milliseconds
+    FixedInterval(u64),
+    /// Once the held text reaches this many characters; a delta of
+    /// another kind, agent or node sends what is held first
+    MinChars(usize),
+    /// On an interval between 20 and 200ms, longer while the consumer is
+    /// slow to take events
+    Adaptive,
+}
+
+/// Cadence of thinking heartbeats (see `GraphConfig::thinking_heartbeat`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThinkingHeartbeatConfig {
+    /// Wait before the first heartbeat; models that answer sooner send none
+    pub first_after_ms: u64,
+    /// Time between later heartbeats
+    pub interval_ms: u64,
+}
+
+impl Default for ThinkingHeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            first_after_ms: 3000,
+            interval_ms: 3000,
+        }
+    }
+}
+
+/// Handling of tool output (see `GraphConfig::tool_content_isolation`)
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolContentIsolationConfig {
+    /// What to do with lines that read like instructions to the assistant
+    pub suspicious_content: SuspiciousContentAction,
+}
+
+/// Activity lines for tool calls (see `GraphConfig::activity`)
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityConfig {
+    /// Line template per tool name, e.g. `search = "Searching for '{query}'"`
+    pub templates: BTreeMap<String, String>,
+    /// Also store each line on the persisted tool call (`DBMessage::summary`)
+    pub persist: bool,
+}
+
+/// Treatment of tool output lines that look like a prompt injection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuspiciousContentAction {
+    /// Wrap only
+    Allow,
+    /// Keep the lines and add a warning note inside the block
+    #[default]
+    Flag,
+    /// Replace the lines with a placeholder
+    Redact,
 }
 
 impl Default for GraphConfig {
@@ -28,6 +175,20 @@ impl Default for GraphConfig {
             max_iterations: 50,
             execution_timeout: Duration::from_secs(300),
             enable_cancellation: true,
+            event_channel_capacity: 1000,
+            agent_name: "assistant".to_string(),
+            send_raw_end_user_id: false,
+            reasoning_summary_model: None,
+            rate_limit_retries: 3,
+            truncate_on_context_overflow: false,
+            smoothing: None,
+            streaming: StreamingConfig::default(),
+            thinking_heartbeat: None,
+            tool_content_isolation: None,
+            activity: None,
+            tool_choice: None,
+            cite_sources: false,
+            parallel_tool_calls: None,
         }
     }
 }
@@ -51,6 +212,139 @@ impl GraphConfig {
         self.enable_cancellation = enabled;
         self
     }
+
+    pub fn with_event_channel_capacity(mut self, capacity: usize) -> Self {
+        self.event_channel_capacity = capacity;
+        self
+    }
+
+    pub fn with_agent_name(mut self, name: impl Into<String>) -> Self {
+        self.agent_name = name.into();
+        self
+    }
+
+    pub fn with_raw_end_user_id(mut self, enabled: bool) -> Self {
+        self.send_raw_end_user_id = enabled;
+        self
+    }
+
+    pub fn with_reasoning_summary_model(mut self, model: impl Into<String>) -> Self {
+        self.reasoning_summary_model = Some(model.into());
+        self
+    }
+
+    pub fn with_rate_limit_retries(mut self, retries: u32) -> Self {
+        self.rate_limit_retries = retries;
+        self
+    }
+
+    pub fn with_truncate_on_context_overflow(mut self, enabled: bool) -> Self {
+        self.truncate_on_context_overflow = enabled;
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: SmoothingConfig) -> Self {
+        self.smoothing = Some(smoothing);
+        self
+    }
+
+    pub fn with_streaming(mut self, streaming: StreamingConfig) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    pub fn with_thinking_heartbeat(mut self, heartbeat: ThinkingHeartbeatConfig) -> Self {
+        self.thinking_heartbeat = Some(heartbeat);
+        self
+    }
+
+    pub fn with_tool_content_isolation(mut self, isolation: ToolContentIsolationConfig) -> Self {
+        self.tool_content_isolation = Some(isolation);
+        self
+    }
+
+    pub fn with_activity(mut self, activity: ActivityConfig) -> Self {
+        self.activity = Some(activity);
+        self
+    }
+
+    pub fn with_tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.tool_choice = Some(choice);
+        self
+    }
+
+    pub fn with_cite_sources(mut self, enabled: bool) -> Self {
+        self.cite_sources = enabled;
+        self
+    }
+
+    pub fn with_parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = Some(enabled);
+        self
+    }
+
+    /// End-user identifier reported to the provider for `user_id`
+    pub fn end_user_id(&self, user_id: &str) -> String {
+        if self.send_raw_end_user_id {
+            user_id.to_string()
+        } else {
+            praxis_persist::content_sha256(user_id)
+        }
+    }
+
+    /// Validate settings, naming the offending field on error
+    pub fn validate(&self) -> Result<()> {
+        if self.max_iterations == 0 {
+            bail!("max_iterations must be greater than 0");
+        }
+        if self.execution_timeout.is_zero() {
+            bail!("execution_timeout_secs must be greater than 0");
+        }
+        if self.event_channel_capacity == 0 {
+            bail!("event_channel_capacity must be greater than 0");
+        }
+        if self.agent_name.trim().is_empty() {
+            bail!("agent_name must not be empty");
+        }
+        if self.smoothing.as_ref().is_some_and(|s| s.target_chars_per_event == 0) {
+            bail!("smoothing.target_chars_per_event must be greater than 0");
+        }
+        match self.streaming.batch {
+            Some(BatchPolicy::FixedInterval(0)) => bail!("streaming.batch interval must be greater than 0"),
+            Some(BatchPolicy::MinChars(0)) => bail!("streaming.batch min_chars must be greater than 0"),
+            _ => {}
+        }
+        if self.thinking_heartbeat.as_ref().is_some_and(|h| h.interval_ms == 0) {
+            bail!("thinking_heartbeat.interval_ms must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+/// Serde helper reading `reasoning_effort` as it was stored when it was a
+/// plain string: unknown levels meant medium, so they still do
+mod lenient_effort {
+    use praxis_llm::ReasoningEffort;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<ReasoningEffort>, D::Error> {
+        let effort = Option::<String>::deserialize(deserializer)?;
+        Ok(effort.map(|effort| effort.parse().unwrap_or(ReasoningEffort::Medium)))
+    }
+}
+
+/// Serde helper storing a `Duration` as whole seconds
+mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,8 +354,30 @@ pub struct LLMConfig {
     pub provider: Provider,
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reasoning_effort: Option<String>,
+    /// Reasoning calls' effort (default: medium when `reasoning_summary` is set)
+    #[serde(default, deserialize_with = "lenient_effort::deserialize", skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Reasoning calls' summary mode (default: auto when `reasoning_effort` is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_summary: Option<SummaryMode>,
+    /// This run's `GraphConfig::tool_choice`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Sent on every LLM call of the run, for reproducible outputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Sequences that end each LLM call's generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Spoken answers from audio models, streamed as `StreamEvent::Audio`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioOptions>,
+    /// Raw fields added to every LLM request body of the run (see
+    /// `ChatOptions::extra_body`); known fields win over them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_body: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 impl LLMConfig {
@@ -72,6 +388,13 @@ impl LLMConfig {
             temperature: None,
             max_tokens: None,
             reasoning_effort: None,
+            reasoning_summary: None,
+            tool_choice: None,
+            top_p: None,
+            seed: None,
+            stop: None,
+            audio: None,
+            extra_body: None,
         }
     }
 
@@ -90,8 +413,43 @@ impl LLMConfig {
         self
     }
 
-    pub fn with_reasoning_effort(mut self, effort: impl Into<String>) -> Self {
-        self.reasoning_effort = Some(effort.into());
+    pub fn with_reasoning_effort(mut self, effort: ReasoningEffort) -> Self {
+        self.reasoning_effort = Some(effort);
+        self
+    }
+
+    pub fn with_reasoning_summary(mut self, summary: SummaryMode) -> Self {
+        self.reasoning_summary = Some(summary);
+        self
+    }
+
+    pub fn with_tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.tool_choice = Some(choice);
+        self
+    }
+
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_stop(mut self, sequences: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.stop = Some(sequences.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_audio(mut self, audio: AudioOptions) -> Self {
+        self.audio = Some(audio);
+        self
+    }
+
+    pub fn with_extra_body(mut self, body: serde_json::Map<String, serde_json::Value>) -> Self {
+        self.extra_body = Some(body);
         self
     }
 }
@@ -104,11 +462,18 @@ impl Default for LLMConfig {
             temperature: Some(1.0),
             max_tokens: Some(4096),
             reasoning_effort: None,
+            reasoning_summary: None,
+            tool_choice: None,
+            top_p: None,
+            seed: None,
+            stop: None,
+            audio: None,
+            extra_body: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContextPolicy {
     LastK { k: usize },
@@ -121,3 +486,173 @@ impl Default for ContextPolicy {
     }
 }
 
+impl ContextPolicy {
+    /// The part of `messages` sent to the model
+    ///
+    /// `LastK` keeps the leading system messages and the last `k` others,
+    /// dropping tool results whose call was cut off.
+    pub fn apply(&self, messages: &[praxis_llm::Message]) -> Vec<praxis_llm::Message> {
+        self.window(messages).into_iter().cloned().collect()
+    }
+
+    /// Like `apply`, borrowing the kept messages instead of copying them
+    pub fn window<'a>(&self, messages: &'a [praxis_llm::Message]) -> Vec<&'a praxis_llm::Message> {
+        let (leading_system, start) = self.bounds(messages);
+        messages[..leading_system].iter().chain(&messages[start..]).collect()
+    }
+
+    /// Replace the body of every tool result outside the window with a short
+    /// reference, returning how many were compacted
+    ///
+    /// The model never sees those results again, so a long run only keeps
+    /// the ones it may still be sent. Persisted messages are not affected:
+    /// the original stays in the thread history.
+    pub fn compact(&self, messages: &mut [praxis_llm::Message]) -> usize {
+        use praxis_llm::{Content, Message};
+
+        let (leading_system, start) = self.bounds(messages);
+        let mut compacted = 0;
+        for message in &mut messages[leading_system..start] {
+            let Message::Tool { tool_call_id, content } = message else {
+                continue;
+            };
+            let bytes = match content {
+                Content::Text(text) if text.starts_with(COMPACTED_TOOL_RESULT) => continue,
+                Content::Text(text) => text.len(),
+                Content::Parts(parts) => parts
+                    .iter()
+                    .map(|part| match part {
+                        praxis_llm::types::ContentPart::Text { text } => text.len(),
+                        praxis_llm::types::ContentPart::ImageUrl { url, .. } => url.len(),
+                        praxis_llm::types::ContentPart::ImageBase64 { data, .. } => data.len(),
+                        praxis_llm::types::ContentPart::Audio { data_base64, .. } => data_base64.len(),
+                    })
+                    .sum(),
+            };
+            let reference = format!("{} {}: {} bytes, kept in the thread history]", COMPACTED_TOOL_RESULT, tool_call_id, bytes);
+            if bytes > reference.len() {
+                *content = Content::text(reference);
+                compacted += 1;
+            }
+        }
+        compacted
+    }
+
+    /// Leading system and developer messages, and where the rest of the
+    /// window starts
+    fn bounds(&self, messages: &[praxis_llm::Message]) -> (usize, usize) {
+        use praxis_llm::Message;
+
+        let leading_system = messages
+            .iter()
+            .take_while(|message| matches!(message, Message::System { .. } | Message::Developer { .. }))
+            .count();
+        let Self::LastK { k } = self else {
+            return (leading_system, leading_system);
+        };
+        let mut start = messages.len().saturating_sub(*k).max(leading_system);
+        while matches!(messages.get(start), Some(Message::Tool { .. })) {
+            start += 1;
+        }
+        (leading_system, start)
+    }
+}
+
+/// Start of the body `ContextPolicy::compact` leaves in place of a tool result
+const COMPACTED_TOOL_RESULT: &str = "[compacted tool result";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_config_round_trip() {
+        let config = GraphConfig::new()
+            .with_max_iterations(7)
+            .with_timeout(Duration::from_secs(42))
+            .with_cancellation(false)
+            .with_event_channel_capacity(16);
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["execution_timeout_secs"], 42);
+
+        let parsed: GraphConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn test_graph_config_partial_uses_defaults() {
+        let parsed: GraphConfig = serde_json::from_str(r#"{"max_iterations": 3}"#).unwrap();
+
+        assert_eq!(parsed.max_iterations, 3);
+        assert_eq!(parsed.execution_timeout, GraphConfig::default().execution_timeout);
+        assert_eq!(parsed.event_channel_capacity, 1000);
+    }
+
+    #[test]
+    fn test_graph_config_validation_names_field() {
+        let err = GraphConfig::new().with_max_iterations(0).validate().unwrap_err();
+        assert!(err.to_string().contains("max_iterations"));
+
+        let err = GraphConfig::new().with_event_channel_capacity(0).validate().unwrap_err();
+        assert!(err.to_string().contains("event_channel_capacity"));
+    }
+
+    #[test]
+    fn test_end_user_id_hashed_unless_raw() {
+        let hashed = GraphConfig::new().end_user_id("alice@example.com");
+        assert_eq!(hashed.len(), 64);
+        assert!(!hashed.contains("alice"));
+
+        let raw = GraphConfig::new().with_raw_end_user_id(true).end_user_id("alice@example.com");
+        assert_eq!(raw, "alice@example.com");
+    }
+
+    #[test]
+    fn test_last_k_keeps_system_and_skips_orphaned_tool_results() {
+        use praxis_llm::Message;
+
+        let tool = |id: &str| Message::tool(id, "42");
+        let messages = vec![
+            Message::system("You are helpful"),
+            Message::human("one"),
+            Message::human("two"),
+            tool("call_1"),
+            Message::human("three"),
+        ];
+
+        let kept = ContextPolicy::LastK { k: 2 }.apply(&messages);
+        assert_eq!(kept.len(), 2);
+        assert!(matches!(&kept[0], Message::System { .. }));
+        assert!(matches!(&kept[1], Message::Human { .. }));
+
+        assert_eq!(ContextPolicy::LastK { k: 10 }.apply(&messages).len(), 5);
+        assert_eq!(ContextPolicy::AllMessages.apply(&messages).len(), 5);
+    }
+
+    #[test]
+    fn test_compact_replaces_tool_results_outside_the_window() {
+        use praxis_llm::Message;
+
+        let tool = |id: &str, text: &str| Message::tool(id, text);
+        let page = "x".repeat(10_000);
+        let mut messages = vec![
+            Message::system("You are helpful"),
+            Message::human("research"),
+            tool("call_1", &page),
+            tool("call_2", "short"),
+            Message::human("more"),
+            tool("call_3", &page),
+        ];
+        let policy = ContextPolicy::LastK { k: 2 };
+
+        assert_eq!(policy.compact(&mut messages), 1);
+        let Message::Tool { content, .. } = &messages[2] else { unreachable!() };
+        assert_eq!(content.as_text(), Some("[compacted tool result call_1: 10000 bytes, kept in the thread history]"));
+        assert!(matches!(&messages[3], Message::Tool { content, .. } if content.as_text() == Some("short")));
+        assert!(matches!(&messages[5], Message::Tool { content, .. } if content.as_text() == Some(page.as_str())));
+        // Already compacted results are left alone, and so is everything under AllMessages
+        assert_eq!(policy.compact(&mut messages), 0);
+        assert_eq!(ContextPolicy::AllMessages.compact(&mut messages), 0);
+    }
+}
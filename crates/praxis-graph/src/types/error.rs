@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Category of a fatal graph error, carried on `StreamEvent::Error`
+///
+/// The serialized form is the stable error code clients match on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphErrorKind {
+    /// The LLM provider request or stream failed
+    ProviderError,
+    /// The run hit `GraphConfig::max_iterations`
+    MaxIterations,
+    /// The run exceeded `GraphConfig::execution_timeout`
+    ExecutionTimeout,
+    /// The run was cancelled
+    Cancelled,
+    /// The provider's content filter stopped the answer
+    ContentFiltered,
+    /// Moderation flagged the run's input or an answer
+    /// (`ModerationPolicy::Block`)
+    ModerationBlocked,
+    /// Any other failure
+    #[default]
+    InternalError,
+}
+
+impl GraphErrorKind {
+    pub const ALL: [GraphErrorKind; 7] = [
+        Self::ProviderError,
+        Self::MaxIterations,
+        Self::ExecutionTimeout,
+        Self::Cancelled,
+        Self::ContentFiltered,
+        Self::ModerationBlocked,
+        Self::InternalError,
+    ];
+
+    /// Stable machine-readable code (same as the serialized form)
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::ProviderError => "provider_error",
+            Self::MaxIterations => "max_iterations",
+            Self::ExecutionTimeout => "execution_timeout",
+            Self::Cancelled => "cancelled",
+            Self::ContentFiltered => "content_filtered",
+            Self::ModerationBlocked => "moderation_blocked",
+            Self::InternalError => "internal_error",
+        }
+    }
+
+    /// Whether retrying the same request may succeed
+    pub const fn is_retryable(&self) -> bool {
+        matches!(self, Self::ProviderError | Self::ExecutionTimeout)
+    }
+
+    /// Kind of an error returned by graph execution (`InternalError` if untagged)
+    pub fn of(error: &anyhow::Error) -> Self {
+        error
+            .downcast_ref::<GraphError>()
+            .map(|e| e.kind)
+            .unwrap_or_default()
+    }
+}
+
+/// Error tagged with a `GraphErrorKind`, propagated through `anyhow`
+#[derive(Debug)]
+pub struct GraphError {
+    pub kind: GraphErrorKind,
+    pub message: String,
+}
+
+impl GraphError {
+    pub fn new(kind: GraphErrorKind, message: impl fmt::Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+
+    /// Tag an LLM provider failure
+    ///
+    /// The tag is added as context, so the provider's `LLMError` can still be
+    /// found with `LLMError::of`.
+    pub fn provider(error: anyhow::Error) -> anyhow::Error {
+        let message = error.to_string();
+        error.context(Self::new(GraphErrorKind::ProviderError, message))
+    }
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use praxis_llm::LLMError;
+
+    #[test]
+    fn test_code_matches_serialized_form() {
+        for kind in GraphErrorKind::ALL {
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.code());
+        }
+    }
+
+    #[test]
+    fn test_kind_recovered_through_context() {
+        let error = GraphError::provider(anyhow::anyhow!("connection reset")).context("LLM node failed");
+        assert_eq!(GraphErrorKind::of(&error), GraphErrorKind::ProviderError);
+        assert_eq!(error.root_cause().to_string(), "connection reset");
+        assert_eq!(GraphErrorKind::of(&anyhow::anyhow!("boom")), GraphErrorKind::InternalError);
+    }
+
+    #[test]
+    fn test_provider_tag_keeps_the_llm_error() {
+        let error = GraphError::provider(LLMError::AuthFailed { message: "bad key".to_string() }.into());
+        assert_eq!(GraphErrorKind::of(&error), GraphErrorKind::ProviderError);
+        assert_eq!(LLMError::of(&error).map(LLMError::code), Some("auth_failed"));
+        assert_eq!(error.to_string(), "Provider rejected the credentials: bad key");
+    }
+}
@@ -1,4 +1,11 @@
+use praxis_llm::{OptionAdjusted, ReasoningKind, ResponseMetadata};
+use praxis_persist::{Citation, ToolVerification};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::config::LLMConfig;
+use super::error::GraphErrorKind;
+use crate::moderation::ModerationStage;
+use crate::node::NodeType;
 
 /// Unified StreamEvent for Graph orchestration
 /// 
@@ -12,16 +19,63 @@ pub enum StreamEvent {
         run_id: String,
         conversation_id: String,
         timestamp: i64,
+        /// LLM settings the run actually uses
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        llm_config: Option<LLMConfig>,
+        /// `GraphInput::variables` of the run
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        variables: HashMap<String, serde_json::Value>,
+        /// Settings of `llm_config` the model doesn't support, dropped from requests
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        option_adjustments: Vec<OptionAdjusted>,
     },
     
     /// Internal reasoning from LLM (streamed token-by-token)
     Reasoning {
         content: String,
+        /// Whether the model's raw reasoning or a summary of it
+        #[serde(default, skip_serializing_if = "ReasoningKind::is_raw")]
+        kind: ReasoningKind,
+        /// Agent that produced this event
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    
+    /// Short summary of a reasoning item, from the provider or generated
+    /// after the fact (`GraphConfig::reasoning_summary_model`)
+    ReasoningSummary {
+        /// Id of the reasoning output (`DBMessage::reasoning_id`)
+        item_id: String,
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
     },
     
     /// Response message from LLM (streamed token-by-token)
     Message {
         content: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    
+    /// Chunk of the LLM's spoken answer (base64, in the format
+    /// `ChatOptions::audio` asked for); the transcript streams as `Message`
+    Audio {
+        data_base64: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
     },
     
     /// LLM decided to call a tool (streamed incrementally)
@@ -33,6 +87,40 @@ pub enum StreamEvent {
         name: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         arguments: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    
+    /// A tool call with its arguments fully assembled
+    ///
+    /// Sent once the LLM call's stream has ended, so after all of the
+    /// call's `ToolCall` deltas, and before the tool runs and its
+    /// `ToolResult`. Calls come in the order the model made them.
+    ToolCallComplete {
+        id: String,
+        name: String,
+        /// Arguments JSON as the model sent it
+        arguments: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    
+    /// Human-readable line for a tool call whose arguments are complete
+    /// (see `crate::activity`)
+    Activity {
+        tool_call_id: String,
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
     },
     
     /// Tool execution completed
@@ -41,12 +129,106 @@ pub enum StreamEvent {
         result: String,
         is_error: bool,
         duration_ms: u64,
+        /// MCP server that ran the call, `_local` for a local tool (None
+        /// when the call didn't run or failed)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_server: Option<String>,
+        /// Read call that checked the effect of a tool configured with
+        /// `verify_with`; a failed check makes the result an error
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        verification: Option<ToolVerification>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    
+    /// Tool results the final answer cites by marker, sent after its last
+    /// message delta (see `GraphConfig::cite_sources`)
+    Citations {
+        citations: Vec<Citation>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Node and loop iteration that produced the event, stamped by the graph
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    
+    /// Control passed between agents (subagent invoked or returned)
+    Handoff {
+        from: String,
+        to: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    
+    /// The provider client dropped request options the model doesn't support
+    OptionsAdjusted {
+        adjustments: Vec<OptionAdjusted>,
+    },
+    
+    /// Request id and rate-limit state of the LLM call, from the provider's
+    /// response headers; the id is what provider support asks for
+    ResponseMetadata {
+        metadata: ResponseMetadata,
+    },
+    
+    /// The provider is rate limiting; the LLM call is retried after a wait
+    Retrying {
+        /// Why the call is retried (`"rate_limited"`)
+        reason: String,
+        in_seconds: u64,
+        /// 1 for the first retry
+        attempt: u32,
+    },
+    
+    /// The model is still working on its answer (reasoning, or nothing
+    /// streamed yet); repeats until the first message or tool call delta
+    Thinking {
+        /// Since the LLM stream opened
+        elapsed_ms: u64,
+    },
+    
+    /// Tokens the LLM call used, as reported by the provider; sent before
+    /// `Done` when the provider reports usage
+    Usage {
+        input_tokens: u32,
+        output_tokens: u32,
+        total_tokens: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reasoning_tokens: Option<u32>,
+        /// Input tokens served from the provider's prompt cache
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cached_tokens: Option<u32>,
     },
     
     /// LLM streaming completed
     Done {
+        /// Chat completions name: `stop`, `tool_calls`, `length` or `content_filter`
         #[serde(skip_serializing_if = "Option::is_none")]
         finish_reason: Option<String>,
+        /// What the run's priced LLM calls cost so far, this one included
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cost_usd: Option<f64>,
+    },
+    
+    /// Something the caller should know about that doesn't fail the run,
+    /// e.g. an answer cut off at the output token limit
+    Warning {
+        /// Stable code, e.g. `truncated`
+        code: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        node_id: Option<String>,
+    },
+    
+    /// Moderation flagged the run's input or an answer under
+    /// `ModerationPolicy::Annotate`; the run goes on
+    ModerationWarning {
+        stage: ModerationStage,
+        /// Flagged categories, e.g. `harassment`
+        categories: Vec<String>,
     },
     
     /// Fatal error occurred
@@ -54,24 +236,116 @@ pub enum StreamEvent {
         message: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         node_id: Option<String>,
+        /// Error category (stable code + retryability)
+        #[serde(default)]
+        kind: GraphErrorKind,
+        /// `LLMError::code` when the provider failed, e.g. `rate_limited`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_code: Option<String>,
     },
     
     /// Graph execution completed
     EndStream {
         status: String,
         total_duration_ms: u64,
+        /// `finish_reason` of the run's last LLM call
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<String>,
+        /// What the run's LLM calls cost, when the graph has a cost
+        /// calculator and the model a price
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cost_usd: Option<f64>,
     },
 }
 
+impl StreamEvent {
+    /// Attribute an agent-produced event to `agent` (other events are unchanged)
+    pub fn with_agent(mut self, name: impl Into<String>) -> Self {
+        match &mut self {
+            Self::Reasoning { agent, .. }
+            | Self::ReasoningSummary { agent, .. }
+            | Self::Message { agent, .. }
+            | Self::Audio { agent, .. }
+            | Self::ToolCall { agent, .. }
+            | Self::ToolCallComplete { agent, .. }
+            | Self::Activity { agent, .. }
+            | Self::Citations { agent, .. }
+            | Self::ToolResult { agent, .. } => *agent = Some(name.into()),
+            _ => {}
+        }
+        self
+    }
+
+    /// Stamp an agent-produced event with where it came from (other events
+    /// are unchanged)
+    pub fn with_provenance(mut self, stamp: Provenance) -> Self {
+        match &mut self {
+            Self::Reasoning { provenance, .. }
+            | Self::ReasoningSummary { provenance, .. }
+            | Self::Message { provenance, .. }
+            | Self::Audio { provenance, .. }
+            | Self::ToolCall { provenance, .. }
+            | Self::ToolCallComplete { provenance, .. }
+            | Self::Activity { provenance, .. }
+            | Self::Citations { provenance, .. }
+            | Self::ToolResult { provenance, .. } => *provenance = Some(stamp),
+            _ => {}
+        }
+        self
+    }
+
+    pub fn provenance(&self) -> Option<Provenance> {
+        match self {
+            Self::Reasoning { provenance, .. }
+            | Self::ReasoningSummary { provenance, .. }
+            | Self::Message { provenance, .. }
+            | Self::Audio { provenance, .. }
+            | Self::ToolCall { provenance, .. }
+            | Self::ToolCallComplete { provenance, .. }
+            | Self::Activity { provenance, .. }
+            | Self::Citations { provenance, .. }
+            | Self::ToolResult { provenance, .. } => *provenance,
+            _ => None,
+        }
+    }
+
+    /// Agent that produced this event, if attributed
+    pub fn agent(&self) -> Option<&str> {
+        match self {
+            Self::Reasoning { agent, .. }
+            | Self::ReasoningSummary { agent, .. }
+            | Self::Message { agent, .. }
+            | Self::Audio { agent, .. }
+            | Self::ToolCall { agent, .. }
+            | Self::ToolCallComplete { agent, .. }
+            | Self::Activity { agent, .. }
+            | Self::Citations { agent, .. }
+            | Self::ToolResult { agent, .. } => agent.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Where in a run an event was produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub node: NodeType,
+    /// Graph loop iteration, 0 for the first LLM call
+    pub iteration: u32,
+}
+
 /// Automatic conversion from LLM StreamEvent to Graph StreamEvent
 impl From<praxis_llm::StreamEvent> for StreamEvent {
     fn from(event: praxis_llm::StreamEvent) -> Self {
         match event {
-            praxis_llm::StreamEvent::Reasoning { content } => {
-                Self::Reasoning { content }
+            praxis_llm::StreamEvent::Reasoning { content, kind, .. } => {
+                Self::Reasoning { content, kind, agent: None, provenance: None }
             }
-            praxis_llm::StreamEvent::Message { content } => {
-                Self::Message { content }
+            praxis_llm::StreamEvent::ReasoningSummary { item_id, text } => {
+                Self::ReasoningSummary { item_id, text, agent: None, provenance: None }
+            }
+            praxis_llm::StreamEvent::Message { content, .. } => {
+                Self::Message { content, agent: None, provenance: None }
             }
             praxis_llm::StreamEvent::ToolCall {
                 index,
@@ -83,10 +357,20 @@ impl From<praxis_llm::StreamEvent> for StreamEvent {
                 id,
                 name,
                 arguments,
+                agent: None,
+                provenance: None,
             },
-            praxis_llm::StreamEvent::Done { finish_reason } => {
-                Self::Done { finish_reason }
+            praxis_llm::StreamEvent::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens } => {
+                Self::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens }
+            }
+            praxis_llm::StreamEvent::Done { finish_reason, .. } => {
+                Self::Done { finish_reason, cost_usd: None }
             }
+            praxis_llm::StreamEvent::OptionsAdjusted { adjustments } => {
+                Self::OptionsAdjusted { adjustments }
+            }
+            praxis_llm::StreamEvent::ResponseMetadata { metadata } => Self::ResponseMetadata { metadata },
+            praxis_llm::StreamEvent::Audio { data_base64 } => Self::Audio { data_base64, agent: None, provenance: None },
         }
     }
 }
@@ -107,21 +391,21 @@ impl praxis_persist::StreamEventExtractor for StreamEvent {
     
     fn reasoning_content(&self) -> Option<&str> {
         match self {
-            Self::Reasoning { content } => Some(content),
+            Self::Reasoning { content, .. } => Some(content),
             _ => None,
         }
     }
     
     fn message_content(&self) -> Option<&str> {
         match self {
-            Self::Message { content } => Some(content),
+            Self::Message { content, .. } => Some(content),
             _ => None,
         }
     }
     
     fn tool_call_info(&self) -> Option<(u32, Option<&str>, Option<&str>, Option<&str>)> {
         match self {
-            Self::ToolCall { index, id, name, arguments } => {
+            Self::ToolCall { index, id, name, arguments, .. } => {
                 Some((
                     *index,
                     id.as_deref(),
@@ -132,5 +416,9 @@ impl praxis_persist::StreamEventExtractor for StreamEvent {
             _ => None,
         }
     }
+    
+    fn agent(&self) -> Option<&str> {
+        StreamEvent::agent(self)
+    }
 }
 
@@ -2,9 +2,14 @@ pub mod state;
 pub mod config;
 pub mod events;
 pub mod output;
+pub mod error;
 
 pub use state::{GraphState, GraphInput};
-pub use config::{GraphConfig, LLMConfig, ContextPolicy, Provider};
-pub use events::StreamEvent;
+pub use config::{
+    GraphConfig, LLMConfig, ContextPolicy, Provider, SmoothingConfig, StreamingConfig, BatchPolicy, SuspiciousContentAction, ThinkingHeartbeatConfig,
+    ToolContentIsolationConfig, ActivityConfig,
+};
+pub use events::{Provenance, StreamEvent};
 pub use output::GraphOutput;
+pub use error::{GraphError, GraphErrorKind};
 
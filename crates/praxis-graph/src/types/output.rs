@@ -1,4 +1,5 @@
 use praxis_llm::ToolCall;
+use praxis_persist::Citation;
 
 /// Graph output items from LLM execution
 /// 
@@ -10,12 +11,16 @@ pub enum GraphOutput {
     Reasoning {
         id: String,
         content: String,
+        /// Short summary for collapsed display, when one is available
+        summary: Option<String>,
     },
     /// Regular message output
     Message {
         id: String,
         content: String,
         tool_calls: Option<Vec<ToolCall>>,
+        /// Tool results the answer cites (see `GraphConfig::cite_sources`)
+        citations: Vec<Citation>,
     },
 }
 
@@ -24,14 +29,24 @@ impl GraphOutput {
         Self::Reasoning {
             id: id.into(),
             content: content.into(),
+            summary: None,
         }
     }
     
+    /// Attach a reasoning summary (no-op for messages)
+    pub fn with_summary(mut self, text: impl Into<String>) -> Self {
+        if let Self::Reasoning { summary, .. } = &mut self {
+            *summary = Some(text.into());
+        }
+        self
+    }
+    
     pub fn message(id: impl Into<String>, content: impl Into<String>) -> Self {
         Self::Message {
             id: id.into(),
             content: content.into(),
             tool_calls: None,
+            citations: Vec::new(),
         }
     }
     
@@ -44,6 +59,7 @@ impl GraphOutput {
             id: id.into(),
             content: content.into(),
             tool_calls: Some(tool_calls),
+            citations: Vec::new(),
         }
     }
     
@@ -1,11 +1,16 @@
 use crate::types::config::{LLMConfig, ContextPolicy};
 use crate::types::GraphOutput;
-use praxis_llm::{Message, ToolCall};
+use praxis_core::{Clock, IdGen, SystemClock, UuidIds};
+use praxis_llm::{CostBreakdown, Message, ResponseMetadata, TokenUsage, Tool, ToolCall};
+use praxis_mcp::LocalTool;
+use praxis_persist::ToolVerification;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct GraphState {
+    /// Caller's conversation identifier; persistence uses `PersistenceContext::thread_id`
     pub conversation_id: String,
     pub run_id: String,
     pub messages: Vec<Message>,
@@ -13,6 +18,33 @@ pub struct GraphState {
     pub variables: HashMap<String, serde_json::Value>,
     #[allow(dead_code)]
     pub last_outputs: Option<Vec<GraphOutput>>,
+    /// Timestamps persisted messages and stream events
+    pub clock: Arc<dyn Clock>,
+    /// Mints run, message and output ids
+    pub ids: Arc<dyn IdGen>,
+    /// Tool rounds completed so far in this run
+    pub tool_rounds: u32,
+    /// Whether the next LLM call may request tools (set by the router)
+    pub tools_enabled: bool,
+    /// Activity lines to store on this run's persisted tool calls, by tool call id
+    pub tool_activity: HashMap<String, String>,
+    /// Server that ran each of this run's executed tool calls, by tool call id
+    pub tool_servers: HashMap<String, String>,
+    /// Verification of each of this run's verified tool calls, by tool call id
+    pub tool_verifications: HashMap<String, ToolVerification>,
+    /// Tokens the latest LLM call used (None until a provider reports usage)
+    pub last_usage: Option<TokenUsage>,
+    /// What the latest LLM call cost (None without a cost calculator, usage
+    /// or a price for the model)
+    pub last_cost: Option<CostBreakdown>,
+    /// What this run's priced LLM calls cost in total
+    pub cost: Option<CostBreakdown>,
+    /// `finish_reason` of the latest LLM call (`stop`, `tool_calls`,
+    /// `length`, `content_filter`), None until a provider reports one
+    pub last_finish_reason: Option<String>,
+    /// Request id and rate-limit state of the latest LLM call, when its
+    /// provider sends them
+    pub last_response_metadata: Option<ResponseMetadata>,
 }
 
 impl GraphState {
@@ -29,17 +61,46 @@ impl GraphState {
             llm_config,
             variables: HashMap::new(),
             last_outputs: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidIds),
+            tool_rounds: 0,
+            tools_enabled: true,
+            tool_activity: HashMap::new(),
+            tool_servers: HashMap::new(),
+            tool_verifications: HashMap::new(),
+            last_usage: None,
+            last_cost: None,
+            cost: None,
+            last_finish_reason: None,
+            last_response_metadata: None,
         }
     }
 
     pub fn from_input(input: GraphInput) -> Self {
+        Self::from_input_with(input, Arc::new(SystemClock), Arc::new(UuidIds))
+    }
+
+    /// Build the state of a run whose timestamps and ids come from `clock` and `ids`
+    pub fn from_input_with(input: GraphInput, clock: Arc<dyn Clock>, ids: Arc<dyn IdGen>) -> Self {
         Self {
             conversation_id: input.conversation_id,
-            run_id: uuid::Uuid::new_v4().to_string(),
+            run_id: ids.next_id(),
             messages: input.messages,
             llm_config: input.llm_config,
-            variables: HashMap::new(),
+            variables: input.variables,
             last_outputs: None,
+            clock,
+            ids,
+            tool_rounds: 0,
+            tools_enabled: true,
+            tool_activity: HashMap::new(),
+            tool_servers: HashMap::new(),
+            tool_verifications: HashMap::new(),
+            last_usage: None,
+            last_cost: None,
+            cost: None,
+            last_finish_reason: None,
+            last_response_metadata: None,
         }
     }
 
@@ -74,19 +135,33 @@ impl GraphState {
     }
 
     pub fn add_tool_result(&mut self, tool_call_id: String, result: String) {
-        self.messages.push(Message::Tool {
-            tool_call_id,
-            content: praxis_llm::Content::text(result),
-        });
+        self.messages.push(Message::tool(tool_call_id, result));
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphInput {
+    /// Caller's conversation identifier, recorded on events and traces
     pub conversation_id: String,
     pub messages: Vec<Message>,
     pub llm_config: LLMConfig,
     pub context_policy: ContextPolicy,
+    /// Local tools available only to this run (e.g. provided by a context strategy)
+    #[serde(skip)]
+    pub run_tools: Vec<Arc<dyn LocalTool>>,
+    /// MCP tool definitions this run is limited to (e.g. the thread's pinned
+    /// tool profile); None offers every tool the servers provide
+    #[serde(default)]
+    pub pinned_tools: Option<Vec<Tool>>,
+    /// Run-level values recorded on the state and echoed in `InitStream`
+    #[serde(default)]
+    pub variables: HashMap<String, serde_json::Value>,
+    /// Tenant of the caller, passed to the tool authorizer
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// Roles of the caller, passed to the tool authorizer
+    #[serde(default)]
+    pub roles: Vec<String>,
 }
 
 impl GraphInput {
@@ -100,6 +175,11 @@ impl GraphInput {
             messages,
             llm_config,
             context_policy: ContextPolicy::default(),
+            run_tools: Vec::new(),
+            pinned_tools: None,
+            variables: HashMap::new(),
+            tenant_id: None,
+            roles: Vec::new(),
         }
     }
 
@@ -107,5 +187,30 @@ impl GraphInput {
         self.context_policy = policy;
         self
     }
+
+    pub fn with_variable(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.variables.insert(key.into(), value);
+        self
+    }
+
+    pub fn with_run_tools(mut self, tools: impl IntoIterator<Item = Arc<dyn LocalTool>>) -> Self {
+        self.run_tools.extend(tools);
+        self
+    }
+
+    pub fn with_pinned_tools(mut self, tools: Vec<Tool>) -> Self {
+        self.pinned_tools = Some(tools);
+        self
+    }
+
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    pub fn with_roles(mut self, roles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.roles.extend(roles.into_iter().map(Into::into));
+        self
+    }
 }
 
@@ -0,0 +1,404 @@
+//! SSE wire schema of praxis-api's message stream (feature `wire`)
+//!
+//! The API builds its stream and hydrate events from these types and
+//! `praxis-client` parses the stream into them, so server and client share
+//! one definition. Depend on this crate with `default-features = false,
+//! features = ["wire"]` to get the types without the graph runtime.
+
+use chrono::{DateTime, Utc};
+use praxis_llm::{OptionAdjusted, ReasoningKind, ResponseMetadata};
+use praxis_persist::{Citation, ToolVerification};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One event of `POST /threads/:id/messages`, named as on the wire
+///
+/// Hydrated history (`GET /threads/:id/hydrate`) uses the same events, plus
+/// `UserMessage`. The API builds its events from this type too, so the two
+/// sides share one schema. Event names this version doesn't know parse as
+/// `Unknown` rather than failing, so an older client keeps working against
+/// a newer server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// Sidebar entry for the thread, before anything else
+    ThreadInfo(ThreadInfo),
+
+    /// Position in the tenant's run queue; repeats until the run starts
+    Queued { position: usize },
+
+    /// The run started
+    Init(RunInfo),
+
+    Reasoning {
+        content: String,
+        /// Raw reasoning, or a summary of it to show instead; sent only for
+        /// summaries
+        #[serde(default, skip_serializing_if = "ReasoningKind::is_raw")]
+        kind: ReasoningKind,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    ReasoningSummary {
+        item_id: String,
+        text: String,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    /// Answer text, streamed in pieces
+    Message {
+        content: String,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    /// Piece of a spoken answer, base64 in the requested audio format; its
+    /// transcript streams as `Message`
+    Audio {
+        data_base64: String,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    /// Piece of a tool call; the name comes first, arguments follow in pieces
+    ToolCall {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        arguments: Option<String>,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    /// A tool call with its arguments assembled, after all of its
+    /// `ToolCall` pieces and before its `ToolResult`
+    ToolCallComplete {
+        id: String,
+        name: String,
+        arguments: String,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    /// Human-readable line for a tool call about to run
+    Activity {
+        tool_call_id: String,
+        text: String,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    ToolResult {
+        result: String,
+        /// MCP server that ran the call, `_local` for a local tool
+        #[serde(default)]
+        tool_server: Option<String>,
+        /// Read call that checked a write tool's effect
+        #[serde(default)]
+        verification: Option<ToolVerification>,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    /// Tool results the answer cites by `[n]` marker, after its last piece
+    Citations {
+        citations: Vec<Citation>,
+        #[serde(default)]
+        agent: Option<String>,
+        #[serde(default)]
+        provenance: Option<Provenance>,
+    },
+
+    Handoff {
+        from: String,
+        to: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+
+    /// The provider is rate limiting; the LLM call is retried after a wait
+    Retrying {
+        reason: String,
+        in_seconds: u64,
+        attempt: u32,
+    },
+
+    /// The model is still working; repeats until the first output
+    Thinking { elapsed_ms: u64 },
+
+    /// The provider's request id and rate-limit state for an LLM call
+    ResponseMetadata(ResponseMetadata),
+
+    Usage {
+        input_tokens: u32,
+        output_tokens: u32,
+        total_tokens: u32,
+        #[serde(default)]
+        reasoning_tokens: Option<u32>,
+        #[serde(default)]
+        cached_tokens: Option<u32>,
+    },
+
+    /// Something worth showing that doesn't end the run, e.g. a `truncated`
+    /// answer
+    Warning { code: String, message: String },
+
+    /// Moderation flagged the input or an answer; the run goes on
+    ModerationWarning {
+        /// `"input"` or `"output"`
+        stage: String,
+        categories: Vec<String>,
+    },
+
+    /// An LLM call finished; a run with tool calls sends several
+    Done {
+        status: String,
+        /// `stop`, `tool_calls`, `length` or `content_filter`
+        #[serde(default)]
+        finish_reason: Option<String>,
+        /// What the run cost so far, when the server prices calls
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cost_usd: Option<f64>,
+    },
+
+    /// The run failed
+    Error(Problem),
+
+    /// Sent once the run's events end
+    Info {},
+
+    /// A turn of the user (hydrated history only)
+    UserMessage { content: String },
+
+    /// Event this version doesn't know
+    #[serde(skip_deserializing)]
+    Unknown { event: String, data: serde_json::Value },
+}
+
+impl StreamEvent {
+    /// Parse the data of an SSE event named `event`
+    pub fn parse(event: &str, data: &str) -> serde_json::Result<Self> {
+        Self::from_parts(event, serde_json::from_str(data)?)
+    }
+
+    /// Event named `event` with payload `data`; unknown names become
+    /// `Unknown`, while a known event with a malformed payload is an error
+    pub fn from_parts(event: &str, data: serde_json::Value) -> serde_json::Result<Self> {
+        match serde_json::from_value(serde_json::json!({ "event": event, "data": &data })) {
+            Err(e) if e.to_string().starts_with("unknown variant") => {
+                Ok(Self::Unknown { event: event.to_string(), data })
+            }
+            parsed => parsed,
+        }
+    }
+
+    /// Event name and payload as sent on the wire; the inverse of
+    /// `from_parts`
+    pub fn into_parts(self) -> (String, serde_json::Value) {
+        if let Self::Unknown { event, data } = self {
+            return (event, data);
+        }
+        let mut wire = serde_json::to_value(&self).unwrap_or_default();
+        let event = wire["event"].as_str().unwrap_or_default().to_string();
+        (event, wire["data"].take())
+    }
+}
+
+/// Payload of the `thread_info` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadInfo {
+    pub thread_id: String,
+    pub title: Option<String>,
+    /// Start of the thread summary, if one was generated
+    pub summary_preview: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Stored messages, including the one just sent
+    pub message_count: u64,
+}
+
+/// Payload of the `init` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunInfo {
+    pub run_id: String,
+    pub conversation_id: String,
+    /// LLM settings the run actually uses, after server-side routing
+    #[serde(default)]
+    pub llm_config: Option<serde_json::Value>,
+    /// Which layer set each routed setting
+    #[serde(default)]
+    pub routing: Option<serde_json::Value>,
+    #[serde(default)]
+    pub context_normalizations: Option<serde_json::Value>,
+    /// Settings the model doesn't support, dropped from its requests
+    #[serde(default)]
+    pub option_adjustments: Vec<OptionAdjusted>,
+}
+
+/// Node and loop iteration that produced an event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// `"llm"` or `"tool"`
+    pub node: String,
+    /// 0 for the run's first LLM call
+    pub iteration: u32,
+}
+
+/// RFC 9457 problem details, the body of every API error and the payload
+/// of the `error` stream event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Stable error code, e.g. `thread_not_found`
+    pub code: String,
+    /// Whether sending the same request again may succeed
+    pub retryable: bool,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.title, self.code)?;
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Problem {}
+
+/// SSE `id` of the `seq`th event (from 1) of the stream `stream_id`
+///
+/// A client that loses the connection sends the last id it saw as
+/// `Last-Event-ID` to `GET /streams/{stream_id}` to get the rest.
+pub fn event_id(stream_id: &str, seq: u64) -> String {
+    format!("{}:{}", stream_id, seq)
+}
+
+/// Stream id and sequence number of an event id; the inverse of `event_id`
+pub fn parse_event_id(id: &str) -> Option<(&str, u64)> {
+    let (stream_id, seq) = id.rsplit_once(':')?;
+    Some((stream_id, seq.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_ids_round_trip() {
+        assert_eq!(parse_event_id(&event_id("3f2a", 7)), Some(("3f2a", 7)));
+        assert_eq!(parse_event_id("3f2a:seven"), None);
+        assert_eq!(parse_event_id("7"), None);
+    }
+
+    #[test]
+    fn test_events_parse_by_name() {
+        let event = StreamEvent::parse(
+            "message",
+            r#"{"content": "Hi", "agent": null, "provenance": {"node": "llm", "iteration": 0}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            event,
+            StreamEvent::Message {
+                content: "Hi".to_string(),
+                agent: None,
+                provenance: Some(Provenance { node: "llm".to_string(), iteration: 0 }),
+            }
+        );
+
+        assert_eq!(StreamEvent::parse("info", "{}").unwrap(), StreamEvent::Info {});
+        assert_eq!(
+            StreamEvent::parse("audio", r#"{"data_base64": "UklGRg=="}"#).unwrap(),
+            StreamEvent::Audio { data_base64: "UklGRg==".to_string(), agent: None, provenance: None }
+        );
+        assert_eq!(StreamEvent::parse("queued", r#"{"position": 2}"#).unwrap(), StreamEvent::Queued { position: 2 });
+    }
+
+    #[test]
+    fn test_error_event_carries_the_problem() {
+        let data = r#"{"type": "urn:praxis:error:provider_error", "title": "LLM provider error",
+            "status": 502, "detail": "upstream closed", "code": "provider_error", "retryable": true}"#;
+        let StreamEvent::Error(problem) = StreamEvent::parse("error", data).unwrap() else {
+            panic!("expected an error event");
+        };
+        assert_eq!(problem.code, "provider_error");
+        assert!(problem.retryable);
+    }
+
+    #[test]
+    fn test_unknown_event_names_are_tolerated() {
+        let event = StreamEvent::parse("citation", r#"{"url": "https://example.com"}"#).unwrap();
+        assert_eq!(
+            event,
+            StreamEvent::Unknown {
+                event: "citation".to_string(),
+                data: serde_json::json!({ "url": "https://example.com" }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parts_round_trip() {
+        let events = vec![
+            StreamEvent::Reasoning {
+                content: "Checking".to_string(),
+                kind: ReasoningKind::Raw,
+                agent: None,
+                provenance: Some(Provenance { node: "llm".to_string(), iteration: 1 }),
+            },
+            StreamEvent::Done { status: "completed".to_string(), finish_reason: Some("stop".to_string()), cost_usd: None },
+            StreamEvent::Info {},
+            StreamEvent::Unknown { event: "citation".to_string(), data: serde_json::json!({ "n": 1 }) },
+        ];
+        for event in events {
+            let (name, data) = event.clone().into_parts();
+            assert_eq!(StreamEvent::from_parts(&name, data).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn test_unset_kind_and_cost_are_left_out() {
+        let (name, data) = StreamEvent::Reasoning {
+            content: "Checking".to_string(),
+            kind: ReasoningKind::Raw,
+            agent: None,
+            provenance: None,
+        }
+        .into_parts();
+        assert_eq!(name, "reasoning");
+        assert_eq!(data, serde_json::json!({ "content": "Checking", "agent": null, "provenance": null }));
+
+        let (_, data) = StreamEvent::Done { status: "completed".to_string(), finish_reason: None, cost_usd: None }.into_parts();
+        assert_eq!(data, serde_json::json!({ "status": "completed", "finish_reason": null }));
+    }
+
+    #[test]
+    fn test_malformed_payload_is_an_error() {
+        assert!(StreamEvent::parse("message", r#"{"text": "Hi"}"#).is_err());
+        assert!(StreamEvent::parse("message", "not json").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Agent attribution on stream events and persisted messages
+
+mod common;
+
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+
+fn answer_turn() -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: "Hello".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+#[tokio::test]
+async fn test_events_and_messages_carry_agent_name() {
+    let persistence = Arc::new(RecordingPersistence::default());
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![answer_turn()])))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_config(GraphConfig::new().with_agent_name("researcher"))
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    let message = events
+        .iter()
+        .find(|e| matches!(e, StreamEvent::Message { .. }))
+        .expect("message event");
+    assert_eq!(message.agent(), Some("researcher"));
+
+    let saved = persistence.wait_for(1).await;
+    assert_eq!(saved[0].agent.as_deref(), Some("researcher"));
+}
+
+#[test]
+fn test_agent_field_is_optional_on_the_wire() {
+    let event: StreamEvent = serde_json::from_str(r#"{"type":"message","content":"hi"}"#).unwrap();
+    assert_eq!(event.agent(), None);
+    assert!(!serde_json::to_string(&event).unwrap().contains("agent"));
+
+    let event = event.with_agent("planner");
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["agent"], "planner");
+}
+
+#[test]
+fn test_handoff_serialization() {
+    let event = StreamEvent::Handoff {
+        from: "assistant".to_string(),
+        to: "researcher".to_string(),
+        reason: Some("needs web search".to_string()),
+    };
+    let json = serde_json::to_value(&event).unwrap();
+
+    assert_eq!(json["type"], "handoff");
+    assert_eq!(json["to"], "researcher");
+    assert_eq!(event.agent(), None);
+}
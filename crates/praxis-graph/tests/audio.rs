@@ -0,0 +1,47 @@
+//! Spoken answers: audio options reach the LLM, audio chunks are forwarded,
+//! and a placeholder is persisted for audio without a transcript
+
+mod common;
+
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::{AudioOptions, Message};
+use praxis_mcp::MCPToolExecutor;
+use praxis_persist::MessageType;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_audio_is_forwarded_and_persisted_as_a_placeholder() {
+    let turn = vec![
+        praxis_llm::StreamEvent::Audio { data_base64: "UklGRg==".to_string() },
+        praxis_llm::StreamEvent::Audio { data_base64: "AAAA".to_string() },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ];
+    let llm = Arc::new(ScriptedLLM::new(vec![turn]));
+    let persistence = Arc::new(RecordingPersistence::default());
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+
+    let llm_config = LLMConfig::new("gpt-4o-audio-preview").with_audio(AudioOptions::new("alloy", "pcm16"));
+    let input = GraphInput::new("thread-1", vec![Message::human("Say hi")], llm_config);
+    let ctx = PersistenceContext { thread_id: "thread-1".to_string(), user_id: "user-1".to_string() };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    let audio: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Audio { data_base64, .. } => Some(data_base64.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(audio, vec!["UklGRg==", "AAAA"]);
+    assert_eq!(llm.chat_options()[0].audio, Some(AudioOptions::new("alloy", "pcm16")));
+
+    let saved = persistence.wait_for(1).await;
+    let message = saved.iter().find(|m| m.message_type == MessageType::Message).unwrap();
+    assert_eq!(message.content, "[audio]");
+}
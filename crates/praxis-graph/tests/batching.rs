@@ -0,0 +1,125 @@
+//! Batched deltas keep the order of events and every character of text
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{BatchPolicy, Graph, GraphInput, LLMConfig, StreamEvent, StreamingConfig};
+use praxis_llm::{Message, ReasoningKind};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Clock;
+
+#[async_trait]
+impl LocalTool for Clock {
+    fn name(&self) -> &str {
+        "now"
+    }
+
+    fn description(&self) -> String {
+        "Current time".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "10:00".to_string() }])
+    }
+}
+
+fn deltas(text: &str, delta: fn(String) -> praxis_llm::StreamEvent) -> Vec<praxis_llm::StreamEvent> {
+    text.chars().map(|c| delta(c.to_string())).collect()
+}
+
+/// Reasoning and text one character at a time around a tool call
+fn turns() -> Vec<Vec<praxis_llm::StreamEvent>> {
+    let reasoning = |content| praxis_llm::StreamEvent::Reasoning { content, kind: ReasoningKind::Raw, item_id: None };
+    let message = |content| praxis_llm::StreamEvent::Message { content, choice_index: None, logprobs: None };
+
+    let mut first = deltas("I should check the clock.", reasoning);
+    first.extend(deltas("Let me look. ", message));
+    first.push(praxis_llm::StreamEvent::ToolCall {
+        index: 0,
+        id: Some("call_1".to_string()),
+        name: Some("now".to_string()),
+        arguments: Some("{}".to_string()),
+    });
+    first.push(praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None });
+
+    let mut second = deltas("It is 10:00 — göod morning!", message);
+    second.push(praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None });
+    vec![first, second]
+}
+
+async fn run(batch: Option<BatchPolicy>) -> Vec<StreamEvent> {
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Clock).await.unwrap();
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(turns())))
+        .mcp_executor(Arc::new(executor))
+        .with_streaming(StreamingConfig { batch })
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("What time is it?")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+/// The run as a list of (event type, text), consecutive deltas of a kind joined
+fn transcript(events: &[StreamEvent]) -> Vec<(String, String)> {
+    let mut transcript: Vec<(String, String)> = Vec::new();
+    for event in events {
+        let kind = serde_json::to_value(event).unwrap()["type"].as_str().unwrap().to_string();
+        let text = match event {
+            StreamEvent::Message { content, .. } | StreamEvent::Reasoning { content, .. } => content.clone(),
+            _ => String::new(),
+        };
+        match transcript.last_mut() {
+            Some((last, joined)) if *last == kind && matches!(kind.as_str(), "message" | "reasoning") => {
+                joined.push_str(&text)
+            }
+            _ => transcript.push((kind, text)),
+        }
+    }
+    transcript
+}
+
+fn delta_count(events: &[StreamEvent]) -> usize {
+    events.iter().filter(|e| matches!(e, StreamEvent::Message { .. } | StreamEvent::Reasoning { .. })).count()
+}
+
+#[tokio::test]
+async fn test_batches_keep_order_and_text() {
+    let unbatched = run(None).await;
+    let expected = transcript(&unbatched);
+    assert!(expected.iter().any(|(kind, _)| kind == "tool_call"));
+
+    for policy in [BatchPolicy::FixedInterval(50), BatchPolicy::MinChars(8), BatchPolicy::Adaptive] {
+        let events = run(Some(policy)).await;
+        assert_eq!(transcript(&events), expected, "{:?}", policy);
+        assert!(
+            delta_count(&events) < delta_count(&unbatched) / 2,
+            "{:?} sent {} deltas of {}",
+            policy,
+            delta_count(&events),
+            delta_count(&unbatched)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_min_chars_batches_reach_the_minimum() {
+    let events = run(Some(BatchPolicy::MinChars(8))).await;
+
+    // Only text cut short by another kind of event may be smaller
+    for (i, event) in events.iter().enumerate() {
+        let next = events.get(i + 1).map(std::mem::discriminant);
+        if let StreamEvent::Message { content, .. } | StreamEvent::Reasoning { content, .. } = event {
+            let cut_short = next != Some(std::mem::discriminant(event));
+            assert!(content.chars().count() >= 8 || cut_short, "batch {:?}", content);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Answers that cite tool results carry citations linking markers to tool calls
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Citation, Graph, GraphConfig, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use praxis_persist::{DBMessage, MessageType};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Weather;
+
+#[async_trait]
+impl LocalTool for Weather {
+    fn name(&self) -> &str {
+        "get_weather"
+    }
+
+    fn description(&self) -> String {
+        "Current weather for a city".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "city": { "type": "string" } } })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "Sunny, 24°C".to_string() }])
+    }
+}
+
+fn turns(answer: &str) -> Vec<Vec<praxis_llm::StreamEvent>> {
+    vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("get_weather".to_string()),
+                arguments: Some(r#"{"city": "Lisbon"}"#.to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: answer.to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]
+}
+
+struct Run {
+    requests: Vec<Vec<Message>>,
+    events: Vec<StreamEvent>,
+    saved: Vec<DBMessage>,
+}
+
+async fn run(config: GraphConfig, answer: &str) -> Run {
+    let llm = Arc::new(ScriptedLLM::new(turns(answer)));
+    let persistence = Arc::new(RecordingPersistence::default());
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Weather).await.unwrap();
+
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(executor))
+        .with_persistence(persistence.clone())
+        .config(config)
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("Weather in Lisbon?")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext { thread_id: "thread-1".to_string(), user_id: "user-1".to_string() };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+    let saved = persistence.wait_for(3).await;
+
+    Run { requests: llm.requests(), events, saved }
+}
+
+fn citation_events(events: &[StreamEvent]) -> Vec<&Vec<Citation>> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Citations { citations, .. } => Some(citations),
+            _ => None,
+        })
+        .collect()
+}
+
+fn saved_answer(saved: &[DBMessage]) -> &DBMessage {
+    saved.iter().find(|m| m.message_type == MessageType::Message).unwrap()
+}
+
+fn asks_for_markers(request: &[Message]) -> bool {
+    request.iter().any(|message| {
+        matches!(message, Message::System { content, .. }
+            if content.as_text().is_some_and(|text| text.contains("[1] get_weather result (call call_1)")))
+    })
+}
+
+#[tokio::test]
+async fn test_markers_become_citations() {
+    let run = run(GraphConfig::new().with_cite_sources(true), "It's sunny in Lisbon [1], 24°C [1].").await;
+
+    // Only the call that follows the tool result lists it as a source
+    assert_eq!(run.requests.len(), 2);
+    assert!(!asks_for_markers(&run.requests[0]));
+    assert!(asks_for_markers(&run.requests[1]));
+
+    let expected = vec![Citation {
+        marker: 1,
+        tool_call_id: "call_1".to_string(),
+        tool_name: "get_weather".to_string(),
+    }];
+    assert_eq!(citation_events(&run.events), vec![&expected]);
+
+    // Sent after the answer's last delta
+    let last_message = run.events.iter().rposition(|e| matches!(e, StreamEvent::Message { .. })).unwrap();
+    let citations = run.events.iter().position(|e| matches!(e, StreamEvent::Citations { .. })).unwrap();
+    assert!(citations > last_message);
+
+    let answer = saved_answer(&run.saved);
+    assert_eq!(answer.content, "It's sunny in Lisbon [1], 24°C [1].");
+    assert_eq!(answer.citations, expected);
+}
+
+#[tokio::test]
+async fn test_answer_without_markers_has_no_citations() {
+    let run = run(GraphConfig::new().with_cite_sources(true), "It's sunny in Lisbon [3].").await;
+
+    assert!(citation_events(&run.events).is_empty());
+    assert!(!run.events.iter().any(|e| matches!(e, StreamEvent::Error { .. })));
+    assert!(saved_answer(&run.saved).citations.is_empty());
+}
+
+#[tokio::test]
+async fn test_citations_are_off_by_default() {
+    let run = run(GraphConfig::new(), "It's sunny in Lisbon [1].").await;
+
+    assert!(!asks_for_markers(&run.requests[1]));
+    assert!(citation_events(&run.events).is_empty());
+    assert!(saved_answer(&run.saved).citations.is_empty());
+}
@@ -0,0 +1,254 @@
+//! Shared helpers for graph integration tests
+#![allow(dead_code)]
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use praxis_llm::{
+    ChatClient, ChatOptions, ChatRequest, ChatResponse, LLMClient, LLMError, Message, RateLimited, ReasoningClient, ResponseOptions,
+    ResponseOutput, ResponseRequest, StreamEvent, ToolChoice,
+};
+use praxis_persist::{DBMessage, HistoryIndex, PersistenceClient, SummaryTrigger, Thread, ThreadMetadata};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// LLM that replays one scripted list of stream events per call
+///
+/// Chat and reasoning calls share the same queue of turns.
+#[derive(Default)]
+pub struct ScriptedLLM {
+    turns: Mutex<VecDeque<Vec<StreamEvent>>>,
+    chat_reply: Option<String>,
+    rate_limits: Mutex<VecDeque<Option<Duration>>>,
+    event_gap: Duration,
+    requests: Mutex<Vec<Vec<Message>>>,
+    tool_choices: Mutex<Vec<Option<ToolChoice>>>,
+    chat_options: Mutex<Vec<ChatOptions>>,
+    response_options: Mutex<Vec<ResponseOptions>>,
+}
+
+impl ScriptedLLM {
+    pub fn new(turns: Vec<Vec<StreamEvent>>) -> Self {
+        Self { turns: Mutex::new(turns.into()), ..Default::default() }
+    }
+
+    /// Answer non-streaming `chat` calls with `reply`
+    pub fn with_chat_reply(mut self, reply: impl Into<String>) -> Self {
+        self.chat_reply = Some(reply.into());
+        self
+    }
+
+    /// Reject the next `count` stream calls as rate limited, asking to wait `retry_after`
+    pub fn with_rate_limits(self, count: usize, retry_after: Option<Duration>) -> Self {
+        self.rate_limits.lock().unwrap().extend(std::iter::repeat_n(retry_after, count));
+        self
+    }
+
+    /// Wait `gap` before each streamed event, like a slow provider
+    pub fn with_event_gap(mut self, gap: Duration) -> Self {
+        self.event_gap = gap;
+        self
+    }
+
+    /// Messages of every streamed request, in call order
+    pub fn requests(&self) -> Vec<Vec<Message>> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Tool choice of every streamed chat request, in call order
+    pub fn tool_choices(&self) -> Vec<Option<ToolChoice>> {
+        self.tool_choices.lock().unwrap().clone()
+    }
+
+    /// Options of every streamed chat request, in call order
+    pub fn chat_options(&self) -> Vec<ChatOptions> {
+        self.chat_options.lock().unwrap().clone()
+    }
+
+    /// Options of every streamed reasoning request, in call order
+    pub fn response_options(&self) -> Vec<ResponseOptions> {
+        self.response_options.lock().unwrap().clone()
+    }
+
+    fn next_turn(&self, messages: &[Message]) -> Result<EventStream> {
+        self.requests.lock().unwrap().push(messages.to_vec());
+        if let Some(retry_after) = self.rate_limits.lock().unwrap().pop_front() {
+            return Err(LLMError::from(RateLimited { retry_after, message: "slow down".to_string() }).into());
+        }
+        let events = self.turns.lock().unwrap().pop_front().unwrap_or_default();
+        if self.event_gap.is_zero() {
+            return Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))));
+        }
+        let gap = self.event_gap;
+        Ok(Box::pin(futures::stream::iter(events).then(move |event| async move {
+            tokio::time::sleep(gap).await;
+            Ok(event)
+        })))
+    }
+}
+
+#[async_trait]
+impl ChatClient for ScriptedLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        let Some(reply) = &self.chat_reply else {
+            anyhow::bail!("not scripted")
+        };
+        Ok(ChatResponse {
+            content: Some(reply.clone()),
+            tool_calls: None,
+            usage: None,
+            finish_reason: Some("stop".to_string()),
+            logprobs: None,
+            choices: Vec::new(),
+            warnings: Vec::new(),
+            raw: serde_json::Value::Null,
+            served_by: None,
+            metadata: None,
+        })
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        self.tool_choices.lock().unwrap().push(request.options.tool_choice.clone());
+        self.chat_options.lock().unwrap().push(request.options.clone());
+        self.next_turn(&request.messages)
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for ScriptedLLM {
+    async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        self.response_options.lock().unwrap().push(request.options.clone());
+        self.next_turn(&request.input)
+    }
+}
+
+impl LLMClient for ScriptedLLM {}
+
+/// Persistence client that records saved messages in memory
+#[derive(Default)]
+pub struct RecordingPersistence {
+    pub messages: Mutex<Vec<DBMessage>>,
+}
+
+impl RecordingPersistence {
+    pub fn saved(&self) -> Vec<DBMessage> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// Wait until at least `count` messages were saved (saves are fire-and-forget)
+    pub async fn wait_for(&self, count: usize) -> Vec<DBMessage> {
+        for _ in 0..100 {
+            if self.messages.lock().unwrap().len() >= count {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        // Let any straggling saves land before inspecting
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        self.saved()
+    }
+}
+
+#[async_trait]
+impl PersistenceClient for RecordingPersistence {
+    async fn save_message(&self, message: DBMessage) -> praxis_persist::Result<()> {
+        self.messages.lock().unwrap().push(message);
+        Ok(())
+    }
+
+    async fn get_messages(&self, thread_id: &str) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self
+            .saved()
+            .into_iter()
+            .filter(|m| m.thread_id == thread_id)
+            .collect())
+    }
+
+    async fn get_messages_after(
+        &self,
+        thread_id: &str,
+        after: DateTime<Utc>,
+    ) -> praxis_persist::Result<Vec<DBMessage>> {
+        Ok(self
+            .get_messages(thread_id)
+            .await?
+            .into_iter()
+            .filter(|m| m.created_at > after)
+            .collect())
+    }
+
+    async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> praxis_persist::Result<Thread> {
+        let now = Utc::now();
+        Ok(Thread {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata,
+            last_summary_update: now,
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        })
+    }
+
+    async fn get_thread(&self, _thread_id: &str) -> praxis_persist::Result<Option<Thread>> {
+        Ok(None)
+    }
+
+    async fn save_thread_summary(
+        &self,
+        _thread_id: &str,
+        _summary: String,
+        _generated_at: DateTime<Utc>,
+        _trigger: SummaryTrigger,
+    ) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn update_thread_metadata(&self, _thread_id: &str, _metadata: ThreadMetadata) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn save_thread_index(&self, _thread_id: &str, _index: HistoryIndex) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn delete_thread(&self, _thread_id: &str, _user_id: &str) -> praxis_persist::Result<()> {
+        Ok(())
+    }
+
+    async fn list_threads(
+        &self,
+        _user_id: &str,
+        _limit: Option<i64>,
+        _skip: Option<i64>,
+    ) -> praxis_persist::Result<Vec<Thread>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Drain graph events until the stream ends
+pub async fn collect_events(
+    mut rx: tokio::sync::mpsc::Receiver<praxis_graph::StreamEvent>,
+) -> Vec<praxis_graph::StreamEvent> {
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        let done = matches!(event, praxis_graph::StreamEvent::EndStream { .. });
+        events.push(event);
+        if done {
+            break;
+        }
+    }
+    events
+}
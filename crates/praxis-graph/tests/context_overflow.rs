@@ -0,0 +1,110 @@
+//! A call rejected as longer than the context window is retried once with
+//! the oldest messages left out, when the graph is configured to
+//! (`GraphConfig::truncate_on_context_overflow`)
+
+mod common;
+
+use common::collect_events;
+use praxis_graph::{Graph, GraphConfig, GraphErrorKind, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::testkit::MockLLMClient;
+use praxis_llm::types::{FunctionCall, ToolCall};
+use praxis_llm::{LLMError, Message};
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+
+fn overflow() -> LLMError {
+    LLMError::ContextLengthExceeded {
+        max: Some(1000),
+        requested: Some(1500),
+        message: "This model's maximum context length is 1000 tokens".to_string(),
+    }
+}
+
+/// System prompt, three exchanges (the second with a tool round) and the question
+fn history() -> Vec<Message> {
+    let call = ToolCall {
+        id: "call_1".to_string(),
+        tool_type: "function".to_string(),
+        function: FunctionCall { name: "lookup".to_string(), arguments: "{}".to_string() },
+    };
+    vec![
+        Message::system("You are helpful"),
+        Message::human("one"),
+        Message::ai("first"),
+        Message::human("two"),
+        Message::ai_with_tools(vec![call]),
+        Message::tool("call_1", "looked up"),
+        Message::ai("second"),
+        Message::human("three"),
+        Message::ai("third"),
+        Message::human("And now?"),
+    ]
+}
+
+async fn run(llm: Arc<MockLLMClient>, config: GraphConfig) -> Vec<StreamEvent> {
+    let graph = Graph::builder()
+        .llm_client(llm)
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .config(config)
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", history(), LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+fn warnings(events: &[StreamEvent]) -> Vec<(&str, &str)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Warning { code, message, .. } => Some((code.as_str(), message.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn error_code(events: &[StreamEvent]) -> Option<&str> {
+    events.iter().find_map(|event| match event {
+        StreamEvent::Error { error_code, .. } => error_code.as_deref(),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+async fn test_overflow_is_retried_without_the_oldest_messages() {
+    let llm = Arc::new(MockLLMClient::new().then_llm_error(overflow()).then_message("Now this."));
+    let events = run(llm.clone(), GraphConfig::new().with_truncate_on_context_overflow(true)).await;
+
+    assert!(matches!(events.last(), Some(StreamEvent::EndStream { status, .. }) if status == "success"));
+    let requests = llm.chat_requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].messages.len(), 10);
+
+    // 40% of the 8 droppable messages go, and the tool round with its call
+    let retried: Vec<&str> = requests[1].messages.iter().map(|message| message.text().unwrap_or("[call]")).collect();
+    assert_eq!(retried, ["You are helpful", "second", "three", "third", "And now?"]);
+    assert_eq!(
+        warnings(&events),
+        [("context_truncated", "The conversation was too long for the model; the oldest 5 messages were left out")]
+    );
+}
+
+#[tokio::test]
+async fn test_overflow_fails_the_run_by_default() {
+    let llm = Arc::new(MockLLMClient::new().then_llm_error(overflow()).then_message("unused"));
+    let events = run(llm.clone(), GraphConfig::new()).await;
+
+    assert_eq!(error_code(&events), Some("context_length_exceeded"));
+    assert!(warnings(&events).is_empty());
+    assert_eq!(llm.chat_requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_truncation_is_retried_once() {
+    let llm = Arc::new(MockLLMClient::new().then_llm_error(overflow()).then_llm_error(overflow()).then_message("unused"));
+    let events = run(llm.clone(), GraphConfig::new().with_truncate_on_context_overflow(true)).await;
+
+    assert_eq!(error_code(&events), Some("context_length_exceeded"));
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Error { kind: GraphErrorKind::ProviderError, .. })));
+    assert_eq!(warnings(&events).len(), 1);
+    assert_eq!(llm.chat_requests().len(), 2);
+}
@@ -0,0 +1,109 @@
+//! With a cost calculator, the run's priced LLM calls add up to the last
+//! `Done`'s and `EndStream`'s `cost_usd`
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{CostCalculator, Message, ModelPrice};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Now;
+
+#[async_trait]
+impl LocalTool for Now {
+    fn name(&self) -> &str {
+        "now"
+    }
+
+    fn description(&self) -> String {
+        "The current time".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "12:00".to_string() }])
+    }
+}
+
+fn usage(input_tokens: u32, output_tokens: u32) -> praxis_llm::StreamEvent {
+    praxis_llm::StreamEvent::Usage {
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        reasoning_tokens: None,
+        cached_tokens: None,
+    }
+}
+
+/// A tool call and an answer, each reporting usage
+async fn run(calculator: Option<CostCalculator>, model: &str) -> Vec<StreamEvent> {
+    let llm = ScriptedLLM::new(vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("now".to_string()),
+                arguments: Some("{}".to_string()),
+            },
+            usage(1_000_000, 0),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "It's noon.".to_string(), choice_index: None, logprobs: None },
+            usage(1_000_000, 500_000),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]);
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Now).await.unwrap();
+    let mut builder = Graph::builder().llm_client(Arc::new(llm)).mcp_executor(Arc::new(executor));
+    if let Some(calculator) = calculator {
+        builder = builder.with_cost_calculator(calculator);
+    }
+    let graph = builder.build().unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("What time is it?")], LLMConfig::new(model));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+fn cost_usd(events: &[StreamEvent]) -> Option<f64> {
+    match events.last() {
+        Some(StreamEvent::EndStream { cost_usd, .. }) => *cost_usd,
+        other => panic!("expected EndStream, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_calls_add_up_to_the_run_cost() {
+    let calculator = CostCalculator::empty().with_price("acme", ModelPrice::new(1.0, 4.0));
+    let events = run(Some(calculator), "acme-large").await;
+
+    // 2M input tokens at $1, 0.5M output tokens at $4
+    let cost = cost_usd(&events).unwrap();
+    assert!((cost - 4.0).abs() < 1e-9, "{}", cost);
+
+    let done: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Done { cost_usd, .. } => Some(cost_usd.unwrap()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(done.len(), 2);
+    assert!((done[0] - 1.0).abs() < 1e-9 && (done[1] - 4.0).abs() < 1e-9, "{:?}", done);
+
+    let end = serde_json::to_value(events.last().unwrap()).unwrap();
+    assert_eq!(end["cost_usd"], json!(4.0));
+}
+
+#[tokio::test]
+async fn test_unpriced_runs_have_no_cost() {
+    assert_eq!(cost_usd(&run(None, "acme-large").await), None);
+    assert_eq!(cost_usd(&run(Some(CostCalculator::new()), "acme-large").await), None);
+}
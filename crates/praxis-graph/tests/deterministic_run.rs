@@ -0,0 +1,172 @@
+//! With a fixed clock and sequential ids, a run persists exactly the same rows every time
+
+mod common;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_core::testkit::{FixedClock, SeqIds};
+use praxis_graph::{Graph, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::{Message, ReasoningKind};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Weather;
+
+#[async_trait]
+impl LocalTool for Weather {
+    fn name(&self) -> &str {
+        "weather"
+    }
+
+    fn description(&self) -> String {
+        "Current weather for a city".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "city": { "type": "string" } } })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "18C, clear".to_string() }])
+    }
+}
+
+fn turns() -> Vec<Vec<praxis_llm::StreamEvent>> {
+    vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("weather".to_string()),
+                arguments: Some(r#"{"city": "Lisbon"}"#.to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Reasoning { content: "The tool says it is clear".to_string(), kind: ReasoningKind::Raw, item_id: None },
+            praxis_llm::StreamEvent::Message { content: "18C and clear in Lisbon".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]
+}
+
+fn start() -> DateTime<Utc> {
+    "2025-01-01T12:00:00Z".parse().unwrap()
+}
+
+async fn scripted_run() -> (Vec<StreamEvent>, Value) {
+    let llm = Arc::new(ScriptedLLM::new(turns()));
+    let persistence = Arc::new(RecordingPersistence::default());
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Weather).await.unwrap();
+
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .reasoning_client(llm)
+        .mcp_executor(Arc::new(executor))
+        .with_persistence(persistence.clone())
+        .with_clock(Arc::new(FixedClock::at(start())))
+        .with_id_gen(Arc::new(SeqIds::new()))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("Weather in Lisbon?")], LLMConfig::new("gpt-5"));
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    // Saves are spawned; ids are minted in order, so sort by them
+    let mut rows = persistence.wait_for(4).await;
+    rows.sort_by(|a, b| a.id.cmp(&b.id));
+    (events, serde_json::to_value(rows).unwrap())
+}
+
+#[tokio::test]
+async fn test_init_event_uses_injected_clock_and_ids() {
+    let (events, _) = scripted_run().await;
+    match &events[0] {
+        StreamEvent::InitStream { run_id, timestamp, .. } => {
+            assert_eq!(run_id, "id-0001");
+            assert_eq!(*timestamp, start().timestamp_millis());
+        }
+        other => panic!("expected init event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_persisted_rows_are_reproducible() {
+    let (_, rows) = scripted_run().await;
+    let (_, again) = scripted_run().await;
+    assert_eq!(rows, again);
+
+    assert_eq!(rows, expected_rows());
+}
+
+fn row(id: &str, iteration: u32, message_type: &str, content: &str, sha256: &str) -> Value {
+    json!({
+        "id": id,
+        "thread_id": "thread-1",
+        "user_id": "user-1",
+        "role": "assistant",
+        "message_type": message_type,
+        "content": content,
+        "content_encoding": "none",
+        "content_sha256": sha256,
+        "tool_call_id": null,
+        "tool_name": null,
+        "arguments": null,
+        "reasoning_id": null,
+        "created_at": "2025-01-01T12:00:00Z",
+        "duration_ms": null,
+        "agent": "assistant",
+        "iteration": iteration,
+    })
+}
+
+fn expected_rows() -> Value {
+    let mut tool_call = row(
+        "id-0003",
+        0,
+        "tool_call",
+        "",
+        "0ee04e560ed3acf087b2285f8dc173d1828f479e2fceb2fa1fd10abf65e3ff1e",
+    );
+    tool_call["tool_call_id"] = json!("call_1");
+    tool_call["tool_name"] = json!("weather");
+    tool_call["arguments"] = json!({ "city": "Lisbon" });
+    tool_call["reasoning_id"] = json!("msg_id-0002");
+
+    let mut tool_result = row(
+        "id-0004",
+        1,
+        "tool_result",
+        "18C, clear",
+        "25e842916b0eaefe93b4ee4a799a464335f257e3a2d3884821b6d2b860addd27",
+    );
+    tool_result["tool_call_id"] = json!("call_1");
+    tool_result["tool_server"] = json!("_local");
+
+    let mut reasoning = row(
+        "id-0007",
+        2,
+        "reasoning",
+        "The tool says it is clear",
+        "452c725f8c0550f64c49e5556ca97900864376a11c15f4aede6eb233fbdffc9f",
+    );
+    reasoning["reasoning_id"] = json!("rs_id-0005");
+
+    let mut message = row(
+        "id-0008",
+        2,
+        "message",
+        "18C and clear in Lisbon",
+        "342129c15f8f09f08314a1d71269d4a09a36621a4e7cb14be4a2c6380d12c3cd",
+    );
+    message["reasoning_id"] = json!("msg_id-0006");
+
+    json!([tool_call, tool_result, reasoning, message])
+}
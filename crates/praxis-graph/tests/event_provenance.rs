@@ -0,0 +1,119 @@
+//! Content events and persisted rows say which node and loop iteration produced them
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, NodeType, PersistenceContext, Provenance, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use praxis_persist::MessageType;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Clock;
+
+#[async_trait]
+impl LocalTool for Clock {
+    fn name(&self) -> &str {
+        "clock"
+    }
+
+    fn description(&self) -> String {
+        "Current time".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "09:30".to_string() }])
+    }
+}
+
+fn call_turn(id: &str) -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::ToolCall {
+            index: 0,
+            id: Some(id.to_string()),
+            name: Some("clock".to_string()),
+            arguments: Some("{}".to_string()),
+        },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+    ]
+}
+
+fn at(node: NodeType, iteration: u32) -> Option<Provenance> {
+    Some(Provenance { node, iteration })
+}
+
+#[tokio::test]
+async fn test_events_and_rows_carry_node_and_iteration() {
+    let answer = vec![
+        praxis_llm::StreamEvent::Message { content: "It is 09:30".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ];
+    let persistence = Arc::new(RecordingPersistence::default());
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Clock).await.unwrap();
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![call_turn("call_1"), call_turn("call_2"), answer])))
+        .mcp_executor(Arc::new(executor))
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("time?")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    let stamped: Vec<(&str, Option<Provenance>)> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::ToolCall { .. } => Some(("tool_call", event.provenance())),
+            StreamEvent::ToolResult { .. } => Some(("tool_result", event.provenance())),
+            StreamEvent::Message { .. } => Some(("message", event.provenance())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        stamped,
+        vec![
+            ("tool_call", at(NodeType::LLM, 0)),
+            ("tool_result", at(NodeType::Tool, 1)),
+            ("tool_call", at(NodeType::LLM, 2)),
+            ("tool_result", at(NodeType::Tool, 3)),
+            ("message", at(NodeType::LLM, 4)),
+        ]
+    );
+    // Orchestration events stay unstamped
+    assert!(events.iter().all(|e| !matches!(e, StreamEvent::InitStream { .. }) || e.provenance().is_none()));
+
+    let mut rows: Vec<(MessageType, Option<u32>)> =
+        persistence.wait_for(5).await.iter().map(|row| (row.message_type, row.iteration)).collect();
+    rows.sort_by_key(|(_, iteration)| *iteration);
+    assert_eq!(
+        rows,
+        vec![
+            (MessageType::ToolCall, Some(0)),
+            (MessageType::ToolResult, Some(1)),
+            (MessageType::ToolCall, Some(2)),
+            (MessageType::ToolResult, Some(3)),
+            (MessageType::Message, Some(4)),
+        ]
+    );
+}
+
+#[test]
+fn test_provenance_is_optional_in_serde() {
+    let old: StreamEvent = serde_json::from_value(json!({ "type": "message", "content": "hi" })).unwrap();
+    assert_eq!(old.provenance(), None);
+
+    let stamped = old.with_provenance(Provenance { node: NodeType::LLM, iteration: 3 });
+    let value = serde_json::to_value(&stamped).unwrap();
+    assert_eq!(value["provenance"], json!({ "node": "llm", "iteration": 3 }));
+}
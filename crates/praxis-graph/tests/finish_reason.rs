@@ -0,0 +1,83 @@
+//! The provider's finish reason is reported on the run's events
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphErrorKind, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+
+async fn run_with_finish_reason(reason: &str) -> Vec<StreamEvent> {
+    let turn = vec![
+        praxis_llm::StreamEvent::Message { content: "Partial".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some(reason.to_string()), choice_index: None },
+    ];
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![turn])))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+fn end_finish_reason(events: &[StreamEvent]) -> Option<&str> {
+    match events.last() {
+        Some(StreamEvent::EndStream { finish_reason, .. }) => finish_reason.as_deref(),
+        other => panic!("expected EndStream, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_stop_ends_quietly() {
+    let events = run_with_finish_reason("stop").await;
+
+    assert_eq!(end_finish_reason(&events), Some("stop"));
+    assert!(!events.iter().any(|e| matches!(e, StreamEvent::Warning { .. } | StreamEvent::Error { .. })));
+}
+
+#[tokio::test]
+async fn test_length_sends_a_warning() {
+    let events = run_with_finish_reason("length").await;
+
+    let warning = events.iter().find_map(|e| match e {
+        StreamEvent::Warning { code, .. } => Some(code.as_str()),
+        _ => None,
+    });
+    assert_eq!(warning, Some("truncated"));
+    assert_eq!(end_finish_reason(&events), Some("length"));
+}
+
+#[tokio::test]
+async fn test_content_filter_sends_an_error() {
+    let events = run_with_finish_reason("content_filter").await;
+
+    let kind = events.iter().find_map(|e| match e {
+        StreamEvent::Error { kind, .. } => Some(*kind),
+        _ => None,
+    });
+    assert_eq!(kind, Some(GraphErrorKind::ContentFiltered));
+    assert_eq!(end_finish_reason(&events), Some("content_filter"));
+}
+
+#[tokio::test]
+async fn test_other_choices_do_not_decide_the_finish() {
+    let turn = vec![
+        praxis_llm::StreamEvent::Message { content: "Short".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Message { content: "A longer".to_string(), choice_index: Some(1), logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("length".to_string()), choice_index: Some(1) },
+    ];
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![turn])))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new("gpt-4o"));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+
+    assert_eq!(events.iter().filter(|e| matches!(e, StreamEvent::Done { .. })).count(), 1);
+    assert!(!events.iter().any(|e| matches!(e, StreamEvent::Warning { .. })));
+    assert_eq!(end_finish_reason(&events), Some("stop"));
+}
@@ -0,0 +1,253 @@
+//! Graphs built from declarative spec files
+
+mod common;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{
+    ContextPolicy, EventSender, GraphBuilder, GraphInput, GraphSpec, GraphState, LLMConfig, Node, NodeRegistry, NodeType,
+    StreamEvent,
+};
+use praxis_llm::{Message, ToolChoice};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+static SPEC_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Write `files` into a fresh directory and return the path of the first one
+fn write_spec(files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "praxis-graph-spec-{}-{}",
+        std::process::id(),
+        SPEC_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, content) in files {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+    dir.join(files[0].0)
+}
+
+/// Adds a note before every LLM call
+struct Annotate;
+
+#[async_trait]
+impl Node for Annotate {
+    async fn execute(&self, state: &mut GraphState, _event_tx: EventSender) -> Result<()> {
+        state.add_message(Message::human("[annotated]"));
+        Ok(())
+    }
+
+    fn node_type(&self) -> NodeType {
+        NodeType::LLM
+    }
+}
+
+fn registry() -> NodeRegistry {
+    NodeRegistry::new().register("annotate", || Arc::new(Annotate))
+}
+
+fn validation_error(spec: &str) -> String {
+    GraphSpec::from_toml(spec).unwrap().validate(&registry()).unwrap_err().to_string()
+}
+
+struct Lookup;
+
+#[async_trait]
+impl LocalTool for Lookup {
+    fn name(&self) -> &str {
+        "lookup"
+    }
+
+    fn description(&self) -> String {
+        "Look something up".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": {} })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "found".to_string() }])
+    }
+}
+
+fn tool_call_turn(id: &str) -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::ToolCall {
+            index: 0,
+            id: Some(id.to_string()),
+            name: Some("lookup".to_string()),
+            arguments: Some("{}".to_string()),
+        },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+    ]
+}
+
+fn answer_turn() -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: "Done".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+async fn run_spec(path: &PathBuf, llm: Arc<ScriptedLLM>, messages: Vec<Message>) -> Vec<StreamEvent> {
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Lookup).await.unwrap();
+    let graph = GraphBuilder::from_spec(path, &registry())
+        .unwrap()
+        .llm_client(llm)
+        .mcp_executor(Arc::new(executor))
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", messages, LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+#[test]
+fn test_toml_and_yaml_specs_parse_alike() {
+    let toml = GraphSpec::from_toml(
+        r#"
+pipeline = ["annotate", "llm", "tool"]
+
+[router]
+max_tool_rounds = 3
+
+[budgets]
+max_iterations = 12
+
+[context_policy]
+type = "last_k"
+k = 4
+
+[guardrails.tool_content_isolation]
+suspicious_content = "redact"
+"#,
+    )
+    .unwrap();
+    let yaml = GraphSpec::from_yaml(
+        r#"
+pipeline: [annotate, llm, tool]
+router:
+  max_tool_rounds: 3
+budgets:
+  max_iterations: 12
+context_policy:
+  type: last_k
+  k: 4
+guardrails:
+  tool_content_isolation:
+    suspicious_content: redact
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(toml, yaml);
+    assert_eq!(toml.context_policy, Some(ContextPolicy::LastK { k: 4 }));
+    toml.validate(&registry()).unwrap();
+}
+
+#[test]
+fn test_empty_spec_is_the_default_graph() {
+    let spec = GraphSpec::from_toml("").unwrap();
+    assert_eq!(spec, GraphSpec::default());
+    spec.validate(&NodeRegistry::new()).unwrap();
+}
+
+#[test]
+fn test_unknown_keys_are_rejected() {
+    let err = GraphSpec::from_toml("[router]\nmax_rounds = 3").unwrap_err();
+    assert!(format!("{:#}", err).contains("max_rounds"));
+
+    let err = GraphSpec::from_toml("pipelines = [\"llm\", \"tool\"]").unwrap_err();
+    assert!(format!("{:#}", err).contains("pipelines"));
+}
+
+#[test]
+fn test_validation_errors_name_the_key() {
+    let err = validation_error(r#"pipeline = ["llm", "tool", "audit"]"#);
+    assert!(err.contains("pipeline[2]") && err.contains("`audit`") && err.contains("registered: annotate"));
+
+    let err = validation_error(r#"pipeline = ["annotate", "tool"]"#);
+    assert!(err.contains("pipeline must contain `llm`"));
+
+    let err = validation_error(r#"pipeline = ["tool", "llm"]"#);
+    assert!(err.contains("`llm` must come before `tool`"));
+
+    let err = validation_error(r#"pipeline = ["annotate", "llm", "annotate", "tool"]"#);
+    assert!(err.contains("pipeline[2]") && err.contains("more than once"));
+
+    let err = validation_error("[router]\nmax_tool_rounds = 0");
+    assert!(err.contains("router.max_tool_rounds"));
+
+    let err = validation_error("[budgets]\nmax_iterations = 0");
+    assert!(err.contains("budgets.max_iterations"));
+
+    let err = validation_error("[budgets]\nexecution_timeout_secs = 0");
+    assert!(err.contains("budgets.execution_timeout_secs"));
+
+    let err = validation_error("[context_policy]\ntype = \"last_k\"\nk = 0");
+    assert!(err.contains("context_policy.k"));
+
+    let err = validation_error(r#"system_prompt = "/nonexistent/prompt.md""#);
+    assert!(err.contains("system_prompt"));
+}
+
+#[test]
+fn test_from_spec_reports_the_file() {
+    let path = write_spec(&[("graph.toml", "[budgets]\nmax_iterations = 0")]);
+    let err = GraphBuilder::from_spec(&path, &registry()).err().unwrap();
+    let message = format!("{:#}", err);
+    assert!(message.contains("graph.toml") && message.contains("budgets.max_iterations"));
+}
+
+#[tokio::test]
+async fn test_custom_node_and_system_prompt_from_spec() {
+    let path = write_spec(&[
+        ("graph.yaml", "pipeline: [annotate, llm, tool]\nsystem_prompt: prompt.md\n"),
+        ("prompt.md", "You are a librarian"),
+    ]);
+    let llm = Arc::new(ScriptedLLM::new(vec![answer_turn()]));
+    let events = run_spec(&path, llm.clone(), vec![Message::human("hi")]).await;
+
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::EndStream { .. })));
+    let request = &llm.requests()[0];
+    assert_eq!(request.len(), 3);
+    assert!(matches!(&request[0], Message::System { content, .. }
+        if content.as_text() == Some("You are a librarian")));
+    assert!(matches!(&request[2], Message::Human { content, .. }
+        if content.as_text() == Some("[annotated]")));
+}
+
+#[tokio::test]
+async fn test_max_tool_rounds_forces_a_final_answer() {
+    let path = write_spec(&[("graph.toml", "[router]\nmax_tool_rounds = 1")]);
+    let llm = Arc::new(ScriptedLLM::new(vec![tool_call_turn("call_1"), tool_call_turn("call_2")]));
+    let events = run_spec(&path, llm.clone(), vec![Message::human("look it up")]).await;
+
+    assert_eq!(llm.tool_choices(), vec![Some(ToolChoice::auto()), Some(ToolChoice::none())]);
+    // The second call is not executed
+    let results = events.iter().filter(|event| matches!(event, StreamEvent::ToolResult { .. })).count();
+    assert_eq!(results, 1);
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::EndStream { .. })));
+}
+
+#[tokio::test]
+async fn test_context_policy_trims_the_request() {
+    let path = write_spec(&[("graph.toml", "[context_policy]\ntype = \"last_k\"\nk = 1")]);
+    let llm = Arc::new(ScriptedLLM::new(vec![answer_turn()]));
+    let history = vec![
+        Message::system("Be brief"),
+        Message::human("first"),
+        Message::human("second"),
+    ];
+    run_spec(&path, llm.clone(), history).await;
+
+    let request = &llm.requests()[0];
+    assert_eq!(request.len(), 2);
+    assert!(matches!(&request[1], Message::Human { content, .. } if content.as_text() == Some("second")));
+}
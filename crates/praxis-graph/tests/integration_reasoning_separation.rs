@@ -26,26 +26,27 @@ mod tests {
         
         let config = LLMConfig::new("gpt-5")
             .with_provider(Provider::OpenAI)
-            .with_reasoning_effort("medium");
+            .with_reasoning_effort(praxis_llm::ReasoningEffort::Medium);
         
         assert_eq!(config.model, "gpt-5");
         assert_eq!(config.provider, Provider::OpenAI);
-        assert_eq!(config.reasoning_effort, Some("medium".to_string()));
+        assert_eq!(config.reasoning_effort, Some(praxis_llm::ReasoningEffort::Medium));
     }
     
     #[test]
     fn test_client_factory_reasoning_detection() {
         use praxis_graph::ClientFactory;
         
+        let registry = praxis_llm::CapabilityRegistry::new();
         // Test reasoning model detection
-        assert!(ClientFactory::supports_reasoning("gpt-5"));
-        assert!(ClientFactory::supports_reasoning("gpt-5-turbo"));
-        assert!(ClientFactory::supports_reasoning("o1-preview"));
-        assert!(ClientFactory::supports_reasoning("o1-mini"));
-        
-        assert!(!ClientFactory::supports_reasoning("gpt-4o"));
-        assert!(!ClientFactory::supports_reasoning("gpt-4o-mini"));
-        assert!(!ClientFactory::supports_reasoning("gpt-3.5-turbo"));
+        assert!(ClientFactory::supports_reasoning("gpt-5", &registry));
+        assert!(ClientFactory::supports_reasoning("gpt-5-turbo", &registry));
+        assert!(ClientFactory::supports_reasoning("o1-preview", &registry));
+        assert!(ClientFactory::supports_reasoning("o1-mini", &registry));
+        
+        assert!(!ClientFactory::supports_reasoning("gpt-4o", &registry));
+        assert!(!ClientFactory::supports_reasoning("gpt-4o-mini", &registry));
+        assert!(!ClientFactory::supports_reasoning("gpt-3.5-turbo", &registry));
     }
     
     #[test]
@@ -151,6 +152,8 @@ mod tests {
         // Test message event adaptation
         let message_event = LLMEvent::Message {
             content: "Response".to_string(),
+            choice_index: None,
+            logprobs: None,
         };
         
         let adapted = adapter.adapt(message_event);
@@ -170,7 +173,7 @@ mod doc_tests {
     /// // Create LLM config with reasoning support
     /// let llm_config = LLMConfig::new("gpt-5")
     ///     .with_provider(Provider::OpenAI)
-    ///     .with_reasoning_effort("medium")
+    ///     .with_reasoning_effort(ReasoningEffort::Medium)
     ///     .with_temperature(0.7);
     /// 
     /// // Create graph input
@@ -0,0 +1,202 @@
+//! Full react loop (LLM → tool → LLM → end) against `praxis_llm::testkit::MockLLMClient`
+
+mod common;
+
+use async_trait::async_trait;
+use common::collect_events;
+use praxis_graph::{ClientFactory, Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::testkit::MockLLMClient;
+use praxis_llm::{CapabilityRegistry, LLMClient, Message, ModelCapabilities, ReasoningConfig, ReasoningEffort, ReplayClient, SummaryMode};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Weather;
+
+#[async_trait]
+impl LocalTool for Weather {
+    fn name(&self) -> &str {
+        "weather"
+    }
+
+    fn description(&self) -> String {
+        "Current weather for a city".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "city": { "type": "string" } } })
+    }
+
+    async fn call(&self, arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: format!("18C, clear in {}", arguments["city"].as_str().unwrap_or("?")) }])
+    }
+}
+
+#[tokio::test]
+async fn test_tool_result_reaches_the_second_llm_call() {
+    let llm = Arc::new(
+        MockLLMClient::new()
+            .then_tool_call("call_1", "weather", r#"{"city": "Lisbon"}"#)
+            .then_message("18C and clear in Lisbon"),
+    );
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Weather).await.unwrap();
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(executor))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("Weather in Lisbon?")], LLMConfig::new("gpt-4o-mini"));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+
+    assert!(events.iter().any(|event| matches!(
+        event,
+        StreamEvent::ToolResult { result, is_error: false, .. } if result == "18C, clear in Lisbon"
+    )));
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Message { content, .. } if content == "18C and clear in Lisbon")));
+    assert!(matches!(events.last(), Some(StreamEvent::EndStream { status, .. }) if status == "success"));
+
+    let requests = llm.chat_requests();
+    assert_eq!(requests.len(), 2);
+    let tools = requests[0].options.tools.as_ref().expect("tools sent to the LLM");
+    assert_eq!(tools[0].function.name, "weather");
+    let tool_result = requests[1].messages.iter().find_map(|message| match message {
+        Message::Tool { tool_call_id, content } => Some((tool_call_id.as_str(), content.as_text()?)),
+        _ => None,
+    });
+    assert_eq!(tool_result, Some(("call_1", "18C, clear in Lisbon")));
+    assert_eq!(llm.remaining(), 0);
+}
+
+#[tokio::test]
+async fn test_llm_failure_ends_the_run_with_an_error() {
+    let llm = Arc::new(MockLLMClient::new().then_error("provider down"));
+    let graph = Graph::builder()
+        .llm_client(llm)
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("Hi")], LLMConfig::new("gpt-4o-mini"));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Error { message, .. } if message.contains("provider down"))));
+}
+
+#[tokio::test]
+async fn test_recorded_run_replays_offline() {
+    async fn run(llm: Arc<dyn LLMClient>) -> Vec<StreamEvent> {
+        let executor = MCPToolExecutor::new();
+        executor.register_local_tool(Weather).await.unwrap();
+        let graph = Graph::builder().llm_client(llm).mcp_executor(Arc::new(executor)).build().unwrap();
+        let input = GraphInput::new("thread-1", vec![Message::human("Weather in Lisbon?")], LLMConfig::new("gpt-4o-mini"));
+        collect_events(graph.spawn_run(input, None)).await
+    }
+    fn answer(events: &[StreamEvent]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                StreamEvent::Message { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    let dir = std::env::temp_dir().join(format!("praxis-graph-recording-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let llm = Arc::new(
+        MockLLMClient::new()
+            .then_tool_call("call_1", "weather", r#"{"city": "Lisbon"}"#)
+            .then_message("18C and clear in Lisbon"),
+    );
+    let recorded = run(ClientFactory::with_record_dir(llm, Some(&dir))).await;
+    let Some(StreamEvent::InitStream { run_id, .. }) = recorded.first() else {
+        panic!("expected init first, got {:?}", recorded.first());
+    };
+
+    // Both LLM calls of the run land in its own file
+    let file = dir.join(format!("{}.jsonl", run_id));
+    assert_eq!(std::fs::read_to_string(&file).unwrap().lines().count(), 2);
+
+    let replay = Arc::new(ReplayClient::load(&file).unwrap());
+    let replayed = run(replay.clone()).await;
+    assert_eq!(answer(&replayed), "18C and clear in Lisbon");
+    assert_eq!(answer(&replayed), answer(&recorded));
+    assert_eq!(replay.remaining(), 0);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_only_the_first_choice_is_streamed_and_kept() {
+    let message = |content: &str, choice_index| praxis_llm::StreamEvent::Message { content: content.to_string(), choice_index, logprobs: None };
+    let llm = Arc::new(MockLLMClient::new().then_stream(vec![
+        message("First", None),
+        message("Second", Some(1)),
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]));
+    let graph = Graph::builder().llm_client(llm).mcp_executor(Arc::new(MCPToolExecutor::new())).build().unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("Hi")], LLMConfig::new("gpt-4o-mini"));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+
+    let streamed: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Message { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(streamed, ["First"]);
+}
+
+#[tokio::test]
+async fn test_reasoning_effort_and_summary_reach_the_request() {
+    let llm = Arc::new(MockLLMClient::new().then_message("4"));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .reasoning_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+
+    let config = LLMConfig::new("gpt-5").with_reasoning_summary(SummaryMode::Detailed);
+    let input = GraphInput::new("thread-1", vec![Message::human("2 + 2?")], config);
+    collect_events(graph.spawn_run(input, None)).await;
+
+    let requests = llm.response_requests();
+    assert_eq!(requests[0].reasoning, Some(ReasoningConfig::new(ReasoningEffort::Medium, SummaryMode::Detailed)));
+}
+
+#[tokio::test]
+async fn test_registered_deployment_uses_the_reasoning_api() {
+    let run = |registry: Option<CapabilityRegistry>| async move {
+        let llm = Arc::new(MockLLMClient::new().then_message("4"));
+        let mut builder = Graph::builder()
+            .llm_client(llm.clone())
+            .reasoning_client(llm.clone())
+            .mcp_executor(Arc::new(MCPToolExecutor::new()));
+        if let Some(registry) = registry {
+            builder = builder.with_capabilities(registry);
+        }
+        let input = GraphInput::new("thread-1", vec![Message::human("2 + 2?")], LLMConfig::new("prod-reasoner"));
+        collect_events(builder.build().unwrap().spawn_run(input, None)).await;
+        (llm.chat_requests().len(), llm.response_requests().len())
+    };
+
+    let registry = CapabilityRegistry::new().with_model("prod-reasoner", ModelCapabilities::reasoning(200_000));
+    assert_eq!(run(Some(registry)).await, (0, 1));
+    assert_eq!(run(None).await, (1, 0));
+}
+
+#[test]
+fn test_llm_config_reads_string_reasoning_effort() {
+    let config: LLMConfig = serde_json::from_value(json!({ "model": "gpt-5", "reasoning_effort": "minimal" })).unwrap();
+    assert_eq!(config.reasoning_effort, Some(ReasoningEffort::Minimal));
+    assert_eq!(serde_json::to_value(&config).unwrap()["reasoning_effort"], "minimal");
+
+    // Stored before the effort was typed: unknown levels meant medium
+    let config: LLMConfig = serde_json::from_value(json!({ "model": "gpt-5", "reasoning_effort": "max" })).unwrap();
+    assert_eq!(config.reasoning_effort, Some(ReasoningEffort::Medium));
+}
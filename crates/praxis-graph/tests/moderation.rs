@@ -0,0 +1,140 @@
+//! Moderation screens the run's input before the first LLM call and each
+//! answer after it, blocking or warning as the policy says
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphErrorKind, GraphInput, LLMConfig, ModerationPolicy, ModerationStage, StreamEvent};
+use praxis_llm::{Message, Moderation, ModerationClient};
+use praxis_mcp::MCPToolExecutor;
+use std::sync::{Arc, Mutex};
+
+/// Flags texts containing `word` for `violence`, and keeps what it was asked
+struct KeywordModeration {
+    word: &'static str,
+    seen: Mutex<Vec<String>>,
+}
+
+impl KeywordModeration {
+    fn new(word: &'static str) -> Arc<Self> {
+        Arc::new(Self { word, seen: Mutex::new(Vec::new()) })
+    }
+
+    fn seen(&self) -> Vec<String> {
+        self.seen.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ModerationClient for KeywordModeration {
+    async fn moderate(&self, input: &str) -> anyhow::Result<Moderation> {
+        self.seen.lock().unwrap().push(input.to_string());
+        if input.contains(self.word) {
+            Ok(Moderation::flagged(["violence"]))
+        } else {
+            Ok(Moderation::clean())
+        }
+    }
+}
+
+fn answer(text: &str) -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: text.to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+async fn run(
+    moderation: Arc<KeywordModeration>,
+    policy: ModerationPolicy,
+    input: &str,
+    reply: &str,
+) -> (Vec<StreamEvent>, Arc<ScriptedLLM>) {
+    let llm = Arc::new(ScriptedLLM::new(vec![answer(reply)]));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_moderation(moderation, policy)
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human(input)], LLMConfig::new("gpt-4o"));
+    (collect_events(graph.spawn_run(input, None)).await, llm)
+}
+
+fn error(events: &[StreamEvent]) -> Option<(GraphErrorKind, &str)> {
+    events.iter().find_map(|event| match event {
+        StreamEvent::Error { kind, message, .. } => Some((*kind, message.as_str())),
+        _ => None,
+    })
+}
+
+fn warnings(events: &[StreamEvent]) -> Vec<(ModerationStage, Vec<String>)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::ModerationWarning { stage, categories } => Some((*stage, categories.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_flagged_input_is_blocked_before_the_llm_call() {
+    let moderation = KeywordModeration::new("attack");
+    let (events, llm) = run(moderation.clone(), ModerationPolicy::Block, "How do I attack him?", "unused").await;
+
+    assert_eq!(
+        error(&events),
+        Some((GraphErrorKind::ModerationBlocked, "The input was flagged by moderation: violence"))
+    );
+    assert!(llm.requests().is_empty());
+    assert!(!events.iter().any(|event| matches!(event, StreamEvent::EndStream { .. })));
+    assert_eq!(moderation.seen(), ["How do I attack him?"]);
+}
+
+#[tokio::test]
+async fn test_flagged_answer_ends_the_run() {
+    let moderation = KeywordModeration::new("attack");
+    let (events, _) = run(moderation.clone(), ModerationPolicy::Block, "Tell me a story", "They attack at dawn.").await;
+
+    assert_eq!(
+        error(&events),
+        Some((GraphErrorKind::ModerationBlocked, "The answer was flagged by moderation: violence"))
+    );
+    assert!(!events.iter().any(|event| matches!(event, StreamEvent::EndStream { .. })));
+    assert_eq!(moderation.seen(), ["Tell me a story", "They attack at dawn."]);
+}
+
+#[tokio::test]
+async fn test_annotate_warns_and_finishes_the_run() {
+    let moderation = KeywordModeration::new("attack");
+    let (events, llm) = run(moderation, ModerationPolicy::Annotate, "Why attack?", "To attack first.").await;
+
+    assert_eq!(
+        warnings(&events),
+        [
+            (ModerationStage::Input, vec!["violence".to_string()]),
+            (ModerationStage::Output, vec!["violence".to_string()]),
+        ]
+    );
+    assert_eq!(error(&events), None);
+    assert_eq!(llm.requests().len(), 1);
+    assert!(matches!(events.last(), Some(StreamEvent::EndStream { status, .. }) if status == "success"));
+
+    let warning = events.iter().find(|event| matches!(event, StreamEvent::ModerationWarning { .. })).unwrap();
+    assert_eq!(
+        serde_json::to_value(warning).unwrap(),
+        serde_json::json!({ "type": "moderation_warning", "stage": "input", "categories": ["violence"] })
+    );
+}
+
+#[tokio::test]
+async fn test_clean_runs_are_untouched() {
+    let moderation = KeywordModeration::new("attack");
+    let (events, _) = run(moderation, ModerationPolicy::Block, "Hello", "Hi there!").await;
+
+    assert!(warnings(&events).is_empty());
+    assert_eq!(error(&events), None);
+    assert!(matches!(events.last(), Some(StreamEvent::EndStream { .. })));
+}
@@ -0,0 +1,115 @@
+//! The observer sees a run in order: trace start, the user's message, nodes, end
+
+#![cfg(feature = "observability")]
+
+mod common;
+
+use async_trait::async_trait;
+use common::collect_events;
+use praxis_graph::{Graph, GraphInput, LLMConfig};
+use praxis_llm::testkit::MockLLMClient;
+use praxis_llm::{Message, ResponseMetadata, StreamEvent};
+use praxis_mcp::MCPToolExecutor;
+use praxis_observability::{NodeObservation, Observer};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Records each call as one line
+#[derive(Default)]
+struct RecordingObserver {
+    calls: Mutex<Vec<String>>,
+}
+
+impl RecordingObserver {
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+#[async_trait]
+impl Observer for RecordingObserver {
+    async fn trace_start(&self, _run_id: String, conversation_id: String) -> anyhow::Result<()> {
+        self.record(format!("start {}", conversation_id));
+        Ok(())
+    }
+
+    async fn trace_user_input(
+        &self,
+        _run_id: String,
+        _conversation_id: String,
+        content: String,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        self.record(format!("user_input {} {}", content, metadata["locale"]));
+        Ok(())
+    }
+
+    async fn trace_llm_node(&self, observation: NodeObservation) -> anyhow::Result<()> {
+        match observation.metadata.get("request_id") {
+            Some(request_id) => self.record(format!("llm {}", request_id)),
+            None => self.record("llm".to_string()),
+        }
+        Ok(())
+    }
+
+    async fn trace_tool_node(&self, _observation: NodeObservation) -> anyhow::Result<()> {
+        self.record("tool".to_string());
+        Ok(())
+    }
+
+    async fn trace_end(&self, _run_id: String, status: String, _total_duration_ms: u64) -> anyhow::Result<()> {
+        self.record(format!("end {}", status));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_user_input_is_traced_between_start_and_the_llm_node() {
+    let observer = Arc::new(RecordingObserver::default());
+    let graph = Graph::builder()
+        .llm_client(Arc::new(MockLLMClient::new().then_message("Hello!")))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_observer(observer.clone())
+        .build()
+        .unwrap();
+
+    let messages = vec![Message::human("Earlier question"), Message::ai("Earlier answer"), Message::human("Hi there")];
+    let input = GraphInput::new("thread-1", messages, LLMConfig::new("gpt-4o-mini"))
+        .with_variable("locale", serde_json::json!("pt-BR"));
+    collect_events(graph.spawn_run(input, None)).await;
+    // Observer calls run in spawned tasks
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+
+    assert_eq!(
+        *observer.calls.lock().unwrap(),
+        vec!["start thread-1", r#"user_input Hi there "pt-BR""#, "llm", "end success"]
+    );
+}
+
+#[tokio::test]
+async fn test_llm_node_trace_carries_the_provider_request_id() {
+    let observer = Arc::new(RecordingObserver::default());
+    let metadata = ResponseMetadata { request_id: Some("req_42".to_string()), ..Default::default() };
+    let llm = MockLLMClient::new().then_stream(vec![
+        StreamEvent::ResponseMetadata { metadata },
+        StreamEvent::Message { content: "Hello!".to_string(), choice_index: None, logprobs: None },
+        StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]);
+    let graph = Graph::builder()
+        .llm_client(Arc::new(llm))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_observer(observer.clone())
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("Hi")], LLMConfig::new("gpt-4o-mini"))
+        .with_variable("locale", serde_json::json!("en"));
+    collect_events(graph.spawn_run(input, None)).await;
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+
+    assert!(observer.calls.lock().unwrap().contains(&r#"llm "req_42""#.to_string()));
+}
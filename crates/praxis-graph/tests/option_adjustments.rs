@@ -0,0 +1,42 @@
+//! Settings the model can't take are reported in the init event
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+
+fn answer_turn() -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: "Hello".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+async fn init_adjustments(llm_config: LLMConfig) -> Vec<String> {
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![answer_turn()])))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], llm_config);
+    let events = collect_events(graph.spawn_run(input, None)).await;
+    match &events[0] {
+        StreamEvent::InitStream { option_adjustments, .. } => {
+            option_adjustments.iter().map(|a| a.option.clone()).collect()
+        }
+        other => panic!("expected init event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_init_reports_dropped_temperature() {
+    let adjusted = init_adjustments(LLMConfig::new("gpt-5").with_temperature(0.2)).await;
+    assert_eq!(adjusted, vec!["temperature".to_string()]);
+
+    let supported = init_adjustments(LLMConfig::new("gpt-4o").with_temperature(0.2)).await;
+    assert!(supported.is_empty());
+}
@@ -0,0 +1,114 @@
+//! Persistence policy enforcement during graph runs
+
+mod common;
+
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, PersistenceContext};
+use praxis_llm::{Message, ReasoningKind, StreamEvent};
+use praxis_mcp::MCPToolExecutor;
+use praxis_persist::{content_sha256, ContentTokenizer, MessageType, PersistencePolicy};
+use std::sync::{Arc, Mutex};
+
+fn reasoning_turn() -> Vec<StreamEvent> {
+    vec![
+        StreamEvent::Reasoning { content: "Thinking it through".to_string(), kind: ReasoningKind::Raw, item_id: None },
+        StreamEvent::Message { content: "The answer is 4".to_string(), choice_index: None, logprobs: None },
+        StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+/// Counts bytes, and records which model it was asked about
+#[derive(Default)]
+struct ByteCounter {
+    models: Mutex<Vec<String>>,
+}
+
+impl ContentTokenizer for ByteCounter {
+    fn count(&self, model: &str, text: &str) -> usize {
+        self.models.lock().unwrap().push(model.to_string());
+        text.len()
+    }
+}
+
+async fn run_reasoning_model(policy: PersistencePolicy) -> Vec<praxis_persist::DBMessage> {
+    run_with_tokenizer(policy, None).await
+}
+
+async fn run_with_tokenizer(
+    policy: PersistencePolicy,
+    tokenizer: Option<Arc<dyn ContentTokenizer>>,
+) -> Vec<praxis_persist::DBMessage> {
+    let llm = Arc::new(ScriptedLLM::new(vec![reasoning_turn()]));
+    let persistence = Arc::new(RecordingPersistence::default());
+
+    let mut builder = Graph::builder()
+        .llm_client(llm.clone())
+        .reasoning_client(llm)
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_persistence(persistence.clone())
+        .with_persistence_policy(policy);
+    if let Some(tokenizer) = tokenizer {
+        builder = builder.with_content_tokenizer(tokenizer);
+    }
+    let graph = builder.build().unwrap();
+
+    let input = GraphInput::new(
+        "thread-1",
+        vec![Message::human("What is 2 + 2?")],
+        LLMConfig::new("gpt-5").with_reasoning_effort(praxis_llm::ReasoningEffort::Low),
+    );
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    persistence.wait_for(1).await
+}
+
+#[tokio::test]
+async fn test_default_policy_saves_reasoning() {
+    let saved = run_reasoning_model(PersistencePolicy::default()).await;
+
+    assert!(saved.iter().any(|m| m.message_type == MessageType::Reasoning));
+    assert!(saved.iter().any(|m| m.message_type == MessageType::Message));
+}
+
+#[tokio::test]
+async fn test_reasoning_disabled_skips_reasoning_rows() {
+    let saved = run_reasoning_model(PersistencePolicy::new().with_reasoning(false)).await;
+
+    assert!(!saved.is_empty());
+    assert!(saved.iter().all(|m| m.message_type != MessageType::Reasoning));
+}
+
+#[tokio::test]
+async fn test_content_is_truncated() {
+    let saved = run_reasoning_model(PersistencePolicy::new().with_content_max_bytes(3)).await;
+
+    let message = saved.iter().find(|m| m.message_type == MessageType::Message).unwrap();
+    assert_eq!(message.content, "The");
+}
+
+#[tokio::test]
+async fn test_saved_messages_carry_hash_and_token_count() {
+    let tokenizer = Arc::new(ByteCounter::default());
+    let saved = run_with_tokenizer(
+        PersistencePolicy::new().with_content_max_bytes(3),
+        Some(tokenizer.clone()),
+    )
+    .await;
+
+    // Both describe the stored (truncated) content
+    let message = saved.iter().find(|m| m.message_type == MessageType::Message).unwrap();
+    assert_eq!(message.content_sha256, Some(content_sha256("The")));
+    assert_eq!(message.token_count, Some(3));
+    assert!(tokenizer.models.lock().unwrap().iter().all(|model| model == "gpt-5"));
+}
+
+#[tokio::test]
+async fn test_without_tokenizer_only_hash_is_saved() {
+    let saved = run_reasoning_model(PersistencePolicy::default()).await;
+
+    assert!(saved.iter().all(|m| m.content_sha256.is_some() && m.token_count.is_none()));
+}
@@ -0,0 +1,80 @@
+//! Rate-limited LLM calls are announced and retried instead of failing the run
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphConfig, GraphErrorKind, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn answer_turn() -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: "Hello".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+async fn run(llm: ScriptedLLM, config: GraphConfig) -> Vec<StreamEvent> {
+    let graph = Graph::builder()
+        .llm_client(Arc::new(llm))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .config(config)
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+fn retries(events: &[StreamEvent]) -> Vec<(String, u64, u32)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Retrying { reason, in_seconds, attempt } => Some((reason.clone(), *in_seconds, *attempt)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn error_kind(events: &[StreamEvent]) -> Option<GraphErrorKind> {
+    events.iter().find_map(|event| match event {
+        StreamEvent::Error { kind, .. } => Some(*kind),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+async fn test_retries_after_rate_limit_and_succeeds() {
+    let llm = ScriptedLLM::new(vec![answer_turn()]).with_rate_limits(2, Some(Duration::from_millis(10)));
+    let events = run(llm, GraphConfig::new()).await;
+
+    assert_eq!(
+        retries(&events),
+        vec![("rate_limited".to_string(), 1, 1), ("rate_limited".to_string(), 1, 2)]
+    );
+    assert!(events.iter().any(|e| matches!(e, StreamEvent::Message { content, .. } if content == "Hello")));
+    assert_eq!(error_kind(&events), None);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_configured_retries() {
+    let llm = ScriptedLLM::new(vec![answer_turn()]).with_rate_limits(3, Some(Duration::from_millis(10)));
+    let events = run(llm, GraphConfig::new().with_rate_limit_retries(1)).await;
+
+    assert_eq!(retries(&events).len(), 1);
+    assert_eq!(error_kind(&events), Some(GraphErrorKind::ProviderError));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        StreamEvent::Error { error_code: Some(code), .. } if code == "rate_limited"
+    )));
+}
+
+#[tokio::test]
+async fn test_wait_past_deadline_fails_as_timeout() {
+    let llm = ScriptedLLM::new(vec![answer_turn()]).with_rate_limits(1, Some(Duration::from_secs(600)));
+    let events = run(llm, GraphConfig::new().with_timeout(Duration::from_secs(5))).await;
+
+    assert!(retries(&events).is_empty());
+    assert_eq!(error_kind(&events), Some(GraphErrorKind::ExecutionTimeout));
+}
@@ -0,0 +1,132 @@
+//! Reasoning summaries are streamed and persisted next to the full reasoning
+
+mod common;
+
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::{Message, ReasoningKind};
+use praxis_mcp::MCPToolExecutor;
+use praxis_persist::{DBMessage, MessageType};
+use std::sync::Arc;
+
+fn reasoning_turn(summary: Option<&str>) -> Vec<praxis_llm::StreamEvent> {
+    let mut events = vec![
+        praxis_llm::StreamEvent::Reasoning { content: "120 km over 2 hours, ".to_string(), kind: ReasoningKind::Raw, item_id: None },
+        praxis_llm::StreamEvent::Reasoning { content: "so divide distance by time.".to_string(), kind: ReasoningKind::Raw, item_id: None },
+    ];
+    if let Some(text) = summary {
+        events.push(praxis_llm::StreamEvent::ReasoningSummary { item_id: "rs_provider".to_string(), text: text.to_string() });
+    }
+    events.push(praxis_llm::StreamEvent::Message { content: "60 km/h".to_string(), choice_index: None, logprobs: None });
+    events.push(praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None });
+    events
+}
+
+async fn run(llm: ScriptedLLM, config: GraphConfig) -> (Vec<StreamEvent>, DBMessage) {
+    let persistence = Arc::new(RecordingPersistence::default());
+    let graph = Graph::builder()
+        .llm_client(Arc::new(llm))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_config(config)
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("average speed?")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+    let reasoning = persistence
+        .wait_for(2)
+        .await
+        .into_iter()
+        .find(|m| m.message_type == MessageType::Reasoning)
+        .expect("reasoning row");
+    (events, reasoning)
+}
+
+fn summary_events(events: &[StreamEvent]) -> Vec<(String, String)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::ReasoningSummary { item_id, text, .. } => Some((item_id.clone(), text.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_provider_summary_is_streamed_and_persisted() {
+    let llm = ScriptedLLM::new(vec![reasoning_turn(Some("Divided distance by time."))]);
+    let (events, reasoning) = run(llm, GraphConfig::new()).await;
+
+    assert_eq!(summary_events(&events), vec![("rs_provider".to_string(), "Divided distance by time.".to_string())]);
+    assert_eq!(reasoning.content, "120 km over 2 hours, so divide distance by time.");
+    assert_eq!(reasoning.summary.as_deref(), Some("Divided distance by time."));
+    assert_eq!(reasoning.reasoning_id.as_deref(), Some("rs_provider"));
+}
+
+#[tokio::test]
+async fn test_no_summary_without_provider_or_summary_model() {
+    let llm = ScriptedLLM::new(vec![reasoning_turn(None)]).with_chat_reply("unused");
+    let (events, reasoning) = run(llm, GraphConfig::new()).await;
+
+    assert!(summary_events(&events).is_empty());
+    assert_eq!(reasoning.summary, None);
+}
+
+#[tokio::test]
+async fn test_summary_model_fills_missing_summary() {
+    let llm = ScriptedLLM::new(vec![reasoning_turn(None)]).with_chat_reply("  Computed speed as distance over time. ");
+    let config = GraphConfig::new().with_reasoning_summary_model("gpt-4o-mini");
+    let (events, reasoning) = run(llm, config).await;
+
+    let summaries = summary_events(&events);
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].1, "Computed speed as distance over time.");
+    assert_eq!(Some(&summaries[0].0), reasoning.reasoning_id.as_ref());
+    assert_eq!(reasoning.summary.as_deref(), Some("Computed speed as distance over time."));
+}
+
+#[tokio::test]
+async fn test_streamed_summary_is_kept_apart_from_raw_reasoning() {
+    let summary = |text: &str| praxis_llm::StreamEvent::Reasoning { content: text.to_string(), kind: ReasoningKind::Summary, item_id: None };
+    let mut turn = reasoning_turn(None);
+    turn.insert(2, summary("Divided distance "));
+    turn.insert(3, summary("by time."));
+    let llm = ScriptedLLM::new(vec![turn]).with_chat_reply("unused");
+    let (events, reasoning) = run(llm, GraphConfig::new()).await;
+
+    let kinds: Vec<ReasoningKind> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Reasoning { kind, .. } => Some(*kind),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(kinds, [ReasoningKind::Raw, ReasoningKind::Raw, ReasoningKind::Summary, ReasoningKind::Summary]);
+    assert_eq!(reasoning.content, "120 km over 2 hours, so divide distance by time.");
+    assert_eq!(reasoning.summary.as_deref(), Some("Divided distance by time."));
+}
+
+#[tokio::test]
+async fn test_reasoning_row_takes_the_item_id_of_its_deltas() {
+    let reasoning = |text: &str| praxis_llm::StreamEvent::Reasoning {
+        content: text.to_string(),
+        kind: ReasoningKind::Raw,
+        item_id: Some("rs_from_deltas".to_string()),
+    };
+    let llm = ScriptedLLM::new(vec![vec![
+        reasoning("Distance over "),
+        reasoning("time."),
+        praxis_llm::StreamEvent::Message { content: "60 km/h".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]]);
+    let (_, row) = run(llm, GraphConfig::new()).await;
+
+    assert_eq!(row.content, "Distance over time.");
+    assert_eq!(row.reasoning_id.as_deref(), Some("rs_from_deltas"));
+    assert_eq!(row.summary, None);
+}
@@ -0,0 +1,94 @@
+//! Reasoning models get the run's tools through the Responses API path
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{Message, ReasoningKind, ToolChoice};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct Weather;
+
+#[async_trait]
+impl LocalTool for Weather {
+    fn name(&self) -> &str {
+        "get_weather"
+    }
+
+    fn description(&self) -> String {
+        "Current weather for a city".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "Sunny, 24°C".to_string() }])
+    }
+}
+
+fn turns() -> Vec<Vec<praxis_llm::StreamEvent>> {
+    vec![
+        vec![
+            praxis_llm::StreamEvent::Reasoning { content: "I should check the weather".to_string(), kind: ReasoningKind::Raw, item_id: None },
+            praxis_llm::StreamEvent::ToolCall {
+                index: 1,
+                id: Some("call_1".to_string()),
+                name: Some("get_weather".to_string()),
+                arguments: None,
+            },
+            praxis_llm::StreamEvent::ToolCall {
+                index: 1,
+                id: None,
+                name: None,
+                arguments: Some(r#"{"city": "Lisbon"}"#.to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "It's sunny in Lisbon.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]
+}
+
+#[tokio::test]
+async fn test_reasoning_model_calls_tools() {
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(Weather).await.unwrap();
+    let llm = Arc::new(ScriptedLLM::new(turns()));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .reasoning_client(llm.clone())
+        .mcp_executor(Arc::new(executor))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("weather in Lisbon?")], LLMConfig::new("gpt-5"));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+
+    let options = llm.response_options();
+    assert_eq!(options.len(), 2);
+    let tools = options[0].tools.as_ref().expect("tools sent to the Responses API");
+    assert_eq!(tools[0].function.name, "get_weather");
+    assert_eq!(options[0].tool_choice, Some(ToolChoice::auto()));
+
+    let result = events.iter().find_map(|event| match event {
+        StreamEvent::ToolResult { result, is_error: false, .. } => Some(result.as_str()),
+        _ => None,
+    });
+    assert_eq!(result, Some("Sunny, 24°C"));
+
+    // The second call sees the call and its result
+    let second = &llm.requests()[1];
+    assert!(second.iter().any(|m| matches!(m, Message::AI { tool_calls: Some(calls), .. } if calls[0].id == "call_1")));
+    assert!(second.iter().any(|m| matches!(m, Message::Tool { tool_call_id, .. } if tool_call_id == "call_1")));
+}
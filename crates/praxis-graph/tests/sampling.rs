@@ -0,0 +1,91 @@
+//! Sampling settings of the run's `LLMConfig` reach every LLM call
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_seed_top_p_and_stop_sent_on_each_call() {
+    let turns = vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("search".to_string()),
+                arguments: Some("{}".to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Done.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ];
+    let llm = Arc::new(ScriptedLLM::new(turns));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+
+    let llm_config = LLMConfig::new("gpt-4o").with_seed(42).with_top_p(0.5).with_stop(["END"]);
+    let input = GraphInput::new("thread-1", vec![Message::human("search")], llm_config);
+    collect_events(graph.spawn_run(input, None)).await;
+
+    let options = llm.chat_options();
+    assert_eq!(options.len(), 2);
+    for call in &options {
+        assert_eq!(call.seed, Some(42));
+        assert_eq!(call.top_p, Some(0.5));
+        assert_eq!(call.stop, Some(vec!["END".to_string()]));
+        assert_eq!(call.presence_penalty, None);
+    }
+}
+
+#[tokio::test]
+async fn test_out_of_range_temperature_fails_before_the_llm_call() {
+    let llm = Arc::new(ScriptedLLM::new(Vec::new()));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("Hi")], LLMConfig::new("gpt-4o").with_temperature(5.0));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+
+    assert!(events.iter().any(|event| matches!(
+        event,
+        StreamEvent::Error { message, error_code: Some(code), .. }
+            if code == "validation_failed" && message.contains("options.temperature")
+    )), "{:?}", events);
+    assert!(llm.chat_options().is_empty());
+}
+
+#[tokio::test]
+async fn test_extra_body_sent_on_chat_and_reasoning_calls() {
+    let extra = serde_json::json!({ "store": false }).as_object().unwrap().clone();
+    let answer = || vec![
+        praxis_llm::StreamEvent::Message { content: "Hi.".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ];
+    let llm = Arc::new(ScriptedLLM::new(vec![answer(), answer()]));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .reasoning_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+
+    for model in ["gpt-4o", "gpt-5"] {
+        let config = LLMConfig::new(model).with_extra_body(extra.clone());
+        collect_events(graph.spawn_run(GraphInput::new("thread-1", vec![Message::human("Hi")], config), None)).await;
+    }
+
+    assert_eq!(llm.chat_options()[0].extra_body, Some(extra.clone()));
+    assert_eq!(llm.response_options()[0].extra_body, Some(extra));
+}
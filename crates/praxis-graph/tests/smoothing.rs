@@ -0,0 +1,72 @@
+//! Bursty message deltas are split into paced events without changing the text
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, SmoothingConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Two multi-kilobyte bursts, like a provider flushing its buffer after a stall
+fn bursty_turn() -> (Vec<praxis_llm::StreamEvent>, String) {
+    let first = "The quick brown fox jumps over the lazy dog. ".repeat(60);
+    let second = "Ünïcödé text keeps its bytes intact. ".repeat(30);
+    let turn = vec![
+        praxis_llm::StreamEvent::Message { content: first.clone(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Message { content: "ok".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Message { content: second.clone(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ];
+    (turn, format!("{}ok{}", first, second))
+}
+
+async fn run(config: GraphConfig) -> (Vec<StreamEvent>, String) {
+    let (turn, text) = bursty_turn();
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![turn])))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .config(config)
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new("gpt-4o"));
+    (collect_events(graph.spawn_run(input, None)).await, text)
+}
+
+fn message_chunks(events: &[StreamEvent]) -> Vec<&str> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Message { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_bursts_are_split_and_text_is_identical() {
+    let smoothing = SmoothingConfig { target_chars_per_event: 100, max_delay_ms: 40 };
+    let started = Instant::now();
+    let (events, text) = run(GraphConfig::new().with_smoothing(smoothing)).await;
+
+    let chunks = message_chunks(&events);
+    assert!(chunks.len() > 30, "expected the bursts to be split, got {} events", chunks.len());
+    assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 100));
+    assert_eq!(chunks.concat().as_bytes(), text.as_bytes());
+
+    // Done follows the last chunk, and nothing waited much past the budget
+    let last_message = events.iter().rposition(|e| matches!(e, StreamEvent::Message { .. })).unwrap();
+    let done = events.iter().position(|e| matches!(e, StreamEvent::Done { .. })).unwrap();
+    assert!(done > last_message);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn test_without_smoothing_deltas_pass_through() {
+    let (events, text) = run(GraphConfig::new()).await;
+
+    let chunks = message_chunks(&events);
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks.concat(), text);
+}
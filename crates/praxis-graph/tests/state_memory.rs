@@ -0,0 +1,188 @@
+//! Memory of a long multi-tool run stays bounded when a context policy
+//! compacts tool results the model can no longer be sent
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use praxis_graph::{ContextPolicy, Graph, GraphConfig, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{
+    ChatClient, ChatRequest, ChatResponse, LLMClient, Message, ReasoningClient, ResponseOutput, ResponseRequest,
+};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicIsize, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Bytes currently allocated by the test binary
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LIVE_BYTES.fetch_add(new_size as isize - layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The counter is process-wide, so runs are measured one at a time
+static MEASURING: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+const ROUNDS: u32 = 30;
+const RESULT_BYTES: usize = 100 * 1024;
+
+/// Calls `fetch_page` for `ROUNDS` turns, then answers, sampling live
+/// memory as each request arrives (without keeping the requests)
+struct ResearchLLM {
+    calls: AtomicU32,
+    samples: Mutex<Vec<isize>>,
+}
+
+impl ResearchLLM {
+    fn new() -> Self {
+        Self { calls: AtomicU32::new(0), samples: Mutex::new(Vec::with_capacity(ROUNDS as usize + 1)) }
+    }
+}
+
+#[async_trait]
+impl ChatClient for ResearchLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn chat_stream(
+        &self,
+        _request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
+        // Let the event consumer drain what earlier rounds sent
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        self.samples.lock().unwrap().push(LIVE_BYTES.load(Ordering::Relaxed));
+
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        let events = if call < ROUNDS {
+            vec![
+                praxis_llm::StreamEvent::ToolCall {
+                    index: 0,
+                    id: Some(format!("call_{}", call)),
+                    name: Some("fetch_page".to_string()),
+                    arguments: Some(format!(r#"{{"url": "https://example.com/{}"}}"#, call)),
+                },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+            ]
+        } else {
+            vec![
+                praxis_llm::StreamEvent::Message { content: "Here is the report.".to_string(), choice_index: None, logprobs: None },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+            ]
+        };
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for ResearchLLM {
+    async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(
+        &self,
+        _request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>> {
+        anyhow::bail!("not scripted")
+    }
+}
+
+impl LLMClient for ResearchLLM {}
+
+/// A page fetch returning `RESULT_BYTES` of text
+struct FetchPage;
+
+#[async_trait]
+impl LocalTool for FetchPage {
+    fn name(&self) -> &str {
+        "fetch_page"
+    }
+
+    fn description(&self) -> String {
+        "Fetch a web page".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "url": { "type": "string" } }, "required": ["url"] })
+    }
+
+    async fn call(&self, _arguments: Value) -> Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "x".repeat(RESULT_BYTES) }])
+    }
+}
+
+/// Live bytes at each LLM request of a `ROUNDS`-round run
+async fn sample_run(policy: Option<ContextPolicy>) -> Vec<isize> {
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(FetchPage).await.unwrap();
+    let llm = Arc::new(ResearchLLM::new());
+
+    let mut builder = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(executor))
+        .with_config(GraphConfig::new().with_max_iterations(2 * ROUNDS as usize + 1).with_event_channel_capacity(1));
+    if let Some(policy) = policy {
+        builder = builder.with_context_policy(policy);
+    }
+    let graph = builder.build().unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("research this")], LLMConfig::new("gpt-4o"));
+    let mut rx = graph.spawn_run(input, None);
+    while let Some(event) = rx.recv().await {
+        assert!(!matches!(event, StreamEvent::Error { .. }), "{:?}", event);
+        if matches!(event, StreamEvent::EndStream { .. }) {
+            break;
+        }
+    }
+
+    let samples = llm.samples.lock().unwrap().clone();
+    assert_eq!(samples.len(), ROUNDS as usize + 1);
+    samples
+}
+
+/// Memory added between the 10th request and the last one
+fn growth(samples: &[isize]) -> isize {
+    samples[samples.len() - 1] - samples[10]
+}
+
+#[tokio::test]
+async fn test_compaction_keeps_memory_flat_across_rounds() {
+    let _measuring = MEASURING.lock().await;
+
+    let samples = sample_run(Some(ContextPolicy::LastK { k: 6 })).await;
+
+    // 20 more rounds of 100 KB results: at most a few results' worth of noise
+    assert!(growth(&samples) < 3 * RESULT_BYTES as isize, "grew by {} bytes", growth(&samples));
+}
+
+#[tokio::test]
+async fn test_without_compaction_every_result_stays_in_memory() {
+    let _measuring = MEASURING.lock().await;
+
+    let samples = sample_run(None).await;
+
+    assert!(growth(&samples) >= 20 * RESULT_BYTES as isize, "grew by {} bytes", growth(&samples));
+}
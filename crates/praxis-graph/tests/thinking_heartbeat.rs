@@ -0,0 +1,77 @@
+//! Slow-to-answer models are reported with periodic `Thinking` events
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, StreamEvent, ThinkingHeartbeatConfig};
+use praxis_llm::{Message, ReasoningKind};
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn heartbeat() -> ThinkingHeartbeatConfig {
+    ThinkingHeartbeatConfig { first_after_ms: 3000, interval_ms: 3000 }
+}
+
+/// Three reasoning deltas, then the answer in two deltas
+fn thinking_turn() -> Vec<praxis_llm::StreamEvent> {
+    let reasoning = |text: &str| praxis_llm::StreamEvent::Reasoning { content: text.to_string(), kind: ReasoningKind::Raw, item_id: None };
+    let message = |text: &str| praxis_llm::StreamEvent::Message { content: text.to_string(), choice_index: None, logprobs: None };
+    vec![
+        reasoning("Let me"),
+        reasoning(" think"),
+        reasoning(" hard."),
+        message("42"),
+        message("."),
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+async fn run(gap: Duration, config: GraphConfig) -> Vec<StreamEvent> {
+    let llm = ScriptedLLM::new(vec![thinking_turn()]).with_event_gap(gap);
+    let graph = Graph::builder()
+        .llm_client(Arc::new(llm))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .config(config)
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+fn heartbeats(events: &[StreamEvent]) -> Vec<u64> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Thinking { elapsed_ms } => Some(*elapsed_ms),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_heartbeats_until_first_message_delta() {
+    // Deltas at 2.5s, 5s, 7.5s; the answer starts at 10s and ends at 12.5s
+    let events = run(Duration::from_millis(2500), GraphConfig::new().with_thinking_heartbeat(heartbeat())).await;
+
+    assert_eq!(heartbeats(&events), vec![3000, 6000, 9000]);
+    let last_heartbeat = events.iter().rposition(|e| matches!(e, StreamEvent::Thinking { .. })).unwrap();
+    let first_message = events.iter().position(|e| matches!(e, StreamEvent::Message { .. })).unwrap();
+    assert!(last_heartbeat < first_message);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_fast_models_send_no_heartbeat() {
+    // The answer starts at 2s, before the first heartbeat is due
+    let events = run(Duration::from_millis(500), GraphConfig::new().with_thinking_heartbeat(heartbeat())).await;
+
+    assert!(heartbeats(&events).is_empty());
+    assert!(events.iter().any(|e| matches!(e, StreamEvent::Message { .. })));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_heartbeats_are_off_by_default() {
+    let events = run(Duration::from_secs(5), GraphConfig::new()).await;
+
+    assert!(heartbeats(&events).is_empty());
+}
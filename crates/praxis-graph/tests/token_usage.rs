@@ -0,0 +1,68 @@
+//! Provider token usage is forwarded and recorded on the turn's assistant row
+
+mod common;
+
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use praxis_persist::MessageType;
+use std::sync::Arc;
+
+fn usage(input_tokens: u32, output_tokens: u32) -> praxis_llm::StreamEvent {
+    praxis_llm::StreamEvent::Usage {
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        reasoning_tokens: None,
+        cached_tokens: None,
+    }
+}
+
+#[tokio::test]
+async fn test_each_turn_records_its_own_usage() {
+    let turns = vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("search".to_string()),
+                arguments: Some("{}".to_string()),
+            },
+            usage(100, 20),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Found it.".to_string(), choice_index: None, logprobs: None },
+            usage(180, 5),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ];
+    let persistence = Arc::new(RecordingPersistence::default());
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(turns)))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("find it")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    let usage_at = events.iter().position(|e| matches!(e, StreamEvent::Usage { .. })).expect("usage event");
+    let done_at = events.iter().position(|e| matches!(e, StreamEvent::Done { .. })).unwrap();
+    assert!(usage_at < done_at);
+
+    let saved = persistence.wait_for(3).await;
+    let tokens = |message_type: MessageType| {
+        let row = saved.iter().find(|m| m.message_type == message_type).unwrap();
+        row.usage.as_ref().map(|usage| (usage.input_tokens, usage.output_tokens))
+    };
+    assert_eq!(tokens(MessageType::ToolCall), Some((100, 20)));
+    assert_eq!(tokens(MessageType::Message), Some((180, 5)));
+    assert_eq!(tokens(MessageType::ToolResult), None);
+}
@@ -0,0 +1,123 @@
+//! Tool calls are described as activity lines, streamed and optionally persisted
+
+mod common;
+
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{
+    ActivityConfig, Graph, GraphConfig, GraphInput, LLMConfig, PersistenceContext, StreamEvent, ToolCallDescriber,
+};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use praxis_persist::{DBMessage, MessageType};
+use serde_json::Value;
+use std::sync::Arc;
+
+fn turns(arguments: &str) -> Vec<Vec<praxis_llm::StreamEvent>> {
+    vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("search".to_string()),
+                arguments: Some(arguments.to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Found it.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]
+}
+
+fn activity(persist: bool) -> ActivityConfig {
+    ActivityConfig {
+        templates: [("search".to_string(), "Searching for '{query}'".to_string())].into(),
+        persist,
+    }
+}
+
+async fn run(arguments: &str, config: GraphConfig, describer: Option<Arc<dyn ToolCallDescriber>>) -> (Vec<StreamEvent>, DBMessage) {
+    let persistence = Arc::new(RecordingPersistence::default());
+    let mut builder = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(turns(arguments))))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_config(config)
+        .with_persistence(persistence.clone());
+    if let Some(describer) = describer {
+        builder = builder.with_tool_call_describer(describer);
+    }
+    let graph = builder.build().unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("channels?")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext {
+        thread_id: "thread-1".to_string(),
+        user_id: "user-1".to_string(),
+    };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+    let call = persistence
+        .wait_for(3)
+        .await
+        .into_iter()
+        .find(|m| m.message_type == MessageType::ToolCall)
+        .expect("tool call row");
+    (events, call)
+}
+
+fn activity_events(events: &[StreamEvent]) -> Vec<(String, String)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Activity { tool_call_id, text, .. } => Some((tool_call_id.clone(), text.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_activity_follows_the_tool_call_and_precedes_its_result() {
+    let config = GraphConfig::new().with_activity(activity(false));
+    let (events, call) = run(r#"{"query": "rust async channels"}"#, config, None).await;
+
+    assert_eq!(
+        activity_events(&events),
+        vec![("call_1".to_string(), "Searching for 'rust async channels'".to_string())]
+    );
+    let position = |matches: fn(&StreamEvent) -> bool| events.iter().position(matches).unwrap();
+    let activity = position(|e| matches!(e, StreamEvent::Activity { .. }));
+    assert!(position(|e| matches!(e, StreamEvent::ToolCall { .. })) < activity);
+    assert!(activity < position(|e| matches!(e, StreamEvent::ToolResult { .. })));
+    assert_eq!(call.summary, None, "not persisted unless asked");
+}
+
+#[tokio::test]
+async fn test_missing_fields_fall_back_to_the_generic_line_and_persist() {
+    let config = GraphConfig::new().with_activity(activity(true));
+    let (events, call) = run(r#"{"q": "typo"}"#, config, None).await;
+
+    assert_eq!(activity_events(&events), vec![("call_1".to_string(), "Using search".to_string())]);
+    assert_eq!(call.summary.as_deref(), Some("Using search"));
+}
+
+#[tokio::test]
+async fn test_no_activity_by_default() {
+    let (events, _) = run(r#"{"query": "x"}"#, GraphConfig::new(), None).await;
+    assert!(activity_events(&events).is_empty());
+}
+
+struct Shouting;
+
+impl ToolCallDescriber for Shouting {
+    fn describe(&self, tool_name: &str, arguments: &Value) -> Option<String> {
+        Some(format!("{} {}!", tool_name.to_uppercase(), arguments["query"].as_str()?))
+    }
+}
+
+#[tokio::test]
+async fn test_builder_describer_replaces_templates() {
+    let config = GraphConfig::new().with_activity(activity(true));
+    let (events, call) = run(r#"{"query": "now"}"#, config, Some(Arc::new(Shouting))).await;
+
+    assert_eq!(activity_events(&events), vec![("call_1".to_string(), "SEARCH now!".to_string())]);
+    assert_eq!(call.summary.as_deref(), Some("SEARCH now!"));
+}
@@ -0,0 +1,128 @@
+//! Truncated tool call arguments are bounced back to the model, never executed
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Search tool that counts how often it actually ran
+struct CountingSearch {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl LocalTool for CountingSearch {
+    fn name(&self) -> &str {
+        "search"
+    }
+
+    fn description(&self) -> String {
+        "Search the web".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": { "query": { "type": "string" }, "limit": { "type": "integer" } },
+            "required": ["query", "limit"]
+        })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![ToolResponse::Text { text: "results".to_string() }])
+    }
+}
+
+/// A tool call whose arguments arrive in the given deltas, then `Done`
+fn tool_call_turn(deltas: &[&str]) -> Vec<praxis_llm::StreamEvent> {
+    let mut events = vec![praxis_llm::StreamEvent::ToolCall {
+        index: 0,
+        id: Some("call_1".to_string()),
+        name: Some("search".to_string()),
+        arguments: None,
+    }];
+    events.extend(deltas.iter().map(|delta| praxis_llm::StreamEvent::ToolCall {
+        index: 0,
+        id: None,
+        name: None,
+        arguments: Some(delta.to_string()),
+    }));
+    events.push(praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None });
+    events
+}
+
+fn answer_turn() -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: "Sorry, let me retry".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+async fn run(deltas: &[&str]) -> (Vec<StreamEvent>, usize) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let executor = MCPToolExecutor::new();
+    executor
+        .register_local_tool(CountingSearch { calls: calls.clone() })
+        .await
+        .unwrap();
+
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(vec![tool_call_turn(deltas), answer_turn()])))
+        .mcp_executor(Arc::new(executor))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("weather in SF?")], LLMConfig::new("gpt-4o"));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+    (events, calls.load(Ordering::SeqCst))
+}
+
+fn tool_result(events: &[StreamEvent]) -> (String, bool) {
+    events
+        .iter()
+        .find_map(|event| match event {
+            StreamEvent::ToolResult { result, is_error, .. } => Some((result.clone(), *is_error)),
+            _ => None,
+        })
+        .expect("tool result event")
+}
+
+#[tokio::test]
+async fn test_truncated_json_is_not_executed() {
+    // The closing delta was dropped
+    let (events, calls) = run(&[r#"{"query": "#, r#""SF", "limit": 3"#]).await;
+
+    assert_eq!(calls, 0);
+    let (result, is_error) = tool_result(&events);
+    assert!(is_error);
+    assert!(result.contains("call the tool again"), "{}", result);
+    // The model got the error and answered; the run did not fail
+    assert!(events.iter().any(|e| matches!(e, StreamEvent::Message { .. })));
+    assert!(!events.iter().any(|e| matches!(e, StreamEvent::Error { .. })));
+}
+
+#[tokio::test]
+async fn test_parseable_fragment_failing_schema_is_not_executed() {
+    // Valid JSON, but cut before the required `limit`
+    let (events, calls) = run(&[r#"{"query": "SF"}"#]).await;
+
+    assert_eq!(calls, 0);
+    let (result, is_error) = tool_result(&events);
+    assert!(is_error);
+    assert!(result.contains("limit"), "{}", result);
+}
+
+#[tokio::test]
+async fn test_complete_arguments_are_executed() {
+    let (events, calls) = run(&[r#"{"query": "SF", "#, r#""limit": 3}"#]).await;
+
+    assert_eq!(calls, 1);
+    assert_eq!(tool_result(&events), ("results".to_string(), false));
+}
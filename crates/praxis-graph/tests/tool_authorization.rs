@@ -0,0 +1,162 @@
+//! Tool calls denied by the authorizer are reported to the model, not executed
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{
+    AuthorizationRule, Graph, GraphInput, LLMConfig, RuleEffect, RulesAuthorizer, StreamEvent,
+};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Record tool that counts how often it actually ran
+struct RecordTool {
+    name: &'static str,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl LocalTool for RecordTool {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> String {
+        format!("{} a record", self.name)
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "id": { "type": "string" } }, "required": ["id"] })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![ToolResponse::Text { text: format!("{} done", self.name) }])
+    }
+}
+
+fn call_turn(id: &str, tool: &str) -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::ToolCall {
+            index: 0,
+            id: Some(id.to_string()),
+            name: Some(tool.to_string()),
+            arguments: Some(r#"{"id": "r-1"}"#.to_string()),
+        },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+    ]
+}
+
+fn answer_turn() -> Vec<praxis_llm::StreamEvent> {
+    vec![
+        praxis_llm::StreamEvent::Message { content: "Done".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]
+}
+
+fn admin_only_delete() -> RulesAuthorizer {
+    RulesAuthorizer::new(RuleEffect::Allow)
+        .with_rule(AuthorizationRule {
+            tool: "delete_*".to_string(),
+            tenants: vec![],
+            roles: vec!["admin".to_string()],
+            effect: RuleEffect::Allow,
+            reason: None,
+        })
+        .with_rule(AuthorizationRule {
+            tool: "delete_*".to_string(),
+            tenants: vec![],
+            roles: vec![],
+            effect: RuleEffect::Deny,
+            reason: Some("deleting records requires the admin role; archive instead".to_string()),
+        })
+}
+
+struct Run {
+    events: Vec<StreamEvent>,
+    deletes: usize,
+    archives: usize,
+}
+
+async fn run(turns: Vec<Vec<praxis_llm::StreamEvent>>, roles: &[&str]) -> Run {
+    let deletes = Arc::new(AtomicUsize::new(0));
+    let archives = Arc::new(AtomicUsize::new(0));
+    let executor = MCPToolExecutor::new();
+    executor
+        .register_local_tool(RecordTool { name: "delete_record", calls: deletes.clone() })
+        .await
+        .unwrap();
+    executor
+        .register_local_tool(RecordTool { name: "archive_record", calls: archives.clone() })
+        .await
+        .unwrap();
+
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(turns)))
+        .mcp_executor(Arc::new(executor))
+        .with_tool_authorizer(Arc::new(admin_only_delete()))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("remove record r-1")], LLMConfig::new("gpt-4o"))
+        .with_tenant("acme")
+        .with_roles(roles.iter().copied());
+    let events = collect_events(graph.spawn_run(input, None)).await;
+    Run {
+        events,
+        deletes: deletes.load(Ordering::SeqCst),
+        archives: archives.load(Ordering::SeqCst),
+    }
+}
+
+fn tool_results(events: &[StreamEvent]) -> Vec<(String, bool)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::ToolResult { result, is_error, .. } => Some((result.clone(), *is_error)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_allowed_call_executes() {
+    let run = run(vec![call_turn("call_1", "delete_record"), answer_turn()], &["admin"]).await;
+
+    assert_eq!(run.deletes, 1);
+    assert_eq!(tool_results(&run.events), vec![("delete_record done".to_string(), false)]);
+}
+
+#[tokio::test]
+async fn test_denied_call_returns_policy_reason() {
+    let run = run(vec![call_turn("call_1", "delete_record"), answer_turn()], &["viewer"]).await;
+
+    assert_eq!(run.deletes, 0);
+    let results = tool_results(&run.events);
+    assert_eq!(results.len(), 1);
+    let (result, is_error) = &results[0];
+    assert!(is_error);
+    assert!(result.contains("requires the admin role"), "{}", result);
+}
+
+#[tokio::test]
+async fn test_model_recovers_after_deny() {
+    let turns = vec![
+        call_turn("call_1", "delete_record"),
+        call_turn("call_2", "archive_record"),
+        answer_turn(),
+    ];
+    let run = run(turns, &[]).await;
+
+    assert_eq!(run.deletes, 0);
+    assert_eq!(run.archives, 1);
+    let results = tool_results(&run.events);
+    assert!(results[0].1);
+    assert_eq!(results[1], ("archive_record done".to_string(), false));
+    assert!(run.events.iter().any(|e| matches!(e, StreamEvent::Message { .. })));
+    assert!(!run.events.iter().any(|e| matches!(e, StreamEvent::Error { .. })));
+}
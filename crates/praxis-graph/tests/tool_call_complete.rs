@@ -0,0 +1,142 @@
+//! Each tool call is sent whole once its arguments are assembled: after all
+//! of its deltas and before its result
+
+mod common;
+
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::MCPToolExecutor;
+use std::sync::Arc;
+
+fn delta(index: u32, id: Option<&str>, name: Option<&str>, arguments: &str) -> praxis_llm::StreamEvent {
+    praxis_llm::StreamEvent::ToolCall {
+        index,
+        id: id.map(str::to_string),
+        name: name.map(str::to_string),
+        arguments: Some(arguments.to_string()),
+    }
+}
+
+/// Two calls whose argument fragments interleave, then the answer
+fn turns() -> Vec<Vec<praxis_llm::StreamEvent>> {
+    vec![
+        vec![
+            delta(0, Some("call_1"), Some("get_weather"), ""),
+            delta(0, None, None, r#"{"city": "#),
+            delta(1, Some("call_2"), Some("get_time"), r#"{"zone""#),
+            delta(0, None, None, r#""SF"}"#),
+            delta(1, None, None, r#": "PST"}"#),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Sunny, 9am.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]
+}
+
+async fn run() -> Vec<StreamEvent> {
+    let graph = Graph::builder()
+        .llm_client(Arc::new(ScriptedLLM::new(turns())))
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("Weather and time?")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await
+}
+
+#[tokio::test]
+async fn test_complete_carries_the_assembled_arguments_in_call_order() {
+    let events = run().await;
+
+    let completed: Vec<(String, String, String)> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::ToolCallComplete { id, name, arguments, .. } => {
+                Some((id.clone(), name.clone(), arguments.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        completed,
+        [
+            ("call_1".to_string(), "get_weather".to_string(), r#"{"city": "SF"}"#.to_string()),
+            ("call_2".to_string(), "get_time".to_string(), r#"{"zone": "PST"}"#.to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_complete_follows_its_deltas_and_precedes_its_result() {
+    let events = run().await;
+
+    for (index, id) in [(0, "call_1"), (1, "call_2")] {
+        let last_delta = events
+            .iter()
+            .rposition(|event| matches!(event, StreamEvent::ToolCall { index: i, .. } if *i == index))
+            .unwrap();
+        let complete = events
+            .iter()
+            .position(|event| matches!(event, StreamEvent::ToolCallComplete { id: i, .. } if i == id))
+            .unwrap();
+        let result = events
+            .iter()
+            .position(|event| matches!(event, StreamEvent::ToolResult { tool_call_id, .. } if tool_call_id == id))
+            .unwrap();
+        assert!(last_delta < complete, "{} completed before its last delta", id);
+        assert!(complete < result, "{} ran before it was completed", id);
+    }
+
+    let complete = events
+        .iter()
+        .find(|event| matches!(event, StreamEvent::ToolCallComplete { id, .. } if id == "call_1"))
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(complete).unwrap(),
+        serde_json::json!({
+            "type": "tool_call_complete",
+            "id": "call_1",
+            "name": "get_weather",
+            "arguments": r#"{"city": "SF"}"#,
+            "agent": "assistant",
+            "provenance": { "node": "llm", "iteration": 0 }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_calls_keep_index_order_when_a_later_call_starts_first() {
+    let turns = vec![
+        vec![
+            delta(1, Some("call_2"), Some("get_time"), r#"{"zone": "PST"}"#),
+            delta(0, Some("call_1"), Some("get_weather"), r#"{"city": "#),
+            delta(0, None, None, r#""SF"}"#),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Sunny, 9am.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ];
+    let llm = Arc::new(ScriptedLLM::new(turns));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("Weather and time?")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await;
+
+    // The AI message sent back with the results lists the calls by index
+    let second_call = &llm.requests()[1];
+    let ids: Vec<&str> = second_call
+        .iter()
+        .find_map(|message| match message {
+            Message::AI { tool_calls: Some(calls), .. } => Some(calls.iter().map(|call| call.id.as_str()).collect()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(ids, ["call_1", "call_2"]);
+}
@@ -0,0 +1,126 @@
+//! A configured tool choice applies to the run's first LLM call only
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig};
+use praxis_llm::{Message, ToolChoice};
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// The tool the forced choice names, so the request passes validation
+struct KnowledgeBase;
+
+#[async_trait]
+impl LocalTool for KnowledgeBase {
+    fn name(&self) -> &str {
+        "search_knowledge_base"
+    }
+
+    fn description(&self) -> String {
+        "Search the help center".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "query": { "type": "string" } } })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "Refunds take 5 days.".to_string() }])
+    }
+}
+
+fn turns() -> Vec<Vec<praxis_llm::StreamEvent>> {
+    vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("search_knowledge_base".to_string()),
+                arguments: Some(r#"{"query": "refunds"}"#.to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Refunds take 5 days.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]
+}
+
+async fn tool_choices(config: GraphConfig, llm_config: LLMConfig) -> Vec<Option<ToolChoice>> {
+    let llm = Arc::new(ScriptedLLM::new(turns()));
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(KnowledgeBase).await.unwrap();
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(executor))
+        .with_config(config)
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("how long do refunds take?")], llm_config);
+    collect_events(graph.spawn_run(input, None)).await;
+    llm.tool_choices()
+}
+
+#[tokio::test]
+async fn test_forced_function_on_first_call_then_auto() {
+    let config = GraphConfig::new().with_tool_choice(ToolChoice::function("search_knowledge_base"));
+    let choices = tool_choices(config, LLMConfig::new("gpt-4o")).await;
+
+    assert_eq!(
+        choices,
+        vec![Some(ToolChoice::function("search_knowledge_base")), Some(ToolChoice::auto())]
+    );
+}
+
+#[tokio::test]
+async fn test_run_override_replaces_config() {
+    let config = GraphConfig::new().with_tool_choice(ToolChoice::function("search_knowledge_base"));
+    let llm_config = LLMConfig::new("gpt-4o").with_tool_choice(ToolChoice::required());
+    let choices = tool_choices(config, llm_config).await;
+
+    assert_eq!(choices, vec![Some(ToolChoice::required()), Some(ToolChoice::auto())]);
+}
+
+#[tokio::test]
+async fn test_auto_by_default() {
+    let choices = tool_choices(GraphConfig::new(), LLMConfig::new("gpt-4o")).await;
+    assert_eq!(choices, vec![Some(ToolChoice::auto()), Some(ToolChoice::auto())]);
+}
+
+#[tokio::test]
+async fn test_parallel_tool_calls_flag_sent_on_each_call() {
+    let llm = Arc::new(ScriptedLLM::new(turns()));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_config(GraphConfig::new().with_parallel_tool_calls(false))
+        .build()
+        .unwrap();
+
+    let input = GraphInput::new("thread-1", vec![Message::human("how long do refunds take?")], LLMConfig::new("gpt-4o"));
+    collect_events(graph.spawn_run(input, None)).await;
+
+    let flags: Vec<_> = llm.chat_options().iter().map(|options| options.parallel_tool_calls).collect();
+    assert_eq!(flags, vec![Some(false), Some(false)]);
+}
+
+#[test]
+fn test_tool_choice_from_toml() {
+    let config: GraphConfig = toml::from_str(
+        r#"
+        [tool_choice]
+        type = "function"
+        function = { name = "search_knowledge_base" }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.tool_choice, Some(ToolChoice::function("search_knowledge_base")));
+
+    let config: GraphConfig = toml::from_str(r#"tool_choice = "required""#).unwrap();
+    assert_eq!(config.tool_choice, Some(ToolChoice::required()));
+}
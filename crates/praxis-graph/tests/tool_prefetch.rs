@@ -0,0 +1,76 @@
+//! The first LLM request, chat or reasoning, reuses the tool list fetched
+//! when the run started
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolResponse};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Counts how often its definition was listed
+struct ListedTool {
+    listings: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl LocalTool for ListedTool {
+    fn name(&self) -> &str {
+        "lookup"
+    }
+
+    fn description(&self) -> String {
+        "Look something up".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        self.listings.fetch_add(1, Ordering::SeqCst);
+        json!({ "type": "object", "properties": {} })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "found".to_string() }])
+    }
+}
+
+/// Listings made by a one-turn run on `model`
+async fn listings_for(model: &str) -> (usize, Vec<StreamEvent>) {
+    let listings = Arc::new(AtomicUsize::new(0));
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(ListedTool { listings: listings.clone() }).await.unwrap();
+
+    let llm = Arc::new(ScriptedLLM::new(vec![vec![
+        praxis_llm::StreamEvent::Message { content: "Hi".to_string(), choice_index: None, logprobs: None },
+        praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+    ]]));
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .reasoning_client(llm)
+        .mcp_executor(Arc::new(executor))
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("hi")], LLMConfig::new(model));
+    let events = collect_events(graph.spawn_run(input, None)).await;
+    (listings.load(Ordering::SeqCst), events)
+}
+
+#[tokio::test]
+async fn test_first_request_lists_tools_once() {
+    let (listings, events) = listings_for("gpt-4o").await;
+
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Message { .. })));
+    // Option adjustments and the request share one listing
+    assert_eq!(listings, 1);
+}
+
+#[tokio::test]
+async fn test_first_reasoning_request_lists_tools_once() {
+    let (listings, events) = listings_for("gpt-5").await;
+
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Message { .. })));
+    assert_eq!(listings, 1);
+}
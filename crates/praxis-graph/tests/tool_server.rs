@@ -0,0 +1,106 @@
+//! Tool results name the server that ran each call
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPClient, MCPToolExecutor, ToolResponse, LOCAL_TOOL_SERVER};
+use praxis_persist::MessageType;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+struct Clock;
+
+#[async_trait]
+impl LocalTool for Clock {
+    fn name(&self) -> &str {
+        "now"
+    }
+
+    fn description(&self) -> String {
+        "Current time".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "10:00".to_string() }])
+    }
+}
+
+/// Stdio server named `name` that serves one tool, `tool` (praxis-mcp's test fixture)
+async fn server(name: &str, tool: &str) -> MCPClient {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../praxis-mcp/tests/fixtures/slow_server.sh");
+    MCPClient::new_stdio(name, fixture, ["0", "0", tool]).await.unwrap()
+}
+
+fn call(index: u32, id: &str, name: &str) -> praxis_llm::StreamEvent {
+    praxis_llm::StreamEvent::ToolCall {
+        index,
+        id: Some(id.to_string()),
+        name: Some(name.to_string()),
+        arguments: Some("{}".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn test_results_are_attributed_to_their_server() {
+    let executor = MCPToolExecutor::new();
+    executor.add_server(server("weather", "get_weather").await).await.unwrap();
+    executor.add_server(server("search", "web_search").await).await.unwrap();
+    executor.register_local_tool(Clock).await.unwrap();
+    let executor = Arc::new(executor);
+
+    let llm = Arc::new(ScriptedLLM::new(vec![
+        vec![
+            call(0, "call_weather", "get_weather"),
+            call(1, "call_search", "web_search"),
+            call(2, "call_now", "now"),
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Done.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]));
+    let persistence = Arc::new(RecordingPersistence::default());
+    let graph = Graph::builder()
+        .llm_client(llm)
+        .mcp_executor(executor.clone())
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("Look it all up")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext { thread_id: "thread-1".to_string(), user_id: "user-1".to_string() };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+    let saved = persistence.wait_for(6).await;
+    executor.shutdown_all().await.unwrap();
+
+    let expected: HashMap<&str, &str> =
+        [("call_weather", "weather"), ("call_search", "search"), ("call_now", LOCAL_TOOL_SERVER)].into();
+
+    let streamed: HashMap<&str, &str> = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::ToolResult { tool_call_id, tool_server, is_error: false, .. } => {
+                Some((tool_call_id.as_str(), tool_server.as_deref()?))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(streamed, expected);
+
+    let persisted: HashMap<&str, &str> = saved
+        .iter()
+        .filter(|m| m.message_type == MessageType::ToolResult)
+        .filter_map(|m| Some((m.tool_call_id.as_deref()?, m.tool_server.as_deref()?)))
+        .collect();
+    assert_eq!(persisted, expected);
+}
@@ -0,0 +1,177 @@
+//! Write tools configured with `verify_with` are checked by a read call
+//! before the model sees their result
+
+mod common;
+
+use async_trait::async_trait;
+use common::{collect_events, RecordingPersistence, ScriptedLLM};
+use praxis_graph::{Graph, GraphInput, LLMConfig, PersistenceContext, StreamEvent};
+use praxis_llm::Message;
+use praxis_mcp::{LocalTool, MCPToolExecutor, ToolConfig, ToolResponse};
+use praxis_persist::{MessageType, ToolVerification};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Tickets = Arc<Mutex<HashMap<String, String>>>;
+
+/// Sets a ticket's status, unless `broken`, when it reports success anyway
+struct UpdateTicket {
+    tickets: Tickets,
+    broken: bool,
+}
+
+#[async_trait]
+impl LocalTool for UpdateTicket {
+    fn name(&self) -> &str {
+        "update_ticket"
+    }
+
+    fn description(&self) -> String {
+        "Set a ticket's status".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "required": ["id", "status"] })
+    }
+
+    async fn call(&self, arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        if !self.broken {
+            let status = arguments["status"].as_str().unwrap_or_default().to_string();
+            self.tickets.lock().unwrap().insert(arguments["id"].as_str().unwrap_or_default().to_string(), status);
+        }
+        Ok(vec![ToolResponse::Text { text: "Updated.".to_string() }])
+    }
+}
+
+struct GetTicket {
+    tickets: Tickets,
+}
+
+#[async_trait]
+impl LocalTool for GetTicket {
+    fn name(&self) -> &str {
+        "get_ticket"
+    }
+
+    fn description(&self) -> String {
+        "Read a ticket".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "required": ["ticket"] })
+    }
+
+    async fn call(&self, arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        let id = arguments["ticket"].as_str().unwrap_or_default();
+        let status = self.tickets.lock().unwrap().get(id).cloned().unwrap_or_default();
+        Ok(vec![ToolResponse::Text { text: format!("#{} status: {}", id, status) }])
+    }
+}
+
+struct Run {
+    events: Vec<StreamEvent>,
+    requests: Vec<Vec<Message>>,
+    verification: Option<ToolVerification>,
+}
+
+/// One `update_ticket` call closing ticket 7, then an answer
+async fn run(broken: bool) -> Run {
+    let tickets: Tickets = Arc::new(Mutex::new([("7".to_string(), "open".to_string())].into()));
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(UpdateTicket { tickets: tickets.clone(), broken }).await.unwrap();
+    executor.register_local_tool(GetTicket { tickets }).await.unwrap();
+    executor
+        .configure_tool(
+            "update_ticket",
+            ToolConfig::verify_with("get_ticket", json!({ "ticket": "{id}" })).with_expect("status: {status}"),
+        )
+        .await;
+    executor.validate_tool_configs().await.unwrap();
+
+    let llm = Arc::new(ScriptedLLM::new(vec![
+        vec![
+            praxis_llm::StreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("update_ticket".to_string()),
+                arguments: Some(r#"{"id": "7", "status": "closed"}"#.to_string()),
+            },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ],
+        vec![
+            praxis_llm::StreamEvent::Message { content: "Ticket 7 is closed.".to_string(), choice_index: None, logprobs: None },
+            praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ],
+    ]));
+    let persistence = Arc::new(RecordingPersistence::default());
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(Arc::new(executor))
+        .with_persistence(persistence.clone())
+        .build()
+        .unwrap();
+    let input = GraphInput::new("thread-1", vec![Message::human("Close ticket 7")], LLMConfig::new("gpt-4o"));
+    let ctx = PersistenceContext { thread_id: "thread-1".to_string(), user_id: "user-1".to_string() };
+    let events = collect_events(graph.spawn_run(input, Some(ctx))).await;
+
+    let saved = persistence.wait_for(4).await;
+    let row = saved.iter().find(|m| m.message_type == MessageType::ToolResult).unwrap();
+    Run { events, requests: llm.requests(), verification: row.verification.clone() }
+}
+
+fn tool_result(events: &[StreamEvent]) -> (&str, bool, Option<&ToolVerification>) {
+    events
+        .iter()
+        .find_map(|event| match event {
+            StreamEvent::ToolResult { result, is_error, verification, .. } => {
+                Some((result.as_str(), *is_error, verification.as_ref()))
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+/// What the model was sent as the tool's result on the second turn
+fn model_saw(requests: &[Vec<Message>]) -> String {
+    requests[1]
+        .iter()
+        .find_map(|message| match message {
+            Message::Tool { content, .. } => Some(content.as_text().unwrap_or_default().to_string()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_confirmed_write_passes() {
+    let run = run(false).await;
+
+    let expected = ToolVerification {
+        tool_name: "get_ticket".to_string(),
+        arguments: json!({ "ticket": "7" }),
+        result: "#7 status: closed".to_string(),
+        passed: true,
+        reason: None,
+    };
+    assert_eq!(tool_result(&run.events), ("Updated.", false, Some(&expected)));
+    assert_eq!(run.verification, Some(expected));
+    assert_eq!(model_saw(&run.requests), "Updated.");
+}
+
+#[tokio::test]
+async fn test_unconfirmed_write_is_an_error() {
+    let run = run(true).await;
+
+    let (result, is_error, verification) = tool_result(&run.events);
+    assert!(is_error);
+    let verification = verification.unwrap();
+    assert!(!verification.passed);
+    assert_eq!(verification.result, "#7 status: open");
+    assert_eq!(verification.reason.as_deref(), Some("result does not contain 'status: closed'"));
+    assert_eq!(run.verification.as_ref(), Some(verification));
+
+    // The model is told, so it retries or reports the change honestly
+    assert!(result.starts_with("Updated.\n\nVerification with 'get_ticket' failed"), "{}", result);
+    assert_eq!(model_saw(&run.requests), result);
+}
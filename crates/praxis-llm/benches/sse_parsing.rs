@@ -0,0 +1,112 @@
+//! SSE line splitting on a 50k-token chat completion stream
+//!
+//! `string_buffer` is the splitter the parsers used before `CircularLineBuffer`:
+//! append each chunk to a `String` and copy the rest of the buffer after every
+//! line. Run with `cargo bench --bench sse_parsing`.
+
+use anyhow::Result;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::StreamExt;
+use praxis_llm::buffer_utils::{parse_sse_byte_stream, SseLineParser};
+use praxis_llm::{CircularLineBuffer, StreamEvent};
+use serde_json::Value;
+
+const TOKENS: usize = 50_000;
+
+/// Chat completion SSE body with one content delta per token
+fn chat_stream(tokens: usize) -> Vec<u8> {
+    let mut body = String::new();
+    for i in 0..tokens {
+        let chunk = serde_json::json!({
+            "id": "chatcmpl-bench",
+            "object": "chat.completion.chunk",
+            "model": "gpt-4o",
+            "choices": [{ "index": 0, "delta": { "content": format!(" tok{}", i % 1000) }, "finish_reason": null }]
+        });
+        body.push_str(&format!("data: {}\n\n", chunk));
+    }
+    body.push_str("data: [DONE]\n\n");
+    body.into_bytes()
+}
+
+/// Splits `body` the way the network delivers it, in chunks that cut lines anywhere
+fn chunks(body: &[u8], size: usize) -> Vec<&[u8]> {
+    body.chunks(size).collect()
+}
+
+/// The splitter the parsers used before `CircularLineBuffer`
+fn string_buffer(chunks: &[&[u8]]) -> usize {
+    let mut buffer = String::new();
+    let mut lines = 0;
+    for chunk in chunks {
+        buffer.push_str(&String::from_utf8_lossy(chunk));
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer = buffer[pos + 1..].to_string();
+            lines += black_box(line).len();
+        }
+    }
+    lines
+}
+
+fn circular_buffer(chunks: &[&[u8]]) -> usize {
+    let mut buffer = CircularLineBuffer::with_capacity(4096);
+    let mut lines = 0;
+    for chunk in chunks {
+        buffer.extend(chunk);
+        while let Some(line) = buffer.next_line() {
+            lines += black_box(line.unwrap()).len();
+        }
+    }
+    lines
+}
+
+/// Reads the content delta of each chunk, like the chat parser does
+struct ContentParser;
+
+impl SseLineParser for ContentParser {
+    fn parse_data_line(&self, data: &str) -> Result<Vec<StreamEvent>> {
+        let chunk: Value = serde_json::from_str(data)?;
+        let content = chunk["choices"][0]["delta"]["content"].as_str().unwrap_or_default();
+        Ok(vec![StreamEvent::Message { content: content.to_string(), choice_index: None, logprobs: None }])
+    }
+}
+
+fn bench_line_splitting(c: &mut Criterion) {
+    let body = chat_stream(TOKENS);
+    let mut group = c.benchmark_group("line_splitting");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.sample_size(10);
+
+    for size in [512, 4096, 16384] {
+        let chunks = chunks(&body, size);
+        group.bench_with_input(BenchmarkId::new("string_buffer", size), &chunks, |b, chunks| {
+            b.iter(|| string_buffer(chunks))
+        });
+        group.bench_with_input(BenchmarkId::new("circular_buffer", size), &chunks, |b, chunks| {
+            b.iter(|| circular_buffer(chunks))
+        });
+    }
+    group.finish();
+}
+
+fn bench_parse_stream(c: &mut Criterion) {
+    let body = chat_stream(TOKENS);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("parse_sse_byte_stream");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.sample_size(10);
+
+    group.bench_function("chat_50k_tokens", |b| {
+        b.iter(|| {
+            let chunks: Vec<Result<Vec<u8>, std::convert::Infallible>> =
+                body.chunks(4096).map(|chunk| Ok(chunk.to_vec())).collect();
+            let events = parse_sse_byte_stream(futures::stream::iter(chunks), ContentParser);
+            runtime.block_on(events.count())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_line_splitting, bench_parse_stream);
+criterion_main!(benches);
@@ -18,7 +18,7 @@ async fn main() -> Result<()> {
 
     while let Some(event) = stream.next().await {
         match event? {
-            StreamEvent::Message { content } => {
+            StreamEvent::Message { content, .. } => {
                 print!("{}", content);
                 std::io::Write::flush(&mut std::io::stdout())?;
             }
@@ -73,7 +73,7 @@ async fn main() -> Result<()> {
 fn display_batch(batch: &[StreamEvent], reasoning_displayed: &mut bool) -> Result<()> {
     for event in batch {
         match event {
-            StreamEvent::Reasoning { content } => {
+            StreamEvent::Reasoning { content, .. } => {
                 if !*reasoning_displayed {
                     println!("[REASONING]");
                     *reasoning_displayed = true;
@@ -81,7 +81,7 @@ fn display_batch(batch: &[StreamEvent], reasoning_displayed: &mut bool) -> Resul
                 print!("{}", content);
                 std::io::Write::flush(&mut std::io::stdout())?;
             }
-            StreamEvent::Message { content } => {
+            StreamEvent::Message { content, .. } => {
                 if *reasoning_displayed {
                     println!("\n\n[RESPONSE]");
                     *reasoning_displayed = false;
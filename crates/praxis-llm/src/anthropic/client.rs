@@ -0,0 +1,724 @@
+// Anthropic Messages API client
+
+use crate::anthropic::streaming::{finish_reason, AnthropicSseParser};
+use crate::anthropic::AnthropicConfig;
+use crate::buffer_utils::{parse_sse_byte_stream, parse_sse_stream};
+use crate::capabilities::{ModelCapabilities, OptionAdjusted};
+use crate::capture::{redact_headers, Capture};
+use crate::error::{LLMError, RateLimited};
+use crate::http::{client_or_build, send_within, with_idle_timeout, HttpOptions, DEFAULT_CONNECT_TIMEOUT};
+use crate::openai::responses::{ContentItem, OutputItem, SummaryText, Usage};
+use crate::openai::{ReasoningConfig, ReasoningEffort, ResponsesResponse};
+use crate::streaming::StreamEvent;
+use crate::traits::{
+    ChatClient, ChatOptions, ChatRequest, ChatResponse, ChoiceOutput, LLMClient, ReasoningClient,
+    ResponseOptions, ResponseOutput, ResponseRequest, TokenUsage, merge_extra_body,
+};
+use crate::types::{CacheHint, Content, ContentPart, Message, Tool, ToolCall, ToolChoice};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::pin::Pin;
+
+const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com/v1";
+const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+const VERSION_HEADER: HeaderName = HeaderName::from_static("anthropic-version");
+
+/// Anthropic Claude client (HTTP direct, no SDK)
+///
+/// Chat requests go to the Messages API. The reasoning methods send the
+/// same request with extended thinking enabled; its budget follows the
+/// `ReasoningConfig` effort.
+pub struct AnthropicClient {
+    http_client: reqwest::Client,
+    /// Sent with each request, so a shared `http_client` carries no credentials
+    headers: HeaderMap,
+    base_url: String,
+    default_max_tokens: u32,
+    http_options: HttpOptions,
+    /// Default headers with credentials redacted, written to payload captures
+    capture_headers: BTreeMap<String, String>,
+}
+
+impl AnthropicClient {
+    /// Create new client with API key
+    ///
+    /// Payload capture is enabled when `PRAXIS_CAPTURE_DIR` is set (see `crate::capture`).
+    pub fn new(api_key: impl Into<String>) -> Result<Self> {
+        Self::from_config(AnthropicConfig::new(api_key))
+    }
+
+    pub fn from_config(config: AnthropicConfig) -> Result<Self> {
+        let headers = Self::default_headers(&config)?;
+
+        let capture_headers = redact_headers(&headers);
+        let http_client = client_or_build(config.http_client.as_ref(), &config.connection, DEFAULT_CONNECT_TIMEOUT)?;
+
+        Ok(Self {
+            http_client,
+            headers,
+            base_url: ANTHROPIC_API_BASE.to_string(),
+            default_max_tokens: config.default_max_tokens,
+            http_options: HttpOptions::from_env(),
+            capture_headers,
+        })
+    }
+
+    /// Headers sent with every request
+    fn default_headers(config: &AnthropicConfig) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(
+            API_KEY_HEADER,
+            HeaderValue::from_str(&config.api_key).context("Invalid API key format")?,
+        );
+        headers.insert(
+            VERSION_HEADER,
+            HeaderValue::from_str(&config.version).context("Invalid Anthropic API version")?,
+        );
+        Ok(headers)
+    }
+
+    /// Override HTTP options (e.g. enable payload capture programmatically)
+    pub fn with_http_options(mut self, options: HttpOptions) -> Self {
+        self.http_options = options;
+        self
+    }
+
+    /// Start a payload capture for this request (None when capture is disabled)
+    fn start_capture(&self, url: &str, payload: &Value) -> Option<Capture> {
+        Capture::start(&self.http_options, "messages", url, &self.capture_headers, payload)
+    }
+
+    /// Build a Messages request payload, with the options the model didn't accept
+    fn build_chat_request(
+        &self,
+        model: &str,
+        messages: Vec<Message>,
+        options: &ChatOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<OptionAdjusted>)> {
+        let (options, mut adjusted) = ModelCapabilities::for_model(model).adjust_chat_options(model, options)?;
+        // The Messages API has no seed, penalties, multiple choices or log
+        // probabilities, whatever the model
+        let unsupported = [
+            ("seed", options.seed.is_some()),
+            ("presence_penalty", options.presence_penalty.is_some()),
+            ("frequency_penalty", options.frequency_penalty.is_some()),
+            ("n", options.n.is_some_and(|n| n > 1)),
+            ("logprobs", options.wants_logprobs()),
+        ];
+        for (option, _) in unsupported.into_iter().filter(|(_, set)| *set) {
+            if options.strict_options {
+                anyhow::bail!("Options not supported by {}: {}", model, option);
+            }
+            adjusted.push(OptionAdjusted {
+                option: option.to_string(),
+                reason: format!("removed: {} does not support {}", model, option),
+            });
+        }
+
+        let mut request = self.base_request(model, messages, options.max_tokens, stream)?;
+        let obj = request.as_object_mut().unwrap();
+
+        if let Some(temp) = options.temperature {
+            obj.insert("temperature".to_string(), json!(temp));
+        }
+        if let Some(top_p) = options.top_p {
+            obj.insert("top_p".to_string(), json!(top_p));
+        }
+        if let Some(stop) = &options.stop {
+            obj.insert("stop_sequences".to_string(), json!(stop));
+        }
+        // An empty tool list can't carry a tool_choice
+        if let Some(tools) = options.tools.as_ref().filter(|tools| !tools.is_empty()) {
+            obj.insert("tools".to_string(), Value::Array(tools.iter().map(convert_tool).collect()));
+            let mut tool_choice = options.tool_choice.as_ref().map(convert_tool_choice);
+            // Sequential calls are a flag on the choice, which "none" can't carry
+            if options.parallel_tool_calls == Some(false) {
+                let choice = tool_choice.get_or_insert_with(|| json!({ "type": "auto" }));
+                if choice["type"] != "none" {
+                    choice["disable_parallel_tool_use"] = json!(true);
+                }
+            }
+            if let Some(tool_choice) = tool_choice {
+                obj.insert("tool_choice".to_string(), tool_choice);
+            }
+        }
+        if let Some(user) = &options.end_user_id {
+            obj.insert("metadata".to_string(), json!({ "user_id": user }));
+        }
+        merge_extra_body(obj, options.extra_body.as_ref());
+
+        Ok((request, adjusted))
+    }
+
+    /// Build a Messages request with extended thinking
+    ///
+    /// Thinking requires the default temperature, so a requested one is
+    /// dropped (or rejected with `strict_options`).
+    fn build_thinking_request(
+        &self,
+        model: &str,
+        input: Vec<Message>,
+        reasoning: Option<&ReasoningConfig>,
+        options: &ResponseOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<OptionAdjusted>)> {
+        let mut adjusted = Vec::new();
+        if options.temperature.is_some() {
+            if options.strict_options {
+                anyhow::bail!("Options not supported by {} with extended thinking: temperature", model);
+            }
+            adjusted.push(OptionAdjusted {
+                option: "temperature".to_string(),
+                reason: format!("removed: {} does not support temperature with extended thinking", model),
+            });
+        }
+
+        let budget = thinking_budget(reasoning.map_or(&ReasoningEffort::Medium, |r| &r.effort));
+        let max_tokens = options.max_output_tokens.unwrap_or(budget + self.default_max_tokens);
+        anyhow::ensure!(
+            max_tokens > budget,
+            "max_output_tokens ({}) must exceed the thinking budget ({})",
+            max_tokens,
+            budget
+        );
+
+        let mut request = self.base_request(model, input, Some(max_tokens), stream)?;
+        let obj = request.as_object_mut().unwrap();
+        obj.insert("thinking".to_string(), json!({ "type": "enabled", "budget_tokens": budget }));
+        if let Some(user) = &options.end_user_id {
+            obj.insert("metadata".to_string(), json!({ "user_id": user }));
+        }
+        merge_extra_body(obj, options.extra_body.as_ref());
+
+        Ok((request, adjusted))
+    }
+
+    fn base_request(&self, model: &str, messages: Vec<Message>, max_tokens: Option<u32>, stream: bool) -> Result<Value> {
+        let (system, messages) = convert_messages(messages)?;
+        let mut request = json!({
+            "model": model,
+            "max_tokens": max_tokens.unwrap_or(self.default_max_tokens),
+            "messages": messages,
+            "stream": stream,
+        });
+        if let Some(system) = system {
+            request.as_object_mut().unwrap().insert("system".to_string(), system);
+        }
+        Ok(request)
+    }
+
+    async fn send(&self, payload: &Value) -> Result<reqwest::Response> {
+        let request = self.http_client.post(format!("{}/messages", self.base_url)).headers(self.headers.clone()).json(payload);
+        let response = send_within(request, self.http_options.timeout).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(response)
+    }
+
+    async fn send_stream(
+        &self,
+        payload: Value,
+        adjustments: Vec<OptionAdjusted>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        let url = format!("{}/messages", self.base_url);
+        let capture = self.start_capture(&url, &payload);
+        let response = self.send(&payload).await?;
+
+        let stream = match capture {
+            Some(capture) => parse_sse_byte_stream(capture.tee(response.bytes_stream()), AnthropicSseParser),
+            None => parse_sse_stream(response, AnthropicSseParser),
+        };
+        let stream = with_idle_timeout(stream, self.http_options.stream_idle_timeout);
+        if adjustments.is_empty() {
+            return Ok(stream);
+        }
+        Ok(Box::pin(futures::stream::once(async { Ok(StreamEvent::OptionsAdjusted { adjustments }) }).chain(stream)))
+    }
+}
+
+/// Split out the system prompt and convert the rest to Messages API turns
+///
+/// Anthropic takes the system prompt as a top-level field (developer
+/// messages join it), returns tool results in user turns, and rejects
+/// consecutive turns of the same role, so adjacent messages of one role are
+/// merged into a single turn. A message's cache hint becomes `cache_control`
+/// on its last block; with a hinted instruction the system prompt is sent as
+/// blocks rather than one string
+fn convert_messages(messages: Vec<Message>) -> Result<(Option<Value>, Vec<Value>)> {
+    let mut system = Vec::new();
+    let mut system_cached = false;
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+
+    for message in messages {
+        let hint = message.cache_hint();
+        let (role, mut blocks) = match message {
+            Message::System { content, .. } | Message::Developer { content, .. } => {
+                let mut blocks = text_blocks(content);
+                if let (Some(hint), Some(last)) = (hint, blocks.last_mut()) {
+                    last["cache_control"] = cache_control(hint);
+                    system_cached = true;
+                }
+                system.extend(blocks);
+                continue;
+            }
+            Message::Human { content, .. } => ("user", text_blocks(content)),
+            Message::AI { content, tool_calls, .. } => {
+                let mut blocks = content.map(text_blocks).unwrap_or_default();
+                for call in tool_calls.unwrap_or_default() {
+                    blocks.push(tool_use_block(call)?);
+                }
+                ("assistant", blocks)
+            }
+            Message::Tool { tool_call_id, content } => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": tool_call_id,
+                    "content": content_texts(content).join("\n"),
+                })],
+            ),
+        };
+        if let (Some(hint), Some(last)) = (hint, blocks.last_mut()) {
+            last["cache_control"] = cache_control(hint);
+        }
+        if blocks.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((last_role, last_blocks)) if *last_role == role => last_blocks.extend(blocks),
+            _ => turns.push((role, blocks)),
+        }
+    }
+
+    let system = if system_cached {
+        Some(json!(system))
+    } else {
+        let text = system.iter().filter_map(|block| block["text"].as_str()).collect::<Vec<_>>().join("\n\n");
+        Some(text).filter(|s| !s.is_empty()).map(Value::String)
+    };
+    let turns = turns
+        .into_iter()
+        .map(|(role, content)| json!({ "role": role, "content": content }))
+        .collect();
+    Ok((system, turns))
+}
+
+/// `cache_control` of a block ending a cached prefix
+fn cache_control(hint: CacheHint) -> Value {
+    match hint {
+        CacheHint::Ephemeral => json!({ "type": "ephemeral" }),
+        CacheHint::Extended => json!({ "type": "ephemeral", "ttl": "1h" }),
+    }
+}
+
+/// Text of the content, leaving out images and audio (not converted for Anthropic yet)
+fn content_texts(content: Content) -> Vec<String> {
+    match content {
+        Content::Text(text) => vec![text],
+        Content::Parts(parts) => parts
+            .into_iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text),
+                ContentPart::ImageUrl { .. } | ContentPart::ImageBase64 { .. } | ContentPart::Audio { .. } => None,
+            })
+            .collect(),
+    }
+}
+
+/// Text blocks of the content (Anthropic rejects empty ones)
+fn text_blocks(content: Content) -> Vec<Value> {
+    content_texts(content)
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .map(|text| json!({ "type": "text", "text": text }))
+        .collect()
+}
+
+fn tool_use_block(call: ToolCall) -> Result<Value> {
+    let input: Value = if call.function.arguments.trim().is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(&call.function.arguments)
+            .with_context(|| format!("Tool call {} has arguments that are not JSON", call.id))?
+    };
+    Ok(json!({
+        "type": "tool_use",
+        "id": call.id,
+        "name": call.function.name,
+        "input": input,
+    }))
+}
+
+fn convert_tool(tool: &Tool) -> Value {
+    let mut converted = json!({
+        "name": tool.function.name,
+        "input_schema": tool.function.parameters,
+    });
+    if let Some(description) = &tool.function.description {
+        converted.as_object_mut().unwrap().insert("description".to_string(), json!(description));
+    }
+    converted
+}
+
+fn convert_tool_choice(choice: &ToolChoice) -> Value {
+    match choice {
+        ToolChoice::Auto(_) => json!({ "type": "auto" }),
+        ToolChoice::None(_) => json!({ "type": "none" }),
+        ToolChoice::Required(_) => json!({ "type": "any" }),
+        ToolChoice::Specific { function, .. } => json!({ "type": "tool", "name": function.name }),
+    }
+}
+
+/// Thinking tokens allowed per effort level
+fn thinking_budget(effort: &ReasoningEffort) -> u32 {
+    match effort {
+        ReasoningEffort::Minimal => 1024,
+        ReasoningEffort::Low => 1024,
+        ReasoningEffort::Medium => 4096,
+        ReasoningEffort::High => 16384,
+    }
+}
+
+/// Text, thinking and tool calls of a non-streaming Messages response
+struct ParsedMessage {
+    text: Option<String>,
+    thinking: Option<String>,
+    tool_calls: Option<Vec<ToolCall>>,
+    usage: TokenUsage,
+    stop_reason: Option<String>,
+}
+
+fn parse_message(raw: &Value) -> ParsedMessage {
+    let mut text = String::new();
+    let mut thinking = String::new();
+    let mut tool_calls = Vec::new();
+    for block in raw["content"].as_array().into_iter().flatten() {
+        match block["type"].as_str().unwrap_or_default() {
+            "text" => text.push_str(block["text"].as_str().unwrap_or_default()),
+            "thinking" => thinking.push_str(block["thinking"].as_str().unwrap_or_default()),
+            "tool_use" => tool_calls.push(ToolCall {
+                id: block["id"].as_str().unwrap_or_default().to_string(),
+                tool_type: "function".to_string(),
+                function: crate::types::FunctionCall {
+                    name: block["name"].as_str().unwrap_or_default().to_string(),
+                    arguments: block["input"].to_string(),
+                },
+            }),
+            _ => {}
+        }
+    }
+
+    let tokens = |key: &str| raw["usage"][key].as_u64().unwrap_or_default() as u32;
+    // `input_tokens` leaves out the tokens read from or written to the cache
+    let cache_read = tokens("cache_read_input_tokens");
+    let input_tokens = tokens("input_tokens") + tokens("cache_creation_input_tokens") + cache_read;
+    let output_tokens = tokens("output_tokens");
+    ParsedMessage {
+        text: Some(text).filter(|t| !t.is_empty()),
+        thinking: Some(thinking).filter(|t| !t.is_empty()),
+        tool_calls: Some(tool_calls).filter(|calls| !calls.is_empty()),
+        usage: TokenUsage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            reasoning_tokens: None,
+            cached_tokens: raw["usage"].get("cache_read_input_tokens").map(|_| cache_read),
+        },
+        stop_reason: raw["stop_reason"].as_str().map(finish_reason),
+    }
+}
+
+// ============================================================================
+// TRAIT IMPLEMENTATIONS
+// ============================================================================
+
+#[async_trait]
+impl ChatClient for AnthropicClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, warnings) = self.build_chat_request(&request.model, request.messages, &request.options, false)?;
+
+        let raw: Value = self.send(&payload).await?.json().await.context("Failed to parse response")?;
+        let parsed = parse_message(&raw);
+        let choice = ChoiceOutput {
+            index: 0,
+            content: parsed.text,
+            tool_calls: parsed.tool_calls,
+            finish_reason: parsed.stop_reason,
+            logprobs: None,
+        };
+        Ok(ChatResponse {
+            content: choice.content.clone(),
+            tool_calls: choice.tool_calls.clone(),
+            usage: Some(parsed.usage),
+            finish_reason: choice.finish_reason.clone(),
+            logprobs: None,
+            choices: vec![choice],
+            warnings,
+            raw,
+            served_by: None,
+            metadata: None,
+        })
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, adjustments) = self.build_chat_request(&request.model, request.messages, &request.options, true)?;
+        self.send_stream(payload, adjustments).await
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for AnthropicClient {
+    /// Extended thinking; `raw` holds the response in Responses form (the
+    /// thinking as a reasoning summary, the text as a message)
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, warnings) = self.build_thinking_request(
+            &request.model,
+            request.input,
+            request.reasoning.as_ref(),
+            &request.options,
+            false,
+        )?;
+
+        let raw: Value = self.send(&payload).await?.json().await.context("Failed to parse response")?;
+        let parsed = parse_message(&raw);
+        let id = raw["id"].as_str().unwrap_or_default().to_string();
+
+        let mut output = Vec::new();
+        if let Some(thinking) = &parsed.thinking {
+            output.push(OutputItem::Reasoning {
+                id: format!("{}_thinking", id),
+                summary: vec![SummaryText { content_type: "summary_text".to_string(), text: thinking.clone() }],
+            });
+        }
+        if let Some(text) = &parsed.text {
+            output.push(OutputItem::Message {
+                id: id.clone(),
+                status: "completed".to_string(),
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText { text: text.clone(), annotations: None }],
+            });
+        }
+        let status = parsed.stop_reason.clone().unwrap_or_else(|| "completed".to_string());
+
+        Ok(ResponseOutput {
+            reasoning: parsed.thinking,
+            message: parsed.text,
+            usage: Some(parsed.usage.clone()),
+            status: Some(status.clone()),
+            warnings,
+            raw: ResponsesResponse {
+                id,
+                object: "message".to_string(),
+                created_at: chrono::Utc::now().timestamp(),
+                status,
+                model: raw["model"].as_str().unwrap_or(&request.model).to_string(),
+                output,
+                usage: Usage {
+                    input_tokens: parsed.usage.input_tokens,
+                    output_tokens: parsed.usage.output_tokens,
+                    total_tokens: parsed.usage.total_tokens,
+                    output_tokens_details: None,
+                    input_tokens_details: None,
+                },
+                reasoning: request.reasoning,
+            },
+            served_by: None,
+            metadata: None,
+        })
+    }
+
+    async fn reason_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, adjustments) = self.build_thinking_request(
+            &request.model,
+            request.input,
+            request.reasoning.as_ref(),
+            &request.options,
+            true,
+        )?;
+        self.send_stream(payload, adjustments).await
+    }
+}
+
+// Anthropic supports both chat and (extended thinking) reasoning
+impl LLMClient for AnthropicClient {}
+
+/// `LLMError` for a non-success response; 529s (overloaded) are rate limits too
+async fn api_error(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let error_text = response.text().await.unwrap_or_default();
+    if status.as_u16() == 529 {
+        return LLMError::RateLimited(RateLimited::from_headers(&headers, error_text)).into();
+    }
+    LLMError::from_status(status, &headers, error_text).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FunctionCall;
+
+    fn weather_call() -> ToolCall {
+        ToolCall {
+            id: "toolu_1".to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall { name: "get_weather".to_string(), arguments: r#"{"city":"Lisbon"}"#.to_string() },
+        }
+    }
+
+    #[test]
+    fn test_messages_use_anthropic_turns() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::human("Weather in Lisbon?"),
+            Message::ai_with_content_and_tools("Checking.", vec![weather_call()]),
+            Message::tool("toolu_1", "22C"),
+            Message::human("Thanks"),
+        ];
+        let (system, turns) = convert_messages(messages).unwrap();
+
+        assert_eq!(system, Some(json!("Be brief.")));
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[1]["content"][1], json!({ "type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": { "city": "Lisbon" } }));
+        // The tool result and the next question share one user turn
+        assert_eq!(turns[2]["role"], "user");
+        assert_eq!(turns[2]["content"][0], json!({ "type": "tool_result", "tool_use_id": "toolu_1", "content": "22C" }));
+        assert_eq!(turns[2]["content"][1]["text"], "Thanks");
+    }
+
+    #[test]
+    fn test_cache_hints_become_cache_control() {
+        let messages = vec![
+            Message::system("You are a travel agent.").with_cache_hint(CacheHint::Extended),
+            Message::developer("Today is Monday."),
+            Message::human("Hi").with_cache_hint(CacheHint::Ephemeral),
+        ];
+        let (system, turns) = convert_messages(messages).unwrap();
+
+        assert_eq!(
+            system,
+            Some(json!([
+                { "type": "text", "text": "You are a travel agent.", "cache_control": { "type": "ephemeral", "ttl": "1h" } },
+                { "type": "text", "text": "Today is Monday." },
+            ]))
+        );
+        assert_eq!(turns[0]["content"][0]["cache_control"], json!({ "type": "ephemeral" }));
+    }
+
+    #[test]
+    fn test_cache_reads_count_as_input() {
+        let raw = json!({
+            "content": [{ "type": "text", "text": "Hi" }],
+            "usage": { "input_tokens": 6, "cache_creation_input_tokens": 0, "cache_read_input_tokens": 2000, "output_tokens": 1 },
+        });
+        let usage = parse_message(&raw).usage;
+        assert_eq!((usage.input_tokens, usage.total_tokens, usage.cached_tokens), (2006, 2007, Some(2000)));
+        assert_eq!(parse_message(&json!({ "usage": { "input_tokens": 6 } })).usage.cached_tokens, None);
+    }
+
+    #[test]
+    fn test_chat_request_payload() {
+        let client = AnthropicClient::new("sk-ant-test").unwrap();
+        let tool = Tool::new("get_weather", "Current weather", json!({ "type": "object" }));
+        let options = ChatOptions::new().tools(vec![tool]).tool_choice(ToolChoice::required()).end_user_id("u-hash");
+
+        let (payload, adjusted) = client
+            .build_chat_request("claude-sonnet-4-5", vec![Message::human("hi")], &options, true)
+            .unwrap();
+        assert!(adjusted.is_empty());
+        assert_eq!(payload["max_tokens"], 4096);
+        assert_eq!(payload["tools"][0]["input_schema"], json!({ "type": "object" }));
+        assert_eq!(payload["tool_choice"], json!({ "type": "any" }));
+        assert_eq!(payload["metadata"]["user_id"], "u-hash");
+        assert!(payload.get("system").is_none());
+
+        let (payload, _) = client
+            .build_chat_request("claude-sonnet-4-5", vec![Message::human("hi")], &ChatOptions::new().tools(Vec::new()).tool_choice(ToolChoice::auto()), true)
+            .unwrap();
+        assert!(payload.get("tools").is_none() && payload.get("tool_choice").is_none());
+    }
+
+    #[test]
+    fn test_sequential_tool_calls_flag_the_choice() {
+        let client = AnthropicClient::new("sk-ant-test").unwrap();
+        let tool = Tool::new("get_weather", "Current weather", json!({ "type": "object" }));
+        let payload = |options: ChatOptions| {
+            client.build_chat_request("claude-sonnet-4-5", vec![Message::human("hi")], &options, true).unwrap().0
+        };
+
+        let sequential = ChatOptions::new().tools(vec![tool.clone()]).parallel_tool_calls(false);
+        assert_eq!(payload(sequential.clone())["tool_choice"], json!({ "type": "auto", "disable_parallel_tool_use": true }));
+        assert_eq!(
+            payload(sequential.clone().tool_choice(ToolChoice::required()))["tool_choice"],
+            json!({ "type": "any", "disable_parallel_tool_use": true })
+        );
+        assert_eq!(payload(sequential.tool_choice(ToolChoice::none()))["tool_choice"], json!({ "type": "none" }));
+        assert!(payload(ChatOptions::new().tools(vec![tool]).parallel_tool_calls(true)).get("tool_choice").is_none());
+    }
+
+    #[test]
+    fn test_sampling_parameters_without_seed_or_penalties() {
+        let client = AnthropicClient::new("sk-ant-test").unwrap();
+        let options = ChatOptions::new().stop(["END"]).top_p(0.8).seed(42).presence_penalty(0.5).n(2);
+
+        let (payload, adjusted) = client
+            .build_chat_request("claude-sonnet-4-5", vec![Message::human("hi")], &options, false)
+            .unwrap();
+        assert_eq!(payload["stop_sequences"], json!(["END"]));
+        assert!(payload["top_p"].is_f64());
+        assert!(payload.get("seed").is_none() && payload.get("presence_penalty").is_none());
+        let dropped: Vec<&str> = adjusted.iter().map(|a| a.option.as_str()).collect();
+        assert_eq!(dropped, vec!["seed", "presence_penalty", "n"]);
+
+        let strict = options.strict_options(true);
+        assert!(client.build_chat_request("claude-sonnet-4-5", vec![Message::human("hi")], &strict, false).is_err());
+    }
+
+    #[test]
+    fn test_extra_body_fills_in_around_known_fields() {
+        let client = AnthropicClient::new("sk-ant-test").unwrap();
+        let extra = json!({ "top_k": 5, "metadata": { "user_id": "other", "session": "s-1" } });
+        let options = ChatOptions::new().end_user_id("u-hash").extra_body(extra.as_object().unwrap().clone());
+
+        let (payload, _) = client
+            .build_chat_request("claude-sonnet-4-5", vec![Message::human("hi")], &options, false)
+            .unwrap();
+        assert_eq!(payload["top_k"], 5);
+        assert_eq!(payload["metadata"], json!({ "user_id": "u-hash", "session": "s-1" }));
+    }
+
+    #[test]
+    fn test_thinking_request_drops_temperature() {
+        let client = AnthropicClient::new("sk-ant-test").unwrap();
+        let options = ResponseOptions::new().temperature(0.2);
+
+        let (payload, adjusted) = client
+            .build_thinking_request("claude-sonnet-4-5", vec![Message::human("hi")], Some(&ReasoningConfig::high()), &options, true)
+            .unwrap();
+        assert_eq!(payload["thinking"], json!({ "type": "enabled", "budget_tokens": 16384 }));
+        assert_eq!(payload["max_tokens"], 16384 + 4096);
+        assert!(payload.get("temperature").is_none());
+        assert_eq!(adjusted[0].option, "temperature");
+
+        let too_small = ResponseOptions::new().max_output_tokens(1000);
+        assert!(client.build_thinking_request("claude-sonnet-4-5", vec![Message::human("hi")], None, &too_small, true).is_err());
+    }
+}
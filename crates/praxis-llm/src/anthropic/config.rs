@@ -0,0 +1,61 @@
+use crate::http::ConnectionOptions;
+
+/// Credentials and API version for `AnthropicClient`
+#[derive(Clone)]
+pub struct AnthropicConfig {
+    pub api_key: String,
+    /// Sent as the `anthropic-version` header
+    pub version: String,
+    /// `max_tokens` for requests that don't set one (the Messages API requires it)
+    pub default_max_tokens: u32,
+    /// Pool and keep-alive settings of the client's own `reqwest::Client`
+    pub connection: ConnectionOptions,
+    /// A `reqwest::Client` shared with other clients, used instead of
+    /// building one
+    pub http_client: Option<reqwest::Client>,
+}
+
+impl AnthropicConfig {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            version: "2023-06-01".to_string(),
+            default_max_tokens: 4096,
+            connection: ConnectionOptions::default(),
+            http_client: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn with_default_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.default_max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_connection_options(mut self, options: ConnectionOptions) -> Self {
+        self.connection = options;
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl std::fmt::Debug for AnthropicConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnthropicConfig")
+            .field("api_key", &"[redacted]")
+            .field("version", &self.version)
+            .field("default_max_tokens", &self.default_max_tokens)
+            .field("connection", &self.connection)
+            .field("shared_http_client", &self.http_client.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,8 @@
+// Anthropic-specific implementations
+
+pub mod client;
+pub mod config;
+pub(crate) mod streaming;
+
+pub use client::AnthropicClient;
+pub use config::AnthropicConfig;
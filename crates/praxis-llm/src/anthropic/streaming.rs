@@ -0,0 +1,104 @@
+//! Messages API stream events to `StreamEvent`
+//!
+//! Text and thinking arrive as `content_block_delta` events; a tool call is
+//! a `tool_use` block whose input streams as partial JSON. Tool calls are
+//! indexed by their content block, so the deltas of one call share the
+//! index of its start event. `message_delta` carries the stop reason.
+
+use crate::buffer_utils::SseLineParser;
+use crate::streaming::{ReasoningKind, StreamEvent};
+use anyhow::Result;
+use serde_json::Value;
+
+/// Messages SSE parser (Strategy Pattern)
+pub(crate) struct AnthropicSseParser;
+
+impl SseLineParser for AnthropicSseParser {
+    fn parse_data_line(&self, data: &str) -> Result<Vec<StreamEvent>> {
+        let chunk: Value = serde_json::from_str(data)
+            .map_err(|e| anyhow::anyhow!("Failed to parse Anthropic event: {}", e))?;
+        let index = chunk["index"].as_u64().unwrap_or_default() as u32;
+        let text = |value: &Value| value.as_str().filter(|s| !s.is_empty()).map(String::from);
+
+        let event = match chunk["type"].as_str().unwrap_or_default() {
+            "content_block_start" if chunk["content_block"]["type"] == "tool_use" => Some(StreamEvent::ToolCall {
+                index,
+                id: text(&chunk["content_block"]["id"]),
+                name: text(&chunk["content_block"]["name"]),
+                arguments: None,
+            }),
+            "content_block_delta" => {
+                let delta = &chunk["delta"];
+                match delta["type"].as_str().unwrap_or_default() {
+                    "text_delta" => text(&delta["text"]).map(|content| StreamEvent::Message { content, choice_index: None, logprobs: None }),
+                    "thinking_delta" => text(&delta["thinking"]).map(|content| StreamEvent::Reasoning { content, kind: ReasoningKind::Raw, item_id: None }),
+                    "input_json_delta" => text(&delta["partial_json"]).map(|arguments| StreamEvent::ToolCall {
+                        index,
+                        id: None,
+                        name: None,
+                        arguments: Some(arguments),
+                    }),
+                    // signature_delta and future delta types
+                    _ => None,
+                }
+            }
+            "message_delta" => chunk["delta"]["stop_reason"]
+                .as_str()
+                .map(|reason| StreamEvent::Done { finish_reason: Some(finish_reason(reason)), choice_index: None }),
+            "error" => anyhow::bail!(
+                "Anthropic stream error: {}",
+                chunk["error"]["message"].as_str().unwrap_or("unknown error")
+            ),
+            // message_start, content_block_stop, message_stop, ping
+            _ => None,
+        };
+        Ok(event.into_iter().collect())
+    }
+}
+
+/// Anthropic stop reason in the Chat Completions vocabulary the graph expects
+pub(crate) fn finish_reason(stop_reason: &str) -> String {
+    match stop_reason {
+        "end_turn" | "stop_sequence" => "stop",
+        "tool_use" => "tool_calls",
+        "max_tokens" => "length",
+        other => other,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_use_deltas_share_the_block_index() {
+        let parser = AnthropicSseParser;
+        let start = parser
+            .parse_data_line(r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"get_weather","input":{}}}"#)
+            .unwrap();
+        let delta = parser
+            .parse_data_line(r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"city\":"}}"#)
+            .unwrap();
+
+        assert!(matches!(&start[..], [StreamEvent::ToolCall { index: 1, id: Some(id), name: Some(name), arguments: None }]
+            if id == "toolu_1" && name == "get_weather"));
+        assert!(matches!(&delta[..], [StreamEvent::ToolCall { index: 1, id: None, arguments: Some(args), .. }]
+            if args == "{\"city\":"));
+    }
+
+    #[test]
+    fn test_stop_reason_and_errors() {
+        let parser = AnthropicSseParser;
+        let done = parser
+            .parse_data_line(r#"{"type":"message_delta","delta":{"stop_reason":"tool_use","stop_sequence":null},"usage":{"output_tokens":12}}"#)
+            .unwrap();
+        assert!(matches!(&done[..], [StreamEvent::Done { finish_reason: Some(reason), choice_index: None }] if reason == "tool_calls"));
+
+        assert!(parser.parse_data_line(r#"{"type":"ping"}"#).unwrap().is_empty());
+        let err = parser
+            .parse_data_line(r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
+}
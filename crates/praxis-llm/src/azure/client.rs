@@ -0,0 +1,252 @@
+// Azure OpenAI client
+//
+// Azure takes OpenAI's request bodies, so payloads, streaming and errors
+// come from `OpenAIClient`; only the URLs (deployment paths plus an
+// `api-version` query) and the `api-key` header differ.
+
+use crate::azure::AzureConfig;
+use crate::openai::responses::{ContentItem, OutputItem, Usage};
+use crate::openai::{OpenAIClient, OpenAIConfig, ReasoningConfig, ReasoningEffort, ResponsesResponse};
+use crate::stats::RateLimitHeadroom;
+use crate::streaming::StreamEvent;
+use crate::traits::{
+    ChatClient, ChatOptions, ChatRequest, ChatResponse, LLMClient, ReasoningClient, ResponseOutput, ResponseRequest,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::pin::Pin;
+
+const API_KEY_HEADER: HeaderName = HeaderName::from_static("api-key");
+
+/// Azure OpenAI client (HTTP direct, no SDK)
+///
+/// Chat requests go to `/openai/deployments/<model>/chat/completions`.
+/// Reasoning requests go to `/openai/responses` when the API version has
+/// it (see `AzureConfig::responses_api_enabled`), so reasoning, summaries
+/// and `reasoning_tokens` come back as they do from OpenAI. Otherwise they
+/// are sent as chat completions, and `ResponseOutput::raw` is built from
+/// the chat reply, with no reasoning in it.
+pub struct AzureOpenAIClient {
+    openai: OpenAIClient,
+    endpoint: String,
+    api_version: String,
+    responses_api: bool,
+}
+
+impl AzureOpenAIClient {
+    /// Create a client for the resource at `endpoint`
+    ///
+    /// Payload capture is enabled when `PRAXIS_CAPTURE_DIR` is set (see `crate::capture`).
+    pub fn new(endpoint: impl Into<String>, api_key: impl Into<String>, api_version: impl Into<String>) -> Result<Self> {
+        Self::from_config(AzureConfig::new(endpoint, api_key, api_version))
+    }
+
+    pub fn from_config(config: AzureConfig) -> Result<Self> {
+        let responses_api = config.responses_api_enabled();
+        let endpoint = config.endpoint.trim_end_matches('/').to_string();
+
+        // Azure authenticates with `api-key`; no bearer token is sent
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_str(&config.api_key).context("Invalid API key format")?);
+        let mut openai = OpenAIConfig::new("")
+            .with_base_url(format!("{}/openai", endpoint))
+            .with_default_headers(headers)
+            .with_connection_options(config.connection);
+        openai.retry_policy = config.retry_policy;
+        openai.capabilities = config.capabilities;
+        openai.http_client = config.http_client;
+
+        Ok(Self {
+            openai: OpenAIClient::from_config(openai)?,
+            endpoint,
+            api_version: config.api_version,
+            responses_api,
+        })
+    }
+
+    /// Whether reasoning requests use the Responses API
+    pub fn uses_responses_api(&self) -> bool {
+        self.responses_api
+    }
+
+    fn chat_url(&self, deployment: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.endpoint, deployment, self.api_version
+        )
+    }
+
+    fn responses_url(&self) -> String {
+        format!("{}/openai/responses?api-version={}", self.endpoint, self.api_version)
+    }
+}
+
+#[async_trait]
+impl ChatClient for AzureOpenAIClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        self.openai.chat_at(&self.chat_url(&request.model), request).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        self.openai.chat_stream_at(&self.chat_url(&request.model), request).await
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for AzureOpenAIClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        if self.responses_api {
+            return self.openai.reason_at(&self.responses_url(), request).await;
+        }
+        let reasoning = request.reasoning.clone();
+        let url = self.chat_url(&request.model);
+        let response = self.openai.chat_at(&url, chat_request(request)).await?;
+        Ok(response_output(response, reasoning))
+    }
+
+    async fn reason_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        if self.responses_api {
+            return self.openai.reason_stream_at(&self.responses_url(), request).await;
+        }
+        let url = self.chat_url(&request.model);
+        self.openai.chat_stream_at(&url, chat_request(request)).await
+    }
+}
+
+impl LLMClient for AzureOpenAIClient {
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        self.openai.rate_limit_headroom()
+    }
+}
+
+/// A reasoning request as a chat request, for API versions without
+/// `/responses`; the effort goes out as `reasoning_effort`
+fn chat_request(request: ResponseRequest) -> ChatRequest {
+    let options = request.options;
+    let reasoning_effort = request.reasoning.map(|reasoning| {
+        match reasoning.effort {
+            ReasoningEffort::Minimal => "minimal",
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+        .to_string()
+    });
+    ChatRequest::new(request.model, request.input).with_options(ChatOptions {
+        temperature: options.temperature,
+        max_tokens: options.max_output_tokens,
+        tools: options.tools,
+        tool_choice: options.tool_choice,
+        reasoning_effort,
+        end_user_id: options.end_user_id,
+        metadata: options.metadata,
+        parallel_tool_calls: options.parallel_tool_calls,
+        extra_body: options.extra_body,
+        strict_options: options.strict_options,
+        ..Default::default()
+    })
+}
+
+/// A chat reply in Responses form: the text as a message item, each tool
+/// call as a function call item
+fn response_output(response: ChatResponse, reasoning: Option<ReasoningConfig>) -> ResponseOutput {
+    let id = response.raw["id"].as_str().unwrap_or_default().to_string();
+    let mut output = Vec::new();
+    if let Some(text) = &response.content {
+        output.push(OutputItem::Message {
+            id: id.clone(),
+            status: "completed".to_string(),
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text: text.clone(), annotations: None }],
+        });
+    }
+    for call in response.tool_calls.iter().flatten() {
+        output.push(OutputItem::FunctionCall {
+            id: call.id.clone(),
+            call_id: call.id.clone(),
+            name: call.function.name.clone(),
+            arguments: call.function.arguments.clone(),
+            status: Some("completed".to_string()),
+        });
+    }
+    let (input_tokens, output_tokens, total_tokens) = response
+        .usage
+        .as_ref()
+        .map_or((0, 0, 0), |usage| (usage.input_tokens, usage.output_tokens, usage.total_tokens));
+    let status = response.finish_reason.clone().unwrap_or_else(|| "completed".to_string());
+
+    ResponseOutput {
+        reasoning: None,
+        message: response.content,
+        usage: response.usage,
+        status: Some(status.clone()),
+        warnings: response.warnings,
+        raw: ResponsesResponse {
+            id,
+            object: response.raw["object"].as_str().unwrap_or("chat.completion").to_string(),
+            created_at: response.raw["created"].as_i64().unwrap_or_default(),
+            status,
+            model: response.raw["model"].as_str().unwrap_or_default().to_string(),
+            output,
+            usage: Usage { input_tokens, output_tokens, total_tokens, output_tokens_details: None, input_tokens_details: None },
+            reasoning,
+        },
+        served_by: None,
+        metadata: response.metadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Message;
+
+    fn client(api_version: &str) -> AzureOpenAIClient {
+        AzureOpenAIClient::new("https://contoso.openai.azure.com/", "azure-key", api_version).unwrap()
+    }
+
+    #[test]
+    fn test_responses_api_follows_the_api_version() {
+        assert!(client("2025-04-01-preview").uses_responses_api());
+        assert!(client("2025-03-01-preview").uses_responses_api());
+        assert!(!client("2024-10-21").uses_responses_api());
+        assert!(!client("2025-01-01-preview").uses_responses_api());
+
+        let forced = AzureConfig::new("https://contoso.openai.azure.com", "azure-key", "2024-10-21").use_responses_api(true);
+        assert!(AzureOpenAIClient::from_config(forced).unwrap().uses_responses_api());
+        let disabled = AzureConfig::new("https://contoso.openai.azure.com", "azure-key", "2025-04-01-preview")
+            .use_responses_api(false);
+        assert!(!AzureOpenAIClient::from_config(disabled).unwrap().uses_responses_api());
+    }
+
+    #[test]
+    fn test_urls_name_the_deployment_and_api_version() {
+        let client = client("2024-10-21");
+        assert_eq!(
+            client.chat_url("gpt-4o"),
+            "https://contoso.openai.azure.com/openai/deployments/gpt-4o/chat/completions?api-version=2024-10-21"
+        );
+        assert_eq!(client.responses_url(), "https://contoso.openai.azure.com/openai/responses?api-version=2024-10-21");
+    }
+
+    #[test]
+    fn test_reasoning_request_as_chat_request() {
+        let request = ResponseRequest::new("o3-mini", vec![Message::human("Hi")])
+            .with_reasoning(ReasoningConfig::high())
+            .with_options(crate::traits::ResponseOptions::new().max_output_tokens(500).end_user_id("user-1"));
+        let chat = chat_request(request);
+
+        assert_eq!(chat.model, "o3-mini");
+        assert_eq!(chat.options.reasoning_effort.as_deref(), Some("high"));
+        assert_eq!(chat.options.max_tokens, Some(500));
+        assert_eq!(chat.options.end_user_id.as_deref(), Some("user-1"));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::capabilities::CapabilityRegistry;
+use crate::http::ConnectionOptions;
+use crate::retry::RetryPolicy;
+
+/// First `api-version` (by date) that serves `/openai/responses`
+pub const RESPONSES_API_SINCE: &str = "2025-03-01";
+
+/// Resource, key and API version for `AzureOpenAIClient`
+///
+/// `endpoint` is the resource root (`https://<resource>.openai.azure.com`),
+/// and models are deployment names. Whether reasoning calls use the
+/// Responses API follows `api_version` unless `use_responses_api` says so.
+#[derive(Clone)]
+pub struct AzureConfig {
+    pub endpoint: String,
+    pub api_key: String,
+    /// `api-version` query parameter, e.g. `2025-04-01-preview`
+    pub api_version: String,
+    /// Force the Responses API on or off (unset = decided by `api_version`)
+    pub responses_api: Option<bool>,
+    /// Resend requests that hit a 429 or a transient 5xx (unset = send once)
+    pub retry_policy: Option<RetryPolicy>,
+    /// What each deployment accepts (default: the built-in table)
+    pub capabilities: Option<CapabilityRegistry>,
+    /// Pool and keep-alive settings of the client's own `reqwest::Client`
+    pub connection: ConnectionOptions,
+    /// A `reqwest::Client` shared with other clients, used instead of
+    /// building one
+    pub http_client: Option<reqwest::Client>,
+}
+
+impl AzureConfig {
+    pub fn new(endpoint: impl Into<String>, api_key: impl Into<String>, api_version: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            api_key: api_key.into(),
+            api_version: api_version.into(),
+            responses_api: None,
+            retry_policy: None,
+            capabilities: None,
+            connection: ConnectionOptions::default(),
+            http_client: None,
+        }
+    }
+
+    /// Send reasoning calls to `/openai/responses` (true) or through chat
+    /// completions (false), whatever `api_version` is
+    pub fn use_responses_api(mut self, enabled: bool) -> Self {
+        self.responses_api = Some(enabled);
+        self
+    }
+
+    /// Whether reasoning calls go to the Responses API
+    pub fn responses_api_enabled(&self) -> bool {
+        self.responses_api.unwrap_or_else(|| {
+            // Versions are `YYYY-MM-DD` with an optional `-preview`
+            self.api_version.get(..10).is_some_and(|date| date >= RESPONSES_API_SINCE)
+        })
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Look deployments up in `registry`, e.g. to mark reasoning deployments
+    pub fn with_capabilities(mut self, registry: CapabilityRegistry) -> Self {
+        self.capabilities = Some(registry);
+        self
+    }
+
+    pub fn with_connection_options(mut self, options: ConnectionOptions) -> Self {
+        self.connection = options;
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl std::fmt::Debug for AzureConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureConfig")
+            .field("endpoint", &self.endpoint)
+            .field("api_key", &"[redacted]")
+            .field("api_version", &self.api_version)
+            .field("responses_api", &self.responses_api)
+            .field("retry_policy", &self.retry_policy)
+            .field("capabilities", &self.capabilities)
+            .field("connection", &self.connection)
+            .field("shared_http_client", &self.http_client.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,7 @@
+// Azure OpenAI implementations
+
+pub mod client;
+pub mod config;
+
+pub use client::AzureOpenAIClient;
+pub use config::{AzureConfig, RESPONSES_API_SINCE};
@@ -2,9 +2,15 @@ use anyhow::Result;
 use std::collections::VecDeque;
 
 /// Circular buffer for efficient line-based parsing
-/// Uses VecDeque for zero-copy line extraction
+///
+/// Taking a line copies it out of the front of a `VecDeque`, so the bytes
+/// still waiting are never moved, and a partial line is scanned for its
+/// newline only once, however many chunks it arrives in. See
+/// `benches/sse_parsing.rs` for a comparison with a `String` buffer.
 pub struct CircularLineBuffer {
     buffer: VecDeque<u8>,
+    /// Leading bytes already known to hold no newline
+    scanned: usize,
 }
 
 impl CircularLineBuffer {
@@ -12,6 +18,7 @@ impl CircularLineBuffer {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             buffer: VecDeque::with_capacity(capacity),
+            scanned: 0,
         }
     }
 
@@ -22,18 +29,53 @@ impl CircularLineBuffer {
 
     /// Extract next line (up to \n) from buffer
     /// Returns None if no complete line is available
+    ///
+    /// Lines are decoded only once complete, so a multi-byte character
+    /// split across network chunks waits in the buffer for its other half.
     pub fn next_line(&mut self) -> Option<Result<String>> {
-        // Find newline position
-        let newline_pos = self.buffer.iter().position(|&b| b == b'\n')?;
+        let Some(newline_pos) = self.find_newline() else {
+            self.scanned = self.buffer.len();
+            return None;
+        };
+        self.scanned = 0;
+        Some(decode_line(self.take_front(newline_pos + 1)))
+    }
 
-        // Drain bytes up to and including newline (zero-copy!)
-        let line_bytes: Vec<u8> = self.buffer.drain(..=newline_pos).collect();
+    /// Take what is left as a final, unterminated line (None if empty)
+    ///
+    /// For the end of the stream: a body may end without a trailing newline.
+    pub fn take_remaining(&mut self) -> Option<Result<String>> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        self.scanned = 0;
+        Some(decode_line(self.take_front(self.buffer.len())))
+    }
 
-        // Convert to UTF-8 string
-        match std::str::from_utf8(&line_bytes) {
-            Ok(line_str) => Some(Ok(line_str.trim().to_string())),
-            Err(e) => Some(Err(anyhow::anyhow!("Invalid UTF-8: {}", e))),
+    /// Position of the first newline past the scanned prefix
+    fn find_newline(&self) -> Option<usize> {
+        let (front, back) = self.buffer.as_slices();
+        if self.scanned < front.len() {
+            if let Some(pos) = front[self.scanned..].iter().position(|&b| b == b'\n') {
+                return Some(self.scanned + pos);
+            }
         }
+        let back_start = self.scanned.saturating_sub(front.len());
+        back[back_start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|pos| front.len() + back_start + pos)
+    }
+
+    /// Remove the first `len` bytes, copying them slice by slice
+    fn take_front(&mut self, len: usize) -> Vec<u8> {
+        let (front, back) = self.buffer.as_slices();
+        let mut bytes = Vec::with_capacity(len);
+        let from_front = len.min(front.len());
+        bytes.extend_from_slice(&front[..from_front]);
+        bytes.extend_from_slice(&back[..len - from_front]);
+        self.buffer.drain(..len);
+        bytes
     }
 
     /// Current buffer size
@@ -47,6 +89,15 @@ impl CircularLineBuffer {
     }
 }
 
+/// `line_bytes` as text without surrounding whitespace, reusing its allocation
+fn decode_line(line_bytes: Vec<u8>) -> Result<String> {
+    let mut line = String::from_utf8(line_bytes).map_err(|e| anyhow::anyhow!("Invalid UTF-8: {}", e.utf8_error()))?;
+    line.truncate(line.trim_end().len());
+    let leading = line.len() - line.trim_start().len();
+    line.drain(..leading);
+    Ok(line)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,5 +123,62 @@ mod tests {
         buffer.extend(b" line\n");
         assert_eq!(buffer.next_line().unwrap().unwrap(), "partial line");
     }
+
+    #[test]
+    fn test_line_spread_over_many_chunks() {
+        let mut buffer = CircularLineBuffer::with_capacity(64);
+
+        for chunk in ["da", "ta: ", "{\"a\"", ":1}"] {
+            buffer.extend(chunk.as_bytes());
+            assert!(buffer.next_line().is_none());
+        }
+        buffer.extend(b"\n\ndata: 2\nda");
+        assert_eq!(buffer.next_line().unwrap().unwrap(), "data: {\"a\":1}");
+        assert_eq!(buffer.next_line().unwrap().unwrap(), "");
+        assert_eq!(buffer.next_line().unwrap().unwrap(), "data: 2");
+        assert!(buffer.next_line().is_none());
+
+        buffer.extend(b"ta: 3\n");
+        assert_eq!(buffer.next_line().unwrap().unwrap(), "data: 3");
+    }
+
+    #[test]
+    fn test_lines_wrapping_around_the_buffer() {
+        let mut buffer = CircularLineBuffer::with_capacity(8);
+
+        for i in 0..20 {
+            buffer.extend(format!("ab{}", i).as_bytes());
+            assert!(buffer.next_line().is_none());
+            buffer.extend(b"cd\nef");
+            assert_eq!(buffer.next_line().unwrap().unwrap(), format!("ab{}cd", i));
+            buffer.extend(b"\n");
+            assert_eq!(buffer.next_line().unwrap().unwrap(), "ef");
+        }
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_character_split_across_chunks() {
+        let mut buffer = CircularLineBuffer::with_capacity(64);
+        let bytes = "olá 👋\n".as_bytes();
+        // Cut inside the 4-byte emoji
+        let (head, tail) = bytes.split_at(bytes.len() - 3);
+
+        buffer.extend(head);
+        assert!(buffer.next_line().is_none());
+
+        buffer.extend(tail);
+        assert_eq!(buffer.next_line().unwrap().unwrap(), "olá 👋");
+    }
+
+    #[test]
+    fn test_unterminated_last_line() {
+        let mut buffer = CircularLineBuffer::with_capacity(64);
+
+        buffer.extend(b"data: [DONE]");
+        assert!(buffer.next_line().is_none());
+        assert_eq!(buffer.take_remaining().unwrap().unwrap(), "data: [DONE]");
+        assert!(buffer.take_remaining().is_none());
+    }
 }
 
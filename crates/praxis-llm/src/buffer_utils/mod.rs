@@ -6,5 +6,5 @@ mod sse_parser;
 pub use buffering::CircularLineBuffer;
 pub use batching::EventBatcher;
 pub use adaptive_batching::{AdaptiveEventBatcher, BatcherStats};
-pub use sse_parser::{SseLineParser, parse_sse_stream};
+pub use sse_parser::{SseFrame, SseFrameDecoder, SseLineParser, parse_sse_stream, parse_sse_byte_stream};
 
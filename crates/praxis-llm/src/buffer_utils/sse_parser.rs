@@ -6,9 +6,60 @@ use std::pin::Pin;
 use super::buffering::CircularLineBuffer;
 use crate::StreamEvent;
 
+/// One dispatched SSE event, before its data is parsed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseFrame {
+    /// `message` when the server didn't name the event
+    pub event: String,
+    /// `data:` lines joined with `\n`
+    pub data: String,
+    pub id: Option<String>,
+}
+
+/// Assembles SSE lines into frames; a blank line dispatches the frame
+#[derive(Debug, Default)]
+pub struct SseFrameDecoder {
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseFrameDecoder {
+    /// Feed one line (without its line ending)
+    ///
+    /// Comment lines (`: keep-alive`) and `retry:` are ignored; an event
+    /// with no `data:` line is dropped, as browsers do.
+    pub fn push_line(&mut self, line: &str) -> Option<SseFrame> {
+        if line.is_empty() {
+            let event = self.event.take();
+            let id = self.id.take();
+            if self.data.is_empty() {
+                return None;
+            }
+            return Some(SseFrame {
+                event: event.unwrap_or_else(|| "message".to_string()),
+                data: std::mem::take(&mut self.data).join("\n"),
+                id,
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" => self.id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
 /// Strategy pattern for parsing different SSE response types
 pub trait SseLineParser: Send {
-    /// Parse a data line into stream events
+    /// Parse the data of one event (its `data:` lines joined with `\n`)
     fn parse_data_line(&self, data: &str) -> Result<Vec<StreamEvent>>;
     
     /// Check if this line signals end of stream
@@ -23,51 +74,162 @@ pub fn parse_sse_stream<P: SseLineParser + 'static>(
     response: Response,
     parser: P,
 ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
-    let stream = response.bytes_stream();
-    
+    parse_sse_byte_stream(response.bytes_stream(), parser)
+}
+
+/// Parse SSE from any byte stream (HTTP body, captured file, test fixture)
+pub fn parse_sse_byte_stream<S, B, E, P>(
+    stream: S,
+    parser: P,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send,
+    E: std::fmt::Display + Send,
+    P: SseLineParser + 'static,
+{
     Box::pin(async_stream::stream! {
         let mut byte_chunks = Box::pin(stream);
         let mut buffer = CircularLineBuffer::with_capacity(4096);
+        let mut decoder = SseFrameDecoder::default();
         
         while let Some(chunk_result) = byte_chunks.next().await {
             match chunk_result {
                 Ok(bytes) => {
-                    buffer.extend(&bytes);
+                    buffer.extend(bytes.as_ref());
                     
                     // Process all complete lines in buffer
                     while let Some(line_result) = buffer.next_line() {
-                        match line_result {
-                            Ok(line) => {
-                                if line.is_empty() {
-                                    continue;
-                                }
-                                
-                                // Parse SSE data lines
-                                if let Some(data) = line.strip_prefix("data: ") {
-                                    // Check for done marker
-                                    if parser.is_done_marker(data) {
-                                        yield Ok(StreamEvent::Done { finish_reason: None });
-                                        break;
-                                    }
-                                    
-                                    // Parse data using strategy
-                                    match parser.parse_data_line(data) {
-                                        Ok(events) => {
-                                            for event in events {
-                                                yield Ok(event);
-                                            }
-                                        }
-                                        Err(e) => yield Err(e),
-                                    }
+                        let line = match line_result {
+                            Ok(line) => line,
+                            Err(e) => {
+                                yield Err(e);
+                                continue;
+                            }
+                        };
+                        let Some(frame) = decoder.push_line(&line) else {
+                            continue;
+                        };
+                        match parse_frame(&parser, &frame) {
+                            SseData::Events(events) => {
+                                for event in events {
+                                    yield event;
                                 }
                             }
-                            Err(e) => yield Err(e),
+                            SseData::Done => {
+                                yield Ok(StreamEvent::Done { finish_reason: None, choice_index: None });
+                                break;
+                            }
                         }
                     }
                 }
                 Err(e) => yield Err(anyhow::anyhow!("Stream error: {}", e)),
             }
         }
+        
+        // A body may end without a newline, or without the blank line that
+        // dispatches its last event
+        let last_line = buffer.take_remaining().and_then(|line| line.ok());
+        let frame = last_line
+            .and_then(|line| decoder.push_line(&line))
+            .or_else(|| decoder.push_line(""));
+        if let Some(frame) = frame {
+            match parse_frame(&parser, &frame) {
+                SseData::Events(events) => {
+                    for event in events {
+                        yield event;
+                    }
+                }
+                SseData::Done => yield Ok(StreamEvent::Done { finish_reason: None, choice_index: None }),
+            }
+        }
     })
 }
 
+/// What one SSE event produced
+enum SseData {
+    Events(Vec<Result<StreamEvent>>),
+    /// The parser's done marker
+    Done,
+}
+
+fn parse_frame<P: SseLineParser>(parser: &P, frame: &SseFrame) -> SseData {
+    if parser.is_done_marker(&frame.data) {
+        return SseData::Done;
+    }
+    match parser.parse_data_line(&frame.data) {
+        Ok(events) => SseData::Events(events.into_iter().map(Ok).collect()),
+        Err(e) => SseData::Events(vec![Err(e)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::ChatSseParser;
+
+    async fn collect(chunks: Vec<Vec<u8>>) -> Vec<StreamEvent> {
+        let stream = futures::stream::iter(chunks.into_iter().map(Ok::<_, std::io::Error>));
+        parse_sse_byte_stream(stream, ChatSseParser)
+            .map(|event| event.unwrap())
+            .collect()
+            .await
+    }
+
+    fn message_text(events: &[StreamEvent]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                StreamEvent::Message { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_character_split_across_chunks_is_kept() {
+        let body = concat!(
+            r#"data: {"id":"c1","object":"chat.completion.chunk","created":0,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"Olá! "}}]}"#,
+            "\n\n",
+            r#"data: {"id":"c1","object":"chat.completion.chunk","created":0,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"Tudo bem? 🙂"}}]}"#,
+            "\n\n",
+        )
+        .as_bytes();
+
+        // Every split point, including inside "á" and the emoji
+        for cut in 1..body.len() {
+            let events = collect(vec![body[..cut].to_vec(), body[cut..].to_vec()]).await;
+            assert_eq!(message_text(&events), "Olá! Tudo bem? 🙂", "split at byte {}", cut);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_last_line_without_newline_is_kept() {
+        let body = concat!(r#"data: {"id":"c1","object":"chat.completion.chunk","created":0,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"Oi"}}]}"#, "\n\n", "data: [DONE]");
+
+        let events = collect(vec![body.as_bytes().to_vec()]).await;
+        assert_eq!(message_text(&events), "Oi");
+        assert!(matches!(events.last(), Some(StreamEvent::Done { finish_reason: None, choice_index: None })));
+    }
+
+    #[tokio::test]
+    async fn test_crlf_multiline_events_split_anywhere() {
+        let body = concat!(
+            ": keep-alive\r\n\r\n",
+            "event: message\r\nid: 1\r\n",
+            r#"data: {"id":"c1","object":"chat.completion.chunk","#, "\r\n",
+            r#"data: "created":0,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"Olá, "}}]}"#, "\r\n\r\n",
+            "retry: 3000\r\n",
+            r#"data:{"id":"c1","object":"chat.completion.chunk","created":0,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"mundo"}}]}"#, "\r\n\r\n",
+            "data: [DONE]\r\n\r\n",
+        )
+        .as_bytes();
+
+        // Every split point, including between '\r' and '\n' and inside "á"
+        for cut in 1..body.len() {
+            let events = collect(vec![body[..cut].to_vec(), body[cut..].to_vec()]).await;
+            assert_eq!(message_text(&events), "Olá, mundo", "split at byte {}", cut);
+            assert_eq!(events.len(), 3, "split at byte {}", cut);
+        }
+    }
+}
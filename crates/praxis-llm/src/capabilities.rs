@@ -0,0 +1,423 @@
+//! What each model accepts, and the one policy for options it doesn't
+//!
+//! Request builders run their options through `ModelCapabilities` before
+//! serializing them. Unsupported options are dropped and reported as
+//! `OptionAdjusted` warnings, or rejected when the request sets
+//! `strict_options`.
+//!
+//! Capabilities are looked up by model-name prefix. `CapabilityRegistry`
+//! adds entries for names the built-in table can't know, such as Azure or
+//! on-prem deployment names.
+
+use crate::openai::ReasoningConfig;
+use crate::traits::{ChatOptions, ResponseOptions};
+use crate::types::Message;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Request options a model family accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    pub temperature: bool,
+    /// Function calling (`tools` / `tool_choice`)
+    pub tools: bool,
+    /// `reasoning_effort` (Chat) and `reasoning` (Responses)
+    pub reasoning: bool,
+    /// Token limit is sent as `max_completion_tokens` instead of `max_tokens`
+    pub max_completion_tokens: bool,
+    /// `top_p`, `stop` and the presence/frequency penalties
+    pub sampling: bool,
+    /// Context window in tokens, when known
+    pub max_context_tokens: Option<u32>,
+    /// Takes `developer` messages; other models get them as `system`
+    pub developer_role: bool,
+    /// Returns token log probabilities (`logprobs` / `top_logprobs`)
+    pub logprobs: bool,
+    /// Answers with speech (`ChatOptions::audio`)
+    pub audio: bool,
+}
+
+/// Built-in entries, by model-name prefix (the longest matching prefix wins)
+const BUILT_IN_CAPABILITIES: &[(&str, ModelCapabilities)] = &[
+    ("gpt-3.5", ModelCapabilities::chat(16_385)),
+    ("gpt-4", ModelCapabilities::chat(8_192)),
+    ("gpt-4-turbo", ModelCapabilities::chat(128_000)),
+    ("gpt-4o", ModelCapabilities::chat(128_000)),
+    ("gpt-4o-audio", ModelCapabilities::chat(128_000).with_audio()),
+    ("gpt-4o-mini-audio", ModelCapabilities::chat(128_000).with_audio()),
+    ("gpt-4.1", ModelCapabilities::chat(1_047_576)),
+    ("gpt-5", ModelCapabilities::reasoning(400_000)),
+    ("o1", ModelCapabilities::reasoning(200_000)),
+    // The first o1 releases shipped without function calling
+    ("o1-mini", ModelCapabilities::reasoning(128_000).without_tools()),
+    ("o1-preview", ModelCapabilities::reasoning(128_000).without_tools()),
+    ("o3", ModelCapabilities::reasoning(200_000)),
+    ("o4", ModelCapabilities::reasoning(200_000)),
+    ("claude-", ModelCapabilities::chat(200_000)),
+    ("gemini-", ModelCapabilities::chat(1_048_576)),
+];
+
+impl ModelCapabilities {
+    /// A chat model accepting every option but reasoning
+    pub const fn chat(max_context_tokens: u32) -> Self {
+        Self {
+            temperature: true,
+            tools: true,
+            reasoning: false,
+            max_completion_tokens: false,
+            sampling: true,
+            max_context_tokens: Some(max_context_tokens),
+            developer_role: false,
+            logprobs: true,
+            audio: false,
+        }
+    }
+
+    /// A reasoning model: no temperature, sampling parameters or log
+    /// probabilities, `max_completion_tokens`, and the `developer` role
+    pub const fn reasoning(max_context_tokens: u32) -> Self {
+        Self {
+            temperature: false,
+            tools: true,
+            reasoning: true,
+            max_completion_tokens: true,
+            sampling: false,
+            max_context_tokens: Some(max_context_tokens),
+            developer_role: true,
+            logprobs: false,
+            audio: false,
+        }
+    }
+
+    /// The same model without function calling (`tools` / `tool_choice`)
+    pub const fn without_tools(mut self) -> Self {
+        self.tools = false;
+        self
+    }
+
+    /// The same model, answering with speech (`ChatOptions::audio`)
+    pub const fn with_audio(mut self) -> Self {
+        self.audio = true;
+        self
+    }
+
+    /// What names outside the table get: a chat model of unknown size
+    const fn unknown() -> Self {
+        Self {
+            max_context_tokens: None,
+            ..Self::chat(0)
+        }
+    }
+
+    /// Capabilities of a model in the built-in table
+    ///
+    /// Deployments with their own model names register them on a
+    /// `CapabilityRegistry` instead.
+    pub fn for_model(model: &str) -> Self {
+        longest_prefix(BUILT_IN_CAPABILITIES.iter().map(|(prefix, caps)| (*prefix, caps)), model)
+            .copied()
+            .unwrap_or(Self::unknown())
+    }
+
+    /// Whether `model` belongs to a known family
+    ///
+    /// Unknown names (typos, Azure deployment names) get the capabilities
+    /// of a non-reasoning chat model.
+    pub fn is_known(model: &str) -> bool {
+        BUILT_IN_CAPABILITIES.iter().any(|(prefix, _)| model.starts_with(prefix))
+    }
+
+    /// Send `developer` messages as `system` messages when the model has no
+    /// developer role
+    pub fn adjust_messages(&self, messages: Vec<Message>) -> Vec<Message> {
+        if self.developer_role {
+            return messages;
+        }
+        messages
+            .into_iter()
+            .map(|message| match message {
+                Message::Developer { content, name, cache_hint } => Message::System { content, name, cache_hint },
+                message => message,
+            })
+            .collect()
+    }
+
+    /// Drop the chat options this model doesn't accept
+    ///
+    /// Fails instead when `options.strict_options` is set and something
+    /// would be dropped.
+    pub fn adjust_chat_options(&self, model: &str, options: &ChatOptions) -> Result<(ChatOptions, Vec<OptionAdjusted>)> {
+        let mut options = options.clone();
+        let mut adjusted = Vec::new();
+
+        if !self.temperature && options.temperature.take().is_some() {
+            adjusted.push(OptionAdjusted::removed("temperature", model, "does not support temperature"));
+        }
+        if !self.tools && (options.tools.take().is_some() | options.tool_choice.take().is_some()) {
+            adjusted.push(OptionAdjusted::removed("tools", model, "does not support function calling"));
+        }
+        if !self.reasoning && options.reasoning_effort.take().is_some() {
+            adjusted.push(OptionAdjusted::removed("reasoning_effort", model, "is not a reasoning model"));
+        }
+        if !self.sampling {
+            let sampling = [
+                ("top_p", options.top_p.take().is_some()),
+                ("stop", options.stop.take().is_some()),
+                ("presence_penalty", options.presence_penalty.take().is_some()),
+                ("frequency_penalty", options.frequency_penalty.take().is_some()),
+            ];
+            for (option, _) in sampling.into_iter().filter(|(_, set)| *set) {
+                adjusted.push(OptionAdjusted::removed(option, model, "does not support sampling parameters"));
+            }
+        }
+
+        if !self.logprobs && options.wants_logprobs() {
+            options.logprobs = None;
+            options.top_logprobs = None;
+            adjusted.push(OptionAdjusted::removed("logprobs", model, "does not return log probabilities"));
+        }
+        if !self.audio && options.audio.take().is_some() {
+            adjusted.push(OptionAdjusted::removed("audio", model, "does not answer with audio"));
+        }
+
+        check_strict(options.strict_options, model, &adjusted)?;
+        Ok((options, adjusted))
+    }
+
+    /// Drop the Responses options (and reasoning config) this model doesn't accept
+    pub fn adjust_response_options(
+        &self,
+        model: &str,
+        reasoning: Option<&ReasoningConfig>,
+        options: &ResponseOptions,
+    ) -> Result<(Option<ReasoningConfig>, ResponseOptions, Vec<OptionAdjusted>)> {
+        let mut options = options.clone();
+        let mut reasoning = reasoning.cloned();
+        let mut adjusted = Vec::new();
+
+        if !self.temperature && options.temperature.take().is_some() {
+            adjusted.push(OptionAdjusted::removed("temperature", model, "does not support temperature"));
+        }
+        if !self.tools && (options.tools.take().is_some() | options.tool_choice.take().is_some()) {
+            adjusted.push(OptionAdjusted::removed("tools", model, "does not support function calling"));
+        }
+        if !self.reasoning && reasoning.take().is_some() {
+            adjusted.push(OptionAdjusted::removed("reasoning", model, "is not a reasoning model"));
+        }
+
+        check_strict(options.strict_options, model, &adjusted)?;
+        Ok((reasoning, options, adjusted))
+    }
+}
+
+/// Capabilities by model-name prefix: the built-in table plus registered models
+///
+/// ```rust
+/// use praxis_llm::{CapabilityRegistry, ModelCapabilities};
+///
+/// // An on-prem deployment of a reasoning model under its own name
+/// let registry = CapabilityRegistry::new().with_model("acme-reasoner", ModelCapabilities::reasoning(200_000));
+/// assert!(registry.get("acme-reasoner-v2").reasoning);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityRegistry {
+    models: HashMap<String, ModelCapabilities>,
+}
+
+impl Default for CapabilityRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CapabilityRegistry {
+    /// The built-in table
+    pub fn new() -> Self {
+        Self {
+            models: BUILT_IN_CAPABILITIES.iter().map(|(prefix, caps)| (prefix.to_string(), *caps)).collect(),
+        }
+    }
+
+    /// Give models starting with `prefix` these capabilities, replacing any entry for it
+    pub fn with_model(mut self, prefix: impl Into<String>, capabilities: ModelCapabilities) -> Self {
+        self.models.insert(prefix.into(), capabilities);
+        self
+    }
+
+    /// Add or replace entries
+    pub fn with_models(mut self, models: impl IntoIterator<Item = (String, ModelCapabilities)>) -> Self {
+        self.models.extend(models);
+        self
+    }
+
+    /// Capabilities of the longest prefix of `model`, or those of an unknown chat model
+    pub fn get(&self, model: &str) -> ModelCapabilities {
+        longest_prefix(self.models.iter().map(|(prefix, caps)| (prefix.as_str(), caps)), model)
+            .copied()
+            .unwrap_or(ModelCapabilities::unknown())
+    }
+
+    /// Whether some entry matches `model`
+    pub fn is_known(&self, model: &str) -> bool {
+        self.models.keys().any(|prefix| model.starts_with(prefix.as_str()))
+    }
+}
+
+fn longest_prefix<'a, T>(entries: impl Iterator<Item = (&'a str, &'a T)>, model: &str) -> Option<&'a T> {
+    entries
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, value)| value)
+}
+
+/// A request option the model doesn't support, dropped before sending
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionAdjusted {
+    pub option: String,
+    pub reason: String,
+}
+
+impl OptionAdjusted {
+    fn removed(option: &str, model: &str, why: &str) -> Self {
+        Self {
+            option: option.to_string(),
+            reason: format!("removed: {} {}", model, why),
+        }
+    }
+}
+
+fn check_strict(strict: bool, model: &str, adjusted: &[OptionAdjusted]) -> Result<()> {
+    if strict && !adjusted.is_empty() {
+        let options: Vec<&str> = adjusted.iter().map(|a| a.option.as_str()).collect();
+        anyhow::bail!("Options not supported by {}: {}", model, options.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Tool, ToolChoice};
+
+    fn tool() -> Tool {
+        Tool::new("search", "Search the web", serde_json::json!({ "type": "object" }))
+    }
+
+    #[test]
+    fn test_capabilities_by_family() {
+        assert!(ModelCapabilities::for_model("gpt-4o-mini").temperature);
+        assert!(!ModelCapabilities::for_model("gpt-4o").reasoning);
+
+        let gpt5 = ModelCapabilities::for_model("gpt-5");
+        assert!(!gpt5.temperature && gpt5.tools && gpt5.reasoning && gpt5.max_completion_tokens);
+        assert!(!ModelCapabilities::for_model("o1-preview").tools);
+        assert!(ModelCapabilities::for_model("o3-mini").tools);
+        assert!(ModelCapabilities::for_model("gpt-4o-audio-preview").audio);
+        assert!(!ModelCapabilities::for_model("gpt-4o").audio);
+
+        assert!(ModelCapabilities::is_known("gpt-4o-mini") && ModelCapabilities::is_known("o4-mini"));
+        assert!(!ModelCapabilities::is_known("gtp-4o") && !ModelCapabilities::is_known("my-deployment"));
+    }
+
+    #[test]
+    fn test_developer_role_by_family() {
+        let messages = || vec![Message::developer("Be brief."), Message::human("hi")];
+
+        assert!(!ModelCapabilities::for_model("gpt-4o").developer_role);
+        let downgraded = ModelCapabilities::for_model("gpt-4o").adjust_messages(messages());
+        assert!(matches!(&downgraded[0], Message::System { content, .. } if content.as_text() == Some("Be brief.")));
+
+        assert!(ModelCapabilities::for_model("gpt-5").developer_role);
+        let kept = ModelCapabilities::for_model("gpt-5").adjust_messages(messages());
+        assert_eq!(kept[0].role(), "developer");
+    }
+
+    #[test]
+    fn test_registry_lookups() {
+        let registry = CapabilityRegistry::new();
+        for model in ["o1", "o1-2024-12-17", "o3-mini", "gpt-5", "gpt-5-mini"] {
+            let caps = registry.get(model);
+            assert!(caps.reasoning && caps.max_completion_tokens && !caps.temperature, "{}", model);
+        }
+        assert!(!registry.get("o1-preview").tools);
+        assert_eq!(registry.get("gpt-4o-mini").max_context_tokens, Some(128_000));
+        assert!(!registry.get("gpt-4o").reasoning);
+
+        // Names merely starting with an "o" are not o-series models
+        let ollama = registry.get("ollama-mistral");
+        assert!(!ollama.reasoning && ollama.temperature && ollama.max_context_tokens.is_none());
+        assert!(!registry.is_known("ollama-mistral"));
+    }
+
+    #[test]
+    fn test_registered_models_override_and_extend() {
+        let registry = CapabilityRegistry::new()
+            .with_model("prod-reasoner", ModelCapabilities::reasoning(200_000))
+            .with_model("gpt-4o-mini", ModelCapabilities::chat(64_000).without_tools());
+
+        let deployment = registry.get("prod-reasoner-eastus");
+        assert!(deployment.reasoning && deployment.max_completion_tokens);
+        assert!(registry.is_known("prod-reasoner-eastus"));
+        assert!(!registry.get("gpt-4o-mini-2024-07-18").tools);
+        assert!(registry.get("gpt-4o").tools);
+        // The static lookup only knows the built-in table
+        assert!(!ModelCapabilities::for_model("prod-reasoner").reasoning);
+    }
+
+    #[test]
+    fn test_unsupported_chat_options_are_dropped_with_warnings() {
+        let options = ChatOptions::new()
+            .temperature(0.2)
+            .max_tokens(100)
+            .tools(vec![tool()])
+            .tool_choice(ToolChoice::auto());
+
+        let (kept, adjusted) = ModelCapabilities::for_model("o1-mini").adjust_chat_options("o1-mini", &options).unwrap();
+        assert_eq!(kept.temperature, None);
+        assert!(kept.tools.is_none() && kept.tool_choice.is_none());
+        assert_eq!(kept.max_tokens, Some(100));
+        let dropped: Vec<&str> = adjusted.iter().map(|a| a.option.as_str()).collect();
+        assert_eq!(dropped, vec!["temperature", "tools"]);
+
+        let (kept, adjusted) = ModelCapabilities::for_model("gpt-4o")
+            .adjust_chat_options("gpt-4o", &options.clone().reasoning_effort("high"))
+            .unwrap();
+        assert_eq!(kept.temperature, Some(0.2));
+        assert_eq!(adjusted, vec![OptionAdjusted::removed("reasoning_effort", "gpt-4o", "is not a reasoning model")]);
+    }
+
+    #[test]
+    fn test_reasoning_models_drop_sampling_parameters_but_keep_seed() {
+        let options = ChatOptions::new().top_p(0.9).stop(["END"]).frequency_penalty(0.5).seed(7);
+
+        let (kept, adjusted) = ModelCapabilities::for_model("gpt-5").adjust_chat_options("gpt-5", &options).unwrap();
+        assert!(kept.top_p.is_none() && kept.stop.is_none() && kept.frequency_penalty.is_none());
+        assert_eq!(kept.seed, Some(7));
+        let dropped: Vec<&str> = adjusted.iter().map(|a| a.option.as_str()).collect();
+        assert_eq!(dropped, vec!["top_p", "stop", "frequency_penalty"]);
+
+        let (kept, adjusted) = ModelCapabilities::for_model("gpt-4o").adjust_chat_options("gpt-4o", &options).unwrap();
+        assert!(adjusted.is_empty());
+        assert_eq!(kept.stop, Some(vec!["END".to_string()]));
+    }
+
+    #[test]
+    fn test_strict_options_fail_instead() {
+        let options = ChatOptions::new().temperature(0.2).strict_options(true);
+        let err = ModelCapabilities::for_model("gpt-5").adjust_chat_options("gpt-5", &options).unwrap_err();
+        assert!(err.to_string().contains("temperature"), "{}", err);
+
+        // Nothing to drop: strict mode is a no-op
+        assert!(ModelCapabilities::for_model("gpt-4o").adjust_chat_options("gpt-4o", &options).is_ok());
+
+        let response = ResponseOptions::new().strict_options(true);
+        let caps = ModelCapabilities::for_model("gpt-4o");
+        assert!(caps.adjust_response_options("gpt-4o", Some(&ReasoningConfig::high()), &response).is_err());
+        let (reasoning, _, adjusted) = caps
+            .adjust_response_options("gpt-4o", Some(&ReasoningConfig::high()), &ResponseOptions::new())
+            .unwrap();
+        assert!(reasoning.is_none());
+        assert_eq!(adjusted[0].option, "reasoning");
+    }
+}
@@ -0,0 +1,303 @@
+//! Raw provider payload capture for debugging
+//!
+//! When `HttpOptions::capture_dir` (or the `PRAXIS_CAPTURE_DIR` environment
+//! variable) is set, every request payload and the raw SSE bytes returned by
+//! the provider are written to timestamped files:
+//!
+//! - `<stem>.request.json`: endpoint, URL, redacted headers and JSON payload
+//! - `<stem>.sse`: the response body exactly as received
+//!
+//! `CapturedExchange::load` reads a capture back and replays it through the
+//! same SSE parsers, so a production bug report becomes a deterministic
+//! test fixture.
+//!
+//! # Security
+//!
+//! Captures contain full prompts, tool arguments and model output, which may
+//! include personal data or secrets pasted by end users. Credential headers
+//! (`Authorization`, `api-key`, ...) are redacted, but bodies are written
+//! verbatim. Only enable capture in controlled environments, restrict
+//! permissions on the capture directory, and delete captures once the issue
+//! is reproduced.
+//!
+//! When capture is disabled (the default) no capture state is created and
+//! response streams are passed through untouched.
+
+use crate::anthropic::streaming::AnthropicSseParser;
+use crate::gemini::streaming::GeminiSseParser;
+use crate::buffer_utils::parse_sse_byte_stream;
+use crate::http::HttpOptions;
+use crate::streaming::{usage_before_done, ChatSseParser, ResponseSseParser, StreamEvent};
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const REDACTED: &str = "[REDACTED]";
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "api-key",
+    "x-api-key",
+    "cookie",
+];
+
+static CAPTURE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Header names and values with credentials redacted
+pub(crate) fn redact_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.as_str().to_ascii_lowercase();
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or(REDACTED).to_string()
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// An in-progress capture for a single request
+pub(crate) struct Capture {
+    sse_path: PathBuf,
+    max_bytes: usize,
+}
+
+impl Capture {
+    /// Write the request file and prepare the SSE file (None when disabled or on I/O errors)
+    pub(crate) fn start(
+        options: &HttpOptions,
+        endpoint: &str,
+        url: &str,
+        headers: &BTreeMap<String, String>,
+        payload: &Value,
+    ) -> Option<Self> {
+        let dir = options.capture_dir.as_ref()?;
+
+        let stem = format!(
+            "{}-{:05}-{}",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            CAPTURE_COUNTER.fetch_add(1, Ordering::Relaxed),
+            endpoint.replace('/', "_"),
+        );
+
+        let mut request = serde_json::json!({
+            "endpoint": endpoint,
+            "url": url,
+            "captured_at": chrono::Utc::now().to_rfc3339(),
+            "headers": headers,
+            "payload": payload,
+        });
+        let mut bytes = serde_json::to_vec_pretty(&request).unwrap_or_default();
+        if bytes.len() > options.capture_max_bytes {
+            request["payload"] = serde_json::json!({
+                "truncated": true,
+                "original_bytes": bytes.len(),
+            });
+            bytes = serde_json::to_vec_pretty(&request).unwrap_or_default();
+        }
+
+        let result = std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(dir.join(format!("{}.request.json", stem)), &bytes));
+        if let Err(e) = result {
+            tracing::warn!("Payload capture disabled for this request: {}", e);
+            return None;
+        }
+
+        Some(Self {
+            sse_path: dir.join(format!("{}.sse", stem)),
+            max_bytes: options.capture_max_bytes,
+        })
+    }
+
+    /// Pass the byte stream through while copying it to the SSE capture file
+    pub(crate) fn tee<S, B, E>(self, stream: S) -> Pin<Box<dyn Stream<Item = std::result::Result<B, E>> + Send>>
+    where
+        S: Stream<Item = std::result::Result<B, E>> + Send + 'static,
+        B: AsRef<[u8]> + Send + 'static,
+        E: Send + 'static,
+    {
+        let mut file = match File::create(&self.sse_path) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                tracing::warn!("Failed to create SSE capture {}: {}", self.sse_path.display(), e);
+                None
+            }
+        };
+        let mut remaining = self.max_bytes;
+
+        Box::pin(stream.map(move |chunk| {
+            if let (Ok(bytes), Some(writer)) = (&chunk, file.as_mut()) {
+                let bytes = bytes.as_ref();
+                let take = bytes.len().min(remaining);
+                let written = writer.write_all(&bytes[..take]);
+                remaining -= take;
+
+                if written.is_err() || remaining == 0 {
+                    if remaining == 0 {
+                        tracing::warn!("SSE capture reached the size cap, truncating");
+                    }
+                    file = None;
+                }
+            }
+            chunk
+        }))
+    }
+}
+
+/// A request/response pair loaded from a capture directory
+#[derive(Debug, Clone)]
+pub struct CapturedExchange {
+    /// API endpoint, e.g. `chat/completions`, `responses`, (Anthropic) `messages` or (Gemini) `generate_content`
+    pub endpoint: String,
+    /// Request file contents (URL, redacted headers, payload)
+    pub request: Value,
+    /// Raw SSE bytes as received from the provider
+    pub sse: Vec<u8>,
+}
+
+impl CapturedExchange {
+    /// Load a capture from its `.request.json` or `.sse` path (or the shared stem)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_string_lossy().to_string();
+        let stem = path
+            .strip_suffix(".request.json")
+            .or_else(|| path.strip_suffix(".sse"))
+            .unwrap_or(&path);
+
+        let request_path = format!("{}.request.json", stem);
+        let request: Value = serde_json::from_slice(
+            &std::fs::read(&request_path).with_context(|| format!("Failed to read {}", request_path))?,
+        )
+        .with_context(|| format!("Invalid capture request file {}", request_path))?;
+
+        let sse_path = format!("{}.sse", stem);
+        let sse = std::fs::read(&sse_path).with_context(|| format!("Failed to read {}", sse_path))?;
+
+        let endpoint = request["endpoint"].as_str().unwrap_or_default().to_string();
+
+        Ok(Self { endpoint, request, sse })
+    }
+
+    /// Replay the captured SSE bytes through the parser matching the endpoint
+    pub fn into_event_stream(self) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
+        let bytes = futures::stream::iter(std::iter::once(Ok::<_, std::convert::Infallible>(self.sse)));
+        match self.endpoint.as_str() {
+            "responses" => parse_sse_byte_stream(bytes, ResponseSseParser),
+            "messages" => parse_sse_byte_stream(bytes, AnthropicSseParser),
+            "generate_content" => parse_sse_byte_stream(bytes, GeminiSseParser::default()),
+            _ => usage_before_done(parse_sse_byte_stream(bytes, ChatSseParser)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "praxis-capture-{}-{}-{}",
+            name,
+            std::process::id(),
+            CAPTURE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn headers() -> BTreeMap<String, String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer sk-secret"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        redact_headers(&headers)
+    }
+
+    async fn capture_chunks(options: &HttpOptions, chunks: Vec<&'static str>) -> PathBuf {
+        let capture = Capture::start(
+            options,
+            "chat/completions",
+            "https://api.openai.com/v1/chat/completions",
+            &headers(),
+            &serde_json::json!({"model": "gpt-4o"}),
+        )
+        .expect("capture enabled");
+        let stream = futures::stream::iter(
+            chunks.into_iter().map(|c| Ok::<_, std::convert::Infallible>(c.as_bytes().to_vec())),
+        );
+        let passed: Vec<_> = capture.tee(stream).collect().await;
+        assert!(passed.iter().all(|c| c.is_ok()));
+
+        let dir = options.capture_dir.clone().unwrap();
+        std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .find(|p| p.extension().is_some_and(|ext| ext == "sse"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_disabled_capture_is_noop() {
+        let capture = Capture::start(
+            &HttpOptions::default(),
+            "chat/completions",
+            "url",
+            &BTreeMap::new(),
+            &Value::Null,
+        );
+        assert!(capture.is_none());
+    }
+
+    #[test]
+    fn test_credentials_are_redacted() {
+        let headers = headers();
+        assert_eq!(headers["authorization"], REDACTED);
+        assert_eq!(headers["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_capture_round_trips_through_replay() {
+        let options = HttpOptions::new().with_capture_dir(temp_dir("replay"));
+        let sse_path = capture_chunks(
+            &options,
+            vec![
+                "data: {\"id\":\"1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",",
+                "\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":null}]}\n\n",
+                "data: [DONE]\n\n",
+            ],
+        )
+        .await;
+
+        let exchange = CapturedExchange::load(&sse_path).unwrap();
+        assert_eq!(exchange.endpoint, "chat/completions");
+        assert_eq!(exchange.request["headers"]["authorization"], REDACTED);
+        assert!(!exchange.request.to_string().contains("sk-secret"));
+
+        let events: Vec<_> = exchange.into_event_stream().collect().await;
+        assert!(matches!(&events[0], Ok(StreamEvent::Message { content, .. }) if content == "Hi"));
+
+        let _ = std::fs::remove_dir_all(options.capture_dir.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sse_capture_respects_size_cap() {
+        let options = HttpOptions::new()
+            .with_capture_dir(temp_dir("cap"))
+            .with_capture_max_bytes(8);
+        let sse_path = capture_chunks(&options, vec!["data: 12345", "67890\n\n"]).await;
+
+        assert_eq!(std::fs::read(&sse_path).unwrap(), b"data: 12");
+
+        let _ = std::fs::remove_dir_all(options.capture_dir.unwrap());
+    }
+}
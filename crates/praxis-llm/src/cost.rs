@@ -0,0 +1,210 @@
+//! What LLM calls cost, from their token usage
+//!
+//! `CostCalculator` prices a call's `TokenUsage` with the entry of the
+//! longest model-name prefix in its table, so `gpt-4o-mini-2024-07-18`
+//! takes the `gpt-4o-mini` price rather than `gpt-4o`'s. The built-in
+//! table holds list prices in USD per 1M tokens; providers change them, so
+//! deployments with negotiated rates or newer models extend or override it.
+
+use crate::traits::TokenUsage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// USD per 1M tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    /// Reasoning tokens, when not billed as output (default: `output`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<f64>,
+}
+
+impl ModelPrice {
+    pub const fn new(input: f64, output: f64) -> Self {
+        Self { input, output, reasoning: None }
+    }
+}
+
+/// Cost of one call (or a sum of calls) in USD
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostBreakdown {
+    pub input_usd: f64,
+    /// Output tokens other than reasoning ones
+    pub output_usd: f64,
+    pub reasoning_usd: f64,
+    pub total_usd: f64,
+}
+
+impl std::ops::AddAssign for CostBreakdown {
+    fn add_assign(&mut self, other: Self) {
+        self.input_usd += other.input_usd;
+        self.output_usd += other.output_usd;
+        self.reasoning_usd += other.reasoning_usd;
+        self.total_usd += other.total_usd;
+    }
+}
+
+/// List prices when this table was last updated, by model-name prefix
+const BUILT_IN_PRICES: &[(&str, ModelPrice)] = &[
+    ("gpt-3.5-turbo", ModelPrice::new(0.50, 1.50)),
+    ("gpt-4", ModelPrice::new(30.0, 60.0)),
+    ("gpt-4-turbo", ModelPrice::new(10.0, 30.0)),
+    ("gpt-4o", ModelPrice::new(2.50, 10.0)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.60)),
+    ("gpt-4.1", ModelPrice::new(2.0, 8.0)),
+    ("gpt-4.1-mini", ModelPrice::new(0.40, 1.60)),
+    ("gpt-4.1-nano", ModelPrice::new(0.10, 0.40)),
+    ("gpt-5", ModelPrice::new(1.25, 10.0)),
+    ("gpt-5-mini", ModelPrice::new(0.25, 2.0)),
+    ("gpt-5-nano", ModelPrice::new(0.05, 0.40)),
+    ("o1", ModelPrice::new(15.0, 60.0)),
+    ("o1-mini", ModelPrice::new(1.10, 4.40)),
+    ("o3", ModelPrice::new(2.0, 8.0)),
+    ("o3-mini", ModelPrice::new(1.10, 4.40)),
+    ("o4-mini", ModelPrice::new(1.10, 4.40)),
+    ("claude-3-haiku", ModelPrice::new(0.25, 1.25)),
+    ("claude-3-5-haiku", ModelPrice::new(0.80, 4.0)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-opus", ModelPrice::new(15.0, 75.0)),
+    ("claude-haiku-4", ModelPrice::new(1.0, 5.0)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0)),
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0)),
+    ("gemini-1.5-flash", ModelPrice::new(0.075, 0.30)),
+    ("gemini-1.5-pro", ModelPrice::new(1.25, 5.0)),
+    ("gemini-2.0-flash", ModelPrice::new(0.10, 0.40)),
+    ("gemini-2.5-flash", ModelPrice::new(0.30, 2.50)),
+    ("gemini-2.5-pro", ModelPrice::new(1.25, 10.0)),
+];
+
+/// Prices calls by model name
+///
+/// ```rust
+/// use praxis_llm::{CostCalculator, ModelPrice};
+///
+/// // A negotiated rate, and a deployment the built-in table doesn't know
+/// let calculator = CostCalculator::new()
+///     .with_price("gpt-4o", ModelPrice::new(2.0, 8.0))
+///     .with_price("acme-gpt", ModelPrice::new(1.0, 4.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostCalculator {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl Default for CostCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CostCalculator {
+    /// The built-in price table
+    pub fn new() -> Self {
+        Self {
+            prices: BUILT_IN_PRICES.iter().map(|(prefix, price)| (prefix.to_string(), *price)).collect(),
+        }
+    }
+
+    /// No prices at all, for tables loaded entirely from config
+    pub fn empty() -> Self {
+        Self { prices: HashMap::new() }
+    }
+
+    /// Price models starting with `prefix` at `price`, replacing any entry for it
+    pub fn with_price(mut self, prefix: impl Into<String>, price: ModelPrice) -> Self {
+        self.prices.insert(prefix.into(), price);
+        self
+    }
+
+    /// Add or replace entries, e.g. a `[pricing.models]` table from TOML
+    pub fn with_prices(mut self, prices: impl IntoIterator<Item = (String, ModelPrice)>) -> Self {
+        self.prices.extend(prices);
+        self
+    }
+
+    /// The price of the longest prefix of `model` in the table
+    pub fn price(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| price)
+    }
+
+    /// What a call to `model` that used `usage` cost (None for unpriced models)
+    ///
+    /// Reasoning tokens are counted within `output_tokens`, as providers
+    /// report them, and priced at the reasoning rate.
+    pub fn cost(&self, model: &str, usage: &TokenUsage) -> Option<CostBreakdown> {
+        let price = self.price(model)?;
+        let per_token = |tokens: u32, per_million: f64| tokens as f64 * per_million / 1_000_000.0;
+        let reasoning_tokens = usage.reasoning_tokens.unwrap_or(0).min(usage.output_tokens);
+
+        let input_usd = per_token(usage.input_tokens, price.input);
+        let output_usd = per_token(usage.output_tokens - reasoning_tokens, price.output);
+        let reasoning_usd = per_token(reasoning_tokens, price.reasoning.unwrap_or(price.output));
+        Some(CostBreakdown {
+            input_usd,
+            output_usd,
+            reasoning_usd,
+            total_usd: input_usd + output_usd + reasoning_usd,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input_tokens: u32, output_tokens: u32, reasoning_tokens: Option<u32>) -> TokenUsage {
+        TokenUsage { input_tokens, output_tokens, total_tokens: input_tokens + output_tokens, reasoning_tokens, cached_tokens: None }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-12, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_longest_prefix_prices_the_call() {
+        let calculator = CostCalculator::new();
+
+        let mini = calculator.cost("gpt-4o-mini-2024-07-18", &usage(1_000_000, 100_000, None)).unwrap();
+        assert_close(mini.input_usd, 0.15);
+        assert_close(mini.output_usd, 0.06);
+        assert_close(mini.total_usd, 0.21);
+
+        let full = calculator.cost("gpt-4o-2024-08-06", &usage(1_000_000, 0, None)).unwrap();
+        assert_close(full.total_usd, 2.50);
+
+        assert_eq!(calculator.cost("llama3.2", &usage(1000, 1000, None)), None);
+    }
+
+    #[test]
+    fn test_reasoning_tokens_priced_within_output() {
+        let calculator = CostCalculator::empty().with_price(
+            "thinker",
+            ModelPrice { input: 1.0, output: 10.0, reasoning: Some(20.0) },
+        );
+
+        let cost = calculator.cost("thinker", &usage(0, 1_000_000, Some(250_000))).unwrap();
+        assert_close(cost.output_usd, 7.5);
+        assert_close(cost.reasoning_usd, 5.0);
+        assert_close(cost.total_usd, 12.5);
+    }
+
+    #[test]
+    fn test_config_prices_override_and_extend() {
+        let prices: HashMap<String, ModelPrice> = serde_json::from_value(serde_json::json!({
+            "gpt-4o": { "input": 2.0, "output": 8.0 },
+            "acme-gpt": { "input": 1.0, "output": 4.0 },
+        }))
+        .unwrap();
+        let calculator = CostCalculator::new().with_prices(prices);
+
+        assert_eq!(calculator.price("gpt-4o-2024-08-06"), Some(&ModelPrice::new(2.0, 8.0)));
+        assert_eq!(calculator.price("acme-gpt-large"), Some(&ModelPrice::new(1.0, 4.0)));
+        assert_eq!(calculator.price("gpt-4o-mini"), Some(&ModelPrice::new(0.15, 0.60)));
+    }
+}
@@ -0,0 +1,319 @@
+//! Provider failures callers can act on
+//!
+//! These travel inside `anyhow::Error`; recover them with `downcast_ref`, or
+//! with `LLMError::of` when context may have been added on the way up.
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::Duration;
+
+/// A failed provider call, classified by what the caller can do about it
+#[derive(Debug)]
+pub enum LLMError {
+    /// HTTP 429, or the provider's "overloaded" status
+    RateLimited(RateLimited),
+    /// The credentials were rejected (HTTP 401/403)
+    AuthFailed { message: String },
+    /// The prompt doesn't fit the model's context window
+    ///
+    /// The token counts are read from the provider's message, when it gives them.
+    ContextLengthExceeded { max: Option<u32>, requested: Option<u32>, message: String },
+    /// The provider rejected the request itself (HTTP 400/404/422)
+    InvalidRequest { message: String },
+    /// Any other non-success status
+    Api { status: u16, message: String },
+    /// No response: connection, TLS or body read failure
+    Transport(reqwest::Error),
+    /// A response body that isn't the JSON we expect
+    Parse(serde_json::Error),
+    /// The request failed `ChatRequest::validate` (or
+    /// `ResponseRequest::validate`) and was never sent
+    Validation(ValidationError),
+}
+
+impl LLMError {
+    /// The `LLMError` in `error`'s chain, if any
+    pub fn of(error: &anyhow::Error) -> Option<&LLMError> {
+        error.chain().find_map(|cause| cause.downcast_ref::<LLMError>())
+    }
+
+    /// Stable machine-readable code
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::RateLimited(_) => "rate_limited",
+            Self::AuthFailed { .. } => "auth_failed",
+            Self::ContextLengthExceeded { .. } => "context_length_exceeded",
+            Self::InvalidRequest { .. } => "invalid_request",
+            Self::Api { .. } => "api_error",
+            Self::Transport(_) => "transport_error",
+            Self::Parse(_) => "parse_error",
+            Self::Validation(_) => "validation_failed",
+        }
+    }
+
+    /// Whether sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited(_) | Self::Transport(_) => true,
+            Self::Api { status, .. } => *status >= 500,
+            Self::AuthFailed { .. }
+            | Self::ContextLengthExceeded { .. }
+            | Self::InvalidRequest { .. }
+            | Self::Parse(_)
+            | Self::Validation(_) => false,
+        }
+    }
+
+    /// Classify a non-success response by its status
+    pub fn from_status(status: StatusCode, headers: &HeaderMap, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match status.as_u16() {
+            429 => Self::RateLimited(RateLimited::from_headers(headers, message)),
+            401 | 403 => Self::AuthFailed { message },
+            400 | 404 | 422 => Self::InvalidRequest { message },
+            status => Self::Api { status, message },
+        }
+    }
+
+    /// Classify an OpenAI (or Azure OpenAI) error response
+    ///
+    /// Both send `{"error": {"message": ..., "code": ...}}`; a body that
+    /// isn't shaped like that is kept as the message. The response's
+    /// `x-request-id` is appended, for reporting the failure to the provider.
+    pub fn from_openai_response(status: StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        let error = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|json| json.get("error").cloned())
+            .unwrap_or_default();
+        let mut message = error["message"].as_str().unwrap_or(body).to_string();
+        if let Some(request_id) = headers.get("x-request-id").and_then(|value| value.to_str().ok()) {
+            message = format!("{} (request id {})", message, request_id);
+        }
+        let context_length = error["code"] == "context_length_exceeded"
+            || (status == StatusCode::BAD_REQUEST && message.contains("maximum context length"));
+        if context_length {
+            return Self::ContextLengthExceeded {
+                max: number_after(&message, "maximum context length is "),
+                requested: number_after(&message, "resulted in ").or_else(|| number_after(&message, "you requested ")),
+                message,
+            };
+        }
+        Self::from_status(status, headers, message)
+    }
+
+    /// Wrap a reqwest failure in `error` as `Transport`; anything else (e.g. `TimedOut`) is kept
+    pub(crate) fn transport(error: anyhow::Error) -> anyhow::Error {
+        match error.downcast::<reqwest::Error>() {
+            Ok(error) => Self::Transport(error).into(),
+            Err(error) => error,
+        }
+    }
+}
+
+/// The number that follows `prefix` in `text`
+fn number_after(text: &str, prefix: &str) -> Option<u32> {
+    let rest = &text[text.find(prefix)? + prefix.len()..];
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+impl fmt::Display for LLMError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RateLimited(limited) => limited.fmt(f),
+            Self::AuthFailed { message } => write!(f, "Provider rejected the credentials: {}", message),
+            Self::ContextLengthExceeded { message, .. } => {
+                write!(f, "Prompt exceeds the model's context window: {}", message)
+            }
+            Self::InvalidRequest { message } => write!(f, "Provider rejected the request: {}", message),
+            Self::Api { status, message } => write!(f, "Provider API error ({}): {}", status, message),
+            Self::Transport(error) => write!(f, "Provider request failed: {}", error),
+            Self::Parse(error) => write!(f, "Failed to parse provider response: {}", error),
+            Self::Validation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LLMError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::RateLimited(limited) => Some(limited),
+            Self::Transport(error) => Some(error),
+            Self::Parse(error) => Some(error),
+            Self::Validation(error) => Some(error),
+            Self::AuthFailed { .. } | Self::ContextLengthExceeded { .. } | Self::InvalidRequest { .. } | Self::Api { .. } => {
+                None
+            }
+        }
+    }
+}
+
+impl From<RateLimited> for LLMError {
+    fn from(limited: RateLimited) -> Self {
+        Self::RateLimited(limited)
+    }
+}
+
+impl From<ValidationError> for LLMError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+/// The provider rejected the request for exceeding its rate limit (HTTP 429)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimited {
+    /// How long the provider asked us to wait, when it said
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+impl RateLimited {
+    /// The rate limit in `error`'s chain, bare or inside an `LLMError`
+    pub fn of(error: &anyhow::Error) -> Option<&RateLimited> {
+        error.chain().find_map(|cause| cause.downcast_ref::<RateLimited>())
+    }
+
+    /// Read the wait from `retry-after-ms` or `retry-after` (whole seconds)
+    pub fn from_headers(headers: &HeaderMap, message: impl Into<String>) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let retry_after = header("retry-after-ms")
+            .and_then(|ms| ms.trim().parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .or_else(|| {
+                header("retry-after")
+                    .and_then(|secs| secs.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs)
+            });
+        Self {
+            retry_after,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rate limited by provider: {}", self.message)
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// A request a provider would reject, caught before it was sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Path of the offending field, e.g. `options.temperature` or `messages[2]`
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid request: {} {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// The provider went quiet: no response, or a stream stalled between events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedOut {
+    pub after: Duration,
+    /// What was still outstanding, e.g. "a response"
+    pub waiting_for: &'static str,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Provider timed out after {:?} waiting for {}", self.after, self.waiting_for)
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_retry_after_prefers_milliseconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("20"));
+        assert_eq!(RateLimited::from_headers(&headers, "").retry_after, Some(Duration::from_secs(20)));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("1500"));
+        assert_eq!(RateLimited::from_headers(&headers, "").retry_after, Some(Duration::from_millis(1500)));
+
+        // HTTP-date form isn't used by OpenAI; treated as unknown
+        let mut dated = HeaderMap::new();
+        dated.insert("retry-after", HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(RateLimited::from_headers(&dated, "").retry_after, None);
+    }
+
+    #[test]
+    fn test_openai_context_length_error_reads_the_token_counts() {
+        let body = r#"{"error": {"message": "This model's maximum context length is 128000 tokens. However, your messages resulted in 130512 tokens. Please reduce the length of the messages.", "type": "invalid_request_error", "param": "messages", "code": "context_length_exceeded"}}"#;
+        let error = LLMError::from_openai_response(StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            LLMError::ContextLengthExceeded { max: Some(128000), requested: Some(130512), .. }
+        ));
+        assert_eq!(error.code(), "context_length_exceeded");
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_openai_and_azure_errors_classified_by_status() {
+        let headers = HeaderMap::new();
+        let auth = LLMError::from_openai_response(
+            StatusCode::UNAUTHORIZED,
+            &headers,
+            r#"{"error": {"message": "Incorrect API key provided", "code": "invalid_api_key"}}"#,
+        );
+        assert!(matches!(&auth, LLMError::AuthFailed { message } if message == "Incorrect API key provided"));
+
+        // Azure's rate limit body carries the status as its code
+        let limited = LLMError::from_openai_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            r#"{"error": {"code": "429", "message": "Requests have exceeded the call rate limit"}}"#,
+        );
+        assert!(matches!(limited, LLMError::RateLimited(RateLimited { ref message, .. }) if message.contains("call rate limit")));
+
+        let upstream = LLMError::from_openai_response(StatusCode::BAD_GATEWAY, &headers, "<html>bad gateway</html>");
+        assert!(matches!(&upstream, LLMError::Api { status: 502, message } if message == "<html>bad gateway</html>"));
+        assert!(upstream.is_retryable());
+    }
+
+    #[test]
+    fn test_openai_errors_name_the_request_id() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("req_abc123"));
+        let error = LLMError::from_openai_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &headers,
+            r#"{"error": {"message": "The server had an error"}}"#,
+        );
+        assert_eq!(error.to_string(), "Provider API error (500): The server had an error (request id req_abc123)");
+    }
+
+    #[test]
+    fn test_found_through_context() {
+        let error = anyhow::Error::from(LLMError::InvalidRequest { message: "bad tool schema".to_string() })
+            .context("LLM call failed");
+        assert_eq!(LLMError::of(&error).map(LLMError::code), Some("invalid_request"));
+        assert!(LLMError::of(&anyhow::anyhow!("boom")).is_none());
+    }
+}
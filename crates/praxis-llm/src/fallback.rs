@@ -0,0 +1,282 @@
+//! Failover across providers
+//!
+//! `FallbackClient` sends each request to its providers in order, moving on
+//! only when a provider fails in a way another might not: a rate limit, a
+//! 5xx, a transport failure or a timeout. Anything else (a bad request,
+//! rejected credentials) is returned as is, since the next provider would
+//! most likely fail the same way.
+
+use crate::error::{LLMError, TimedOut};
+use crate::stats::RateLimitHeadroom;
+use crate::streaming::StreamEvent;
+use crate::traits::{ChatClient, ChatRequest, ChatResponse, LLMClient, ReasoningClient, ResponseOutput, ResponseRequest};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// One provider of a `FallbackClient`
+pub struct FallbackProvider {
+    name: String,
+    client: Arc<dyn LLMClient>,
+    /// Request model name -> this provider's name for it
+    models: HashMap<String, String>,
+}
+
+impl FallbackProvider {
+    pub fn new(name: impl Into<String>, client: Arc<dyn LLMClient>) -> Self {
+        Self {
+            name: name.into(),
+            client,
+            models: HashMap::new(),
+        }
+    }
+
+    /// Call `model` by another name on this provider (e.g. an Azure
+    /// deployment); unmapped models keep the request's name
+    pub fn with_model(mut self, model: impl Into<String>, provider_model: impl Into<String>) -> Self {
+        self.models.insert(model.into(), provider_model.into());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn model(&self, model: &str) -> String {
+        self.models.get(model).cloned().unwrap_or_else(|| model.to_string())
+    }
+}
+
+/// Client trying each provider in order until one serves the request
+///
+/// Responses name the provider that served them in `served_by`. A stream
+/// falls back only if it fails before its first event: after that the
+/// caller has seen part of an answer, so the error is passed on.
+pub struct FallbackClient {
+    providers: Vec<FallbackProvider>,
+}
+
+impl FallbackClient {
+    /// `providers` in the order to try them; the first is the primary
+    pub fn new(providers: Vec<FallbackProvider>) -> Self {
+        Self { providers }
+    }
+
+    pub fn providers(&self) -> &[FallbackProvider] {
+        &self.providers
+    }
+
+    /// Send `call` to each provider's client in turn, with the model it should use
+    async fn try_each<T, F, Fut>(&self, model: &str, call: F) -> Result<(T, &str)>
+    where
+        F: Fn(Arc<dyn LLMClient>, String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut failures = Vec::new();
+        for (i, provider) in self.providers.iter().enumerate() {
+            match call(Arc::clone(&provider.client), provider.model(model)).await {
+                Ok(value) => return Ok((value, provider.name.as_str())),
+                Err(error) if i + 1 < self.providers.len() && should_fall_back(&error) => {
+                    tracing::warn!("Provider {} failed, falling back: {:#}", provider.name, error);
+                    failures.push(provider.name.as_str());
+                }
+                Err(error) if failures.is_empty() => return Err(error),
+                Err(error) => {
+                    return Err(error.context(format!("{} failed after {} failed", provider.name, failures.join(", "))))
+                }
+            }
+        }
+        anyhow::bail!("FallbackClient has no providers")
+    }
+}
+
+/// Whether another provider might serve a request that failed with `error`
+fn should_fall_back(error: &anyhow::Error) -> bool {
+    LLMError::of(error).is_some_and(LLMError::is_retryable)
+        || error.chain().any(|cause| cause.is::<TimedOut>())
+}
+
+/// Open `stream` and wait for its first event, so a stream that fails
+/// before producing anything counts as a failed call
+async fn first_event(opened: Result<EventStream>) -> Result<EventStream> {
+    let mut stream = opened?;
+    match stream.next().await {
+        Some(Err(error)) => Err(error),
+        Some(Ok(event)) => Ok(Box::pin(futures::stream::once(async { Ok(event) }).chain(stream))),
+        None => Ok(Box::pin(futures::stream::empty())),
+    }
+}
+
+#[async_trait]
+impl ChatClient for FallbackClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let (mut response, served_by) = self
+            .try_each(&request.model, |client, model| {
+                let request = ChatRequest { model, ..request.clone() };
+                async move { client.chat(request).await }
+            })
+            .await?;
+        response.served_by = Some(served_by.to_string());
+        Ok(response)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let (stream, _) = self
+            .try_each(&request.model, |client, model| {
+                let request = ChatRequest { model, ..request.clone() };
+                async move { first_event(client.chat_stream(request).await).await }
+            })
+            .await?;
+        Ok(stream)
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for FallbackClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        let (mut output, served_by) = self
+            .try_each(&request.model, |client, model| {
+                let request = ResponseRequest { model, ..request.clone() };
+                async move { client.reason(request).await }
+            })
+            .await?;
+        output.served_by = Some(served_by.to_string());
+        Ok(output)
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        let (stream, _) = self
+            .try_each(&request.model, |client, model| {
+                let request = ResponseRequest { model, ..request.clone() };
+                async move { first_event(client.reason_stream(request).await).await }
+            })
+            .await?;
+        Ok(stream)
+    }
+}
+
+impl LLMClient for FallbackClient {
+    /// The primary's headroom
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        self.providers.first().and_then(|provider| provider.client.rate_limit_headroom())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::MockLLMClient;
+    use crate::Message;
+
+    fn unavailable() -> LLMError {
+        LLMError::Api { status: 503, message: "overloaded".to_string() }
+    }
+
+    fn request() -> ChatRequest {
+        ChatRequest::new("gpt-4o", vec![Message::human("hi")])
+    }
+
+    async fn collect(stream: EventStream) -> Vec<String> {
+        stream
+            .map(|event| match event {
+                Ok(StreamEvent::Message { content, .. }) => content,
+                Ok(other) => format!("{:?}", other),
+                Err(error) => format!("error: {}", error),
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_retryable_failures_fall_back_with_mapped_model() {
+        let primary = Arc::new(MockLLMClient::new().then_llm_error(unavailable()).then_llm_error(unavailable()));
+        let secondary = Arc::new(MockLLMClient::new().then_message("from azure").then_message("streamed"));
+        let client = FallbackClient::new(vec![
+            FallbackProvider::new("openai", primary.clone()),
+            FallbackProvider::new("azure", secondary.clone()).with_model("gpt-4o", "gpt-4o-prod"),
+        ]);
+
+        let response = client.chat(request()).await.unwrap();
+        assert_eq!(response.content.as_deref(), Some("from azure"));
+        assert_eq!(response.served_by.as_deref(), Some("azure"));
+
+        let events = collect(client.chat_stream(request()).await.unwrap()).await;
+        assert_eq!(events[0], "streamed");
+
+        assert_eq!(primary.chat_requests()[0].model, "gpt-4o");
+        assert_eq!(secondary.chat_requests().iter().map(|r| r.model.as_str()).collect::<Vec<_>>(), ["gpt-4o-prod"; 2]);
+    }
+
+    #[tokio::test]
+    async fn test_other_failures_are_returned() {
+        let primary = Arc::new(MockLLMClient::new().then_llm_error(LLMError::AuthFailed { message: "bad key".to_string() }));
+        let secondary = Arc::new(MockLLMClient::new().then_message("unused"));
+        let client = FallbackClient::new(vec![
+            FallbackProvider::new("openai", primary),
+            FallbackProvider::new("azure", secondary.clone()),
+        ]);
+
+        let error = client.chat(request()).await.unwrap_err();
+        assert_eq!(LLMError::of(&error).map(LLMError::code), Some("auth_failed"));
+        assert_eq!(secondary.remaining(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_last_error_names_the_providers_that_failed() {
+        let client = FallbackClient::new(vec![
+            FallbackProvider::new("openai", Arc::new(MockLLMClient::new().then_llm_error(unavailable()))),
+            FallbackProvider::new("azure", Arc::new(MockLLMClient::new().then_llm_error(unavailable()))),
+        ]);
+
+        let error = client.chat(request()).await.unwrap_err();
+        assert_eq!(error.to_string(), "azure failed after openai failed");
+        assert!(LLMError::of(&error).is_some_and(LLMError::is_retryable));
+    }
+
+    /// Streams one message, then fails
+    struct BreaksMidStream;
+
+    #[async_trait]
+    impl ChatClient for BreaksMidStream {
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+            anyhow::bail!("unused")
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<EventStream> {
+            let events = vec![Ok(StreamEvent::Message { content: "Hel".to_string(), choice_index: None, logprobs: None }), Err(unavailable().into())];
+            Ok(Box::pin(futures::stream::iter(events)))
+        }
+    }
+
+    #[async_trait]
+    impl ReasoningClient for BreaksMidStream {
+        async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+            anyhow::bail!("unused")
+        }
+
+        async fn reason_stream(&self, _request: ResponseRequest) -> Result<EventStream> {
+            anyhow::bail!("unused")
+        }
+    }
+
+    impl LLMClient for BreaksMidStream {}
+
+    #[tokio::test]
+    async fn test_stream_failing_after_an_event_does_not_fall_back() {
+        let secondary = Arc::new(MockLLMClient::new().then_message("unused"));
+        let client = FallbackClient::new(vec![
+            FallbackProvider::new("openai", Arc::new(BreaksMidStream)),
+            FallbackProvider::new("azure", secondary.clone()),
+        ]);
+
+        let events = collect(client.chat_stream(request()).await.unwrap()).await;
+        assert_eq!(events, ["Hel", "error: Provider API error (503): overloaded"]);
+        assert_eq!(secondary.remaining(), 1);
+    }
+}
@@ -0,0 +1,639 @@
+// Google Gemini API client
+
+use crate::buffer_utils::{parse_sse_byte_stream, parse_sse_stream};
+use crate::capabilities::{ModelCapabilities, OptionAdjusted};
+use crate::capture::{redact_headers, Capture};
+use crate::error::{LLMError, RateLimited};
+use crate::gemini::streaming::{finish_reason, tool_call_id, GeminiSseParser};
+use crate::gemini::GeminiConfig;
+use crate::http::{client_or_build, send_within, with_idle_timeout, HttpOptions, DEFAULT_CONNECT_TIMEOUT};
+use crate::openai::responses::{ContentItem, OutputItem, SummaryText, Usage};
+use crate::openai::{ReasoningConfig, ReasoningEffort, ResponsesResponse};
+use crate::streaming::StreamEvent;
+use crate::traits::{
+    ChatClient, ChatOptions, ChatRequest, ChatResponse, ChoiceOutput, LLMClient, ReasoningClient,
+    ResponseOptions, ResponseOutput, ResponseRequest, TokenUsage, merge_extra_body,
+};
+use crate::types::{Content, ContentPart, FunctionCall, Message, Tool, ToolCall, ToolChoice};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+
+const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com";
+const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-goog-api-key");
+
+/// Google Gemini client (HTTP direct, no SDK)
+///
+/// Chat requests go to `generateContent` / `streamGenerateContent`. The
+/// reasoning methods send the same request with a thinking budget taken
+/// from the `ReasoningConfig` effort and thoughts included in the output.
+pub struct GeminiClient {
+    http_client: reqwest::Client,
+    /// Sent with each request, so a shared `http_client` carries no credentials
+    headers: HeaderMap,
+    base_url: String,
+    http_options: HttpOptions,
+    /// Default headers with credentials redacted, written to payload captures
+    capture_headers: BTreeMap<String, String>,
+}
+
+impl GeminiClient {
+    /// Create new client with API key
+    ///
+    /// Payload capture is enabled when `PRAXIS_CAPTURE_DIR` is set (see `crate::capture`).
+    pub fn new(api_key: impl Into<String>) -> Result<Self> {
+        Self::from_config(GeminiConfig::new(api_key))
+    }
+
+    pub fn from_config(config: GeminiConfig) -> Result<Self> {
+        let headers = Self::default_headers(&config)?;
+
+        let capture_headers = redact_headers(&headers);
+        let http_client = client_or_build(config.http_client.as_ref(), &config.connection, DEFAULT_CONNECT_TIMEOUT)?;
+
+        Ok(Self {
+            http_client,
+            headers,
+            base_url: format!("{}/{}", GEMINI_API_BASE, config.api_version),
+            http_options: HttpOptions::from_env(),
+            capture_headers,
+        })
+    }
+
+    /// Headers sent with every request
+    fn default_headers(config: &GeminiConfig) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(
+            API_KEY_HEADER,
+            HeaderValue::from_str(&config.api_key).context("Invalid API key format")?,
+        );
+        Ok(headers)
+    }
+
+    /// Override HTTP options (e.g. enable payload capture programmatically)
+    pub fn with_http_options(mut self, options: HttpOptions) -> Self {
+        self.http_options = options;
+        self
+    }
+
+    /// Start a payload capture for this request (None when capture is disabled)
+    fn start_capture(&self, url: &str, payload: &Value) -> Option<Capture> {
+        Capture::start(&self.http_options, "generate_content", url, &self.capture_headers, payload)
+    }
+
+    fn url(&self, model: &str, stream: bool) -> String {
+        if stream {
+            format!("{}/models/{}:streamGenerateContent?alt=sse", self.base_url, model)
+        } else {
+            format!("{}/models/{}:generateContent", self.base_url, model)
+        }
+    }
+
+    /// Build a `generateContent` payload, with the options the model didn't accept
+    ///
+    /// Gemini has no end-user field, so `end_user_id` is not sent.
+    fn build_chat_request(&self, model: &str, messages: Vec<Message>, options: &ChatOptions) -> Result<(Value, Vec<OptionAdjusted>)> {
+        let (options, mut adjusted) = ModelCapabilities::for_model(model).adjust_chat_options(model, options)?;
+        // Only the first candidate is read, so more are never asked for
+        if options.n.is_some_and(|n| n > 1) {
+            if options.strict_options {
+                anyhow::bail!("Options not supported by {}: n", model);
+            }
+            adjusted.push(OptionAdjusted {
+                option: "n".to_string(),
+                reason: format!("removed: {} is called for one candidate", model),
+            });
+        }
+        // Token log probabilities are not read from Gemini responses
+        if options.wants_logprobs() {
+            if options.strict_options {
+                anyhow::bail!("Options not supported by {}: logprobs", model);
+            }
+            adjusted.push(OptionAdjusted {
+                option: "logprobs".to_string(),
+                reason: format!("removed: {} is called without log probabilities", model),
+            });
+        }
+        if options.parallel_tool_calls == Some(false) && options.tools.as_ref().is_some_and(|tools| !tools.is_empty()) {
+            if options.strict_options {
+                anyhow::bail!("Options not supported by {}: parallel_tool_calls", model);
+            }
+            adjusted.push(OptionAdjusted {
+                option: "parallel_tool_calls".to_string(),
+                reason: format!("removed: {} cannot be limited to one function call per turn", model),
+            });
+        }
+
+        let mut request = base_request(messages)?;
+        let obj = request.as_object_mut().unwrap();
+
+        if let Some(tools) = options.tools.as_ref().filter(|tools| !tools.is_empty()) {
+            let declarations: Vec<Value> = tools.iter().map(convert_tool).collect();
+            obj.insert("tools".to_string(), json!([{ "functionDeclarations": declarations }]));
+            if let Some(tool_choice) = &options.tool_choice {
+                obj.insert("toolConfig".to_string(), json!({ "functionCallingConfig": convert_tool_choice(tool_choice) }));
+            }
+        }
+        let mut generation = generation_config(options.temperature, options.max_tokens);
+        if let Some(stop) = &options.stop {
+            generation.insert("stopSequences".to_string(), json!(stop));
+        }
+        if let Some(top_p) = options.top_p {
+            generation.insert("topP".to_string(), json!(top_p));
+        }
+        if let Some(seed) = options.seed {
+            generation.insert("seed".to_string(), json!(seed));
+        }
+        if let Some(penalty) = options.presence_penalty {
+            generation.insert("presencePenalty".to_string(), json!(penalty));
+        }
+        if let Some(penalty) = options.frequency_penalty {
+            generation.insert("frequencyPenalty".to_string(), json!(penalty));
+        }
+        if !generation.is_empty() {
+            obj.insert("generationConfig".to_string(), Value::Object(generation));
+        }
+        merge_extra_body(obj, options.extra_body.as_ref());
+
+        Ok((request, adjusted))
+    }
+
+    /// Build a `generateContent` payload with thinking enabled
+    fn build_thinking_request(
+        &self,
+        input: Vec<Message>,
+        reasoning: Option<&ReasoningConfig>,
+        options: &ResponseOptions,
+    ) -> Result<Value> {
+        let mut request = base_request(input)?;
+        let mut generation = generation_config(options.temperature, options.max_output_tokens);
+        let budget = thinking_budget(reasoning.map_or(&ReasoningEffort::Medium, |r| &r.effort));
+        generation.insert("thinkingConfig".to_string(), json!({ "thinkingBudget": budget, "includeThoughts": true }));
+        let obj = request.as_object_mut().unwrap();
+        obj.insert("generationConfig".to_string(), Value::Object(generation));
+        merge_extra_body(obj, options.extra_body.as_ref());
+        Ok(request)
+    }
+
+    async fn send(&self, url: &str, payload: &Value) -> Result<reqwest::Response> {
+        let response = send_within(self.http_client.post(url).headers(self.headers.clone()).json(payload), self.http_options.timeout).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(response)
+    }
+
+    async fn generate(&self, model: &str, payload: &Value) -> Result<Value> {
+        let response = self.send(&self.url(model, false), payload).await?;
+        response.json().await.context("Failed to parse response")
+    }
+
+    async fn send_stream(
+        &self,
+        model: &str,
+        payload: Value,
+        adjustments: Vec<OptionAdjusted>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        let url = self.url(model, true);
+        let capture = self.start_capture(&url, &payload);
+        let response = self.send(&url, &payload).await?;
+
+        let stream = match capture {
+            Some(capture) => parse_sse_byte_stream(capture.tee(response.bytes_stream()), GeminiSseParser::default()),
+            None => parse_sse_stream(response, GeminiSseParser::default()),
+        };
+        let stream = with_idle_timeout(stream, self.http_options.stream_idle_timeout);
+        if adjustments.is_empty() {
+            return Ok(stream);
+        }
+        Ok(Box::pin(futures::stream::once(async { Ok(StreamEvent::OptionsAdjusted { adjustments }) }).chain(stream)))
+    }
+}
+
+fn base_request(messages: Vec<Message>) -> Result<Value> {
+    let (system, contents) = convert_messages(messages)?;
+    let mut request = json!({ "contents": contents });
+    if let Some(system) = system {
+        request
+            .as_object_mut()
+            .unwrap()
+            .insert("systemInstruction".to_string(), json!({ "parts": [{ "text": system }] }));
+    }
+    Ok(request)
+}
+
+fn generation_config(temperature: Option<f32>, max_tokens: Option<u32>) -> Map<String, Value> {
+    let mut config = Map::new();
+    if let Some(temp) = temperature {
+        config.insert("temperature".to_string(), json!(temp));
+    }
+    if let Some(max_tokens) = max_tokens {
+        config.insert("maxOutputTokens".to_string(), json!(max_tokens));
+    }
+    config
+}
+
+/// Split out the system prompt and convert the rest to Gemini `contents`
+///
+/// Developer messages join the system instruction. Assistant turns have
+/// the `model` role and tool results are
+/// `functionResponse` parts of a user turn. A function response names the
+/// function rather than the call, so names are looked up from the earlier
+/// tool calls. Adjacent turns of one role are merged, which also keeps the
+/// responses to parallel calls in a single turn as Gemini requires.
+fn convert_messages(messages: Vec<Message>) -> Result<(Option<String>, Vec<Value>)> {
+    let mut system = Vec::new();
+    let mut call_names: HashMap<String, String> = HashMap::new();
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+
+    for message in messages {
+        let (role, parts) = match message {
+            Message::System { content, .. } | Message::Developer { content, .. } => {
+                system.extend(content_texts(content));
+                continue;
+            }
+            Message::Human { content, .. } => ("user", text_parts(content)),
+            Message::AI { content, tool_calls, .. } => {
+                let mut parts = content.map(text_parts).unwrap_or_default();
+                for call in tool_calls.unwrap_or_default() {
+                    call_names.insert(call.id.clone(), call.function.name.clone());
+                    parts.push(function_call_part(call)?);
+                }
+                ("model", parts)
+            }
+            Message::Tool { tool_call_id, content } => {
+                let name = call_names
+                    .get(&tool_call_id)
+                    .with_context(|| format!("Tool result {} has no matching tool call", tool_call_id))?;
+                let result = content_texts(content).join("\n");
+                // `response` must be an object; other results are wrapped
+                let response = match serde_json::from_str::<Value>(&result) {
+                    Ok(object @ Value::Object(_)) => object,
+                    _ => json!({ "content": result }),
+                };
+                ("user", vec![json!({ "functionResponse": { "name": name, "response": response } })])
+            }
+        };
+        if parts.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((last_role, last_parts)) if *last_role == role => last_parts.extend(parts),
+            _ => turns.push((role, parts)),
+        }
+    }
+
+    let system = Some(system.join("\n\n")).filter(|s| !s.is_empty());
+    let contents = turns
+        .into_iter()
+        .map(|(role, parts)| json!({ "role": role, "parts": parts }))
+        .collect();
+    Ok((system, contents))
+}
+
+/// Text parts only; images and audio aren't converted for Gemini yet
+fn content_texts(content: Content) -> Vec<String> {
+    match content {
+        Content::Text(text) => vec![text],
+        Content::Parts(parts) => parts
+            .into_iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text),
+                ContentPart::ImageUrl { .. } | ContentPart::ImageBase64 { .. } | ContentPart::Audio { .. } => None,
+            })
+            .collect(),
+    }
+}
+
+fn text_parts(content: Content) -> Vec<Value> {
+    content_texts(content)
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .map(|text| json!({ "text": text }))
+        .collect()
+}
+
+fn function_call_part(call: ToolCall) -> Result<Value> {
+    let args: Value = if call.function.arguments.trim().is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(&call.function.arguments)
+            .with_context(|| format!("Tool call {} has arguments that are not JSON", call.id))?
+    };
+    Ok(json!({ "functionCall": { "name": call.function.name, "args": args } }))
+}
+
+fn convert_tool(tool: &Tool) -> Value {
+    let mut declaration = json!({ "name": tool.function.name });
+    let obj = declaration.as_object_mut().unwrap();
+    if let Some(description) = &tool.function.description {
+        obj.insert("description".to_string(), json!(description));
+    }
+    // Gemini rejects object schemas without properties; omit them instead
+    let has_properties = tool.function.parameters["properties"].as_object().is_some_and(|p| !p.is_empty());
+    if has_properties {
+        obj.insert("parameters".to_string(), gemini_schema(&tool.function.parameters));
+    }
+    declaration
+}
+
+/// JSON Schema without the keywords Gemini's OpenAPI subset rejects
+fn gemini_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !matches!(key.as_str(), "$schema" | "additionalProperties"))
+                .map(|(key, value)| (key.clone(), gemini_schema(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+fn convert_tool_choice(choice: &ToolChoice) -> Value {
+    match choice {
+        ToolChoice::Auto(_) => json!({ "mode": "AUTO" }),
+        ToolChoice::None(_) => json!({ "mode": "NONE" }),
+        ToolChoice::Required(_) => json!({ "mode": "ANY" }),
+        ToolChoice::Specific { function, .. } => json!({ "mode": "ANY", "allowedFunctionNames": [function.name] }),
+    }
+}
+
+/// Thinking tokens allowed per effort level
+fn thinking_budget(effort: &ReasoningEffort) -> u32 {
+    match effort {
+        ReasoningEffort::Minimal => 512,
+        ReasoningEffort::Low => 1024,
+        ReasoningEffort::Medium => 8192,
+        ReasoningEffort::High => 24576,
+    }
+}
+
+/// Text, thoughts and function calls of a non-streaming response
+struct ParsedResponse {
+    text: Option<String>,
+    thoughts: Option<String>,
+    tool_calls: Option<Vec<ToolCall>>,
+    usage: TokenUsage,
+    finish_reason: Option<String>,
+}
+
+fn parse_response(raw: &Value) -> ParsedResponse {
+    let candidate = &raw["candidates"][0];
+    let mut text = String::new();
+    let mut thoughts = String::new();
+    let mut tool_calls = Vec::new();
+    for part in candidate["content"]["parts"].as_array().into_iter().flatten() {
+        if let Some(call) = part.get("functionCall") {
+            let id = call["id"].as_str().map_or_else(|| tool_call_id(tool_calls.len() as u32), String::from);
+            tool_calls.push(ToolCall {
+                id,
+                tool_type: "function".to_string(),
+                function: FunctionCall {
+                    name: call["name"].as_str().unwrap_or_default().to_string(),
+                    arguments: call.get("args").map_or_else(|| "{}".to_string(), Value::to_string),
+                },
+            });
+        } else if part["thought"].as_bool() == Some(true) {
+            thoughts.push_str(part["text"].as_str().unwrap_or_default());
+        } else {
+            text.push_str(part["text"].as_str().unwrap_or_default());
+        }
+    }
+
+    ParsedResponse {
+        text: Some(text).filter(|t| !t.is_empty()),
+        thoughts: Some(thoughts).filter(|t| !t.is_empty()),
+        finish_reason: candidate["finishReason"].as_str().map(|reason| finish_reason(reason, !tool_calls.is_empty())),
+        tool_calls: Some(tool_calls).filter(|calls| !calls.is_empty()),
+        usage: usage(&raw["usageMetadata"]),
+    }
+}
+
+/// `TokenUsage` from `usageMetadata`; thinking tokens count as output
+fn usage(metadata: &Value) -> TokenUsage {
+    let tokens = |key: &str| metadata[key].as_u64().map(|n| n as u32);
+    let input_tokens = tokens("promptTokenCount").unwrap_or_default();
+    let reasoning_tokens = tokens("thoughtsTokenCount");
+    let output_tokens = tokens("candidatesTokenCount").unwrap_or_default() + reasoning_tokens.unwrap_or_default();
+    TokenUsage {
+        input_tokens,
+        output_tokens,
+        total_tokens: tokens("totalTokenCount").unwrap_or(input_tokens + output_tokens),
+        reasoning_tokens,
+        cached_tokens: tokens("cachedContentTokenCount"),
+    }
+}
+
+// ============================================================================
+// TRAIT IMPLEMENTATIONS
+// ============================================================================
+
+#[async_trait]
+impl ChatClient for GeminiClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, warnings) = self.build_chat_request(&request.model, request.messages, &request.options)?;
+
+        let raw = self.generate(&request.model, &payload).await?;
+        let parsed = parse_response(&raw);
+        let choice = ChoiceOutput {
+            index: 0,
+            content: parsed.text,
+            tool_calls: parsed.tool_calls,
+            finish_reason: parsed.finish_reason,
+            logprobs: None,
+        };
+        Ok(ChatResponse {
+            content: choice.content.clone(),
+            tool_calls: choice.tool_calls.clone(),
+            usage: Some(parsed.usage),
+            finish_reason: choice.finish_reason.clone(),
+            logprobs: None,
+            choices: vec![choice],
+            warnings,
+            raw,
+            served_by: None,
+            metadata: None,
+        })
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, adjustments) = self.build_chat_request(&request.model, request.messages, &request.options)?;
+        self.send_stream(&request.model, payload, adjustments).await
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for GeminiClient {
+    /// Thinking request; `raw` holds the response in Responses form (the
+    /// thoughts as a reasoning summary, the text as a message)
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        request.validate().map_err(LLMError::from)?;
+        let payload = self.build_thinking_request(request.input, request.reasoning.as_ref(), &request.options)?;
+
+        let raw = self.generate(&request.model, &payload).await?;
+        let parsed = parse_response(&raw);
+        let id = raw["responseId"].as_str().unwrap_or_default().to_string();
+
+        let mut output = Vec::new();
+        if let Some(thoughts) = &parsed.thoughts {
+            output.push(OutputItem::Reasoning {
+                id: format!("{}_thoughts", id),
+                summary: vec![SummaryText { content_type: "summary_text".to_string(), text: thoughts.clone() }],
+            });
+        }
+        if let Some(text) = &parsed.text {
+            output.push(OutputItem::Message {
+                id: id.clone(),
+                status: "completed".to_string(),
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText { text: text.clone(), annotations: None }],
+            });
+        }
+        let status = parsed.finish_reason.clone().unwrap_or_else(|| "completed".to_string());
+
+        Ok(ResponseOutput {
+            reasoning: parsed.thoughts,
+            message: parsed.text,
+            usage: Some(parsed.usage.clone()),
+            status: Some(status.clone()),
+            warnings: Vec::new(),
+            raw: ResponsesResponse {
+                id,
+                object: "generate_content".to_string(),
+                created_at: chrono::Utc::now().timestamp(),
+                status,
+                model: raw["modelVersion"].as_str().unwrap_or(&request.model).to_string(),
+                output,
+                usage: Usage {
+                    input_tokens: parsed.usage.input_tokens,
+                    output_tokens: parsed.usage.output_tokens,
+                    total_tokens: parsed.usage.total_tokens,
+                    output_tokens_details: None,
+                    input_tokens_details: None,
+                },
+                reasoning: request.reasoning,
+            },
+            served_by: None,
+            metadata: None,
+        })
+    }
+
+    async fn reason_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        request.validate().map_err(LLMError::from)?;
+        let payload = self.build_thinking_request(request.input, request.reasoning.as_ref(), &request.options)?;
+        self.send_stream(&request.model, payload, Vec::new()).await
+    }
+}
+
+// Gemini supports both chat and (thinking) reasoning
+impl LLMClient for GeminiClient {}
+
+/// `LLMError` for a non-success response; 503s (overloaded) are rate limits too
+async fn api_error(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let error_text = response.text().await.unwrap_or_default();
+    if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        return LLMError::RateLimited(RateLimited::from_headers(&headers, error_text)).into();
+    }
+    LLMError::from_status(status, &headers, error_text).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weather_call() -> ToolCall {
+        ToolCall {
+            id: "gemini_call_0".to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall { name: "get_weather".to_string(), arguments: r#"{"city":"Lisbon"}"#.to_string() },
+        }
+    }
+
+    #[test]
+    fn test_messages_use_gemini_contents() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::human("Weather in Lisbon?"),
+            Message::ai_with_content_and_tools("Checking.", vec![weather_call()]),
+            Message::tool("gemini_call_0", "22C"),
+            Message::human("Thanks"),
+        ];
+        let (system, contents) = convert_messages(messages).unwrap();
+
+        assert_eq!(system.as_deref(), Some("Be brief."));
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents[1]["role"], "model");
+        assert_eq!(contents[1]["parts"][1], json!({ "functionCall": { "name": "get_weather", "args": { "city": "Lisbon" } } }));
+        // The function response names the function and shares a turn with the next question
+        assert_eq!(contents[2]["parts"][0], json!({ "functionResponse": { "name": "get_weather", "response": { "content": "22C" } } }));
+        assert_eq!(contents[2]["parts"][1]["text"], "Thanks");
+
+        let orphan = vec![Message::tool("missing", "{}")];
+        assert!(convert_messages(orphan).is_err());
+    }
+
+    #[test]
+    fn test_chat_request_payload() {
+        let client = GeminiClient::new("AIza-test").unwrap();
+        let weather = Tool::new(
+            "get_weather",
+            "Current weather",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } }, "additionalProperties": false }),
+        );
+        let clock = Tool::new("get_time", "Current time", json!({ "type": "object", "properties": {} }));
+        let options = ChatOptions::new()
+            .tools(vec![weather, clock])
+            .tool_choice(ToolChoice::required())
+            .temperature(0.3)
+            .max_tokens(256);
+
+        let (payload, adjusted) = client.build_chat_request("gemini-2.5-flash", vec![Message::human("hi")], &options).unwrap();
+        assert!(adjusted.is_empty());
+        let declarations = &payload["tools"][0]["functionDeclarations"];
+        assert_eq!(declarations[0]["parameters"], json!({ "type": "object", "properties": { "city": { "type": "string" } } }));
+        assert!(declarations[1].get("parameters").is_none());
+        assert_eq!(payload["toolConfig"]["functionCallingConfig"]["mode"], "ANY");
+        assert_eq!(payload["generationConfig"], json!({ "temperature": 0.3f32, "maxOutputTokens": 256 }));
+        assert!(payload.get("systemInstruction").is_none());
+
+        let sequential = options.clone().parallel_tool_calls(false);
+        let (_, adjusted) = client.build_chat_request("gemini-2.5-flash", vec![Message::human("hi")], &sequential).unwrap();
+        assert_eq!(adjusted[0].option, "parallel_tool_calls");
+
+        let sampling = ChatOptions::new().stop(["END"]).seed(3);
+        let (payload, _) = client.build_chat_request("gemini-2.5-flash", vec![Message::human("hi")], &sampling).unwrap();
+        assert_eq!(payload["generationConfig"], json!({ "stopSequences": ["END"], "seed": 3 }));
+    }
+
+    #[test]
+    fn test_usage_from_usage_metadata() {
+        let raw = json!({
+            "candidates": [{ "content": { "parts": [{ "text": "Hi" }] }, "finishReason": "STOP" }],
+            "usageMetadata": { "promptTokenCount": 10, "candidatesTokenCount": 4, "thoughtsTokenCount": 6, "totalTokenCount": 20 },
+        });
+        let parsed = parse_response(&raw);
+
+        assert_eq!(parsed.text.as_deref(), Some("Hi"));
+        assert_eq!(parsed.finish_reason.as_deref(), Some("stop"));
+        assert_eq!((parsed.usage.input_tokens, parsed.usage.output_tokens, parsed.usage.total_tokens), (10, 10, 20));
+        assert_eq!(parsed.usage.reasoning_tokens, Some(6));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::http::ConnectionOptions;
+
+/// Credentials and API version for `GeminiClient`
+#[derive(Clone)]
+pub struct GeminiConfig {
+    pub api_key: String,
+    /// Path segment of the API version (`v1beta` has function calling and thinking)
+    pub api_version: String,
+    /// Pool and keep-alive settings of the client's own `reqwest::Client`
+    pub connection: ConnectionOptions,
+    /// A `reqwest::Client` shared with other clients, used instead of
+    /// building one
+    pub http_client: Option<reqwest::Client>,
+}
+
+impl GeminiConfig {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            api_version: "v1beta".to_string(),
+            connection: ConnectionOptions::default(),
+            http_client: None,
+        }
+    }
+
+    pub fn with_api_version(mut self, version: impl Into<String>) -> Self {
+        self.api_version = version.into();
+        self
+    }
+
+    pub fn with_connection_options(mut self, options: ConnectionOptions) -> Self {
+        self.connection = options;
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl std::fmt::Debug for GeminiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeminiConfig")
+            .field("api_key", &"[redacted]")
+            .field("api_version", &self.api_version)
+            .field("connection", &self.connection)
+            .field("shared_http_client", &self.http_client.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,8 @@
+// Google Gemini-specific implementations
+
+pub mod client;
+pub mod config;
+pub(crate) mod streaming;
+
+pub use client::GeminiClient;
+pub use config::GeminiConfig;
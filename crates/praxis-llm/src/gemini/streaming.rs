@@ -0,0 +1,109 @@
+//! `streamGenerateContent` chunks to `StreamEvent`
+//!
+//! Every chunk is a complete `GenerateContentResponse` holding the new parts
+//! of the first candidate. Gemini sends each function call whole, with its
+//! arguments as an object, and without an id, so the parser numbers calls
+//! across the stream and makes up ids the history can refer back to.
+
+use crate::buffer_utils::SseLineParser;
+use crate::streaming::{ReasoningKind, StreamEvent};
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Gemini SSE parser (Strategy Pattern)
+#[derive(Default)]
+pub(crate) struct GeminiSseParser {
+    tool_calls: AtomicU32,
+}
+
+impl SseLineParser for GeminiSseParser {
+    fn parse_data_line(&self, data: &str) -> Result<Vec<StreamEvent>> {
+        let chunk: Value = serde_json::from_str(data)
+            .map_err(|e| anyhow::anyhow!("Failed to parse Gemini chunk: {}", e))?;
+        if let Some(message) = chunk["error"]["message"].as_str() {
+            anyhow::bail!("Gemini stream error: {}", message);
+        }
+
+        let candidate = &chunk["candidates"][0];
+        let mut events = Vec::new();
+        for part in candidate["content"]["parts"].as_array().into_iter().flatten() {
+            if let Some(call) = part.get("functionCall") {
+                let index = self.tool_calls.fetch_add(1, Ordering::SeqCst);
+                events.push(StreamEvent::ToolCall {
+                    index,
+                    id: Some(call["id"].as_str().map_or_else(|| tool_call_id(index), String::from)),
+                    name: call["name"].as_str().map(String::from),
+                    arguments: Some(call.get("args").map_or_else(|| "{}".to_string(), Value::to_string)),
+                });
+            } else if let Some(text) = part["text"].as_str().filter(|t| !t.is_empty()) {
+                let content = text.to_string();
+                events.push(match part["thought"].as_bool() {
+                    // Gemini only returns summaries of its thoughts
+                    Some(true) => StreamEvent::Reasoning { content, kind: ReasoningKind::Summary, item_id: None },
+                    _ => StreamEvent::Message { content, choice_index: None, logprobs: None },
+                });
+            }
+        }
+        if let Some(reason) = candidate["finishReason"].as_str() {
+            let called_tools = self.tool_calls.load(Ordering::SeqCst) > 0;
+            events.push(StreamEvent::Done { finish_reason: Some(finish_reason(reason, called_tools)), choice_index: None });
+        }
+        Ok(events)
+    }
+}
+
+/// Id for the `index`-th function call of a response that didn't send one
+pub(crate) fn tool_call_id(index: u32) -> String {
+    format!("gemini_call_{}", index)
+}
+
+/// Gemini finish reason in the Chat Completions vocabulary the graph expects
+///
+/// Gemini ends a function-calling turn with `STOP`, so `called_tools`
+/// turns that into `tool_calls`.
+pub(crate) fn finish_reason(reason: &str, called_tools: bool) -> String {
+    match reason {
+        "STOP" if called_tools => "tool_calls".to_string(),
+        "STOP" => "stop".to_string(),
+        "MAX_TOKENS" => "length".to_string(),
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => "content_filter".to_string(),
+        other => other.to_ascii_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_calls_get_indexes_and_ids() {
+        let parser = GeminiSseParser::default();
+        let first = parser
+            .parse_data_line(r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Checking."},{"functionCall":{"name":"get_weather","args":{"city":"Lisbon"}}}]}}]}"#)
+            .unwrap();
+        let second = parser
+            .parse_data_line(r#"{"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"name":"get_time","args":{}}}]},"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":12}}"#)
+            .unwrap();
+
+        assert!(matches!(&first[0], StreamEvent::Message { content, .. } if content == "Checking."));
+        assert!(matches!(&first[1], StreamEvent::ToolCall { index: 0, id: Some(id), name: Some(name), arguments: Some(args) }
+            if id == "gemini_call_0" && name == "get_weather" && args == r#"{"city":"Lisbon"}"#));
+        assert!(matches!(&second[0], StreamEvent::ToolCall { index: 1, .. }));
+        assert!(matches!(&second[1], StreamEvent::Done { finish_reason: Some(reason), choice_index: None } if reason == "tool_calls"));
+    }
+
+    #[test]
+    fn test_thoughts_finish_reasons_and_errors() {
+        let parser = GeminiSseParser::default();
+        let events = parser
+            .parse_data_line(r#"{"candidates":[{"content":{"parts":[{"text":"Hmm","thought":true},{"text":"42"}]},"finishReason":"MAX_TOKENS"}]}"#)
+            .unwrap();
+        assert!(matches!(&events[0], StreamEvent::Reasoning { content, kind: ReasoningKind::Summary, .. } if content == "Hmm"));
+        assert!(matches!(&events[1], StreamEvent::Message { content, .. } if content == "42"));
+        assert!(matches!(&events[2], StreamEvent::Done { finish_reason: Some(reason), choice_index: None } if reason == "length"));
+
+        let err = parser.parse_data_line(r#"{"error":{"code":503,"message":"The model is overloaded."}}"#).unwrap_err();
+        assert!(err.to_string().contains("overloaded"));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::error::TimedOut;
+use crate::streaming::StreamEvent;
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Environment variable that enables raw payload capture
+pub const CAPTURE_DIR_ENV: &str = "PRAXIS_CAPTURE_DIR";
+
+/// Default per-file size cap for captured payloads (4 MiB)
+pub const DEFAULT_CAPTURE_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+/// Default wait for a response (the whole body, for non-streaming calls)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Default wait for a TCP/TLS connection to the provider
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default longest gap between two events of a streaming response
+pub const DEFAULT_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// HTTP-level options shared by provider clients
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Directory for raw request/SSE captures (see `crate::capture`); None disables capture
+    pub capture_dir: Option<PathBuf>,
+    /// Hard size cap for each captured file
+    pub capture_max_bytes: usize,
+    /// Wait for the response headers, and for non-streaming calls the body
+    pub timeout: Duration,
+    /// Abort a streaming response when no event arrives for this long
+    pub stream_idle_timeout: Duration,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            capture_dir: None,
+            capture_max_bytes: DEFAULT_CAPTURE_MAX_BYTES,
+            timeout: DEFAULT_TIMEOUT,
+            stream_idle_timeout: DEFAULT_STREAM_IDLE_TIMEOUT,
+        }
+    }
+}
+
+impl HttpOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defaults plus overrides from the environment (`PRAXIS_CAPTURE_DIR`)
+    pub fn from_env() -> Self {
+        let capture_dir = std::env::var_os(CAPTURE_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+
+        Self {
+            capture_dir,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_capture_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.capture_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_capture_max_bytes(mut self, max: usize) -> Self {
+        self.capture_max_bytes = max;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_stream_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_idle_timeout = timeout;
+        self
+    }
+}
+
+/// Connection pool and transport settings of a provider client's
+/// `reqwest::Client`
+///
+/// Unset fields keep reqwest's defaults. Under many concurrent streams, a
+/// larger `pool_max_idle_per_host` and a longer `pool_idle_timeout` save a
+/// new TCP/TLS handshake per request.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionOptions {
+    /// Idle connections kept open per host (default: no limit)
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection is kept (default: 90s)
+    pub pool_idle_timeout: Option<Duration>,
+    /// Interval of TCP keep-alive probes (default: off)
+    pub tcp_keepalive: Option<Duration>,
+    /// Speak HTTP/2 without negotiating it first, for servers known to
+    /// support it
+    pub http2_prior_knowledge: bool,
+}
+
+impl ConnectionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    pub fn with_http2_prior_knowledge(mut self) -> Self {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Build a `reqwest::Client` with these settings
+    ///
+    /// Pass it to several clients' configs (`with_http_client`) so they
+    /// share one connection pool.
+    pub fn build_client(&self, connect_timeout: Duration) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        builder.build().context("Failed to create HTTP client")
+    }
+}
+
+/// `shared`, or a new client built from `options`
+pub(crate) fn client_or_build(
+    shared: Option<&reqwest::Client>,
+    options: &ConnectionOptions,
+    connect_timeout: Duration,
+) -> Result<reqwest::Client> {
+    match shared {
+        Some(client) => Ok(client.clone()),
+        None => options.build_client(connect_timeout),
+    }
+}
+
+/// Send `request`, failing with `TimedOut` if no response arrives within `timeout`
+pub(crate) async fn send_within(request: reqwest::RequestBuilder, timeout: Duration) -> Result<reqwest::Response> {
+    match tokio::time::timeout(timeout, request.send()).await {
+        Ok(sent) => sent.context("Failed to send request"),
+        Err(_) => Err(TimedOut { after: timeout, waiting_for: "a response" }.into()),
+    }
+}
+
+/// End `stream` with a `TimedOut` error once it goes `idle` without an event
+pub(crate) fn with_idle_timeout(
+    stream: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>,
+    idle: Duration,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
+    Box::pin(async_stream::stream! {
+        let mut stream = stream;
+        loop {
+            match tokio::time::timeout(idle, stream.next()).await {
+                Ok(Some(event)) => yield event,
+                Ok(None) => break,
+                Err(_) => {
+                    yield Err(TimedOut { after: idle, waiting_for: "the next stream event" }.into());
+                    break;
+                }
+            }
+        }
+    })
+}
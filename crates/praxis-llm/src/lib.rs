@@ -2,20 +2,51 @@ pub mod types;
 pub mod traits;
 pub mod streaming;
 pub mod buffer_utils;
+pub mod http;
+pub mod capture;
+pub mod capabilities;
+pub mod cost;
+pub mod error;
+pub mod limit;
+pub mod fallback;
+pub mod retry;
+pub mod stats;
+pub mod recording;
+pub mod testkit;
 pub mod openai;
+pub mod azure;
+pub mod anthropic;
+pub mod gemini;
+pub mod validation;
 
 pub use traits::{
     ChatClient,
     ReasoningClient,
     LLMClient, 
-    ChatRequest, ChatResponse, ChatOptions,
+    ChatRequest, ChatResponse, ChatOptions, AudioOptions, ChoiceOutput, TokenLogprob, TopLogprob,
     ResponseRequest, ResponseOutput, ResponseOptions,
     TokenUsage,
+    EmbeddingsClient, Embeddings,
+    ModerationClient, Moderation,
+    TokenCounter,
 };
 
-pub use streaming::StreamEvent;
+pub use streaming::{ReasoningKind, StreamEvent};
 pub use streaming::{CircularLineBuffer, EventBatcher};
-pub use openai::OpenAIClient;
+pub use http::{ConnectionOptions, HttpOptions};
+pub use capture::CapturedExchange;
+pub use capabilities::{CapabilityRegistry, ModelCapabilities, OptionAdjusted};
+pub use cost::{CostBreakdown, CostCalculator, ModelPrice};
+pub use error::{LLMError, RateLimited, TimedOut, ValidationError};
+pub use limit::ConcurrencyLimitedClient;
+pub use fallback::{FallbackClient, FallbackProvider};
+pub use retry::RetryPolicy;
+pub use recording::{with_run_id, RecordedCall, RecordingClient, ReplayClient};
+pub use stats::{ProviderError, ProviderStats, RateLimitHeadroom, ResponseMetadata, StatsRecorder, StatsRecordingClient};
+pub use openai::{OpenAIClient, OpenAIConfig};
+pub use azure::{AzureConfig, AzureOpenAIClient};
+pub use anthropic::{AnthropicClient, AnthropicConfig};
+pub use gemini::{GeminiClient, GeminiConfig};
 pub use openai::{ReasoningConfig, ReasoningEffort, SummaryMode};
-pub use types::{Message, Content, Tool, ToolCall, ToolChoice};
+pub use types::{CacheHint, Message, Content, ContentPart, ImageDetail, Tool, ToolCall, ToolChoice};
 
@@ -0,0 +1,130 @@
+//! Cap on concurrent requests to one provider
+//!
+//! Runs share the provider's rate limit; queueing requests behind a
+//! semaphore keeps one burst of runs from tripping it for all of them.
+
+use crate::stats::RateLimitHeadroom;
+use crate::streaming::StreamEvent;
+use crate::traits::{ChatClient, ChatRequest, ChatResponse, LLMClient, ReasoningClient, ResponseOutput, ResponseRequest};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// Client wrapper allowing at most `max_concurrent` requests in flight
+///
+/// A streaming request holds its slot until the stream is dropped.
+pub struct ConcurrencyLimitedClient {
+    inner: Arc<dyn LLMClient>,
+    permits: Arc<Semaphore>,
+}
+
+impl ConcurrencyLimitedClient {
+    pub fn new(inner: Arc<dyn LLMClient>, max_concurrent: usize) -> Self {
+        Self {
+            inner,
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+        }
+    }
+
+    /// Requests that can start right now without waiting
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+
+    async fn acquire(&self) -> Result<OwnedSemaphorePermit> {
+        Ok(Arc::clone(&self.permits).acquire_owned().await?)
+    }
+}
+
+/// Keep `permit` alive for as long as the stream is
+fn hold(permit: OwnedSemaphorePermit, stream: EventStream) -> EventStream {
+    Box::pin(stream.map(move |event| {
+        let _ = &permit;
+        event
+    }))
+}
+
+#[async_trait]
+impl ChatClient for ConcurrencyLimitedClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let _permit = self.acquire().await?;
+        self.inner.chat(request).await
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let permit = self.acquire().await?;
+        Ok(hold(permit, self.inner.chat_stream(request).await?))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for ConcurrencyLimitedClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        let _permit = self.acquire().await?;
+        self.inner.reason(request).await
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        let permit = self.acquire().await?;
+        Ok(hold(permit, self.inner.reason_stream(request).await?))
+    }
+}
+
+impl LLMClient for ConcurrencyLimitedClient {
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        self.inner.rate_limit_headroom()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Idle;
+
+    #[async_trait]
+    impl ChatClient for Idle {
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+            anyhow::bail!("unused")
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<EventStream> {
+            Ok(Box::pin(futures::stream::pending()))
+        }
+    }
+
+    #[async_trait]
+    impl ReasoningClient for Idle {
+        async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+            anyhow::bail!("unused")
+        }
+
+        async fn reason_stream(&self, _request: ResponseRequest) -> Result<EventStream> {
+            Ok(Box::pin(futures::stream::pending()))
+        }
+    }
+
+    impl LLMClient for Idle {}
+
+    #[tokio::test]
+    async fn test_open_streams_hold_their_slot() {
+        let client = ConcurrencyLimitedClient::new(Arc::new(Idle), 2);
+        let request = || ChatRequest::new("gpt-4o", vec![]);
+
+        let first = client.chat_stream(request()).await.unwrap();
+        let _second = client.chat_stream(request()).await.unwrap();
+        assert_eq!(client.available(), 0);
+
+        let third = tokio::time::timeout(std::time::Duration::from_millis(20), client.chat_stream(request())).await;
+        assert!(third.is_err(), "third request should wait for a slot");
+
+        drop(first);
+        assert_eq!(client.available(), 1);
+        assert!(client.chat_stream(request()).await.is_ok());
+    }
+}
@@ -1,61 +1,194 @@
 // OpenAI-specific client implementation
 
-use crate::openai::{ReasoningConfig, ResponsesResponse};
-use crate::streaming::{parse_chat_sse_stream, parse_response_sse_stream, StreamEvent};
+use crate::buffer_utils::parse_sse_byte_stream;
+use crate::capabilities::{CapabilityRegistry, OptionAdjusted};
+use crate::capture::{redact_headers, Capture};
+use crate::error::LLMError;
+use crate::http::{client_or_build, with_idle_timeout, HttpOptions, DEFAULT_CONNECT_TIMEOUT};
+use crate::retry::RetryPolicy;
+use crate::stats::{RateLimitHeadroom, ResponseMetadata};
+use crate::openai::{OpenAIConfig, ReasoningConfig, ResponsesResponse};
+use crate::streaming::{
+    parse_chat_sse_stream, parse_response_sse_stream, usage_before_done, ChatSseParser, ChoiceLogprobs, PromptTokensDetails,
+    ResponseSseParser, StreamEvent,
+};
 use crate::traits::{
-    ChatClient, ChatOptions, ChatRequest, ChatResponse, LLMClient, ReasoningClient,
-    ResponseOptions, ResponseOutput, ResponseRequest, TokenUsage,
+    ChatClient, ChatOptions, ChatRequest, ChatResponse, ChoiceOutput, Embeddings, EmbeddingsClient, LLMClient, Moderation,
+    ModerationClient, ReasoningClient, ResponseOptions, ResponseOutput, ResponseRequest, TokenUsage, merge_extra_body,
 };
-use crate::types::{Content, Message, ToolCall};
+use crate::types::{Content, Message, Tool, ToolCall, ToolChoice};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use futures::Stream;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::pin::Pin;
+use std::sync::Mutex;
 
 const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
+const ORGANIZATION_HEADER: HeaderName = HeaderName::from_static("openai-organization");
+const PROJECT_HEADER: HeaderName = HeaderName::from_static("openai-project");
+/// Most inputs `/embeddings` accepts in one request
+pub const MAX_EMBEDDING_INPUTS: usize = 2048;
+/// Model of `/moderations` requests unless `OpenAIConfig::moderation_model` says otherwise
+pub const DEFAULT_MODERATION_MODEL: &str = "omni-moderation-latest";
 
 /// OpenAI client (HTTP direct, no SDK)
 pub struct OpenAIClient {
     http_client: reqwest::Client,
+    /// Sent with each request, so a shared `http_client` carries no credentials
+    headers: HeaderMap,
     base_url: String,
+    http_options: HttpOptions,
+    retry_policy: RetryPolicy,
+    /// Default headers with credentials redacted, written to payload captures
+    capture_headers: BTreeMap<String, String>,
+    /// Rate-limit headers of the latest response that had them
+    rate_limit: Mutex<Option<RateLimitHeadroom>>,
+    capabilities: CapabilityRegistry,
+    moderation_model: String,
 }
 
 impl OpenAIClient {
     /// Create new client with API key
+    ///
+    /// Payload capture is enabled when `PRAXIS_CAPTURE_DIR` is set (see `crate::capture`).
     pub fn new(api_key: impl Into<String>) -> Result<Self> {
-        let api_key = api_key.into();
-        
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", api_key))
-                .context("Invalid API key format")?,
-        );
+        Self::from_config(OpenAIConfig::new(api_key))
+    }
+
+    /// Create a client for an OpenAI-compatible server at `base_url`
+    ///
+    /// Local servers such as Ollama ignore the key; pass an empty one and no
+    /// `Authorization` header is sent.
+    pub fn with_base_url(api_key: impl Into<String>, base_url: impl Into<String>) -> Result<Self> {
+        Self::from_config(OpenAIConfig::new(api_key).with_base_url(base_url))
+    }
+
+    /// Create a client from `config` (scoping, base URL, retries, timeouts,
+    /// connection pool)
+    ///
+    /// An organization or project the config leaves unset comes from
+    /// `OPENAI_ORG_ID` or `OPENAI_PROJECT_ID`.
+    pub fn from_config(config: OpenAIConfig) -> Result<Self> {
+        let config = config.with_env_defaults();
+        let headers = Self::default_headers(&config)?;
         
-        let http_client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .context("Failed to create HTTP client")?;
+        let capture_headers = redact_headers(&headers);
+        let http_client = client_or_build(
+            config.http_client.as_ref(),
+            &config.connection,
+            config.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+        )?;
+
+        let mut http_options = HttpOptions::from_env();
+        if let Some(timeout) = config.timeout {
+            http_options = http_options.with_timeout(timeout);
+        }
+        if let Some(timeout) = config.stream_idle_timeout {
+            http_options = http_options.with_stream_idle_timeout(timeout);
+        }
         
         Ok(Self {
             http_client,
-            base_url: OPENAI_API_BASE.to_string(),
+            headers,
+            base_url: config
+                .base_url
+                .as_deref()
+                .unwrap_or(OPENAI_API_BASE)
+                .trim_end_matches('/')
+                .to_string(),
+            http_options,
+            retry_policy: config.retry_policy.unwrap_or_else(RetryPolicy::none),
+            capture_headers,
+            rate_limit: Mutex::new(None),
+            capabilities: config.capabilities.unwrap_or_default(),
+            moderation_model: config.moderation_model.unwrap_or_else(|| DEFAULT_MODERATION_MODEL.to_string()),
         })
     }
+
+    /// Headers sent with every request
+    fn default_headers(config: &OpenAIConfig) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if !config.api_key.is_empty() {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", config.api_key))
+                    .context("Invalid API key format")?,
+            );
+        }
+        if let Some(organization) = &config.organization {
+            headers.insert(
+                ORGANIZATION_HEADER,
+                HeaderValue::from_str(organization).context("Invalid OpenAI organization id")?,
+            );
+        }
+        if let Some(project) = &config.project {
+            headers.insert(
+                PROJECT_HEADER,
+                HeaderValue::from_str(project).context("Invalid OpenAI project id")?,
+            );
+        }
+        headers.extend(config.default_headers.clone());
+        Ok(headers)
+    }
+
+    /// Override HTTP options (e.g. enable payload capture programmatically)
+    ///
+    /// The connect timeout is fixed when the client is built; set it on the config.
+    pub fn with_http_options(mut self, options: HttpOptions) -> Self {
+        self.http_options = options;
+        self
+    }
+
+    /// Resend requests that fail with a 429 or a transient 5xx per `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Look models up in `registry` instead of the built-in table
+    pub fn with_capabilities(mut self, registry: CapabilityRegistry) -> Self {
+        self.capabilities = registry;
+        self
+    }
+
+    /// POST `payload` to `url`, retrying per the client's policy
+    ///
+    /// A streamed body may take longer than `timeout`; only its headers must not.
+    async fn post(&self, url: &str, payload: &Value, stream: bool) -> Result<reqwest::Response> {
+        let timeout = self.http_options.timeout;
+        let request = || {
+            let request = self.http_client.post(url).headers(self.headers.clone()).json(payload);
+            if stream { request } else { request.timeout(timeout) }
+        };
+        let response = self.retry_policy.send(request, timeout).await.map_err(LLMError::transport)?;
+        if let Some(headroom) = RateLimitHeadroom::from_headers(response.headers()) {
+            *self.rate_limit.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(headroom);
+        }
+        Ok(response)
+    }
+
+    /// Start a payload capture for this request (None when capture is disabled)
+    fn start_capture(&self, endpoint: &str, url: &str, payload: &Value) -> Option<Capture> {
+        Capture::start(&self.http_options, endpoint, url, &self.capture_headers, payload)
+    }
     
-    /// Build chat completion request payload
+    /// Build chat completion request payload, with the options the model didn't accept
     fn build_chat_request(
         &self,
         model: &str,
         messages: Vec<Message>,
         options: &ChatOptions,
         stream: bool,
-    ) -> Result<Value> {
-        let openai_messages: Vec<Value> = messages
+    ) -> Result<(Value, Vec<OptionAdjusted>)> {
+        let capabilities = self.capabilities.get(model);
+        let (options, adjusted) = capabilities.adjust_chat_options(model, options)?;
+        
+        let openai_messages: Vec<Value> = cached_instructions_first(capabilities.adjust_messages(messages))
             .into_iter()
             .map(|msg| self.convert_message(msg))
             .collect::<Result<Vec<_>>>()?;
@@ -68,18 +201,11 @@ impl OpenAIClient {
         
         let obj = request.as_object_mut().unwrap();
         
-        // Check if it's an o1 or gpt-5 model (uses different parameter names)
-        let is_reasoning_model = model.starts_with("o1") || model.starts_with("gpt-5");
-        
         if let Some(temp) = options.temperature {
-            // o1 and gpt-5 models don't support temperature
-            if !is_reasoning_model {
             obj.insert("temperature".to_string(), serde_json::json!(temp));
-            }
         }
         if let Some(max_tokens) = options.max_tokens {
-            // o1 and gpt-5 use max_completion_tokens instead of max_tokens
-            let token_field = if is_reasoning_model {
+            let token_field = if capabilities.max_completion_tokens {
                 "max_completion_tokens"
             } else {
                 "max_tokens"
@@ -89,17 +215,62 @@ impl OpenAIClient {
         if let Some(ref reasoning_effort) = options.reasoning_effort {
             obj.insert("reasoning_effort".to_string(), serde_json::json!(reasoning_effort));
         }
-        if let Some(tools) = &options.tools {
+        if let Some(stop) = &options.stop {
+            obj.insert("stop".to_string(), serde_json::json!(stop));
+        }
+        if let Some(top_p) = options.top_p {
+            obj.insert("top_p".to_string(), serde_json::json!(top_p));
+        }
+        if let Some(seed) = options.seed {
+            obj.insert("seed".to_string(), serde_json::json!(seed));
+        }
+        if let Some(penalty) = options.presence_penalty {
+            obj.insert("presence_penalty".to_string(), serde_json::json!(penalty));
+        }
+        if let Some(penalty) = options.frequency_penalty {
+            obj.insert("frequency_penalty".to_string(), serde_json::json!(penalty));
+        }
+        // Sent only when asked for: some deployments reject the fields
+        if options.wants_logprobs() {
+            obj.insert("logprobs".to_string(), serde_json::json!(true));
+        }
+        if let Some(top_logprobs) = options.top_logprobs {
+            obj.insert("top_logprobs".to_string(), serde_json::json!(top_logprobs));
+        }
+        if let Some(n) = options.n {
+            obj.insert("n".to_string(), serde_json::json!(n));
+        }
+        if let Some(audio) = &options.audio {
+            obj.insert("modalities".to_string(), serde_json::json!(["text", "audio"]));
+            obj.insert("audio".to_string(), serde_json::json!({ "voice": audio.voice, "format": audio.format }));
+        }
+        // An empty tool list (and a choice among no tools) is left out:
+        // OpenAI rejects it, and local models without tool support may too
+        if let Some(tools) = options.tools.as_ref().filter(|tools| !tools.is_empty()) {
             obj.insert("tools".to_string(), serde_json::to_value(tools)?);
+            if let Some(tool_choice) = &options.tool_choice {
+                obj.insert("tool_choice".to_string(), serde_json::to_value(tool_choice)?);
+            }
+            if let Some(parallel) = options.parallel_tool_calls {
+                obj.insert("parallel_tool_calls".to_string(), serde_json::json!(parallel));
+            }
         }
-        if let Some(tool_choice) = &options.tool_choice {
-            obj.insert("tool_choice".to_string(), serde_json::to_value(tool_choice)?);
+        if let Some(user) = &options.end_user_id {
+            obj.insert("user".to_string(), serde_json::json!(user));
         }
+        if let Some(metadata) = &options.metadata {
+            obj.insert("metadata".to_string(), serde_json::json!(metadata));
+        }
+        if stream {
+            // Otherwise streamed completions never say how many tokens they used
+            obj.insert("stream_options".to_string(), serde_json::json!({ "include_usage": true }));
+        }
+        merge_extra_body(obj, options.extra_body.as_ref());
         
-        Ok(request)
+        Ok((request, adjusted))
     }
     
-    /// Build responses request payload
+    /// Build responses request payload, with the options the model didn't accept
     fn build_response_request(
         &self,
         model: &str,
@@ -107,21 +278,24 @@ impl OpenAIClient {
         reasoning: Option<&ReasoningConfig>,
         options: &ResponseOptions,
         stream: bool,
-    ) -> Result<Value> {
-        let openai_messages: Vec<Value> = input
-            .into_iter()
-            .map(|msg| self.convert_message(msg))
-            .collect::<Result<Vec<_>>>()?;
+    ) -> Result<(Value, Vec<OptionAdjusted>)> {
+        let capabilities = self.capabilities.get(model);
+        let (reasoning, options, adjusted) = capabilities.adjust_response_options(model, reasoning, options)?;
+        
+        let mut input_items = Vec::with_capacity(input.len());
+        for message in cached_instructions_first(capabilities.adjust_messages(input)) {
+            input_items.extend(self.convert_input_item(message)?);
+        }
         
         let mut request = serde_json::json!({
             "model": model,
-            "input": openai_messages,
+            "input": input_items,
             "stream": stream,
         });
         
         let obj = request.as_object_mut().unwrap();
         
-        if let Some(reasoning) = reasoning {
+        if let Some(reasoning) = &reasoning {
             obj.insert("reasoning".to_string(), serde_json::to_value(reasoning)?);
         }
         if let Some(temp) = options.temperature {
@@ -130,16 +304,71 @@ impl OpenAIClient {
         if let Some(max_tokens) = options.max_output_tokens {
             obj.insert("max_output_tokens".to_string(), serde_json::json!(max_tokens));
         }
+        // Same rule as chat: no empty tool list, no choice without tools
+        if let Some(tools) = options.tools.as_ref().filter(|tools| !tools.is_empty()) {
+            obj.insert("tools".to_string(), Value::Array(tools.iter().map(response_tool).collect()));
+            if let Some(tool_choice) = &options.tool_choice {
+                obj.insert("tool_choice".to_string(), response_tool_choice(tool_choice));
+            }
+            if let Some(parallel) = options.parallel_tool_calls {
+                obj.insert("parallel_tool_calls".to_string(), serde_json::json!(parallel));
+            }
+        }
+        if let Some(user) = &options.end_user_id {
+            obj.insert("user".to_string(), serde_json::json!(user));
+        }
+        if let Some(metadata) = &options.metadata {
+            obj.insert("metadata".to_string(), serde_json::json!(metadata));
+        }
+        merge_extra_body(obj, options.extra_body.as_ref());
         
-        Ok(request)
+        Ok((request, adjusted))
+    }
+    
+    /// Convert a message to Responses API input items
+    ///
+    /// Tool calls and tool results are items of their own (`function_call`
+    /// and `function_call_output`) rather than message fields, so an
+    /// assistant turn with calls becomes its text plus one item per call.
+    fn convert_input_item(&self, message: Message) -> Result<Vec<Value>> {
+        match message {
+            Message::AI { content, tool_calls: Some(tool_calls), name } => {
+                let mut items = Vec::with_capacity(tool_calls.len() + 1);
+                if content.is_some() {
+                    items.push(self.convert_message(Message::AI { content, tool_calls: None, name })?);
+                }
+                items.extend(tool_calls.into_iter().map(|call| {
+                    serde_json::json!({
+                        "type": "function_call",
+                        "call_id": call.id,
+                        "name": call.function.name,
+                        "arguments": call.function.arguments,
+                    })
+                }));
+                Ok(items)
+            }
+            Message::Tool { tool_call_id, content } => {
+                let output = match content {
+                    Content::Text(text) => text,
+                    Content::Parts(parts) => parts.iter().filter_map(|part| part.as_text()).collect::<Vec<_>>().join("\n"),
+                };
+                Ok(vec![serde_json::json!({
+                    "type": "function_call_output",
+                    "call_id": tool_call_id,
+                    "output": output,
+                })])
+            }
+            message => Ok(vec![self.convert_message(message)?]),
+        }
     }
     
     /// Convert our Message type to OpenAI format
     fn convert_message(&self, message: Message) -> Result<Value> {
+        let role = message.role().to_string();
         match message {
-            Message::System { content, name } => {
+            Message::System { content, name, .. } | Message::Developer { content, name, .. } => {
                 let mut obj = serde_json::json!({
-                    "role": "system",
+                    "role": role,
                     "content": self.convert_content(content)?,
                 });
                 if let Some(name) = name {
@@ -147,7 +376,7 @@ impl OpenAIClient {
                 }
                 Ok(obj)
             }
-            Message::Human { content, name } => {
+            Message::Human { content, name, .. } => {
                 let mut obj = serde_json::json!({
                     "role": "user",
                     "content": self.convert_content(content)?,
@@ -202,6 +431,16 @@ impl OpenAIClient {
                                 "text": text,
                             })
                         }
+                        crate::types::ContentPart::ImageUrl { url, detail } => image_url_part(url, detail),
+                        crate::types::ContentPart::ImageBase64 { data, mime_type, detail } => {
+                            image_url_part(format!("data:{};base64,{}", mime_type, data), detail)
+                        }
+                        crate::types::ContentPart::Audio { data_base64, format } => {
+                            serde_json::json!({
+                                "type": "input_audio",
+                                "input_audio": { "data": data_base64, "format": format },
+                            })
+                        }
                     })
                     .collect();
                 Ok(serde_json::json!(converted))
@@ -210,88 +449,151 @@ impl OpenAIClient {
     }
 }
 
+/// Responses API function tool: the chat definition without the `function` wrapper
+fn response_tool(tool: &Tool) -> Value {
+    let mut definition = serde_json::json!({
+        "type": "function",
+        "name": tool.function.name,
+        "parameters": tool.function.parameters,
+    });
+    if let Some(description) = &tool.function.description {
+        definition["description"] = serde_json::json!(description);
+    }
+    if let Some(strict) = tool.function.strict {
+        definition["strict"] = serde_json::json!(strict);
+    }
+    definition
+}
+
+/// Responses API tool choice; a forced function is `{"type": "function", "name": ...}`
+fn response_tool_choice(choice: &ToolChoice) -> Value {
+    match choice {
+        ToolChoice::Auto(mode) | ToolChoice::None(mode) | ToolChoice::Required(mode) => serde_json::json!(mode),
+        ToolChoice::Specific { tool_type, function } => serde_json::json!({ "type": tool_type, "name": function.name }),
+    }
+}
+
+/// OpenAI caches the longest prompt prefix it has seen before, so within the
+/// leading run of system/developer messages the ones with a cache hint go
+/// first (in their order) and the unhinted ones, which may change per call,
+/// after them. The conversation itself is never reordered
+fn cached_instructions_first(mut messages: Vec<Message>) -> Vec<Message> {
+    let leading = messages
+        .iter()
+        .take_while(|message| matches!(message, Message::System { .. } | Message::Developer { .. }))
+        .count();
+    messages[..leading].sort_by_key(|message| message.cache_hint().is_none());
+    messages
+}
+
+/// Chat Completions image block; inline images travel as `data:` URLs
+fn image_url_part(url: String, detail: Option<crate::types::ImageDetail>) -> Value {
+    let mut image_url = serde_json::json!({ "url": url });
+    if let Some(detail) = detail {
+        image_url["detail"] = serde_json::json!(detail);
+    }
+    serde_json::json!({
+        "type": "image_url",
+        "image_url": image_url,
+    })
+}
+
 // ============================================================================
-// TRAIT IMPLEMENTATIONS
+// REQUESTS (shared with clients whose URLs differ, such as Azure's)
 // ============================================================================
 
-#[async_trait]
-impl ChatClient for OpenAIClient {
-    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
-        let payload = self.build_chat_request(
+impl OpenAIClient {
+    /// `chat` against the chat completions endpoint at `url`
+    pub(crate) async fn chat_at(&self, url: &str, request: ChatRequest) -> Result<ChatResponse> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, warnings) = self.build_chat_request(
             &request.model,
             request.messages,
             &request.options,
             false,
         )?;
         
-        let response = self
-            .http_client
-            .post(format!("{}/chat/completions", self.base_url))
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send request")?;
+        let response = self.post(url, &payload, false).await?;
         
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("OpenAI API error ({}): {}", status, error_text);
+            return Err(api_error(response).await);
         }
         
-        let raw: OpenAIChatResponse = response
-            .json()
-            .await
-            .context("Failed to parse response")?;
+        let metadata = ResponseMetadata::from_headers(response.headers());
+        let raw: OpenAIChatResponse = read_json(response).await.context("Failed to parse response")?;
         
         // Convert to provider-agnostic response
-        let choice = raw.choices.first();
+        let mut choices: Vec<ChoiceOutput> = raw
+            .choices
+            .iter()
+            .map(|c| ChoiceOutput {
+                index: c.index,
+                // Audio answers have no text content; their transcript stands in
+                content: c.message.content.clone().or_else(|| c.message.audio.as_ref().map(|audio| audio.transcript.clone())),
+                tool_calls: c.message.tool_calls.clone(),
+                finish_reason: c.finish_reason.clone(),
+                logprobs: c.logprobs.as_ref().and_then(|logprobs| logprobs.content.clone()),
+            })
+            .collect();
+        choices.sort_by_key(|c| c.index);
+        let choice = choices.first();
         Ok(ChatResponse {
-            content: choice.and_then(|c| c.message.content.clone()),
-            tool_calls: choice.and_then(|c| c.message.tool_calls.clone()),
+            content: choice.and_then(|c| c.content.clone()),
+            tool_calls: choice.and_then(|c| c.tool_calls.clone()),
             usage: Some(TokenUsage {
                 input_tokens: raw.usage.prompt_tokens,
                 output_tokens: raw.usage.completion_tokens,
                 total_tokens: raw.usage.total_tokens,
                 reasoning_tokens: None,
+                cached_tokens: raw.usage.prompt_tokens_details.as_ref().and_then(|d| d.cached_tokens),
             }),
             finish_reason: choice.and_then(|c| c.finish_reason.clone()),
+            logprobs: choice.and_then(|c| c.logprobs.clone()),
+            choices,
+            warnings,
             raw: serde_json::to_value(raw)?,
+            served_by: None,
+            metadata,
         })
     }
     
-    async fn chat_stream(
+    /// `chat_stream` against the chat completions endpoint at `url`
+    pub(crate) async fn chat_stream_at(
         &self,
+        url: &str,
         request: ChatRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
-        let payload = self.build_chat_request(
+        request.validate().map_err(LLMError::from)?;
+        let (payload, adjustments) = self.build_chat_request(
             &request.model,
             request.messages,
             &request.options,
             true,
         )?;
         
-        let response = self
-            .http_client
-            .post(format!("{}/chat/completions", self.base_url))
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send request")?;
+        let capture = self.start_capture("chat/completions", url, &payload);
+        
+        let response = self.post(url, &payload, true).await?;
         
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("OpenAI API error ({}): {}", status, error_text);
+            return Err(api_error(response).await);
         }
         
-        Ok(parse_chat_sse_stream(response))
-    }
+        let metadata = ResponseMetadata::from_headers(response.headers());
+        let stream = match capture {
+            Some(capture) => {
+                usage_before_done(parse_sse_byte_stream(capture.tee(response.bytes_stream()), ChatSseParser))
+            }
+            None => parse_chat_sse_stream(response),
+        };
+        let stream = announce_metadata(metadata, with_idle_timeout(stream, self.http_options.stream_idle_timeout));
+        Ok(announce_adjustments(adjustments, stream))
     }
     
-#[async_trait]
-impl ReasoningClient for OpenAIClient {
-    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
-        let payload = self.build_response_request(
+    /// `reason` against the Responses endpoint at `url`
+    pub(crate) async fn reason_at(&self, url: &str, request: ResponseRequest) -> Result<ResponseOutput> {
+        request.validate().map_err(LLMError::from)?;
+        let (payload, warnings) = self.build_response_request(
             &request.model,
             request.input,
             request.reasoning.as_ref(),
@@ -299,24 +601,14 @@ impl ReasoningClient for OpenAIClient {
             false,
         )?;
         
-        let response = self
-            .http_client
-            .post(format!("{}/responses", self.base_url))
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send request")?;
+        let response = self.post(url, &payload, false).await?;
         
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("OpenAI API error ({}): {}", status, error_text);
+            return Err(api_error(response).await);
         }
         
-        let raw: ResponsesResponse = response
-            .json()
-            .await
-            .context("Failed to parse response")?;
+        let metadata = ResponseMetadata::from_headers(response.headers());
+        let raw: ResponsesResponse = read_json(response).await.context("Failed to parse response")?;
         
         // Convert to provider-agnostic response
         Ok(ResponseOutput {
@@ -329,17 +621,26 @@ impl ReasoningClient for OpenAIClient {
                 reasoning_tokens: raw.usage.output_tokens_details
                     .as_ref()
                     .and_then(|d| d.reasoning_tokens),
+                cached_tokens: raw.usage.input_tokens_details
+                    .as_ref()
+                    .and_then(|d| d.cached_tokens),
             }),
             status: Some(raw.status.clone()),
+            warnings,
             raw,
+            served_by: None,
+            metadata,
         })
     }
     
-    async fn reason_stream(
+    /// `reason_stream` against the Responses endpoint at `url`
+    pub(crate) async fn reason_stream_at(
         &self,
+        url: &str,
         request: ResponseRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
-        let payload = self.build_response_request(
+        request.validate().map_err(LLMError::from)?;
+        let (payload, adjustments) = self.build_response_request(
             &request.model,
             request.input,
             request.reasoning.as_ref(),
@@ -347,26 +648,165 @@ impl ReasoningClient for OpenAIClient {
             true,
         )?;
         
-        let response = self
-            .http_client
-            .post(format!("{}/responses", self.base_url))
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send request")?;
+        let capture = self.start_capture("responses", url, &payload);
+        
+        let response = self.post(url, &payload, true).await?;
         
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("OpenAI API error ({}): {}", status, error_text);
+            return Err(api_error(response).await);
         }
         
-        Ok(parse_response_sse_stream(response))
+        let metadata = ResponseMetadata::from_headers(response.headers());
+        let stream = match capture {
+            Some(capture) => parse_sse_byte_stream(capture.tee(response.bytes_stream()), ResponseSseParser),
+            None => parse_response_sse_stream(response),
+        };
+        let stream = announce_metadata(metadata, with_idle_timeout(stream, self.http_options.stream_idle_timeout));
+        Ok(announce_adjustments(adjustments, stream))
+    }
+}
+
+// ============================================================================
+// TRAIT IMPLEMENTATIONS
+// ============================================================================
+
+#[async_trait]
+impl ChatClient for OpenAIClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        self.chat_at(&format!("{}/chat/completions", self.base_url), request).await
+    }
+    
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        self.chat_stream_at(&format!("{}/chat/completions", self.base_url), request).await
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for OpenAIClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        self.reason_at(&format!("{}/responses", self.base_url), request).await
+    }
+    
+    async fn reason_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        self.reason_stream_at(&format!("{}/responses", self.base_url), request).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingsClient for OpenAIClient {
+    /// Sends `inputs` in batches of `MAX_EMBEDDING_INPUTS`, one after another
+    async fn embed(&self, model: &str, inputs: Vec<String>) -> Result<Embeddings> {
+        let url = format!("{}/embeddings", self.base_url);
+        let mut embeddings = Embeddings {
+            vectors: Vec::with_capacity(inputs.len()),
+            usage: TokenUsage { input_tokens: 0, output_tokens: 0, total_tokens: 0, reasoning_tokens: None, cached_tokens: None },
+        };
+        
+        for batch in inputs.chunks(MAX_EMBEDDING_INPUTS) {
+            let payload = serde_json::json!({
+                "model": model,
+                "input": batch,
+                "encoding_format": "float",
+            });
+            let response = self.post(&url, &payload, false).await?;
+            
+            if !response.status().is_success() {
+                return Err(api_error(response).await);
+            }
+            
+            let mut raw: OpenAIEmbeddingResponse = read_json(response).await.context("Failed to parse embeddings response")?;
+            anyhow::ensure!(
+                raw.data.len() == batch.len(),
+                "OpenAI returned {} embeddings for {} inputs",
+                raw.data.len(),
+                batch.len()
+            );
+            raw.data.sort_by_key(|item| item.index);
+            embeddings.vectors.extend(raw.data.into_iter().map(|item| item.embedding));
+            embeddings.usage.input_tokens += raw.usage.prompt_tokens;
+            embeddings.usage.total_tokens += raw.usage.total_tokens;
+        }
+        
+        Ok(embeddings)
+    }
+}
+
+#[async_trait]
+impl ModerationClient for OpenAIClient {
+    async fn moderate(&self, input: &str) -> Result<Moderation> {
+        let url = format!("{}/moderations", self.base_url);
+        let payload = serde_json::json!({
+            "model": self.moderation_model,
+            "input": input,
+        });
+        let response = self.post(&url, &payload, false).await?;
+        
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        
+        let raw: OpenAIModerationResponse = read_json(response).await.context("Failed to parse moderation response")?;
+        let result = raw
+            .results
+            .into_iter()
+            .next()
+            .context("OpenAI returned no moderation result")?;
+        Ok(Moderation {
+            flagged: result.flagged,
+            categories: result.categories.into_iter().filter(|(_, flagged)| *flagged).map(|(name, _)| name).collect(),
+            category_scores: result.category_scores,
+        })
     }
 }
 
 // OpenAI supports both chat and reasoning
-impl LLMClient for OpenAIClient {}
+impl LLMClient for OpenAIClient {
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        self.rate_limit.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+/// Read a success body as JSON: `Transport` if it can't be read, `Parse` if it doesn't parse
+async fn read_json<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let body = response.bytes().await.map_err(LLMError::Transport)?;
+    Ok(serde_json::from_slice(&body).map_err(LLMError::Parse)?)
+}
+
+/// `LLMError` for a non-success response, read from the error body
+async fn api_error(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let error_text = response.text().await.unwrap_or_default();
+    LLMError::from_openai_response(status, &headers, &error_text).into()
+}
+
+/// Lead the stream with a `ResponseMetadata` event when the headers had any
+fn announce_metadata(
+    metadata: Option<ResponseMetadata>,
+    stream: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
+    match metadata {
+        Some(metadata) => Box::pin(futures::stream::once(async { Ok(StreamEvent::ResponseMetadata { metadata }) }).chain(stream)),
+        None => stream,
+    }
+}
+
+/// Lead the stream with an `OptionsAdjusted` event when options were dropped
+fn announce_adjustments(
+    adjustments: Vec<OptionAdjusted>,
+    stream: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
+    if adjustments.is_empty() {
+        return stream;
+    }
+    Box::pin(futures::stream::once(async { Ok(StreamEvent::OptionsAdjusted { adjustments }) }).chain(stream))
+}
 
 // ============================================================================
 // OPENAI-SPECIFIC RESPONSE TYPES (for Chat Completions)
@@ -387,6 +827,8 @@ struct Choice {
     pub index: u32,
     pub message: ResponseMessage,
     pub finish_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<ChoiceLogprobs>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -394,6 +836,19 @@ struct ResponseMessage {
     pub role: String,
     pub content: Option<String>,
     pub tool_calls: Option<Vec<ToolCall>>,
+    /// Spoken answer, when the request asked for audio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<MessageAudio>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MessageAudio {
+    pub id: String,
+    /// Base64 audio, in the requested format
+    pub data: String,
+    pub transcript: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -401,5 +856,382 @@ struct Usage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+// ============================================================================
+// OPENAI-SPECIFIC RESPONSE TYPES (for Embeddings)
+// ============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+struct OpenAIEmbeddingResponse {
+    pub data: Vec<EmbeddingItem>,
+    pub usage: EmbeddingUsage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EmbeddingItem {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EmbeddingUsage {
+    pub prompt_tokens: u32,
+    pub total_tokens: u32,
+}
+
+// ============================================================================
+// OPENAI-SPECIFIC RESPONSE TYPES (for Moderations)
+// ============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+struct OpenAIModerationResponse {
+    pub results: Vec<ModerationResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModerationResult {
+    pub flagged: bool,
+    pub categories: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub category_scores: BTreeMap<String, f64>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoping_headers_set_from_config() {
+        let config = OpenAIConfig::new("sk-test").with_organization("org-123").with_project("proj_abc");
+        let headers = OpenAIClient::default_headers(&config).unwrap();
+
+        assert_eq!(headers["openai-organization"], "org-123");
+        assert_eq!(headers["openai-project"], "proj_abc");
+
+        let unscoped = OpenAIClient::default_headers(&OpenAIConfig::new("sk-test")).unwrap();
+        assert!(!unscoped.contains_key("openai-organization"));
+        assert!(!unscoped.contains_key("openai-project"));
+    }
+
+    #[test]
+    fn test_scoping_defaults_to_the_environment() {
+        let env = |name: &str| match name {
+            "OPENAI_ORG_ID" => Some("org-env".to_string()),
+            "OPENAI_PROJECT_ID" => Some("proj_env".to_string()),
+            _ => None,
+        };
+
+        let defaulted = OpenAIConfig::new("sk-test").with_defaults_from(env);
+        assert_eq!(defaulted.organization.as_deref(), Some("org-env"));
+        assert_eq!(defaulted.project.as_deref(), Some("proj_env"));
+
+        let explicit = OpenAIConfig::new("sk-test").with_project("proj_abc").with_defaults_from(env);
+        assert_eq!(explicit.project.as_deref(), Some("proj_abc"));
+
+        let empty = OpenAIConfig::new("sk-test").with_defaults_from(|_| Some(String::new()));
+        assert_eq!(empty.organization, None);
+    }
+
+    #[test]
+    fn test_default_headers_sent_and_override() {
+        let custom: std::collections::HashMap<String, String> =
+            [("X-Org-Id", "acme"), ("OpenAI-Project", "proj_gateway")].map(|(k, v)| (k.to_string(), v.to_string())).into();
+        let config = OpenAIConfig::new("sk-test")
+            .with_project("proj_abc")
+            .with_default_headers(HeaderMap::try_from(&custom).unwrap());
+        let headers = OpenAIClient::default_headers(&config).unwrap();
+
+        assert_eq!(headers["x-org-id"], "acme");
+        assert_eq!(headers.get_all("openai-project").iter().collect::<Vec<_>>(), ["proj_gateway"]);
+        assert!(headers.contains_key(AUTHORIZATION));
+        assert!(!format!("{:?}", config).contains("acme"));
+    }
+
+    #[test]
+    fn test_base_url_and_keyless_local_servers() {
+        let local = OpenAIClient::with_base_url("", "http://localhost:11434/v1/").unwrap();
+        assert_eq!(local.base_url, "http://localhost:11434/v1");
+        assert!(!local.capture_headers.keys().any(|name| name.eq_ignore_ascii_case("authorization")));
+
+        let hosted = OpenAIClient::new("sk-test").unwrap();
+        assert_eq!(hosted.base_url, OPENAI_API_BASE);
+    }
+
+    #[test]
+    fn test_empty_tool_list_is_left_out() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let options = ChatOptions::new().tools(Vec::new()).tool_choice(crate::types::ToolChoice::auto());
+
+        let (request, _) = client.build_chat_request("llama3.2", vec![Message::human("hi")], &options, true).unwrap();
+        assert!(request.get("tools").is_none());
+        assert!(request.get("tool_choice").is_none());
+    }
+
+    #[test]
+    fn test_streamed_chat_asks_for_usage() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let messages = vec![Message::human("hi")];
+
+        let (streamed, _) = client.build_chat_request("gpt-4o", messages.clone(), &ChatOptions::new(), true).unwrap();
+        assert_eq!(streamed["stream_options"]["include_usage"], true);
+
+        let (blocking, _) = client.build_chat_request("gpt-4o", messages, &ChatOptions::new(), false).unwrap();
+        assert!(blocking.get("stream_options").is_none());
+    }
+
+    #[test]
+    fn test_tool_choice_wire_format() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let tools = vec![crate::types::Tool::new("search_knowledge_base", "Search", serde_json::json!({"type": "object"}))];
+        let payload = |choice: crate::types::ToolChoice| {
+            let options = ChatOptions::new().tools(tools.clone()).tool_choice(choice);
+            client.build_chat_request("gpt-4o", vec![Message::human("hi")], &options, true).unwrap().0
+        };
+
+        assert_eq!(payload(crate::types::ToolChoice::auto())["tool_choice"], "auto");
+        assert_eq!(payload(crate::types::ToolChoice::none())["tool_choice"], "none");
+        assert_eq!(payload(crate::types::ToolChoice::required())["tool_choice"], "required");
+        assert_eq!(
+            payload(crate::types::ToolChoice::function("search_knowledge_base"))["tool_choice"],
+            serde_json::json!({"type": "function", "function": {"name": "search_knowledge_base"}})
+        );
+    }
+
+    #[test]
+    fn test_parallel_tool_calls_sent_with_tools() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let tools = vec![crate::types::Tool::new("search_knowledge_base", "Search", serde_json::json!({"type": "object"}))];
+        let messages = vec![Message::human("hi")];
+
+        let options = ChatOptions::new().tools(tools.clone()).parallel_tool_calls(false);
+        let (payload, _) = client.build_chat_request("gpt-4o", messages.clone(), &options, true).unwrap();
+        assert_eq!(payload["parallel_tool_calls"], false);
+
+        let options = ResponseOptions::new().tools(tools.clone()).parallel_tool_calls(false);
+        let (payload, _) = client.build_response_request("gpt-5", messages.clone(), None, &options, true).unwrap();
+        assert_eq!(payload["parallel_tool_calls"], false);
+
+        // Unset, or without tools to call, the provider default applies
+        let (payload, _) = client.build_chat_request("gpt-4o", messages.clone(), &ChatOptions::new().tools(tools), true).unwrap();
+        assert!(payload.get("parallel_tool_calls").is_none());
+        let options = ChatOptions::new().parallel_tool_calls(false);
+        let (payload, _) = client.build_chat_request("gpt-4o", messages, &options, true).unwrap();
+        assert!(payload.get("parallel_tool_calls").is_none());
+    }
+
+    #[test]
+    fn test_responses_tools_and_tool_history() {
+        use crate::types::{FunctionCall, ToolChoice};
+
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let tools = vec![crate::types::Tool::new("get_weather", "Weather", serde_json::json!({"type": "object"}))];
+        let call = ToolCall {
+            id: "call_1".to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall { name: "get_weather".to_string(), arguments: r#"{"city":"Lisbon"}"#.to_string() },
+        };
+        let input = vec![
+            Message::human("Weather in Lisbon?"),
+            Message::ai_with_tools(vec![call]),
+            Message::tool("call_1", "Sunny"),
+        ];
+        let options = ResponseOptions::new().tools(tools).tool_choice(ToolChoice::function("get_weather"));
+
+        let (payload, _) = client.build_response_request("gpt-5", input, None, &options, true).unwrap();
+        assert_eq!(
+            payload["tools"],
+            serde_json::json!([{ "type": "function", "name": "get_weather", "description": "Weather", "parameters": {"type": "object"} }])
+        );
+        assert_eq!(payload["tool_choice"], serde_json::json!({ "type": "function", "name": "get_weather" }));
+        assert_eq!(
+            payload["input"],
+            serde_json::json!([
+                { "role": "user", "content": "Weather in Lisbon?" },
+                { "type": "function_call", "call_id": "call_1", "name": "get_weather", "arguments": r#"{"city":"Lisbon"}"# },
+                { "type": "function_call_output", "call_id": "call_1", "output": "Sunny" }
+            ])
+        );
+
+        let (empty, _) = client
+            .build_response_request("gpt-5", vec![Message::human("hi")], None, &ResponseOptions::new().tools(Vec::new()), true)
+            .unwrap();
+        assert!(empty.get("tools").is_none());
+    }
+
+    #[test]
+    fn test_image_parts_sent_as_image_url_blocks() {
+        use crate::types::{ContentPart, ImageDetail};
+
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let message = Message::human(Content::Parts(vec![
+            ContentPart::text("What's wrong here?"),
+            ContentPart::image_url("https://example.com/screen.png").with_detail(ImageDetail::Low),
+            ContentPart::image_base64("iVBORw0KGgo=", "image/png"),
+        ]));
+        let (payload, _) = client.build_chat_request("gpt-4o", vec![message], &ChatOptions::new(), false).unwrap();
+
+        assert_eq!(
+            payload["messages"][0]["content"],
+            serde_json::json!([
+                { "type": "text", "text": "What's wrong here?" },
+                { "type": "image_url", "image_url": { "url": "https://example.com/screen.png", "detail": "low" } },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw0KGgo=" } }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_developer_messages_downgrade_for_chat_models() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let messages = vec![
+            Message::system("Follow the org policy."),
+            Message::developer("Answer in French."),
+            Message::human("hi"),
+        ];
+        let roles = |items: &Value| -> Vec<String> {
+            items.as_array().unwrap().iter().map(|item| item["role"].as_str().unwrap().to_string()).collect()
+        };
+
+        let (gpt4o, _) = client.build_chat_request("gpt-4o", messages.clone(), &ChatOptions::new(), false).unwrap();
+        assert_eq!(roles(&gpt4o["messages"]), ["system", "system", "user"]);
+        assert_eq!(gpt4o["messages"][1]["content"], "Answer in French.");
+
+        let (gpt5, _) = client.build_chat_request("gpt-5", messages.clone(), &ChatOptions::new(), false).unwrap();
+        assert_eq!(roles(&gpt5["messages"]), ["system", "developer", "user"]);
+
+        let (response, _) =
+            client.build_response_request("gpt-5", messages, None, &ResponseOptions::new(), false).unwrap();
+        assert_eq!(roles(&response["input"]), ["system", "developer", "user"]);
+    }
+
+    #[test]
+    fn test_end_user_id_sent_as_user_field() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let messages = vec![Message::human("hi")];
+
+        let (chat, _) = client
+            .build_chat_request("gpt-4o", messages.clone(), &ChatOptions::new().end_user_id("u-hash"), true)
+            .unwrap();
+        assert_eq!(chat["user"], "u-hash");
+
+        let (response, _) = client
+            .build_response_request("gpt-5", messages.clone(), None, &ResponseOptions::new().end_user_id("u-hash"), true)
+            .unwrap();
+        assert_eq!(response["user"], "u-hash");
+
+        let (anonymous, _) = client.build_chat_request("gpt-4o", messages, &ChatOptions::new(), true).unwrap();
+        assert!(anonymous.get("user").is_none());
+        assert!(anonymous.get("metadata").is_none());
+    }
+
+    #[test]
+    fn test_metadata_sent() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let messages = vec![Message::human("hi")];
+        let metadata = serde_json::json!({ "feature": "support", "tenant": "acme" });
+
+        let options = ChatOptions::new().metadata([("tenant", "acme"), ("feature", "support")]);
+        let (chat, _) = client.build_chat_request("gpt-4o", messages.clone(), &options, true).unwrap();
+        assert_eq!(chat["metadata"], metadata);
+
+        let options = ResponseOptions::new().metadata([("tenant", "acme"), ("feature", "support")]);
+        let (response, _) = client.build_response_request("gpt-5", messages, None, &options, true).unwrap();
+        assert_eq!(response["metadata"], metadata);
+    }
+
+    #[test]
+    fn test_choice_count_sent() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let messages = vec![Message::human("hi")];
+
+        let (payload, _) = client.build_chat_request("gpt-4o", messages.clone(), &ChatOptions::new().n(3), false).unwrap();
+        assert_eq!(payload["n"], 3);
+        let (payload, _) = client.build_chat_request("gpt-4o", messages, &ChatOptions::new(), false).unwrap();
+        assert!(payload.get("n").is_none());
+    }
+
+    #[test]
+    fn test_logprobs_sent_only_when_asked_for() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let messages = vec![Message::human("hi")];
+
+        let options = ChatOptions::new().top_logprobs(5);
+        let (payload, _) = client.build_chat_request("gpt-4o", messages.clone(), &options, false).unwrap();
+        assert_eq!(payload["logprobs"], true);
+        assert_eq!(payload["top_logprobs"], 5);
+
+        let options = ChatOptions::new().logprobs(false);
+        let (payload, _) = client.build_chat_request("gpt-4o", messages.clone(), &options, false).unwrap();
+        assert!(payload.get("logprobs").is_none());
+        assert!(payload.get("top_logprobs").is_none());
+
+        // Reasoning models return none
+        let options = ChatOptions::new().logprobs(true).top_logprobs(5);
+        let (payload, adjusted) = client.build_chat_request("o3-mini", messages, &options, false).unwrap();
+        assert!(payload.get("logprobs").is_none());
+        assert!(payload.get("top_logprobs").is_none());
+        assert_eq!(adjusted.iter().map(|a| a.option.as_str()).collect::<Vec<_>>(), ["logprobs"]);
+    }
+
+    #[test]
+    fn test_registered_deployment_gets_reasoning_payload() {
+        let registry = CapabilityRegistry::new().with_model("prod-reasoner", crate::ModelCapabilities::reasoning(200_000));
+        let client = OpenAIClient::from_config(OpenAIConfig::new("sk-test").with_capabilities(registry)).unwrap();
+        let options = ChatOptions::new().temperature(0.2).max_tokens(500);
+
+        let (payload, adjusted) = client.build_chat_request("prod-reasoner", vec![Message::human("hi")], &options, false).unwrap();
+        assert_eq!(payload["max_completion_tokens"], 500);
+        assert!(payload.get("temperature").is_none());
+        assert_eq!(adjusted[0].option, "temperature");
+
+        // Unregistered, the same name is an ordinary chat model
+        let (payload, _) = OpenAIClient::new("sk-test")
+            .unwrap()
+            .build_chat_request("prod-reasoner", vec![Message::human("hi")], &options, false)
+            .unwrap();
+        assert_eq!(payload["max_tokens"], 500);
+    }
+
+    #[test]
+    fn test_sampling_parameters_sent() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let options = ChatOptions::new()
+            .stop(["\n\n", "END"])
+            .top_p(0.9)
+            .seed(42)
+            .presence_penalty(0.1)
+            .frequency_penalty(0.2);
+
+        let (payload, _) = client.build_chat_request("gpt-4o", vec![Message::human("hi")], &options, false).unwrap();
+        assert_eq!(payload["stop"], serde_json::json!(["\n\n", "END"]));
+        assert_eq!(payload["seed"], 42);
+        assert!(payload["top_p"].is_f64() && payload["presence_penalty"].is_f64() && payload["frequency_penalty"].is_f64());
+
+        // Reasoning models keep only the seed
+        let (payload, adjusted) = client.build_chat_request("o1", vec![Message::human("hi")], &options, false).unwrap();
+        assert_eq!(payload["seed"], 42);
+        assert!(payload.get("stop").is_none() && payload.get("top_p").is_none());
+        assert_eq!(adjusted.len(), 4);
+    }
+
+    #[test]
+    fn test_builder_applies_capability_policy() {
+        let client = OpenAIClient::new("sk-test").unwrap();
+        let options = ChatOptions::new().temperature(0.3).max_tokens(200);
+
+        let (payload, adjusted) = client.build_chat_request("gpt-5", vec![Message::human("hi")], &options, true).unwrap();
+        assert!(payload.get("temperature").is_none());
+        assert_eq!(payload["max_completion_tokens"], 200);
+        assert_eq!(adjusted.len(), 1);
+
+        let (payload, adjusted) = client.build_chat_request("gpt-4o", vec![Message::human("hi")], &options, true).unwrap();
+        assert_eq!(payload["max_tokens"], 200);
+        assert!(payload.get("temperature").is_some() && adjusted.is_empty());
+
+        let strict = options.strict_options(true);
+        assert!(client.build_chat_request("gpt-5", vec![Message::human("hi")], &strict, true).is_err());
+    }
+}
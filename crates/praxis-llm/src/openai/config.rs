@@ -0,0 +1,155 @@
+use crate::capabilities::CapabilityRegistry;
+use crate::http::ConnectionOptions;
+use crate::retry::RetryPolicy;
+use reqwest::header::HeaderMap;
+use std::time::Duration;
+
+/// Organization of clients whose config sets none
+pub const ORGANIZATION_ENV: &str = "OPENAI_ORG_ID";
+/// Project of clients whose config sets none
+pub const PROJECT_ENV: &str = "OPENAI_PROJECT_ID";
+
+/// Credentials and account scoping for `OpenAIClient`
+///
+/// `organization` and `project` are sent as the `OpenAI-Organization` and
+/// `OpenAI-Project` headers, so usage is billed to (and visible under) that
+/// project. Left unset, they default to `OPENAI_ORG_ID` and
+/// `OPENAI_PROJECT_ID`. `base_url` points the client at another OpenAI-compatible
+/// server, such as Ollama (`http://localhost:11434/v1`). `default_headers`
+/// go out with every request too, e.g. what a gateway in front of the API
+/// requires.
+#[derive(Clone, Default)]
+pub struct OpenAIConfig {
+    pub api_key: String,
+    pub organization: Option<String>,
+    pub project: Option<String>,
+    /// API root, up to and including the version (default: `https://api.openai.com/v1`)
+    pub base_url: Option<String>,
+    /// Resend requests that hit a 429 or a transient 5xx (unset = send once)
+    pub retry_policy: Option<RetryPolicy>,
+    /// Wait for a response (default: `http::DEFAULT_TIMEOUT`)
+    pub timeout: Option<Duration>,
+    /// Wait for a connection (default: `http::DEFAULT_CONNECT_TIMEOUT`)
+    pub connect_timeout: Option<Duration>,
+    /// Longest silence inside a stream (default: `http::DEFAULT_STREAM_IDLE_TIMEOUT`)
+    pub stream_idle_timeout: Option<Duration>,
+    /// Pool and keep-alive settings of the client's own `reqwest::Client`
+    pub connection: ConnectionOptions,
+    /// A `reqwest::Client` shared with other clients, used instead of
+    /// building one; `connect_timeout` and `connection` are then its own
+    pub http_client: Option<reqwest::Client>,
+    /// Sent with every request, replacing the client's own headers of the same name
+    pub default_headers: HeaderMap,
+    /// What each model accepts (default: the built-in table)
+    pub capabilities: Option<CapabilityRegistry>,
+    /// Model `/moderations` requests use (default: `omni-moderation-latest`)
+    pub moderation_model: Option<String>,
+}
+
+impl OpenAIConfig {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Fill an unset organization and project from `OPENAI_ORG_ID` and
+    /// `OPENAI_PROJECT_ID`, as `OpenAIClient::from_config` does
+    pub fn with_env_defaults(self) -> Self {
+        self.with_defaults_from(|name| std::env::var(name).ok())
+    }
+
+    pub(crate) fn with_defaults_from(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name| var(name).filter(|value| !value.is_empty());
+        self.organization = self.organization.or_else(|| var(ORGANIZATION_ENV));
+        self.project = self.project.or_else(|| var(PROJECT_ENV));
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_stream_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_connection_options(mut self, options: ConnectionOptions) -> Self {
+        self.connection = options;
+        self
+    }
+
+    /// Send requests through `client`, sharing its connection pool
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Look models up in `registry`, e.g. to declare deployment names
+    pub fn with_capabilities(mut self, registry: CapabilityRegistry) -> Self {
+        self.capabilities = Some(registry);
+        self
+    }
+
+    pub fn with_moderation_model(mut self, model: impl Into<String>) -> Self {
+        self.moderation_model = Some(model.into());
+        self
+    }
+
+    /// Add `headers` to the default headers
+    ///
+    /// From a config map: `HeaderMap::try_from(&HashMap<String, String>)`.
+    pub fn with_default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+}
+
+impl std::fmt::Debug for OpenAIConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAIConfig")
+            .field("api_key", &"[redacted]")
+            .field("organization", &self.organization)
+            .field("project", &self.project)
+            .field("base_url", &self.base_url)
+            .field("retry_policy", &self.retry_policy)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("stream_idle_timeout", &self.stream_idle_timeout)
+            .field("connection", &self.connection)
+            .field("shared_http_client", &self.http_client.is_some())
+            // Names only: gateway headers can carry credentials
+            .field("default_headers", &self.default_headers.keys().collect::<Vec<_>>())
+            .field("capabilities", &self.capabilities)
+            .field("moderation_model", &self.moderation_model)
+            .finish()
+    }
+}
@@ -1,12 +1,14 @@
 // OpenAI-specific implementations
 
 pub mod client;
+pub mod config;
 pub mod responses;
 
-pub use client::OpenAIClient;
+pub use client::{OpenAIClient, DEFAULT_MODERATION_MODEL, MAX_EMBEDDING_INPUTS};
+pub use config::{OpenAIConfig, ORGANIZATION_ENV, PROJECT_ENV};
 
 pub use responses::{
     ReasoningConfig, ReasoningEffort, SummaryMode,
-    ResponsesResponse, OutputItem, ResponseStreamChunk,
+    ResponsesResponse, OutputItem,
 };
 
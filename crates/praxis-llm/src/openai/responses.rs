@@ -1,19 +1,36 @@
 // OpenAI Responses API
 // https://platform.openai.com/docs/api-reference/responses
 
+use crate::types::{FunctionCall, ToolCall};
 use serde::{Deserialize, Serialize};
 
 /// Reasoning effort level
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ReasoningEffort {
+    /// As little reasoning as the model allows, for latency-sensitive calls
+    Minimal,
     Low,
     Medium,
     High,
 }
 
+impl std::str::FromStr for ReasoningEffort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minimal" => Ok(Self::Minimal),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            other => anyhow::bail!("unknown reasoning effort '{}' (expected minimal, low, medium or high)", other),
+        }
+    }
+}
+
 /// Summary mode for reasoning
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SummaryMode {
     Auto,
@@ -32,6 +49,10 @@ impl ReasoningConfig {
         Self { effort, summary }
     }
     
+    pub fn minimal() -> Self {
+        Self::new(ReasoningEffort::Minimal, SummaryMode::Auto)
+    }
+    
     pub fn low() -> Self {
         Self::new(ReasoningEffort::Low, SummaryMode::Auto)
     }
@@ -73,6 +94,16 @@ pub enum OutputItem {
         role: String,
         content: Vec<ContentItem>,
     },
+    /// Tool call; `call_id` is what the matching `function_call_output` refers to
+    FunctionCall {
+        id: String,
+        call_id: String,
+        name: String,
+        /// JSON-encoded arguments
+        arguments: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<String>,
+    },
 }
 
 /// Summary text for reasoning
@@ -103,139 +134,21 @@ pub struct Usage {
     pub total_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_tokens_details: Option<OutputTokensDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens_details: Option<InputTokensDetails>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OutputTokensDetails {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reasoning_tokens: Option<u32>,
+pub struct InputTokensDetails {
+    /// Input tokens served from the prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_tokens: Option<u32>,
 }
 
-// ============================================================================
-// STREAMING TYPES
-// ============================================================================
-
-/// Streaming chunk from /v1/responses (with stream=true)
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResponseStreamChunk {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub object: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub created_at: Option<i64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub model: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub output_index: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub delta: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub status: Option<String>,
-}
-
-/// Delta for streaming output
-/// Note: The Responses API sends different structures without explicit type tags
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OutputDelta {
-    /// For reasoning deltas - can be text or structured
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub summary: Option<serde_json::Value>,
-    
-    /// For message deltas
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub role: Option<String>,
-    
-    /// Content can be text or structured
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<serde_json::Value>,
-    
-    /// Direct text field (sometimes used)
+pub struct OutputTokensDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub text: Option<String>,
-    
-    /// Type indicator (if present)
-    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
-    pub delta_type: Option<String>,
-}
-
-impl ResponseStreamChunk {
-    /// Extract text content from reasoning delta
-    pub fn reasoning_text(&self) -> Option<String> {
-        let delta = self.delta.as_ref()?;
-        
-        // Try as direct string
-        if let Some(text) = delta.as_str() {
-            return Some(text.to_string());
-        }
-        
-        // Try as object
-        let obj = delta.as_object()?;
-        
-        // Try direct text field
-        if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
-            return Some(text.to_string());
-        }
-        
-        // Try summary field (can be array or object or string)
-        if let Some(summary) = obj.get("summary") {
-            // Try as array of objects with text field
-            if let Some(arr) = summary.as_array() {
-                for item in arr {
-                    if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                        return Some(text.to_string());
-                    }
-                }
-            }
-            // Try as direct string
-            if let Some(text) = summary.as_str() {
-                return Some(text.to_string());
-            }
-        }
-        
-        None
-    }
-    
-    /// Extract text content from message delta
-    pub fn message_text(&self) -> Option<String> {
-        let delta = self.delta.as_ref()?;
-        
-        // Try as direct string
-        if let Some(text) = delta.as_str() {
-            return Some(text.to_string());
-        }
-        
-        // Try as object
-        let obj = delta.as_object()?;
-        
-        // Try direct text field
-        if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
-            return Some(text.to_string());
-        }
-        
-        // Try content field (can be array or object or string)
-        if let Some(content) = obj.get("content") {
-            // Try as array of objects with text field
-            if let Some(arr) = content.as_array() {
-                for item in arr {
-                    if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                        return Some(text.to_string());
-                    }
-                }
-            }
-            // Try as direct string
-            if let Some(text) = content.as_str() {
-                return Some(text.to_string());
-            }
-        }
-        
-        None
-    }
-    
-    /// Check if this chunk indicates completion
-    pub fn is_done(&self) -> bool {
-        self.status.as_deref() == Some("completed")
-    }
+    pub reasoning_tokens: Option<u32>,
 }
 
 impl ResponsesResponse {
@@ -298,5 +211,59 @@ impl ResponsesResponse {
             Some(message_texts.join("\n"))
         }
     }
+    
+    /// Tool calls in the output, in order (None if there are none)
+    pub fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        let tool_calls: Vec<ToolCall> = self
+            .output
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::FunctionCall { call_id, name, arguments, .. } => Some(ToolCall {
+                    id: call_id.clone(),
+                    tool_type: "function".to_string(),
+                    function: FunctionCall {
+                        name: name.clone(),
+                        arguments: arguments.clone(),
+                    },
+                }),
+                _ => None,
+            })
+            .collect();
+        
+        if tool_calls.is_empty() {
+            None
+        } else {
+            Some(tool_calls)
+        }
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_call_output_items() {
+        let response: ResponsesResponse = serde_json::from_value(serde_json::json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1736337600,
+            "status": "completed",
+            "model": "gpt-5",
+            "output": [
+                { "id": "rs_1", "type": "reasoning", "summary": [] },
+                { "id": "fc_1", "type": "function_call", "status": "completed", "call_id": "call_1", "name": "get_weather", "arguments": "{\"city\":\"Lisbon\"}" }
+            ],
+            "usage": { "input_tokens": 10, "output_tokens": 20, "total_tokens": 30 }
+        }))
+        .unwrap();
+
+        let calls = response.tool_calls().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].function.name, "get_weather");
+        assert_eq!(calls[0].function.arguments, r#"{"city":"Lisbon"}"#);
+        assert_eq!(response.message_text(), None);
+    }
+}
@@ -0,0 +1,482 @@
+//! Record LLM calls and replay them offline
+//!
+//! `RecordingClient` wraps any client and appends each call it forwards to
+//! `<dir>/<run_id>.jsonl`, one [`RecordedCall`] per line: the request, and
+//! either the response, the streamed events or the error. Streamed events
+//! keep their offset from the start of the stream.
+//!
+//! Calls are filed under the run id set with [`with_run_id`]; the graph
+//! sets it around each LLM node, so one file holds one run. Calls made
+//! outside a run go to `unscoped.jsonl`.
+//!
+//! `ReplayClient` loads recordings and answers each call with the next
+//! recording of the same request ([`request_key`]), optionally with the
+//! recorded pacing. Errors replay as plain `anyhow` errors carrying the
+//! recorded message, so their original types (e.g. `RateLimited`) are lost.
+//!
+//! # Security
+//!
+//! Recordings hold full prompts, tool arguments and model output, like
+//! payload captures (see `capture`). Only record in controlled environments
+//! and delete recordings once the incident is reproduced.
+
+use crate::stats::RateLimitHeadroom;
+use crate::streaming::StreamEvent;
+use crate::traits::{ChatClient, ChatRequest, ChatResponse, LLMClient, ReasoningClient, ResponseOutput, ResponseRequest};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// File stem of calls made outside `with_run_id`
+pub const UNSCOPED_RUN: &str = "unscoped";
+
+tokio::task_local! {
+    static RUN_ID: String;
+}
+
+/// Run `future` with the LLM calls it makes recorded under `run_id`
+pub async fn with_run_id<F: Future>(run_id: impl Into<String>, future: F) -> F::Output {
+    RUN_ID.scope(run_id.into(), future).await
+}
+
+fn current_run_id() -> String {
+    RUN_ID.try_with(Clone::clone).unwrap_or_else(|_| UNSCOPED_RUN.to_string())
+}
+
+/// Client method a call went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    Chat,
+    ChatStream,
+    Reason,
+    ReasonStream,
+}
+
+/// One line of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub run_id: String,
+    pub kind: CallKind,
+    /// `request_key` of `request`
+    pub key: String,
+    pub recorded_at: DateTime<Utc>,
+    /// The `ChatRequest` or `ResponseRequest`
+    pub request: Value,
+    /// Non-streaming calls: the `ChatResponse` or `ResponseOutput`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+    /// Streaming calls: everything the stream yielded, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<RecordedEvent>,
+    /// Error the call returned instead of a response or stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RecordedCall {
+    fn new(kind: CallKind, request: &impl Serialize) -> Self {
+        let request = serde_json::to_value(request).unwrap_or_default();
+        Self {
+            run_id: current_run_id(),
+            kind,
+            key: request_key(kind, &request),
+            recorded_at: Utc::now(),
+            request,
+            response: None,
+            events: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+/// A streamed item and when it arrived
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Time since the stream was returned
+    pub offset_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<StreamEvent>,
+    /// Error the stream yielded instead of an event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Key matching replayed calls to recordings: a 64-bit FNV-1a hash of the
+/// call kind and the request JSON, in hex
+///
+/// Any change to the request (model, messages, options) changes the key.
+pub fn request_key(kind: CallKind, request: &Value) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let kind = serde_json::to_string(&kind).unwrap_or_default();
+    let request = request.to_string();
+    let hash = kind
+        .bytes()
+        .chain(request.bytes())
+        .fold(OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+    format!("{:016x}", hash)
+}
+
+/// Appends calls to per-run files; failures are logged, never returned
+struct Recorder {
+    dir: PathBuf,
+    /// Keeps concurrent calls of one run from interleaving lines
+    write_lock: Mutex<()>,
+}
+
+impl Recorder {
+    fn write(&self, call: &RecordedCall) {
+        let stem: String = call
+            .run_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let path = self.dir.join(format!("{}.jsonl", stem));
+        let mut line = match serde_json::to_string(call) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize recorded LLM call: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let result = std::fs::create_dir_all(&self.dir).and_then(|_| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?
+                .write_all(line.as_bytes())
+        });
+        if let Err(e) = result {
+            tracing::warn!("Failed to record LLM call to {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// A streaming call being recorded, written once its stream is dropped
+struct StreamRecording {
+    recorder: Arc<Recorder>,
+    call: RecordedCall,
+    started: Instant,
+}
+
+impl Drop for StreamRecording {
+    fn drop(&mut self) {
+        self.recorder.write(&self.call);
+    }
+}
+
+fn record_stream(mut recording: StreamRecording, stream: EventStream) -> EventStream {
+    Box::pin(stream.map(move |item| {
+        let offset_ms = recording.started.elapsed().as_millis() as u64;
+        recording.call.events.push(match &item {
+            Ok(event) => RecordedEvent { offset_ms, event: Some(event.clone()), error: None },
+            Err(e) => RecordedEvent { offset_ms, event: None, error: Some(e.to_string()) },
+        });
+        item
+    }))
+}
+
+/// Client wrapper recording every call to `<dir>/<run_id>.jsonl`
+///
+/// Streaming calls are written when their stream is dropped, with whatever
+/// the consumer read by then.
+pub struct RecordingClient<C> {
+    inner: C,
+    recorder: Arc<Recorder>,
+}
+
+impl<C> RecordingClient<C> {
+    pub fn new(inner: C, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            recorder: Arc::new(Recorder { dir: dir.into(), write_lock: Mutex::new(()) }),
+        }
+    }
+
+    /// Record a non-streaming call's outcome and pass it on
+    fn finish<T: Serialize>(&self, mut call: RecordedCall, result: Result<T>) -> Result<T> {
+        match &result {
+            Ok(response) => call.response = serde_json::to_value(response).ok(),
+            Err(e) => call.error = Some(e.to_string()),
+        }
+        self.recorder.write(&call);
+        result
+    }
+
+    /// Record a streaming call, wrapping its stream when it started
+    fn finish_stream(&self, mut call: RecordedCall, result: Result<EventStream>) -> Result<EventStream> {
+        match result {
+            Ok(stream) => {
+                let recording = StreamRecording { recorder: Arc::clone(&self.recorder), call, started: Instant::now() };
+                Ok(record_stream(recording, stream))
+            }
+            Err(e) => {
+                call.error = Some(e.to_string());
+                self.recorder.write(&call);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ChatClient> ChatClient for RecordingClient<C> {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let call = RecordedCall::new(CallKind::Chat, &request);
+        self.finish(call, self.inner.chat(request).await)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let call = RecordedCall::new(CallKind::ChatStream, &request);
+        self.finish_stream(call, self.inner.chat_stream(request).await)
+    }
+}
+
+#[async_trait]
+impl<C: ReasoningClient> ReasoningClient for RecordingClient<C> {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        let call = RecordedCall::new(CallKind::Reason, &request);
+        self.finish(call, self.inner.reason(request).await)
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        let call = RecordedCall::new(CallKind::ReasonStream, &request);
+        self.finish_stream(call, self.inner.reason_stream(request).await)
+    }
+}
+
+impl<C: LLMClient> LLMClient for RecordingClient<C> {
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        self.inner.rate_limit_headroom()
+    }
+}
+
+/// Client answering calls from recordings instead of a provider
+///
+/// Each call takes the next unused recording with its request key, so
+/// repeated identical requests replay in recorded order. A call without
+/// one fails.
+pub struct ReplayClient {
+    calls: Mutex<HashMap<String, VecDeque<RecordedCall>>>,
+    timing: bool,
+}
+
+impl ReplayClient {
+    pub fn new(calls: impl IntoIterator<Item = RecordedCall>) -> Self {
+        let mut by_key: HashMap<String, VecDeque<RecordedCall>> = HashMap::new();
+        for call in calls {
+            by_key.entry(call.key.clone()).or_default().push_back(call);
+        }
+        Self { calls: Mutex::new(by_key), timing: false }
+    }
+
+    /// Load a recording file, or every `.jsonl` file of a directory in
+    /// name order
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let files = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.extension().is_some_and(|ext| ext == "jsonl"))
+                .collect();
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut calls = Vec::new();
+        for file in files {
+            let text = std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+            for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                let call: RecordedCall = serde_json::from_str(line)
+                    .with_context(|| format!("Invalid recorded call at {}:{}", file.display(), number + 1))?;
+                calls.push(call);
+            }
+        }
+        Ok(Self::new(calls))
+    }
+
+    /// Wait out each event's recorded offset before yielding it
+    /// (default: replay as fast as the consumer reads)
+    pub fn with_timing(mut self, timing: bool) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Recordings no call has used yet
+    pub fn remaining(&self) -> usize {
+        self.calls.lock().unwrap().values().map(VecDeque::len).sum()
+    }
+
+    fn take(&self, kind: CallKind, request: &impl Serialize) -> Result<RecordedCall> {
+        let key = request_key(kind, &serde_json::to_value(request)?);
+        let call = self
+            .calls
+            .lock()
+            .unwrap()
+            .get_mut(&key)
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| anyhow::anyhow!("No recorded {:?} call left for request {}", kind, key))?;
+        match call.error {
+            Some(error) => Err(anyhow::anyhow!(error)),
+            None => Ok(call),
+        }
+    }
+
+    fn response<T: serde::de::DeserializeOwned>(call: RecordedCall) -> Result<T> {
+        let response = call.response.context("Recorded call has no response")?;
+        serde_json::from_value(response).context("Recorded response doesn't match the client's type")
+    }
+
+    fn stream(&self, call: RecordedCall) -> EventStream {
+        let timing = self.timing;
+        Box::pin(async_stream::stream! {
+            let started = tokio::time::Instant::now();
+            for recorded in call.events {
+                if timing {
+                    tokio::time::sleep_until(started + Duration::from_millis(recorded.offset_ms)).await;
+                }
+                match (recorded.event, recorded.error) {
+                    (Some(event), _) => yield Ok(event),
+                    (None, error) => yield Err(anyhow::anyhow!(error.unwrap_or_else(|| "Recorded stream error".to_string()))),
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl ChatClient for ReplayClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        Self::response(self.take(CallKind::Chat, &request)?)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        Ok(self.stream(self.take(CallKind::ChatStream, &request)?))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for ReplayClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        Self::response(self.take(CallKind::Reason, &request)?)
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        Ok(self.stream(self.take(CallKind::ReasonStream, &request)?))
+    }
+}
+
+impl LLMClient for ReplayClient {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::MockLLMClient;
+    use crate::Message;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "praxis-recording-{}-{}-{}",
+            name,
+            std::process::id(),
+            DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn request(text: &str) -> ChatRequest {
+        ChatRequest::new("gpt-4o-mini", vec![Message::human(text)])
+    }
+
+    #[tokio::test]
+    async fn test_streams_record_per_run_and_replay() {
+        let dir = temp_dir("stream");
+        let llm = RecordingClient::new(
+            MockLLMClient::new().then_tool_call("call_1", "get_weather", "{}").then_message("Sunny"),
+            &dir,
+        );
+
+        with_run_id("run-1", async {
+            let first: Vec<_> = llm.chat_stream(request("Weather?")).await.unwrap().collect().await;
+            assert_eq!(first.len(), 2);
+        })
+        .await;
+        let _: Vec<_> = llm.chat_stream(request("Again?")).await.unwrap().collect().await;
+
+        let recorded = std::fs::read_to_string(dir.join("run-1.jsonl")).unwrap();
+        assert_eq!(recorded.lines().count(), 1);
+        assert!(dir.join(format!("{}.jsonl", UNSCOPED_RUN)).exists());
+
+        let replay = ReplayClient::load(&dir).unwrap();
+        assert_eq!(replay.remaining(), 2);
+        let events: Vec<_> = replay.chat_stream(request("Weather?")).await.unwrap().collect().await;
+        assert!(matches!(
+            events[0].as_ref().unwrap(),
+            StreamEvent::ToolCall { name: Some(name), .. } if name == "get_weather"
+        ));
+        assert!(matches!(events[1].as_ref().unwrap(), StreamEvent::Done { .. }));
+        // Used up, and a request that was never recorded has no match
+        assert!(replay.chat_stream(request("Weather?")).await.is_err());
+        assert!(replay.chat(request("Weather?")).await.is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_responses_and_errors_replay() {
+        let dir = temp_dir("chat");
+        let llm = RecordingClient::new(MockLLMClient::new().then_message("Hi there").then_error("provider down"), &dir);
+
+        let response = llm.chat(request("Hi")).await.unwrap();
+        assert!(llm.chat(request("Hi again")).await.is_err());
+
+        let replay = ReplayClient::load(dir.join(format!("{}.jsonl", UNSCOPED_RUN))).unwrap();
+        let replayed = replay.chat(request("Hi")).await.unwrap();
+        assert_eq!(replayed.content, response.content);
+        assert_eq!(replayed.finish_reason.as_deref(), Some("stop"));
+        let error = replay.chat(request("Hi again")).await.unwrap_err();
+        assert_eq!(error.to_string(), "provider down");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_timing_replays_recorded_offsets() {
+        let request = request("Hi");
+        let mut call = RecordedCall::new(CallKind::ChatStream, &request);
+        call.events = vec![
+            RecordedEvent { offset_ms: 0, event: Some(StreamEvent::Message { content: "Hi".to_string(), choice_index: None, logprobs: None }), error: None },
+            RecordedEvent { offset_ms: 60, event: Some(StreamEvent::Done { finish_reason: None, choice_index: None }), error: None },
+        ];
+
+        let replay = ReplayClient::new(vec![call]).with_timing(true);
+        let started = Instant::now();
+        let events: Vec<_> = replay.chat_stream(request).await.unwrap().collect().await;
+        assert_eq!(events.len(), 2);
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+}
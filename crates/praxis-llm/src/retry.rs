@@ -0,0 +1,179 @@
+//! Retries of failed provider requests
+//!
+//! A `RetryPolicy` resends a request that failed with a 429, a transient 5xx,
+//! a connection error or no response within the timeout, waiting with
+//! exponential backoff in between. A 429 that says how long to wait
+//! (`retry-after-ms` / `retry-after`) is retried after that wait instead,
+//! unless it is longer than `max_delay`: then the 429 is returned for the
+//! caller to handle.
+//!
+//! Only sending is retried. Streaming calls return their stream after a
+//! successful response, so a failure mid-stream is never retried and no
+//! token is delivered twice.
+
+use crate::error::{RateLimited, TimedOut};
+use crate::http::send_within;
+use anyhow::Result;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How often and how patiently a client resends failed requests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts per request, the first one included (1 = no retries)
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each one after
+    #[serde(rename = "base_delay_ms", with = "duration_ms")]
+    pub base_delay: Duration,
+    /// Longest wait between attempts
+    #[serde(rename = "max_delay_ms", with = "duration_ms")]
+    pub max_delay: Duration,
+    /// Randomize each backoff wait between half and all of it
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Up to `max_attempts` attempts with the default backoff
+    pub fn new(max_attempts: u32) -> Self {
+        Self { max_attempts, ..Self::default() }
+    }
+
+    /// Send every request once
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Backoff before retry number `retry` (1 for the first retry)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        let half = delay / 2;
+        let spread = half.as_millis() as u64;
+        let random = RandomState::new().build_hasher().finish();
+        half + Duration::from_millis(if spread == 0 { 0 } else { random % (spread + 1) })
+    }
+
+    /// Send the request built by `request`, retrying per the policy
+    ///
+    /// Each attempt waits up to `timeout` for a response. The last response
+    /// is returned whatever its status, so callers turn error statuses into
+    /// errors as they would without retries.
+    pub(crate) async fn send(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder,
+        timeout: Duration,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 1;
+        loop {
+            let retries_left = attempt < self.max_attempts;
+            let wait = match send_within(request(), timeout).await {
+                Ok(response) if retries_left && is_transient(response.status()) => {
+                    let retry_after = (response.status() == StatusCode::TOO_MANY_REQUESTS)
+                        .then(|| RateLimited::from_headers(response.headers(), "").retry_after)
+                        .flatten();
+                    match retry_after {
+                        Some(wait) if wait > self.max_delay => return Ok(response),
+                        Some(wait) => wait,
+                        None => self.backoff(attempt),
+                    }
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if retries_left && is_transient_error(&e) => self.backoff(attempt),
+                Err(e) => return Err(e),
+            };
+            tracing::warn!("Provider request failed (attempt {}/{}), retrying in {:?}", attempt, self.max_attempts, wait);
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Statuses worth retrying: rate limits and overloaded or unreachable upstreams
+fn is_transient(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Send failures worth retrying: no connection, or no response in time
+fn is_transient_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<TimedOut>().is_some()
+        || error
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_timeout())
+}
+
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_millis(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max_delay() {
+        let policy = RetryPolicy::new(10)
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(350))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+
+        let jittered = policy.with_jitter(true);
+        for _ in 0..20 {
+            let wait = jittered.backoff(2);
+            assert!(wait >= Duration::from_millis(100) && wait <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_policy_reads_millisecond_fields() {
+        let policy: RetryPolicy = serde_json::from_str(r#"{"max_attempts": 5, "base_delay_ms": 250}"#).unwrap();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.base_delay, Duration::from_millis(250));
+        assert_eq!(policy.max_delay, RetryPolicy::default().max_delay);
+    }
+}
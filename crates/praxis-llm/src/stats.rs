@@ -0,0 +1,408 @@
+//! Rolling per-provider health: success rate, latency percentiles,
+//! rate-limit headroom and the last error
+//!
+//! `StatsRecordingClient` wraps a provider client and records every call
+//! into a `StatsRecorder`, a ring buffer over the last `window` calls.
+//! Everything lives in memory and starts over on restart; the numbers are
+//! meant for a health page, e.g. to decide when to change provider order.
+
+use crate::streaming::StreamEvent;
+use crate::traits::{ChatClient, ChatRequest, ChatResponse, LLMClient, ReasoningClient, ResponseOutput, ResponseRequest};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// Calls kept per provider unless configured otherwise
+pub const DEFAULT_STATS_WINDOW: usize = 200;
+
+/// What the provider's rate limit still allows, from its latest response
+///
+/// Read from `x-ratelimit-{limit,remaining}-{requests,tokens}`; providers
+/// that don't send some of these leave them None.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitHeadroom {
+    pub remaining_requests: Option<u64>,
+    pub limit_requests: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    pub limit_tokens: Option<u64>,
+}
+
+impl RateLimitHeadroom {
+    /// None when the response carries no rate-limit headers
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let headroom = Self {
+            remaining_requests: header("x-ratelimit-remaining-requests"),
+            limit_requests: header("x-ratelimit-limit-requests"),
+            remaining_tokens: header("x-ratelimit-remaining-tokens"),
+            limit_tokens: header("x-ratelimit-limit-tokens"),
+        };
+        (headroom != Self::default()).then_some(headroom)
+    }
+}
+
+/// What a provider's response headers say about the call
+///
+/// `request_id` is what provider support asks for when a call went wrong.
+/// Read from OpenAI's `x-request-id` and `x-ratelimit-*` headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratelimit_remaining_requests: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratelimit_remaining_tokens: Option<u64>,
+    /// Until the token limit resets, as sent (e.g. `6m0s`); the request
+    /// limit's when the token one isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratelimit_reset: Option<String>,
+}
+
+impl ResponseMetadata {
+    /// None when the response carries none of these headers
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let number = |name: &str| header(name).and_then(|value| value.parse::<u64>().ok());
+        let metadata = Self {
+            request_id: header("x-request-id").map(String::from),
+            ratelimit_remaining_requests: number("x-ratelimit-remaining-requests"),
+            ratelimit_remaining_tokens: number("x-ratelimit-remaining-tokens"),
+            ratelimit_reset: header("x-ratelimit-reset-tokens")
+                .or_else(|| header("x-ratelimit-reset-requests"))
+                .map(String::from),
+        };
+        (metadata != Self::default()).then_some(metadata)
+    }
+}
+
+/// Most recent failed call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderError {
+    pub message: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Snapshot of one provider's recent calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderStats {
+    pub provider: String,
+    /// Calls in the window
+    pub calls: usize,
+    pub failures: usize,
+    /// Share of calls in the window that succeeded (None before the first call)
+    pub success_rate: Option<f64>,
+    pub p50_latency_ms: Option<u64>,
+    pub p95_latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitHeadroom>,
+    /// Kept after it leaves the window, so a recovered provider still shows
+    /// what last went wrong
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<ProviderError>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    ok: bool,
+    latency: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    samples: VecDeque<Sample>,
+    last_error: Option<ProviderError>,
+}
+
+/// Ring buffer of a provider's last `window` calls
+#[derive(Debug)]
+pub struct StatsRecorder {
+    provider: String,
+    window: usize,
+    state: Mutex<Window>,
+}
+
+impl StatsRecorder {
+    pub fn new(provider: impl Into<String>, window: usize) -> Self {
+        Self {
+            provider: provider.into(),
+            window: window.max(1),
+            state: Mutex::new(Window::default()),
+        }
+    }
+
+    pub fn record_success(&self, latency: Duration) {
+        self.push(Sample { ok: true, latency }, None);
+    }
+
+    pub fn record_failure(&self, latency: Duration, error: impl fmt::Display) {
+        let error = ProviderError {
+            message: error.to_string(),
+            at: Utc::now(),
+        };
+        self.push(Sample { ok: false, latency }, Some(error));
+    }
+
+    fn push(&self, sample: Sample, error: Option<ProviderError>) {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.samples.len() == self.window {
+            state.samples.pop_front();
+        }
+        state.samples.push_back(sample);
+        if error.is_some() {
+            state.last_error = error;
+        }
+    }
+
+    /// Stats over the window (`rate_limit` is left to the client)
+    pub fn stats(&self) -> ProviderStats {
+        let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let calls = state.samples.len();
+        let failures = state.samples.iter().filter(|sample| !sample.ok).count();
+        let mut latencies: Vec<Duration> = state.samples.iter().map(|sample| sample.latency).collect();
+        latencies.sort_unstable();
+
+        ProviderStats {
+            provider: self.provider.clone(),
+            calls,
+            failures,
+            success_rate: (calls > 0).then(|| (calls - failures) as f64 / calls as f64),
+            p50_latency_ms: percentile_ms(&latencies, 50),
+            p95_latency_ms: percentile_ms(&latencies, 95),
+            rate_limit: None,
+            last_error: state.last_error.clone(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of `sorted`, in whole milliseconds
+fn percentile_ms(sorted: &[Duration], percent: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1].as_millis() as u64)
+}
+
+/// Client wrapper recording every call's outcome and latency
+///
+/// Latency runs to the end of a stream; a stream is a failure if any of its
+/// events is an error, and isn't counted if it is dropped before the end.
+pub struct StatsRecordingClient {
+    inner: Arc<dyn LLMClient>,
+    recorder: Arc<StatsRecorder>,
+}
+
+impl StatsRecordingClient {
+    pub fn new(inner: Arc<dyn LLMClient>, provider: impl Into<String>) -> Self {
+        Self {
+            inner,
+            recorder: Arc::new(StatsRecorder::new(provider, DEFAULT_STATS_WINDOW)),
+        }
+    }
+
+    /// Keep the last `window` calls instead of `DEFAULT_STATS_WINDOW`
+    ///
+    /// Starts a fresh recorder; set it before the first call.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.recorder = Arc::new(StatsRecorder::new(self.recorder.provider.clone(), window));
+        self
+    }
+
+    /// Current stats, with the headroom the provider last reported
+    pub fn stats(&self) -> ProviderStats {
+        ProviderStats {
+            rate_limit: self.inner.rate_limit_headroom(),
+            ..self.recorder.stats()
+        }
+    }
+
+    fn record<T>(&self, started: Instant, result: &Result<T>) {
+        match result {
+            Ok(_) => self.recorder.record_success(started.elapsed()),
+            Err(error) => self.recorder.record_failure(started.elapsed(), format!("{:#}", error)),
+        }
+    }
+
+    fn track(&self, started: Instant, opened: Result<EventStream>) -> Result<EventStream> {
+        let stream = match opened {
+            Ok(stream) => stream,
+            Err(error) => {
+                self.recorder.record_failure(started.elapsed(), format!("{:#}", error));
+                return Err(error);
+            }
+        };
+        let recorder = Arc::clone(&self.recorder);
+        Ok(Box::pin(async_stream::stream! {
+            let mut stream = stream;
+            let mut failed = false;
+            while let Some(event) = stream.next().await {
+                if let Err(error) = &event {
+                    if !failed {
+                        recorder.record_failure(started.elapsed(), format!("{:#}", error));
+                        failed = true;
+                    }
+                }
+                yield event;
+            }
+            if !failed {
+                recorder.record_success(started.elapsed());
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl ChatClient for StatsRecordingClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let started = Instant::now();
+        let result = self.inner.chat(request).await;
+        self.record(started, &result);
+        result
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        let started = Instant::now();
+        self.track(started, self.inner.chat_stream(request).await)
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for StatsRecordingClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        let started = Instant::now();
+        let result = self.inner.reason(request).await;
+        self.record(started, &result);
+        result
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        let started = Instant::now();
+        self.track(started, self.inner.reason_stream(request).await)
+    }
+}
+
+impl LLMClient for StatsRecordingClient {
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        self.inner.rate_limit_headroom()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_percentiles_use_nearest_rank() {
+        let recorder = StatsRecorder::new("openai", 100);
+        // Recorded out of order: 1..=100 ms
+        for millis in (1..=100).rev() {
+            recorder.record_success(ms(millis));
+        }
+
+        let stats = recorder.stats();
+        assert_eq!(stats.p50_latency_ms, Some(50));
+        assert_eq!(stats.p95_latency_ms, Some(95));
+
+        let small = StatsRecorder::new("openai", 100);
+        for millis in [30, 10, 20] {
+            small.record_success(ms(millis));
+        }
+        let stats = small.stats();
+        assert_eq!(stats.p50_latency_ms, Some(20));
+        assert_eq!(stats.p95_latency_ms, Some(30));
+    }
+
+    #[test]
+    fn test_window_drops_oldest_calls_but_keeps_last_error() {
+        let recorder = StatsRecorder::new("openai", 4);
+        recorder.record_failure(ms(900), "503 Service Unavailable");
+        recorder.record_failure(ms(800), "timed out");
+        for _ in 0..3 {
+            recorder.record_success(ms(100));
+        }
+
+        let stats = recorder.stats();
+        assert_eq!((stats.calls, stats.failures), (4, 1));
+        assert_eq!(stats.success_rate, Some(0.75));
+        assert_eq!(stats.p95_latency_ms, Some(800));
+
+        recorder.record_success(ms(100));
+        let stats = recorder.stats();
+        assert_eq!((stats.failures, stats.success_rate), (0, Some(1.0)));
+        assert_eq!(stats.last_error.unwrap().message, "timed out");
+    }
+
+    #[test]
+    fn test_empty_window_has_no_rates() {
+        let stats = StatsRecorder::new("openai", 10).stats();
+        assert_eq!(stats.calls, 0);
+        assert_eq!(stats.success_rate, None);
+        assert_eq!(stats.p50_latency_ms, None);
+    }
+
+    #[test]
+    fn test_headroom_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateLimitHeadroom::from_headers(&headers), None);
+
+        headers.insert("x-ratelimit-remaining-requests", HeaderValue::from_static("498"));
+        headers.insert("x-ratelimit-limit-requests", HeaderValue::from_static("500"));
+        headers.insert("x-ratelimit-remaining-tokens", HeaderValue::from_static("29000"));
+        assert_eq!(
+            RateLimitHeadroom::from_headers(&headers),
+            Some(RateLimitHeadroom {
+                remaining_requests: Some(498),
+                limit_requests: Some(500),
+                remaining_tokens: Some(29000),
+                limit_tokens: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_metadata_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(ResponseMetadata::from_headers(&headers), None);
+
+        headers.insert("x-request-id", HeaderValue::from_static("req_123"));
+        headers.insert("x-ratelimit-remaining-tokens", HeaderValue::from_static("29000"));
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("120ms"));
+        headers.insert("x-ratelimit-reset-tokens", HeaderValue::from_static("6m0s"));
+        assert_eq!(
+            ResponseMetadata::from_headers(&headers),
+            Some(ResponseMetadata {
+                request_id: Some("req_123".to_string()),
+                ratelimit_remaining_requests: None,
+                ratelimit_remaining_tokens: Some(29000),
+                ratelimit_reset: Some("6m0s".to_string()),
+            })
+        );
+    }
+}
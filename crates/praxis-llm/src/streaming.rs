@@ -1,24 +1,65 @@
 use anyhow::Result;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
 
 use crate::buffer_utils::{SseLineParser, parse_sse_stream};
+use crate::capabilities::OptionAdjusted;
+use crate::stats::ResponseMetadata;
+use crate::traits::TokenLogprob;
 
 pub use crate::buffer_utils::{CircularLineBuffer, EventBatcher};
 
-use crate::openai::ResponseStreamChunk;
+
+/// What a reasoning delta holds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningKind {
+    /// The model's own reasoning text
+    #[default]
+    Raw,
+    /// A summary of it, written for display (OpenAI reasoning summaries,
+    /// Gemini thought summaries)
+    Summary,
+}
+
+impl ReasoningKind {
+    pub fn is_raw(&self) -> bool {
+        *self == Self::Raw
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum StreamEvent {
     Reasoning {
         content: String,
+        #[serde(default, skip_serializing_if = "ReasoningKind::is_raw")]
+        kind: ReasoningKind,
+        /// Provider id of the reasoning output item (Responses API `rs_...`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        item_id: Option<String>,
+    },
+    
+    /// Provider summary of a finished reasoning item
+    ReasoningSummary {
+        item_id: String,
+        text: String,
     },
     
     Message {
         content: String,
+        /// Choice the text belongs to when the request asked for several
+        /// (`ChatOptions::n`); None for the first, and for single-choice APIs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        choice_index: Option<u32>,
+        /// Log probabilities of the delta's tokens, when the request asked
+        /// for them (`ChatOptions::logprobs`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        logprobs: Option<Vec<TokenLogprob>>,
     },
     
     ToolCall {
@@ -31,9 +72,43 @@ pub enum StreamEvent {
         arguments: Option<String>,
     },
     
+    /// Tokens the call used, reported once near the end of the stream,
+    /// before `Done`
+    Usage {
+        input_tokens: u32,
+        output_tokens: u32,
+        total_tokens: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reasoning_tokens: Option<u32>,
+        /// Input tokens read from the provider's prompt cache
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cached_tokens: Option<u32>,
+    },
+    
     Done {
         #[serde(skip_serializing_if = "Option::is_none")]
         finish_reason: Option<String>,
+        /// Choice that finished when the request asked for several
+        /// (`ChatOptions::n`); None for the first, and for single-choice APIs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        choice_index: Option<u32>,
+    },
+    
+    /// First event of a stream whose request had options dropped
+    OptionsAdjusted {
+        adjustments: Vec<OptionAdjusted>,
+    },
+    
+    /// What the response headers said (request id, rate limits), sent
+    /// before the first delta by providers that have them
+    ResponseMetadata {
+        metadata: ResponseMetadata,
+    },
+    
+    /// Chunk of spoken output, in the format `ChatOptions::audio` asked
+    /// for; its transcript streams as `Message` text
+    Audio {
+        data_base64: String,
     },
 }
 
@@ -44,6 +119,36 @@ pub struct ChatStreamChunk {
     pub created: i64,
     pub model: String,
     pub choices: Vec<StreamChoice>,
+    /// Only on the last chunk, which has no choices, when the request set
+    /// `stream_options.include_usage`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<StreamUsage>,
+}
+
+/// Token counts of a streamed chat completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<CompletionTokensDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+/// Breakdown of a chat completion's prompt tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTokensDetails {
+    /// Prompt tokens served from OpenAI's prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionTokensDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +156,15 @@ pub struct StreamChoice {
     pub index: u32,
     pub delta: Delta,
     pub finish_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<ChoiceLogprobs>,
+}
+
+/// `logprobs` of a chat choice or streamed delta
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChoiceLogprobs {
+    #[serde(default)]
+    pub content: Option<Vec<TokenLogprob>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +172,19 @@ pub struct Delta {
     pub role: Option<String>,
     pub content: Option<String>,
     pub tool_calls: Option<Vec<ToolCallDelta>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioDelta>,
+}
+
+/// Fragment of an audio answer: base64 audio, its transcript, or both
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,17 +216,32 @@ impl ChatStreamChunk {
             .is_some()
     }
     
+    /// Text deltas and finish reasons of every choice, tagged with their
+    /// index; audio and tool calls of the first choice only
     fn to_stream_events(&self) -> Vec<StreamEvent> {
         let mut events = Vec::new();
         
-        if let Some(choice) = self.choices.first() {
+        for choice in &self.choices {
             if let Some(content) = &choice.delta.content {
                 if !content.is_empty() {
                     events.push(StreamEvent::Message {
                         content: content.clone(),
+                        choice_index: (choice.index != 0).then_some(choice.index),
+                        logprobs: choice.logprobs.as_ref().and_then(|logprobs| logprobs.content.clone()),
                     });
                 }
             }
+        }
+        
+        if let Some(choice) = self.choices.iter().find(|choice| choice.index == 0) {
+            if let Some(audio) = &choice.delta.audio {
+                if let Some(transcript) = audio.transcript.as_ref().filter(|text| !text.is_empty()) {
+                    events.push(StreamEvent::Message { content: transcript.clone(), choice_index: None, logprobs: None });
+                }
+                if let Some(data) = audio.data.as_ref().filter(|data| !data.is_empty()) {
+                    events.push(StreamEvent::Audio { data_base64: data.clone() });
+                }
+            }
             
             if let Some(tool_calls) = &choice.delta.tool_calls {
                 for tc in tool_calls {
@@ -112,19 +254,33 @@ impl ChatStreamChunk {
                 }
             }
             
+        }
+        
+        for choice in &self.choices {
             if let Some(finish_reason) = &choice.finish_reason {
                 events.push(StreamEvent::Done {
                     finish_reason: Some(finish_reason.clone()),
+                    choice_index: (choice.index != 0).then_some(choice.index),
                 });
             }
         }
         
+        if let Some(usage) = &self.usage {
+            events.push(StreamEvent::Usage {
+                input_tokens: usage.prompt_tokens,
+                output_tokens: usage.completion_tokens,
+                total_tokens: usage.total_tokens,
+                reasoning_tokens: usage.completion_tokens_details.as_ref().and_then(|d| d.reasoning_tokens),
+                cached_tokens: usage.prompt_tokens_details.as_ref().and_then(|d| d.cached_tokens),
+            });
+        }
+        
         events
     }
 }
 
 /// Chat SSE parser (Strategy Pattern)
-struct ChatSseParser;
+pub(crate) struct ChatSseParser;
 
 impl SseLineParser for ChatSseParser {
     fn parse_data_line(&self, data: &str) -> Result<Vec<StreamEvent>> {
@@ -135,57 +291,242 @@ impl SseLineParser for ChatSseParser {
     }
 }
 
+/// What a Responses API stream event becomes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseEventKind {
+    ReasoningDelta,
+    ReasoningSummaryDelta,
+    TextDelta,
+    Completed,
+    Incomplete,
+    Failed,
+    /// New output item; function calls open with their call id and name
+    OutputItemAdded,
+    /// Argument fragment of the function call at `output_index`
+    FunctionCallArgumentsDelta,
+    /// Finished output item; reasoning items carry their summary
+    OutputItemDone,
+    /// Known event that carries nothing we stream (item/part lifecycle, etc.)
+    Ignored,
+}
+
+/// Responses stream event types, keyed by the chunk's `type`
+///
+/// The SSE `event:` line repeats the same name, so dispatching on the data
+/// line alone is enough. Types missing here are logged once and skipped.
+const RESPONSE_EVENT_KINDS: &[(&str, ResponseEventKind)] = &[
+    ("response.reasoning_summary_text.delta", ResponseEventKind::ReasoningSummaryDelta),
+    ("response.reasoning_text.delta", ResponseEventKind::ReasoningDelta),
+    ("response.output_text.delta", ResponseEventKind::TextDelta),
+    ("response.refusal.delta", ResponseEventKind::TextDelta),
+    ("response.completed", ResponseEventKind::Completed),
+    ("response.incomplete", ResponseEventKind::Incomplete),
+    ("response.failed", ResponseEventKind::Failed),
+    ("error", ResponseEventKind::Failed),
+    ("response.created", ResponseEventKind::Ignored),
+    ("response.queued", ResponseEventKind::Ignored),
+    ("response.in_progress", ResponseEventKind::Ignored),
+    ("response.output_item.added", ResponseEventKind::OutputItemAdded),
+    ("response.output_item.done", ResponseEventKind::OutputItemDone),
+    ("response.content_part.added", ResponseEventKind::Ignored),
+    ("response.content_part.done", ResponseEventKind::Ignored),
+    ("response.output_text.done", ResponseEventKind::Ignored),
+    ("response.output_text.annotation.added", ResponseEventKind::Ignored),
+    ("response.refusal.done", ResponseEventKind::Ignored),
+    ("response.reasoning_summary_part.added", ResponseEventKind::Ignored),
+    ("response.reasoning_summary_part.done", ResponseEventKind::Ignored),
+    ("response.reasoning_summary_text.done", ResponseEventKind::Ignored),
+    ("response.reasoning_text.done", ResponseEventKind::Ignored),
+    ("response.function_call_arguments.delta", ResponseEventKind::FunctionCallArgumentsDelta),
+    ("response.function_call_arguments.done", ResponseEventKind::Ignored),
+];
+
+fn response_event_kind(event_type: &str) -> Option<ResponseEventKind> {
+    RESPONSE_EVENT_KINDS
+        .iter()
+        .find(|(name, _)| *name == event_type)
+        .map(|(_, kind)| *kind)
+}
+
+/// Warn about an unmapped event type the first time it is seen
+fn log_unknown_response_event(event_type: &str) {
+    static SEEN: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    let mut seen = SEEN.get_or_init(Mutex::default).lock().unwrap_or_else(|e| e.into_inner());
+    if seen.insert(event_type.to_string()) {
+        tracing::warn!("Skipping unknown Responses stream event type '{}'", event_type);
+    }
+}
+
+/// `ReasoningSummary` for a finished reasoning item with a non-empty summary
+fn reasoning_summary(item: &serde_json::Value) -> Option<StreamEvent> {
+    if item["type"] != "reasoning" {
+        return None;
+    }
+    let parts: Vec<&str> = item["summary"]
+        .as_array()?
+        .iter()
+        .filter_map(|part| part["text"].as_str())
+        .filter(|text| !text.is_empty())
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    Some(StreamEvent::ReasoningSummary {
+        item_id: item["id"].as_str().unwrap_or_default().to_string(),
+        text: parts.join("\n\n"),
+    })
+}
+
+/// `ToolCall` opening a function call item, keyed by its output index
+///
+/// Output indexes are unique within a response, so they serve as the
+/// call's `index` for the argument deltas that follow.
+fn function_call_start(chunk: &serde_json::Value) -> Option<StreamEvent> {
+    let item = &chunk["item"];
+    if item["type"] != "function_call" {
+        return None;
+    }
+    Some(StreamEvent::ToolCall {
+        index: output_index(chunk),
+        id: item["call_id"].as_str().map(String::from),
+        name: item["name"].as_str().map(String::from),
+        arguments: item["arguments"].as_str().filter(|args| !args.is_empty()).map(String::from),
+    })
+}
+
+fn output_index(chunk: &serde_json::Value) -> u32 {
+    chunk["output_index"].as_u64().unwrap_or_default() as u32
+}
+
 /// Response SSE parser (Strategy Pattern)
-struct ResponseSseParser;
+pub(crate) struct ResponseSseParser;
 
 impl SseLineParser for ResponseSseParser {
     fn parse_data_line(&self, data: &str) -> Result<Vec<StreamEvent>> {
-        let chunk: ResponseStreamChunk = serde_json::from_str(data)
+        let chunk: serde_json::Value = serde_json::from_str(data)
             .map_err(|e| anyhow::anyhow!("Failed to parse response chunk: {}", e))?;
+        let event_type = chunk["type"].as_str().unwrap_or_default();
         
-        let mut events = Vec::new();
+        let Some(kind) = response_event_kind(event_type) else {
+            log_unknown_response_event(event_type);
+            return Ok(Vec::new());
+        };
         
-        if chunk.is_done() {
-            events.push(StreamEvent::Done {
-                finish_reason: chunk.status.clone(),
-            });
-            return Ok(events);
-        }
-        
-        let is_reasoning = chunk.output_index.map(|idx| idx == 0).unwrap_or(false);
-        
-        // Debug: log what we're receiving
-        tracing::debug!(
-            "ResponseStreamChunk - output_index: {:?}, is_reasoning: {}, delta: {:?}",
-            chunk.output_index,
-            is_reasoning,
-            chunk.delta
-        );
+        let delta = || chunk["delta"].as_str().filter(|d| !d.is_empty()).map(String::from);
+        let reasoning = |kind| {
+            let item_id = chunk["item_id"].as_str().map(String::from);
+            delta().map(|content| StreamEvent::Reasoning { content, kind, item_id })
+        };
+        let event = match kind {
+            ResponseEventKind::ReasoningDelta => reasoning(ReasoningKind::Raw),
+            ResponseEventKind::ReasoningSummaryDelta => reasoning(ReasoningKind::Summary),
+            ResponseEventKind::TextDelta => delta().map(|content| StreamEvent::Message { content, choice_index: None, logprobs: None }),
+            ResponseEventKind::Completed | ResponseEventKind::Incomplete => Some(StreamEvent::Done {
+                finish_reason: Some(response_finish_reason(&chunk["response"])),
+                choice_index: None,
+            }),
+            ResponseEventKind::Failed => {
+                let error = if chunk["response"]["error"].is_object() {
+                    &chunk["response"]["error"]
+                } else {
+                    &chunk
+                };
+                anyhow::bail!(
+                    "Response failed: {}",
+                    error["message"].as_str().unwrap_or("unknown error")
+                );
+            }
+            ResponseEventKind::OutputItemAdded => function_call_start(&chunk),
+            ResponseEventKind::FunctionCallArgumentsDelta => delta().map(|arguments| StreamEvent::ToolCall {
+                index: output_index(&chunk),
+                id: None,
+                name: None,
+                arguments: Some(arguments),
+            }),
+            ResponseEventKind::OutputItemDone => reasoning_summary(&chunk["item"]),
+            ResponseEventKind::Ignored => None,
+        };
         
-        if is_reasoning {
-            if let Some(text) = chunk.reasoning_text() {
-                if !text.is_empty() {
-                    tracing::debug!("Emitting Reasoning event with {} chars", text.len());
-                    events.push(StreamEvent::Reasoning { content: text });
+        // Finished responses carry the call's usage, reported ahead of Done
+        let usage = match kind {
+            ResponseEventKind::Completed | ResponseEventKind::Incomplete => response_usage(&chunk["response"]["usage"]),
+            _ => None,
+        };
+        Ok(usage.into_iter().chain(event).collect())
+    }
+}
+
+/// Chat completions `finish_reason` of a finished response
+///
+/// `stop`, or `tool_calls` when the output ends in function calls; an
+/// incomplete response reports `length` when it ran out of output tokens
+/// and its `incomplete_details` reason (e.g. `content_filter`) otherwise.
+fn response_finish_reason(response: &serde_json::Value) -> String {
+    if response["status"] == "incomplete" {
+        return match response["incomplete_details"]["reason"].as_str() {
+            Some("max_output_tokens") => "length",
+            Some(reason) => reason,
+            None => "incomplete",
+        }
+        .to_string();
+    }
+    let called_tools = response["output"]
+        .as_array()
+        .is_some_and(|output| output.iter().any(|item| item["type"] == "function_call"));
+    if called_tools { "tool_calls" } else { "stop" }.to_string()
+}
+
+fn response_usage(usage: &serde_json::Value) -> Option<StreamEvent> {
+    let tokens = |field: &str| usage[field].as_u64().map(|n| n as u32);
+    Some(StreamEvent::Usage {
+        input_tokens: tokens("input_tokens")?,
+        output_tokens: tokens("output_tokens")?,
+        total_tokens: tokens("total_tokens")?,
+        reasoning_tokens: usage["output_tokens_details"]["reasoning_tokens"].as_u64().map(|n| n as u32),
+        cached_tokens: usage["input_tokens_details"]["cached_tokens"].as_u64().map(|n| n as u32),
+    })
+}
+
+/// Move `Usage` ahead of the `Done` events it follows
+///
+/// Chat completions report usage in a last chunk of their own, after the
+/// chunks carrying `finish_reason`; consumers expect it before `Done`. With
+/// several choices, each choice's `Done` is held until the usage (or the end
+/// of the stream), while the other choices' deltas pass through.
+pub(crate) fn usage_before_done(
+    stream: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
+    Box::pin(async_stream::stream! {
+        let mut stream = stream;
+        let mut held_done = Vec::new();
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(done @ StreamEvent::Done { .. }) => held_done.push(done),
+                Ok(usage @ StreamEvent::Usage { .. }) => {
+                    yield Ok(usage);
+                    for held in held_done.drain(..) {
+                        yield Ok(held);
+                    }
                 }
-            }
-        } else {
-            if let Some(text) = chunk.message_text() {
-                if !text.is_empty() {
-                    tracing::debug!("Emitting Message event with {} chars", text.len());
-                    events.push(StreamEvent::Message { content: text });
+                Err(error) => {
+                    for held in held_done.drain(..) {
+                        yield Ok(held);
+                    }
+                    yield Err(error);
                 }
+                other => yield other,
             }
         }
-        
-        Ok(events)
-    }
+        for held in held_done {
+            yield Ok(held);
+        }
+    })
 }
 
 pub fn parse_chat_sse_stream(
     response: Response,
 ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>> {
-    parse_sse_stream(response, ChatSseParser)
+    usage_before_done(parse_sse_stream(response, ChatSseParser))
 }
 
 pub fn parse_response_sse_stream(
@@ -201,3 +542,121 @@ pub fn parse_sse_stream_legacy(response: Response) -> Pin<Box<dyn Stream<Item =
     parse_chat_sse_stream(response)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_types_dispatch_regardless_of_output_index() {
+        let parser = ResponseSseParser;
+        // Reasoning streamed second, message first: no output_index guessing
+        let message = parser
+            .parse_data_line(r#"{"type":"response.output_text.delta","output_index":0,"delta":"Hi"}"#)
+            .unwrap();
+        let reasoning = parser
+            .parse_data_line(r#"{"type":"response.reasoning_summary_text.delta","output_index":1,"delta":"Think"}"#)
+            .unwrap();
+
+        assert!(matches!(&message[..], [StreamEvent::Message { content, .. }] if content == "Hi"));
+        assert!(matches!(&reasoning[..], [StreamEvent::Reasoning { content, .. }] if content == "Think"));
+    }
+
+    #[test]
+    fn test_summary_and_raw_reasoning_are_told_apart() {
+        let parser = ResponseSseParser;
+        let summary = parser
+            .parse_data_line(r#"{"type":"response.reasoning_summary_text.delta","output_index":0,"delta":"Weighing options"}"#)
+            .unwrap();
+        let raw = parser
+            .parse_data_line(r#"{"type":"response.reasoning_text.delta","output_index":0,"delta":"Option A costs"}"#)
+            .unwrap();
+
+        assert!(matches!(&summary[..], [StreamEvent::Reasoning { kind: ReasoningKind::Summary, .. }]));
+        assert!(matches!(&raw[..], [StreamEvent::Reasoning { kind: ReasoningKind::Raw, .. }]));
+
+        // Raw deltas keep the wire format they always had
+        let json = serde_json::to_value(&raw[0]).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "reasoning", "content": "Option A costs" }));
+        assert_eq!(serde_json::to_value(&summary[0]).unwrap()["kind"], "summary");
+    }
+
+    #[test]
+    fn test_unknown_and_lifecycle_events_are_skipped() {
+        let parser = ResponseSseParser;
+        assert!(parser.parse_data_line(r#"{"type":"response.future_feature.delta","delta":"x"}"#).unwrap().is_empty());
+        assert!(parser.parse_data_line(r#"{"type":"response.in_progress","response":{}}"#).unwrap().is_empty());
+        assert!(parser.parse_data_line(r#"{"delta":"no type"}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_completed_response_reports_usage_before_done() {
+        let events = ResponseSseParser
+            .parse_data_line(r#"{"type":"response.completed","response":{"status":"completed","usage":{"input_tokens":12,"output_tokens":30,"total_tokens":42,"output_tokens_details":{"reasoning_tokens":20}}}}"#)
+            .unwrap();
+
+        assert!(matches!(
+            &events[..],
+            [
+                StreamEvent::Usage { input_tokens: 12, output_tokens: 30, total_tokens: 42, reasoning_tokens: Some(20), cached_tokens: None },
+                StreamEvent::Done { .. },
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_finish_reasons_use_chat_completions_names() {
+        let finish_reason = |data: &str| match ResponseSseParser.parse_data_line(data).unwrap().pop() {
+            Some(StreamEvent::Done { finish_reason, .. }) => finish_reason,
+            other => panic!("expected Done, got {:?}", other),
+        };
+        assert_eq!(
+            finish_reason(r#"{"type":"response.completed","response":{"status":"completed","output":[{"type":"message"}]}}"#).as_deref(),
+            Some("stop")
+        );
+        assert_eq!(
+            finish_reason(r#"{"type":"response.completed","response":{"status":"completed","output":[{"type":"function_call"}]}}"#).as_deref(),
+            Some("tool_calls")
+        );
+        assert_eq!(
+            finish_reason(r#"{"type":"response.incomplete","response":{"status":"incomplete","incomplete_details":{"reason":"max_output_tokens"}}}"#).as_deref(),
+            Some("length")
+        );
+        assert_eq!(
+            finish_reason(r#"{"type":"response.incomplete","response":{"status":"incomplete","incomplete_details":{"reason":"content_filter"}}}"#).as_deref(),
+            Some("content_filter")
+        );
+    }
+
+    #[test]
+    fn test_function_calls_stream_as_tool_calls() {
+        let parser = ResponseSseParser;
+        let added = parser
+            .parse_data_line(r#"{"type":"response.output_item.added","output_index":1,"item":{"type":"function_call","id":"fc_1","call_id":"call_1","name":"get_weather","arguments":"","status":"in_progress"}}"#)
+            .unwrap();
+        let delta = parser
+            .parse_data_line(r#"{"type":"response.function_call_arguments.delta","item_id":"fc_1","output_index":1,"delta":"{\"city\":"}"#)
+            .unwrap();
+        let message_added = parser
+            .parse_data_line(r#"{"type":"response.output_item.added","output_index":2,"item":{"type":"message","id":"msg_1"}}"#)
+            .unwrap();
+
+        assert!(matches!(
+            &added[..],
+            [StreamEvent::ToolCall { index: 1, id: Some(id), name: Some(name), arguments: None }]
+                if id == "call_1" && name == "get_weather"
+        ));
+        assert!(matches!(
+            &delta[..],
+            [StreamEvent::ToolCall { index: 1, id: None, name: None, arguments: Some(args) }] if args == "{\"city\":"
+        ));
+        assert!(message_added.is_empty());
+    }
+
+    #[test]
+    fn test_failed_response_errors_the_stream() {
+        let err = ResponseSseParser
+            .parse_data_line(r#"{"type":"response.failed","response":{"status":"failed","error":{"code":"server_error","message":"boom"}}}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("boom"));
+    }
+}
@@ -0,0 +1,351 @@
+//! Scripted LLM client for tests that run without a provider
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use std::collections::{BTreeMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use crate::error::LLMError;
+use crate::openai::responses::{ResponsesResponse, Usage};
+use crate::traits::{
+    ChatClient, ChatRequest, ChatResponse, ChoiceOutput, LLMClient, ReasoningClient, ResponseOutput, ResponseRequest, TokenLogprob,
+    TokenUsage,
+};
+use crate::types::{FunctionCall, ToolCall};
+use crate::stats::ResponseMetadata;
+use crate::StreamEvent;
+
+enum Turn {
+    Events(Vec<StreamEvent>),
+    Fail(anyhow::Error),
+}
+
+/// LLM client that answers each call with the next scripted turn
+///
+/// Chat and reasoning calls, streaming or not, take turns from one queue in
+/// call order. Streaming calls replay the turn's events; `chat` and
+/// `reason` fold them into one response. A call with no turn left fails, so
+/// a loop that runs longer than the script shows up as an error.
+///
+/// ```rust
+/// use praxis_llm::testkit::MockLLMClient;
+///
+/// let llm = MockLLMClient::new()
+///     .then_tool_call("call_1", "get_weather", r#"{"city": "Lisbon"}"#)
+///     .then_message("It's sunny in Lisbon.");
+/// ```
+#[derive(Default)]
+pub struct MockLLMClient {
+    turns: Mutex<VecDeque<Turn>>,
+    chat_requests: Mutex<Vec<ChatRequest>>,
+    response_requests: Mutex<Vec<ResponseRequest>>,
+}
+
+impl MockLLMClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the next call with `events`, as they are
+    pub fn then_stream(self, events: Vec<StreamEvent>) -> Self {
+        self.turns.lock().unwrap().push_back(Turn::Events(events));
+        self
+    }
+
+    /// Answer the next call with `text` and `Done`
+    pub fn then_message(self, text: impl Into<String>) -> Self {
+        self.then_stream(vec![
+            StreamEvent::Message { content: text.into(), choice_index: None, logprobs: None },
+            StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ])
+    }
+
+    /// Answer the next call with one complete tool call and `Done`
+    pub fn then_tool_call(self, id: impl Into<String>, name: impl Into<String>, arguments: impl Into<String>) -> Self {
+        self.then_stream(vec![
+            StreamEvent::ToolCall {
+                index: 0,
+                id: Some(id.into()),
+                name: Some(name.into()),
+                arguments: Some(arguments.into()),
+            },
+            StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ])
+    }
+
+    /// Fail the next call with `message` before anything is streamed
+    pub fn then_error(self, message: impl Into<String>) -> Self {
+        self.turns.lock().unwrap().push_back(Turn::Fail(anyhow::anyhow!(message.into())));
+        self
+    }
+
+    /// Fail the next call with a classified provider error, e.g. a 503
+    pub fn then_llm_error(self, error: LLMError) -> Self {
+        self.turns.lock().unwrap().push_back(Turn::Fail(error.into()));
+        self
+    }
+
+    /// Chat requests received, streaming or not, in call order
+    pub fn chat_requests(&self) -> Vec<ChatRequest> {
+        self.chat_requests.lock().unwrap().clone()
+    }
+
+    /// Reasoning requests received, streaming or not, in call order
+    pub fn response_requests(&self) -> Vec<ResponseRequest> {
+        self.response_requests.lock().unwrap().clone()
+    }
+
+    /// Scripted turns no call has taken yet
+    pub fn remaining(&self) -> usize {
+        self.turns.lock().unwrap().len()
+    }
+
+    fn next_turn(&self) -> Result<Vec<StreamEvent>> {
+        match self.turns.lock().unwrap().pop_front() {
+            Some(Turn::Events(events)) => Ok(events),
+            Some(Turn::Fail(error)) => Err(error),
+            None => anyhow::bail!("MockLLMClient: no scripted turn left"),
+        }
+    }
+}
+
+/// What a non-streaming call returns for a turn's events
+#[derive(Default)]
+struct Folded {
+    reasoning: Option<String>,
+    message: Option<String>,
+    /// Token log probabilities of the first choice's text
+    logprobs: Option<Vec<TokenLogprob>>,
+    /// Text of the choices after the first, by index
+    other_choices: BTreeMap<u32, String>,
+    /// Finish reasons of the choices after the first, by index
+    other_finish_reasons: BTreeMap<u32, String>,
+    tool_calls: Vec<ToolCall>,
+    usage: Option<TokenUsage>,
+    finish_reason: Option<String>,
+    metadata: Option<ResponseMetadata>,
+}
+
+impl Folded {
+    fn from_events(events: Vec<StreamEvent>) -> Self {
+        let mut folded = Self::default();
+        // Tool call deltas by index, like a provider stream
+        let mut calls: Vec<(u32, ToolCall)> = Vec::new();
+        for event in events {
+            match event {
+                StreamEvent::Reasoning { content, .. } => folded.reasoning.get_or_insert_with(String::new).push_str(&content),
+                StreamEvent::Message { content, choice_index: None | Some(0), logprobs } => {
+                    folded.message.get_or_insert_with(String::new).push_str(&content);
+                    if let Some(logprobs) = logprobs {
+                        folded.logprobs.get_or_insert_with(Vec::new).extend(logprobs);
+                    }
+                }
+                StreamEvent::Message { content, choice_index: Some(index), .. } => {
+                    folded.other_choices.entry(index).or_default().push_str(&content)
+                }
+                StreamEvent::ToolCall { index, id, name, arguments } => {
+                    let position = match calls.iter().position(|(i, _)| *i == index) {
+                        Some(position) => position,
+                        None => {
+                            let call = ToolCall {
+                                id: String::new(),
+                                tool_type: "function".to_string(),
+                                function: FunctionCall { name: String::new(), arguments: String::new() },
+                            };
+                            calls.push((index, call));
+                            calls.len() - 1
+                        }
+                    };
+                    let call = &mut calls[position].1;
+                    if let Some(id) = id {
+                        call.id = id;
+                    }
+                    if let Some(name) = name {
+                        call.function.name.push_str(&name);
+                    }
+                    if let Some(arguments) = arguments {
+                        call.function.arguments.push_str(&arguments);
+                    }
+                }
+                StreamEvent::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens } => {
+                    folded.usage = Some(TokenUsage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens });
+                }
+                StreamEvent::Done { finish_reason, choice_index: None | Some(0) } => folded.finish_reason = finish_reason,
+                StreamEvent::Done { finish_reason, choice_index: Some(index) } => {
+                    if let Some(reason) = finish_reason {
+                        folded.other_finish_reasons.insert(index, reason);
+                    }
+                }
+                StreamEvent::ResponseMetadata { metadata } => folded.metadata = Some(metadata),
+                StreamEvent::ReasoningSummary { .. } | StreamEvent::OptionsAdjusted { .. } | StreamEvent::Audio { .. } => {}
+            }
+        }
+        folded.tool_calls = calls.into_iter().map(|(_, call)| call).collect();
+        folded
+    }
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+#[async_trait]
+impl ChatClient for MockLLMClient {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        self.chat_requests.lock().unwrap().push(request);
+        let folded = Folded::from_events(self.next_turn()?);
+        let first = ChoiceOutput {
+            index: 0,
+            content: folded.message,
+            tool_calls: (!folded.tool_calls.is_empty()).then_some(folded.tool_calls),
+            finish_reason: folded.finish_reason,
+            logprobs: folded.logprobs,
+        };
+        let mut other_finish_reasons = folded.other_finish_reasons;
+        let others = folded.other_choices.into_iter().map(|(index, text)| ChoiceOutput {
+            index,
+            content: Some(text),
+            tool_calls: None,
+            finish_reason: other_finish_reasons.remove(&index).or_else(|| first.finish_reason.clone()),
+            logprobs: None,
+        });
+        let choices: Vec<ChoiceOutput> = std::iter::once(first.clone()).chain(others).collect();
+        Ok(ChatResponse {
+            content: first.content,
+            tool_calls: first.tool_calls,
+            usage: folded.usage,
+            finish_reason: first.finish_reason,
+            logprobs: first.logprobs,
+            choices,
+            warnings: Vec::new(),
+            raw: serde_json::Value::Null,
+            served_by: None,
+            metadata: folded.metadata,
+        })
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<EventStream> {
+        self.chat_requests.lock().unwrap().push(request);
+        let events = self.next_turn()?;
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for MockLLMClient {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        let model = request.model.clone();
+        self.response_requests.lock().unwrap().push(request);
+        let folded = Folded::from_events(self.next_turn()?);
+        let (input_tokens, output_tokens, total_tokens) = folded
+            .usage
+            .as_ref()
+            .map_or((0, 0, 0), |usage| (usage.input_tokens, usage.output_tokens, usage.total_tokens));
+        Ok(ResponseOutput {
+            reasoning: folded.reasoning,
+            message: folded.message,
+            usage: folded.usage,
+            status: Some("completed".to_string()),
+            warnings: Vec::new(),
+            raw: ResponsesResponse {
+                id: "mock".to_string(),
+                object: "response".to_string(),
+                created_at: 0,
+                status: "completed".to_string(),
+                model,
+                output: Vec::new(),
+                usage: Usage { input_tokens, output_tokens, total_tokens, output_tokens_details: None, input_tokens_details: None },
+                reasoning: None,
+            },
+            served_by: None,
+            metadata: folded.metadata,
+        })
+    }
+
+    async fn reason_stream(&self, request: ResponseRequest) -> Result<EventStream> {
+        self.response_requests.lock().unwrap().push(request);
+        let events = self.next_turn()?;
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+impl LLMClient for MockLLMClient {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Message;
+    use futures::StreamExt;
+
+    fn request(text: &str) -> ChatRequest {
+        ChatRequest::new("gpt-4o-mini", vec![Message::human(text)])
+    }
+
+    #[tokio::test]
+    async fn test_turns_replay_in_order_and_requests_are_recorded() {
+        let llm = MockLLMClient::new()
+            .then_tool_call("call_1", "get_weather", r#"{"city": "Lisbon"}"#)
+            .then_message("Sunny");
+
+        let first: Vec<_> = llm.chat_stream(request("Weather?")).await.unwrap().collect().await;
+        assert!(matches!(
+            first[0].as_ref().unwrap(),
+            StreamEvent::ToolCall { name: Some(name), .. } if name == "get_weather"
+        ));
+        let second: Vec<_> = llm.chat_stream(request("And now?")).await.unwrap().collect().await;
+        assert!(matches!(second[0].as_ref().unwrap(), StreamEvent::Message { content, .. } if content == "Sunny"));
+
+        let requests = llm.chat_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&requests[1].messages).unwrap(),
+            serde_json::to_value(vec![Message::human("And now?")]).unwrap()
+        );
+        assert_eq!(llm.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_script_and_scripted_errors_fail_the_call() {
+        let llm = MockLLMClient::new().then_error("provider down");
+
+        let error = llm.chat_stream(request("Hi")).await.err().unwrap();
+        assert_eq!(error.to_string(), "provider down");
+        assert!(llm.chat_stream(request("Hi")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_non_streaming_calls_fold_the_turn() {
+        let llm = MockLLMClient::new().then_stream(vec![
+            StreamEvent::ToolCall { index: 0, id: Some("call_1".to_string()), name: Some("search".to_string()), arguments: None },
+            StreamEvent::ToolCall { index: 0, id: None, name: None, arguments: Some(r#"{"q": "#.to_string()) },
+            StreamEvent::ToolCall { index: 0, id: None, name: None, arguments: Some(r#""rust"}"#.to_string()) },
+            StreamEvent::Usage { input_tokens: 10, output_tokens: 5, total_tokens: 15, reasoning_tokens: None, cached_tokens: None },
+            StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+        ]);
+
+        let response = llm.chat(request("Find rust")).await.unwrap();
+        let calls = response.tool_calls.unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].function.arguments, r#"{"q": "rust"}"#);
+        assert_eq!(response.usage.unwrap().total_tokens, 15);
+        assert_eq!(response.finish_reason.as_deref(), Some("tool_calls"));
+        assert!(response.content.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tagged_messages_fold_into_choices() {
+        let message = |content: &str, choice_index| StreamEvent::Message { content: content.to_string(), choice_index, logprobs: None };
+        let llm = MockLLMClient::new().then_stream(vec![
+            message("Fox", None),
+            message("Dog", Some(1)),
+            message(" naps", Some(1)),
+            StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+        ]);
+
+        let response = llm.chat(request("Two taglines").with_options(crate::ChatOptions::new().n(2))).await.unwrap();
+        assert_eq!(response.content.as_deref(), Some("Fox"));
+        let choices: Vec<_> = response.choices.iter().map(|c| (c.index, c.content.as_deref())).collect();
+        assert_eq!(choices, [(0, Some("Fox")), (1, Some("Dog naps"))]);
+    }
+}
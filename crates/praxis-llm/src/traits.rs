@@ -1,15 +1,23 @@
+use crate::capabilities::OptionAdjusted;
 use crate::openai::{ReasoningConfig, ResponsesResponse};
+use crate::stats::{RateLimitHeadroom, ResponseMetadata};
 use crate::streaming::StreamEvent;
 use crate::types::{Message, Tool, ToolChoice};
 use anyhow::Result;
 use async_trait::async_trait;
 use futures::Stream;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::pin::Pin;
+use std::sync::Arc;
 
 /// Trait for chat-based LLM interactions (GPT-4, etc)
 /// 
 /// Provides both streaming and non-streaming completions for conversational use cases.
+/// Clients are shared as `Arc<dyn LLMClient>` across runs and tasks, hence
+/// `Send + Sync`; `Arc<T>` implements the trait too, so wrappers can hold
+/// either.
 #[async_trait]
 pub trait ChatClient: Send + Sync {
     /// Non-streaming chat completion
@@ -37,10 +45,97 @@ pub trait ReasoningClient: Send + Sync {
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>>;
 }
 
+/// Trait for embedding models
+///
+/// Providers cap the inputs of one request; implementations send larger
+/// lists in batches. The result holds one vector per input, in input order.
+#[async_trait]
+pub trait EmbeddingsClient: Send + Sync {
+    async fn embed(&self, model: &str, inputs: Vec<String>) -> Result<Embeddings>;
+}
+
+/// Trait for moderation models
+///
+/// Screens a text against the provider's usage policies, e.g. a user's
+/// prompt before it reaches the model or the model's answer after.
+#[async_trait]
+pub trait ModerationClient: Send + Sync {
+    async fn moderate(&self, input: &str) -> Result<Moderation>;
+}
+
+/// Trait for counting the prompt tokens of a request ahead of sending it
+///
+/// Counts are for `model`'s encoding and include what the provider adds
+/// around each message and tool definition, so they can be checked against
+/// its context window.
+pub trait TokenCounter: Send + Sync {
+    fn count_messages(&self, model: &str, messages: &[Message], tools: &[Tool]) -> usize;
+}
+
 /// Convenience trait for clients that support both chat and reasoning
-pub trait LLMClient: ChatClient + ReasoningClient {}
+pub trait LLMClient: ChatClient + ReasoningClient {
+    /// What the provider's rate limit allowed as of its latest response
+    /// (None for clients that don't read it)
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        None
+    }
+}
+
+#[async_trait]
+impl<T: ChatClient + ?Sized> ChatClient for Arc<T> {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        (**self).chat(request).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        (**self).chat_stream(request).await
+    }
+}
+
+#[async_trait]
+impl<T: ReasoningClient + ?Sized> ReasoningClient for Arc<T> {
+    async fn reason(&self, request: ResponseRequest) -> Result<ResponseOutput> {
+        (**self).reason(request).await
+    }
+
+    async fn reason_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
+        (**self).reason_stream(request).await
+    }
+}
+
+#[async_trait]
+impl<T: EmbeddingsClient + ?Sized> EmbeddingsClient for Arc<T> {
+    async fn embed(&self, model: &str, inputs: Vec<String>) -> Result<Embeddings> {
+        (**self).embed(model, inputs).await
+    }
+}
+
+#[async_trait]
+impl<T: ModerationClient + ?Sized> ModerationClient for Arc<T> {
+    async fn moderate(&self, input: &str) -> Result<Moderation> {
+        (**self).moderate(input).await
+    }
+}
+
+impl<T: TokenCounter + ?Sized> TokenCounter for Arc<T> {
+    fn count_messages(&self, model: &str, messages: &[Message], tools: &[Tool]) -> usize {
+        (**self).count_messages(model, messages, tools)
+    }
+}
+
+impl<T: LLMClient + ?Sized> LLMClient for Arc<T> {
+    fn rate_limit_headroom(&self) -> Option<RateLimitHeadroom> {
+        (**self).rate_limit_headroom()
+    }
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatRequest {
     pub model: String,
     pub messages: Vec<Message>,
@@ -62,13 +157,65 @@ impl ChatRequest {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChatOptions {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub tools: Option<Vec<Tool>>,
     pub tool_choice: Option<ToolChoice>,
     pub reasoning_effort: Option<String>,
+    /// Sequences that end generation
+    pub stop: Option<Vec<String>>,
+    pub top_p: Option<f32>,
+    /// Best-effort determinism: equal seeds and parameters should give equal outputs
+    pub seed: Option<u64>,
+    pub presence_penalty: Option<f32>,
+    pub frequency_penalty: Option<f32>,
+    /// Stable id of the end user, sent as `user` for provider abuse monitoring
+    pub end_user_id: Option<String>,
+    /// Key-value tags sent as OpenAI's `metadata` (other providers ignore them)
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// Completions to generate, returned as `ChatResponse::choices`; streams
+    /// tag the text of choices after the first with `choice_index`
+    pub n: Option<u32>,
+    /// Return the log probability of each output token, as
+    /// `ChatResponse::logprobs` and on streamed `Message` events
+    pub logprobs: Option<bool>,
+    /// Most likely alternatives to return for each token (OpenAI allows up
+    /// to 20); implies `logprobs`
+    pub top_logprobs: Option<u32>,
+    /// `Some(false)` asks for at most one tool call per turn, for tools
+    /// that must run one after another (unset: the provider's default)
+    pub parallel_tool_calls: Option<bool>,
+    /// Answer with speech as well as text, streamed as `StreamEvent::Audio`
+    /// (audio models only, such as `gpt-4o-audio-preview`)
+    pub audio: Option<AudioOptions>,
+    /// Raw fields merged into the provider's request body, for parameters
+    /// this crate doesn't know yet (e.g. OpenAI's `prediction` or `store`)
+    ///
+    /// Fields the request already has win: extra keys are only added where
+    /// the body has none, and nested objects are merged key by key the same
+    /// way. Sent as given, to whichever provider serves the request.
+    pub extra_body: Option<Map<String, Value>>,
+    /// Fail instead of dropping options the model doesn't support
+    pub strict_options: bool,
+}
+
+/// Spoken output of an audio model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioOptions {
+    /// e.g. `alloy`
+    pub voice: String,
+    /// Encoding of the audio, e.g. `wav`, `mp3` or `pcm16` (the only one
+    /// OpenAI streams)
+    pub format: String,
+}
+
+impl AudioOptions {
+    pub fn new(voice: impl Into<String>, format: impl Into<String>) -> Self {
+        Self { voice: voice.into(), format: format.into() }
+    }
 }
 
 impl ChatOptions {
@@ -100,18 +247,143 @@ impl ChatOptions {
         self.reasoning_effort = Some(effort.into());
         self
     }
+    
+    pub fn stop(mut self, sequences: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.stop = Some(sequences.into_iter().map(Into::into).collect());
+        self
+    }
+    
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+    
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    
+    pub fn presence_penalty(mut self, penalty: f32) -> Self {
+        self.presence_penalty = Some(penalty);
+        self
+    }
+    
+    pub fn frequency_penalty(mut self, penalty: f32) -> Self {
+        self.frequency_penalty = Some(penalty);
+        self
+    }
+    
+    pub fn end_user_id(mut self, id: impl Into<String>) -> Self {
+        self.end_user_id = Some(id.into());
+        self
+    }
+    
+    pub fn metadata(mut self, metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>) -> Self {
+        self.metadata = Some(metadata.into_iter().map(|(key, value)| (key.into(), value.into())).collect());
+        self
+    }
+    
+    pub fn n(mut self, n: u32) -> Self {
+        self.n = Some(n);
+        self
+    }
+    
+    pub fn logprobs(mut self, enabled: bool) -> Self {
+        self.logprobs = Some(enabled);
+        self
+    }
+    
+    pub fn top_logprobs(mut self, count: u32) -> Self {
+        self.top_logprobs = Some(count);
+        self
+    }
+    
+    /// Whether the request asks for log probabilities
+    pub fn wants_logprobs(&self) -> bool {
+        self.logprobs == Some(true) || self.top_logprobs.is_some()
+    }
+    
+    pub fn parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = Some(enabled);
+        self
+    }
+    
+    pub fn audio(mut self, audio: AudioOptions) -> Self {
+        self.audio = Some(audio);
+        self
+    }
+    
+    pub fn extra_body(mut self, body: Map<String, Value>) -> Self {
+        self.extra_body = Some(body);
+        self
+    }
+    
+    pub fn strict_options(mut self, strict: bool) -> Self {
+        self.strict_options = strict;
+        self
+    }
 }
 
-#[derive(Debug, Clone)]
+/// One of the completions a chat request asked for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceOutput {
+    pub index: u32,
+    pub content: Option<String>,
+    pub tool_calls: Option<Vec<crate::types::ToolCall>>,
+    pub finish_reason: Option<String>,
+    /// Log probabilities of the choice's tokens, when `ChatOptions::logprobs` asked for them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+}
+
+/// Log probability of one output token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, for tokens that split a character
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+    /// Most likely tokens at this position, up to `ChatOptions::top_logprobs`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// One alternative of `TokenLogprob::top_logprobs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatResponse {
+    /// The first choice's text, tool calls and finish reason
     pub content: Option<String>,
     pub tool_calls: Option<Vec<crate::types::ToolCall>>,
     pub usage: Option<TokenUsage>,
     pub finish_reason: Option<String>,
+    /// The first choice's token log probabilities (see `ChatOptions::logprobs`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    /// Every choice by index, the first included (one unless `ChatOptions::n`)
+    #[serde(default)]
+    pub choices: Vec<ChoiceOutput>,
+    /// Options dropped because the model doesn't support them
+    pub warnings: Vec<OptionAdjusted>,
     pub raw: serde_json::Value,
+    /// Provider that answered, when a `FallbackClient` chose it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_by: Option<String>,
+    /// Request id and rate-limit state from the response headers, for
+    /// providers that send them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ResponseMetadata>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseRequest {
     pub model: String,
     pub input: Vec<Message>,
@@ -140,10 +412,23 @@ impl ResponseRequest {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ResponseOptions {
     pub temperature: Option<f32>,
     pub max_output_tokens: Option<u32>,
+    pub tools: Option<Vec<Tool>>,
+    pub tool_choice: Option<ToolChoice>,
+    /// See `ChatOptions::end_user_id`
+    pub end_user_id: Option<String>,
+    /// See `ChatOptions::metadata`
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// See `ChatOptions::parallel_tool_calls`
+    pub parallel_tool_calls: Option<bool>,
+    /// See `ChatOptions::extra_body`
+    pub extra_body: Option<Map<String, Value>>,
+    /// See `ChatOptions::strict_options`
+    pub strict_options: bool,
 }
 
 impl ResponseOptions {
@@ -160,15 +445,72 @@ impl ResponseOptions {
         self.max_output_tokens = Some(tokens);
         self
     }
+    
+    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+    
+    pub fn tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.tool_choice = Some(choice);
+        self
+    }
+    
+    pub fn end_user_id(mut self, id: impl Into<String>) -> Self {
+        self.end_user_id = Some(id.into());
+        self
+    }
+    
+    pub fn metadata(mut self, metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>) -> Self {
+        self.metadata = Some(metadata.into_iter().map(|(key, value)| (key.into(), value.into())).collect());
+        self
+    }
+    
+    pub fn parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = Some(enabled);
+        self
+    }
+    
+    pub fn extra_body(mut self, body: Map<String, Value>) -> Self {
+        self.extra_body = Some(body);
+        self
+    }
+    
+    pub fn strict_options(mut self, strict: bool) -> Self {
+        self.strict_options = strict;
+        self
+    }
+}
+
+/// Add `extra` to a built request body without replacing what it has (see
+/// `ChatOptions::extra_body`)
+pub(crate) fn merge_extra_body(body: &mut Map<String, Value>, extra: Option<&Map<String, Value>>) {
+    for (key, value) in extra.into_iter().flatten() {
+        match (body.get_mut(key), value) {
+            (Some(Value::Object(known)), Value::Object(more)) => merge_extra_body(known, Some(more)),
+            (Some(_), _) => {}
+            (None, _) => {
+                body.insert(key.clone(), value.clone());
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseOutput {
     pub reasoning: Option<String>,
     pub message: Option<String>,
     pub usage: Option<TokenUsage>,
     pub status: Option<String>,
+    /// See `ChatResponse::warnings`
+    pub warnings: Vec<OptionAdjusted>,
     pub raw: ResponsesResponse,
+    /// See `ChatResponse::served_by`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_by: Option<String>,
+    /// See `ChatResponse::metadata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ResponseMetadata>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,5 +520,45 @@ pub struct TokenUsage {
     pub total_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_tokens: Option<u32>,
+    /// Input tokens served from the provider's prompt cache (part of
+    /// `input_tokens`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_tokens: Option<u32>,
+}
+
+/// Result of `EmbeddingsClient::embed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Embeddings {
+    /// One vector per input, in input order
+    pub vectors: Vec<Vec<f32>>,
+    /// Summed over all batches; embeddings have no output tokens
+    pub usage: TokenUsage,
 }
 
+/// Result of `ModerationClient::moderate`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Moderation {
+    pub flagged: bool,
+    /// Categories the input was flagged for, e.g. `harassment` or
+    /// `violence/graphic`, in name order
+    pub categories: Vec<String>,
+    /// The provider's score of each category it rates, from 0 to 1
+    #[serde(default)]
+    pub category_scores: BTreeMap<String, f64>,
+}
+
+impl Moderation {
+    /// An input nothing was flagged in
+    pub fn clean() -> Self {
+        Self::default()
+    }
+
+    /// An input flagged for `categories`
+    pub fn flagged(categories: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            flagged: true,
+            categories: categories.into_iter().map(Into::into).collect(),
+            category_scores: BTreeMap::new(),
+        }
+    }
+}
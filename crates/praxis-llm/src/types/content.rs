@@ -8,38 +8,83 @@ pub enum Content {
     /// Simple text content
     Text(String),
     
-    /// Multipart content (for mixing text + images in future)
+    /// Multipart content, e.g. text with images
     Parts(Vec<ContentPart>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentPart {
     Text {
         text: String,
     },
     
-    // Future: Image support
-    // ImageUrl {
-    //     image_url: ImageUrl,
-    // },
+    /// Image the provider fetches itself (or a `data:` URL)
+    ImageUrl {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<ImageDetail>,
+    },
+    
+    /// Image sent inline, base64-encoded
+    ImageBase64 {
+        data: String,
+        /// e.g. `image/png`
+        mime_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<ImageDetail>,
+    },
+    
+    /// Audio sent inline, base64-encoded (OpenAI's `input_audio`)
+    Audio {
+        data_base64: String,
+        /// e.g. `wav` or `mp3`
+        format: String,
+    },
 }
 
-// Future multimodal support
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ImageUrl {
-//     pub url: String,
-//     #[serde(skip_serializing_if = "Option::is_none")]
-//     pub detail: Option<ImageDetail>,
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// #[serde(rename_all = "lowercase")]
-// pub enum ImageDetail {
-//     Auto,
-//     Low,
-//     High,
-// }
+/// Resolution the model looks at an image with (OpenAI's `detail`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageDetail {
+    Auto,
+    Low,
+    High,
+}
+
+impl ContentPart {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+    
+    pub fn image_url(url: impl Into<String>) -> Self {
+        Self::ImageUrl { url: url.into(), detail: None }
+    }
+    
+    pub fn image_base64(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Self::ImageBase64 { data: data.into(), mime_type: mime_type.into(), detail: None }
+    }
+    
+    pub fn audio(data_base64: impl Into<String>, format: impl Into<String>) -> Self {
+        Self::Audio { data_base64: data_base64.into(), format: format.into() }
+    }
+    
+    /// Set the `detail` of an image part (other parts are returned unchanged)
+    pub fn with_detail(mut self, level: ImageDetail) -> Self {
+        if let Self::ImageUrl { detail, .. } | Self::ImageBase64 { detail, .. } = &mut self {
+            *detail = Some(level);
+        }
+        self
+    }
+    
+    /// The text of a text part
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text { text } => Some(text),
+            Self::ImageUrl { .. } | Self::ImageBase64 { .. } | Self::Audio { .. } => None,
+        }
+    }
+}
 
 impl Content {
     /// Create text content
@@ -48,16 +93,18 @@ impl Content {
     }
     
     /// Get as plain text (if possible)
+    ///
+    /// Image and audio parts are skipped, so text with images still reads as its text;
+    /// None if there are several text parts or none.
     pub fn as_text(&self) -> Option<&str> {
         match self {
             Self::Text(s) => Some(s),
             Self::Parts(parts) => {
-                // If single text part, return it
-                if parts.len() == 1 {
-                    let ContentPart::Text { text } = &parts[0];
-                    return Some(text);
+                let mut texts = parts.iter().filter_map(ContentPart::as_text);
+                match (texts.next(), texts.next()) {
+                    (Some(text), None) => Some(text),
+                    _ => None,
                 }
-                None
             }
         }
     }
@@ -2,6 +2,20 @@ use serde::{Deserialize, Serialize};
 use super::content::Content;
 use super::tool::ToolCall;
 
+/// Marks the end of a prompt prefix worth caching: providers with explicit
+/// prompt caching (Anthropic) cache everything up to and including the
+/// marked message. OpenAI caches long prefixes on its own, so there the hint
+/// only moves marked instructions ahead of the unmarked ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheHint {
+    /// The provider's default lifetime (5 minutes on Anthropic)
+    #[default]
+    Ephemeral,
+    /// An hour, billed higher on writes (Anthropic `ttl: "1h"`)
+    Extended,
+}
+
 /// Praxis message types (high-level, provider-agnostic)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "role", rename_all = "lowercase")]
@@ -12,6 +26,22 @@ pub enum Message {
         
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_hint: Option<CacheHint>,
+    },
+    
+    /// Developer prompt: instructions that rank below the system prompt on
+    /// models with a `developer` role, and are sent as a system message to
+    /// the others (see `ModelCapabilities::developer_role`)
+    Developer {
+        content: Content,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_hint: Option<CacheHint>,
     },
     
     /// User/Human message
@@ -21,6 +51,9 @@ pub enum Message {
         
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_hint: Option<CacheHint>,
     },
     
     /// Assistant/AI message
@@ -49,6 +82,16 @@ impl Message {
         Self::System {
             content: content.into(),
             name: None,
+            cache_hint: None,
+        }
+    }
+    
+    /// Create developer message
+    pub fn developer(content: impl Into<Content>) -> Self {
+        Self::Developer {
+            content: content.into(),
+            name: None,
+            cache_hint: None,
         }
     }
     
@@ -57,6 +100,7 @@ impl Message {
         Self::Human {
             content: content.into(),
             name: None,
+            cache_hint: None,
         }
     }
     
@@ -78,21 +122,77 @@ impl Message {
         }
     }
     
+    /// Create AI message with text alongside its tool calls
+    ///
+    /// This is the `(content, calls)` form: `ai_with_tools` keeps its
+    /// original calls-only signature so existing callers still compile.
+    pub fn ai_with_content_and_tools(content: impl Into<Content>, tool_calls: Vec<ToolCall>) -> Self {
+        Self::AI {
+            content: Some(content.into()),
+            tool_calls: Some(tool_calls),
+            name: None,
+        }
+    }
+    
     /// Create tool result message
-    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<Content>) -> Self {
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<Content>) -> Self {
         Self::Tool {
             tool_call_id: tool_call_id.into(),
             content: content.into(),
         }
     }
     
+    /// Create tool result message
+    #[deprecated(note = "use `Message::tool`")]
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<Content>) -> Self {
+        Self::tool(tool_call_id, content)
+    }
+    
+    /// Mark the prompt up to this message as cacheable (see `CacheHint`);
+    /// assistant and tool messages take no hint and are returned unchanged
+    pub fn with_cache_hint(mut self, hint: CacheHint) -> Self {
+        match &mut self {
+            Self::System { cache_hint, .. } | Self::Developer { cache_hint, .. } | Self::Human { cache_hint, .. } => {
+                *cache_hint = Some(hint);
+            }
+            Self::AI { .. } | Self::Tool { .. } => {}
+        }
+        self
+    }
+    
+    /// The message's cache hint, if any
+    pub fn cache_hint(&self) -> Option<CacheHint> {
+        match self {
+            Self::System { cache_hint, .. } | Self::Developer { cache_hint, .. } | Self::Human { cache_hint, .. } => *cache_hint,
+            Self::AI { .. } | Self::Tool { .. } => None,
+        }
+    }
+    
     /// Get role as string
     pub fn role(&self) -> &str {
         match self {
             Self::System { .. } => "system",
+            Self::Developer { .. } => "developer",
             Self::Human { .. } => "user",
             Self::AI { .. } => "assistant",
             Self::Tool { .. } => "tool",
         }
     }
+    
+    /// The message's text (see `Content::as_text`); None for an AI message
+    /// with only tool calls
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::System { content, .. }
+            | Self::Developer { content, .. }
+            | Self::Human { content, .. }
+            | Self::Tool { content, .. } => content.as_text(),
+            Self::AI { content, .. } => content.as_ref().and_then(Content::as_text),
+        }
+    }
+    
+    /// Whether this is an AI message calling at least one tool
+    pub fn is_tool_call(&self) -> bool {
+        matches!(self, Self::AI { tool_calls: Some(calls), .. } if !calls.is_empty())
+    }
 }
@@ -19,6 +19,9 @@ pub struct FunctionDefinition {
     /// JSON Schema for parameters
     pub parameters: Value,
     
+    /// OpenAI structured outputs: arguments always match `parameters`,
+    /// which must then list every property as required and set
+    /// `"additionalProperties": false`
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strict: Option<bool>,
 }
@@ -35,6 +38,58 @@ impl Tool {
             },
         }
     }
+
+    /// Set [`FunctionDefinition::strict`]
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.function.strict = Some(strict);
+        self
+    }
+
+    /// Check that `parameters` is a JSON Schema object a provider will accept
+    ///
+    /// Catches mangled schemas (e.g. from an MCP server) before they turn
+    /// into an opaque 400 for the whole request. The error names the tool.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.check_parameters()
+            .map_err(|problem| anyhow::anyhow!("Tool '{}' has an invalid input schema: {}", self.function.name, problem))
+    }
+
+    fn check_parameters(&self) -> Result<(), String> {
+        let Some(schema) = self.function.parameters.as_object() else {
+            return Err("not a JSON object".to_string());
+        };
+        if schema.get("type").and_then(Value::as_str) != Some("object") {
+            return Err(r#""type" must be "object""#.to_string());
+        }
+        let properties = match schema.get("properties") {
+            None => None,
+            Some(Value::Object(properties)) => Some(properties),
+            Some(_) => return Err(r#""properties" must be an object"#.to_string()),
+        };
+        if let Some((name, _)) = properties.into_iter().flatten().find(|(_, property)| !property.is_object() && !property.is_boolean()) {
+            return Err(format!("property '{}' must be a schema object", name));
+        }
+        let required = match schema.get("required") {
+            None => Vec::new(),
+            Some(Value::Array(names)) => names
+                .iter()
+                .map(|name| name.as_str().ok_or_else(|| r#""required" must list property names"#.to_string()))
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => return Err(r#""required" must be an array"#.to_string()),
+        };
+        if self.function.strict == Some(true) {
+            if let Some(missing) = required.iter().find(|name| !properties.is_some_and(|p| p.contains_key(**name))) {
+                return Err(format!("strict mode needs required property '{}' in \"properties\"", missing));
+            }
+            if schema.get("additionalProperties") != Some(&Value::Bool(false)) {
+                return Err(r#"strict mode needs "additionalProperties": false"#.to_string());
+            }
+            if let Some(optional) = properties.into_iter().flatten().map(|(name, _)| name).find(|name| !required.contains(&name.as_str())) {
+                return Err(format!("strict mode needs every property required, '{}' is not", optional));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Tool call made by the LLM (in assistant message)
@@ -67,7 +122,11 @@ impl ToolCall {
 }
 
 /// Tool choice parameter (how aggressive to use tools)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// Serializes to the Chat Completions wire format (also used by Azure
+/// OpenAI): `"auto"`, `"none"`, `"required"`, or
+/// `{"type": "function", "function": {"name": "..."}}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum ToolChoice {
     /// "auto" - let model decide
@@ -92,6 +151,37 @@ pub struct ToolChoiceFunction {
     pub name: String,
 }
 
+/// Wire shapes a tool choice deserializes from
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ToolChoiceRepr {
+    Mode(String),
+    Function {
+        #[serde(rename = "type", default = "function_type")]
+        tool_type: String,
+        function: ToolChoiceFunction,
+    },
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+// Derived untagged deserialization would read every string as `Auto`
+impl<'de> Deserialize<'de> for ToolChoice {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match ToolChoiceRepr::deserialize(deserializer)? {
+            ToolChoiceRepr::Mode(mode) => match mode.as_str() {
+                "auto" => Ok(Self::Auto(mode)),
+                "none" => Ok(Self::None(mode)),
+                "required" => Ok(Self::Required(mode)),
+                other => Err(serde::de::Error::unknown_variant(other, &["auto", "none", "required"])),
+            },
+            ToolChoiceRepr::Function { tool_type, function } => Ok(Self::Specific { tool_type, function }),
+        }
+    }
+}
+
 impl ToolChoice {
     pub fn auto() -> Self {
         Self::Auto("auto".to_string())
@@ -105,6 +195,11 @@ impl ToolChoice {
         Self::Required("required".to_string())
     }
     
+    /// Make the model call `tool_name`
+    pub fn function(tool_name: impl Into<String>) -> Self {
+        Self::force(tool_name)
+    }
+    
     pub fn force(tool_name: impl Into<String>) -> Self {
         Self::Specific {
             tool_type: "function".to_string(),
@@ -0,0 +1,178 @@
+//! Checks run on a request before it is sent
+//!
+//! Providers answer a malformed request with a 400 whose message rarely
+//! names the culprit. `ChatRequest::validate` and `ResponseRequest::validate`
+//! catch the common mistakes first and name the field. The clients run them
+//! before every call.
+
+use crate::error::ValidationError;
+use crate::traits::{ChatRequest, ResponseRequest};
+use crate::types::{Message, Tool, ToolChoice};
+
+/// Longest tool name OpenAI and Anthropic accept
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+impl ChatRequest {
+    /// Check the request for mistakes every provider would reject
+    ///
+    /// Messages must not be empty, and the conversation after the leading
+    /// instructions must not open with a tool result. `temperature` must be
+    /// in [0, 2], `top_p` in (0, 1] and `max_tokens` at least 1. Tool names
+    /// must be unique, at most 64 letters, digits, `_` or `-`, and a forced
+    /// `tool_choice` must name one of the tools.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_messages("messages", &self.messages)?;
+        let options = &self.options;
+        if let Some(temperature) = options.temperature {
+            check_temperature(temperature)?;
+        }
+        if let Some(top_p) = options.top_p {
+            if !(top_p > 0.0 && top_p <= 1.0) {
+                return Err(ValidationError::new("options.top_p", format!("must be above 0 and at most 1, got {}", top_p)));
+            }
+        }
+        if options.max_tokens == Some(0) {
+            return Err(ValidationError::new("options.max_tokens", "must be at least 1"));
+        }
+        check_tools(options.tools.as_deref().unwrap_or_default(), options.tool_choice.as_ref())
+    }
+}
+
+impl ResponseRequest {
+    /// Same checks as `ChatRequest::validate`, on `input` and the options
+    /// the Responses API has
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_messages("input", &self.input)?;
+        let options = &self.options;
+        if let Some(temperature) = options.temperature {
+            check_temperature(temperature)?;
+        }
+        if options.max_output_tokens == Some(0) {
+            return Err(ValidationError::new("options.max_output_tokens", "must be at least 1"));
+        }
+        check_tools(options.tools.as_deref().unwrap_or_default(), options.tool_choice.as_ref())
+    }
+}
+
+fn check_messages(field: &str, messages: &[Message]) -> Result<(), ValidationError> {
+    if messages.is_empty() {
+        return Err(ValidationError::new(field, "must not be empty"));
+    }
+    let first = messages
+        .iter()
+        .position(|message| !matches!(message, Message::System { .. } | Message::Developer { .. }));
+    match first {
+        None => Err(ValidationError::new(field, "must have a user message after the instructions")),
+        Some(index) if matches!(messages[index], Message::Tool { .. }) => Err(ValidationError::new(
+            format!("{}[{}]", field, index),
+            "is a tool result without the assistant message that called the tool",
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+fn check_temperature(temperature: f32) -> Result<(), ValidationError> {
+    if (0.0..=2.0).contains(&temperature) {
+        return Ok(());
+    }
+    Err(ValidationError::new("options.temperature", format!("must be between 0 and 2, got {}", temperature)))
+}
+
+fn check_tools(tools: &[Tool], tool_choice: Option<&ToolChoice>) -> Result<(), ValidationError> {
+    for (index, tool) in tools.iter().enumerate() {
+        let name = &tool.function.name;
+        let field = || format!("options.tools[{}].function.name", index);
+        if name.is_empty() || name.len() > MAX_TOOL_NAME_LEN {
+            return Err(ValidationError::new(field(), format!("must be 1 to {} characters, got {}", MAX_TOOL_NAME_LEN, name.len())));
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(ValidationError::new(field(), format!("may only hold letters, digits, '_' and '-', got '{}'", name)));
+        }
+        if let Some(first) = tools[..index].iter().position(|other| other.function.name == *name) {
+            return Err(ValidationError::new(field(), format!("'{}' is already the name of tools[{}]", name, first)));
+        }
+    }
+    if let Some(ToolChoice::Specific { function, .. }) = tool_choice {
+        if !tools.iter().any(|tool| tool.function.name == function.name) {
+            return Err(ValidationError::new(
+                "options.tool_choice",
+                format!("forces tool '{}', which is not in options.tools", function.name),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{ChatOptions, ResponseOptions};
+    use serde_json::json;
+
+    fn tool(name: &str) -> Tool {
+        Tool::new(name, "A tool", json!({ "type": "object" }))
+    }
+
+    fn chat(messages: Vec<Message>, options: ChatOptions) -> Result<(), ValidationError> {
+        ChatRequest::new("gpt-4o", messages).with_options(options).validate()
+    }
+
+    fn field(result: Result<(), ValidationError>) -> String {
+        result.unwrap_err().field
+    }
+
+    #[test]
+    fn test_valid_request_passes() {
+        let options = ChatOptions::new()
+            .temperature(0.0)
+            .top_p(1.0)
+            .max_tokens(1)
+            .tools(vec![tool("get_weather"), tool("search-web")])
+            .tool_choice(ToolChoice::function("search-web"));
+        assert_eq!(chat(vec![Message::system("Be brief."), Message::human("Hi")], options), Ok(()));
+    }
+
+    #[test]
+    fn test_messages_are_checked() {
+        assert_eq!(field(chat(Vec::new(), ChatOptions::new())), "messages");
+        assert_eq!(field(chat(vec![Message::system("Be brief.")], ChatOptions::new())), "messages");
+        let orphan = vec![Message::developer("Be brief."), Message::tool("call_1", "22C"), Message::human("Thanks")];
+        let error = chat(orphan, ChatOptions::new()).unwrap_err();
+        assert_eq!(error.field, "messages[1]");
+        assert!(error.to_string().starts_with("Invalid request: messages[1] is a tool result"), "{}", error);
+    }
+
+    #[test]
+    fn test_sampling_options_are_checked() {
+        let hi = || vec![Message::human("Hi")];
+        let error = chat(hi(), ChatOptions::new().temperature(5.0)).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid request: options.temperature must be between 0 and 2, got 5");
+        assert_eq!(field(chat(hi(), ChatOptions::new().temperature(f32::NAN))), "options.temperature");
+        assert_eq!(field(chat(hi(), ChatOptions::new().top_p(0.0))), "options.top_p");
+        assert_eq!(field(chat(hi(), ChatOptions::new().max_tokens(0))), "options.max_tokens");
+    }
+
+    #[test]
+    fn test_tools_are_checked() {
+        let hi = || vec![Message::human("Hi")];
+        let with_tools = |tools: Vec<Tool>| chat(hi(), ChatOptions::new().tools(tools));
+        assert_eq!(field(with_tools(vec![tool("a"), tool("b"), tool("a")])), "options.tools[2].function.name");
+        assert_eq!(field(with_tools(vec![tool("")])), "options.tools[0].function.name");
+        assert_eq!(field(with_tools(vec![tool(&"x".repeat(65))])), "options.tools[0].function.name");
+        assert_eq!(field(with_tools(vec![tool("files.read")])), "options.tools[0].function.name");
+
+        let forced = ChatOptions::new().tools(vec![tool("a")]).tool_choice(ToolChoice::function("b"));
+        assert_eq!(field(chat(hi(), forced)), "options.tool_choice");
+        assert_eq!(chat(hi(), ChatOptions::new().tool_choice(ToolChoice::none())), Ok(()));
+    }
+
+    #[test]
+    fn test_response_request_names_its_fields() {
+        let request = ResponseRequest::new("o4-mini", Vec::new());
+        assert_eq!(field(request.validate()), "input");
+
+        let request = ResponseRequest::new("o4-mini", vec![Message::human("Hi")])
+            .with_options(ResponseOptions::new().max_output_tokens(0));
+        assert_eq!(field(request.validate()), "options.max_output_tokens");
+    }
+}
@@ -0,0 +1,35 @@
+//! Anthropic Messages streams replayed from captures
+
+use futures::StreamExt;
+use praxis_llm::{CapturedExchange, StreamEvent};
+use serde_json::{json, Value};
+use std::path::Path;
+
+async fn replay(name: &str) -> Vec<Value> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/anthropic/{}.sse", name));
+    CapturedExchange::load(path)
+        .unwrap()
+        .into_event_stream()
+        .map(|event: anyhow::Result<StreamEvent>| serde_json::to_value(event.unwrap()).unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_thinking_text_and_tool_use() {
+    let events = replay("thinking_then_tool_use").await;
+
+    assert_eq!(
+        events,
+        vec![
+            json!({ "type": "reasoning", "content": "The user wants the weather," }),
+            json!({ "type": "reasoning", "content": " so I should call the tool." }),
+            json!({ "type": "message", "content": "Let me check " }),
+            json!({ "type": "message", "content": "that for you." }),
+            json!({ "type": "tool_call", "index": 2, "id": "toolu_01T1x1fJ34qAmk2tNTrN7Up6", "name": "get_weather" }),
+            json!({ "type": "tool_call", "index": 2, "arguments": "{\"city\": \"Lis" }),
+            json!({ "type": "tool_call", "index": 2, "arguments": "bon\"}" }),
+            json!({ "type": "done", "finish_reason": "tool_calls" }),
+        ]
+    );
+}
@@ -0,0 +1,87 @@
+//! Audio input parts and spoken answers
+
+mod common;
+
+use common::{response, serve_recorded};
+use futures::StreamExt;
+use praxis_llm::{AudioOptions, ChatClient, ChatOptions, ChatRequest, Content, ContentPart, Message, OpenAIClient, StreamEvent};
+use serde_json::{json, Value};
+
+const AUDIO_SSE: &str = concat!(
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o-audio-preview\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"audio\":{\"id\":\"audio_1\",\"transcript\":\"Hel\"}},\"finish_reason\":null}]}\n\n",
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o-audio-preview\",\"choices\":[{\"index\":0,\"delta\":{\"audio\":{\"data\":\"UklGRg==\"}},\"finish_reason\":null}]}\n\n",
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o-audio-preview\",\"choices\":[{\"index\":0,\"delta\":{\"audio\":{\"transcript\":\"lo\"}},\"finish_reason\":\"stop\"}]}\n\n",
+    "data: [DONE]\n\n",
+);
+
+const AUDIO_BODY: &str = r#"{
+    "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o-audio-preview",
+    "choices": [{
+        "index": 0,
+        "message": { "role": "assistant", "content": null, "audio": { "id": "audio_1", "data": "UklGRg==", "transcript": "Hello", "expires_at": 1 } },
+        "finish_reason": "stop"
+    }],
+    "usage": { "prompt_tokens": 40, "completion_tokens": 12, "total_tokens": 52 }
+}"#;
+
+fn body(request: &str) -> Value {
+    serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+}
+
+fn spoken_question() -> Vec<Message> {
+    vec![Message::human(Content::Parts(vec![
+        ContentPart::text("Answer the question."),
+        ContentPart::audio("UklGRg==", "wav"),
+    ]))]
+}
+
+#[tokio::test]
+async fn test_audio_parts_and_options_are_sent_and_streamed_back() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", AUDIO_SSE)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let options = ChatOptions::new().audio(AudioOptions::new("alloy", "pcm16"));
+    let request = ChatRequest::new("gpt-4o-audio-preview", spoken_question()).with_options(options);
+
+    let mut stream = client.chat_stream(request).await.unwrap();
+    let (mut transcript, mut audio) = (String::new(), Vec::new());
+    while let Some(event) = stream.next().await {
+        match event.unwrap() {
+            StreamEvent::Message { content, .. } => transcript.push_str(&content),
+            StreamEvent::Audio { data_base64 } => audio.push(data_base64),
+            _ => {}
+        }
+    }
+
+    assert_eq!(transcript, "Hello");
+    assert_eq!(audio, vec!["UklGRg==".to_string()]);
+    let sent = body(&received.lock().unwrap()[0]);
+    assert_eq!(sent["messages"][0]["content"][1], json!({ "type": "input_audio", "input_audio": { "data": "UklGRg==", "format": "wav" } }));
+    assert_eq!(sent["modalities"], json!(["text", "audio"]));
+    assert_eq!(sent["audio"], json!({ "voice": "alloy", "format": "pcm16" }));
+}
+
+#[tokio::test]
+async fn test_transcript_is_the_content_of_an_audio_answer() {
+    let (url, _) = serve_recorded(vec![response("200 OK", &[], "application/json", AUDIO_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let options = ChatOptions::new().audio(AudioOptions::new("alloy", "wav"));
+    let request = ChatRequest::new("gpt-4o-audio-preview", spoken_question()).with_options(options);
+
+    let response = client.chat(request).await.unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("Hello"));
+    assert_eq!(response.raw["choices"][0]["message"]["audio"]["data"], "UklGRg==");
+}
+
+#[tokio::test]
+async fn test_audio_output_is_dropped_for_text_models() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", AUDIO_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let options = ChatOptions::new().audio(AudioOptions::new("alloy", "wav"));
+
+    let response = client.chat(ChatRequest::new("gpt-4o", vec![Message::human("Hi")]).with_options(options)).await.unwrap();
+
+    assert!(response.warnings.iter().any(|warning| warning.option == "audio"));
+    let sent = body(&received.lock().unwrap()[0]);
+    assert!(sent.get("audio").is_none() && sent.get("modalities").is_none());
+}
@@ -0,0 +1,172 @@
+//! Azure OpenAI: `AzureOpenAIClient` against the deployment and Responses
+//! endpoints, and `OpenAIClient` against Azure's v1 API
+//! (`https://<resource>.openai.azure.com/openai/v1`)
+//!
+//! The `tests/fixtures/azure` exchanges are in Azure's shape: chat streams
+//! open with `prompt_filter_results` and carry `content_filter_results` on
+//! each choice. Each `.request.json` holds the payload the client must send.
+
+mod common;
+
+use common::{response, serve_recorded};
+use futures::StreamExt;
+use praxis_llm::{
+    AzureConfig, AzureOpenAIClient, CapabilityRegistry, ChatClient, ChatRequest, Message, ModelCapabilities,
+    OpenAIClient, OpenAIConfig, ReasoningClient, ReasoningConfig, ReasoningEffort, ReasoningKind, ResponseRequest,
+    StreamEvent, SummaryMode,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::Value;
+use std::path::Path;
+
+fn azure_client(url: &str) -> OpenAIClient {
+    let mut headers = HeaderMap::new();
+    headers.insert("api-key", HeaderValue::from_static("azure-key"));
+    let config = OpenAIConfig::new("").with_base_url(format!("{}/openai/v1", url.trim_end_matches("/v1"))).with_default_headers(headers);
+    OpenAIClient::from_config(config).unwrap()
+}
+
+/// Client for the test server, with `o3-mini` and `o4-mini` registered as
+/// reasoning deployments
+fn deployment_client(url: &str, configure: impl FnOnce(AzureConfig) -> AzureConfig, api_version: &str) -> AzureOpenAIClient {
+    let registry = CapabilityRegistry::new()
+        .with_model("o3-mini", ModelCapabilities::reasoning(200_000))
+        .with_model("o4-mini", ModelCapabilities::reasoning(200_000));
+    let config = AzureConfig::new(url.trim_end_matches("/v1"), "azure-key", api_version).with_capabilities(registry);
+    AzureOpenAIClient::from_config(configure(config)).unwrap()
+}
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(path)).unwrap()
+}
+
+fn assert_sent_to_azure(request: &str, path: &str) {
+    let request = request.to_lowercase();
+    assert!(request.starts_with(&format!("post {} ", path)), "{}", request);
+    assert!(request.contains("api-key: azure-key"), "{}", request);
+    assert!(!request.contains("authorization:"), "{}", request);
+}
+
+/// The request went to the fixture's path with the fixture's payload
+fn assert_matches_fixture(request: &str, name: &str) {
+    let recorded: Value = serde_json::from_str(&fixture(&format!("azure/{}.request.json", name))).unwrap();
+    let url = recorded["url"].as_str().unwrap();
+    let path = &url[url.find("/openai/").unwrap()..];
+    assert_sent_to_azure(request, path);
+
+    let (_, body) = request.split_once("\r\n\r\n").unwrap();
+    let sent: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(sent, recorded["payload"]);
+}
+
+fn speed_question() -> ResponseRequest {
+    ResponseRequest::new("o4-mini", vec![Message::human("A train covers 120 km in 2 hours. How fast is it?")])
+        .with_reasoning(ReasoningConfig::new(ReasoningEffort::Low, SummaryMode::Auto))
+}
+
+async fn collect(stream: impl futures::Stream<Item = anyhow::Result<StreamEvent>>) -> Vec<StreamEvent> {
+    stream.map(|event| event.unwrap()).collect().await
+}
+
+fn answer(events: &[StreamEvent]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Message { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_reason_stream_uses_the_responses_api_from_its_api_version() {
+    let sse = fixture("azure/responses_reasoning.sse");
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", &sse)]).await;
+
+    let client = deployment_client(&url, |config| config, "2025-04-01-preview");
+    let events = collect(client.reason_stream(speed_question()).await.unwrap()).await;
+
+    assert_matches_fixture(&received.lock().unwrap()[0], "responses_reasoning");
+    let summary: String = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Reasoning { content, kind: ReasoningKind::Summary, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(summary, "**Dividing distance by time**\n\n120 km over 2 hours.");
+    assert_eq!(answer(&events), "It travels at 60 km/h.");
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Usage { reasoning_tokens: Some(64), .. })));
+}
+
+#[tokio::test]
+async fn test_reason_stream_falls_back_to_chat_completions_on_older_api_versions() {
+    let sse = fixture("azure/chat_fallback.sse");
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", &sse)]).await;
+
+    let client = deployment_client(&url, |config| config, "2024-10-21");
+    let request = ResponseRequest { model: "o3-mini".to_string(), ..speed_question() };
+    let events = collect(client.reason_stream(request).await.unwrap()).await;
+
+    assert_matches_fixture(&received.lock().unwrap()[0], "chat_fallback");
+    assert_eq!(answer(&events), "It travels at 60 km/h.");
+    // Chat completions have no reasoning to stream
+    assert!(!events.iter().any(|event| matches!(event, StreamEvent::Reasoning { .. })));
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Usage { total_tokens: 171, .. })));
+}
+
+#[tokio::test]
+async fn test_use_responses_api_overrides_the_api_version() {
+    let sse = fixture("azure/responses_reasoning.sse");
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", &sse)]).await;
+
+    let client = deployment_client(&url, |config| config.use_responses_api(true), "2024-10-21");
+    let events = collect(client.reason_stream(speed_question()).await.unwrap()).await;
+
+    assert_sent_to_azure(&received.lock().unwrap()[0], "/openai/responses?api-version=2024-10-21");
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Reasoning { .. })));
+}
+
+#[tokio::test]
+async fn test_reason_falls_back_to_a_chat_reply_in_responses_form() {
+    let body = r#"{"id":"chatcmpl-C9xQ2mT7a1","object":"chat.completion","created":1756480496,"model":"o3-mini-2025-01-31","prompt_filter_results":[{"prompt_index":0,"content_filter_results":{}}],"choices":[{"index":0,"message":{"role":"assistant","content":"It travels at 60 km/h."},"finish_reason":"stop","content_filter_results":{}}],"usage":{"prompt_tokens":21,"completion_tokens":150,"total_tokens":171,"completion_tokens_details":{"reasoning_tokens":128}}}"#;
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", body)]).await;
+
+    let client = deployment_client(&url, |config| config, "2024-10-21");
+    let output = client.reason(ResponseRequest { model: "o3-mini".to_string(), ..speed_question() }).await.unwrap();
+
+    assert_sent_to_azure(&received.lock().unwrap()[0], "/openai/deployments/o3-mini/chat/completions?api-version=2024-10-21");
+    assert_eq!(output.message.as_deref(), Some("It travels at 60 km/h."));
+    assert_eq!(output.reasoning, None);
+    assert_eq!(output.raw.id, "chatcmpl-C9xQ2mT7a1");
+    assert_eq!(output.raw.message_text().as_deref(), Some("It travels at 60 km/h."));
+    assert_eq!(output.usage.unwrap().total_tokens, 171);
+}
+
+#[tokio::test]
+async fn test_reason_stream_posts_to_the_v1_responses_path() {
+    let sse = fixture("responses/reasoning_then_message.sse");
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", &sse)]).await;
+
+    let mut stream = azure_client(&url)
+        .reason_stream(ResponseRequest::new("gpt-5-deployment", vec![Message::human("How fast?")]))
+        .await
+        .unwrap();
+    while stream.next().await.is_some() {}
+
+    assert_sent_to_azure(&received.lock().unwrap()[0], "/openai/v1/responses");
+}
+
+#[tokio::test]
+async fn test_chat_stream_posts_to_the_v1_chat_completions_path() {
+    let sse = fixture("chat/multiple_choices.sse");
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", &sse)]).await;
+
+    let mut stream = azure_client(&url)
+        .chat_stream(ChatRequest::new("gpt-4o-deployment", vec![Message::human("Hi")]))
+        .await
+        .unwrap();
+    while stream.next().await.is_some() {}
+
+    assert_sent_to_azure(&received.lock().unwrap()[0], "/openai/v1/chat/completions");
+}
@@ -0,0 +1,107 @@
+//! Chat completion streams replayed from captures
+
+use futures::StreamExt;
+use praxis_llm::{CapturedExchange, StreamEvent};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn load(name: &str) -> CapturedExchange {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/chat/{}.sse", name));
+    CapturedExchange::load(path).unwrap()
+}
+
+async fn replay(exchange: CapturedExchange) -> Vec<Value> {
+    exchange
+        .into_event_stream()
+        .map(|event: anyhow::Result<StreamEvent>| serde_json::to_value(event.unwrap()).unwrap())
+        .collect()
+        .await
+}
+
+/// Azure front-end: CRLF line endings, events split over several `data:`
+/// lines, `event:`/`id:`/`retry:` fields and keep-alive comments
+#[tokio::test]
+async fn test_azure_crlf_multiline_data() {
+    let exchange = load("azure_crlf_multiline");
+    assert!(exchange.sse.windows(2).any(|pair| pair == b"\r\n"));
+
+    assert_eq!(
+        replay(exchange).await,
+        vec![
+            json!({ "type": "message", "content": "Lisbon is " }),
+            json!({ "type": "message", "content": "sunny, 24°C." }),
+            json!({ "type": "usage", "input_tokens": 14, "output_tokens": 9, "total_tokens": 23 }),
+            json!({ "type": "done", "finish_reason": "stop" }),
+            // `data: [DONE]`
+            json!({ "type": "done" }),
+        ]
+    );
+}
+
+
+/// `n: 2`: text and finish reason of the second choice are tagged with its
+/// index; tool calls come from the first
+#[tokio::test]
+async fn test_multiple_choices_tagged_by_index() {
+    assert_eq!(
+        replay(load("multiple_choices")).await,
+        vec![
+            json!({ "type": "message", "content": "Quick brown fox" }),
+            json!({ "type": "message", "content": "Lazy dog", "choice_index": 1 }),
+            json!({ "type": "message", "content": " naps", "choice_index": 1 }),
+            json!({ "type": "usage", "input_tokens": 11, "output_tokens": 7, "total_tokens": 18 }),
+            json!({ "type": "done", "finish_reason": "stop" }),
+            json!({ "type": "done", "finish_reason": "stop", "choice_index": 1 }),
+            json!({ "type": "done" }),
+        ]
+    );
+}
+
+/// `n: 2` where the second choice keeps streaming after the first finished:
+/// its later text still arrives, and each choice reports its own finish
+#[tokio::test]
+async fn test_choices_finish_independently() {
+    assert_eq!(
+        replay(load("interleaved_choices")).await,
+        vec![
+            json!({ "type": "message", "content": "Short" }),
+            json!({ "type": "message", "content": "A longer", "choice_index": 1 }),
+            json!({ "type": "message", "content": " answer cut", "choice_index": 1 }),
+            json!({ "type": "usage", "input_tokens": 11, "output_tokens": 7, "total_tokens": 18 }),
+            json!({ "type": "done", "finish_reason": "stop" }),
+            json!({ "type": "done", "finish_reason": "length", "choice_index": 1 }),
+            json!({ "type": "done" }),
+        ]
+    );
+}
+
+/// `logprobs` with `top_logprobs: 2`: each text delta carries its tokens'
+/// log probabilities and alternatives
+#[tokio::test]
+async fn test_logprobs_ride_on_message_deltas() {
+    let top = |token: &str, logprob: f64, bytes: &[u8]| json!({ "token": token, "logprob": logprob, "bytes": bytes });
+    assert_eq!(
+        replay(load("logprobs")).await,
+        vec![
+            json!({
+                "type": "message",
+                "content": "Yes",
+                "logprobs": [{
+                    "token": "Yes", "logprob": -0.0012, "bytes": [89, 101, 115],
+                    "top_logprobs": [top("Yes", -0.0012, b"Yes"), top("No", -6.75, b"No")],
+                }],
+            }),
+            json!({
+                "type": "message",
+                "content": ".",
+                "logprobs": [{
+                    "token": ".", "logprob": -0.25, "bytes": [46],
+                    "top_logprobs": [top(".", -0.25, b"."), top("!", -1.5, b"!")],
+                }],
+            }),
+            json!({ "type": "usage", "input_tokens": 12, "output_tokens": 2, "total_tokens": 14 }),
+            json!({ "type": "done", "finish_reason": "stop" }),
+            json!({ "type": "done" }),
+        ]
+    );
+}
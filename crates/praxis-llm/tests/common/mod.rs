@@ -0,0 +1,144 @@
+//! A scripted HTTP server for client tests
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// What the server does with one connection
+pub enum Reply {
+    /// Write the bytes and close
+    Full(String),
+    /// Write the bytes, then keep the connection open without sending more
+    Stall(String),
+}
+
+/// Serves `responses` in order, one per connection, and counts requests
+pub async fn serve(responses: Vec<String>) -> (String, Arc<AtomicUsize>) {
+    serve_replies(responses.into_iter().map(Reply::Full).collect()).await
+}
+
+/// Like `serve`, keeping each request's head and body as received
+pub async fn serve_recorded(responses: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let (url, _, received) = serve_inner(responses.into_iter().map(Reply::Full).collect()).await;
+    (url, received)
+}
+
+/// Like `serve`, with control over when each connection ends
+pub async fn serve_replies(replies: Vec<Reply>) -> (String, Arc<AtomicUsize>) {
+    let (url, requests, _) = serve_inner(replies).await;
+    (url, requests)
+}
+
+async fn serve_inner(replies: Vec<Reply>) -> (String, Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let (counter, log) = (requests.clone(), received.clone());
+    tokio::spawn(async move {
+        let mut stalled = Vec::new();
+        for reply in replies {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut socket).await;
+            log.lock().unwrap().push(request);
+            counter.fetch_add(1, Ordering::SeqCst);
+            match reply {
+                Reply::Full(bytes) => {
+                    socket.write_all(bytes.as_bytes()).await.unwrap();
+                    socket.shutdown().await.ok();
+                }
+                Reply::Stall(bytes) => {
+                    socket.write_all(bytes.as_bytes()).await.unwrap();
+                    socket.flush().await.ok();
+                    stalled.push(socket);
+                }
+            }
+        }
+        // Stalled connections stay open until the test ends
+        std::future::pending::<()>().await;
+        drop(stalled);
+    });
+    (url, requests, received)
+}
+
+/// Read one request: headers, then `content-length` bytes of body
+async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = socket.read(&mut chunk).await.unwrap_or(0);
+        buffer.extend_from_slice(&chunk[..read]);
+        let text = String::from_utf8_lossy(&buffer);
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(0);
+            if buffer.len() >= end + 4 + length || read == 0 {
+                return text.into_owned();
+            }
+        } else if read == 0 {
+            return text.into_owned();
+        }
+    }
+}
+
+/// A complete response with `body`
+pub fn response(status: &str, headers: &[&str], content_type: &str, body: &str) -> String {
+    let extra: String = headers.iter().map(|header| format!("{}\r\n", header)).collect();
+    format!(
+        "HTTP/1.1 {}\r\ncontent-type: {}\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        extra,
+        body
+    )
+}
+
+/// Headers of a chunked SSE response followed by the first `events`
+pub fn sse_start(events: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+        events.len(),
+        events
+    )
+}
+
+/// Answers every request with `body` as JSON on kept-alive connections,
+/// counting connections and keeping each request as received
+pub async fn serve_keep_alive(body: &'static str) -> (String, Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let (counter, log) = (connections.clone(), received.clone());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+            let log = log.clone();
+            tokio::spawn(async move {
+                loop {
+                    let request = read_request(&mut socket).await;
+                    if request.is_empty() {
+                        break;
+                    }
+                    log.lock().unwrap().push(request);
+                    let reply = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    if socket.write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    (url, connections, received)
+}
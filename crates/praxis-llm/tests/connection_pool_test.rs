@@ -0,0 +1,69 @@
+//! Clients reuse pooled connections, and clients given one `reqwest::Client`
+//! share its pool while keeping their own credentials
+
+mod common;
+
+use common::serve_keep_alive;
+use praxis_llm::http::DEFAULT_CONNECT_TIMEOUT;
+use praxis_llm::{ChatClient, ChatRequest, ConnectionOptions, Message, OpenAIClient, OpenAIConfig};
+use std::sync::atomic::Ordering;
+
+const CHAT_BODY: &str = r#"{
+    "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o",
+    "choices": [{ "index": 0, "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" }],
+    "usage": { "prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4 }
+}"#;
+
+async fn say_hi(client: &OpenAIClient) {
+    client.chat(ChatRequest::new("gpt-4o", vec![Message::human("Hi")])).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_clients_sharing_an_http_client_share_its_connections() {
+    let (url, connections, received) = serve_keep_alive(CHAT_BODY).await;
+    let shared = ConnectionOptions::new()
+        .with_pool_max_idle_per_host(8)
+        .build_client(DEFAULT_CONNECT_TIMEOUT)
+        .unwrap();
+    let first = OpenAIClient::from_config(
+        OpenAIConfig::new("sk-first").with_base_url(url.clone()).with_http_client(shared.clone()),
+    )
+    .unwrap();
+    let second = OpenAIClient::from_config(
+        OpenAIConfig::new("sk-second").with_base_url(url).with_http_client(shared),
+    )
+    .unwrap();
+
+    say_hi(&first).await;
+    say_hi(&second).await;
+    say_hi(&first).await;
+
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+    let received = received.lock().unwrap().iter().map(|request| request.to_lowercase()).collect::<Vec<_>>();
+    assert!(received[0].contains("authorization: bearer sk-first\r\n"), "{}", received[0]);
+    assert!(received[1].contains("authorization: bearer sk-second\r\n"), "{}", received[1]);
+    assert!(!received[1].contains("sk-first"), "{}", received[1]);
+}
+
+#[tokio::test]
+async fn test_clients_with_their_own_pools_open_their_own_connections() {
+    let (url, connections, _) = serve_keep_alive(CHAT_BODY).await;
+    let first = OpenAIClient::with_base_url("sk-test", url.clone()).unwrap();
+    let second = OpenAIClient::with_base_url("sk-test", url.clone()).unwrap();
+
+    say_hi(&first).await;
+    say_hi(&first).await;
+    say_hi(&second).await;
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+    // Without idle connections kept, every request opens a new one
+    let unpooled = OpenAIClient::from_config(
+        OpenAIConfig::new("sk-test")
+            .with_base_url(url)
+            .with_connection_options(ConnectionOptions::new().with_pool_max_idle_per_host(0)),
+    )
+    .unwrap();
+    say_hi(&unpooled).await;
+    say_hi(&unpooled).await;
+    assert_eq!(connections.load(Ordering::SeqCst), 4);
+}
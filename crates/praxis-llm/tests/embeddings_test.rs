@@ -0,0 +1,73 @@
+//! OpenAIClient embeddings against a local server
+
+mod common;
+
+use common::{response, serve};
+use praxis_llm::openai::MAX_EMBEDDING_INPUTS;
+use praxis_llm::{EmbeddingsClient, OpenAIClient};
+use std::sync::atomic::Ordering;
+
+/// `/embeddings` body answering `count` inputs, listed last to first;
+/// input `i` embeds as `[offset + i, 0.5]`
+fn embeddings_body(count: usize, offset: usize, tokens: u32) -> String {
+    let data: Vec<_> = (0..count)
+        .rev()
+        .map(|index| {
+            serde_json::json!({ "object": "embedding", "index": index, "embedding": [(offset + index) as f32, 0.5] })
+        })
+        .collect();
+    serde_json::json!({
+        "object": "list",
+        "data": data,
+        "model": "text-embedding-3-small",
+        "usage": { "prompt_tokens": tokens, "total_tokens": tokens },
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_embed_returns_vectors_in_input_order() {
+    let (url, requests) = serve(vec![response("200 OK", &[], "application/json", &embeddings_body(3, 0, 9))]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let inputs = vec!["red".to_string(), "green".to_string(), "blue".to_string()];
+    let embeddings = client.embed("text-embedding-3-small", inputs).await.unwrap();
+
+    assert_eq!(embeddings.vectors, vec![vec![0.0, 0.5], vec![1.0, 0.5], vec![2.0, 0.5]]);
+    assert_eq!(embeddings.usage.input_tokens, 9);
+    assert_eq!(embeddings.usage.output_tokens, 0);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_embed_splits_inputs_over_the_batch_limit() {
+    let (url, requests) = serve(vec![
+        response("200 OK", &[], "application/json", &embeddings_body(MAX_EMBEDDING_INPUTS, 0, 2048)),
+        response("200 OK", &[], "application/json", &embeddings_body(1, MAX_EMBEDDING_INPUTS, 1)),
+    ])
+    .await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let inputs = vec!["word".to_string(); MAX_EMBEDDING_INPUTS + 1];
+    let embeddings = client.embed("text-embedding-3-small", inputs).await.unwrap();
+
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(embeddings.vectors.len(), MAX_EMBEDDING_INPUTS + 1);
+    assert_eq!(embeddings.vectors[MAX_EMBEDDING_INPUTS][0], MAX_EMBEDDING_INPUTS as f32);
+    assert_eq!(embeddings.usage.total_tokens, 2049);
+}
+
+#[tokio::test]
+async fn test_embed_surfaces_api_errors() {
+    let (url, _) = serve(vec![response(
+        "400 Bad Request",
+        &[],
+        "application/json",
+        r#"{"error":{"message":"model not found"}}"#,
+    )])
+    .await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let error = client.embed("nope", vec!["hi".to_string()]).await.unwrap_err();
+    assert!(error.to_string().contains("model not found"));
+}
@@ -0,0 +1,82 @@
+//! Raw `extra_body` fields are merged into the request without replacing known fields
+
+mod common;
+
+use common::{response, serve_recorded};
+use futures::StreamExt;
+use praxis_llm::{ChatClient, ChatOptions, ChatRequest, Message, OpenAIClient, ReasoningClient, ResponseOptions, ResponseRequest};
+use serde_json::{json, Map, Value};
+
+const CHAT_BODY: &str = r#"{
+    "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o",
+    "choices": [{ "index": 0, "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" }],
+    "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 }
+}"#;
+
+const CHAT_SSE: &str = concat!(
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}]}\n\n",
+    "data: [DONE]\n\n",
+);
+
+const RESPONSE_BODY: &str = r#"{
+    "id": "resp_1", "object": "response", "created_at": 0, "status": "completed", "model": "o4-mini",
+    "output": [{ "type": "message", "id": "msg_1", "role": "assistant", "status": "completed",
+                 "content": [{ "type": "output_text", "text": "Hi", "annotations": [] }] }],
+    "usage": { "input_tokens": 5, "output_tokens": 1, "total_tokens": 6 }
+}"#;
+
+fn body(request: &str) -> Value {
+    serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+}
+
+fn extra(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_extra_fields_are_added_and_known_fields_win() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", CHAT_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let options = ChatOptions::new().temperature(0.2).extra_body(extra(json!({
+        "store": true,
+        "prediction": { "type": "content", "content": "Hello" },
+        "model": "gpt-4o-mini",
+        "temperature": 1.5,
+    })));
+
+    client.chat(ChatRequest::new("gpt-4o", vec![Message::human("Hi")]).with_options(options)).await.unwrap();
+
+    let sent = body(&received.lock().unwrap()[0]);
+    assert_eq!(sent["store"], true);
+    assert_eq!(sent["prediction"], json!({ "type": "content", "content": "Hello" }));
+    assert_eq!(sent["model"], "gpt-4o");
+    assert_eq!(sent["temperature"].as_f64().unwrap() as f32, 0.2);
+}
+
+#[tokio::test]
+async fn test_nested_objects_merge_key_by_key() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "text/event-stream", CHAT_SSE)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let options = ChatOptions::new().extra_body(extra(json!({
+        "stream_options": { "include_usage": false, "include_obfuscation": false },
+    })));
+
+    let mut stream = client.chat_stream(ChatRequest::new("gpt-4o", vec![Message::human("Hi")]).with_options(options)).await.unwrap();
+    while stream.next().await.is_some() {}
+
+    let sent = body(&received.lock().unwrap()[0]);
+    assert_eq!(sent["stream_options"], json!({ "include_usage": true, "include_obfuscation": false }));
+}
+
+#[tokio::test]
+async fn test_response_requests_take_extra_fields() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", RESPONSE_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let options = ResponseOptions::new().extra_body(extra(json!({ "store": false, "stream": true })));
+
+    client.reason(ResponseRequest::new("o4-mini", vec![Message::human("Hi")]).with_options(options)).await.unwrap();
+
+    let sent = body(&received.lock().unwrap()[0]);
+    assert_eq!(sent["store"], false);
+    assert_eq!(sent["stream"], false);
+}
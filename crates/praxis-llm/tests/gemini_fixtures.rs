@@ -0,0 +1,31 @@
+//! Gemini `streamGenerateContent` streams replayed from captures
+
+use futures::StreamExt;
+use praxis_llm::{CapturedExchange, StreamEvent};
+use serde_json::{json, Value};
+use std::path::Path;
+
+async fn replay(name: &str) -> Vec<Value> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/gemini/{}.sse", name));
+    CapturedExchange::load(path)
+        .unwrap()
+        .into_event_stream()
+        .map(|event: anyhow::Result<StreamEvent>| serde_json::to_value(event.unwrap()).unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_text_then_function_call() {
+    let events = replay("text_then_function_call").await;
+
+    assert_eq!(
+        events,
+        vec![
+            json!({ "type": "message", "content": "Let me check " }),
+            json!({ "type": "message", "content": "that for you." }),
+            json!({ "type": "tool_call", "index": 0, "id": "gemini_call_0", "name": "get_weather", "arguments": "{\"city\":\"Lisbon\"}" }),
+            json!({ "type": "done", "finish_reason": "tool_calls" }),
+        ]
+    );
+}
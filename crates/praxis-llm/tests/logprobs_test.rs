@@ -0,0 +1,59 @@
+//! Token log probabilities of a chat completion
+
+mod common;
+
+use common::{response, serve_recorded};
+use praxis_llm::{ChatClient, ChatOptions, ChatRequest, Message, OpenAIClient, TokenLogprob, TopLogprob};
+
+const CHAT_BODY: &str = r#"{
+    "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o",
+    "choices": [{
+        "index": 0,
+        "message": { "role": "assistant", "content": "Yes" },
+        "logprobs": { "content": [{
+            "token": "Yes", "logprob": -0.01, "bytes": [89, 101, 115],
+            "top_logprobs": [{ "token": "Yes", "logprob": -0.01, "bytes": [89, 101, 115] }, { "token": "No", "logprob": -4.5, "bytes": null }]
+        }], "refusal": null },
+        "finish_reason": "stop"
+    }],
+    "usage": { "prompt_tokens": 9, "completion_tokens": 1, "total_tokens": 10 }
+}"#;
+
+#[tokio::test]
+async fn test_chat_returns_token_logprobs() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", CHAT_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let request = ChatRequest::new("gpt-4o", vec![Message::human("Is Lisbon in Portugal?")])
+        .with_options(ChatOptions::new().logprobs(true).top_logprobs(2));
+
+    let response = client.chat(request).await.unwrap();
+
+    let expected = vec![TokenLogprob {
+        token: "Yes".to_string(),
+        logprob: -0.01,
+        bytes: Some(b"Yes".to_vec()),
+        top_logprobs: vec![
+            TopLogprob { token: "Yes".to_string(), logprob: -0.01, bytes: Some(b"Yes".to_vec()) },
+            TopLogprob { token: "No".to_string(), logprob: -4.5, bytes: None },
+        ],
+    }];
+    assert_eq!(response.logprobs, Some(expected.clone()));
+    assert_eq!(response.choices[0].logprobs, Some(expected));
+
+    let request = received.lock().unwrap()[0].clone();
+    assert!(request.contains(r#""logprobs":true"#), "{}", request);
+    assert!(request.contains(r#""top_logprobs":2"#), "{}", request);
+}
+
+#[tokio::test]
+async fn test_logprobs_are_left_out_unless_asked_for() {
+    let body = CHAT_BODY.replace(r#""logprobs": { "content""#, r#""unused": { "content""#);
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", &body)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let response = client.chat(ChatRequest::new("gpt-4o", vec![Message::human("Hi")])).await.unwrap();
+
+    assert_eq!(response.logprobs, None);
+    assert!(serde_json::to_value(&response).unwrap().get("logprobs").is_none());
+    assert!(!received.lock().unwrap()[0].contains("logprobs"));
+}
@@ -0,0 +1,43 @@
+//! OpenAIClient moderation against a local server
+
+mod common;
+
+use common::{response, serve};
+use praxis_llm::{ModerationClient, OpenAIClient};
+
+#[tokio::test]
+async fn test_moderate_lists_flagged_categories() {
+    let body = serde_json::json!({
+        "id": "modr-123",
+        "model": "omni-moderation-latest",
+        "results": [{
+            "flagged": true,
+            "categories": { "violence": true, "harassment": true, "self-harm": false },
+            "category_scores": { "violence": 0.91, "harassment": 0.62, "self-harm": 0.01 },
+        }],
+    })
+    .to_string();
+    let (url, _) = serve(vec![response("200 OK", &[], "application/json", &body)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let moderation = client.moderate("some text").await.unwrap();
+
+    assert!(moderation.flagged);
+    assert_eq!(moderation.categories, ["harassment", "violence"]);
+    assert_eq!(moderation.category_scores["violence"], 0.91);
+}
+
+#[tokio::test]
+async fn test_moderate_surfaces_api_errors() {
+    let (url, _) = serve(vec![response(
+        "401 Unauthorized",
+        &[],
+        "application/json",
+        r#"{"error":{"message":"Incorrect API key provided"}}"#,
+    )])
+    .await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let error = client.moderate("hi").await.unwrap_err();
+    assert!(error.to_string().contains("Incorrect API key"));
+}
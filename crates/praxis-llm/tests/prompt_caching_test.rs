@@ -0,0 +1,48 @@
+//! Cache hints on messages and cached-token usage
+
+mod common;
+
+use common::{response, serve_recorded};
+use praxis_llm::{CacheHint, ChatClient, ChatRequest, Message, OpenAIClient};
+use serde_json::{json, Value};
+
+const CHAT_BODY: &str = r#"{
+    "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o",
+    "choices": [{ "index": 0, "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" }],
+    "usage": { "prompt_tokens": 2006, "completion_tokens": 1, "total_tokens": 2007, "prompt_tokens_details": { "cached_tokens": 1920 } }
+}"#;
+
+fn body(request: &str) -> Value {
+    serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_openai_sends_hinted_instructions_first_and_reports_cached_tokens() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", CHAT_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+    let messages = vec![
+        Message::system("Today is Monday."),
+        Message::system("You are a travel agent.").with_cache_hint(CacheHint::Ephemeral),
+        Message::human("Hi"),
+    ];
+
+    let response = client.chat(ChatRequest::new("gpt-4o", messages)).await.unwrap();
+
+    let usage = response.usage.unwrap();
+    assert_eq!((usage.input_tokens, usage.cached_tokens), (2006, Some(1920)));
+    let sent = body(&received.lock().unwrap()[0]);
+    assert_eq!(sent["messages"][0], json!({ "role": "system", "content": "You are a travel agent." }));
+    assert_eq!(sent["messages"][1]["content"], "Today is Monday.");
+    assert_eq!(sent["messages"][2]["role"], "user");
+}
+
+#[test]
+fn test_cache_hint_round_trips_and_is_left_out_when_unset() {
+    let message = Message::developer("Rules").with_cache_hint(CacheHint::Ephemeral);
+    let value = serde_json::to_value(&message).unwrap();
+    assert_eq!(value["cache_hint"], "ephemeral");
+    assert_eq!(serde_json::from_value::<Message>(value).unwrap().cache_hint(), Some(CacheHint::Ephemeral));
+
+    assert!(serde_json::to_value(Message::human("Hi")).unwrap().get("cache_hint").is_none());
+    assert_eq!(Message::ai("Hi").with_cache_hint(CacheHint::Ephemeral).cache_hint(), None);
+}
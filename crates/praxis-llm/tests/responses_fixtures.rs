@@ -0,0 +1,100 @@
+//! Snapshot tests for the Responses API stream event taxonomy
+//!
+//! Each `tests/fixtures/responses/<name>.sse` is replayed through the
+//! Responses parser and the resulting events are compared with
+//! `<name>.events.json`. Run with `UPDATE_SNAPSHOTS=1` to rewrite the
+//! snapshots after an intended change, then review the diff.
+
+use futures::StreamExt;
+use praxis_llm::CapturedExchange;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/responses")
+}
+
+/// Replay a fixture; errors become `{"error": ...}` entries
+async fn replay(name: &str) -> Value {
+    let exchange = CapturedExchange::load(fixture_dir().join(format!("{}.sse", name))).unwrap();
+    let events: Vec<Value> = exchange
+        .into_event_stream()
+        .map(|event| match event {
+            Ok(event) => serde_json::to_value(event).unwrap(),
+            Err(e) => json!({ "error": e.to_string() }),
+        })
+        .collect()
+        .await;
+    Value::Array(events)
+}
+
+async fn assert_snapshot(name: &str) {
+    let actual = replay(name).await;
+    let path = fixture_dir().join(format!("{}.events.json", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+    let expected: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        actual,
+        expected,
+        "{} no longer matches its snapshot:\n{}",
+        name,
+        serde_json::to_string_pretty(&actual).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_reasoning_then_message() {
+    assert_snapshot("reasoning_then_message").await;
+}
+
+#[tokio::test]
+async fn test_message_only() {
+    // The message is output item 0; it must not be taken for reasoning
+    assert_snapshot("message_only").await;
+}
+
+#[tokio::test]
+async fn test_multiple_summary_parts() {
+    assert_snapshot("multiple_summary_parts").await;
+}
+
+#[tokio::test]
+async fn test_incomplete() {
+    assert_snapshot("incomplete").await;
+}
+
+#[tokio::test]
+async fn test_unknown_event_is_skipped() {
+    assert_snapshot("unknown_event").await;
+}
+
+#[tokio::test]
+async fn test_reasoning_then_function_call() {
+    assert_snapshot("reasoning_then_function_call").await;
+}
+
+#[tokio::test]
+async fn test_interleaved_items() {
+    // A function call first, then two reasoning items each followed by text:
+    // every delta is classified by its event type, and carries its item id
+    assert_snapshot("interleaved_items").await;
+}
+
+#[tokio::test]
+async fn test_failed() {
+    assert_snapshot("failed").await;
+}
+
+#[test]
+fn test_every_fixture_has_a_snapshot() {
+    for entry in std::fs::read_dir(fixture_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "sse") {
+            assert!(path.with_extension("events.json").exists(), "{} has no snapshot", path.display());
+        }
+    }
+}
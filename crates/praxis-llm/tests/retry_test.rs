@@ -0,0 +1,103 @@
+//! OpenAIClient retries against a local server that fails before it answers
+
+mod common;
+
+use common::{response, serve};
+use futures::StreamExt;
+use praxis_llm::{ChatClient, ChatRequest, Message, OpenAIClient, OpenAIConfig, RateLimited, RetryPolicy, StreamEvent};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+const CHAT_BODY: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt-4o","choices":[{"index":0,"message":{"role":"assistant","content":"Hello"},"finish_reason":"stop"}],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}"#;
+
+const CHAT_SSE: &str = "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"}}]}\n\n\
+data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n\
+data: [DONE]\n\n";
+
+fn rate_limited(retry_after_ms: u64) -> String {
+    let header = format!("retry-after-ms: {}", retry_after_ms);
+    response("429 Too Many Requests", &[&header], "application/json", r#"{"error":{"message":"slow down"}}"#)
+}
+
+fn client(url: &str, policy: RetryPolicy) -> OpenAIClient {
+    OpenAIClient::from_config(OpenAIConfig::new("sk-test").with_base_url(url).with_retry_policy(policy)).unwrap()
+}
+
+fn quick_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(max_attempts)
+        .with_base_delay(Duration::from_millis(5))
+        .with_max_delay(Duration::from_secs(1))
+}
+
+fn request() -> ChatRequest {
+    ChatRequest::new("gpt-4o", vec![Message::human("hi")])
+}
+
+#[tokio::test]
+async fn test_chat_retries_rate_limits_then_succeeds() {
+    let (url, requests) = serve(vec![
+        rate_limited(10),
+        rate_limited(10),
+        response("200 OK", &[], "application/json", CHAT_BODY),
+    ])
+    .await;
+
+    let reply = client(&url, quick_policy(3)).chat(request()).await.unwrap();
+
+    assert_eq!(reply.content.as_deref(), Some("Hello"));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_stream_retries_before_the_first_byte() {
+    let (url, requests) = serve(vec![
+        rate_limited(10),
+        response("503 Service Unavailable", &[], "application/json", "{}"),
+        response("200 OK", &[], "text/event-stream", CHAT_SSE),
+    ])
+    .await;
+
+    let mut stream = client(&url, quick_policy(3)).chat_stream(request()).await.unwrap();
+    let mut text = String::new();
+    while let Some(event) = stream.next().await {
+        if let StreamEvent::Message { content, .. } = event.unwrap() {
+            text.push_str(&content);
+        }
+    }
+
+    assert_eq!(text, "Hello");
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_attempts() {
+    let (url, requests) = serve(vec![rate_limited(10), rate_limited(10), rate_limited(10)]).await;
+
+    let err = client(&url, quick_policy(2)).chat(request()).await.unwrap_err();
+
+    assert!(RateLimited::of(&err).is_some());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_long_retry_after_is_left_to_the_caller() {
+    let (url, requests) = serve(vec![rate_limited(60_000), response("200 OK", &[], "application/json", CHAT_BODY)]).await;
+
+    let err = client(&url, quick_policy(3)).chat(request()).await.unwrap_err();
+
+    let limited = RateLimited::of(&err).unwrap();
+    assert_eq!(limited.retry_after, Some(Duration::from_secs(60)));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() {
+    let (url, requests) = serve(vec![
+        response("400 Bad Request", &[], "application/json", r#"{"error":{"message":"bad"}}"#),
+        response("200 OK", &[], "application/json", CHAT_BODY),
+    ])
+    .await;
+
+    assert!(client(&url, quick_policy(3)).chat(request()).await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
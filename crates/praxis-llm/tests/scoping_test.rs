@@ -0,0 +1,27 @@
+//! Organization and project headers reach the server
+
+mod common;
+
+use common::{response, serve_recorded};
+use praxis_llm::{ChatClient, ChatRequest, Message, OpenAIClient, OpenAIConfig};
+
+const CHAT_BODY: &str = r#"{
+    "id": "chatcmpl-1", "object": "chat.completion", "created": 0, "model": "gpt-4o",
+    "choices": [{ "index": 0, "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" }],
+    "usage": { "prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4 }
+}"#;
+
+#[tokio::test]
+async fn test_requests_carry_the_organization_and_project() {
+    let (url, received) = serve_recorded(vec![response("200 OK", &[], "application/json", CHAT_BODY)]).await;
+    let client = OpenAIClient::from_config(
+        OpenAIConfig::new("sk-test").with_base_url(url).with_organization("org-123").with_project("proj_billing"),
+    )
+    .unwrap();
+
+    client.chat(ChatRequest::new("gpt-4o", vec![Message::human("Hi")])).await.unwrap();
+
+    let request = received.lock().unwrap()[0].to_lowercase();
+    assert!(request.contains("openai-organization: org-123\r\n"), "{}", request);
+    assert!(request.contains("openai-project: proj_billing\r\n"), "{}", request);
+}
@@ -0,0 +1,77 @@
+//! OpenAIClient response headers against a local server: StatsRecordingClient
+//! headroom, and request ids on responses and errors
+
+mod common;
+
+use common::{response, serve};
+use futures::StreamExt;
+use praxis_llm::{ChatClient, ChatRequest, Message, OpenAIClient, OpenAIConfig, StatsRecordingClient, StreamEvent};
+use std::sync::Arc;
+
+const CHAT_BODY: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt-4o","choices":[{"index":0,"message":{"role":"assistant","content":"Hello"},"finish_reason":"stop"}],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}"#;
+
+const BROKEN_SSE: &str = "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"}}]}\n\n\
+data: {not json}\n\n\
+data: [DONE]\n\n";
+
+fn request() -> ChatRequest {
+    ChatRequest::new("gpt-4o", vec![Message::human("hi")])
+}
+
+#[tokio::test]
+async fn test_records_outcomes_and_rate_limit_headroom() {
+    let (url, _) = serve(vec![
+        response(
+            "200 OK",
+            &["x-ratelimit-remaining-requests: 499", "x-ratelimit-limit-requests: 500"],
+            "application/json",
+            CHAT_BODY,
+        ),
+        response("400 Bad Request", &[], "application/json", r#"{"error":{"message":"bad"}}"#),
+        response("200 OK", &[], "text/event-stream", BROKEN_SSE),
+    ])
+    .await;
+    let openai = OpenAIClient::from_config(OpenAIConfig::new("sk-test").with_base_url(&url)).unwrap();
+    let client = StatsRecordingClient::new(Arc::new(openai), "openai");
+
+    client.chat(request()).await.unwrap();
+    assert!(client.chat(request()).await.is_err());
+    let events: Vec<_> = client.chat_stream(request()).await.unwrap().collect().await;
+    assert!(events.iter().any(|event| event.is_err()));
+
+    let stats = client.stats();
+    assert_eq!(stats.provider, "openai");
+    assert_eq!((stats.calls, stats.failures), (3, 2));
+    let headroom = stats.rate_limit.expect("headroom from the first response");
+    assert_eq!((headroom.remaining_requests, headroom.limit_requests), (Some(499), Some(500)));
+    assert!(stats.last_error.unwrap().message.contains("Failed to parse chat chunk"));
+}
+
+const CHAT_SSE: &str = "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}]}\n\n\
+data: [DONE]\n\n";
+
+#[tokio::test]
+async fn test_request_id_and_rate_limits_reach_responses_and_errors() {
+    let headers = ["x-request-id: req_1", "x-ratelimit-remaining-tokens: 29000", "x-ratelimit-reset-tokens: 6m0s"];
+    let (url, _) = serve(vec![
+        response("200 OK", &headers, "application/json", CHAT_BODY),
+        response("200 OK", &["x-request-id: req_2"], "text/event-stream", CHAT_SSE),
+        response("500 Internal Server Error", &["x-request-id: req_3"], "application/json", r#"{"error":{"message":"oops"}}"#),
+    ])
+    .await;
+    let client = OpenAIClient::from_config(OpenAIConfig::new("sk-test").with_base_url(&url)).unwrap();
+
+    let metadata = client.chat(request()).await.unwrap().metadata.unwrap();
+    assert_eq!(metadata.request_id.as_deref(), Some("req_1"));
+    assert_eq!(metadata.ratelimit_remaining_tokens, Some(29000));
+    assert_eq!(metadata.ratelimit_reset.as_deref(), Some("6m0s"));
+
+    let mut stream = client.chat_stream(request()).await.unwrap();
+    match stream.next().await {
+        Some(Ok(StreamEvent::ResponseMetadata { metadata })) => assert_eq!(metadata.request_id.as_deref(), Some("req_2")),
+        other => panic!("expected ResponseMetadata first, got {:?}", other),
+    }
+
+    let error = client.chat(request()).await.unwrap_err();
+    assert!(error.to_string().contains("(request id req_3)"), "{}", error);
+}
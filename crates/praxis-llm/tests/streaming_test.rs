@@ -1,13 +1,19 @@
-use praxis_llm::StreamEvent;
+mod common;
+
+use common::{response, serve};
+use futures::StreamExt;
+use praxis_llm::{ChatClient, ChatRequest, Message, OpenAIClient, OpenAIConfig, ReasoningKind, StreamEvent};
 
 #[test]
 fn test_stream_event_message() {
     let event = StreamEvent::Message {
         content: "Hello".to_string(),
+        choice_index: None,
+        logprobs: None,
     };
     
     match event {
-        StreamEvent::Message { content } => assert_eq!(content, "Hello"),
+        StreamEvent::Message { content, .. } => assert_eq!(content, "Hello"),
         _ => panic!("Expected Message variant"),
     }
 }
@@ -16,10 +22,12 @@ fn test_stream_event_message() {
 fn test_stream_event_reasoning() {
     let event = StreamEvent::Reasoning {
         content: "Thinking...".to_string(),
+        kind: ReasoningKind::Raw,
+        item_id: None,
     };
     
     match event {
-        StreamEvent::Reasoning { content } => assert_eq!(content, "Thinking..."),
+        StreamEvent::Reasoning { content, .. } => assert_eq!(content, "Thinking..."),
         _ => panic!("Expected Reasoning variant"),
     }
 }
@@ -48,10 +56,11 @@ fn test_stream_event_tool_call() {
 fn test_stream_event_done() {
     let event = StreamEvent::Done {
         finish_reason: Some("stop".to_string()),
+        choice_index: None,
     };
     
     match event {
-        StreamEvent::Done { finish_reason } => {
+        StreamEvent::Done { finish_reason, .. } => {
             assert_eq!(finish_reason, Some("stop".to_string()));
         },
         _ => panic!("Expected Done variant"),
@@ -62,6 +71,8 @@ fn test_stream_event_done() {
 fn test_stream_event_serialization_message() {
     let event = StreamEvent::Message {
         content: "Test".to_string(),
+        choice_index: None,
+        logprobs: None,
     };
     
     let json = serde_json::to_string(&event).unwrap();
@@ -73,6 +84,8 @@ fn test_stream_event_serialization_message() {
 fn test_stream_event_serialization_reasoning() {
     let event = StreamEvent::Reasoning {
         content: "Analyze".to_string(),
+        kind: ReasoningKind::Raw,
+        item_id: None,
     };
     
     let json = serde_json::to_string(&event).unwrap();
@@ -85,7 +98,7 @@ fn test_stream_event_deserialization_message() {
     let event: StreamEvent = serde_json::from_str(json).unwrap();
     
     match event {
-        StreamEvent::Message { content } => assert_eq!(content, "Hello"),
+        StreamEvent::Message { content, .. } => assert_eq!(content, "Hello"),
         _ => panic!("Expected Message variant"),
     }
 }
@@ -105,12 +118,14 @@ fn test_stream_event_deserialization_tool_call() {
 fn test_stream_event_clone() {
     let event = StreamEvent::Message {
         content: "Original".to_string(),
+        choice_index: None,
+        logprobs: None,
     };
     
     let cloned = event.clone();
     
     match (event, cloned) {
-        (StreamEvent::Message { content: c1 }, StreamEvent::Message { content: c2 }) => {
+        (StreamEvent::Message { content: c1, .. }, StreamEvent::Message { content: c2, .. }) => {
             assert_eq!(c1, c2);
         },
         _ => panic!("Clone should preserve variant"),
@@ -121,6 +136,8 @@ fn test_stream_event_clone() {
 fn test_stream_event_debug() {
     let event = StreamEvent::Message {
         content: "Debug test".to_string(),
+        choice_index: None,
+        logprobs: None,
     };
     
     let debug_str = format!("{:?}", event);
@@ -151,13 +168,40 @@ fn test_stream_event_tool_call_partial() {
 fn test_stream_event_done_no_reason() {
     let event = StreamEvent::Done {
         finish_reason: None,
+        choice_index: None,
     };
     
     match event {
-        StreamEvent::Done { finish_reason } => {
+        StreamEvent::Done { finish_reason, .. } => {
             assert_eq!(finish_reason, None);
         },
         _ => panic!("Expected Done variant"),
     }
 }
 
+/// Usage arrives in a chunk of its own after `finish_reason`, before `[DONE]`
+const CHAT_SSE_WITH_USAGE: &str = "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"o4-mini\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}]}\n\n\
+data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"o4-mini\",\"choices\":[],\"usage\":{\"prompt_tokens\":9,\"completion_tokens\":40,\"total_tokens\":49,\"completion_tokens_details\":{\"reasoning_tokens\":32}}}\n\n\
+data: [DONE]\n\n";
+
+#[tokio::test]
+async fn test_chat_stream_reports_usage_before_done() {
+    let (url, _) = serve(vec![response("200 OK", &[], "text/event-stream", CHAT_SSE_WITH_USAGE)]).await;
+    let client = OpenAIClient::from_config(OpenAIConfig::new("sk-test").with_base_url(&url)).unwrap();
+
+    let events: Vec<StreamEvent> = client
+        .chat_stream(ChatRequest::new("gpt-4o", vec![Message::human("hi")]))
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    let usage = events.iter().position(|e| matches!(e, StreamEvent::Usage { .. })).expect("usage event");
+    let done = events.iter().position(|e| matches!(e, StreamEvent::Done { .. })).unwrap();
+    assert!(usage < done, "{:?}", events);
+    assert!(matches!(
+        events[usage],
+        StreamEvent::Usage { input_tokens: 9, output_tokens: 40, total_tokens: 49, reasoning_tokens: Some(32), cached_tokens: None }
+    ));
+}
@@ -0,0 +1,75 @@
+//! Client timeouts against a local server that stops answering
+
+mod common;
+
+use common::{serve_replies, sse_start, Reply};
+use futures::StreamExt;
+use praxis_llm::{ChatClient, ChatRequest, Message, OpenAIClient, OpenAIConfig, RetryPolicy, StreamEvent, TimedOut};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+const FIRST_CHUNK: &str = "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"}}]}\n\n";
+
+fn config(url: &str) -> OpenAIConfig {
+    OpenAIConfig::new("sk-test")
+        .with_base_url(url)
+        .with_timeout(Duration::from_millis(200))
+        .with_stream_idle_timeout(Duration::from_millis(200))
+}
+
+fn request() -> ChatRequest {
+    ChatRequest::new("gpt-4o", vec![Message::human("hi")])
+}
+
+#[tokio::test]
+async fn test_no_response_times_out() {
+    let (url, _) = serve_replies(vec![Reply::Stall(String::new())]).await;
+    let client = OpenAIClient::from_config(config(&url)).unwrap();
+
+    let err = match client.chat_stream(request()).await {
+        Ok(_) => panic!("stream started without a response"),
+        Err(err) => err,
+    };
+
+    let timed_out = err.downcast_ref::<TimedOut>().unwrap();
+    assert_eq!(timed_out.after, Duration::from_millis(200));
+    assert_eq!(timed_out.waiting_for, "a response");
+}
+
+#[tokio::test]
+async fn test_stalled_stream_ends_with_a_timeout_error() {
+    let (url, _) = serve_replies(vec![Reply::Stall(sse_start(FIRST_CHUNK))]).await;
+    let client = OpenAIClient::from_config(config(&url)).unwrap();
+
+    let events: Vec<_> = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.chat_stream(request()).await.unwrap().collect::<Vec<_>>(),
+    )
+    .await
+    .expect("stream hung instead of timing out");
+
+    assert!(matches!(&events[0], Ok(StreamEvent::Message { content, .. }) if content == "Hel"));
+    let err = events.last().unwrap().as_ref().unwrap_err();
+    assert_eq!(err.downcast_ref::<TimedOut>().unwrap().waiting_for, "the next stream event");
+}
+
+#[tokio::test]
+async fn test_timed_out_attempts_are_retried() {
+    let (url, requests) = serve_replies(vec![
+        Reply::Stall(String::new()),
+        Reply::Full(common::response(
+            "200 OK",
+            &[],
+            "text/event-stream",
+            &format!("{}data: [DONE]\n\n", FIRST_CHUNK),
+        )),
+    ])
+    .await;
+    let policy = RetryPolicy::new(2).with_base_delay(Duration::from_millis(5));
+    let client = OpenAIClient::from_config(config(&url).with_retry_policy(policy)).unwrap();
+
+    let events: Vec<_> = client.chat_stream(request()).await.unwrap().collect().await;
+
+    assert!(events.iter().all(|event| event.is_ok()));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
@@ -103,6 +103,15 @@ fn test_response_options_default() {
     assert_eq!(options.max_output_tokens, None);
 }
 
+#[test]
+fn test_reasoning_config_minimal() {
+    let config = ReasoningConfig::minimal();
+    assert!(matches!(config.effort, praxis_llm::ReasoningEffort::Minimal));
+    assert_eq!(serde_json::to_value(&config).unwrap()["effort"], "minimal");
+    assert_eq!("minimal".parse::<praxis_llm::ReasoningEffort>().unwrap(), praxis_llm::ReasoningEffort::Minimal);
+    assert!("extreme".parse::<praxis_llm::ReasoningEffort>().is_err());
+}
+
 #[test]
 fn test_reasoning_config_low() {
     let config = ReasoningConfig::low();
@@ -19,6 +19,17 @@ fn test_message_system() {
     assert_eq!(msg.role(), "system");
 }
 
+#[test]
+fn test_message_developer() {
+    let msg = Message::developer("Answer in French");
+    assert_eq!(msg.role(), "developer");
+    assert_eq!(msg.text(), Some("Answer in French"));
+
+    let json = serde_json::to_value(&msg).unwrap();
+    assert_eq!(json, serde_json::json!({ "role": "developer", "content": "Answer in French" }));
+    assert_eq!(serde_json::from_value::<Message>(json).unwrap().role(), "developer");
+}
+
 #[test]
 fn test_message_human() {
     let msg = Message::human("Hello");
@@ -32,11 +43,33 @@ fn test_message_ai() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_message_tool_result() {
     let msg = Message::tool_result("call_123", "42");
     assert_eq!(msg.role(), "tool");
 }
 
+#[test]
+fn test_message_tool() {
+    let msg = Message::tool("call_123", "42");
+    assert_eq!(msg.role(), "tool");
+    assert_eq!(msg.text(), Some("42"));
+    assert!(!msg.is_tool_call());
+}
+
+#[test]
+fn test_message_text() {
+    assert_eq!(Message::system("You are helpful").text(), Some("You are helpful"));
+    assert_eq!(Message::human("Hello").text(), Some("Hello"));
+    assert_eq!(Message::ai("Hi there!").text(), Some("Hi there!"));
+
+    let with_image = Message::human(Content::Parts(vec![
+        praxis_llm::ContentPart::text("What is this?"),
+        praxis_llm::ContentPart::image_url("https://example.com/cat.png"),
+    ]));
+    assert_eq!(with_image.text(), Some("What is this?"));
+}
+
 #[test]
 fn test_message_serialization_human() {
     let msg = Message::human("Hello");
@@ -76,6 +109,46 @@ fn test_tool_creation() {
     assert!(tool.function.description.is_some());
 }
 
+#[test]
+fn test_tool_schema_validation() {
+    let schema = json!({
+        "type": "object",
+        "properties": { "city": { "type": "string" }, "unit": { "type": "string" } },
+        "required": ["city"]
+    });
+    assert!(Tool::new("get_weather", "Weather", schema.clone()).validate().is_ok());
+    assert!(Tool::new("now", "Time", json!({ "type": "object" })).validate().is_ok());
+
+    let invalid = [
+        (json!("object"), "not a JSON object"),
+        (json!({ "type": "string" }), r#""type" must be "object""#),
+        (json!({ "type": "object", "properties": [] }), r#""properties" must be an object"#),
+        (json!({ "type": "object", "properties": { "city": "string" } }), "property 'city' must be a schema object"),
+        (json!({ "type": "object", "required": "city" }), r#""required" must be an array"#),
+    ];
+    for (parameters, problem) in invalid {
+        let error = Tool::new("get_weather", "Weather", parameters).validate().unwrap_err().to_string();
+        assert!(error.starts_with("Tool 'get_weather' has an invalid input schema: "), "{}", error);
+        assert!(error.contains(problem), "{}", error);
+    }
+
+    // Strict mode: closed objects with every property required
+    let strict = Tool::new("get_weather", "Weather", schema).with_strict(true);
+    assert!(strict.validate().unwrap_err().to_string().contains("additionalProperties"));
+    let undeclared = json!({ "type": "object", "required": ["city"], "additionalProperties": false });
+    let error = Tool::new("get_weather", "Weather", undeclared).with_strict(true).validate().unwrap_err();
+    assert!(error.to_string().contains("required property 'city'"), "{}", error);
+    let closed = json!({
+        "type": "object",
+        "properties": { "city": { "type": "string" } },
+        "required": ["city"],
+        "additionalProperties": false
+    });
+    let strict = Tool::new("get_weather", "Weather", closed).with_strict(true);
+    assert!(strict.validate().is_ok());
+    assert_eq!(serde_json::to_value(&strict).unwrap()["function"]["strict"], true);
+}
+
 #[test]
 fn test_tool_choice_auto() {
     let choice = ToolChoice::auto();
@@ -109,6 +182,26 @@ fn test_tool_choice_force() {
     }
 }
 
+#[test]
+fn test_tool_choice_function_wire_format() {
+    let json = serde_json::to_value(ToolChoice::function("search_knowledge_base")).unwrap();
+    assert_eq!(json, json!({"type": "function", "function": {"name": "search_knowledge_base"}}));
+}
+
+#[test]
+fn test_tool_choice_round_trips() {
+    for choice in [
+        ToolChoice::auto(),
+        ToolChoice::none(),
+        ToolChoice::required(),
+        ToolChoice::function("get_weather"),
+    ] {
+        let json = serde_json::to_string(&choice).unwrap();
+        assert_eq!(serde_json::from_str::<ToolChoice>(&json).unwrap(), choice);
+    }
+    assert!(serde_json::from_str::<ToolChoice>(r#""sometimes""#).is_err());
+}
+
 #[test]
 fn test_tool_call_parse_arguments() {
     let tool_call = ToolCall {
@@ -159,8 +252,17 @@ fn test_message_ai_with_tools() {
         }
     ];
     
-    let msg = Message::ai_with_tools(tool_calls);
+    let msg = Message::ai_with_tools(tool_calls.clone());
     assert_eq!(msg.role(), "assistant");
+    assert!(msg.is_tool_call());
+    assert_eq!(msg.text(), None);
+
+    let explained = Message::ai_with_content_and_tools("Let me check.", tool_calls);
+    assert!(explained.is_tool_call());
+    assert_eq!(explained.text(), Some("Let me check."));
+
+    assert!(!Message::ai("Done").is_tool_call());
+    assert!(!Message::ai_with_tools(Vec::new()).is_tool_call());
 }
 
 #[test]
@@ -176,3 +278,34 @@ fn test_content_parts() {
     assert_eq!(content.as_text(), Some("Hello"));
 }
 
+#[test]
+fn test_image_parts_round_trip_and_are_skipped_by_as_text() {
+    use praxis_llm::{ContentPart, ImageDetail};
+
+    let content = Content::Parts(vec![
+        ContentPart::text("What's on this screen?"),
+        ContentPart::image_url("https://example.com/screen.png").with_detail(ImageDetail::High),
+        ContentPart::image_base64("iVBORw0KGgo=", "image/png"),
+    ]);
+    assert_eq!(content.as_text(), Some("What's on this screen?"));
+
+    let json = serde_json::to_value(&content).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            { "type": "text", "text": "What's on this screen?" },
+            { "type": "image_url", "url": "https://example.com/screen.png", "detail": "high" },
+            { "type": "image_base64", "data": "iVBORw0KGgo=", "mime_type": "image/png" }
+        ])
+    );
+    let Content::Parts(parts) = serde_json::from_value::<Content>(json).unwrap() else {
+        panic!("expected parts");
+    };
+    let Content::Parts(original) = content else { unreachable!() };
+    assert_eq!(parts, original);
+
+    // Images only, or several text parts: no single text
+    assert_eq!(Content::Parts(vec![ContentPart::image_url("https://example.com/a.png")]).as_text(), None);
+    assert_eq!(Content::Parts(vec![ContentPart::text("a"), ContentPart::text("b")]).as_text(), None);
+}
+
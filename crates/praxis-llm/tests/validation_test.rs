@@ -0,0 +1,29 @@
+//! Invalid requests fail before anything is sent
+
+mod common;
+
+use common::{response, serve};
+use praxis_llm::{ChatClient, ChatOptions, ChatRequest, LLMError, Message, OpenAIClient, ReasoningClient, ResponseRequest};
+use std::sync::atomic::Ordering;
+
+const CHAT_BODY: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt-4o","choices":[{"index":0,"message":{"role":"assistant","content":"Hello"},"finish_reason":"stop"}],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}"#;
+
+#[tokio::test]
+async fn test_invalid_requests_never_reach_the_provider() {
+    let (url, requests) = serve(vec![response("200 OK", &[], "application/json", CHAT_BODY)]).await;
+    let client = OpenAIClient::with_base_url("sk-test", url).unwrap();
+
+    let request = ChatRequest::new("gpt-4o", vec![Message::human("hi")]).with_options(ChatOptions::new().temperature(5.0));
+    let error = client.chat(request.clone()).await.unwrap_err();
+    let Some(LLMError::Validation(invalid)) = LLMError::of(&error) else {
+        panic!("expected a validation error, got {:?}", error);
+    };
+    assert_eq!(invalid.field, "options.temperature");
+    assert_eq!(LLMError::of(&error).map(LLMError::code), Some("validation_failed"));
+    assert!(client.chat_stream(request).await.is_err());
+    assert!(client.reason(ResponseRequest::new("o4-mini", Vec::new())).await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+    client.chat(ChatRequest::new("gpt-4o", vec![Message::human("hi")])).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
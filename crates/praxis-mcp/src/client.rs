@@ -1,13 +1,25 @@
 use anyhow::Result;
 use rmcp::{ServiceExt, service::RoleClient};
 use rmcp::transport::streamable_http_client::StreamableHttpClientWorker;
+use rmcp::transport::TokioChildProcess;
+use process_wrap::tokio::{KillOnDrop, TokioCommandWrap};
 use serde_json::Value;
+use std::ffi::OsStr;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Default time allowed for a graceful shutdown before the server is killed
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// MCP Client wrapper that manages connection to MCP servers
 /// 
 /// Maintains an active connection to an MCP server and provides methods
 /// to list tools and execute them. The connection is kept alive for the
-/// lifetime of this client.
+/// lifetime of this client, or until [`MCPClient::shutdown`] is called.
+/// 
+/// Stdio servers run in their own process group (on Unix) so that
+/// shutting down or dropping the client also kills any grandchildren the
+/// server spawned.
 /// 
 /// # Examples
 /// 
@@ -33,10 +45,13 @@ use serde_json::Value;
 /// ```
 pub struct MCPClient {
     server_name: String,
-    /// Keep the running service alive (connection stays open)
-    _running_service: rmcp::service::RunningService<RoleClient, ()>,
+    /// Keep the running service alive (connection stays open); taken on shutdown
+    running_service: Mutex<Option<rmcp::service::RunningService<RoleClient, ()>>>,
     /// Peer for making MCP calls
     peer: rmcp::service::Peer<RoleClient>,
+    /// Process group of a stdio server (the child's pid, as group leader)
+    child_pgid: Option<u32>,
+    shutdown_timeout: Duration,
 }
 
 impl MCPClient {
@@ -74,14 +89,116 @@ impl MCPClient {
         let running_service = ().serve(worker).await
             .map_err(|e| anyhow::anyhow!("Failed to connect to MCP server at {}: {}", url, e))?;
         
+        Ok(Self::from_service(server_name, running_service, None))
+    }
+
+    /// Create a new MCP client by spawning a server over stdio
+    /// 
+    /// The server is started as the leader of a new process group on Unix,
+    /// and killed (with its whole group) on shutdown or drop.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use praxis_mcp::MCPClient;
+    /// 
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = MCPClient::new_stdio(
+    ///     "filesystem",
+    ///     "npx",
+    ///     ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"],
+    /// ).await?;
+    /// client.shutdown().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn new_stdio<I, S>(
+        server_name: impl Into<String>,
+        command: impl AsRef<OsStr>,
+        args: I,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let server_name = server_name.into();
+        let program = command.as_ref().to_string_lossy().to_string();
+
+        let mut command = TokioCommandWrap::with_new(command, |cmd| {
+            cmd.args(args);
+        });
+        #[cfg(unix)]
+        command.wrap(process_wrap::tokio::ProcessGroup::leader());
+        command.wrap(KillOnDrop);
+
+        let transport = TokioChildProcess::new(command)
+            .map_err(|e| anyhow::anyhow!("Failed to spawn MCP server '{}': {}", program, e))?;
+        let child_pgid = transport.id();
+
+        let running_service = ().serve(transport).await
+            .map_err(|e| anyhow::anyhow!("Failed to initialize MCP server '{}': {}", program, e))?;
+
+        Ok(Self::from_service(server_name, running_service, child_pgid))
+    }
+
+    fn from_service(
+        server_name: String,
+        running_service: rmcp::service::RunningService<RoleClient, ()>,
+        child_pgid: Option<u32>,
+    ) -> Self {
         // Get peer for making calls (clone to own it)
         let peer = running_service.peer().clone();
-        
-        Ok(Self {
+
+        Self {
             server_name,
-            _running_service: running_service,
+            running_service: Mutex::new(Some(running_service)),
             peer,
-        })
+            child_pgid,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+        }
+    }
+
+    /// Set how long `shutdown` waits for the server to exit before killing it
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Shut down the connection and, for stdio servers, the server process
+    /// 
+    /// Closes the transport (for stdio this closes the server's stdin, which
+    /// is the MCP exit signal) and waits up to the shutdown timeout. The
+    /// process group is then killed so no children or grandchildren are left
+    /// behind. Calls still in flight resolve with a transport-closed error.
+    /// Calling this more than once is a no-op.
+    pub async fn shutdown(&self) -> Result<()> {
+        let Some(service) = self.running_service.lock().await.take() else {
+            return Ok(());
+        };
+
+        let result = tokio::time::timeout(self.shutdown_timeout, service.cancel()).await;
+        self.kill_process_group();
+
+        match result {
+            Ok(Err(e)) => Err(anyhow::anyhow!(
+                "MCP server '{}' did not shut down cleanly: {}",
+                self.server_name,
+                e
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Send SIGKILL to the stdio server's process group (best effort)
+    fn kill_process_group(&self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.child_pgid {
+            use nix::sys::signal::{killpg, Signal};
+            use nix::unistd::Pid;
+
+            // ESRCH just means the whole group already exited
+            let _ = killpg(Pid::from_raw(pgid as i32), Signal::SIGKILL);
+        }
     }
 
     /// List all available tools from the MCP server
@@ -116,7 +233,14 @@ impl MCPClient {
         };
         
         let result = self.peer.call_tool(param).await
-            .map_err(|e| anyhow::anyhow!("Failed to call tool '{}': {}", name, e))?;
+            .map_err(|e| match e {
+                rmcp::ServiceError::TransportClosed => anyhow::anyhow!(
+                    "Failed to call tool '{}': MCP server '{}' transport closed",
+                    name,
+                    self.server_name
+                ),
+                e => anyhow::anyhow!("Failed to call tool '{}': {}", name, e),
+            })?;
         
         // Convert MCP content to ToolResponse
         Ok(result.content.into_iter().map(|content| {
@@ -144,12 +268,27 @@ impl MCPClient {
         }).collect())
     }
 
+    /// Whether the connection is still open (false after shutdown or server exit)
+    pub fn is_healthy(&self) -> bool {
+        !self.peer.is_transport_closed()
+    }
+
     /// Get server name
     pub fn name(&self) -> &str {
         &self.server_name
     }
 }
 
+impl Drop for MCPClient {
+    fn drop(&mut self) {
+        // Not shut down explicitly: kill the server tree now instead of
+        // relying on the transport's background cleanup
+        if self.running_service.get_mut().is_some() {
+            self.kill_process_group();
+        }
+    }
+}
+
 /// Tool information from MCP server
 #[derive(Debug, Clone)]
 pub struct ToolInfo {
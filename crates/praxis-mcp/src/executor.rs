@@ -1,18 +1,87 @@
-use crate::client::{MCPClient, ToolResponse};
+use crate::client::{MCPClient, ToolInfo, ToolResponse};
+use crate::local::LocalTool;
+use crate::pool::{PoolStats, StdioServerPool};
+use crate::tool_config::ToolConfig;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-/// Tool executor that delegates to MCP servers
+/// A connected MCP server: one connection or a pool of stdio instances
+#[derive(Clone)]
+enum McpServer {
+    Client(Arc<MCPClient>),
+    Pool(Arc<StdioServerPool>),
+}
+
+impl McpServer {
+    fn name(&self) -> &str {
+        match self {
+            Self::Client(client) => client.name(),
+            Self::Pool(pool) => pool.name(),
+        }
+    }
+
+    async fn list_tools(&self) -> Result<Vec<ToolInfo>> {
+        match self {
+            Self::Client(client) => client.list_tools().await,
+            Self::Pool(pool) => pool.list_tools().await,
+        }
+    }
+
+    async fn get_llm_tools(&self) -> Result<Vec<praxis_llm::Tool>> {
+        match self {
+            Self::Client(client) => client.get_llm_tools().await,
+            Self::Pool(pool) => pool.get_llm_tools().await,
+        }
+    }
+
+    async fn call_tool(&self, name: &str, arguments: serde_json::Value) -> Result<Vec<ToolResponse>> {
+        match self {
+            Self::Client(client) => client.call_tool(name, arguments).await,
+            Self::Pool(pool) => pool.call_tool(name, arguments).await,
+        }
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        match self {
+            Self::Client(client) => client.shutdown().await,
+            Self::Pool(pool) => pool.shutdown().await,
+        }
+    }
+}
+
+/// Server name reported for calls served by local tools
+pub const LOCAL_TOOL_SERVER: &str = "_local";
+
+/// What a tool call returned, and which server ran it
+#[derive(Debug, Clone)]
+pub struct ToolCallOutput {
+    /// MCP server name, or `LOCAL_TOOL_SERVER` for a local tool
+    pub server: String,
+    pub responses: Vec<ToolResponse>,
+}
+
+/// Tool executor that delegates to MCP servers and in-process local tools
 pub struct MCPToolExecutor {
-    clients: Arc<RwLock<HashMap<String, Arc<MCPClient>>>>,
+    clients: Arc<RwLock<HashMap<String, McpServer>>>,
+    local_tools: Arc<RwLock<HashMap<String, Arc<dyn LocalTool>>>>,
+    /// Settings by tool name, shared with scoped executors
+    tool_configs: Arc<RwLock<HashMap<String, ToolConfig>>>,
+    /// MCP tools a pinned run may see (None = whatever the servers offer)
+    pinned: Option<Arc<Vec<praxis_llm::Tool>>>,
+    /// Fail on a tool with an invalid input schema instead of leaving it out
+    fail_on_invalid_schemas: bool,
 }
 
 impl MCPToolExecutor {
     pub fn new() -> Self {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
+            local_tools: Arc::new(RwLock::new(HashMap::new())),
+            tool_configs: Arc::new(RwLock::new(HashMap::new())),
+            pinned: None,
+            fail_on_invalid_schemas: false,
         }
     }
 
@@ -20,50 +89,241 @@ impl MCPToolExecutor {
     pub async fn add_server(&self, client: MCPClient) -> Result<()> {
         let name = client.name().to_string();
         let mut clients = self.clients.write().await;
-        clients.insert(name, Arc::new(client));
+        clients.insert(name, McpServer::Client(Arc::new(client)));
         Ok(())
     }
 
+    /// Add a pooled stdio MCP server
+    pub async fn add_pool(&self, pool: StdioServerPool) -> Result<()> {
+        let name = pool.name().to_string();
+        let mut clients = self.clients.write().await;
+        clients.insert(name, McpServer::Pool(Arc::new(pool)));
+        Ok(())
+    }
+
+    /// Gauges of every pooled server, by server name
+    pub async fn pool_stats(&self) -> Vec<(String, PoolStats)> {
+        self.servers()
+            .await
+            .into_iter()
+            .filter_map(|server| match server {
+                McpServer::Pool(pool) => Some((pool.name().to_string(), pool.stats())),
+                McpServer::Client(_) => None,
+            })
+            .collect()
+    }
+
+    /// Register an in-process tool
+    ///
+    /// Local tools take precedence over MCP tools with the same name.
+    pub async fn register_local_tool(&self, tool: impl LocalTool + 'static) -> Result<()> {
+        let name = tool.name().to_string();
+        let mut local_tools = self.local_tools.write().await;
+        local_tools.insert(name, Arc::new(tool));
+        Ok(())
+    }
+
+    /// Set `tool_name`'s settings, replacing earlier ones
+    pub async fn configure_tool(&self, tool_name: impl Into<String>, config: ToolConfig) {
+        self.tool_configs.write().await.insert(tool_name.into(), config);
+    }
+
+    /// Settings of `tool_name`, if it was configured
+    pub async fn tool_config(&self, tool_name: &str) -> Option<ToolConfig> {
+        self.tool_configs.read().await.get(tool_name).cloned()
+    }
+
+    /// Fails when a tool's `verify_with` names a tool no local tool or
+    /// server provides
+    ///
+    /// Call once servers are connected and local tools registered.
+    pub async fn validate_tool_configs(&self) -> Result<()> {
+        let available: Vec<String> = self.get_llm_tools().await?.into_iter().map(|tool| tool.function.name).collect();
+        let mut dangling: Vec<String> = self
+            .tool_configs
+            .read()
+            .await
+            .iter()
+            .filter_map(|(tool, config)| {
+                let read_tool = config.verify_with.as_ref()?;
+                (!available.contains(read_tool)).then(|| format!("{} (verify_with = '{}')", tool, read_tool))
+            })
+            .collect();
+
+        if dangling.is_empty() {
+            return Ok(());
+        }
+        dangling.sort();
+        Err(anyhow::anyhow!("Tools are verified with tools that don't exist: {}", dangling.join(", ")))
+    }
+
+    /// Executor for a single run: shares MCP servers, adds run-only local tools
+    ///
+    /// Tools registered here are invisible to other runs. The MCP server map
+    /// is shared, so call `shutdown_all` on the original executor only.
+    pub async fn scoped(&self, tools: impl IntoIterator<Item = Arc<dyn LocalTool>>) -> Self {
+        let mut local_tools = self.local_tools.read().await.clone();
+        for tool in tools {
+            local_tools.insert(tool.name().to_string(), tool);
+        }
+
+        Self {
+            clients: Arc::clone(&self.clients),
+            local_tools: Arc::new(RwLock::new(local_tools)),
+            tool_configs: Arc::clone(&self.tool_configs),
+            pinned: self.pinned.clone(),
+            fail_on_invalid_schemas: self.fail_on_invalid_schemas,
+        }
+    }
+
+    /// Restrict MCP tools to a pinned set of definitions
+    ///
+    /// The LLM is offered the pinned definitions of tools some server still
+    /// provides; pinned tools that disappeared are left out with a warning,
+    /// and calling one fails with an error saying so. Local tools are not
+    /// affected. Use on a `scoped` executor.
+    pub fn with_pinned_tools(mut self, tools: Vec<praxis_llm::Tool>) -> Self {
+        self.pinned = Some(Arc::new(tools));
+        self
+    }
+
+    /// Make `get_llm_tools` fail on a tool whose input schema doesn't pass
+    /// `Tool::validate`; by default the tool is left out with a warning
+    pub fn with_fail_on_invalid_schemas(mut self, enabled: bool) -> Self {
+        self.fail_on_invalid_schemas = enabled;
+        self
+    }
+
+    /// Snapshot of connected servers
+    ///
+    /// The lock is released before any server call, so a slow or hung call
+    /// never blocks `add_server` or `shutdown_all`.
+    async fn servers(&self) -> Vec<McpServer> {
+        self.clients.read().await.values().cloned().collect()
+    }
+
     /// List all available tools from all connected MCP servers
-    pub async fn list_all_tools(&self) -> Result<Vec<(String, Vec<crate::client::ToolInfo>)>> {
-        let clients = self.clients.read().await;
+    pub async fn list_all_tools(&self) -> Result<Vec<(String, Vec<ToolInfo>)>> {
         let mut all_tools = Vec::new();
 
-        for (server_name, client) in clients.iter() {
+        for client in self.servers().await {
             let tools = client.list_tools().await?;
-            all_tools.push((server_name.clone(), tools));
+            all_tools.push((client.name().to_string(), tools));
         }
 
         Ok(all_tools)
     }
 
-    /// Get all tools from all connected MCP servers in LLM format
-    pub async fn get_llm_tools(&self) -> Result<Vec<praxis_llm::Tool>> {
+    /// Tools of the connected MCP servers in LLM format (no local tools,
+    /// no pinning): what a tool profile records
+    pub async fn mcp_tools(&self) -> Result<Vec<praxis_llm::Tool>> {
+        self.checked(self.server_tools().await?)
+    }
+
+    async fn server_tools(&self) -> Result<Vec<praxis_llm::Tool>> {
         let mut all_tools = Vec::new();
-        let clients = self.clients.read().await;
-        
-        for client in clients.values() {
-            let tools = client.get_llm_tools().await?;
-            all_tools.extend(tools);
+        for client in self.servers().await {
+            all_tools.extend(client.get_llm_tools().await?);
         }
-        
         Ok(all_tools)
     }
 
-    /// Execute a tool by finding the right MCP server
+    /// `tools` with valid input schemas, or the first schema error when
+    /// `fail_on_invalid_schemas` is set
+    fn checked(&self, tools: Vec<praxis_llm::Tool>) -> Result<Vec<praxis_llm::Tool>> {
+        let mut valid = Vec::with_capacity(tools.len());
+        for tool in tools {
+            match tool.validate() {
+                Ok(()) => valid.push(tool),
+                Err(error) if self.fail_on_invalid_schemas => return Err(error),
+                Err(error) => tracing::warn!("Leaving out tool: {}", error),
+            }
+        }
+        Ok(valid)
+    }
+
+    /// Get all tools (local and MCP) in LLM format
+    pub async fn get_llm_tools(&self) -> Result<Vec<praxis_llm::Tool>> {
+        let mut all_tools: Vec<praxis_llm::Tool> = self
+            .local_tools
+            .read()
+            .await
+            .values()
+            .map(|tool| tool.to_llm_tool())
+            .collect();
+        let mcp_tools = self.server_tools().await?;
+        match &self.pinned {
+            None => all_tools.extend(mcp_tools),
+            Some(pinned) => {
+                for tool in pinned.iter() {
+                    if mcp_tools.iter().any(|t| t.function.name == tool.function.name) {
+                        all_tools.push(tool.clone());
+                    } else {
+                        tracing::warn!("Pinned tool '{}' is no longer provided by any MCP server", tool.function.name);
+                    }
+                }
+            }
+        }
+        
+        self.checked(all_tools)
+    }
+
+    /// Execute a tool by finding the right local tool or MCP server
     pub async fn execute_tool(&self, tool_name: &str, arguments: serde_json::Value) 
         -> Result<Vec<ToolResponse>> {
-        let clients = self.clients.read().await;
-        
-        for client in clients.values() {
+        self.call_tool(tool_name, arguments).await.map(|output| output.responses)
+    }
+
+    /// Execute a tool and report the server that ran it
+    pub async fn call_tool(&self, tool_name: &str, arguments: serde_json::Value) -> Result<ToolCallOutput> {
+        let local_tool = self.local_tools.read().await.get(tool_name).cloned();
+        if let Some(tool) = local_tool {
+            let responses = tool.call(arguments).await?;
+            return Ok(ToolCallOutput { server: LOCAL_TOOL_SERVER.to_string(), responses });
+        }
+
+        let pinned = self.pinned.as_ref();
+        if pinned.is_some_and(|tools| !tools.iter().any(|t| t.function.name == tool_name)) {
+            return Err(anyhow::anyhow!("Tool '{}' is not in this thread's pinned tool profile", tool_name));
+        }
+
+        for client in self.servers().await {
             let tools = client.list_tools().await?;
             if tools.iter().any(|t| t.name == tool_name) {
-                return client.call_tool(tool_name, arguments).await;
+                let responses = client.call_tool(tool_name, arguments).await?;
+                return Ok(ToolCallOutput { server: client.name().to_string(), responses });
             }
         }
         
+        if pinned.is_some() {
+            return Err(anyhow::anyhow!(
+                "Tool '{}' is pinned on this thread but no MCP server provides it anymore; repin the thread to use the current tools",
+                tool_name
+            ));
+        }
         Err(anyhow::anyhow!("Tool '{}' not found", tool_name))
     }
+
+    /// Disconnect all MCP servers and terminate stdio server processes
+    ///
+    /// Servers are removed from the executor and shut down concurrently.
+    /// Returns an error naming every server that failed to shut down cleanly
+    /// (those servers are still killed).
+    pub async fn shutdown_all(&self) -> Result<()> {
+        let clients: Vec<McpServer> = self.clients.write().await.drain().map(|(_, c)| c).collect();
+
+        let results = futures::future::join_all(clients.iter().map(|c| c.shutdown())).await;
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|r| r.err().map(|e| e.to_string()))
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("MCP shutdown failed: {}", failures.join("; ")))
+        }
+    }
 }
 
 // Note: We're intentionally NOT implementing the ToolExecutor trait here
@@ -79,5 +339,108 @@ mod tests {
         let executor = MCPToolExecutor::new();
         assert!(executor.list_all_tools().await.unwrap().is_empty());
     }
-}
 
+    struct EchoTool;
+
+    #[async_trait::async_trait]
+    impl LocalTool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> String {
+            "Echo the input text".to_string()
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"]
+            })
+        }
+
+        async fn call(&self, arguments: serde_json::Value) -> Result<Vec<ToolResponse>> {
+            let text = arguments["text"].as_str().unwrap_or_default().to_string();
+            Ok(vec![ToolResponse::Text { text }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_tool_registration_and_execution() {
+        let executor = MCPToolExecutor::new();
+        executor.register_local_tool(EchoTool).await.unwrap();
+
+        let tools = executor.get_llm_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].function.name, "echo");
+
+        let responses = executor
+            .execute_tool("echo", serde_json::json!({ "text": "hi" }))
+            .await
+            .unwrap();
+        assert_eq!(ToolResponse::join_responses(&responses), "hi");
+
+        let output = executor.call_tool("echo", serde_json::json!({ "text": "hi" })).await.unwrap();
+        assert_eq!(output.server, LOCAL_TOOL_SERVER);
+
+        assert!(executor.execute_tool("missing", serde_json::json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dangling_verify_with_is_rejected() {
+        let executor = MCPToolExecutor::new();
+        executor.register_local_tool(EchoTool).await.unwrap();
+        executor.configure_tool("write", ToolConfig::verify_with("echo", serde_json::Value::Null)).await;
+        assert!(executor.validate_tool_configs().await.is_ok());
+
+        executor.configure_tool("delete", ToolConfig::verify_with("lookup", serde_json::Value::Null)).await;
+        let error = executor.validate_tool_configs().await.unwrap_err().to_string();
+        assert!(error.contains("delete (verify_with = 'lookup')"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_scoped_tools_do_not_leak() {
+        let executor = MCPToolExecutor::new();
+        let scoped = executor.scoped([Arc::new(EchoTool) as Arc<dyn LocalTool>]).await;
+
+        assert!(scoped.execute_tool("echo", serde_json::json!({ "text": "hi" })).await.is_ok());
+        assert!(executor.execute_tool("echo", serde_json::json!({ "text": "hi" })).await.is_err());
+    }
+
+    /// Declares `required` as a string, as some servers do
+    struct MangledTool;
+
+    #[async_trait::async_trait]
+    impl LocalTool for MangledTool {
+        fn name(&self) -> &str {
+            "mangled"
+        }
+
+        fn description(&self) -> String {
+            "Broken schema".to_string()
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object", "properties": { "id": { "type": "string" } }, "required": "id" })
+        }
+
+        async fn call(&self, _arguments: serde_json::Value) -> Result<Vec<ToolResponse>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_schemas_are_left_out_or_fail() {
+        let executor = MCPToolExecutor::new();
+        executor.register_local_tool(EchoTool).await.unwrap();
+        executor.register_local_tool(MangledTool).await.unwrap();
+
+        let tools = executor.get_llm_tools().await.unwrap();
+        assert_eq!(tools.iter().map(|t| t.function.name.as_str()).collect::<Vec<_>>(), ["echo"]);
+
+        let strict = executor.scoped([]).await.with_fail_on_invalid_schemas(true);
+        let error = strict.get_llm_tools().await.unwrap_err().to_string();
+        assert!(error.contains("Tool 'mangled' has an invalid input schema"), "{}", error);
+    }
+}
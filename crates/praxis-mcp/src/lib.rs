@@ -1,6 +1,12 @@
 pub mod client;
 pub mod executor;
+pub mod local;
+pub mod pool;
+pub mod tool_config;
 
 pub use client::{MCPClient, ToolInfo, ToolResponse};
-pub use executor::MCPToolExecutor;
+pub use executor::{MCPToolExecutor, ToolCallOutput, LOCAL_TOOL_SERVER};
+pub use local::LocalTool;
+pub use pool::{PoolConfig, PoolStats, PooledClient, StdioServerConfig, StdioServerPool};
+pub use tool_config::ToolConfig;
 
@@ -0,0 +1,35 @@
+use crate::client::ToolResponse;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// In-process tool that runs without an MCP server round-trip
+///
+/// Local tools are registered on the `MCPToolExecutor` next to MCP servers and
+/// are exposed to the LLM exactly like remote tools. Returning `Err` marks the
+/// tool result as an error for the model.
+#[async_trait]
+pub trait LocalTool: Send + Sync {
+    /// Unique tool name exposed to the LLM
+    fn name(&self) -> &str;
+
+    /// Human readable description (shown to the LLM)
+    fn description(&self) -> String;
+
+    /// JSON Schema describing the tool arguments
+    fn input_schema(&self) -> Value;
+
+    /// Execute the tool with the given arguments
+    async fn call(&self, arguments: Value) -> Result<Vec<ToolResponse>>;
+
+    /// Tool definition in LLM format
+    fn to_llm_tool(&self) -> praxis_llm::Tool {
+        praxis_llm::Tool::new(self.name(), self.description(), self.input_schema())
+    }
+}
+
+impl std::fmt::Debug for dyn LocalTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalTool").field("name", &self.name()).finish()
+    }
+}
@@ -0,0 +1,435 @@
+use crate::client::{MCPClient, ToolInfo, ToolResponse};
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+/// Size limits of a stdio server pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolConfig {
+    /// Instances kept warm, including right after startup
+    pub min: usize,
+    /// Upper bound on instances, i.e. on concurrent calls to this server
+    pub max: usize,
+    /// Idle instances above `min` are shut down after this long
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            min: 1,
+            max: 1,
+            idle_timeout_secs: 300,
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn new(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout_secs = timeout.as_secs();
+        self
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max == 0 {
+            bail!("pool.max must be at least 1");
+        }
+        if self.min > self.max {
+            bail!("pool.min ({}) must not exceed pool.max ({})", self.min, self.max);
+        }
+        Ok(())
+    }
+}
+
+/// A stdio MCP server definition
+///
+/// ```toml
+/// name = "search"
+/// command = "python"
+/// args = ["-m", "search_server"]
+/// pool = { min = 1, max = 4 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StdioServerConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Run as a warm pool instead of a single connection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolConfig>,
+}
+
+impl StdioServerConfig {
+    pub fn new(name: impl Into<String>, command: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            command: command.into(),
+            args: Vec::new(),
+            pool: None,
+        }
+    }
+
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_pool(mut self, pool: PoolConfig) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Spawn one server instance
+    pub async fn spawn(&self) -> Result<MCPClient> {
+        MCPClient::new_stdio(&self.name, &self.command, &self.args).await
+    }
+}
+
+/// Point-in-time pool gauges and counters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub idle: usize,
+    pub in_use: usize,
+    /// Live instances (idle + in use)
+    pub total: usize,
+    /// Instances started since the pool was created
+    pub spawned: u64,
+    pub checkouts: u64,
+    pub last_checkout_latency: Duration,
+    pub mean_checkout_latency: Duration,
+}
+
+struct IdleInstance {
+    client: MCPClient,
+    since: Instant,
+}
+
+struct PoolInner {
+    server: StdioServerConfig,
+    config: PoolConfig,
+    /// Most recently returned last, so the oldest instances age out first
+    idle: Mutex<Vec<IdleInstance>>,
+    /// One permit per instance that may be checked out
+    slots: Arc<Semaphore>,
+    tools: OnceCell<Vec<ToolInfo>>,
+    total: AtomicUsize,
+    in_use: AtomicUsize,
+    spawned: AtomicU64,
+    checkouts: AtomicU64,
+    checkout_micros_total: AtomicU64,
+    last_checkout_micros: AtomicU64,
+    closed: AtomicBool,
+}
+
+impl PoolInner {
+    async fn spawn(&self) -> Result<MCPClient> {
+        let client = self.server.spawn().await?;
+        self.total.fetch_add(1, Ordering::SeqCst);
+        self.spawned.fetch_add(1, Ordering::Relaxed);
+        Ok(client)
+    }
+
+    fn discard(&self, instances: Vec<IdleInstance>) {
+        self.total.fetch_sub(instances.len(), Ordering::SeqCst);
+        // Dropping a client kills its process group
+        drop(instances);
+    }
+
+    /// Drop dead idle instances and those idle too long above `min`
+    fn reap(&self) {
+        let expired = {
+            let mut idle = self.idle.lock().unwrap();
+            let (healthy, mut expired): (Vec<_>, Vec<_>) =
+                idle.drain(..).partition(|instance| instance.client.is_healthy());
+            *idle = healthy;
+
+            let in_use = self.in_use.load(Ordering::SeqCst);
+            let timeout = self.config.idle_timeout();
+            // Oldest first; stop once the pool is down to `min`
+            while idle.len() + in_use > self.config.min
+                && idle.first().is_some_and(|instance| instance.since.elapsed() >= timeout)
+            {
+                expired.push(idle.remove(0));
+            }
+            expired
+        };
+        self.discard(expired);
+    }
+
+    /// Spawn instances until `min` are alive (after reaping dead ones)
+    async fn replenish(&self) -> Result<()> {
+        while !self.closed.load(Ordering::SeqCst) && self.total.load(Ordering::SeqCst) < self.config.min {
+            let client = self.spawn().await?;
+            self.idle.lock().unwrap().push(IdleInstance {
+                client,
+                since: Instant::now(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Warm pool of instances of one stdio MCP server
+///
+/// `min` instances are started up front so the first tool call does not pay
+/// the server's startup time. Each call checks an instance out exclusively,
+/// so concurrent calls run on separate processes (up to `max`); this is what
+/// single-threaded servers need. A background task drops instances whose
+/// transport has closed, re-spawns up to `min`, and shuts down instances
+/// idle for longer than `idle_timeout_secs`.
+pub struct StdioServerPool {
+    inner: Arc<PoolInner>,
+    maintenance: JoinHandle<()>,
+}
+
+impl StdioServerPool {
+    /// Start the pool and warm up `min` instances
+    pub async fn start(server: StdioServerConfig) -> Result<Self> {
+        let config = server.pool.clone().unwrap_or_default();
+        config
+            .validate()
+            .map_err(|e| anyhow!("Invalid pool for MCP server '{}': {}", server.name, e))?;
+
+        let inner = Arc::new(PoolInner {
+            slots: Arc::new(Semaphore::new(config.max)),
+            server,
+            config,
+            idle: Mutex::new(Vec::new()),
+            tools: OnceCell::new(),
+            total: AtomicUsize::new(0),
+            in_use: AtomicUsize::new(0),
+            spawned: AtomicU64::new(0),
+            checkouts: AtomicU64::new(0),
+            checkout_micros_total: AtomicU64::new(0),
+            last_checkout_micros: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        });
+
+        let warm = futures::future::try_join_all((0..inner.config.min).map(|_| inner.spawn())).await?;
+        inner.idle.lock().unwrap().extend(warm.into_iter().map(|client| IdleInstance {
+            client,
+            since: Instant::now(),
+        }));
+
+        let maintenance = tokio::spawn(maintain(Arc::downgrade(&inner)));
+        Ok(Self { inner, maintenance })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.server.name
+    }
+
+    pub fn config(&self) -> &PoolConfig {
+        &self.inner.config
+    }
+
+    /// Take an instance for exclusive use; it returns to the pool on drop
+    ///
+    /// Waits while `max` instances are checked out. Idle instances are
+    /// health-checked first; a new one is spawned if none is usable.
+    pub async fn checkout(&self) -> Result<PooledClient> {
+        let started = Instant::now();
+        let permit = Arc::clone(&self.inner.slots)
+            .acquire_owned()
+            .await
+            .map_err(|_| anyhow!("MCP server pool '{}' is shut down", self.name()))?;
+
+        let client = loop {
+            let instance = self.inner.idle.lock().unwrap().pop();
+            match instance {
+                Some(instance) if instance.client.is_healthy() => break instance.client,
+                Some(dead) => self.inner.discard(vec![dead]),
+                None => break self.inner.spawn().await?,
+            }
+        };
+
+        let micros = started.elapsed().as_micros() as u64;
+        self.inner.in_use.fetch_add(1, Ordering::SeqCst);
+        self.inner.checkouts.fetch_add(1, Ordering::Relaxed);
+        self.inner.checkout_micros_total.fetch_add(micros, Ordering::Relaxed);
+        self.inner.last_checkout_micros.store(micros, Ordering::Relaxed);
+
+        Ok(PooledClient {
+            client: Some(client),
+            inner: Arc::clone(&self.inner),
+            _permit: permit,
+        })
+    }
+
+    /// Tools of the server (listed once; all instances run the same server)
+    pub async fn list_tools(&self) -> Result<Vec<ToolInfo>> {
+        self.inner
+            .tools
+            .get_or_try_init(|| async { self.checkout().await?.list_tools().await })
+            .await
+            .cloned()
+    }
+
+    pub async fn get_llm_tools(&self) -> Result<Vec<praxis_llm::Tool>> {
+        Ok(self
+            .list_tools()
+            .await?
+            .into_iter()
+            .map(|t| praxis_llm::Tool::new(t.name, t.description.unwrap_or_default(), t.input_schema))
+            .collect())
+    }
+
+    /// Call a tool on a checked-out instance
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<Vec<ToolResponse>> {
+        self.checkout().await?.call_tool(name, arguments).await
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        let inner = &self.inner;
+        let checkouts = inner.checkouts.load(Ordering::Relaxed);
+        let mean_micros = inner
+            .checkout_micros_total
+            .load(Ordering::Relaxed)
+            .checked_div(checkouts)
+            .unwrap_or(0);
+
+        PoolStats {
+            idle: inner.idle.lock().unwrap().len(),
+            in_use: inner.in_use.load(Ordering::SeqCst),
+            total: inner.total.load(Ordering::SeqCst),
+            spawned: inner.spawned.load(Ordering::Relaxed),
+            checkouts,
+            last_checkout_latency: Duration::from_micros(inner.last_checkout_micros.load(Ordering::Relaxed)),
+            mean_checkout_latency: Duration::from_micros(mean_micros),
+        }
+    }
+
+    /// Stop accepting checkouts and shut down idle instances
+    ///
+    /// Instances still checked out are killed when they are returned.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.inner.closed.store(true, Ordering::SeqCst);
+        self.inner.slots.close();
+        self.maintenance.abort();
+
+        let idle: Vec<IdleInstance> = self.inner.idle.lock().unwrap().drain(..).collect();
+        let results = futures::future::join_all(idle.iter().map(|i| i.client.shutdown())).await;
+        self.inner.discard(idle);
+
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|r| r.err().map(|e| e.to_string()))
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("{}", failures.join("; ")))
+        }
+    }
+}
+
+impl Drop for StdioServerPool {
+    fn drop(&mut self) {
+        self.inner.closed.store(true, Ordering::SeqCst);
+        self.maintenance.abort();
+    }
+}
+
+/// Background health checks, reaping and replenishing
+async fn maintain(pool: Weak<PoolInner>) {
+    let period = match pool.upgrade() {
+        Some(inner) => (inner.config.idle_timeout() / 4).clamp(Duration::from_millis(10), Duration::from_secs(30)),
+        None => return,
+    };
+
+    loop {
+        tokio::time::sleep(period).await;
+        let Some(inner) = pool.upgrade() else {
+            return;
+        };
+        if inner.closed.load(Ordering::SeqCst) {
+            return;
+        }
+        inner.reap();
+        // A failed spawn is retried on the next tick
+        let _ = inner.replenish().await;
+    }
+}
+
+/// Instance checked out of a `StdioServerPool`
+pub struct PooledClient {
+    client: Option<MCPClient>,
+    inner: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledClient {
+    type Target = MCPClient;
+
+    fn deref(&self) -> &MCPClient {
+        self.client.as_ref().expect("client is present until drop")
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        self.inner.in_use.fetch_sub(1, Ordering::SeqCst);
+        let Some(client) = self.client.take() else {
+            return;
+        };
+
+        if client.is_healthy() && !self.inner.closed.load(Ordering::SeqCst) {
+            self.inner.idle.lock().unwrap().push(IdleInstance {
+                client,
+                since: Instant::now(),
+            });
+        } else {
+            self.inner.discard(vec![IdleInstance {
+                client,
+                since: Instant::now(),
+            }]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_config_with_pool() {
+        let server: StdioServerConfig = serde_json::from_value(serde_json::json!({
+            "name": "search",
+            "command": "python",
+            "args": ["-m", "search_server"],
+            "pool": { "min": 1, "max": 4 }
+        }))
+        .unwrap();
+
+        let pool = server.pool.unwrap();
+        assert_eq!((pool.min, pool.max), (1, 4));
+        assert_eq!(pool.idle_timeout(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_pool_config_validation() {
+        assert!(PoolConfig::new(0, 1).validate().is_ok());
+        assert!(PoolConfig::new(2, 1).validate().is_err());
+        assert!(PoolConfig::new(0, 0).validate().is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Per-tool settings of an `MCPToolExecutor`
+//!
+//! A tool with side effects can name a read tool to verify its effect with:
+//! after each successful call the graph's `ToolNode` calls the read tool with
+//! arguments mapped from the write call's, and reports a failed check as an
+//! error result so the model retries or says the change wasn't confirmed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Settings of one tool, by tool name in `MCPToolExecutor::configure_tool`
+///
+/// ```toml
+/// [mcp.tools.update_ticket]
+/// verify_with = "get_ticket"
+/// verify_arguments = { id = "{ticket_id}" }
+/// verify_expect = "{status}"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolConfig {
+    /// Read tool to call after each successful call of this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_with: Option<String>,
+    /// Arguments of the read call. String values are templates: `{field}`
+    /// takes the write call's argument of that name (`{a.b}` reaches into
+    /// objects); a value that is a single placeholder keeps the argument's
+    /// JSON type. Null means no arguments.
+    pub verify_arguments: Value,
+    /// Text the read result must contain, templated the same way; without
+    /// it any successful read passes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_expect: Option<String>,
+}
+
+impl ToolConfig {
+    /// Verify calls of the tool with `read_tool`
+    pub fn verify_with(read_tool: impl Into<String>, arguments: Value) -> Self {
+        Self {
+            verify_with: Some(read_tool.into()),
+            verify_arguments: arguments,
+            verify_expect: None,
+        }
+    }
+
+    pub fn with_expect(mut self, expect: impl Into<String>) -> Self {
+        self.verify_expect = Some(expect.into());
+        self
+    }
+
+    /// Arguments of the read call verifying a call made with `arguments`
+    pub fn verification_arguments(&self, arguments: &Value) -> Result<Value, String> {
+        match &self.verify_arguments {
+            Value::Null => Ok(Value::Object(Default::default())),
+            template => fill(template, arguments),
+        }
+    }
+
+    /// Check the read tool's `result` for a call made with `arguments`
+    pub fn check(&self, arguments: &Value, result: &str) -> Result<(), String> {
+        let Some(expect) = &self.verify_expect else {
+            return Ok(());
+        };
+        let expected = render(expect, arguments)?;
+        if result.contains(&expected) {
+            Ok(())
+        } else {
+            Err(format!("result does not contain '{}'", expected))
+        }
+    }
+}
+
+/// `template` with every string filled from `arguments`
+fn fill(template: &Value, arguments: &Value) -> Result<Value, String> {
+    match template {
+        Value::String(text) => match placeholder(text) {
+            Some(path) => lookup(arguments, path).cloned(),
+            None => render(text, arguments).map(Value::String),
+        },
+        Value::Array(items) => items.iter().map(|item| fill(item, arguments)).collect::<Result<_, _>>().map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), fill(value, arguments)?)))
+            .collect::<Result<_, String>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+/// The path of a template that is exactly one `{placeholder}`
+fn placeholder(text: &str) -> Option<&str> {
+    let path = text.strip_prefix('{')?.strip_suffix('}')?;
+    (!path.contains(['{', '}'])).then_some(path)
+}
+
+fn render(template: &str, arguments: &Value) -> Result<String, String> {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        text.push_str(&rest[..open]);
+        match lookup(arguments, &rest[open + 1..close])? {
+            Value::String(value) => text.push_str(value),
+            other => text.push_str(&other.to_string()),
+        }
+        rest = &rest[close + 1..];
+    }
+    text.push_str(rest);
+    Ok(text)
+}
+
+fn lookup<'a>(arguments: &'a Value, path: &str) -> Result<&'a Value, String> {
+    path.split('.')
+        .try_fold(arguments, |value, key| value.get(key.trim()))
+        .ok_or_else(|| format!("the call has no argument '{}'", path.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_arguments_are_mapped_from_the_write_call() {
+        let config = ToolConfig::verify_with(
+            "get_ticket",
+            json!({ "id": "{ticket.id}", "label": "ticket {ticket.id}", "fields": ["{field}"], "limit": 1 }),
+        );
+        let arguments = json!({ "ticket": { "id": 42 }, "field": "status" });
+
+        assert_eq!(
+            config.verification_arguments(&arguments).unwrap(),
+            json!({ "id": 42, "label": "ticket 42", "fields": ["status"], "limit": 1 })
+        );
+        assert_eq!(
+            config.verification_arguments(&json!({ "field": "status" })).unwrap_err(),
+            "the call has no argument 'ticket.id'"
+        );
+        assert_eq!(ToolConfig::verify_with("list", Value::Null).verification_arguments(&arguments).unwrap(), json!({}));
+    }
+
+    #[test]
+    fn test_expected_text_is_templated() {
+        let config = ToolConfig::verify_with("get_ticket", Value::Null).with_expect("status: {status}");
+        let arguments = json!({ "status": "closed" });
+
+        assert!(config.check(&arguments, "#42 status: closed").is_ok());
+        assert_eq!(config.check(&arguments, "#42 status: open").unwrap_err(), "result does not contain 'status: closed'");
+        assert!(ToolConfig::verify_with("get_ticket", Value::Null).check(&arguments, "").is_ok());
+    }
+}
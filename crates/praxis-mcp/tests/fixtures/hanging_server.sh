@@ -0,0 +1,20 @@
+#!/bin/sh
+# Minimal stdio MCP server used by the shutdown tests.
+#
+# Answers `initialize`, never answers anything else, spawns a long-lived
+# grandchild (its pid is written to $1) and keeps running after stdin is
+# closed, so the client has to escalate to killing the process group.
+
+sleep 300 &
+echo $! > "$1"
+
+while IFS= read -r line; do
+  case "$line" in
+    *'"method":"initialize"'*)
+      id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"hanging","version":"0.0.0"}}}\n' "$id"
+      ;;
+  esac
+done
+
+exec sleep 300
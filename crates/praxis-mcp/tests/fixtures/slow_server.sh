@@ -0,0 +1,25 @@
+#!/bin/sh
+# Minimal stdio MCP server used by the pool tests.
+#
+# Sleeps $1 seconds before answering anything (a slow interpreter or model
+# load), then serves one tool, named $3 (`echo` by default), whose calls
+# take $2 seconds and return the server's pid.
+
+sleep "${1:-0}"
+tool="${3:-echo}"
+
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"slow","version":"0.0.0"}}}\n' "$id"
+      ;;
+    *'"method":"tools/list"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"%s","description":"Report the server pid","inputSchema":{"type":"object","properties":{}}}]}}\n' "$id" "$tool"
+      ;;
+    *'"method":"tools/call"'*)
+      sleep "${2:-0}"
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"pid %s"}]}}\n' "$id" "$$"
+      ;;
+  esac
+done
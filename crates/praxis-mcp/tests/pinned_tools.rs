@@ -0,0 +1,47 @@
+//! Executors pinned to a thread's tool profile
+#![cfg(target_os = "linux")]
+
+use praxis_llm::Tool;
+use praxis_mcp::{MCPClient, MCPToolExecutor};
+use serde_json::json;
+use std::path::PathBuf;
+
+async fn executor() -> MCPToolExecutor {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/slow_server.sh");
+    let executor = MCPToolExecutor::new();
+    let client = MCPClient::new_stdio("slow", fixture, ["0", "0"]).await.unwrap();
+    executor.add_server(client).await.unwrap();
+    executor
+}
+
+#[tokio::test]
+async fn test_pinned_definitions_are_offered_while_the_server_has_them() {
+    let base = executor().await;
+    let pinned_echo = Tool::new("echo", "Pinned description", json!({ "type": "object" }));
+    let retired = Tool::new("retired", "Removed from the server", json!({ "type": "object" }));
+    let pinned = base.scoped(Vec::new()).await.with_pinned_tools(vec![pinned_echo, retired]);
+
+    let tools = pinned.get_llm_tools().await.unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].function.description.as_deref(), Some("Pinned description"));
+    assert!(pinned.execute_tool("echo", json!({})).await.is_ok());
+
+    let error = pinned.execute_tool("retired", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("no MCP server provides it"), "{}", error);
+
+    // The unpinned executor still sees the server's own definitions
+    assert_eq!(base.mcp_tools().await.unwrap()[0].function.description.as_deref(), Some("Report the server pid"));
+    base.shutdown_all().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_tools_added_after_pinning_are_not_callable() {
+    let base = executor().await;
+    let retired = Tool::new("retired", "Removed from the server", json!({ "type": "object" }));
+    let pinned = base.scoped(Vec::new()).await.with_pinned_tools(vec![retired]);
+
+    assert!(pinned.get_llm_tools().await.unwrap().is_empty());
+    let error = pinned.execute_tool("echo", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("not in this thread's pinned tool profile"), "{}", error);
+    base.shutdown_all().await.unwrap();
+}
@@ -0,0 +1,51 @@
+//! Tool calls report the server that ran them
+#![cfg(target_os = "linux")]
+
+use async_trait::async_trait;
+use praxis_mcp::{LocalTool, MCPClient, MCPToolExecutor, ToolResponse, LOCAL_TOOL_SERVER};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+struct Clock;
+
+#[async_trait]
+impl LocalTool for Clock {
+    fn name(&self) -> &str {
+        "now"
+    }
+
+    fn description(&self) -> String {
+        "Current time".to_string()
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> anyhow::Result<Vec<ToolResponse>> {
+        Ok(vec![ToolResponse::Text { text: "10:00".to_string() }])
+    }
+}
+
+/// Stdio server named `name` that serves one tool, `tool`
+async fn server(name: &str, tool: &str) -> MCPClient {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/slow_server.sh");
+    MCPClient::new_stdio(name, fixture, ["0", "0", tool]).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_each_call_names_its_server() {
+    let executor = MCPToolExecutor::new();
+    executor.add_server(server("weather", "get_weather").await).await.unwrap();
+    executor.add_server(server("search", "web_search").await).await.unwrap();
+    executor.register_local_tool(Clock).await.unwrap();
+
+    for (tool, server) in [("get_weather", "weather"), ("web_search", "search"), ("now", LOCAL_TOOL_SERVER)] {
+        let output = executor.call_tool(tool, json!({})).await.unwrap();
+        assert_eq!(output.server, server, "server of {}", tool);
+        assert!(!output.responses.is_empty());
+    }
+    assert!(executor.call_tool("missing", json!({})).await.is_err());
+
+    executor.shutdown_all().await.unwrap();
+}
@@ -0,0 +1,124 @@
+//! Warm pool tests for stdio MCP servers
+#![cfg(target_os = "linux")]
+
+use praxis_mcp::{MCPClient, MCPToolExecutor, PoolConfig, StdioServerConfig, StdioServerPool, ToolResponse};
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const STARTUP_SECS: &str = "0.5";
+
+fn fixture() -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/slow_server.sh")
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn server(name: &str, call_secs: &str, pool: PoolConfig) -> StdioServerConfig {
+    StdioServerConfig::new(name, fixture())
+        .with_args([STARTUP_SECS, call_secs])
+        .with_pool(pool)
+}
+
+#[tokio::test]
+async fn test_warm_pool_skips_startup_on_first_call() {
+    let started = Instant::now();
+    let cold = MCPClient::new_stdio("cold", fixture(), [STARTUP_SECS, "0"]).await.unwrap();
+    cold.call_tool("echo", json!({})).await.unwrap();
+    let cold_latency = started.elapsed();
+    cold.shutdown().await.unwrap();
+
+    let pool = StdioServerPool::start(server("warm", "0", PoolConfig::new(1, 2))).await.unwrap();
+    let started = Instant::now();
+    pool.call_tool("echo", json!({})).await.unwrap();
+    let warm_latency = started.elapsed();
+
+    assert!(cold_latency >= Duration::from_millis(500));
+    assert!(
+        warm_latency < Duration::from_millis(250),
+        "warm call took {:?} (cold {:?})",
+        warm_latency,
+        cold_latency
+    );
+    let stats = pool.stats();
+    assert_eq!((stats.spawned, stats.checkouts), (1, 1));
+    assert!(stats.last_checkout_latency < Duration::from_millis(250));
+
+    pool.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_concurrent_calls_use_separate_instances_up_to_max() {
+    let pool = Arc::new(
+        StdioServerPool::start(server("parallel", "0.3", PoolConfig::new(2, 2)))
+            .await
+            .unwrap(),
+    );
+
+    let calls = (0..4).map(|_| {
+        let pool = Arc::clone(&pool);
+        tokio::spawn(async move {
+            let response = pool.call_tool("echo", json!({})).await.unwrap();
+            let stats = pool.stats();
+            assert!(stats.total <= 2, "pool grew past max: {:?}", stats);
+            response[0].to_string()
+        })
+    });
+    let pids: HashSet<String> = futures::future::join_all(calls)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+
+    // Four calls, two instances: each instance served two of them
+    assert_eq!(pids.len(), 2);
+    let stats = pool.stats();
+    assert_eq!((stats.spawned, stats.total, stats.idle, stats.in_use), (2, 2, 2, 0));
+
+    pool.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_idle_instances_above_min_are_reaped() {
+    let config = PoolConfig::new(1, 3).with_idle_timeout(Duration::from_secs(1));
+    let pool = Arc::new(StdioServerPool::start(server("reaped", "0.2", config)).await.unwrap());
+
+    let calls = (0..3).map(|_| {
+        let pool = Arc::clone(&pool);
+        tokio::spawn(async move { pool.call_tool("echo", json!({})).await.unwrap() })
+    });
+    futures::future::join_all(calls).await;
+    assert_eq!(pool.stats().total, 3);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pool.stats().total > 1 {
+        assert!(Instant::now() < deadline, "idle instances not reaped: {:?}", pool.stats());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(pool.stats().idle, 1);
+
+    pool.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_executor_routes_calls_through_pool() {
+    let executor = MCPToolExecutor::new();
+    let pool = StdioServerPool::start(server("pooled", "0", PoolConfig::new(1, 1))).await.unwrap();
+    executor.add_pool(pool).await.unwrap();
+
+    let tools = executor.get_llm_tools().await.unwrap();
+    assert_eq!(tools[0].function.name, "echo");
+    let result = executor.execute_tool("echo", json!({})).await.unwrap();
+    assert!(ToolResponse::join_responses(&result).contains("pid"));
+
+    let stats = executor.pool_stats().await;
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].0, "pooled");
+    assert_eq!(stats[0].1.spawned, 1);
+
+    executor.shutdown_all().await.unwrap();
+    assert!(executor.pool_stats().await.is_empty());
+}
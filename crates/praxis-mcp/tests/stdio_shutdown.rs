@@ -0,0 +1,120 @@
+//! Lifecycle tests for stdio MCP servers: no orphans, no hung calls
+#![cfg(target_os = "linux")]
+
+use praxis_mcp::{MCPClient, MCPToolExecutor};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hanging_server.sh")
+}
+
+/// Spawn the hanging server, returning the client and the grandchild pid
+async fn spawn_server(name: &str) -> (MCPClient, u32, u32) {
+    let pid_file = std::env::temp_dir().join(format!("praxis-mcp-{}-{}.pid", name, std::process::id()));
+    let _ = std::fs::remove_file(&pid_file);
+
+    let client = MCPClient::new_stdio(name, fixture(), [pid_file.as_os_str()])
+        .await
+        .expect("server should initialize")
+        .with_shutdown_timeout(Duration::from_millis(300));
+
+    let grandchild = wait_for_pid_file(&pid_file).await;
+    let _ = std::fs::remove_file(&pid_file);
+    let leader = leader_pid(grandchild);
+
+    (client, leader, grandchild)
+}
+
+async fn wait_for_pid_file(path: &std::path::Path) -> u32 {
+    for _ in 0..100 {
+        if let Some(pid) = std::fs::read_to_string(path).ok().and_then(|s| s.trim().parse().ok()) {
+            return pid;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("server did not write its pid file");
+}
+
+/// Parent pid from /proc/<pid>/stat
+fn leader_pid(pid: u32) -> u32 {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap();
+    let after_name = &stat[stat.rfind(')').unwrap() + 2..];
+    after_name.split_whitespace().nth(1).unwrap().parse().unwrap()
+}
+
+/// Running = present in /proc and not a zombie awaiting its reaper
+fn is_running(pid: u32) -> bool {
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        Ok(stat) => !stat[stat.rfind(')').unwrap() + 2..].starts_with('Z'),
+        Err(_) => false,
+    }
+}
+
+async fn assert_gone(pids: &[u32]) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pids.iter().any(|&pid| is_running(pid)) {
+        assert!(Instant::now() < deadline, "processes still running: {:?}", pids);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_shutdown_kills_process_tree() {
+    let (client, leader, grandchild) = spawn_server("tree").await;
+    assert!(is_running(leader) && is_running(grandchild));
+
+    client.shutdown().await.unwrap();
+
+    assert_gone(&[leader, grandchild]).await;
+    // Second shutdown is a no-op
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_in_flight_call_fails_with_transport_closed() {
+    let (client, leader, grandchild) = spawn_server("in-flight").await;
+    let client = Arc::new(client);
+
+    let call = tokio::spawn({
+        let client = Arc::clone(&client);
+        async move { client.call_tool("never_answers", serde_json::json!({})).await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    client.shutdown().await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), call)
+        .await
+        .expect("in-flight call should not hang")
+        .unwrap();
+    let err = result.expect_err("call should fail once the transport is closed");
+    assert!(err.to_string().contains("closed"), "{}", err);
+
+    assert_gone(&[leader, grandchild]).await;
+}
+
+#[tokio::test]
+async fn test_drop_kills_process_tree() {
+    let (client, leader, grandchild) = spawn_server("drop").await;
+
+    drop(client);
+
+    assert_gone(&[leader, grandchild]).await;
+}
+
+#[tokio::test]
+async fn test_executor_shutdown_all() {
+    let (first, first_leader, first_grandchild) = spawn_server("first").await;
+    let (second, second_leader, second_grandchild) = spawn_server("second").await;
+
+    let executor = MCPToolExecutor::new();
+    executor.add_server(first).await.unwrap();
+    executor.add_server(second).await.unwrap();
+
+    executor.shutdown_all().await.unwrap();
+
+    assert_gone(&[first_leader, first_grandchild, second_leader, second_grandchild]).await;
+    assert!(executor.list_all_tools().await.unwrap().is_empty());
+}
@@ -61,6 +61,7 @@ async fn main() -> anyhow::Result<()> {
             },
             model: "gpt-4".to_string(),
             usage: None,
+            cost: None,
         },
         metadata: std::collections::HashMap::new(),
     };
@@ -90,6 +91,7 @@ async fn main() -> anyhow::Result<()> {
                 result: "Sunny, 72°F".to_string(),
                 is_error: false,
                 duration_ms: 450,
+                tool_server: Some("weather".to_string()),
             }],
         },
         metadata: std::collections::HashMap::new(),
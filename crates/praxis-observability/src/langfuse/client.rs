@@ -120,6 +120,25 @@ impl LangfuseClient {
         self.handle_response(response).await
     }
 
+    /// Check the keys against the host, which lists the keys' project
+    /// (`GET /api/public/projects`) only when they are valid
+    pub async fn check_credentials(&self) -> Result<()> {
+        if self.public_key.is_empty() || self.secret_key.is_empty() {
+            anyhow::bail!("Langfuse public or secret key is missing");
+        }
+        let url = format!("{}/api/public/projects", self.host);
+
+        let response = self
+            .client
+            .get(&url)
+            .basic_auth(&self.public_key, Some(&self.secret_key))
+            .send()
+            .await
+            .context("Failed to reach Langfuse")?;
+
+        self.handle_response(response).await
+    }
+
     /// Handle API response
     async fn handle_response(&self, response: reqwest::Response) -> Result<()> {
         let status = response.status();
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use praxis_core::{Clock, IdGen, SystemClock, UuidIds};
 
 use crate::observer::Observer;
 use crate::types::{NodeObservation, NodeObservationData, NodeOutput};
@@ -16,6 +17,8 @@ pub struct LangfuseObserver {
     client: Arc<LangfuseClient>,
     /// Stores trace IDs for active runs
     traces: Arc<Mutex<HashMap<String, String>>>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGen>,
 }
 
 impl LangfuseObserver {
@@ -32,15 +35,27 @@ impl LangfuseObserver {
         Ok(Self {
             client: Arc::new(client),
             traces: Arc::new(Mutex::new(HashMap::new())),
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidIds),
         })
     }
 
+    /// Timestamp ingestion events with `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Mint trace ids with `ids` instead of random UUIDs
+    pub fn with_id_gen(mut self, ids: Arc<dyn IdGen>) -> Self {
+        self.ids = ids;
+        self
+    }
+
     /// Get or create trace ID for a run
     fn get_or_create_trace_id(&self, run_id: &str) -> String {
         let traces = self.traces.lock().unwrap();
-        traces.get(run_id).cloned().unwrap_or_else(|| {
-            uuid::Uuid::new_v4().to_string()
-        })
+        traces.get(run_id).cloned().unwrap_or_else(|| self.ids.next_id())
     }
 
     /// Store trace ID for a run
@@ -67,6 +82,7 @@ impl LangfuseObserver {
                 outputs,
                 model,
                 usage,
+                cost,
             } => {
                 tracing::info!(
                     "Preparing generation for Langfuse - input_messages: {}, outputs: {}",
@@ -112,6 +128,10 @@ impl LangfuseObserver {
                     metadata.insert("openai_ids".to_string(), serde_json::json!(openai_ids));
                 }
                 metadata.insert("output_types".to_string(), serde_json::json!(output_types));
+                // Langfuse's usage has no cache counter; the prompt-cache hits ride along here
+                if let Some(cached_tokens) = usage.as_ref().and_then(|u| u.cached_tokens) {
+                    metadata.insert("cached_tokens".to_string(), serde_json::json!(cached_tokens));
+                }
                 
                 tracing::info!(
                     "Creating single generation for llm_node: input_len={}, output_types={:?}",
@@ -144,6 +164,10 @@ impl LangfuseObserver {
                         prompt_tokens: Some(u.input_tokens),
                         completion_tokens: Some(u.output_tokens),
                         total_tokens: Some(u.total_tokens),
+                        input_cost: cost.map(|c| c.input_usd),
+                        // Langfuse has no reasoning cost; it is billed as output
+                        output_cost: cost.map(|c| c.output_usd + c.reasoning_usd),
+                        total_cost: cost.map(|c| c.total_usd),
                     }),
                 };
 
@@ -154,7 +178,7 @@ impl LangfuseObserver {
                 );
 
                 // Create batch ingestion event for the single generation
-                let now = chrono::Utc::now();
+                let now = self.clock.now();
                 let event = IngestionEvent {
                     id: format!("{}-generation-event", observation.span_id),
                     timestamp: now.to_rfc3339(),
@@ -214,7 +238,7 @@ impl LangfuseObserver {
                 };
 
                 // Create batch ingestion event
-                let now = chrono::Utc::now();
+                let now = self.clock.now();
                 let event = IngestionEvent {
                     id: format!("{}-span-event", observation.span_id),
                     timestamp: now.to_rfc3339(),
@@ -241,7 +265,7 @@ impl LangfuseObserver {
 #[async_trait]
 impl Observer for LangfuseObserver {
     async fn trace_start(&self, run_id: String, conversation_id: String) -> Result<()> {
-        let trace_id = uuid::Uuid::new_v4().to_string();
+        let trace_id = self.ids.next_id();
         
         tracing::info!(
             "Starting Langfuse trace: trace_id={}, run_id={}, conversation_id={}",
@@ -250,10 +274,10 @@ impl Observer for LangfuseObserver {
             conversation_id
         );
         
-        let now = chrono::Utc::now();
+        let now = self.clock.now();
         let trace_body = TraceBody {
             id: trace_id.clone(),
-            name: Some(format!("agent_run_{}", &run_id[..8])),
+            name: Some(format!("agent_run_{}", &run_id[..8.min(run_id.len())])),
             user_id: Some(conversation_id.clone()),
             metadata: Some({
                 let mut map = HashMap::new();
@@ -266,6 +290,7 @@ impl Observer for LangfuseObserver {
             }),
             tags: Some(vec!["praxis".to_string(), "agent".to_string()]),
             timestamp: Some(now.to_rfc3339()),
+            input: None,
         };
 
         // Store trace ID for this run
@@ -297,6 +322,50 @@ impl Observer for LangfuseObserver {
         }
     }
 
+    async fn trace_user_input(
+        &self,
+        run_id: String,
+        conversation_id: String,
+        content: String,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let trace_id = self.get_or_create_trace_id(&run_id);
+
+        tracing::debug!("Setting Langfuse trace input: trace_id={}, run_id={}", trace_id, run_id);
+
+        // Sets the trace's top-level input; the trace's other fields are kept
+        let now = self.clock.now();
+        let input_body = TraceBody {
+            id: trace_id.clone(),
+            name: None,
+            user_id: Some(conversation_id),
+            metadata: if metadata.is_empty() { None } else { Some(metadata) },
+            tags: None,
+            timestamp: None,
+            input: Some(serde_json::json!(content)),
+        };
+
+        let event = IngestionEvent {
+            id: format!("{}-trace-input-event", trace_id),
+            timestamp: now.to_rfc3339(),
+            event_type: "trace-create".to_string(),
+            body: serde_json::to_value(&input_body)
+                .context("Failed to serialize trace input body")?,
+        };
+
+        let batch = IngestionBatch {
+            batch: vec![event],
+        };
+
+        match self.client.ingest_batch(batch).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::error!("Failed to set Langfuse trace input: {}", e);
+                Err(e)
+            }
+        }
+    }
+
     async fn trace_llm_node(&self, observation: NodeObservation) -> Result<()> {
         tracing::debug!(
             "Tracing LLM node: span_id={}, run_id={}",
@@ -346,7 +415,7 @@ impl Observer for LangfuseObserver {
             total_duration_ms
         );
 
-        let now = chrono::Utc::now();
+        let now = self.clock.now();
         let update_body = TraceBody {
             id: trace_id.clone(),
             name: Some(format!("agent_run_{}", &run_id[..8.min(run_id.len())])),
@@ -362,6 +431,7 @@ impl Observer for LangfuseObserver {
             }),
             tags: Some(vec!["praxis".to_string(), "completed".to_string()]),
             timestamp: None,
+            input: None,
         };
 
         // Create batch ingestion event
@@ -10,6 +10,9 @@ pub struct TraceBody {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     pub tags: Option<Vec<String>>,
     pub timestamp: Option<String>,
+    /// Left out when unset, so updates keep the input already on the trace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<serde_json::Value>,
 }
 
 /// Request body for creating a span
@@ -54,6 +57,13 @@ pub struct UsageInfo {
     pub prompt_tokens: Option<u32>,
     pub completion_tokens: Option<u32>,
     pub total_tokens: Option<u32>,
+    /// USD, shown as the generation's cost instead of Langfuse's own estimate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_cost: Option<f64>,
 }
 
 /// Request body for updating a trace
@@ -1,4 +1,6 @@
 use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
 use anyhow::Result;
 use crate::types::NodeObservation;
 
@@ -6,6 +8,8 @@ use crate::types::NodeObservation;
 /// 
 /// Implementations provide tracing and monitoring for AI agent executions.
 /// All methods are async and use fire-and-forget pattern to avoid blocking.
+/// The graph holds its observer as `Arc<dyn Observer>` and calls it from
+/// spawned tasks; `Arc<T>` is an observer as well.
 #[async_trait]
 pub trait Observer: Send + Sync {
     /// Initialize a new trace for a graph execution run
@@ -22,6 +26,29 @@ pub trait Observer: Send + Sync {
         conversation_id: String,
     ) -> Result<()>;
 
+    /// Record the user message that started the run
+    ///
+    /// Called once per run, after `trace_start`, with the text of the last
+    /// human message of the input. The default does nothing.
+    ///
+    /// # Arguments
+    /// * `run_id` - The run identifier from trace_start
+    /// * `conversation_id` - Thread/conversation identifier
+    /// * `content` - Text of the user message
+    /// * `metadata` - The run's input variables
+    ///
+    /// # Returns
+    /// Result indicating whether tracing succeeded
+    async fn trace_user_input(
+        &self,
+        _run_id: String,
+        _conversation_id: String,
+        _content: String,
+        _metadata: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Record an LLM node execution
     /// 
     /// # Arguments
@@ -63,3 +90,32 @@ pub trait Observer: Send + Sync {
     ) -> Result<()>;
 }
 
+
+#[async_trait]
+impl<T: Observer + ?Sized> Observer for Arc<T> {
+    async fn trace_start(&self, run_id: String, conversation_id: String) -> Result<()> {
+        (**self).trace_start(run_id, conversation_id).await
+    }
+
+    async fn trace_user_input(
+        &self,
+        run_id: String,
+        conversation_id: String,
+        content: String,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        (**self).trace_user_input(run_id, conversation_id, content, metadata).await
+    }
+
+    async fn trace_llm_node(&self, observation: NodeObservation) -> Result<()> {
+        (**self).trace_llm_node(observation).await
+    }
+
+    async fn trace_tool_node(&self, observation: NodeObservation) -> Result<()> {
+        (**self).trace_tool_node(observation).await
+    }
+
+    async fn trace_end(&self, run_id: String, status: String, total_duration_ms: u64) -> Result<()> {
+        (**self).trace_end(run_id, status, total_duration_ms).await
+    }
+}
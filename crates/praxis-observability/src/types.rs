@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Re-export TokenUsage from praxis-llm to avoid duplication
-pub use praxis_llm::TokenUsage;
+pub use praxis_llm::{CostBreakdown, TokenUsage};
 
 /// Observation data captured during node execution
 /// 
@@ -80,6 +80,10 @@ pub enum NodeObservationData {
         /// Token usage information
         #[serde(skip_serializing_if = "Option::is_none")]
         usage: Option<TokenUsage>,
+
+        /// What the call cost, when the graph prices calls
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cost: Option<CostBreakdown>,
     },
     
     /// Tool node execution data
@@ -144,6 +148,10 @@ pub struct ToolResultInfo {
     
     /// Execution duration in milliseconds
     pub duration_ms: u64,
+    
+    /// MCP server that ran the call (`_local` for a local tool)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_server: Option<String>,
 }
 
 /// Context for managing trace and span IDs
@@ -170,11 +178,21 @@ pub struct TraceContext {
 impl TraceContext {
     /// Create a new trace context
     pub fn new(run_id: String, conversation_id: String) -> Self {
+        Self::new_with(run_id, conversation_id, &praxis_core::SystemClock, &praxis_core::UuidIds)
+    }
+
+    /// Create a trace context stamped by `clock`, with a trace id from `ids`
+    pub fn new_with(
+        run_id: String,
+        conversation_id: String,
+        clock: &dyn praxis_core::Clock,
+        ids: &dyn praxis_core::IdGen,
+    ) -> Self {
         Self {
-            trace_id: uuid::Uuid::new_v4().to_string(),
+            trace_id: ids.next_id(),
             run_id,
             conversation_id,
-            started_at: chrono::Utc::now(),
+            started_at: clock.now(),
             span_counter: 0,
         }
     }
@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::time::Instant;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
-use crate::{DBMessage, MessageRole, MessageType};
+use praxis_core::{Clock, IdGen, SystemClock, UuidIds};
+
+use crate::{ContentEncoding, DBMessage, MessageRole, MessageType};
 
 /// Trait for extracting information from stream events
 /// This allows EventAccumulator to work with any event type
@@ -14,6 +17,11 @@ pub trait StreamEventExtractor {
     fn reasoning_content(&self) -> Option<&str>;
     fn message_content(&self) -> Option<&str>;
     fn tool_call_info(&self) -> Option<(u32, Option<&str>, Option<&str>, Option<&str>)>;
+    
+    /// Agent that produced the event (for multi-agent attribution)
+    fn agent(&self) -> Option<&str> {
+        None
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,15 +62,20 @@ pub struct EventAccumulator<E> {
     thread_id: String,
     user_id: String,
     current_type: Option<EventType>,
+    current_agent: Option<String>,
     
     // Buffers for different event types
     reasoning_buffer: String,
     message_buffer: String,
-    tool_calls: HashMap<String, ToolCallBuffer>,
+    /// Tool calls by stream index, so they finalize in the order the model made them
+    tool_calls: BTreeMap<u32, ToolCallBuffer>,
     
     // Timing tracking
     current_start: Option<Instant>,
     
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGen>,
+    
     // Phantom data to track event type
     _phantom: PhantomData<E>,
 }
@@ -73,19 +86,37 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
             thread_id,
             user_id,
             current_type: None,
+            current_agent: None,
             reasoning_buffer: String::new(),
             message_buffer: String::new(),
-            tool_calls: HashMap::new(),
+            tool_calls: BTreeMap::new(),
             current_start: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidIds),
             _phantom: PhantomData,
         }
     }
     
+    /// Timestamp finalized messages with `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Mint message ids with `ids` instead of random UUIDs
+    pub fn with_id_gen(mut self, ids: Arc<dyn IdGen>) -> Self {
+        self.ids = ids;
+        self
+    }
+    
     /// Push event and check for type transition (Observer Pattern)
     /// 
-    /// Returns Some(DBMessage) when type changes, indicating the previous buffer is complete
-    pub fn push_and_check_transition(&mut self, event: &E) -> Option<DBMessage> {
-        let new_type = EventType::from_event(event)?;
+    /// Returns the previous buffer's messages when the type changes, one per
+    /// tool call for a tool-call buffer; empty otherwise
+    pub fn push_and_check_transition(&mut self, event: &E) -> Vec<DBMessage> {
+        let Some(new_type) = EventType::from_event(event) else {
+            return Vec::new();
+        };
         
         // Detect transition
         let transitioned = self.current_type.map_or(false, |prev| prev != new_type);
@@ -94,7 +125,7 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
             // Finalize previous buffer before switching
             self.finalize_current_buffer()
         } else {
-            None
+            Vec::new()
         };
         
         // Update state
@@ -115,14 +146,17 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
         completed_message
     }
     
-    fn finalize_current_buffer(&mut self) -> Option<DBMessage> {
+    fn finalize_current_buffer(&mut self) -> Vec<DBMessage> {
         let duration_ms = self.current_start
             .map(|start| start.elapsed().as_millis() as u64);
+        let Some(current_type) = self.current_type else {
+            return Vec::new();
+        };
         
-        let message = match self.current_type? {
+        let message = match current_type {
             EventType::Reasoning if !self.reasoning_buffer.is_empty() => {
                 Some(DBMessage {
-                    id: uuid::Uuid::new_v4().to_string(),
+                    id: self.ids.next_id(),
                     thread_id: self.thread_id.clone(),
                     user_id: self.user_id.clone(),
                     role: MessageRole::Assistant,
@@ -130,15 +164,25 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
                     content: std::mem::take(&mut self.reasoning_buffer),
                     tool_call_id: None,
                     tool_name: None,
+                    tool_server: None,
+                    verification: None,
                     arguments: None,
                     reasoning_id: None,
-                    created_at: chrono::Utc::now(),
+                    created_at: self.clock.now(),
                     duration_ms,
+                    agent: self.current_agent.clone(),
+                    content_encoding: ContentEncoding::None,
+                    content_sha256: None,
+                    token_count: None,
+                    summary: None,
+                    iteration: None,
+                    usage: None,
+                    citations: Vec::new(),
                 })
             },
             EventType::Message if !self.message_buffer.is_empty() => {
                 Some(DBMessage {
-                    id: uuid::Uuid::new_v4().to_string(),
+                    id: self.ids.next_id(),
                     thread_id: self.thread_id.clone(),
                     user_id: self.user_id.clone(),
                     role: MessageRole::Assistant,
@@ -146,23 +190,34 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
                     content: std::mem::take(&mut self.message_buffer),
                     tool_call_id: None,
                     tool_name: None,
+                    tool_server: None,
+                    verification: None,
                     arguments: None,
                     reasoning_id: None,
-                    created_at: chrono::Utc::now(),
+                    created_at: self.clock.now(),
                     duration_ms,
+                    agent: self.current_agent.clone(),
+                    content_encoding: ContentEncoding::None,
+                    content_sha256: None,
+                    token_count: None,
+                    summary: None,
+                    iteration: None,
+                    usage: None,
+                    citations: Vec::new(),
                 })
             },
-            EventType::ToolCall => {
-                // Finalize tool calls
-                self.finalize_tool_calls()
-            },
+            EventType::ToolCall => return self.finalize_tool_calls(),
             _ => None,
         };
         
-        message
+        message.into_iter().collect()
     }
     
     fn accumulate_event(&mut self, event: &E) {
+        if let Some(agent) = event.agent() {
+            self.current_agent = Some(agent.to_string());
+        }
+        
         if let Some(content) = event.reasoning_content() {
             self.reasoning_buffer.push_str(content);
         }
@@ -172,17 +227,18 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
         }
         
         if let Some((index, id, name, arguments)) = event.tool_call_info() {
-            let tool_call_id = id.map(String::from)
-                .unwrap_or_else(|| format!("call_{}", index));
-            
-            let entry = self.tool_calls.entry(tool_call_id.clone())
+            // Only a call's first delta has its id; later ones find it by index
+            let entry = self.tool_calls.entry(index)
                 .or_insert_with(|| ToolCallBuffer {
-                    tool_call_id: tool_call_id.clone(),
+                    tool_call_id: format!("call_{}", index),
                     tool_name: String::new(),
                     arguments: String::new(),
                     started_at: Instant::now(),
                 });
             
+            if let Some(id) = id {
+                entry.tool_call_id = id.to_string();
+            }
             if let Some(name) = name {
                 entry.tool_name = name.to_string();
             }
@@ -192,17 +248,12 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
         }
     }
     
-    fn finalize_tool_calls(&mut self) -> Option<DBMessage> {
-        // Take the first tool call and create a message for it
-        // In a real scenario, you might want to handle multiple tool calls differently
-        if let Some((_, tool_call)) = self.tool_calls.drain().next() {
-            let duration_ms = tool_call.started_at.elapsed().as_millis() as u64;
-            
-            // Parse arguments as JSON
-            let arguments = serde_json::from_str(&tool_call.arguments).ok();
-            
-            Some(DBMessage {
-                id: uuid::Uuid::new_v4().to_string(),
+    /// One message per buffered tool call, in index order
+    fn finalize_tool_calls(&mut self) -> Vec<DBMessage> {
+        std::mem::take(&mut self.tool_calls)
+            .into_values()
+            .map(|tool_call| DBMessage {
+                id: self.ids.next_id(),
                 thread_id: self.thread_id.clone(),
                 user_id: self.user_id.clone(),
                 role: MessageRole::Assistant,
@@ -210,18 +261,96 @@ impl<E: StreamEventExtractor> EventAccumulator<E> {
                 content: String::new(),
                 tool_call_id: Some(tool_call.tool_call_id),
                 tool_name: Some(tool_call.tool_name),
-                arguments,
+                tool_server: None,
+                verification: None,
+                // Parse arguments as JSON
+                arguments: serde_json::from_str(&tool_call.arguments).ok(),
                 reasoning_id: None,
-                created_at: chrono::Utc::now(),
-                duration_ms: Some(duration_ms),
+                created_at: self.clock.now(),
+                duration_ms: Some(tool_call.started_at.elapsed().as_millis() as u64),
+                agent: self.current_agent.clone(),
+                content_encoding: ContentEncoding::None,
+                content_sha256: None,
+                token_count: None,
+                summary: None,
+                iteration: None,
+                usage: None,
+                citations: Vec::new(),
             })
-        } else {
-            None
-        }
+            .collect()
     }
     
     /// Called at end of stream to finalize any remaining buffer
-    pub fn finalize(&mut self) -> Option<DBMessage> {
+    pub fn finalize(&mut self) -> Vec<DBMessage> {
         self.finalize_current_buffer()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tool call delta, or message text
+    enum Event {
+        ToolCall(u32, Option<&'static str>, Option<&'static str>, &'static str),
+        Message(&'static str),
+    }
+
+    impl StreamEventExtractor for Event {
+        fn is_reasoning(&self) -> bool {
+            false
+        }
+        fn is_message(&self) -> bool {
+            matches!(self, Self::Message(_))
+        }
+        fn is_tool_call(&self) -> bool {
+            matches!(self, Self::ToolCall(..))
+        }
+        fn reasoning_content(&self) -> Option<&str> {
+            None
+        }
+        fn message_content(&self) -> Option<&str> {
+            match self {
+                Self::Message(text) => Some(text),
+                Self::ToolCall(..) => None,
+            }
+        }
+        fn tool_call_info(&self) -> Option<(u32, Option<&str>, Option<&str>, Option<&str>)> {
+            match self {
+                Self::ToolCall(index, id, name, arguments) => Some((*index, *id, *name, Some(*arguments))),
+                Self::Message(_) => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_interleaved_tool_calls_finalize_in_index_order() {
+        let mut accumulator = EventAccumulator::new("thread-1".to_string(), "user-1".to_string());
+        let events = [
+            Event::ToolCall(1, Some("call_2"), Some("get_time"), r#"{"zone""#),
+            Event::ToolCall(0, Some("call_1"), Some("get_weather"), r#"{"city": "#),
+            Event::ToolCall(1, None, None, r#": "PST"}"#),
+            Event::ToolCall(0, None, None, r#""SF"}"#),
+        ];
+        for event in &events {
+            assert!(accumulator.push_and_check_transition(event).is_empty());
+        }
+
+        let saved = accumulator.push_and_check_transition(&Event::Message("Sunny, 9am."));
+        let calls: Vec<(Option<&str>, Option<&str>, Option<&serde_json::Value>)> = saved
+            .iter()
+            .map(|m| (m.tool_call_id.as_deref(), m.tool_name.as_deref(), m.arguments.as_ref()))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                (Some("call_1"), Some("get_weather"), Some(&serde_json::json!({ "city": "SF" }))),
+                (Some("call_2"), Some("get_time"), Some(&serde_json::json!({ "zone": "PST" }))),
+            ]
+        );
+
+        let answer = accumulator.finalize();
+        assert_eq!(answer.len(), 1);
+        assert_eq!(answer[0].content, "Sunny, 9am.");
+    }
+}
@@ -0,0 +1,371 @@
+//! Anonymized thread exports for building shareable datasets
+//!
+//! [`anonymize_thread`] rewrites a [`ThreadExport`] so it can leave the
+//! system: text matching the [`Redactor`] patterns is replaced with seeded
+//! pseudonyms, user ids are pseudonymized, blocklisted keys are dropped and
+//! results of sensitive tools are removed.
+//!
+//! Pseudonyms are a keyed hash of the original value, so the same email is
+//! the same placeholder everywhere in a thread, and two exports made with
+//! the same seed are identical.
+
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::fingerprint::content_sha256;
+use crate::models::{AttachmentRef, DBMessage, MessageType, Thread};
+use crate::trait_client::PersistenceClient;
+
+/// Content of a tool result removed because its tool is sensitive
+pub const SENSITIVE_RESULT_PLACEHOLDER: &str = "[sensitive tool result removed]";
+
+/// A thread with its messages, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadExport {
+    pub thread: Thread,
+    pub messages: Vec<DBMessage>,
+    /// Files uploaded to the thread (metadata only, not their content)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<AttachmentRef>,
+}
+
+impl ThreadExport {
+    /// Read a thread and all its messages (None if the thread doesn't exist)
+    pub async fn load(client: &dyn PersistenceClient, thread_id: &str) -> crate::Result<Option<Self>> {
+        let Some(thread) = client.get_thread(thread_id).await? else {
+            return Ok(None);
+        };
+        client.flush(thread_id).await?;
+        let messages = client.get_messages(thread_id).await?;
+        let attachments = client.list_attachments(thread_id).await?;
+        Ok(Some(Self { thread, messages, attachments }))
+    }
+}
+
+/// Patterns of personal data to find in free text
+///
+/// Each pattern has a kind (`email`, `phone`, ...) that names the
+/// placeholders its matches become, e.g. `<email_1f3a9c0e>`.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    patterns: Vec<(String, Regex)>,
+}
+
+impl Redactor {
+    /// A redactor without patterns
+    pub fn empty() -> Self {
+        Self { patterns: Vec::new() }
+    }
+
+    /// Add a pattern; matches are replaced in the order patterns were added
+    pub fn with_pattern(mut self, kind: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        self.patterns.push((kind.into(), Regex::new(pattern)?));
+        Ok(self)
+    }
+
+    /// Replace every match in `text` with the pseudonym `pseudonym` gives
+    pub fn replace(&self, text: &str, pseudonym: impl Fn(&str, &str) -> String) -> String {
+        let mut text = text.to_string();
+        for (kind, regex) in &self.patterns {
+            if regex.is_match(&text) {
+                text = regex
+                    .replace_all(&text, |captures: &regex::Captures| pseudonym(kind, &captures[0]))
+                    .into_owned();
+            }
+        }
+        text
+    }
+}
+
+impl Default for Redactor {
+    /// Emails, API keys, card numbers, IPv4 addresses and phone numbers
+    fn default() -> Self {
+        Self::empty()
+            .with_pattern("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+            .and_then(|r| r.with_pattern("api_key", r"\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}"))
+            .and_then(|r| r.with_pattern("card", r"\b(?:\d[ -]?){12,15}\d\b"))
+            .and_then(|r| r.with_pattern("ip", r"\b(?:\d{1,3}\.){3}\d{1,3}\b"))
+            .and_then(|r| r.with_pattern("phone", r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{2,4}\)|\b\d{2,4})[ .-]\d{3,4}[ .-]\d{3,4}\b"))
+            .expect("built-in patterns are valid")
+    }
+}
+
+/// What [`anonymize_thread`] removes and how it names replacements
+#[derive(Debug, Clone)]
+pub struct AnonymizerConfig {
+    /// Keys the pseudonyms; change it to make exports unlinkable
+    pub seed: u64,
+    pub redactor: Redactor,
+    /// Keys dropped from tool call arguments (at any depth) and from thread
+    /// metadata (`title`, `tags`, `model_override`, `developer_prompt`);
+    /// compared case-insensitively
+    pub blocked_keys: HashSet<String>,
+    /// Tools whose results are replaced by [`SENSITIVE_RESULT_PLACEHOLDER`]
+    pub sensitive_tools: HashSet<String>,
+}
+
+impl Default for AnonymizerConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            redactor: Redactor::default(),
+            blocked_keys: ["password", "secret", "token", "api_key", "authorization"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            sensitive_tools: HashSet::new(),
+        }
+    }
+}
+
+impl AnonymizerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    pub fn with_blocked_key(mut self, key: impl Into<String>) -> Self {
+        self.blocked_keys.insert(key.into().to_lowercase());
+        self
+    }
+
+    pub fn with_sensitive_tool(mut self, tool: impl Into<String>) -> Self {
+        self.sensitive_tools.insert(tool.into());
+        self
+    }
+
+    fn is_blocked(&self, key: &str) -> bool {
+        self.blocked_keys.iter().any(|blocked| blocked.eq_ignore_ascii_case(key))
+    }
+
+    /// Stable placeholder for `value`, e.g. `<email_1f3a9c0e>`
+    fn pseudonym(&self, kind: &str, value: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.seed.to_be_bytes());
+        hasher.update(kind.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        format!("<{}_{}>", kind, hex::encode(&hasher.finalize()[..4]))
+    }
+
+    fn redact(&self, text: &str) -> String {
+        self.redactor.replace(text, |kind, value| self.pseudonym(kind, value))
+    }
+
+    fn scrub_value(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => Value::String(self.redact(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.scrub_value(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(key, _)| !self.is_blocked(key))
+                    .map(|(key, v)| (key, self.scrub_value(v)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+/// Anonymize a thread export
+///
+/// Ids of threads and messages are kept so exports can be compared run to
+/// run; user ids and conversation keys are pseudonymized. The cached
+/// history index is dropped since its lines quote message text.
+/// `content_sha256` is recomputed for rows that had one.
+pub fn anonymize_thread(export: ThreadExport, config: &AnonymizerConfig) -> ThreadExport {
+    let ThreadExport { mut thread, messages, mut attachments } = export;
+
+    thread.user_id = config.pseudonym("user", &thread.user_id);
+    thread.conversation_key = thread.conversation_key.map(|key| config.pseudonym("conversation", &key));
+    thread.history_index = None;
+    let metadata = &mut thread.metadata;
+    metadata.title = metadata.title.take().filter(|_| !config.is_blocked("title")).map(|t| config.redact(&t));
+    metadata.tags = if config.is_blocked("tags") {
+        Vec::new()
+    } else {
+        metadata.tags.iter().map(|tag| config.redact(tag)).collect()
+    };
+    if config.is_blocked("model_override") {
+        metadata.model_override = None;
+    }
+    metadata.developer_prompt = metadata
+        .developer_prompt
+        .take()
+        .filter(|_| !config.is_blocked("developer_prompt"))
+        .map(|prompt| config.redact(&prompt));
+    if let Some(summary) = thread.summary.as_mut() {
+        summary.text = config.redact(&summary.text);
+    }
+
+    let sensitive_calls: HashSet<String> = messages
+        .iter()
+        .filter(|m| m.message_type == MessageType::ToolCall)
+        .filter(|m| m.tool_name.as_ref().is_some_and(|name| config.sensitive_tools.contains(name)))
+        .filter_map(|m| m.tool_call_id.clone())
+        .collect();
+
+    let messages = messages
+        .into_iter()
+        .map(|mut message| {
+            message.user_id = config.pseudonym("user", &message.user_id);
+            let sensitive = message.message_type == MessageType::ToolResult
+                && message.tool_call_id.as_ref().is_some_and(|id| sensitive_calls.contains(id));
+            message.content = if sensitive {
+                SENSITIVE_RESULT_PLACEHOLDER.to_string()
+            } else {
+                config.redact(&message.content)
+            };
+            message.arguments = message.arguments.take().map(|arguments| config.scrub_value(arguments));
+            message.summary = message.summary.take().map(|summary| config.redact(&summary));
+            if message.content_sha256.is_some() {
+                message.content_sha256 = Some(content_sha256(&message.model_text()));
+            }
+            message
+        })
+        .collect();
+
+    for attachment in &mut attachments {
+        attachment.name = config.redact(&attachment.name);
+    }
+
+    ThreadExport { thread, messages, attachments }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MessageRole, ThreadMetadata};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn message(role: MessageRole, message_type: MessageType, content: &str) -> DBMessage {
+        DBMessage {
+            thread_id: "t-1".to_string(),
+            user_id: "alice-42".to_string(),
+            role,
+            message_type,
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn export() -> ThreadExport {
+        let now = Utc::now();
+        let mut call = message(MessageRole::Assistant, MessageType::ToolCall, "");
+        call.tool_call_id = Some("c1".to_string());
+        call.tool_name = Some("lookup_account".to_string());
+        call.arguments = Some(json!({ "email": "alice@example.com", "api_key": "abc", "nested": { "Token": "x" } }));
+        call.content_sha256 = Some(String::new());
+        let mut result = message(MessageRole::Assistant, MessageType::ToolResult, "Card 4111 1111 1111 1111 on file");
+        result.tool_call_id = Some("c1".to_string());
+
+        ThreadExport {
+            thread: Thread {
+                id: "t-1".to_string(),
+                user_id: "alice-42".to_string(),
+                created_at: now,
+                updated_at: now,
+                metadata: ThreadMetadata {
+                    title: Some("Billing for alice@example.com".to_string()),
+                    tags: vec!["vip".to_string()],
+                    model_override: None,
+                    tool_profile: None,
+                    developer_prompt: Some("Greet alice@example.com by name".to_string()),
+                },
+                last_summary_update: now,
+                summary: None,
+                history_index: None,
+                archived_at: None,
+                conversation_key: None,
+            },
+            messages: vec![
+                message(MessageRole::User, MessageType::Message, "I'm alice@example.com, call +1 415 555 0100"),
+                call,
+                result,
+                message(MessageRole::Assistant, MessageType::Message, "Emailed alice@example.com and bob@example.org"),
+            ],
+            attachments: vec![AttachmentRef {
+                id: "a-1".to_string(),
+                thread_id: "t-1".to_string(),
+                name: "alice@example.com invoice.pdf".to_string(),
+                mime_type: "application/pdf".to_string(),
+                size: 1024,
+                created_at: now,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_pseudonyms_are_consistent_within_a_thread() {
+        let config = AnonymizerConfig::new().with_seed(7);
+        let anonymized = anonymize_thread(export(), &config);
+        let alice = config.pseudonym("email", "alice@example.com");
+
+        let texts: Vec<String> = anonymized.messages.iter().map(|m| m.model_text().into_owned()).collect();
+        assert!(texts.iter().all(|text| !text.contains("alice@example.com")), "{:?}", texts);
+        assert!(texts[0].starts_with(&format!("I'm {}, call <phone_", alice)), "{}", texts[0]);
+        assert!(texts[1].contains(&alice));
+        assert!(texts[3].contains(&alice));
+        assert!(texts[3].contains(&config.pseudonym("email", "bob@example.org")));
+        assert_ne!(alice, config.pseudonym("email", "bob@example.org"));
+        assert_eq!(anonymized.thread.metadata.title, Some(format!("Billing for {}", alice)));
+        assert_eq!(anonymized.thread.metadata.developer_prompt, Some(format!("Greet {} by name", alice)));
+        assert_eq!(anonymized.attachments[0].name, format!("{} invoice.pdf", alice));
+
+        let user = config.pseudonym("user", "alice-42");
+        assert_eq!(anonymized.thread.user_id, user);
+        assert!(anonymized.messages.iter().all(|m| m.user_id == user));
+    }
+
+    #[test]
+    fn test_same_seed_gives_identical_exports() {
+        let config = AnonymizerConfig::new().with_seed(7);
+        let first = serde_json::to_value(anonymize_thread(export(), &config).messages).unwrap();
+        let mut second = anonymize_thread(export(), &config).messages;
+        // Row ids and timestamps come from the fixture, not the anonymizer
+        for (row, original) in second.iter_mut().zip(first.as_array().unwrap()) {
+            row.id = original["id"].as_str().unwrap().to_string();
+            row.created_at = serde_json::from_value(original["created_at"].clone()).unwrap();
+        }
+        assert_eq!(serde_json::to_value(second).unwrap(), first);
+
+        let other = AnonymizerConfig::new().with_seed(8);
+        assert_ne!(config.pseudonym("email", "a@b.co"), other.pseudonym("email", "a@b.co"));
+    }
+
+    #[test]
+    fn test_blocked_keys_and_sensitive_results_removed() {
+        let config = AnonymizerConfig::new().with_blocked_key("tags").with_sensitive_tool("lookup_account");
+        let anonymized = anonymize_thread(export(), &config);
+
+        let arguments = anonymized.messages[1].arguments.clone().unwrap();
+        assert_eq!(arguments, json!({ "email": config.pseudonym("email", "alice@example.com"), "nested": {} }));
+        assert_eq!(
+            anonymized.messages[1].content_sha256,
+            Some(content_sha256(&arguments.to_string()))
+        );
+        assert_eq!(anonymized.messages[2].content, SENSITIVE_RESULT_PLACEHOLDER);
+        assert!(anonymized.thread.metadata.tags.is_empty());
+
+        // Not sensitive: the result is kept, with the card number redacted
+        let kept = anonymize_thread(export(), &AnonymizerConfig::new());
+        assert!(kept.messages[2].content.starts_with("Card <card_"), "{}", kept.messages[2].content);
+        assert!(kept.messages[2].content.ends_with(" on file"));
+        // Dates are not mistaken for phone numbers
+        assert_eq!(config.redact("due 2025-01-08"), "due 2025-01-08");
+    }
+}
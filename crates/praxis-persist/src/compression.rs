@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PersistError, Result};
+
+/// When backends compress message content at rest
+///
+/// Only content larger than `threshold_bytes` is compressed, and only if the
+/// compressed form is actually smaller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    pub threshold_bytes: usize,
+    /// zstd level (1-22)
+    pub level: i32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_bytes: 16 * 1024,
+            level: 3,
+        }
+    }
+}
+
+impl CompressionConfig {
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// zstd-compress `content` if it crosses the threshold and shrinks
+    pub fn compress(&self, content: &str) -> Option<Vec<u8>> {
+        if !self.enabled || content.len() <= self.threshold_bytes {
+            return None;
+        }
+        zstd::bulk::compress(content.as_bytes(), self.level)
+            .ok()
+            .filter(|compressed| compressed.len() < content.len())
+    }
+}
+
+pub fn decompress(bytes: &[u8]) -> Result<String> {
+    let raw = zstd::stream::decode_all(bytes)
+        .map_err(|e| PersistError::Internal(format!("zstd decompression failed: {}", e)))?;
+    String::from_utf8(raw)
+        .map_err(|e| PersistError::Internal(format!("decompressed content is not UTF-8: {}", e)))
+}
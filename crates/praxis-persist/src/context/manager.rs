@@ -118,6 +118,7 @@ impl ContextManager {
             LLMMessage::System {
                 content: Content::text(system_prompt),
                 name: None,
+                cache_hint: None,
             }
         ];
         
@@ -127,6 +128,7 @@ impl ContextManager {
                 MessageRole::User => LLMMessage::Human {
                     content: Content::text(&msg.content),
                     name: None,
+                    cache_hint: None,
                 },
                 MessageRole::Assistant => LLMMessage::AI {
                     content: Some(Content::text(&msg.content)),
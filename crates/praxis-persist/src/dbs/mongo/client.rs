@@ -1,25 +1,78 @@
 #[cfg(feature = "mongodb")]
 use mongodb::{Client, bson::oid::ObjectId};
 #[cfg(feature = "mongodb")]
+use mongodb::options::{DatabaseOptions, ReadConcern, ReadPreference, SelectionCriteria, WriteConcern};
+#[cfg(feature = "mongodb")]
 use async_trait::async_trait;
 #[cfg(feature = "mongodb")]
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 
 #[cfg(feature = "mongodb")]
 use crate::trait_client::PersistenceClient;
 #[cfg(feature = "mongodb")]
-use crate::models::{DBMessage, Thread, ThreadMetadata, ThreadSummary};
+use crate::models::{AttachmentRef, DBMessage, HistoryIndex, SummaryTrigger, Thread, ThreadMetadata, ThreadSummary};
+#[cfg(feature = "mongodb")]
+use crate::compression::CompressionConfig;
+#[cfg(feature = "mongodb")]
+use crate::fingerprint::ContentTokenizer;
+#[cfg(feature = "mongodb")]
+use crate::outbox::{OutboxEntry, OutboxStore};
+#[cfg(feature = "mongodb")]
+use crate::maintenance::HygieneStore;
+#[cfg(feature = "mongodb")]
+use crate::dbs::mongo::models::{to_bson_date, MongoMessage, MongoOutboxEntry, MongoThread};
+#[cfg(feature = "mongodb")]
+use crate::invariants::check_message_invariants;
+#[cfg(feature = "mongodb")]
+use crate::migrations::{MigrationReport, SCHEMA_VERSION};
 #[cfg(feature = "mongodb")]
-use crate::dbs::mongo::models::MongoMessage;
+use crate::dbs::mongo::migrations::mongo_migrations;
 #[cfg(feature = "mongodb")]
-use crate::dbs::mongo::repositories::{MongoMessageRepository, MongoThreadRepository};
+use crate::dbs::mongo::repositories::{
+    MongoAttachmentRepository, MongoLeaseRepository, MongoMessageRepository, MongoOutboxRepository, MongoThreadRepository,
+};
 #[cfg(feature = "mongodb")]
 use crate::error::{Result, PersistError};
 
+/// Consistency of reads relative to this client's own writes
+#[cfg(feature = "mongodb")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadConsistency {
+    /// Driver defaults from the connection string; with a secondary read
+    /// preference a message just saved may not be returned yet
+    #[default]
+    Default,
+    /// Read from the primary with majority read and write concern, so an
+    /// acknowledged save is always visible to the next read
+    ReadYourWrites,
+}
+
+#[cfg(feature = "mongodb")]
+impl ReadConsistency {
+    fn database_options(self) -> DatabaseOptions {
+        match self {
+            Self::Default => DatabaseOptions::default(),
+            Self::ReadYourWrites => DatabaseOptions::builder()
+                .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+                .read_concern(ReadConcern::majority())
+                .write_concern(WriteConcern::majority())
+                .build(),
+        }
+    }
+}
+
 #[cfg(feature = "mongodb")]
 pub struct MongoPersistenceClient {
+    client: Client,
+    database: String,
     message_repo: MongoMessageRepository,
     thread_repo: MongoThreadRepository,
+    outbox_repo: MongoOutboxRepository,
+    attachment_repo: MongoAttachmentRepository,
+    lease_repo: MongoLeaseRepository,
+    /// Set once the `conversation_key` unique index is known to exist
+    conversation_key_index: tokio::sync::OnceCell<()>,
 }
 
 #[cfg(feature = "mongodb")]
@@ -32,12 +85,73 @@ impl MongoPersistenceClient {
         
         let message_repo = MongoMessageRepository::new(&client, database);
         let thread_repo = MongoThreadRepository::new(&client, database);
+        let outbox_repo = MongoOutboxRepository::new(&client, database);
+        let attachment_repo = MongoAttachmentRepository::new(&client, database);
+        let lease_repo = MongoLeaseRepository::new(&client, database);
         
         Ok(Self {
+            client,
+            database: database.to_string(),
             message_repo,
             thread_repo,
+            outbox_repo,
+            attachment_repo,
+            lease_repo,
+            conversation_key_index: tokio::sync::OnceCell::new(),
         })
     }
+    
+    /// Read consistency of thread and message queries (default: driver defaults)
+    pub fn with_read_consistency(mut self, consistency: ReadConsistency) -> Self {
+        let database = self
+            .client
+            .database_with_options(&self.database, consistency.database_options());
+        self.message_repo = self.message_repo.with_database(&database);
+        self.thread_repo = self.thread_repo.with_database(&database);
+        self.outbox_repo = self.outbox_repo.with_database(&database);
+        self.attachment_repo = self.attachment_repo.with_database(&database);
+        self.lease_repo = self.lease_repo.with_database(&database);
+        self
+    }
+    
+    /// Compression of large message content (default: zstd above 16 KiB)
+    pub fn with_compression(mut self, compression: CompressionConfig) -> Self {
+        self.message_repo = self.message_repo.with_compression(compression);
+        self
+    }
+    
+    /// Backfill hashes and token counts of a thread's messages saved without
+    /// them, returning how many messages were updated
+    pub async fn reindex_thread(
+        &self,
+        thread_id: &str,
+        tokenizer: &dyn ContentTokenizer,
+        model: &str,
+    ) -> Result<usize> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        let mut updated = 0;
+        for message in self.message_repo.get_messages(object_id).await? {
+            if message.token_count.is_some() {
+                continue;
+            }
+            let id = message.id;
+            let mut message: DBMessage = message.into();
+            message.fingerprint(Some(tokenizer), model);
+            if let (Some(sha256), Some(tokens)) = (&message.content_sha256, message.token_count) {
+                self.message_repo.set_stats(id, sha256, tokens).await?;
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+    
+    /// Threads that still have messages without a token count
+    pub async fn threads_needing_reindex(&self) -> Result<Vec<String>> {
+        let ids = self.message_repo.threads_missing_token_counts().await?;
+        Ok(ids.into_iter().map(|id| id.to_hex()).collect())
+    }
 }
 
 #[cfg(feature = "mongodb")]
@@ -49,6 +163,11 @@ impl PersistenceClient for MongoPersistenceClient {
         Ok(())
     }
     
+    async fn save_messages(&self, messages: Vec<DBMessage>) -> Result<()> {
+        let mongo_messages = messages.into_iter().map(MongoMessage::from).collect();
+        self.message_repo.save_messages(mongo_messages, None).await
+    }
+    
     async fn get_messages(&self, thread_id: &str) -> Result<Vec<DBMessage>> {
         let object_id = ObjectId::parse_str(thread_id)
             .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
@@ -71,11 +190,86 @@ impl PersistenceClient for MongoPersistenceClient {
         Ok(db_messages)
     }
     
+    async fn thread_token_total(&self, thread_id: &str) -> Result<u64> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        self.message_repo.token_total(object_id).await
+    }
+    
+    async fn count_messages(&self, thread_id: &str) -> Result<u64> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        self.message_repo.count(object_id).await
+    }
+    
     async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Result<Thread> {
         let mongo_thread = self.thread_repo.create_thread(user_id.to_string(), metadata).await?;
         Ok(mongo_thread.into())
     }
     
+    async fn create_thread_with_messages(
+        &self,
+        user_id: &str,
+        metadata: ThreadMetadata,
+        messages: Vec<DBMessage>,
+    ) -> Result<Thread> {
+        check_message_invariants(&messages)?;
+        
+        // Imported history may predate the thread; it must still fall after
+        // `last_summary_update` to be part of the context window
+        let now = Utc::now();
+        let created_at = messages.first().map_or(now, |m| m.created_at.min(now));
+        let thread = MongoThread {
+            id: ObjectId::new(),
+            user_id: user_id.to_string(),
+            created_at,
+            updated_at: now,
+            metadata,
+            last_summary_update: created_at - Duration::milliseconds(1),
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+            schema_version: SCHEMA_VERSION,
+        };
+        let thread_id = thread.id.to_hex();
+        let messages: Vec<MongoMessage> = messages
+            .into_iter()
+            .map(|mut message| {
+                message.thread_id = thread_id.clone();
+                message.user_id = user_id.to_string();
+                message.into()
+            })
+            .collect();
+        
+        let mut session = self.client.start_session().await?;
+        if session.start_transaction().await.is_ok() {
+            let written = async {
+                self.thread_repo.insert_thread(&thread, Some(&mut session)).await?;
+                self.message_repo.save_messages(messages, Some(&mut session)).await
+            }
+            .await;
+            match written {
+                Ok(()) => session.commit_transaction().await?,
+                Err(e) => {
+                    let _ = session.abort_transaction().await;
+                    return Err(e);
+                }
+            }
+        } else {
+            // Standalone server: no transactions, so undo the thread by hand
+            self.thread_repo.insert_thread(&thread, None).await?;
+            if let Err(e) = self.message_repo.save_messages(messages, None).await {
+                let _ = self.thread_repo.delete_thread(thread.id, user_id).await;
+                return Err(e);
+            }
+        }
+        
+        Ok(thread.into())
+    }
+    
     async fn get_thread(&self, thread_id: &str) -> Result<Option<Thread>> {
         let object_id = ObjectId::parse_str(thread_id)
             .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
@@ -84,11 +278,61 @@ impl PersistenceClient for MongoPersistenceClient {
         Ok(mongo_thread.map(|t| t.into()))
     }
     
+    async fn resolve_thread(&self, conversation_key: &str) -> Result<Option<Thread>> {
+        let mongo_thread = self.thread_repo.get_by_conversation_key(conversation_key).await?;
+        Ok(mongo_thread.map(|t| t.into()))
+    }
+    
+    async fn get_or_create_thread_for_conversation(&self, conversation_key: &str, user_id: &str) -> Result<Thread> {
+        // The unique index is what makes racing creators agree on one thread
+        self.conversation_key_index
+            .get_or_try_init(|| self.thread_repo.ensure_conversation_key_index())
+            .await?;
+        
+        let owned_by_user = |thread: MongoThread| {
+            if thread.user_id == user_id {
+                Ok(Thread::from(thread))
+            } else {
+                Err(PersistError::ConversationKeyConflict(conversation_key.to_string()))
+            }
+        };
+        if let Some(thread) = self.thread_repo.get_by_conversation_key(conversation_key).await? {
+            return owned_by_user(thread);
+        }
+        
+        let now = Utc::now();
+        let thread = MongoThread {
+            id: ObjectId::new(),
+            user_id: user_id.to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata: ThreadMetadata::default(),
+            last_summary_update: now,
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: Some(conversation_key.to_string()),
+            schema_version: SCHEMA_VERSION,
+        };
+        if self.thread_repo.insert_keyed_thread(&thread).await? {
+            return Ok(thread.into());
+        }
+        // Another caller created it between our lookup and insert
+        match self.thread_repo.get_by_conversation_key(conversation_key).await? {
+            Some(thread) => owned_by_user(thread),
+            None => Err(PersistError::Internal(format!(
+                "conversation key {} collided but no thread has it",
+                conversation_key
+            ))),
+        }
+    }
+    
     async fn save_thread_summary(
         &self,
         thread_id: &str,
         summary: String,
         generated_at: DateTime<Utc>,
+        trigger: SummaryTrigger,
     ) -> Result<()> {
         let object_id = ObjectId::parse_str(thread_id)
             .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
@@ -98,17 +342,36 @@ impl PersistenceClient for MongoPersistenceClient {
             generated_at,
             total_tokens_before_summary: 0, // TODO: calculate this properly
             messages_count: 0, // TODO: calculate this properly
+            trigger,
         };
         
         self.thread_repo.update_summary(object_id, thread_summary).await?;
         Ok(())
     }
     
+    async fn update_thread_metadata(&self, thread_id: &str, metadata: ThreadMetadata) -> Result<()> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        self.thread_repo.update_metadata(object_id, metadata).await?;
+        Ok(())
+    }
+    
+    async fn save_thread_index(&self, thread_id: &str, index: HistoryIndex) -> Result<()> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        self.thread_repo.update_history_index(object_id, index).await?;
+        Ok(())
+    }
+    
     async fn delete_thread(&self, thread_id: &str, user_id: &str) -> Result<()> {
         let object_id = ObjectId::parse_str(thread_id)
             .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
         
-        self.thread_repo.delete_thread(object_id, user_id).await?;
+        if self.thread_repo.delete_thread(object_id, user_id).await? {
+            self.attachment_repo.delete_thread_attachments(thread_id).await?;
+        }
         Ok(())
     }
     
@@ -122,5 +385,135 @@ impl PersistenceClient for MongoPersistenceClient {
         let threads = mongo_threads.into_iter().map(|t| t.into()).collect();
         Ok(threads)
     }
+    
+    async fn save_attachment(&self, thread_id: &str, name: &str, mime_type: &str, bytes: Vec<u8>) -> Result<AttachmentRef> {
+        if self.get_thread(thread_id).await?.is_none() {
+            return Err(PersistError::ThreadNotFound(thread_id.to_string()));
+        }
+        self.attachment_repo.save(thread_id, name, mime_type, &bytes).await
+    }
+    
+    async fn list_attachments(&self, thread_id: &str) -> Result<Vec<AttachmentRef>> {
+        self.attachment_repo.list(thread_id).await
+    }
+    
+    async fn load_attachment(&self, thread_id: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        self.attachment_repo.load(thread_id, name).await
+    }
+    
+    async fn migrate(&self, dry_run: bool) -> Result<MigrationReport> {
+        let database = self.client.database(&self.database);
+        mongo_migrations().run(&database, dry_run).await
+    }
 }
 
+
+#[cfg(feature = "mongodb")]
+#[async_trait]
+impl OutboxStore for MongoPersistenceClient {
+    async fn save_messages_with_outbox(&self, messages: Vec<DBMessage>, entries: Vec<OutboxEntry>) -> Result<()> {
+        let now = Utc::now();
+        let messages: Vec<MongoMessage> = messages.into_iter().map(MongoMessage::from).collect();
+        let entries: Vec<MongoOutboxEntry> = entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.created_at = now;
+                entry.next_attempt_at = now;
+                entry.into()
+            })
+            .collect();
+        
+        let mut session = self.client.start_session().await?;
+        if session.start_transaction().await.is_ok() {
+            let written = async {
+                self.message_repo.save_messages(messages, Some(&mut session)).await?;
+                self.outbox_repo.insert_entries(entries, Some(&mut session)).await
+            }
+            .await;
+            match written {
+                Ok(()) => session.commit_transaction().await?,
+                Err(e) => {
+                    let _ = session.abort_transaction().await;
+                    return Err(e);
+                }
+            }
+        } else {
+            // Standalone server: no transactions. Messages go first so an
+            // intent never announces a write that failed; a crash between
+            // the two inserts loses the intents.
+            self.message_repo.save_messages(messages, None).await?;
+            self.outbox_repo.insert_entries(entries, None).await?;
+        }
+        Ok(())
+    }
+    
+    async fn lease_outbox(&self, owner: &str, limit: usize, lease: std::time::Duration) -> Result<Vec<OutboxEntry>> {
+        let now = Utc::now();
+        let expires_at = now + Duration::from_std(lease).map_err(|e| PersistError::Other(e.to_string()))?;
+        let mut leased = Vec::new();
+        while leased.len() < limit {
+            match self.outbox_repo.lease_next(owner, now, expires_at).await? {
+                Some(entry) => leased.push(entry.into()),
+                None => break,
+            }
+        }
+        Ok(leased)
+    }
+    
+    async fn complete_outbox(&self, id: &str, owner: &str) -> Result<bool> {
+        let update = mongodb::bson::doc! {
+            "$set": { "status": "delivered", "delivered_at": to_bson_date(Utc::now()) },
+            "$unset": { "lease_owner": "", "lease_expires_at": "" },
+        };
+        self.outbox_repo.update_leased(id, owner, update).await
+    }
+    
+    async fn fail_outbox(&self, id: &str, owner: &str, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<bool> {
+        let set = match retry_at {
+            Some(at) => mongodb::bson::doc! { "last_error": error, "next_attempt_at": to_bson_date(at) },
+            None => mongodb::bson::doc! { "last_error": error, "status": "failed" },
+        };
+        let update = mongodb::bson::doc! {
+            "$set": set,
+            "$unset": { "lease_owner": "", "lease_expires_at": "" },
+        };
+        self.outbox_repo.update_leased(id, owner, update).await
+    }
+    
+    async fn get_outbox_entry(&self, id: &str) -> Result<Option<OutboxEntry>> {
+        Ok(self.outbox_repo.get_entry(id).await?.map(OutboxEntry::from))
+    }
+}
+
+#[cfg(feature = "mongodb")]
+#[async_trait]
+impl HygieneStore for MongoPersistenceClient {
+    async fn idle_threads(&self, idle_since: DateTime<Utc>, limit: usize) -> Result<Vec<Thread>> {
+        let threads = self.thread_repo.idle_threads(idle_since, limit).await?;
+        Ok(threads.into_iter().map(|t| t.into()).collect())
+    }
+    
+    async fn compress_tool_results(&self, thread_id: &str, compression: &CompressionConfig) -> Result<(usize, u64)> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        self.message_repo.compress_tool_results(object_id, compression).await
+    }
+    
+    async fn archive_thread(&self, thread_id: &str, archived_at: DateTime<Utc>) -> Result<()> {
+        let object_id = ObjectId::parse_str(thread_id)
+            .map_err(|e| PersistError::InvalidObjectId(e.to_string()))?;
+        
+        self.thread_repo.set_archived(object_id, archived_at).await
+    }
+    
+    async fn acquire_lease(&self, name: &str, owner: &str, ttl: std::time::Duration) -> Result<bool> {
+        let now = Utc::now();
+        let expires_at = now + Duration::from_std(ttl).map_err(|e| PersistError::Other(e.to_string()))?;
+        self.lease_repo.acquire(name, owner, now, expires_at).await
+    }
+    
+    async fn release_lease(&self, name: &str, owner: &str) -> Result<()> {
+        self.lease_repo.release(name, owner).await
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(feature = "mongodb")]
+use async_trait::async_trait;
+#[cfg(feature = "mongodb")]
+use futures::TryStreamExt;
+#[cfg(feature = "mongodb")]
+use mongodb::{bson::{doc, Bson, Document}, Collection, Database};
+
+#[cfg(feature = "mongodb")]
+use crate::error::Result;
+#[cfg(feature = "mongodb")]
+use crate::migrations::{Migration, MigrationRegistry};
+
+/// Documents updated per `update_many`
+#[cfg(feature = "mongodb")]
+const BATCH_SIZE: i64 = 1000;
+
+/// Migrations of the `threads` and `messages` collections, up to `SCHEMA_VERSION`
+#[cfg(feature = "mongodb")]
+pub fn mongo_migrations() -> MigrationRegistry<Database> {
+    MigrationRegistry::new().with_migration(SetFields {
+        version: 1,
+        description: "record the schema version of documents written before versioning",
+        changes: vec![("threads", doc! {}), ("messages", doc! {})],
+    })
+}
+
+/// Migration setting fields on every document of some collections that is
+/// below `version`, together with the version itself
+#[cfg(feature = "mongodb")]
+struct SetFields {
+    version: u32,
+    description: &'static str,
+    /// Collection and the fields set on its documents
+    changes: Vec<(&'static str, Document)>,
+}
+
+/// Documents below `version`, including those without a `schema_version`
+#[cfg(feature = "mongodb")]
+fn below_version(version: u32) -> Document {
+    doc! {
+        "$or": [
+            { "schema_version": { "$lt": version as i64 } },
+            { "schema_version": { "$exists": false } },
+        ]
+    }
+}
+
+#[cfg(feature = "mongodb")]
+#[async_trait]
+impl Migration<Database> for SetFields {
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    async fn pending(&self, database: &Database) -> Result<u64> {
+        let mut pending = 0;
+        for (name, _) in &self.changes {
+            let collection: Collection<Document> = database.collection(name);
+            pending += collection.count_documents(below_version(self.version)).await?;
+        }
+        Ok(pending)
+    }
+
+    async fn apply(&self, database: &Database) -> Result<u64> {
+        let mut migrated = 0;
+        for (name, fields) in &self.changes {
+            let collection: Collection<Document> = database.collection(name);
+            let mut set = fields.clone();
+            set.insert("schema_version", self.version as i64);
+            migrated += update_in_batches(&collection, self.version, doc! { "$set": set }).await?;
+        }
+        Ok(migrated)
+    }
+}
+
+/// Apply `update` to the documents of `collection` below `version`,
+/// `BATCH_SIZE` at a time, returning how many were modified
+///
+/// `update` must move a document to `version`, or the same batch would be
+/// picked again; a batch that modifies nothing ends the loop.
+#[cfg(feature = "mongodb")]
+async fn update_in_batches(collection: &Collection<Document>, version: u32, update: Document) -> Result<u64> {
+    let mut modified = 0;
+    loop {
+        let ids: Vec<Bson> = collection
+            .find(below_version(version))
+            .projection(doc! { "_id": 1 })
+            .limit(BATCH_SIZE)
+            .await?
+            .try_collect::<Vec<Document>>()
+            .await?
+            .into_iter()
+            .filter_map(|document| document.get("_id").cloned())
+            .collect();
+        if ids.is_empty() {
+            return Ok(modified);
+        }
+
+        let result = collection
+            .update_many(doc! { "_id": { "$in": ids } }, update.clone())
+            .await?;
+        if result.modified_count == 0 {
+            return Ok(modified);
+        }
+        modified += result.modified_count;
+        tracing::info!("{}: {} documents migrated to schema v{}", collection.name(), modified, version);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "mongodb")]
+mod tests {
+    use super::*;
+    use crate::migrations::SCHEMA_VERSION;
+
+    #[test]
+    fn test_migrations_reach_the_current_schema_version() {
+        assert_eq!(mongo_migrations().latest_version(), SCHEMA_VERSION);
+    }
+}
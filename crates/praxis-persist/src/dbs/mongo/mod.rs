@@ -1,6 +1,6 @@
 pub mod models;
 pub mod repositories;
 pub mod client;
+pub mod migrations;
 
-pub use client::MongoPersistenceClient;
-
+pub use client::{MongoPersistenceClient, ReadConsistency};
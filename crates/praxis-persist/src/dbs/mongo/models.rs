@@ -3,7 +3,18 @@ use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
-use crate::{DBMessage, MessageRole, MessageType, Thread as DBThread, ThreadMetadata, ThreadSummary};
+#[cfg(feature = "mongodb")]
+use mongodb::bson::{spec::BinarySubtype, Binary};
+#[cfg(feature = "mongodb")]
+use praxis_llm::TokenUsage;
+#[cfg(feature = "mongodb")]
+use crate::{Citation, ToolVerification};
+
+use crate::compression::{decompress, CompressionConfig};
+use crate::error::{PersistError, Result};
+use crate::{ContentEncoding, DBMessage, HistoryIndex, MessageRole, MessageType, Thread as DBThread, ThreadMetadata, ThreadSummary};
+use crate::outbox::{OutboxEntry, OutboxStatus};
+use crate::migrations::SCHEMA_VERSION;
 
 /// MongoDB-specific Message model (uses ObjectId)
 #[cfg(feature = "mongodb")]
@@ -21,6 +32,10 @@ pub struct MongoMessage {
     pub tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_server: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ToolVerification>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arguments: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -28,6 +43,59 @@ pub struct MongoMessage {
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// `Zstd`: `content` is empty and the text is in `compressed_content`
+    #[serde(default)]
+    pub content_encoding: ContentEncoding,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compressed_content: Option<Binary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iteration: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
+    /// `SCHEMA_VERSION` of the build that wrote or last migrated the
+    /// document (0: written before versioning)
+    #[serde(default)]
+    pub schema_version: u32,
+}
+
+#[cfg(feature = "mongodb")]
+impl MongoMessage {
+    /// Move large content into `compressed_content` (no-op below the threshold)
+    pub fn compress(mut self, config: &CompressionConfig) -> Self {
+        if self.content_encoding != ContentEncoding::None {
+            return self;
+        }
+        if let Some(bytes) = config.compress(&self.content) {
+            self.content = String::new();
+            self.content_encoding = ContentEncoding::Zstd;
+            self.compressed_content = Some(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes,
+            });
+        }
+        self
+    }
+
+    /// Restore plain-text `content`; `content_encoding` keeps the stored encoding
+    pub fn decompress(mut self) -> Result<Self> {
+        if self.content_encoding == ContentEncoding::Zstd {
+            let blob = self.compressed_content.take().ok_or_else(|| {
+                PersistError::Internal(format!("message {} is zstd-encoded but has no compressed content", self.id))
+            })?;
+            self.content = decompress(&blob.bytes)?;
+        }
+        Ok(self)
+    }
 }
 
 /// MongoDB-specific Thread model (uses ObjectId)
@@ -43,10 +111,103 @@ pub struct MongoThread {
     pub last_summary_update: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<ThreadSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_index: Option<HistoryIndex>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Unique when present (partial index `conversation_key_unique`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_key: Option<String>,
+    /// See `MongoMessage::schema_version`
+    #[serde(default)]
+    pub schema_version: u32,
+}
+
+/// MongoDB-specific outbox entry
+///
+/// Times are BSON dates, not chrono strings, so lease queries can compare
+/// them on the server.
+#[cfg(feature = "mongodb")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MongoOutboxEntry {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: OutboxStatus,
+    pub attempts: u32,
+    pub created_at: bson::DateTime,
+    pub next_attempt_at: bson::DateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_expires_at: Option<bson::DateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<bson::DateTime>,
+}
+
+/// Named lease shared by replicas (e.g. the hygiene run lease)
+#[cfg(feature = "mongodb")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MongoLease {
+    #[serde(rename = "_id")]
+    pub name: String,
+    pub owner: String,
+    pub expires_at: bson::DateTime,
 }
 
 // Conversions between database-agnostic and MongoDB-specific models
 
+#[cfg(feature = "mongodb")]
+pub(crate) fn to_bson_date(time: DateTime<Utc>) -> bson::DateTime {
+    bson::DateTime::from_millis(time.timestamp_millis())
+}
+
+#[cfg(feature = "mongodb")]
+pub(crate) fn from_bson_date(time: bson::DateTime) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(time.timestamp_millis()).unwrap_or_default()
+}
+
+#[cfg(feature = "mongodb")]
+impl From<OutboxEntry> for MongoOutboxEntry {
+    fn from(entry: OutboxEntry) -> Self {
+        Self {
+            id: entry.id,
+            kind: entry.kind,
+            payload: entry.payload,
+            status: entry.status,
+            attempts: entry.attempts,
+            created_at: to_bson_date(entry.created_at),
+            next_attempt_at: to_bson_date(entry.next_attempt_at),
+            lease_owner: entry.lease_owner,
+            lease_expires_at: entry.lease_expires_at.map(to_bson_date),
+            last_error: entry.last_error,
+            delivered_at: entry.delivered_at.map(to_bson_date),
+        }
+    }
+}
+
+#[cfg(feature = "mongodb")]
+impl From<MongoOutboxEntry> for OutboxEntry {
+    fn from(entry: MongoOutboxEntry) -> Self {
+        Self {
+            id: entry.id,
+            kind: entry.kind,
+            payload: entry.payload,
+            status: entry.status,
+            attempts: entry.attempts,
+            created_at: from_bson_date(entry.created_at),
+            next_attempt_at: from_bson_date(entry.next_attempt_at),
+            lease_owner: entry.lease_owner,
+            lease_expires_at: entry.lease_expires_at.map(from_bson_date),
+            last_error: entry.last_error,
+            delivered_at: entry.delivered_at.map(from_bson_date),
+        }
+    }
+}
+
 #[cfg(feature = "mongodb")]
 impl From<DBMessage> for MongoMessage {
     fn from(msg: DBMessage) -> Self {
@@ -54,7 +215,8 @@ impl From<DBMessage> for MongoMessage {
         let thread_id = ObjectId::parse_str(&msg.thread_id)
             .unwrap_or_else(|_| ObjectId::new());
         
-        // Parse id as ObjectId, or create new if invalid
+        // Parse id as ObjectId, or create new if invalid: callers that refer
+        // back to a message by id must give it an ObjectId hex id
         let id = ObjectId::parse_str(&msg.id)
             .unwrap_or_else(|_| ObjectId::new());
         
@@ -67,10 +229,22 @@ impl From<DBMessage> for MongoMessage {
             content: msg.content,
             tool_call_id: msg.tool_call_id,
             tool_name: msg.tool_name,
+            tool_server: msg.tool_server,
+            verification: msg.verification,
             arguments: msg.arguments,
             reasoning_id: msg.reasoning_id,
             created_at: msg.created_at,
             duration_ms: msg.duration_ms,
+            agent: msg.agent,
+            content_encoding: ContentEncoding::None,
+            compressed_content: None,
+            content_sha256: msg.content_sha256,
+            token_count: msg.token_count,
+            summary: msg.summary,
+            iteration: msg.iteration,
+            usage: msg.usage,
+            citations: msg.citations,
+            schema_version: SCHEMA_VERSION,
         }
     }
 }
@@ -78,7 +252,7 @@ impl From<DBMessage> for MongoMessage {
 #[cfg(feature = "mongodb")]
 impl From<MongoMessage> for DBMessage {
     fn from(msg: MongoMessage) -> Self {
-        Self {
+        let mut message = Self {
             id: msg.id.to_hex(),
             thread_id: msg.thread_id.to_hex(),
             user_id: msg.user_id,
@@ -87,11 +261,26 @@ impl From<MongoMessage> for DBMessage {
             content: msg.content,
             tool_call_id: msg.tool_call_id,
             tool_name: msg.tool_name,
+            tool_server: msg.tool_server,
+            verification: msg.verification,
             arguments: msg.arguments,
             reasoning_id: msg.reasoning_id,
             created_at: msg.created_at,
             duration_ms: msg.duration_ms,
+            agent: msg.agent,
+            content_encoding: msg.content_encoding,
+            content_sha256: msg.content_sha256,
+            token_count: msg.token_count,
+            summary: msg.summary,
+            iteration: msg.iteration,
+            usage: msg.usage,
+            citations: msg.citations,
+        };
+        // Messages saved before hashing existed get their hash on read
+        if message.content_sha256.is_none() {
+            message.fingerprint(None, "");
         }
+        message
     }
 }
 
@@ -106,7 +295,102 @@ impl From<MongoThread> for DBThread {
             metadata: thread.metadata,
             last_summary_update: thread.last_summary_update,
             summary: thread.summary,
+            history_index: thread.history_index,
+            archived_at: thread.archived_at,
+            conversation_key: thread.conversation_key,
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Repetitive JSON, typical of a large tool result
+    fn large_tool_result() -> String {
+        let rows: Vec<_> = (0..2000)
+            .map(|i| serde_json::json!({ "id": i, "status": "ok", "region": "us-east-1", "tags": ["alpha", "beta"] }))
+            .collect();
+        serde_json::to_string(&rows).unwrap()
+    }
+
+    fn mongo_message(content: String) -> MongoMessage {
+        DBMessage {
+            message_type: MessageType::ToolResult,
+            content,
+            ..Default::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_large_content_round_trips_compressed() {
+        let content = large_tool_result();
+        let stored = mongo_message(content.clone()).compress(&CompressionConfig::default());
+
+        assert_eq!(stored.content_encoding, ContentEncoding::Zstd);
+        assert!(stored.content.is_empty());
+        let compressed_len = stored.compressed_content.as_ref().unwrap().bytes.len();
+        assert!(compressed_len * 10 < content.len(), "{} -> {} bytes", content.len(), compressed_len);
+
+        // Survives a BSON round trip like a real insert/find
+        let document = bson::to_document(&stored).unwrap();
+        let loaded: MongoMessage = bson::from_document(document).unwrap();
+
+        let message: DBMessage = loaded.decompress().unwrap().into();
+        assert_eq!(message.content, content);
+        assert_eq!(message.content_encoding, ContentEncoding::Zstd);
+    }
+
+    #[test]
+    fn test_small_or_disabled_content_stays_plain() {
+        let small = mongo_message("short answer".to_string()).compress(&CompressionConfig::default());
+        assert_eq!(small.content_encoding, ContentEncoding::None);
+        assert_eq!(small.content, "short answer");
+        assert!(small.compressed_content.is_none());
+
+        let disabled = mongo_message(large_tool_result()).compress(&CompressionConfig::disabled());
+        assert_eq!(disabled.content_encoding, ContentEncoding::None);
+    }
+
+    #[test]
+    fn test_legacy_documents_without_encoding_load_as_plain() {
+        let mut document = bson::to_document(&mongo_message("hello".to_string())).unwrap();
+        document.remove("content_encoding");
+
+        let loaded: MongoMessage = bson::from_document(document).unwrap();
+        assert_eq!(loaded.decompress().unwrap().content, "hello");
+    }
+
+    #[test]
+    fn test_missing_blob_is_an_error() {
+        let mut broken = mongo_message(String::new());
+        broken.content_encoding = ContentEncoding::Zstd;
+        assert!(broken.decompress().is_err());
+    }
+
+    #[test]
+    fn test_object_id_message_ids_are_kept() {
+        let id = ObjectId::new().to_hex();
+        let stored: MongoMessage = DBMessage { id: id.clone(), ..Default::default() }.into();
+
+        let document = bson::to_document(&stored).unwrap();
+        let loaded: DBMessage = bson::from_document::<MongoMessage>(document).unwrap().into();
+        assert_eq!(loaded.id, id);
+
+        // Any other id is replaced, so it can't be used to find the message again
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let replaced: DBMessage = MongoMessage::from(DBMessage { id: uuid.clone(), ..Default::default() }).into();
+        assert_ne!(replaced.id, uuid);
+    }
+
+    #[test]
+    fn test_hash_filled_in_on_read_for_old_messages() {
+        let stored = mongo_message("legacy".to_string());
+        assert!(stored.content_sha256.is_none());
+
+        let message: DBMessage = stored.into();
+        assert_eq!(message.content_sha256, Some(crate::content_sha256("legacy")));
+        assert_eq!(message.token_count, None);
+    }
+}
@@ -0,0 +1,115 @@
+#[cfg(feature = "mongodb")]
+use mongodb::{Client, Database, bson::{doc, Bson}, gridfs::{FilesCollectionDocument, GridFsBucket}};
+#[cfg(feature = "mongodb")]
+use mongodb::options::GridFsBucketOptions;
+#[cfg(feature = "mongodb")]
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "mongodb")]
+use futures::TryStreamExt;
+
+#[cfg(feature = "mongodb")]
+use crate::models::AttachmentRef;
+#[cfg(feature = "mongodb")]
+use crate::dbs::mongo::models::from_bson_date;
+#[cfg(feature = "mongodb")]
+use crate::error::{PersistError, Result};
+
+#[cfg(feature = "mongodb")]
+const BUCKET: &str = "attachments";
+
+/// Thread attachments in the `attachments` GridFS bucket
+///
+/// Files are named after the attachment; the thread and mime type are kept
+/// in the file's metadata.
+#[cfg(feature = "mongodb")]
+#[derive(Clone)]
+pub struct MongoAttachmentRepository {
+    bucket: GridFsBucket,
+}
+
+#[cfg(feature = "mongodb")]
+impl MongoAttachmentRepository {
+    pub fn new(client: &Client, db_name: &str) -> Self {
+        Self::on(&client.database(db_name))
+    }
+    
+    /// Use the bucket of `database` (inheriting its read/write options)
+    pub fn with_database(mut self, database: &Database) -> Self {
+        self.bucket = Self::on(database).bucket;
+        self
+    }
+    
+    fn on(database: &Database) -> Self {
+        let options = GridFsBucketOptions::builder().bucket_name(BUCKET.to_string()).build();
+        Self { bucket: database.gridfs_bucket(options) }
+    }
+    
+    /// Upload `bytes` as `name`, then remove earlier files of that name
+    pub async fn save(&self, thread_id: &str, name: &str, mime_type: &str, bytes: &[u8]) -> Result<AttachmentRef> {
+        let earlier = self.files(doc! { "metadata.thread_id": thread_id, "filename": name }).await?;
+        
+        let metadata = doc! { "thread_id": thread_id, "mime_type": mime_type };
+        let mut upload = self.bucket.open_upload_stream(name).metadata(metadata).await?;
+        upload.write_all(bytes).await.map_err(|e| PersistError::Other(e.to_string()))?;
+        upload.close().await.map_err(|e| PersistError::Other(e.to_string()))?;
+        let id = upload.id().clone();
+        
+        for file in earlier {
+            self.bucket.delete(file.id).await?;
+        }
+        let file = self
+            .bucket
+            .find_one(doc! { "_id": id.clone() })
+            .await?
+            .ok_or_else(|| PersistError::Internal(format!("uploaded attachment {} not found", id)))?;
+        Ok(to_attachment_ref(file))
+    }
+    
+    /// Attachments of a thread, oldest first
+    pub async fn list(&self, thread_id: &str) -> Result<Vec<AttachmentRef>> {
+        let files = self.files(doc! { "metadata.thread_id": thread_id }).await?;
+        Ok(files.into_iter().map(to_attachment_ref).collect())
+    }
+    
+    /// Content of the newest file named `name`
+    pub async fn load(&self, thread_id: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        let files = self.files(doc! { "metadata.thread_id": thread_id, "filename": name }).await?;
+        let Some(file) = files.into_iter().last() else {
+            return Ok(None);
+        };
+        let mut download = self.bucket.open_download_stream(file.id).await?;
+        let mut bytes = Vec::with_capacity(file.length as usize);
+        download.read_to_end(&mut bytes).await.map_err(|e| PersistError::Other(e.to_string()))?;
+        Ok(Some(bytes))
+    }
+    
+    /// Delete every attachment of a thread
+    pub async fn delete_thread_attachments(&self, thread_id: &str) -> Result<()> {
+        for file in self.files(doc! { "metadata.thread_id": thread_id }).await? {
+            self.bucket.delete(file.id).await?;
+        }
+        Ok(())
+    }
+    
+    async fn files(&self, filter: mongodb::bson::Document) -> Result<Vec<FilesCollectionDocument>> {
+        let cursor = self.bucket.find(filter).sort(doc! { "uploadDate": 1, "_id": 1 }).await?;
+        Ok(cursor.try_collect().await?)
+    }
+}
+
+#[cfg(feature = "mongodb")]
+fn to_attachment_ref(file: FilesCollectionDocument) -> AttachmentRef {
+    let metadata = file.metadata.unwrap_or_default();
+    let field = |key: &str| metadata.get_str(key).unwrap_or_default().to_string();
+    AttachmentRef {
+        id: match &file.id {
+            Bson::ObjectId(id) => id.to_hex(),
+            other => other.to_string(),
+        },
+        thread_id: field("thread_id"),
+        name: file.filename.unwrap_or_default(),
+        mime_type: field("mime_type"),
+        size: file.length,
+        created_at: from_bson_date(file.upload_date),
+    }
+}
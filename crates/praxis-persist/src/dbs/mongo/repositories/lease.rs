@@ -0,0 +1,60 @@
+#[cfg(feature = "mongodb")]
+use mongodb::{Client, Collection, Database, bson::doc};
+#[cfg(feature = "mongodb")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "mongodb")]
+use crate::dbs::mongo::models::{to_bson_date, MongoLease};
+#[cfg(feature = "mongodb")]
+use crate::dbs::mongo::repositories::is_duplicate_key;
+#[cfg(feature = "mongodb")]
+use crate::error::Result;
+
+#[cfg(feature = "mongodb")]
+#[derive(Clone)]
+pub struct MongoLeaseRepository {
+    collection: Collection<MongoLease>,
+}
+
+#[cfg(feature = "mongodb")]
+impl MongoLeaseRepository {
+    pub fn new(client: &Client, db_name: &str) -> Self {
+        let collection = client.database(db_name).collection("leases");
+        Self { collection }
+    }
+    
+    /// Use the `leases` collection of `database` (inheriting its read/write options)
+    pub fn with_database(mut self, database: &Database) -> Self {
+        self.collection = database.collection("leases");
+        self
+    }
+    
+    /// Take or renew the lease `name` for `owner` until `expires_at`
+    ///
+    /// The filter only matches a free or expired lease, or one `owner`
+    /// holds; otherwise the upsert collides with the existing document on
+    /// `_id` and nothing changes.
+    pub async fn acquire(&self, name: &str, owner: &str, now: DateTime<Utc>, expires_at: DateTime<Utc>) -> Result<bool> {
+        let filter = doc! {
+            "_id": name,
+            "$or": [
+                { "owner": owner },
+                { "expires_at": { "$lte": to_bson_date(now) } },
+            ],
+        };
+        let update = doc! {
+            "$set": { "owner": owner, "expires_at": to_bson_date(expires_at) },
+        };
+        match self.collection.update_one(filter, update).upsert(true).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    /// Delete the lease `name` if `owner` holds it
+    pub async fn release(&self, name: &str, owner: &str) -> Result<()> {
+        self.collection.delete_one(doc! { "_id": name, "owner": owner }).await?;
+        Ok(())
+    }
+}
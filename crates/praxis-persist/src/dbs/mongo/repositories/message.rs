@@ -1,8 +1,10 @@
 #[cfg(feature = "mongodb")]
-use mongodb::{Client, Collection, bson, bson::doc, bson::oid::ObjectId};
+use mongodb::{Client, ClientSession, Collection, Database, bson, bson::doc, bson::oid::ObjectId};
 #[cfg(feature = "mongodb")]
 use futures::TryStreamExt;
 
+#[cfg(feature = "mongodb")]
+use crate::compression::CompressionConfig;
 #[cfg(feature = "mongodb")]
 use crate::dbs::mongo::models::MongoMessage;
 #[cfg(feature = "mongodb")]
@@ -12,34 +14,67 @@ use crate::error::Result;
 #[derive(Clone)]
 pub struct MongoMessageRepository {
     collection: Collection<MongoMessage>,
+    compression: CompressionConfig,
 }
 
 #[cfg(feature = "mongodb")]
 impl MongoMessageRepository {
     pub fn new(client: &Client, db_name: &str) -> Self {
         let collection = client.database(db_name).collection("messages");
-        Self { collection }
+        Self {
+            collection,
+            compression: CompressionConfig::default(),
+        }
+    }
+    
+    pub fn with_compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+    
+    /// Use the `messages` collection of `database` (inheriting its read/write options)
+    pub fn with_database(mut self, database: &Database) -> Self {
+        self.collection = database.collection("messages");
+        self
     }
     
     /// Save a single message
     pub async fn save_message(&self, message: MongoMessage) -> Result<ObjectId> {
+        let message = message.compress(&self.compression);
         self.collection.insert_one(&message).await?;
         Ok(message.id)
     }
     
-    /// Get all messages for a thread
+    /// Save messages in one `insert_many`, optionally inside `session`'s transaction
+    pub async fn save_messages(&self, messages: Vec<MongoMessage>, session: Option<&mut ClientSession>) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let messages: Vec<MongoMessage> = messages
+            .into_iter()
+            .map(|message| message.compress(&self.compression))
+            .collect();
+        let insert = self.collection.insert_many(&messages);
+        match session {
+            Some(session) => insert.session(session).await?,
+            None => insert.await?,
+        };
+        Ok(())
+    }
+    
+    /// Get all messages for a thread (content decompressed)
     pub async fn get_messages(&self, thread_id: ObjectId) -> Result<Vec<MongoMessage>> {
         let filter = doc! { "thread_id": thread_id };
-        let messages = self.collection
+        let messages: Vec<MongoMessage> = self.collection
             .find(filter)
-            .sort(doc! { "created_at": 1 })
+            .sort(doc! { "created_at": 1, "_id": 1 })
             .await?
             .try_collect()
             .await?;
-        Ok(messages)
+        messages.into_iter().map(MongoMessage::decompress).collect()
     }
     
-    /// Get messages after a certain timestamp
+    /// Get messages after a certain timestamp (content decompressed)
     pub async fn get_messages_after(
         &self,
         thread_id: ObjectId,
@@ -49,13 +84,82 @@ impl MongoMessageRepository {
             "thread_id": thread_id,
             "created_at": { "$gt": bson::DateTime::from_millis(after.timestamp_millis()) }
         };
-        let messages = self.collection
+        let messages: Vec<MongoMessage> = self.collection
             .find(filter)
-            .sort(doc! { "created_at": 1 })
+            .sort(doc! { "created_at": 1, "_id": 1 })
             .await?
             .try_collect()
             .await?;
-        Ok(messages)
+        messages.into_iter().map(MongoMessage::decompress).collect()
+    }
+    
+    /// Sum of the stored token counts of a thread's messages
+    pub async fn token_total(&self, thread_id: ObjectId) -> Result<u64> {
+        let pipeline = vec![
+            doc! { "$match": { "thread_id": thread_id } },
+            doc! { "$group": { "_id": null, "total": { "$sum": "$token_count" } } },
+        ];
+        let Some(group) = self.collection.aggregate(pipeline).await?.try_next().await? else {
+            return Ok(0);
+        };
+        // $sum yields int32 or int64 depending on magnitude
+        let total = group
+            .get_i64("total")
+            .or_else(|_| group.get_i32("total").map(i64::from))
+            .unwrap_or(0);
+        Ok(total.max(0) as u64)
+    }
+    
+    /// Number of messages stored for a thread
+    pub async fn count(&self, thread_id: ObjectId) -> Result<u64> {
+        Ok(self.collection.count_documents(doc! { "thread_id": thread_id }).await?)
+    }
+    
+    /// Record the hash and token count of a stored message
+    pub async fn set_stats(&self, id: ObjectId, content_sha256: &str, token_count: u32) -> Result<()> {
+        let update = doc! {
+            "$set": { "content_sha256": content_sha256, "token_count": i64::from(token_count) }
+        };
+        self.collection.update_one(doc! { "_id": id }, update).await?;
+        Ok(())
+    }
+    
+    /// Compress the thread's uncompressed tool results that `compression`
+    /// shrinks, returning how many were compressed and the bytes saved
+    pub async fn compress_tool_results(&self, thread_id: ObjectId, compression: &CompressionConfig) -> Result<(usize, u64)> {
+        let filter = doc! {
+            "thread_id": thread_id,
+            "message_type": "tool_result",
+            "content_encoding": { "$ne": "zstd" },
+        };
+        let messages: Vec<MongoMessage> = self.collection.find(filter).await?.try_collect().await?;
+        let mut count = 0;
+        let mut saved = 0;
+        for message in messages {
+            let before = message.content.len();
+            let message = message.compress(compression);
+            let Some(blob) = &message.compressed_content else {
+                continue;
+            };
+            let update = doc! {
+                "$set": {
+                    "content": "",
+                    "content_encoding": "zstd",
+                    "compressed_content": bson::to_bson(blob)?,
+                }
+            };
+            self.collection.update_one(doc! { "_id": message.id, "content_encoding": { "$ne": "zstd" } }, update).await?;
+            count += 1;
+            saved += (before - blob.bytes.len()) as u64;
+        }
+        Ok((count, saved))
+    }
+    
+    /// Threads having at least one message without a token count
+    pub async fn threads_missing_token_counts(&self) -> Result<Vec<ObjectId>> {
+        let ids = self.collection
+            .distinct("thread_id", doc! { "token_count": { "$exists": false } })
+            .await?;
+        Ok(ids.into_iter().filter_map(|id| id.as_object_id()).collect())
     }
 }
-
@@ -1,6 +1,28 @@
+pub mod attachment;
+pub mod lease;
 pub mod message;
+pub mod outbox;
 pub mod thread;
 
+pub use attachment::MongoAttachmentRepository;
+pub use lease::MongoLeaseRepository;
 pub use message::MongoMessageRepository;
+pub use outbox::MongoOutboxRepository;
 pub use thread::MongoThreadRepository;
 
+
+#[cfg(feature = "mongodb")]
+use mongodb::error::{ErrorKind, WriteFailure};
+
+/// Duplicate key: an insert or upsert colliding with a unique index
+#[cfg(feature = "mongodb")]
+const DUPLICATE_KEY: i32 = 11000;
+
+#[cfg(feature = "mongodb")]
+pub(crate) fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(e)) => e.code == DUPLICATE_KEY,
+        ErrorKind::Command(e) => e.code == DUPLICATE_KEY,
+        _ => false,
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(feature = "mongodb")]
+use mongodb::{Client, ClientSession, Collection, Database, bson, bson::doc, options::ReturnDocument};
+#[cfg(feature = "mongodb")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "mongodb")]
+use crate::dbs::mongo::models::{to_bson_date, MongoOutboxEntry};
+#[cfg(feature = "mongodb")]
+use crate::error::Result;
+
+#[cfg(feature = "mongodb")]
+#[derive(Clone)]
+pub struct MongoOutboxRepository {
+    collection: Collection<MongoOutboxEntry>,
+}
+
+#[cfg(feature = "mongodb")]
+impl MongoOutboxRepository {
+    pub fn new(client: &Client, db_name: &str) -> Self {
+        let collection = client.database(db_name).collection("outbox");
+        Self { collection }
+    }
+    
+    /// Use the `outbox` collection of `database` (inheriting its read/write options)
+    pub fn with_database(mut self, database: &Database) -> Self {
+        self.collection = database.collection("outbox");
+        self
+    }
+    
+    /// Insert entries in one `insert_many`, optionally inside `session`'s transaction
+    pub async fn insert_entries(&self, entries: Vec<MongoOutboxEntry>, session: Option<&mut ClientSession>) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let insert = self.collection.insert_many(&entries);
+        match session {
+            Some(session) => insert.session(session).await?,
+            None => insert.await?,
+        };
+        Ok(())
+    }
+    
+    /// Lease the oldest due entry to `owner` until `expires_at`
+    ///
+    /// `find_one_and_update` makes the claim atomic, so two dispatchers
+    /// never lease the same entry at once.
+    pub async fn lease_next(
+        &self,
+        owner: &str,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<MongoOutboxEntry>> {
+        let now = to_bson_date(now);
+        let filter = doc! {
+            "status": "pending",
+            "next_attempt_at": { "$lte": now },
+            "$or": [
+                { "lease_expires_at": null },
+                { "lease_expires_at": { "$lte": now } },
+            ],
+        };
+        let update = doc! {
+            "$set": { "lease_owner": owner, "lease_expires_at": to_bson_date(expires_at) },
+            "$inc": { "attempts": 1 },
+        };
+        Ok(self.collection
+            .find_one_and_update(filter, update)
+            .sort(doc! { "next_attempt_at": 1, "_id": 1 })
+            .return_document(ReturnDocument::After)
+            .await?)
+    }
+    
+    /// Apply `update` to entry `id` if `owner` holds its lease; returns whether it did
+    pub async fn update_leased(&self, id: &str, owner: &str, update: bson::Document) -> Result<bool> {
+        let filter = doc! { "_id": id, "status": "pending", "lease_owner": owner };
+        let result = self.collection.update_one(filter, update).await?;
+        Ok(result.matched_count == 1)
+    }
+    
+    /// Get an entry by ID
+    pub async fn get_entry(&self, id: &str) -> Result<Option<MongoOutboxEntry>> {
+        Ok(self.collection.find_one(doc! { "_id": id }).await?)
+    }
+}
@@ -1,16 +1,20 @@
 #[cfg(feature = "mongodb")]
-use mongodb::{Client, Collection, bson::doc, bson::oid::ObjectId};
+use mongodb::{Client, ClientSession, Collection, Database, IndexModel, bson::doc, bson::oid::ObjectId, options::IndexOptions};
 #[cfg(feature = "mongodb")]
 use futures::TryStreamExt;
 #[cfg(feature = "mongodb")]
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 
 #[cfg(feature = "mongodb")]
 use crate::dbs::mongo::models::MongoThread;
 #[cfg(feature = "mongodb")]
-use crate::models::{ThreadMetadata, ThreadSummary};
+use crate::models::{HistoryIndex, ThreadMetadata, ThreadSummary};
 #[cfg(feature = "mongodb")]
-use crate::error::Result;
+use crate::error::{PersistError, Result};
+#[cfg(feature = "mongodb")]
+use crate::migrations::SCHEMA_VERSION;
+#[cfg(feature = "mongodb")]
+use crate::dbs::mongo::repositories::is_duplicate_key;
 
 #[cfg(feature = "mongodb")]
 #[derive(Clone)]
@@ -25,6 +29,12 @@ impl MongoThreadRepository {
         Self { collection }
     }
     
+    /// Use the `threads` collection of `database` (inheriting its read/write options)
+    pub fn with_database(mut self, database: &Database) -> Self {
+        self.collection = database.collection("threads");
+        self
+    }
+    
     /// Create a new thread
     pub async fn create_thread(
         &self,
@@ -40,12 +50,59 @@ impl MongoThreadRepository {
             metadata,
             last_summary_update: now,
             summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+            schema_version: SCHEMA_VERSION,
         };
         
         self.collection.insert_one(&thread).await?;
         Ok(thread)
     }
     
+    /// Insert a fully built thread, optionally inside `session`'s transaction
+    pub async fn insert_thread(&self, thread: &MongoThread, session: Option<&mut ClientSession>) -> Result<()> {
+        let insert = self.collection.insert_one(thread);
+        match session {
+            Some(session) => insert.session(session).await?,
+            None => insert.await?,
+        };
+        Ok(())
+    }
+    
+    /// Create the unique index on `conversation_key` (threads without a key
+    /// are left out of it); a no-op once the index exists
+    pub async fn ensure_conversation_key_index(&self) -> Result<()> {
+        let index = IndexModel::builder()
+            .keys(doc! { "conversation_key": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("conversation_key_unique".to_string())
+                    .unique(true)
+                    .partial_filter_expression(doc! { "conversation_key": { "$type": "string" } })
+                    .build(),
+            )
+            .build();
+        self.collection.create_index(index).await?;
+        Ok(())
+    }
+    
+    /// Thread created for the external `conversation_key`
+    pub async fn get_by_conversation_key(&self, conversation_key: &str) -> Result<Option<MongoThread>> {
+        let filter = doc! { "conversation_key": conversation_key };
+        Ok(self.collection.find_one(filter).await?)
+    }
+    
+    /// Insert `thread` unless another thread already has its conversation
+    /// key; returns whether it was inserted
+    pub async fn insert_keyed_thread(&self, thread: &MongoThread) -> Result<bool> {
+        match self.collection.insert_one(thread).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(PersistError::from(e)),
+        }
+    }
+    
     /// Get thread by ID
     pub async fn get_thread(&self, thread_id: ObjectId) -> Result<Option<MongoThread>> {
         let filter = doc! { "_id": thread_id };
@@ -78,6 +135,39 @@ impl MongoThreadRepository {
         Ok(threads)
     }
     
+    /// Unarchived threads last updated at or before `idle_since`, least
+    /// recently updated first
+    pub async fn idle_threads(&self, idle_since: DateTime<Utc>, limit: usize) -> Result<Vec<MongoThread>> {
+        // Threads are created with chrono timestamps (RFC 3339 strings) and
+        // summary updates write BSON dates, so match both
+        let filter = doc! {
+            "archived_at": null,
+            "$or": [
+                { "updated_at": { "$lte": bson::DateTime::from_millis(idle_since.timestamp_millis()) } },
+                { "updated_at": { "$lte": idle_since.to_rfc3339_opts(SecondsFormat::AutoSi, true) } },
+            ],
+        };
+        let threads = self.collection
+            .find(filter)
+            .sort(doc! { "updated_at": 1 })
+            .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+            .await?
+            .try_collect()
+            .await?;
+        Ok(threads)
+    }
+    
+    /// Set `archived_at`, leaving `updated_at` as it is
+    pub async fn set_archived(&self, thread_id: ObjectId, archived_at: DateTime<Utc>) -> Result<()> {
+        let filter = doc! { "_id": thread_id };
+        let update = doc! {
+            "$set": { "archived_at": bson::to_bson(&archived_at)? }
+        };
+        
+        self.collection.update_one(filter, update).await?;
+        Ok(())
+    }
+    
     /// Update thread summary
     pub async fn update_summary(
         &self,
@@ -98,11 +188,45 @@ impl MongoThreadRepository {
         Ok(())
     }
     
+    /// Replace thread metadata
+    pub async fn update_metadata(
+        &self,
+        thread_id: ObjectId,
+        metadata: ThreadMetadata,
+    ) -> Result<()> {
+        let filter = doc! { "_id": thread_id };
+        let update = doc! {
+            "$set": {
+                "metadata": bson::to_bson(&metadata)?,
+                "updated_at": bson::DateTime::now()
+            }
+        };
+        
+        self.collection.update_one(filter, update).await?;
+        Ok(())
+    }
+    
+    /// Replace the cached history index
+    pub async fn update_history_index(
+        &self,
+        thread_id: ObjectId,
+        index: HistoryIndex,
+    ) -> Result<()> {
+        let filter = doc! { "_id": thread_id };
+        let update = doc! {
+            "$set": { "history_index": bson::to_bson(&index)? }
+        };
+        
+        self.collection.update_one(filter, update).await?;
+        Ok(())
+    }
+    
     /// Delete thread
-    pub async fn delete_thread(&self, thread_id: ObjectId, user_id: &str) -> Result<()> {
+    /// Delete a thread owned by `user_id`; returns whether it did
+    pub async fn delete_thread(&self, thread_id: ObjectId, user_id: &str) -> Result<bool> {
         let filter = doc! { "_id": thread_id, "user_id": user_id };
-        self.collection.delete_one(filter).await?;
-        Ok(())
+        let result = self.collection.delete_one(filter).await?;
+        Ok(result.deleted_count == 1)
     }
 }
 
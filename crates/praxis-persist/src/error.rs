@@ -20,9 +20,15 @@ pub enum PersistError {
     #[error("Message not found: {0}")]
     MessageNotFound(String),
     
+    #[error("Conversation key {0} belongs to another user's thread")]
+    ConversationKeyConflict(String),
+    
     #[error("Invalid object ID: {0}")]
     InvalidObjectId(String),
     
+    #[error("Invalid message at index {index}: {reason}")]
+    InvalidMessage { index: usize, reason: String },
+    
     #[error("Connection error: {0}")]
     Connection(String),
     
@@ -33,6 +39,15 @@ pub enum PersistError {
     Other(String),
 }
 
+impl From<crate::invariants::InvariantViolation> for PersistError {
+    fn from(violation: crate::invariants::InvariantViolation) -> Self {
+        PersistError::InvalidMessage {
+            index: violation.index,
+            reason: violation.reason,
+        }
+    }
+}
+
 // Allow conversion from anyhow::Error
 impl From<anyhow::Error> for PersistError {
     fn from(err: anyhow::Error) -> Self {
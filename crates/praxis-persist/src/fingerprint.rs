@@ -0,0 +1,59 @@
+use sha2::{Digest, Sha256};
+
+/// Counts tokens of message text with a model's tokenizer
+///
+/// Persistence stays tokenizer-agnostic; callers that know the model (the
+/// graph's persistence worker, reindex jobs) supply an implementation.
+pub trait ContentTokenizer: Send + Sync {
+    fn count(&self, model: &str, text: &str) -> usize;
+}
+
+/// Lowercase hex SHA-256 of `text`
+pub fn content_sha256(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DBMessage, MessageType};
+
+    struct WordCounter;
+
+    impl ContentTokenizer for WordCounter {
+        fn count(&self, _model: &str, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_hashes_and_counts_model_text() {
+        let mut message = DBMessage {
+            content: "three little words".to_string(),
+            ..Default::default()
+        };
+        message.fingerprint(Some(&WordCounter), "gpt-4o");
+
+        assert_eq!(message.content_sha256.as_deref(), Some(content_sha256("three little words").as_str()));
+        assert_eq!(message.token_count, Some(3));
+        assert_eq!(
+            content_sha256(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_tool_call_fingerprint_covers_arguments() {
+        let mut call = DBMessage {
+            message_type: MessageType::ToolCall,
+            arguments: Some(serde_json::json!({ "city": "Lisbon" })),
+            token_count: Some(7),
+            ..Default::default()
+        };
+        call.fingerprint(None, "gpt-4o");
+
+        assert_eq!(call.content_sha256, Some(content_sha256(r#"{"city":"Lisbon"}"#)));
+        // No tokenizer: an existing count is kept
+        assert_eq!(call.token_count, Some(7));
+    }
+}
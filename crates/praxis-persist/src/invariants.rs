@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::models::{DBMessage, MessageType};
+
+/// A message list broken at `index`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantViolation {
+    pub index: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "messages[{}]: {}", self.index, self.reason)
+    }
+}
+
+/// Check that a thread's messages can be replayed to a model
+///
+/// Every tool call has an id and is answered by exactly one later tool
+/// result, every tool result answers an earlier open call, and
+/// `created_at` never goes backwards (stored order is timestamp order).
+pub fn check_message_invariants(messages: &[DBMessage]) -> Result<(), InvariantViolation> {
+    let violation = |index: usize, reason: String| Err(InvariantViolation { index, reason });
+    // Open tool calls: id -> index of the call
+    let mut open: Vec<(&str, usize)> = Vec::new();
+    let mut answered: HashSet<&str> = HashSet::new();
+
+    for (index, message) in messages.iter().enumerate() {
+        if index > 0 && message.created_at < messages[index - 1].created_at {
+            return violation(index, "created_at is earlier than the previous message".to_string());
+        }
+
+        match message.message_type {
+            MessageType::ToolCall => {
+                let Some(id) = message.tool_call_id.as_deref() else {
+                    return violation(index, "tool call has no tool_call_id".to_string());
+                };
+                if answered.contains(id) || open.iter().any(|(open_id, _)| *open_id == id) {
+                    return violation(index, format!("duplicate tool call id '{}'", id));
+                }
+                open.push((id, index));
+            }
+            MessageType::ToolResult => {
+                let Some(id) = message.tool_call_id.as_deref() else {
+                    return violation(index, "tool result has no tool_call_id".to_string());
+                };
+                match open.iter().position(|(open_id, _)| *open_id == id) {
+                    Some(position) => {
+                        open.remove(position);
+                        answered.insert(id);
+                    }
+                    None => {
+                        return violation(index, format!("tool result '{}' has no matching tool call", id));
+                    }
+                }
+            }
+            MessageType::Message | MessageType::Reasoning => {
+                if let Some((id, call_index)) = open.first() {
+                    return violation(*call_index, format!("tool call '{}' has no result before the next message", id));
+                }
+            }
+        }
+    }
+
+    match open.first() {
+        Some((id, call_index)) => violation(*call_index, format!("tool call '{}' has no result", id)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageRole;
+
+    fn message(message_type: MessageType, tool_call_id: Option<&str>) -> DBMessage {
+        DBMessage {
+            role: MessageRole::Assistant,
+            message_type,
+            tool_call_id: tool_call_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_paired_tool_calls_pass() {
+        let messages = vec![
+            message(MessageType::Message, None),
+            message(MessageType::ToolCall, Some("a")),
+            message(MessageType::ToolCall, Some("b")),
+            message(MessageType::ToolResult, Some("b")),
+            message(MessageType::ToolResult, Some("a")),
+            message(MessageType::Message, None),
+        ];
+        assert_eq!(check_message_invariants(&messages), Ok(()));
+    }
+
+    #[test]
+    fn test_violations_name_the_offending_index() {
+        let orphan = vec![message(MessageType::Message, None), message(MessageType::ToolResult, Some("x"))];
+        assert_eq!(check_message_invariants(&orphan).unwrap_err().index, 1);
+
+        let unanswered = vec![message(MessageType::ToolCall, Some("a")), message(MessageType::Message, None)];
+        let err = check_message_invariants(&unanswered).unwrap_err();
+        assert_eq!(err.index, 0);
+        assert!(err.to_string().starts_with("messages[0]: tool call 'a'"), "{}", err);
+
+        let mut backwards = vec![message(MessageType::Message, None), message(MessageType::Message, None)];
+        backwards[0].created_at = backwards[1].created_at + chrono::Duration::seconds(1);
+        assert_eq!(check_message_invariants(&backwards).unwrap_err().index, 1);
+    }
+}
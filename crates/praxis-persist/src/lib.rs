@@ -3,6 +3,15 @@ mod models;
 mod error;
 mod trait_client;
 mod accumulator;
+mod policy;
+mod fingerprint;
+mod invariants;
+mod outbox;
+mod memory;
+mod migrations;
+mod compression;
+mod maintenance;
+pub mod anonymize;
 
 #[cfg(feature = "mongodb")]
 mod dbs;
@@ -10,8 +19,19 @@ mod dbs;
 // Public exports
 pub use trait_client::PersistenceClient;
 pub use accumulator::{EventAccumulator, StreamEventExtractor};
-pub use models::{DBMessage, MessageRole, MessageType, Thread, ThreadMetadata, ThreadSummary};
+pub use models::{
+    AttachmentRef, Citation, ContentEncoding, DBMessage, HistoryIndex, HistoryIndexEntry, LLMConfigOverride, MessageRole, MessageType, Thread, ThreadMetadata,
+    SummaryTrigger, ThreadSummary, ToolProfile, ToolVerification,
+};
 pub use error::{PersistError, Result};
-
+pub use policy::PersistencePolicy;
+pub use fingerprint::{content_sha256, ContentTokenizer};
+pub use invariants::{check_message_invariants, InvariantViolation};
+pub use anonymize::{anonymize_thread, AnonymizerConfig, Redactor, ThreadExport};
+pub use outbox::{OutboxDispatcher, OutboxEntry, OutboxHandler, OutboxStatus, OutboxStore};
+pub use memory::InMemoryPersistenceClient;
+pub use migrations::{Migration, MigrationRegistry, MigrationReport, MigrationStep, SCHEMA_VERSION};
+pub use compression::CompressionConfig;
+pub use maintenance::{HYGIENE_LEASE, HygieneAction, HygienePolicy, HygieneReport, HygieneRunner, HygieneStore, ThreadSummarizer};
 #[cfg(feature = "mongodb")]
-pub use dbs::mongo::MongoPersistenceClient;
+pub use dbs::mongo::{MongoPersistenceClient, ReadConsistency};
@@ -0,0 +1,493 @@
+//! Hygiene of idle threads
+//!
+//! A thread nobody has written to in months still keeps everything needed
+//! to continue it: its full history, large tool results, the cached history
+//! index. A hygiene run finds threads idle past `HygienePolicy::idle_days`
+//! and applies the policy's actions to each, always in this order:
+//!
+//! - `Summarize`: a final summary of what the current one doesn't cover yet
+//! - `CompressToolResults`: zstd-compress tool results above a size threshold
+//! - `PruneIndex`: drop the cached history index (sparse strategies rebuild it)
+//! - `Archive`: set `Thread::archived_at`, so later runs skip the thread
+//!
+//! If an action fails the thread's remaining actions are skipped, so a
+//! thread is never archived without its final summary.
+//!
+//! Replicas sharing a store each run a `HygieneRunner`; a run first takes
+//! the store's `hygiene` lease, so only one replica processes threads at a
+//! time. The lease is renewed after every thread and released when the run
+//! ends; a runner that dies mid-run blocks others only until it expires.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use praxis_core::{Clock, SystemClock};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::compression::CompressionConfig;
+use crate::error::Result;
+use crate::models::{HistoryIndex, Thread, ThreadSummary};
+use crate::trait_client::PersistenceClient;
+
+/// Lease taken for the duration of a hygiene run
+pub const HYGIENE_LEASE: &str = "hygiene";
+
+/// Something a hygiene run does to an idle thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HygieneAction {
+    Summarize,
+    CompressToolResults,
+    PruneIndex,
+    Archive,
+}
+
+impl HygieneAction {
+    /// Every action, in the order a run applies them
+    pub const ALL: [HygieneAction; 4] = [Self::Summarize, Self::CompressToolResults, Self::PruneIndex, Self::Archive];
+}
+
+/// Which threads a hygiene run processes and what it does to them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HygienePolicy {
+    /// Days without an update before a thread is processed
+    pub idle_days: u32,
+    /// Actions to apply; their order here doesn't matter
+    pub actions: Vec<HygieneAction>,
+    /// Tool results up to this size are left uncompressed
+    pub tool_result_min_bytes: usize,
+    /// Threads processed per run, least recently updated first; the rest
+    /// wait for the next run
+    pub max_threads: usize,
+}
+
+impl Default for HygienePolicy {
+    fn default() -> Self {
+        Self {
+            idle_days: 90,
+            actions: HygieneAction::ALL.to_vec(),
+            tool_result_min_bytes: 1024,
+            max_threads: 500,
+        }
+    }
+}
+
+impl HygienePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_idle_days(mut self, days: u32) -> Self {
+        self.idle_days = days;
+        self
+    }
+
+    pub fn with_actions(mut self, actions: impl IntoIterator<Item = HygieneAction>) -> Self {
+        self.actions = actions.into_iter().collect();
+        self
+    }
+
+    pub fn with_tool_result_min_bytes(mut self, bytes: usize) -> Self {
+        self.tool_result_min_bytes = bytes;
+        self
+    }
+
+    pub fn with_max_threads(mut self, max_threads: usize) -> Self {
+        self.max_threads = max_threads;
+        self
+    }
+
+    fn applies(&self, action: HygieneAction) -> bool {
+        self.actions.contains(&action)
+    }
+
+    fn compression(&self) -> CompressionConfig {
+        CompressionConfig {
+            enabled: true,
+            threshold_bytes: self.tool_result_min_bytes,
+            ..CompressionConfig::default()
+        }
+    }
+}
+
+/// What a hygiene run did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HygieneReport {
+    /// Idle threads the run processed
+    pub threads: usize,
+    /// Threads given a final summary
+    pub summarized: usize,
+    /// Tool result messages compressed
+    pub tool_results_compressed: usize,
+    /// Threads whose history index was dropped
+    pub indexes_pruned: usize,
+    pub archived: usize,
+    /// Threads where an action failed; their later actions were skipped
+    pub failed: usize,
+    /// Stored bytes saved by compression and pruning
+    pub bytes_reclaimed: u64,
+}
+
+impl fmt::Display for HygieneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "idle threads: {}", self.threads)?;
+        writeln!(f, "summarized: {}", self.summarized)?;
+        writeln!(f, "tool results compressed: {}", self.tool_results_compressed)?;
+        writeln!(f, "indexes pruned: {}", self.indexes_pruned)?;
+        writeln!(f, "archived: {}", self.archived)?;
+        writeln!(f, "failed: {}", self.failed)?;
+        write!(f, "bytes reclaimed: {}", self.bytes_reclaimed)
+    }
+}
+
+/// Backend operations used by hygiene runs
+///
+/// `acquire_lease` must be atomic, since runners on several replicas call
+/// it concurrently.
+#[async_trait]
+pub trait HygieneStore: PersistenceClient {
+    /// Unarchived threads last updated at or before `idle_since`, least
+    /// recently updated first
+    async fn idle_threads(&self, idle_since: DateTime<Utc>, limit: usize) -> Result<Vec<Thread>>;
+
+    /// Compress the thread's tool results that `compression` would
+    /// compress, returning how many were compressed and the bytes saved
+    ///
+    /// Reads still return the plain text, with `content_encoding` telling
+    /// how it is stored.
+    async fn compress_tool_results(&self, thread_id: &str, compression: &CompressionConfig) -> Result<(usize, u64)>;
+
+    /// Mark a thread archived, leaving `updated_at` as it is
+    async fn archive_thread(&self, thread_id: &str, archived_at: DateTime<Utc>) -> Result<()>;
+
+    /// Take the lease `name` for `owner`, or renew it if `owner` already
+    /// holds it, until `ttl` from now
+    ///
+    /// Returns `false` (and changes nothing) if another owner holds an
+    /// unexpired lease.
+    async fn acquire_lease(&self, name: &str, owner: &str, ttl: Duration) -> Result<bool>;
+
+    /// Give up the lease `name` if `owner` holds it
+    async fn release_lease(&self, name: &str, owner: &str) -> Result<()>;
+}
+
+/// Writes the final summary of an idle thread
+///
+/// Context strategies that keep summaries implement this; the persistence
+/// layer can't depend on them directly.
+#[async_trait]
+pub trait ThreadSummarizer: Send + Sync {
+    /// Summarize what the thread's summary doesn't cover yet and save it
+    ///
+    /// `None` when nothing was said since the last summary.
+    async fn summarize_thread(
+        &self,
+        thread_id: &str,
+        persist: &Arc<dyn PersistenceClient>,
+    ) -> anyhow::Result<Option<ThreadSummary>>;
+}
+
+/// Applies a `HygienePolicy` to the idle threads of a store
+///
+/// Each replica runs one runner with a unique `owner`. `Summarize` needs
+/// `with_summarizer`; without one the action is skipped.
+pub struct HygieneRunner {
+    store: Arc<dyn HygieneStore>,
+    owner: String,
+    summarizer: Option<Arc<dyn ThreadSummarizer>>,
+    clock: Arc<dyn Clock>,
+    lease: Duration,
+}
+
+impl HygieneRunner {
+    pub fn new(store: Arc<dyn HygieneStore>, owner: impl Into<String>) -> Self {
+        Self {
+            store,
+            owner: owner.into(),
+            summarizer: None,
+            clock: Arc::new(SystemClock),
+            lease: Duration::from_secs(600),
+        }
+    }
+
+    pub fn with_summarizer(mut self, summarizer: Arc<dyn ThreadSummarizer>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+
+    /// Clock that ages threads and stamps `archived_at` (default: system clock)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// How long the run lease lasts between renewals (default: 10 minutes)
+    ///
+    /// Should exceed the slowest thread, summary included, or another
+    /// replica may start a run meanwhile.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Process the idle threads `policy` selects
+    ///
+    /// Returns `None` without touching any thread if another runner holds
+    /// the lease.
+    pub async fn run_hygiene(&self, policy: &HygienePolicy) -> Result<Option<HygieneReport>> {
+        if !self.store.acquire_lease(HYGIENE_LEASE, &self.owner, self.lease).await? {
+            return Ok(None);
+        }
+        let outcome = self.process(policy).await;
+        if let Err(e) = self.store.release_lease(HYGIENE_LEASE, &self.owner).await {
+            tracing::warn!("Failed to release the hygiene lease: {}", e);
+        }
+        outcome.map(Some)
+    }
+
+    /// Run every `interval` until the returned task is aborted
+    pub fn spawn(self, policy: HygienePolicy, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run_hygiene(&policy).await {
+                    Ok(Some(report)) => tracing::info!(
+                        "Hygiene run: {} idle threads, {} archived, {} failed, {} bytes reclaimed",
+                        report.threads,
+                        report.archived,
+                        report.failed,
+                        report.bytes_reclaimed
+                    ),
+                    Ok(None) => tracing::debug!("Hygiene run skipped: another replica holds the lease"),
+                    Err(e) => tracing::error!("Hygiene run failed: {}", e),
+                }
+            }
+        })
+    }
+
+    async fn process(&self, policy: &HygienePolicy) -> Result<HygieneReport> {
+        let idle_since = self.clock.now() - chrono::Duration::days(i64::from(policy.idle_days));
+        let threads = self.store.idle_threads(idle_since, policy.max_threads).await?;
+        let mut report = HygieneReport::default();
+        for thread in threads {
+            report.threads += 1;
+            if let Err(e) = self.process_thread(&thread, policy, &mut report).await {
+                tracing::warn!("Hygiene of thread {} failed: {}", thread.id, e);
+                report.failed += 1;
+            }
+            if !self.store.acquire_lease(HYGIENE_LEASE, &self.owner, self.lease).await? {
+                tracing::warn!("Lost the hygiene lease; stopping after {} threads", report.threads);
+                break;
+            }
+        }
+        Ok(report)
+    }
+
+    async fn process_thread(&self, thread: &Thread, policy: &HygienePolicy, report: &mut HygieneReport) -> anyhow::Result<()> {
+        if policy.applies(HygieneAction::Summarize) {
+            if let Some(summarizer) = &self.summarizer {
+                let persist: Arc<dyn PersistenceClient> = self.store.clone();
+                if summarizer.summarize_thread(&thread.id, &persist).await?.is_some() {
+                    report.summarized += 1;
+                }
+            }
+        }
+        if policy.applies(HygieneAction::CompressToolResults) {
+            let (compressed, bytes) = self.store.compress_tool_results(&thread.id, &policy.compression()).await?;
+            report.tool_results_compressed += compressed;
+            report.bytes_reclaimed += bytes;
+        }
+        if policy.applies(HygieneAction::PruneIndex) {
+            if let Some(index) = thread.history_index.as_ref().filter(|index| index.indexed_count > 0) {
+                let empty = HistoryIndex::default();
+                self.store.save_thread_index(&thread.id, empty.clone()).await?;
+                report.indexes_pruned += 1;
+                report.bytes_reclaimed += index_bytes(index).saturating_sub(index_bytes(&empty));
+            }
+        }
+        if policy.applies(HygieneAction::Archive) {
+            self.store.archive_thread(&thread.id, self.clock.now()).await?;
+            report.archived += 1;
+        }
+        Ok(())
+    }
+}
+
+fn index_bytes(index: &HistoryIndex) -> u64 {
+    serde_json::to_vec(index).map_or(0, |bytes| bytes.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryPersistenceClient;
+    use crate::{ContentEncoding, DBMessage, HistoryIndexEntry, MessageType, SummaryTrigger, ThreadMetadata};
+    use praxis_core::testkit::FixedClock;
+    use std::sync::Mutex;
+
+    /// Saves "Final summary" for each thread; fails for `fail_on`
+    #[derive(Default)]
+    struct RecordingSummarizer {
+        summarized: Mutex<Vec<String>>,
+        fail_on: Option<String>,
+    }
+
+    #[async_trait]
+    impl ThreadSummarizer for RecordingSummarizer {
+        async fn summarize_thread(
+            &self,
+            thread_id: &str,
+            persist: &Arc<dyn PersistenceClient>,
+        ) -> anyhow::Result<Option<ThreadSummary>> {
+            if self.fail_on.as_deref() == Some(thread_id) {
+                anyhow::bail!("LLM unavailable");
+            }
+            self.summarized.lock().unwrap().push(thread_id.to_string());
+            let at = Utc::now();
+            persist.save_thread_summary(thread_id, "Final summary".to_string(), at, SummaryTrigger::Manual).await?;
+            Ok(persist.get_thread(thread_id).await?.and_then(|thread| thread.summary))
+        }
+    }
+
+    fn clock() -> Arc<FixedClock> {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        Arc::new(FixedClock::at(start))
+    }
+
+    /// A thread with a large tool result, a small one and a history index
+    async fn seed_thread(store: &InMemoryPersistenceClient) -> String {
+        let thread = store.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+        let tool_result = |content: String| DBMessage {
+            thread_id: thread.id.clone(),
+            user_id: "user-1".to_string(),
+            message_type: MessageType::ToolResult,
+            content,
+            ..Default::default()
+        };
+        let rows = serde_json::Value::Array(vec![serde_json::json!({ "city": "Lisbon", "temperature": 21 }); 200]).to_string();
+        store.save_message(tool_result(rows)).await.unwrap();
+        store.save_message(tool_result("ok".to_string())).await.unwrap();
+        let entry = HistoryIndexEntry { seq: 1, message_id: "m-1".to_string(), line: "tool: weather rows".to_string() };
+        store
+            .save_thread_index(&thread.id, HistoryIndex { entries: vec![entry], indexed_count: 2 })
+            .await
+            .unwrap();
+        thread.id
+    }
+
+    #[tokio::test]
+    async fn test_only_threads_idle_past_the_threshold_are_processed() {
+        let clock = clock();
+        let store = Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+        let stale = seed_thread(&store).await;
+        clock.advance(chrono::Duration::days(60));
+        let recent = seed_thread(&store).await;
+        clock.advance(chrono::Duration::days(40));
+
+        let summarizer = Arc::new(RecordingSummarizer::default());
+        let runner = HygieneRunner::new(store.clone(), "replica-a")
+            .with_clock(clock.clone())
+            .with_summarizer(summarizer.clone());
+        let report = runner.run_hygiene(&HygienePolicy::default()).await.unwrap().expect("the lease is free");
+
+        assert_eq!(report.threads, 1);
+        assert_eq!(report.summarized, 1);
+        assert_eq!(report.tool_results_compressed, 1);
+        assert_eq!(report.indexes_pruned, 1);
+        assert_eq!(report.archived, 1);
+        assert_eq!(report.failed, 0);
+        assert!(report.bytes_reclaimed > 5000, "reclaimed {}", report.bytes_reclaimed);
+        assert_eq!(*summarizer.summarized.lock().unwrap(), vec![stale.clone()]);
+
+        let thread = store.get_thread(&stale).await.unwrap().unwrap();
+        assert_eq!(thread.archived_at, Some(clock.now()));
+        assert_eq!(thread.summary.unwrap().text, "Final summary");
+        assert_eq!(thread.history_index.unwrap().indexed_count, 0);
+        // The large result is compressed at rest but reads back as before
+        let messages = store.get_messages(&stale).await.unwrap();
+        assert_eq!(messages[0].content_encoding, ContentEncoding::Zstd);
+        assert!(messages[0].content.starts_with(r#"[{"city":"Lisbon""#));
+        assert_eq!(messages[1].content_encoding, ContentEncoding::None);
+
+        let untouched = store.get_thread(&recent).await.unwrap().unwrap();
+        assert!(untouched.archived_at.is_none());
+        assert!(untouched.summary.is_none());
+        assert_eq!(untouched.history_index.unwrap().indexed_count, 2);
+        assert!(store.get_messages(&recent).await.unwrap().iter().all(|m| m.content_encoding == ContentEncoding::None));
+
+        // Archived threads are skipped from then on
+        let report = runner.run_hygiene(&HygienePolicy::default()).await.unwrap().unwrap();
+        assert_eq!(report, HygieneReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_policy_actions_and_thresholds_apply() {
+        let clock = clock();
+        let store = Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+        let thread_id = seed_thread(&store).await;
+        clock.advance(chrono::Duration::days(40));
+
+        let runner = HygieneRunner::new(store.clone(), "replica-a").with_clock(clock.clone());
+        let policy = HygienePolicy::new()
+            .with_idle_days(30)
+            .with_actions([HygieneAction::CompressToolResults])
+            .with_tool_result_min_bytes(1_000_000);
+        let report = runner.run_hygiene(&policy).await.unwrap().unwrap();
+        assert_eq!(report, HygieneReport { threads: 1, ..Default::default() });
+
+        let report = runner.run_hygiene(&policy.with_tool_result_min_bytes(1024)).await.unwrap().unwrap();
+        assert_eq!(report.tool_results_compressed, 1);
+        assert_eq!(report.archived, 0);
+        let thread = store.get_thread(&thread_id).await.unwrap().unwrap();
+        assert!(thread.archived_at.is_none());
+        assert_eq!(thread.history_index.unwrap().indexed_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_summary_keeps_the_thread_unarchived() {
+        let clock = clock();
+        let store = Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+        let failing = seed_thread(&store).await;
+        let other = seed_thread(&store).await;
+        clock.advance(chrono::Duration::days(91));
+
+        let summarizer = Arc::new(RecordingSummarizer { fail_on: Some(failing.clone()), ..Default::default() });
+        let runner = HygieneRunner::new(store.clone(), "replica-a")
+            .with_clock(clock.clone())
+            .with_summarizer(summarizer);
+        let report = runner.run_hygiene(&HygienePolicy::default()).await.unwrap().unwrap();
+
+        assert_eq!((report.threads, report.failed, report.archived), (2, 1, 1));
+        assert!(store.get_thread(&failing).await.unwrap().unwrap().archived_at.is_none());
+        assert!(store.get_thread(&other).await.unwrap().unwrap().archived_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_lease_keeps_replicas_from_running_concurrently() {
+        let clock = clock();
+        let store = Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+        let thread_id = seed_thread(&store).await;
+        clock.advance(chrono::Duration::days(91));
+        let runner = |owner: &str| {
+            HygieneRunner::new(store.clone(), owner)
+                .with_clock(clock.clone())
+                .with_lease(Duration::from_secs(60))
+        };
+
+        // Replica A takes the lease and dies mid-run
+        assert!(store.acquire_lease(HYGIENE_LEASE, "replica-a", Duration::from_secs(60)).await.unwrap());
+        assert!(runner("replica-b").run_hygiene(&HygienePolicy::default()).await.unwrap().is_none());
+        assert!(store.get_thread(&thread_id).await.unwrap().unwrap().archived_at.is_none());
+
+        // Once it expires, B runs and releases the lease when done
+        clock.advance(chrono::Duration::seconds(61));
+        let report = runner("replica-b").run_hygiene(&HygienePolicy::default()).await.unwrap();
+        assert_eq!(report.map(|report| report.archived), Some(1));
+        assert!(runner("replica-a").run_hygiene(&HygienePolicy::default()).await.unwrap().is_some());
+    }
+}
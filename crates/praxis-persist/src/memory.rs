@@ -0,0 +1,471 @@
+//! Process-local `PersistenceClient`
+//!
+//! Keeps threads, messages, attachments, outbox entries and leases in memory. Meant for
+//! tests and single-process tools; nothing survives a restart.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use praxis_core::{Clock, SystemClock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::compression::{decompress, CompressionConfig};
+use crate::error::{PersistError, Result};
+use crate::maintenance::HygieneStore;
+use crate::migrations::MigrationReport;
+use crate::models::{
+    AttachmentRef, ContentEncoding, DBMessage, HistoryIndex, MessageType, SummaryTrigger, Thread, ThreadMetadata, ThreadSummary,
+};
+use crate::outbox::{OutboxEntry, OutboxStatus, OutboxStore};
+use crate::trait_client::PersistenceClient;
+
+#[derive(Default)]
+struct MemoryState {
+    threads: Vec<Thread>,
+    messages: Vec<DBMessage>,
+    attachments: Vec<(AttachmentRef, Vec<u8>)>,
+    outbox: Vec<OutboxEntry>,
+    /// zstd content of compressed messages, by message id
+    compressed: HashMap<String, Vec<u8>>,
+    /// Lease name to owner and expiry
+    leases: HashMap<String, (String, DateTime<Utc>)>,
+}
+
+/// In-memory persistence with outbox support
+pub struct InMemoryPersistenceClient {
+    state: Mutex<MemoryState>,
+    clock: Arc<dyn Clock>,
+}
+
+impl InMemoryPersistenceClient {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MemoryState::default()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Clock for thread timestamps and outbox leases (default: system clock)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn new_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Thread {
+        let now = self.clock.now();
+        Thread {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata,
+            last_summary_update: now,
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        }
+    }
+}
+
+impl Default for InMemoryPersistenceClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryState {
+    fn thread_mut(&mut self, thread_id: &str) -> Result<&mut Thread> {
+        self.threads
+            .iter_mut()
+            .find(|thread| thread.id == thread_id)
+            .ok_or_else(|| PersistError::ThreadNotFound(thread_id.to_string()))
+    }
+
+    fn thread_by_key(&self, conversation_key: &str) -> Option<&Thread> {
+        self.threads
+            .iter()
+            .find(|thread| thread.conversation_key.as_deref() == Some(conversation_key))
+    }
+
+    /// The entry `id` if `owner` holds its lease
+    fn leased_entry(&mut self, id: &str, owner: &str) -> Option<&mut OutboxEntry> {
+        self.outbox
+            .iter_mut()
+            .find(|entry| entry.id == id && entry.status == OutboxStatus::Pending && entry.lease_owner.as_deref() == Some(owner))
+    }
+
+    /// `message` with its plain-text content
+    fn restore(&self, message: &DBMessage) -> Result<DBMessage> {
+        let mut message = message.clone();
+        if message.content_encoding == ContentEncoding::Zstd {
+            let bytes = self.compressed.get(&message.id).ok_or_else(|| {
+                PersistError::Internal(format!("message {} is zstd-encoded but has no compressed content", message.id))
+            })?;
+            message.content = decompress(bytes)?;
+        }
+        Ok(message)
+    }
+}
+
+#[async_trait]
+impl PersistenceClient for InMemoryPersistenceClient {
+    async fn save_message(&self, message: DBMessage) -> Result<()> {
+        self.state().messages.push(message);
+        Ok(())
+    }
+
+    async fn get_messages(&self, thread_id: &str) -> Result<Vec<DBMessage>> {
+        let state = self.state();
+        let mut messages = state
+            .messages
+            .iter()
+            .filter(|message| message.thread_id == thread_id)
+            .map(|message| state.restore(message))
+            .collect::<Result<Vec<DBMessage>>>()?;
+        messages.sort_by_key(|message| message.created_at);
+        Ok(messages)
+    }
+
+    async fn get_messages_after(&self, thread_id: &str, after: DateTime<Utc>) -> Result<Vec<DBMessage>> {
+        let mut messages = self.get_messages(thread_id).await?;
+        messages.retain(|message| message.created_at > after);
+        Ok(messages)
+    }
+
+    async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Result<Thread> {
+        let thread = self.new_thread(user_id, metadata);
+        self.state().threads.push(thread.clone());
+        Ok(thread)
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> Result<Option<Thread>> {
+        Ok(self.state().threads.iter().find(|thread| thread.id == thread_id).cloned())
+    }
+
+    async fn resolve_thread(&self, conversation_key: &str) -> Result<Option<Thread>> {
+        Ok(self.state().thread_by_key(conversation_key).cloned())
+    }
+
+    async fn get_or_create_thread_for_conversation(&self, conversation_key: &str, user_id: &str) -> Result<Thread> {
+        // Lookup and insert happen under one lock, so racing callers see
+        // the first caller's thread
+        let mut state = self.state();
+        if let Some(thread) = state.thread_by_key(conversation_key) {
+            if thread.user_id != user_id {
+                return Err(PersistError::ConversationKeyConflict(conversation_key.to_string()));
+            }
+            return Ok(thread.clone());
+        }
+        let mut thread = self.new_thread(user_id, ThreadMetadata::default());
+        thread.conversation_key = Some(conversation_key.to_string());
+        state.threads.push(thread.clone());
+        Ok(thread)
+    }
+
+    async fn save_thread_summary(
+        &self,
+        thread_id: &str,
+        summary: String,
+        generated_at: DateTime<Utc>,
+        trigger: SummaryTrigger,
+    ) -> Result<()> {
+        let now = self.clock.now();
+        let mut state = self.state();
+        let messages_count = state.messages.iter().filter(|message| message.thread_id == thread_id).count();
+        let thread = state.thread_mut(thread_id)?;
+        thread.summary = Some(ThreadSummary {
+            text: summary,
+            generated_at,
+            total_tokens_before_summary: 0,
+            messages_count,
+            trigger,
+        });
+        thread.last_summary_update = generated_at;
+        thread.updated_at = now;
+        Ok(())
+    }
+
+    async fn update_thread_metadata(&self, thread_id: &str, metadata: ThreadMetadata) -> Result<()> {
+        let now = self.clock.now();
+        let mut state = self.state();
+        let thread = state.thread_mut(thread_id)?;
+        thread.metadata = metadata;
+        thread.updated_at = now;
+        Ok(())
+    }
+
+    async fn save_thread_index(&self, thread_id: &str, index: HistoryIndex) -> Result<()> {
+        self.state().thread_mut(thread_id)?.history_index = Some(index);
+        Ok(())
+    }
+
+    async fn delete_thread(&self, thread_id: &str, user_id: &str) -> Result<()> {
+        let mut state = self.state();
+        let before = state.threads.len();
+        state.threads.retain(|thread| !(thread.id == thread_id && thread.user_id == user_id));
+        if state.threads.len() == before {
+            return Err(PersistError::ThreadNotFound(thread_id.to_string()));
+        }
+        let MemoryState { messages, compressed, .. } = &mut *state;
+        messages.retain(|message| {
+            let keep = message.thread_id != thread_id;
+            if !keep {
+                compressed.remove(&message.id);
+            }
+            keep
+        });
+        state.attachments.retain(|(attachment, _)| attachment.thread_id != thread_id);
+        Ok(())
+    }
+
+    async fn list_threads(&self, user_id: &str, limit: Option<i64>, skip: Option<i64>) -> Result<Vec<Thread>> {
+        let mut threads: Vec<Thread> = self
+            .state()
+            .threads
+            .iter()
+            .filter(|thread| thread.user_id == user_id)
+            .cloned()
+            .collect();
+        threads.sort_by_key(|thread| std::cmp::Reverse(thread.updated_at));
+        let skip = skip.unwrap_or(0).max(0) as usize;
+        let limit = limit.map_or(usize::MAX, |limit| limit.max(0) as usize);
+        Ok(threads.into_iter().skip(skip).take(limit).collect())
+    }
+
+    async fn save_attachment(&self, thread_id: &str, name: &str, mime_type: &str, bytes: Vec<u8>) -> Result<AttachmentRef> {
+        let attachment = AttachmentRef {
+            id: uuid::Uuid::new_v4().to_string(),
+            thread_id: thread_id.to_string(),
+            name: name.to_string(),
+            mime_type: mime_type.to_string(),
+            size: bytes.len() as u64,
+            created_at: self.clock.now(),
+        };
+        let mut state = self.state();
+        state.thread_mut(thread_id)?;
+        state.attachments.retain(|(existing, _)| !(existing.thread_id == thread_id && existing.name == name));
+        state.attachments.push((attachment.clone(), bytes));
+        Ok(attachment)
+    }
+
+    async fn list_attachments(&self, thread_id: &str) -> Result<Vec<AttachmentRef>> {
+        let mut attachments: Vec<AttachmentRef> = self
+            .state()
+            .attachments
+            .iter()
+            .filter(|(attachment, _)| attachment.thread_id == thread_id)
+            .map(|(attachment, _)| attachment.clone())
+            .collect();
+        attachments.sort_by_key(|attachment| attachment.created_at);
+        Ok(attachments)
+    }
+
+    async fn load_attachment(&self, thread_id: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .state()
+            .attachments
+            .iter()
+            .find(|(attachment, _)| attachment.thread_id == thread_id && attachment.name == name)
+            .map(|(_, bytes)| bytes.clone()))
+    }
+
+    async fn migrate(&self, dry_run: bool) -> Result<MigrationReport> {
+        // Everything in memory was written by this build
+        Ok(MigrationReport::empty(dry_run))
+    }
+}
+
+#[async_trait]
+impl OutboxStore for InMemoryPersistenceClient {
+    async fn save_messages_with_outbox(&self, messages: Vec<DBMessage>, entries: Vec<OutboxEntry>) -> Result<()> {
+        let now = self.clock.now();
+        let mut state = self.state();
+        state.messages.extend(messages);
+        state.outbox.extend(entries.into_iter().map(|mut entry| {
+            entry.created_at = now;
+            entry.next_attempt_at = now;
+            entry
+        }));
+        Ok(())
+    }
+
+    async fn lease_outbox(&self, owner: &str, limit: usize, lease: Duration) -> Result<Vec<OutboxEntry>> {
+        let now = self.clock.now();
+        let expires_at = now + chrono::Duration::from_std(lease).map_err(|e| PersistError::Other(e.to_string()))?;
+        let mut state = self.state();
+        let mut leased = Vec::new();
+        for entry in state.outbox.iter_mut().filter(|entry| entry.is_leasable(now)).take(limit) {
+            entry.attempts += 1;
+            entry.lease_owner = Some(owner.to_string());
+            entry.lease_expires_at = Some(expires_at);
+            leased.push(entry.clone());
+        }
+        Ok(leased)
+    }
+
+    async fn complete_outbox(&self, id: &str, owner: &str) -> Result<bool> {
+        let now = self.clock.now();
+        let mut state = self.state();
+        let Some(entry) = state.leased_entry(id, owner) else {
+            return Ok(false);
+        };
+        entry.status = OutboxStatus::Delivered;
+        entry.delivered_at = Some(now);
+        entry.lease_owner = None;
+        entry.lease_expires_at = None;
+        Ok(true)
+    }
+
+    async fn fail_outbox(&self, id: &str, owner: &str, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<bool> {
+        let mut state = self.state();
+        let Some(entry) = state.leased_entry(id, owner) else {
+            return Ok(false);
+        };
+        match retry_at {
+            Some(at) => entry.next_attempt_at = at,
+            None => entry.status = OutboxStatus::Failed,
+        }
+        entry.last_error = Some(error.to_string());
+        entry.lease_owner = None;
+        entry.lease_expires_at = None;
+        Ok(true)
+    }
+
+    async fn get_outbox_entry(&self, id: &str) -> Result<Option<OutboxEntry>> {
+        Ok(self.state().outbox.iter().find(|entry| entry.id == id).cloned())
+    }
+}
+
+#[async_trait]
+impl HygieneStore for InMemoryPersistenceClient {
+    async fn idle_threads(&self, idle_since: DateTime<Utc>, limit: usize) -> Result<Vec<Thread>> {
+        let mut threads: Vec<Thread> = self
+            .state()
+            .threads
+            .iter()
+            .filter(|thread| thread.archived_at.is_none() && thread.updated_at <= idle_since)
+            .cloned()
+            .collect();
+        threads.sort_by_key(|thread| thread.updated_at);
+        threads.truncate(limit);
+        Ok(threads)
+    }
+
+    async fn compress_tool_results(&self, thread_id: &str, compression: &CompressionConfig) -> Result<(usize, u64)> {
+        let mut state = self.state();
+        let MemoryState { messages, compressed, .. } = &mut *state;
+        let mut count = 0;
+        let mut saved = 0;
+        for message in messages.iter_mut().filter(|message| {
+            message.thread_id == thread_id
+                && message.message_type == MessageType::ToolResult
+                && message.content_encoding == ContentEncoding::None
+        }) {
+            if let Some(bytes) = compression.compress(&message.content) {
+                count += 1;
+                saved += (message.content.len() - bytes.len()) as u64;
+                message.content = String::new();
+                message.content_encoding = ContentEncoding::Zstd;
+                compressed.insert(message.id.clone(), bytes);
+            }
+        }
+        Ok((count, saved))
+    }
+
+    async fn archive_thread(&self, thread_id: &str, archived_at: DateTime<Utc>) -> Result<()> {
+        self.state().thread_mut(thread_id)?.archived_at = Some(archived_at);
+        Ok(())
+    }
+
+    async fn acquire_lease(&self, name: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        let now = self.clock.now();
+        let expires_at = now + chrono::Duration::from_std(ttl).map_err(|e| PersistError::Other(e.to_string()))?;
+        let mut state = self.state();
+        if let Some((holder, expires)) = state.leases.get(name) {
+            if holder != owner && *expires > now {
+                return Ok(false);
+            }
+        }
+        state.leases.insert(name.to_string(), (owner.to_string(), expires_at));
+        Ok(true)
+    }
+
+    async fn release_lease(&self, name: &str, owner: &str) -> Result<()> {
+        let mut state = self.state();
+        if state.leases.get(name).is_some_and(|(holder, _)| holder == owner) {
+            state.leases.remove(name);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_attachments_are_replaced_by_name_and_deleted_with_the_thread() {
+        let client = InMemoryPersistenceClient::new();
+        let thread = client.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+
+        client.save_attachment(&thread.id, "notes.txt", "text/plain", b"first".to_vec()).await.unwrap();
+        let saved = client.save_attachment(&thread.id, "notes.txt", "text/plain", b"second!".to_vec()).await.unwrap();
+        assert_eq!(saved.size, 7);
+        assert_eq!(client.list_attachments(&thread.id).await.unwrap(), vec![saved]);
+        assert_eq!(client.load_attachment(&thread.id, "notes.txt").await.unwrap(), Some(b"second!".to_vec()));
+        assert_eq!(client.load_attachment(&thread.id, "other.txt").await.unwrap(), None);
+
+        client.delete_thread(&thread.id, "user-1").await.unwrap();
+        assert!(client.list_attachments(&thread.id).await.unwrap().is_empty());
+        assert_eq!(client.load_attachment(&thread.id, "notes.txt").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_conversation_key_maps_to_one_thread_under_concurrency() {
+        let client = Arc::new(InMemoryPersistenceClient::new());
+        assert!(client.resolve_thread("C024BE91L").await.unwrap().is_none());
+
+        let calls = (0..16).map(|_| {
+            let client = Arc::clone(&client);
+            tokio::spawn(async move { client.get_or_create_thread_for_conversation("C024BE91L", "user-1").await })
+        });
+        let threads: Vec<Thread> = futures::future::join_all(calls)
+            .await
+            .into_iter()
+            .map(|joined| joined.unwrap().unwrap())
+            .collect();
+
+        assert!(threads.iter().all(|thread| thread.id == threads[0].id));
+        assert_eq!(client.list_threads("user-1", None, None).await.unwrap().len(), 1);
+        let resolved = client.resolve_thread("C024BE91L").await.unwrap().unwrap();
+        assert_eq!(resolved.id, threads[0].id);
+        assert_eq!(resolved.conversation_key.as_deref(), Some("C024BE91L"));
+    }
+
+    #[tokio::test]
+    async fn test_conversation_key_of_another_user_is_a_conflict() {
+        let client = InMemoryPersistenceClient::new();
+        let thread = client.get_or_create_thread_for_conversation("C024BE91L", "user-1").await.unwrap();
+
+        let result = client.get_or_create_thread_for_conversation("C024BE91L", "user-2").await;
+        assert!(matches!(result, Err(PersistError::ConversationKeyConflict(key)) if key == "C024BE91L"));
+
+        // A key that happens to look like a thread id is still just a key
+        let other = client.get_or_create_thread_for_conversation(&thread.id, "user-2").await.unwrap();
+        assert_ne!(other.id, thread.id);
+        assert_eq!(client.resolve_thread(&thread.id).await.unwrap().unwrap().id, other.id);
+    }
+
+    #[tokio::test]
+    async fn test_attachment_needs_an_existing_thread() {
+        let client = InMemoryPersistenceClient::new();
+        let result = client.save_attachment("missing", "a.txt", "text/plain", Vec::new()).await;
+        assert!(matches!(result, Err(PersistError::ThreadNotFound(_))));
+    }
+}
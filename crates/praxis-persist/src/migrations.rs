@@ -0,0 +1,284 @@
+//! Versioned schema migrations
+//!
+//! Stored documents record the `schema_version` that wrote them. A
+//! `Migration` brings the documents below its version up to it and stamps
+//! them with that version, so applying it twice changes nothing the second
+//! time. Migrations are written for one backend type `B` (the Mongo ones
+//! run against a `mongodb::Database`); a `MigrationRegistry` runs them in
+//! version order, or only counts what they would change in dry-run mode.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::error::Result;
+
+/// Schema version of the documents this build writes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One step of the stored schema, applied to backend `B`
+#[async_trait]
+pub trait Migration<B: ?Sized + Sync>: Send + Sync {
+    /// Version documents are at once migrated
+    fn version(&self) -> u32;
+
+    fn description(&self) -> &str;
+
+    /// Documents still below `version()`
+    async fn pending(&self, backend: &B) -> Result<u64>;
+
+    /// Migrate the documents below `version()` and stamp them with it,
+    /// returning how many were changed
+    async fn apply(&self, backend: &B) -> Result<u64>;
+}
+
+/// What one migration did, or would do in a dry run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationStep {
+    pub version: u32,
+    pub description: String,
+    /// Documents migrated (dry run: documents that would be)
+    pub documents: u64,
+}
+
+/// Outcome of `PersistenceClient::migrate`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    /// Every registered migration in version order, including those with
+    /// nothing left to do
+    pub steps: Vec<MigrationStep>,
+}
+
+impl MigrationReport {
+    /// Report of a backend that has nothing to migrate
+    pub fn empty(dry_run: bool) -> Self {
+        Self { dry_run, steps: Vec::new() }
+    }
+
+    pub fn documents(&self) -> u64 {
+        self.steps.iter().map(|step| step.documents).sum()
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run { "would migrate" } else { "migrated" };
+        if self.steps.is_empty() {
+            return write!(f, "no migrations");
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "v{} {}: {} {} documents", step.version, step.description, verb, step.documents)?;
+        }
+        Ok(())
+    }
+}
+
+/// Ordered set of migrations for backend `B`
+pub struct MigrationRegistry<B: ?Sized> {
+    migrations: Vec<Box<dyn Migration<B>>>,
+}
+
+impl<B: ?Sized + Sync> Default for MigrationRegistry<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: ?Sized + Sync> MigrationRegistry<B> {
+    pub fn new() -> Self {
+        Self { migrations: Vec::new() }
+    }
+
+    /// Add `migration`; registration order doesn't matter, they run by version
+    ///
+    /// Panics if a migration with the same version is already registered.
+    pub fn with_migration(mut self, migration: impl Migration<B> + 'static) -> Self {
+        let version = migration.version();
+        assert!(
+            self.migrations.iter().all(|existing| existing.version() != version),
+            "duplicate migration for schema version {}",
+            version
+        );
+        self.migrations.push(Box::new(migration));
+        self.migrations.sort_by_key(|migration| migration.version());
+        self
+    }
+
+    /// Highest registered version (0 without migrations)
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.last().map_or(0, |migration| migration.version())
+    }
+
+    /// Apply every migration in version order, or only count the documents
+    /// each one would change with `dry_run`
+    ///
+    /// Stops at the first failing migration; the ones before it stay applied.
+    pub async fn run(&self, backend: &B, dry_run: bool) -> Result<MigrationReport> {
+        let mut report = MigrationReport::empty(dry_run);
+        for migration in &self.migrations {
+            let pending = migration.pending(backend).await?;
+            let documents = if dry_run || pending == 0 {
+                pending
+            } else {
+                tracing::info!(
+                    "Migrating {} documents to schema v{}: {}",
+                    pending,
+                    migration.version(),
+                    migration.description()
+                );
+                let migrated = migration.apply(backend).await?;
+                tracing::info!("Schema v{} done: {} documents migrated", migration.version(), migrated);
+                migrated
+            };
+            report.steps.push(MigrationStep {
+                version: migration.version(),
+                description: migration.description().to_string(),
+                documents,
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+
+    /// Documents as a backend would store them
+    struct Fixtures {
+        documents: Mutex<Vec<Value>>,
+    }
+
+    fn schema_version(document: &Value) -> u64 {
+        document["schema_version"].as_u64().unwrap_or(0)
+    }
+
+    /// v2: `tags` changed from a comma-separated string to a list
+    struct SplitTags;
+
+    #[async_trait]
+    impl Migration<Fixtures> for SplitTags {
+        fn version(&self) -> u32 {
+            2
+        }
+
+        fn description(&self) -> &str {
+            "split tags into a list"
+        }
+
+        async fn pending(&self, backend: &Fixtures) -> Result<u64> {
+            let documents = backend.documents.lock().unwrap();
+            Ok(documents.iter().filter(|document| schema_version(document) < 2).count() as u64)
+        }
+
+        async fn apply(&self, backend: &Fixtures) -> Result<u64> {
+            let mut documents = backend.documents.lock().unwrap();
+            let mut migrated = 0;
+            for document in documents.iter_mut().filter(|document| schema_version(document) < 2) {
+                let tags: Vec<String> = document["tags"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect();
+                document["tags"] = json!(tags);
+                document["schema_version"] = json!(2);
+                migrated += 1;
+            }
+            Ok(migrated)
+        }
+    }
+
+    /// v1: stamp documents written before versioning
+    struct Stamp;
+
+    #[async_trait]
+    impl Migration<Fixtures> for Stamp {
+        fn version(&self) -> u32 {
+            1
+        }
+
+        fn description(&self) -> &str {
+            "record schema version"
+        }
+
+        async fn pending(&self, backend: &Fixtures) -> Result<u64> {
+            let documents = backend.documents.lock().unwrap();
+            Ok(documents.iter().filter(|document| schema_version(document) < 1).count() as u64)
+        }
+
+        async fn apply(&self, backend: &Fixtures) -> Result<u64> {
+            let mut documents = backend.documents.lock().unwrap();
+            let mut migrated = 0;
+            for document in documents.iter_mut().filter(|document| schema_version(document) < 1) {
+                document["schema_version"] = json!(1);
+                migrated += 1;
+            }
+            Ok(migrated)
+        }
+    }
+
+    fn seeded() -> Fixtures {
+        Fixtures {
+            documents: Mutex::new(vec![
+                json!({ "id": "t1", "tags": "a,b" }),
+                json!({ "id": "t2", "tags": "", "schema_version": 1 }),
+                json!({ "id": "t3", "tags": ["c"], "schema_version": 2 }),
+            ]),
+        }
+    }
+
+    fn registry() -> MigrationRegistry<Fixtures> {
+        // Registered out of order on purpose
+        MigrationRegistry::new().with_migration(SplitTags).with_migration(Stamp)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_counts_without_changing_documents() {
+        let fixtures = seeded();
+        let report = registry().run(&fixtures, true).await.unwrap();
+
+        assert!(report.dry_run);
+        let counts: Vec<(u32, u64)> = report.steps.iter().map(|step| (step.version, step.documents)).collect();
+        assert_eq!(counts, [(1, 1), (2, 2)]);
+        assert_eq!(fixtures.documents.lock().unwrap()[0], json!({ "id": "t1", "tags": "a,b" }));
+    }
+
+    #[tokio::test]
+    async fn test_migrations_run_in_order_and_rerun_changes_nothing() {
+        let fixtures = seeded();
+        let registry = registry();
+        assert_eq!(registry.latest_version(), 2);
+
+        let report = registry.run(&fixtures, false).await.unwrap();
+        assert_eq!(report.documents(), 3);
+        assert_eq!(
+            *fixtures.documents.lock().unwrap(),
+            [
+                json!({ "id": "t1", "tags": ["a", "b"], "schema_version": 2 }),
+                json!({ "id": "t2", "tags": [], "schema_version": 2 }),
+                json!({ "id": "t3", "tags": ["c"], "schema_version": 2 }),
+            ]
+        );
+
+        let before = fixtures.documents.lock().unwrap().clone();
+        let rerun = registry.run(&fixtures, false).await.unwrap();
+        assert_eq!(rerun.documents(), 0);
+        assert_eq!(rerun.steps.len(), 2);
+        assert_eq!(*fixtures.documents.lock().unwrap(), before);
+        assert_eq!(rerun.to_string(), "v1 record schema version: migrated 0 documents\nv2 split tags into a list: migrated 0 documents");
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate migration")]
+    fn test_duplicate_versions_are_rejected() {
+        let _ = MigrationRegistry::new().with_migration(Stamp).with_migration(Stamp);
+    }
+}
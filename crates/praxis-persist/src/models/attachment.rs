@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// A file uploaded to a thread, without its content
+///
+/// Names are unique within a thread: saving an attachment under a name
+/// already in use replaces the earlier file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentRef {
+    pub id: String,
+    pub thread_id: String,
+    pub name: String,
+    pub mime_type: String,
+    /// Size in bytes
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
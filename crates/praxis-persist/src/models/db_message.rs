@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use praxis_llm::types::FunctionCall;
+use praxis_llm::TokenUsage;
+use std::borrow::Cow;
+
+use crate::fingerprint::{content_sha256, ContentTokenizer};
 
 /// Database-agnostic message model
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,10 +17,67 @@ pub struct DBMessage {
     pub content: String,
     pub tool_call_id: Option<String>,
     pub tool_name: Option<String>,
+    /// Tool result rows: server that ran the call, an MCP server name or
+    /// `_local` for an in-process tool (None when the call didn't run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_server: Option<String>,
+    /// Tool result rows: the read call that checked the call's effect, for
+    /// tools configured with `verify_with`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ToolVerification>,
     pub arguments: Option<serde_json::Value>,
     pub reasoning_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub duration_ms: Option<u64>,
+    /// Name of the agent that produced the message (None for user messages)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// How the backend stores `content`; `content` itself is always plain text
+    #[serde(default)]
+    pub content_encoding: ContentEncoding,
+    /// SHA-256 of `model_text()`, set on save
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_sha256: Option<String>,
+    /// Tokens in `model_text()` per the model's tokenizer at write time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<u32>,
+    /// Reasoning rows: short summary shown while the full reasoning is collapsed.
+    /// Tool call rows: the call's activity line, when the graph persists them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Graph loop iteration that produced the message (None for user messages)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iteration: Option<u32>,
+    /// Assistant message rows: tokens the LLM call that produced it used,
+    /// as reported by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Final answers: sources the answer's `[n]` markers refer to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
+}
+
+/// A `[n]` marker in an answer, linked to the tool call that supplied the fact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Number inside the marker
+    pub marker: u32,
+    pub tool_call_id: String,
+    pub tool_name: String,
+}
+
+/// Receipt of a write tool's verification: the read call made after it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolVerification {
+    /// Read tool that was called
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    /// What the read tool returned, or its error
+    pub result: String,
+    pub passed: bool,
+    /// Why the check failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 impl Default for DBMessage {
@@ -30,15 +91,58 @@ impl Default for DBMessage {
             content: String::new(),
             tool_call_id: None,
             tool_name: None,
+            tool_server: None,
+            verification: None,
             arguments: None,
             reasoning_id: None,
             created_at: Utc::now(),
             duration_ms: None,
+            agent: None,
+            content_encoding: ContentEncoding::None,
+            content_sha256: None,
+            token_count: None,
+            summary: None,
+            iteration: None,
+            usage: None,
+            citations: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl DBMessage {
+    /// Text the model sees for this message: the arguments of a tool call,
+    /// `content` otherwise
+    pub fn model_text(&self) -> Cow<'_, str> {
+        match (self.message_type, &self.arguments) {
+            (MessageType::ToolCall, Some(arguments)) => Cow::Owned(arguments.to_string()),
+            _ => Cow::Borrowed(&self.content),
+        }
+    }
+
+    /// Set `content_sha256` and, given a tokenizer, `token_count`
+    ///
+    /// Call after any truncation so both describe what is actually stored.
+    pub fn fingerprint(&mut self, tokenizer: Option<&dyn ContentTokenizer>, model: &str) {
+        let text = self.model_text();
+        let sha256 = content_sha256(&text);
+        let tokens = tokenizer.map(|tokenizer| tokenizer.count(model, &text) as u32);
+
+        self.content_sha256 = Some(sha256);
+        if tokens.is_some() {
+            self.token_count = tokens;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentEncoding {
+    #[default]
+    None,
+    Zstd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageRole {
     User,
@@ -64,6 +168,7 @@ impl TryFrom<DBMessage> for praxis_llm::Message {
                 Ok(praxis_llm::Message::Human {
                     content: praxis_llm::Content::text(msg.content),
                     name: None,
+                    cache_hint: None,
                 })
             },
             (MessageRole::Assistant, MessageType::Message) => {
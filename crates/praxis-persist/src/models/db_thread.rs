@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use praxis_llm::Tool;
+
+use crate::fingerprint::content_sha256;
 
 /// Database-agnostic thread model
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,12 +14,88 @@ pub struct Thread {
     pub metadata: ThreadMetadata,
     pub last_summary_update: DateTime<Utc>,
     pub summary: Option<ThreadSummary>,
+    /// Cached one-line-per-message index used by sparse history strategies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_index: Option<HistoryIndex>,
+    /// Set by hygiene runs once the thread has been idle long enough
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// External key the thread is addressed by (e.g. a Slack channel id),
+    /// unique across threads; see `PersistenceClient::resolve_thread`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ThreadMetadata {
     pub title: Option<String>,
     pub tags: Vec<String>,
+    /// Server-side LLM settings that win over what clients request for this thread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_override: Option<LLMConfigOverride>,
+    /// MCP tools this thread runs with, recorded on its first run so later
+    /// changes to the server configuration don't alter its tool set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_profile: Option<ToolProfile>,
+    /// Instructions for this thread's runs, sent as a developer message
+    /// after the system prompt (see `ContextWindow::developer_prompt`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub developer_prompt: Option<String>,
+}
+
+/// The MCP tool definitions a thread is pinned to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolProfile {
+    /// Hash of the definitions; equal versions mean identical tool sets
+    pub version: String,
+    pub pinned_at: DateTime<Utc>,
+    /// Sorted by name
+    pub tools: Vec<Tool>,
+}
+
+impl ToolProfile {
+    pub fn new(mut tools: Vec<Tool>, pinned_at: DateTime<Utc>) -> Self {
+        tools.sort_by(|a, b| a.function.name.cmp(&b.function.name));
+        let definitions = serde_json::to_string(&tools).unwrap_or_default();
+        Self {
+            version: content_sha256(&definitions)[..16].to_string(),
+            pinned_at,
+            tools,
+        }
+    }
+
+    /// Names of pinned tools missing from `current`, and of current tools
+    /// not pinned
+    pub fn diff(&self, current: &[Tool]) -> (Vec<String>, Vec<String>) {
+        let has = |tools: &[Tool], name: &str| tools.iter().any(|t| t.function.name == name);
+        let removed = self
+            .tools
+            .iter()
+            .filter(|t| !has(current, &t.function.name))
+            .map(|t| t.function.name.clone())
+            .collect();
+        let mut added: Vec<String> = current
+            .iter()
+            .filter(|t| !has(&self.tools, &t.function.name))
+            .map(|t| t.function.name.clone())
+            .collect();
+        added.sort();
+        (removed, added)
+    }
+}
+
+/// Partial LLM settings; `None` fields fall through to the next routing layer
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct LLMConfigOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,5 +104,38 @@ pub struct ThreadSummary {
     pub generated_at: DateTime<Utc>,
     pub total_tokens_before_summary: usize,
     pub messages_count: usize,
+    /// What caused this summary; summaries saved before triggers existed
+    /// read as `Overflow`
+    #[serde(default)]
+    pub trigger: SummaryTrigger,
+}
+
+/// Why a thread summary was generated
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryTrigger {
+    /// The unsummarized history outgrew the context budget
+    #[default]
+    Overflow,
+    /// A configured cadence (turn count or idle gap) came due
+    Cadence,
+    /// Requested explicitly, e.g. by an operator
+    Manual,
 }
 
+
+/// Compact index of a thread's messages, refreshed incrementally
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct HistoryIndex {
+    pub entries: Vec<HistoryIndexEntry>,
+    /// Number of thread messages already covered (entries may skip some kinds)
+    pub indexed_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryIndexEntry {
+    /// 1-based position of the message in the thread
+    pub seq: usize,
+    pub message_id: String,
+    pub line: String,
+}
@@ -1,6 +1,8 @@
+mod attachment;
 mod db_message;
 mod db_thread;
 
 // Export database-agnostic models
-pub use db_message::{DBMessage, MessageRole, MessageType};
-pub use db_thread::{Thread, ThreadMetadata, ThreadSummary};
+pub use attachment::AttachmentRef;
+pub use db_message::{Citation, ContentEncoding, DBMessage, MessageRole, MessageType, ToolVerification};
+pub use db_thread::{HistoryIndex, HistoryIndexEntry, LLMConfigOverride, SummaryTrigger, Thread, ThreadMetadata, ThreadSummary, ToolProfile};
@@ -0,0 +1,403 @@
+//! Transactional outbox for side effects of persisted writes
+//!
+//! A webhook sent after a save is lost if the process dies between the two,
+//! and a webhook sent before it can announce a write that never happened.
+//! Instead, side-effect intents are written in the same batch as the
+//! messages (`OutboxStore::save_messages_with_outbox`) and an
+//! `OutboxDispatcher` delivers them afterwards, retrying failures.
+//!
+//! Dispatchers lease entries before delivering them, so replicas sharing a
+//! store don't send an entry concurrently. A dispatcher that dies while
+//! holding a lease leaves the entry pending; once the lease expires another
+//! dispatcher picks it up. Delivery is at-least-once, so handlers should use
+//! `OutboxEntry::id` as an idempotency key.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use praxis_core::{Clock, SystemClock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::error::Result;
+use crate::models::DBMessage;
+use crate::trait_client::PersistenceClient;
+
+/// Delivery state of an outbox entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    Pending,
+    Delivered,
+    /// Gave up: no handler for the kind, or out of attempts
+    Failed,
+}
+
+/// A side effect to perform once the write it belongs to is stored
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutboxEntry {
+    pub id: String,
+    /// Selects the `OutboxHandler` that delivers it (e.g. `"webhook"`)
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: OutboxStatus,
+    /// Deliveries started so far, counted when the entry is leased
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    /// Not leased before this time (set after a failed attempt)
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl OutboxEntry {
+    /// A pending entry; stores stamp `created_at` and `next_attempt_at` when
+    /// they save it
+    pub fn new(kind: impl Into<String>, payload: serde_json::Value) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.into(),
+            payload,
+            status: OutboxStatus::Pending,
+            attempts: 0,
+            created_at: now,
+            next_attempt_at: now,
+            lease_owner: None,
+            lease_expires_at: None,
+            last_error: None,
+            delivered_at: None,
+        }
+    }
+
+    /// Whether `lease_outbox` may hand this entry out at `now`
+    pub fn is_leasable(&self, now: DateTime<Utc>) -> bool {
+        self.status == OutboxStatus::Pending
+            && self.next_attempt_at <= now
+            && self.lease_expires_at.is_none_or(|expires| expires <= now)
+    }
+}
+
+/// Outbox operations of a persistence backend
+///
+/// `lease_outbox`, `complete_outbox` and `fail_outbox` must be atomic per
+/// entry, since dispatchers on several replicas call them concurrently.
+#[async_trait]
+pub trait OutboxStore: PersistenceClient {
+    /// Save `messages` and `entries` as one write: either all are stored or none
+    async fn save_messages_with_outbox(&self, messages: Vec<DBMessage>, entries: Vec<OutboxEntry>) -> Result<()>;
+
+    /// Lease up to `limit` due entries to `owner` for `lease`, oldest first
+    async fn lease_outbox(&self, owner: &str, limit: usize, lease: Duration) -> Result<Vec<OutboxEntry>>;
+
+    /// Mark a leased entry delivered
+    ///
+    /// Returns `false` (and changes nothing) if `owner` no longer holds the lease.
+    async fn complete_outbox(&self, id: &str, owner: &str) -> Result<bool>;
+
+    /// Release a leased entry after a failed delivery, to be retried at
+    /// `retry_at`, or marked failed when `retry_at` is `None`
+    ///
+    /// Returns `false` (and changes nothing) if `owner` no longer holds the lease.
+    async fn fail_outbox(&self, id: &str, owner: &str, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<bool>;
+
+    /// Get an outbox entry by ID
+    async fn get_outbox_entry(&self, id: &str) -> Result<Option<OutboxEntry>>;
+}
+
+/// Performs the side effect of one kind of outbox entry
+#[async_trait]
+pub trait OutboxHandler: Send + Sync {
+    async fn deliver(&self, entry: &OutboxEntry) -> anyhow::Result<()>;
+}
+
+/// Background delivery of outbox entries
+///
+/// Each replica runs one dispatcher with a unique `owner`. Failed deliveries
+/// are retried with exponential backoff until `max_attempts`; entries whose
+/// kind has no handler are marked failed straight away.
+pub struct OutboxDispatcher {
+    store: Arc<dyn OutboxStore>,
+    owner: String,
+    handlers: HashMap<String, Arc<dyn OutboxHandler>>,
+    clock: Arc<dyn Clock>,
+    lease: Duration,
+    batch_size: usize,
+    poll_interval: Duration,
+    max_attempts: u32,
+    retry_backoff: Duration,
+}
+
+impl OutboxDispatcher {
+    pub fn new(store: Arc<dyn OutboxStore>, owner: impl Into<String>) -> Self {
+        Self {
+            store,
+            owner: owner.into(),
+            handlers: HashMap::new(),
+            clock: Arc::new(SystemClock),
+            lease: Duration::from_secs(60),
+            batch_size: 32,
+            poll_interval: Duration::from_secs(1),
+            max_attempts: 8,
+            retry_backoff: Duration::from_secs(2),
+        }
+    }
+
+    /// Deliver entries of `kind` with `handler`
+    pub fn with_handler(mut self, kind: impl Into<String>, handler: Arc<dyn OutboxHandler>) -> Self {
+        self.handlers.insert(kind.into(), handler);
+        self
+    }
+
+    /// Clock for retry times (default: system clock)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// How long a leased entry is reserved for this dispatcher (default: 60s)
+    ///
+    /// Should exceed the slowest delivery, or a second dispatcher may send
+    /// the entry while the first is still delivering it.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Entries leased per poll (default: 32)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Wait between polls of the store (default: 1s)
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Attempts before an entry is marked failed (default: 8)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Wait before the first retry, doubled after each further failure (default: 2s)
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Lease one batch and deliver it, returning how many entries were delivered
+    pub async fn run_once(&self) -> Result<usize> {
+        let entries = self.store.lease_outbox(&self.owner, self.batch_size, self.lease).await?;
+        let mut delivered = 0;
+        for entry in entries {
+            let outcome = match self.handlers.get(&entry.kind) {
+                Some(handler) => handler.deliver(&entry).await,
+                None => Err(anyhow::anyhow!("no outbox handler for kind '{}'", entry.kind)),
+            };
+            match outcome {
+                Ok(()) => {
+                    if self.store.complete_outbox(&entry.id, &self.owner).await? {
+                        delivered += 1;
+                    } else {
+                        tracing::warn!("Outbox entry {} was delivered after its lease expired", entry.id);
+                    }
+                }
+                Err(e) => {
+                    let retry_at = (self.handlers.contains_key(&entry.kind) && entry.attempts < self.max_attempts)
+                        .then(|| self.clock.now() + self.backoff(entry.attempts));
+                    match retry_at {
+                        Some(at) => tracing::warn!("Outbox entry {} failed (attempt {}), retrying at {}: {}", entry.id, entry.attempts, at, e),
+                        None => tracing::error!("Outbox entry {} failed permanently after {} attempts: {}", entry.id, entry.attempts, e),
+                    }
+                    self.store.fail_outbox(&entry.id, &self.owner, &e.to_string(), retry_at).await?;
+                }
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Poll the store until the returned task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.run_once().await {
+                    // A full batch means more entries may be due right away
+                    Ok(delivered) if delivered == self.batch_size => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("Outbox dispatch failed: {}", e),
+                }
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        })
+    }
+
+    /// Backoff after the `attempts`-th failed attempt
+    fn backoff(&self, attempts: u32) -> chrono::Duration {
+        let backoff = self.retry_backoff.saturating_mul(1 << attempts.saturating_sub(1).min(16));
+        chrono::Duration::from_std(backoff).unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryPersistenceClient;
+    use crate::{ContentEncoding, MessageRole, MessageType, ThreadMetadata};
+    use praxis_core::testkit::FixedClock;
+    use std::sync::Mutex;
+
+    /// Records deliveries; fails while `failures` is positive
+    #[derive(Default)]
+    struct RecordingHandler {
+        delivered: Mutex<Vec<String>>,
+        failures: Mutex<u32>,
+    }
+
+    #[async_trait]
+    impl OutboxHandler for RecordingHandler {
+        async fn deliver(&self, entry: &OutboxEntry) -> anyhow::Result<()> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                anyhow::bail!("endpoint unavailable");
+            }
+            self.delivered.lock().unwrap().push(entry.id.clone());
+            Ok(())
+        }
+    }
+
+    fn clock() -> Arc<FixedClock> {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        Arc::new(FixedClock::at(start))
+    }
+
+    fn message(thread_id: &str) -> DBMessage {
+        DBMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            thread_id: thread_id.to_string(),
+            user_id: "user-1".to_string(),
+            role: MessageRole::Assistant,
+            message_type: MessageType::Message,
+            content: "Done.".to_string(),
+            tool_call_id: None,
+            tool_name: None,
+            tool_server: None,
+            verification: None,
+            arguments: None,
+            reasoning_id: None,
+            created_at: Utc::now(),
+            duration_ms: None,
+            agent: None,
+            content_encoding: ContentEncoding::None,
+            content_sha256: None,
+            token_count: None,
+            summary: None,
+            iteration: None,
+            usage: None,
+            citations: Vec::new(),
+        }
+    }
+
+    async fn store_with_intent(clock: &Arc<FixedClock>) -> (Arc<InMemoryPersistenceClient>, String) {
+        let store = Arc::new(InMemoryPersistenceClient::new().with_clock(clock.clone()));
+        let thread = store.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+        let intent = OutboxEntry::new("webhook", serde_json::json!({ "thread_id": thread.id }));
+        let intent_id = intent.id.clone();
+        store.save_messages_with_outbox(vec![message(&thread.id)], vec![intent]).await.unwrap();
+        (store, intent_id)
+    }
+
+    #[tokio::test]
+    async fn test_intent_survives_a_crash_between_lease_and_delivery() {
+        let clock = clock();
+        let (store, intent_id) = store_with_intent(&clock).await;
+        let handler = Arc::new(RecordingHandler::default());
+        let dispatcher = |owner: &str| {
+            OutboxDispatcher::new(store.clone(), owner)
+                .with_handler("webhook", handler.clone())
+                .with_clock(clock.clone())
+                .with_lease(Duration::from_secs(30))
+        };
+
+        // Replica A leases the intent and dies before delivering it
+        let leased = store.lease_outbox("replica-a", 10, Duration::from_secs(30)).await.unwrap();
+        assert_eq!(leased.len(), 1);
+
+        // While A's lease holds, replica B must not send it
+        assert_eq!(dispatcher("replica-b").run_once().await.unwrap(), 0);
+        assert!(handler.delivered.lock().unwrap().is_empty());
+
+        // Once it expires, B delivers it, exactly once
+        clock.advance(chrono::Duration::seconds(31));
+        assert_eq!(dispatcher("replica-b").run_once().await.unwrap(), 1);
+        assert_eq!(dispatcher("replica-c").run_once().await.unwrap(), 0);
+        assert_eq!(*handler.delivered.lock().unwrap(), vec![intent_id.clone()]);
+
+        let entry = store.get_outbox_entry(&intent_id).await.unwrap().unwrap();
+        assert_eq!(entry.status, OutboxStatus::Delivered);
+        assert_eq!(entry.attempts, 2);
+
+        // A's late completion is rejected
+        assert!(!store.complete_outbox(&intent_id, "replica-a").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried_after_backoff() {
+        let clock = clock();
+        let (store, intent_id) = store_with_intent(&clock).await;
+        let handler = Arc::new(RecordingHandler { failures: Mutex::new(1), ..Default::default() });
+        let dispatcher = OutboxDispatcher::new(store.clone(), "replica-a")
+            .with_handler("webhook", handler.clone())
+            .with_clock(clock.clone())
+            .with_retry_backoff(Duration::from_secs(10));
+
+        assert_eq!(dispatcher.run_once().await.unwrap(), 0);
+        let entry = store.get_outbox_entry(&intent_id).await.unwrap().unwrap();
+        assert_eq!(entry.status, OutboxStatus::Pending);
+        assert_eq!(entry.last_error.as_deref(), Some("endpoint unavailable"));
+
+        // Not due yet
+        clock.advance(chrono::Duration::seconds(5));
+        assert_eq!(dispatcher.run_once().await.unwrap(), 0);
+
+        clock.advance(chrono::Duration::seconds(5));
+        assert_eq!(dispatcher.run_once().await.unwrap(), 1);
+        assert_eq!(handler.delivered.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts_and_on_unknown_kinds() {
+        let clock = clock();
+        let (store, intent_id) = store_with_intent(&clock).await;
+        let handler = Arc::new(RecordingHandler { failures: Mutex::new(u32::MAX), ..Default::default() });
+        let dispatcher = OutboxDispatcher::new(store.clone(), "replica-a")
+            .with_handler("webhook", handler)
+            .with_clock(clock.clone())
+            .with_max_attempts(2)
+            .with_retry_backoff(Duration::from_secs(1));
+
+        dispatcher.run_once().await.unwrap();
+        clock.advance(chrono::Duration::seconds(1));
+        dispatcher.run_once().await.unwrap();
+        let entry = store.get_outbox_entry(&intent_id).await.unwrap().unwrap();
+        assert_eq!((entry.status, entry.attempts), (OutboxStatus::Failed, 2));
+
+        let unknown = OutboxEntry::new("carrier_pigeon", serde_json::Value::Null);
+        let unknown_id = unknown.id.clone();
+        store.save_messages_with_outbox(Vec::new(), vec![unknown]).await.unwrap();
+        dispatcher.run_once().await.unwrap();
+        let entry = store.get_outbox_entry(&unknown_id).await.unwrap().unwrap();
+        assert_eq!((entry.status, entry.attempts), (OutboxStatus::Failed, 1));
+    }
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use crate::models::{DBMessage, MessageRole, MessageType};
+
+/// Controls which messages are persisted
+///
+/// Defaults keep everything. Applied centrally before any `save_message`
+/// call so every backend gets the same behavior.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistencePolicy {
+    pub save_reasoning: bool,
+    pub save_tool_calls: bool,
+    pub save_tool_results: bool,
+    pub save_user_messages: bool,
+    pub save_final_messages: bool,
+    /// Truncate stored content to this many bytes (None = unlimited)
+    pub content_max_bytes: Option<usize>,
+}
+
+impl Default for PersistencePolicy {
+    fn default() -> Self {
+        Self {
+            save_reasoning: true,
+            save_tool_calls: true,
+            save_tool_results: true,
+            save_user_messages: true,
+            save_final_messages: true,
+            content_max_bytes: None,
+        }
+    }
+}
+
+impl PersistencePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_reasoning(mut self, enabled: bool) -> Self {
+        self.save_reasoning = enabled;
+        self
+    }
+
+    pub fn with_tool_calls(mut self, enabled: bool) -> Self {
+        self.save_tool_calls = enabled;
+        self
+    }
+
+    pub fn with_tool_results(mut self, enabled: bool) -> Self {
+        self.save_tool_results = enabled;
+        self
+    }
+
+    pub fn with_user_messages(mut self, enabled: bool) -> Self {
+        self.save_user_messages = enabled;
+        self
+    }
+
+    pub fn with_final_messages(mut self, enabled: bool) -> Self {
+        self.save_final_messages = enabled;
+        self
+    }
+
+    pub fn with_content_max_bytes(mut self, max: usize) -> Self {
+        self.content_max_bytes = Some(max);
+        self
+    }
+
+    /// Whether a message of this kind should be persisted
+    pub fn allows(&self, message: &DBMessage) -> bool {
+        match (&message.role, message.message_type) {
+            (_, MessageType::Reasoning) => self.save_reasoning,
+            (_, MessageType::ToolCall) => self.save_tool_calls,
+            (_, MessageType::ToolResult) => self.save_tool_results,
+            (MessageRole::User, MessageType::Message) => self.save_user_messages,
+            (MessageRole::Assistant, MessageType::Message) => self.save_final_messages,
+        }
+    }
+
+    /// Apply the policy: drop disallowed messages and truncate content
+    pub fn apply(&self, mut message: DBMessage) -> Option<DBMessage> {
+        if !self.allows(&message) {
+            return None;
+        }
+
+        if let Some(max) = self.content_max_bytes {
+            if message.content.len() > max {
+                let mut end = max;
+                while !message.content.is_char_boundary(end) {
+                    end -= 1;
+                }
+                message.content.truncate(end);
+            }
+        }
+
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, message_type: MessageType, content: &str) -> DBMessage {
+        DBMessage {
+            role,
+            message_type,
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_policy_keeps_everything() {
+        let policy = PersistencePolicy::default();
+        for message_type in [MessageType::Message, MessageType::Reasoning, MessageType::ToolCall, MessageType::ToolResult] {
+            assert!(policy.allows(&message(MessageRole::Assistant, message_type, "x")));
+        }
+        assert!(policy.allows(&message(MessageRole::User, MessageType::Message, "x")));
+    }
+
+    #[test]
+    fn test_disabled_kinds_are_dropped() {
+        let policy = PersistencePolicy::new().with_reasoning(false).with_user_messages(false);
+
+        assert!(policy.apply(message(MessageRole::Assistant, MessageType::Reasoning, "x")).is_none());
+        assert!(policy.apply(message(MessageRole::User, MessageType::Message, "x")).is_none());
+        assert!(policy.apply(message(MessageRole::Assistant, MessageType::Message, "x")).is_some());
+    }
+
+    #[test]
+    fn test_content_truncated_on_char_boundary() {
+        let policy = PersistencePolicy::new().with_content_max_bytes(3);
+        let saved = policy.apply(message(MessageRole::Assistant, MessageType::Message, "aéb")).unwrap();
+        assert_eq!(saved.content, "aé");
+
+        let saved = policy.apply(message(MessageRole::Assistant, MessageType::Message, "éé")).unwrap();
+        assert_eq!(saved.content, "é");
+    }
+}
@@ -1,16 +1,37 @@
 use async_trait::async_trait;
+use std::sync::Arc;
 use chrono::{DateTime, Utc};
-use crate::models::{DBMessage, Thread, ThreadMetadata};
-use crate::error::Result;
+use crate::models::{AttachmentRef, DBMessage, HistoryIndex, SummaryTrigger, Thread, ThreadMetadata};
+use crate::error::{PersistError, Result};
+use crate::invariants::check_message_invariants;
+use crate::migrations::MigrationReport;
 
 /// Trait for database persistence operations
 /// 
-/// Implementations provide database-specific CRUD operations
+/// Implementations provide database-specific CRUD operations. Strategies
+/// and tools keep an `Arc<dyn PersistenceClient>` and move it into spawned
+/// tasks, so implementations must be `Send + Sync + 'static`.
 #[async_trait]
 pub trait PersistenceClient: Send + Sync {
     /// Save a single message to the database
     async fn save_message(&self, message: DBMessage) -> Result<()>;
     
+    /// Save several messages, in order
+    async fn save_messages(&self, messages: Vec<DBMessage>) -> Result<()> {
+        for message in messages {
+            self.save_message(message).await?;
+        }
+        Ok(())
+    }
+    
+    /// Wait until every message saved so far for `thread_id` is readable
+    ///
+    /// Clients that write synchronously are already consistent once
+    /// `save_message` returns; clients that queue writes must override this.
+    async fn flush(&self, _thread_id: &str) -> Result<()> {
+        Ok(())
+    }
+    
     /// Get all messages for a thread
     async fn get_messages(&self, thread_id: &str) -> Result<Vec<DBMessage>>;
     
@@ -21,21 +42,97 @@ pub trait PersistenceClient: Send + Sync {
         after: chrono::DateTime<chrono::Utc>,
     ) -> Result<Vec<DBMessage>>;
     
+    /// Sum of the stored `token_count` of a thread's messages
+    ///
+    /// Messages saved without a count add nothing; reindex old threads to
+    /// backfill them. Backends should override this with a server-side sum.
+    async fn thread_token_total(&self, thread_id: &str) -> Result<u64> {
+        Ok(self
+            .get_messages(thread_id)
+            .await?
+            .iter()
+            .filter_map(|message| message.token_count)
+            .map(u64::from)
+            .sum())
+    }
+    
+    /// Number of stored messages in a thread
+    ///
+    /// Backends should override this with a server-side count.
+    async fn count_messages(&self, thread_id: &str) -> Result<u64> {
+        Ok(self.get_messages(thread_id).await?.len() as u64)
+    }
+    
     /// Create a new thread
     async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Result<Thread>;
     
+    /// Create a thread that already contains `messages` (e.g. imported history)
+    ///
+    /// The messages are checked with `check_message_invariants` first and
+    /// get the new thread's id and `user_id`. Backends with transactions
+    /// should override this to write thread and messages atomically; this
+    /// default deletes the thread again if saving the messages fails.
+    async fn create_thread_with_messages(
+        &self,
+        user_id: &str,
+        metadata: ThreadMetadata,
+        mut messages: Vec<DBMessage>,
+    ) -> Result<Thread> {
+        check_message_invariants(&messages)?;
+        
+        let thread = self.create_thread(user_id, metadata).await?;
+        for message in &mut messages {
+            message.thread_id = thread.id.clone();
+            message.user_id = user_id.to_string();
+        }
+        if let Err(e) = self.save_messages(messages).await {
+            let _ = self.delete_thread(&thread.id, user_id).await;
+            return Err(e);
+        }
+        Ok(thread)
+    }
+    
     /// Get a thread by ID
     async fn get_thread(&self, thread_id: &str) -> Result<Option<Thread>>;
     
+    /// Thread addressed by the external `conversation_key`, if one was
+    /// created for it
+    ///
+    /// Backends without conversation keys return an error.
+    async fn resolve_thread(&self, _conversation_key: &str) -> Result<Option<Thread>> {
+        Err(PersistError::Other("conversation keys are not supported by this backend".to_string()))
+    }
+    
+    /// Thread of `conversation_key`, created for `user_id` on first use
+    ///
+    /// Idempotent, also under concurrency: callers racing on a new key all
+    /// get the one thread that was created. A key whose thread belongs to
+    /// another user fails with `PersistError::ConversationKeyConflict`.
+    async fn get_or_create_thread_for_conversation(&self, _conversation_key: &str, _user_id: &str) -> Result<Thread> {
+        Err(PersistError::Other("conversation keys are not supported by this backend".to_string()))
+    }
+    
     /// Save a thread summary
+    ///
+    /// `generated_at` also moves the thread's `last_summary_update`, so
+    /// messages after it form the next unsummarized window.
     async fn save_thread_summary(
         &self,
         thread_id: &str,
         summary: String,
         generated_at: DateTime<Utc>,
+        trigger: SummaryTrigger,
     ) -> Result<()>;
     
+    /// Replace the metadata of a thread
+    async fn update_thread_metadata(&self, thread_id: &str, metadata: ThreadMetadata) -> Result<()>;
+    
+    /// Save the cached history index of a thread
+    async fn save_thread_index(&self, thread_id: &str, index: HistoryIndex) -> Result<()>;
+    
     /// Delete a thread
+    ///
+    /// Backends that store attachments delete them with the thread.
     async fn delete_thread(&self, thread_id: &str, user_id: &str) -> Result<()>;
     
     /// List threads for a user
@@ -45,5 +142,138 @@ pub trait PersistenceClient: Send + Sync {
         limit: Option<i64>,
         skip: Option<i64>,
     ) -> Result<Vec<Thread>>;
+    
+    /// Store a file uploaded to a thread, replacing any attachment of the
+    /// same name
+    ///
+    /// Backends without attachment storage return an error.
+    async fn save_attachment(
+        &self,
+        _thread_id: &str,
+        _name: &str,
+        _mime_type: &str,
+        _bytes: Vec<u8>,
+    ) -> Result<AttachmentRef> {
+        Err(PersistError::Other("attachments are not supported by this backend".to_string()))
+    }
+    
+    /// Attachments of a thread, oldest first
+    async fn list_attachments(&self, _thread_id: &str) -> Result<Vec<AttachmentRef>> {
+        Ok(Vec::new())
+    }
+    
+    /// Content of the attachment `name`, if the thread has one
+    async fn load_attachment(&self, _thread_id: &str, _name: &str) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+    
+    /// Bring stored documents up to `SCHEMA_VERSION`, or with `dry_run`
+    /// only count what each migration would change
+    ///
+    /// Safe to run again: documents already migrated are skipped. Backends
+    /// without versioned storage return an error.
+    async fn migrate(&self, _dry_run: bool) -> Result<MigrationReport> {
+        Err(PersistError::Other("schema migrations are not supported by this backend".to_string()))
+    }
 }
 
+
+/// Forwards every method, including the defaulted ones, so an `Arc` keeps
+/// the overrides of the client it wraps
+#[async_trait]
+impl<T: PersistenceClient + ?Sized> PersistenceClient for Arc<T> {
+    async fn save_message(&self, message: DBMessage) -> Result<()> {
+        (**self).save_message(message).await
+    }
+
+    async fn save_messages(&self, messages: Vec<DBMessage>) -> Result<()> {
+        (**self).save_messages(messages).await
+    }
+
+    async fn flush(&self, thread_id: &str) -> Result<()> {
+        (**self).flush(thread_id).await
+    }
+
+    async fn get_messages(&self, thread_id: &str) -> Result<Vec<DBMessage>> {
+        (**self).get_messages(thread_id).await
+    }
+
+    async fn get_messages_after(&self, thread_id: &str, after: DateTime<Utc>) -> Result<Vec<DBMessage>> {
+        (**self).get_messages_after(thread_id, after).await
+    }
+
+    async fn thread_token_total(&self, thread_id: &str) -> Result<u64> {
+        (**self).thread_token_total(thread_id).await
+    }
+
+    async fn count_messages(&self, thread_id: &str) -> Result<u64> {
+        (**self).count_messages(thread_id).await
+    }
+
+    async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Result<Thread> {
+        (**self).create_thread(user_id, metadata).await
+    }
+
+    async fn create_thread_with_messages(
+        &self,
+        user_id: &str,
+        metadata: ThreadMetadata,
+        messages: Vec<DBMessage>,
+    ) -> Result<Thread> {
+        (**self).create_thread_with_messages(user_id, metadata, messages).await
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> Result<Option<Thread>> {
+        (**self).get_thread(thread_id).await
+    }
+
+    async fn resolve_thread(&self, conversation_key: &str) -> Result<Option<Thread>> {
+        (**self).resolve_thread(conversation_key).await
+    }
+
+    async fn get_or_create_thread_for_conversation(&self, conversation_key: &str, user_id: &str) -> Result<Thread> {
+        (**self).get_or_create_thread_for_conversation(conversation_key, user_id).await
+    }
+
+    async fn save_thread_summary(
+        &self,
+        thread_id: &str,
+        summary: String,
+        generated_at: DateTime<Utc>,
+        trigger: SummaryTrigger,
+    ) -> Result<()> {
+        (**self).save_thread_summary(thread_id, summary, generated_at, trigger).await
+    }
+
+    async fn update_thread_metadata(&self, thread_id: &str, metadata: ThreadMetadata) -> Result<()> {
+        (**self).update_thread_metadata(thread_id, metadata).await
+    }
+
+    async fn save_thread_index(&self, thread_id: &str, index: HistoryIndex) -> Result<()> {
+        (**self).save_thread_index(thread_id, index).await
+    }
+
+    async fn delete_thread(&self, thread_id: &str, user_id: &str) -> Result<()> {
+        (**self).delete_thread(thread_id, user_id).await
+    }
+
+    async fn list_threads(&self, user_id: &str, limit: Option<i64>, skip: Option<i64>) -> Result<Vec<Thread>> {
+        (**self).list_threads(user_id, limit, skip).await
+    }
+
+    async fn save_attachment(&self, thread_id: &str, name: &str, mime_type: &str, bytes: Vec<u8>) -> Result<AttachmentRef> {
+        (**self).save_attachment(thread_id, name, mime_type, bytes).await
+    }
+
+    async fn list_attachments(&self, thread_id: &str) -> Result<Vec<AttachmentRef>> {
+        (**self).list_attachments(thread_id).await
+    }
+
+    async fn load_attachment(&self, thread_id: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        (**self).load_attachment(thread_id, name).await
+    }
+
+    async fn migrate(&self, dry_run: bool) -> Result<MigrationReport> {
+        (**self).migrate(dry_run).await
+    }
+}
@@ -0,0 +1,287 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use praxis_mcp::{LocalTool, ToolResponse};
+use rhai::{Dynamic, Engine, EvalAltResult};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Resource limits applied to every `evaluate` call
+#[derive(Debug, Clone)]
+pub struct EvaluateLimits {
+    /// Maximum number of Rhai operations (CPU bound)
+    pub max_operations: u64,
+    /// Wall-clock budget for a single evaluation
+    pub max_duration: Duration,
+    /// Maximum length of any string value (memory bound)
+    pub max_string_size: usize,
+    /// Maximum number of elements in any array (memory bound)
+    pub max_array_size: usize,
+    /// Maximum number of properties in any object map (memory bound)
+    pub max_map_size: usize,
+    /// Maximum function call nesting depth
+    pub max_call_levels: usize,
+}
+
+impl Default for EvaluateLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 1_000_000,
+            max_duration: Duration::from_secs(2),
+            max_string_size: 64 * 1024,
+            max_array_size: 10_000,
+            max_map_size: 10_000,
+            max_call_levels: 32,
+        }
+    }
+}
+
+impl EvaluateLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_operations(mut self, max: u64) -> Self {
+        self.max_operations = max;
+        self
+    }
+
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = duration;
+        self
+    }
+
+    pub fn with_max_string_size(mut self, max: usize) -> Self {
+        self.max_string_size = max;
+        self
+    }
+
+    pub fn with_max_array_size(mut self, max: usize) -> Self {
+        self.max_array_size = max;
+        self
+    }
+
+    pub fn with_max_map_size(mut self, max: usize) -> Self {
+        self.max_map_size = max;
+        self
+    }
+
+    pub fn with_max_call_levels(mut self, max: usize) -> Self {
+        self.max_call_levels = max;
+        self
+    }
+}
+
+/// Sandboxed `evaluate` tool backed by the Rhai scripting engine
+///
+/// Scripts run on a blocking thread with operation, time and memory limits.
+/// Module imports are disabled and Rhai has no filesystem or network access,
+/// so the only observable effects are the returned value and `print` output.
+///
+/// # Examples
+///
+/// ```no_run
+/// use praxis_mcp::MCPToolExecutor;
+/// use praxis_tools::EvaluateTool;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let executor = MCPToolExecutor::new();
+/// executor.register_local_tool(EvaluateTool::new()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EvaluateTool {
+    limits: EvaluateLimits,
+}
+
+impl EvaluateTool {
+    pub const NAME: &'static str = "evaluate";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limits(mut self, limits: EvaluateLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn limits(&self) -> &EvaluateLimits {
+        &self.limits
+    }
+
+    /// Evaluate a script synchronously, returning captured output
+    pub fn evaluate(&self, script: &str) -> Result<String> {
+        let printed = Arc::new(Mutex::new(Vec::new()));
+        let engine = self.build_engine(Arc::clone(&printed));
+
+        let value = engine
+            .eval::<Dynamic>(script)
+            .map_err(|e| self.map_error(*e))?;
+
+        let mut output = printed.lock().map(|lines| lines.join("\n")).unwrap_or_default();
+        if !value.is_unit() {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&value.to_string());
+        }
+        Ok(output)
+    }
+
+    fn build_engine(&self, printed: Arc<Mutex<Vec<String>>>) -> Engine {
+        let limits = &self.limits;
+        let mut engine = Engine::new();
+
+        engine.set_max_operations(limits.max_operations);
+        engine.set_max_string_size(limits.max_string_size);
+        engine.set_max_array_size(limits.max_array_size);
+        engine.set_max_map_size(limits.max_map_size);
+        engine.set_max_call_levels(limits.max_call_levels);
+        engine.set_max_modules(0);
+        engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+
+        let started = Instant::now();
+        let max_duration = limits.max_duration;
+        engine.on_progress(move |_| {
+            if started.elapsed() > max_duration {
+                Some(Dynamic::from("timeout"))
+            } else {
+                None
+            }
+        });
+
+        let print_sink = Arc::clone(&printed);
+        engine.on_print(move |text| {
+            if let Ok(mut lines) = print_sink.lock() {
+                lines.push(text.to_string());
+            }
+        });
+        engine.on_debug(move |text, _, _| {
+            if let Ok(mut lines) = printed.lock() {
+                lines.push(text.to_string());
+            }
+        });
+
+        engine
+    }
+
+    fn map_error(&self, error: EvalAltResult) -> anyhow::Error {
+        match error {
+            EvalAltResult::ErrorTerminated(..) => anyhow!(
+                "Evaluation exceeded the time limit of {} ms",
+                self.limits.max_duration.as_millis()
+            ),
+            EvalAltResult::ErrorTooManyOperations(..) => anyhow!(
+                "Evaluation exceeded the limit of {} operations",
+                self.limits.max_operations
+            ),
+            other => anyhow!("Evaluation failed: {}", other),
+        }
+    }
+}
+
+#[async_trait]
+impl LocalTool for EvaluateTool {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Evaluate a Rhai script in a sandbox and return the value of the last expression \
+             (plus any print() output). Use it for arithmetic and data munging. \
+             Rhai syntax is Rust-like: `let x = 40; x + 2`. \
+             Limits: {} operations, {} ms, strings up to {} bytes, arrays/maps up to {} items. \
+             No filesystem, network or module imports.",
+            self.limits.max_operations,
+            self.limits.max_duration.as_millis(),
+            self.limits.max_string_size,
+            self.limits.max_array_size,
+        )
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "script": {
+                    "type": "string",
+                    "description": "Rhai source code to evaluate"
+                }
+            },
+            "required": ["script"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Vec<ToolResponse>> {
+        let script = arguments
+            .get("script")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Missing required string argument 'script'"))?
+            .to_string();
+
+        let tool = self.clone();
+        let text = tokio::task::spawn_blocking(move || tool.evaluate(&script)).await??;
+
+        Ok(vec![ToolResponse::Text { text }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluates_expression() {
+        let tool = EvaluateTool::new();
+        assert_eq!(tool.evaluate("let x = 40; x + 2").unwrap(), "42");
+    }
+
+    #[test]
+    fn test_captures_print_output() {
+        let tool = EvaluateTool::new();
+        assert_eq!(tool.evaluate("print(\"hi\"); 1 + 1").unwrap(), "hi\n2");
+    }
+
+    #[test]
+    fn test_infinite_loop_is_terminated() {
+        let tool = EvaluateTool::new().with_limits(EvaluateLimits::new().with_max_operations(10_000));
+        let err = tool.evaluate("loop { }").unwrap_err();
+        assert!(err.to_string().contains("10000 operations"), "{}", err);
+    }
+
+    #[test]
+    fn test_timeout_is_enforced() {
+        let limits = EvaluateLimits::new()
+            .with_max_operations(0)
+            .with_max_duration(Duration::from_millis(50));
+        let tool = EvaluateTool::new().with_limits(limits);
+
+        let started = Instant::now();
+        let err = tool.evaluate("let i = 0; while true { i += 1; }").unwrap_err();
+
+        assert!(err.to_string().contains("time limit"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_string_size_is_limited() {
+        let tool = EvaluateTool::new().with_limits(EvaluateLimits::new().with_max_string_size(16));
+        assert!(tool.evaluate("let s = \"\"; for i in 0..100 { s += \"x\"; } s").is_err());
+    }
+
+    #[test]
+    fn test_imports_are_disabled() {
+        let tool = EvaluateTool::new();
+        assert!(tool.evaluate("import \"/etc/passwd\" as m; 1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_call_reports_missing_script() {
+        let tool = EvaluateTool::new();
+        assert!(tool.call(serde_json::json!({})).await.is_err());
+    }
+}
@@ -0,0 +1,11 @@
+//! Built-in local tools for Praxis agents
+//!
+//! Local tools run in-process and are registered on the `MCPToolExecutor`
+//! with `register_local_tool`, so simple capabilities don't require an
+//! external MCP server.
+
+#[cfg(feature = "evaluate")]
+pub mod evaluate;
+
+#[cfg(feature = "evaluate")]
+pub use evaluate::{EvaluateLimits, EvaluateTool};
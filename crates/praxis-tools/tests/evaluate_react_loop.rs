@@ -0,0 +1,124 @@
+//! Runs the `evaluate` tool through the full ReAct loop with a scripted LLM
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use praxis_graph::{Graph, GraphConfig, GraphInput, LLMConfig, StreamEvent};
+use praxis_llm::{
+    ChatClient, ChatRequest, ChatResponse, LLMClient, Message, ReasoningClient, ResponseOutput,
+    ResponseRequest,
+};
+use praxis_mcp::MCPToolExecutor;
+use praxis_tools::{EvaluateLimits, EvaluateTool};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<praxis_llm::StreamEvent>> + Send>>;
+
+/// LLM that replays one scripted list of stream events per call
+struct ScriptedLLM {
+    turns: Mutex<VecDeque<Vec<praxis_llm::StreamEvent>>>,
+}
+
+impl ScriptedLLM {
+    fn calling_evaluate(script: &str) -> Self {
+        let arguments = serde_json::json!({ "script": script }).to_string();
+        let turns = vec![
+            vec![
+                praxis_llm::StreamEvent::ToolCall {
+                    index: 0,
+                    id: Some("call_1".to_string()),
+                    name: Some("evaluate".to_string()),
+                    arguments: Some(arguments),
+                },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("tool_calls".to_string()), choice_index: None },
+            ],
+            vec![
+                praxis_llm::StreamEvent::Message { content: "Done.".to_string(), choice_index: None, logprobs: None },
+                praxis_llm::StreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None },
+            ],
+        ];
+        Self { turns: Mutex::new(turns.into()) }
+    }
+}
+
+#[async_trait]
+impl ChatClient for ScriptedLLM {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn chat_stream(&self, _request: ChatRequest) -> Result<EventStream> {
+        let events = self.turns.lock().unwrap().pop_front().unwrap_or_default();
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for ScriptedLLM {
+    async fn reason(&self, _request: ResponseRequest) -> Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(&self, _request: ResponseRequest) -> Result<EventStream> {
+        anyhow::bail!("not scripted")
+    }
+}
+
+impl LLMClient for ScriptedLLM {}
+
+/// Run one conversation and return the (result, is_error) of the tool call
+async fn run_with_script(tool: EvaluateTool, script: &str) -> (String, bool) {
+    let executor = MCPToolExecutor::new();
+    executor.register_local_tool(tool).await.unwrap();
+
+    let graph = Graph::new(
+        Arc::new(ScriptedLLM::calling_evaluate(script)),
+        Arc::new(executor),
+        GraphConfig::default(),
+    );
+    let input = GraphInput::new(
+        "conversation-1",
+        vec![Message::human("compute something")],
+        LLMConfig::new("mock-model"),
+    );
+
+    let mut events = graph.spawn_run(input, None);
+    let mut tool_result = None;
+    while let Some(event) = events.recv().await {
+        match event {
+            StreamEvent::ToolResult { result, is_error, .. } => tool_result = Some((result, is_error)),
+            StreamEvent::EndStream { .. } => break,
+            StreamEvent::Error { message, .. } => panic!("graph error: {}", message),
+            _ => {}
+        }
+    }
+    tool_result.expect("tool result event")
+}
+
+#[tokio::test]
+async fn test_sample_computation_is_deterministic() {
+    let script = "let total = 0; for i in 1..=100 { total += i * i; } total";
+
+    let first = run_with_script(EvaluateTool::new(), script).await;
+    let second = run_with_script(EvaluateTool::new(), script).await;
+
+    assert_eq!(first, ("338350".to_string(), false));
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn test_infinite_loop_is_reported_as_tool_error() {
+    let tool = EvaluateTool::new().with_limits(
+        EvaluateLimits::new()
+            .with_max_operations(0)
+            .with_max_duration(Duration::from_millis(100)),
+    );
+
+    let (result, is_error) = run_with_script(tool, "loop { }").await;
+
+    assert!(is_error);
+    assert!(result.contains("time limit"), "{}", result);
+}
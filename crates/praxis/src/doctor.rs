@@ -0,0 +1,329 @@
+//! Configuration self-check
+//!
+//! `doctor` exercises every dependency an agent needs before the first
+//! request does: a write/read/delete probe of the persistence backend, a
+//! tool listing of each MCP server, a 1-token call per LLM provider (only
+//! with `DoctorConfig::spending`, since it costs money), the model names
+//! against `ModelCapabilities`, and the observer's credentials. Each check
+//! reports a status, its latency and, when it fails, a remediation hint.
+
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use futures::future::{self, BoxFuture, FutureExt};
+use serde::Serialize;
+
+use praxis_llm::{ChatOptions, ChatRequest, LLMClient, Message, ModelCapabilities};
+use praxis_mcp::{MCPClient, StdioServerConfig};
+use praxis_persist::{DBMessage, MessageRole, MessageType, PersistenceClient, ThreadMetadata};
+
+/// How long a check may take before it fails (default)
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// User id of the thread the persistence probe writes
+const PROBE_USER: &str = "praxis-doctor";
+
+/// An MCP server to connect to
+#[derive(Debug, Clone)]
+pub enum McpServerTarget {
+    Http { name: String, url: String },
+    Stdio(StdioServerConfig),
+}
+
+impl McpServerTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Http { name, .. } => name,
+            Self::Stdio(server) => &server.name,
+        }
+    }
+
+    pub async fn connect(&self) -> Result<MCPClient> {
+        match self {
+            Self::Http { name, url } => MCPClient::new_http(name, url).await,
+            Self::Stdio(server) => server.spawn().await,
+        }
+    }
+}
+
+/// What `doctor` checks
+pub struct DoctorConfig {
+    persistence: Option<Arc<dyn PersistenceClient>>,
+    mcp_servers: Vec<McpServerTarget>,
+    /// Provider name, client and the model its probe call uses
+    providers: Vec<(String, Arc<dyn LLMClient>, String)>,
+    models: Vec<String>,
+    #[cfg(feature = "langfuse")]
+    langfuse: Option<crate::LangfuseClient>,
+    spending: bool,
+    timeout: Duration,
+}
+
+impl Default for DoctorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DoctorConfig {
+    pub fn new() -> Self {
+        Self {
+            persistence: None,
+            mcp_servers: Vec::new(),
+            providers: Vec::new(),
+            models: Vec::new(),
+            #[cfg(feature = "langfuse")]
+            langfuse: None,
+            spending: false,
+            timeout: DEFAULT_CHECK_TIMEOUT,
+        }
+    }
+
+    pub fn with_persistence(mut self, client: Arc<dyn PersistenceClient>) -> Self {
+        self.persistence = Some(client);
+        self
+    }
+
+    pub fn with_mcp_server(mut self, server: McpServerTarget) -> Self {
+        self.mcp_servers.push(server);
+        self
+    }
+
+    /// Probe `client` with a 1-token call to `model` (see `spending`);
+    /// `model` is checked against the capabilities registry either way
+    pub fn with_provider(mut self, name: impl Into<String>, client: Arc<dyn LLMClient>, model: impl Into<String>) -> Self {
+        let model = model.into();
+        self.models.push(model.clone());
+        self.providers.push((name.into(), client, model));
+        self
+    }
+
+    /// Check a configured model name against the capabilities registry
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.models.push(model.into());
+        self
+    }
+
+    #[cfg(feature = "langfuse")]
+    pub fn with_langfuse(mut self, client: crate::LangfuseClient) -> Self {
+        self.langfuse = Some(client);
+        self
+    }
+
+    /// Make the provider calls, which cost money (default: skipped)
+    pub fn spending(mut self, spending: bool) -> Self {
+        self.spending = spending;
+        self
+    }
+
+    /// Time limit of each check (default `DEFAULT_CHECK_TIMEOUT`)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but likely not as intended
+    Warn,
+    Fail,
+    /// Not run (see `detail`)
+    Skipped,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    /// e.g. `persistence`, `mcp:search`, `llm:openai`, `model:gpt-4o`
+    pub name: String,
+    pub status: CheckStatus,
+    /// A failure makes the configuration unusable (`DoctorReport::passed`)
+    pub critical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    pub detail: String,
+    /// What to fix, for failures and warnings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// No critical check failed
+    pub fn passed(&self) -> bool {
+        !self.checks.iter().any(|check| check.critical && check.status == CheckStatus::Fail)
+    }
+
+    /// Critical checks that failed
+    pub fn critical_failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|check| check.critical && check.status == CheckStatus::Fail)
+    }
+
+    /// One row per check, with hints under the rows that have one
+    pub fn to_table(&self) -> String {
+        let width = self.checks.iter().map(|check| check.name.len()).max().unwrap_or(0).max("CHECK".len());
+        let mut table = format!("{:<width$}  {:<7}  {:>8}  DETAIL\n", "CHECK", "STATUS", "LATENCY");
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail if check.critical => "FAIL",
+                CheckStatus::Fail => "fail",
+                CheckStatus::Skipped => "skipped",
+            };
+            let latency = check.latency_ms.map(|ms| format!("{}ms", ms)).unwrap_or_default();
+            let _ = writeln!(table, "{:<width$}  {:<7}  {:>8}  {}", check.name, status, latency, check.detail);
+            if let Some(hint) = &check.hint {
+                let _ = writeln!(table, "{:<width$}  -> {}", "", hint);
+            }
+        }
+        table
+    }
+}
+
+/// Run every check `config` describes, concurrently
+///
+/// The report lists them in a fixed order: persistence, MCP servers,
+/// providers, models, observer.
+pub async fn doctor(config: DoctorConfig) -> DoctorReport {
+    let timeout = config.timeout;
+    let mut checks: Vec<BoxFuture<'static, CheckResult>> = Vec::new();
+
+    if let Some(client) = config.persistence {
+        let hint = "Check the persistence backend's URI and credentials, and that it is reachable";
+        checks.push(timed(timeout, "persistence", hint, async move { probe_persistence(client.as_ref()).await }).boxed());
+    }
+
+    for server in config.mcp_servers {
+        let hint = match &server {
+            McpServerTarget::Http { url, .. } => format!("Check that an MCP server listens at {}", url),
+            McpServerTarget::Stdio(server) => format!("Check that `{}` runs and speaks MCP on stdio", server.command),
+        };
+        let name = format!("mcp:{}", server.name());
+        checks.push(timed(timeout, name, hint, async move { probe_mcp_server(&server).await }).boxed());
+    }
+
+    for (provider, client, model) in config.providers {
+        let name = format!("llm:{}", provider);
+        if !config.spending {
+            checks.push(
+                future::ready(CheckResult {
+                    name,
+                    status: CheckStatus::Skipped,
+                    critical: true,
+                    latency_ms: None,
+                    detail: "costs a 1-token call; enable spending to run it".to_string(),
+                    hint: None,
+                })
+                .boxed(),
+            );
+            continue;
+        }
+        let hint = format!("Check the {} API key and that the account can use {}", provider, model);
+        checks.push(timed(timeout, name, hint, probe_provider(client, model)).boxed());
+    }
+
+    let mut models = config.models;
+    models.sort();
+    models.dedup();
+    checks.extend(models.into_iter().map(|model| future::ready(check_model(model)).boxed()));
+
+    #[cfg(feature = "langfuse")]
+    if let Some(client) = config.langfuse {
+        let hint = "Check LANGFUSE_PUBLIC_KEY, LANGFUSE_SECRET_KEY and LANGFUSE_HOST";
+        let check = async move { client.check_credentials().await.map(|()| "credentials accepted".to_string()) };
+        checks.push(timed(timeout, "observer:langfuse", hint, check).boxed());
+    }
+
+    DoctorReport { checks: future::join_all(checks).await }
+}
+
+/// Run critical `check` within `timeout`; its `Ok` is the detail of a passed check
+async fn timed(
+    timeout: Duration,
+    name: impl Into<String>,
+    hint: impl Into<String>,
+    check: impl Future<Output = Result<String>>,
+) -> CheckResult {
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(timeout, check).await;
+    let latency_ms = Some(start.elapsed().as_millis() as u64);
+
+    let (status, detail, hint) = match outcome {
+        Ok(Ok(detail)) => (CheckStatus::Ok, detail, None),
+        Ok(Err(e)) => (CheckStatus::Fail, format!("{:#}", e), Some(hint.into())),
+        Err(_) => (CheckStatus::Fail, format!("no answer within {:?}", timeout), Some(hint.into())),
+    };
+    CheckResult { name: name.into(), status, critical: true, latency_ms, detail, hint }
+}
+
+/// Create a thread, write a message, read it back, delete the thread
+async fn probe_persistence(client: &dyn PersistenceClient) -> Result<String> {
+    let thread = client.create_thread(PROBE_USER, ThreadMetadata::default()).await?;
+    let written = DBMessage {
+        thread_id: thread.id.clone(),
+        user_id: PROBE_USER.to_string(),
+        role: MessageRole::User,
+        message_type: MessageType::Message,
+        content: "doctor probe".to_string(),
+        ..Default::default()
+    };
+    let probe = async {
+        client.save_message(written.clone()).await?;
+        client.flush(&thread.id).await?;
+        let read = client.get_messages(&thread.id).await?;
+        anyhow::ensure!(
+            read.iter().any(|message| message.id == written.id && message.content == written.content),
+            "the probe message was not read back"
+        );
+        Ok(())
+    }
+    .await;
+    // Delete even when the probe failed, so failed checks leave nothing behind
+    let deleted = client.delete_thread(&thread.id, PROBE_USER).await;
+
+    probe?;
+    deleted?;
+    Ok("write, read and delete ok".to_string())
+}
+
+async fn probe_mcp_server(server: &McpServerTarget) -> Result<String> {
+    let client = server.connect().await?;
+    let tools = client.list_tools().await;
+    // The check is about reaching the server; a failed shutdown doesn't change it
+    let _ = client.shutdown().await;
+    Ok(format!("{} tools", tools?.len()))
+}
+
+async fn probe_provider(client: Arc<dyn LLMClient>, model: String) -> Result<String> {
+    let request = ChatRequest::new(model.clone(), vec![Message::human("ping")])
+        .with_options(ChatOptions::new().max_tokens(1));
+    client.chat(request).await?;
+    Ok(format!("{} answered", model))
+}
+
+fn check_model(model: String) -> CheckResult {
+    let known = ModelCapabilities::is_known(&model);
+    CheckResult {
+        name: format!("model:{}", model),
+        status: if known { CheckStatus::Ok } else { CheckStatus::Warn },
+        critical: false,
+        latency_ms: None,
+        detail: if known {
+            "known model family".to_string()
+        } else {
+            "unknown model family; requests use default capabilities".to_string()
+        },
+        hint: (!known).then(|| "Check the name for typos; deployment aliases can be ignored".to_string()),
+    }
+}
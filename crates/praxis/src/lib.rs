@@ -38,10 +38,7 @@
 //!     // Create input
 //!     let input = GraphInput::new(
 //!         "conversation-123",
-//!         vec![Message::Human {
-//!             content: Content::text("Hello!"),
-//!             name: None,
-//!         }],
+//!         vec![Message::human("Hello!")],
 //!         LLMConfig::new("gpt-4o"),
 //!     );
 //!
@@ -49,7 +46,7 @@
 //!     let mut events = graph.spawn_run(input, None);
 //!     while let Some(event) = events.recv().await {
 //!         match event {
-//!             StreamEvent::Message { content } => println!("{}", content),
+//!             StreamEvent::Message { content, .. } => println!("{}", content),
 //!             StreamEvent::Done { .. } => break,
 //!             _ => {}
 //!         }
@@ -63,11 +60,14 @@
 //!
 //! Praxis is organized into focused crates:
 //!
+//! - **`praxis-core`**: `Clock` and `IdGen`, with fixed versions for tests
 //! - **`praxis-graph`**: React agent orchestrator with graph execution
 //! - **`praxis-llm`**: Provider-agnostic LLM client (OpenAI, Azure)
 //! - **`praxis-mcp`**: Model Context Protocol client and executor
 //! - **`praxis-persist`**: Persistence layer with MongoDB support
 //! - **`praxis-context`**: Context management and summarization
+//! - **`praxis-tools`**: Built-in local tools such as the sandboxed `evaluate` (feature `tools`)
+//! - **`praxis-eval`**: Prompt suites run through the graph and graded (feature `eval`)
 //!
 //! ## Features
 //!
@@ -78,44 +78,116 @@
 //! - ✅ **Type Safety**: Strong typing throughout the framework
 //! - ✅ **Async**: Built on Tokio for high performance
 //!
+//! ## Cargo Features
+//!
+//! None are on by default. Each one enables what it needs in the
+//! underlying crates, so it is the only switch to set:
+//!
+//! | Feature | Unlocks |
+//! |---------|---------|
+//! | `mongodb` | `MongoPersistenceClient`, `CompressionConfig`, `ReadConsistency` |
+//! | `observability` | `Observer` and the observation types; `GraphBuilder::with_observer` |
+//! | `langfuse` | `LangfuseObserver` (implies `observability`) |
+//! | `tools` | `EvaluateTool`, `EvaluateLimits` |
+//! | `eval` | `EvalSuite`, `EvalRunner`, `EvalReport` (regression evals) |
+//! | `wire` | `praxis::wire`, the SSE schema shared with `praxis-client` |
+//!
+//! ```toml
+//! praxis = { version = "0.2", features = ["mongodb", "langfuse"] }
+//! ```
+//!
 //! ## License
 //!
 //! MIT
 
+pub mod doctor;
 pub mod prelude;
 
+pub use praxis_core::{testkit, Clock, IdGen, SystemClock, UuidIds};
+
+pub use doctor::{doctor, CheckResult, CheckStatus, DoctorConfig, DoctorReport, McpServerTarget};
+
 pub use praxis_graph::{
     Graph, GraphBuilder, GraphConfig, GraphInput, GraphState, LLMConfig, ContextPolicy,
-    StreamEvent, PersistenceConfig, PersistenceContext, Provider, GraphOutput,
+    StreamEvent, Provenance, NodeType, PersistenceConfig, PersistenceContext, Provider, GraphOutput,
+    ThinkingHeartbeatConfig, StreamingConfig, BatchPolicy, ToolContentIsolationConfig, SuspiciousContentAction, GraphError, GraphErrorKind,
+    AuthorizationRule, Decision, RuleEffect, RulesAuthorizer, RunContext, ToolAuthorizer,
+    ModerationPolicy, ModerationStage,
+    GraphSpec, NodeRegistry, Router, NextNode, SimpleRouter, BudgetRouter,
+    ActivityConfig, TemplateDescriber, ToolCallDescriber, ClientFactory, ProviderConfig,
 };
 
 pub use praxis_llm::{
     ChatClient, ReasoningClient, LLMClient,
     OpenAIClient,
-    ChatRequest, ChatOptions, ResponseRequest, ResponseOptions,
-    Message, Content, Tool, ToolCall, ToolChoice,
-    ReasoningConfig, ReasoningEffort, SummaryMode,
+    OpenAIConfig,
+    AzureOpenAIClient,
+    AzureConfig,
+    AnthropicClient,
+    AnthropicConfig,
+    GeminiClient,
+    GeminiConfig,
+    ChatRequest, ChatOptions, AudioOptions, ChatResponse, ChoiceOutput, TokenLogprob, TopLogprob, ResponseRequest, ResponseOptions, ResponseOutput,
+    EmbeddingsClient, Embeddings, ModerationClient, Moderation, TokenCounter,
+    CacheHint, Message, Content, ContentPart, ImageDetail, Tool, ToolCall, ToolChoice,
+    ReasoningConfig, ReasoningEffort, ReasoningKind, SummaryMode,
+    CapabilityRegistry, ModelCapabilities, OptionAdjusted,
+    ConcurrencyLimitedClient, FallbackClient, FallbackProvider, LLMError, RateLimited, ValidationError, RetryPolicy, EventBatcher,
+    ConnectionOptions,
+    CostBreakdown, CostCalculator, ModelPrice,
+    StatsRecordingClient, ProviderStats, ProviderError, RateLimitHeadroom,
+    RecordingClient, ReplayClient, RecordedCall,
 };
+/// Provider stream events, as returned by `ChatClient::chat_stream`
+pub use praxis_llm::StreamEvent as LLMStreamEvent;
+/// Scripted LLM client for tests (see `praxis_llm::testkit`)
+pub use praxis_llm::testkit::MockLLMClient;
 
 pub use praxis_mcp::{
-    MCPClient, MCPToolExecutor, ToolResponse,
+    MCPClient, MCPToolExecutor, ToolResponse, LocalTool,
+    PoolConfig, PoolStats, StdioServerConfig, StdioServerPool, ToolConfig,
 };
 
+#[cfg(feature = "tools")]
+pub use praxis_tools::{EvaluateTool, EvaluateLimits};
+
+/// SSE schema of praxis-api's message stream, as `praxis-client` parses it
+#[cfg(feature = "wire")]
+pub use praxis_graph::wire;
+
+#[cfg(feature = "eval")]
+pub use praxis_eval::{EvalSuite, EvalCase, EvalRunner, EvalReport, CaseReport, Grader, MockTool};
+#[cfg(feature = "eval")]
+pub use praxis_eval::DEFAULT_CONCURRENCY as DEFAULT_EVAL_CONCURRENCY;
+
 pub use praxis_persist::{
     PersistenceClient, EventAccumulator, StreamEventExtractor,
-    DBMessage, MessageRole, MessageType, Thread, ThreadMetadata, ThreadSummary, PersistError,
+    Citation, ContentEncoding, DBMessage, MessageRole, MessageType, Thread, ThreadMetadata, ThreadSummary, SummaryTrigger, PersistError,
+    PersistencePolicy, HistoryIndex, HistoryIndexEntry, LLMConfigOverride, ToolProfile, ToolVerification,
+    ContentTokenizer, content_sha256, check_message_invariants, InvariantViolation,
+    ThreadExport, AnonymizerConfig, Redactor, anonymize_thread,
+    OutboxDispatcher, OutboxEntry, OutboxHandler, OutboxStatus, OutboxStore, InMemoryPersistenceClient, CompressionConfig,
+    AttachmentRef, Migration, MigrationRegistry, MigrationReport, MigrationStep, SCHEMA_VERSION,
+    HygieneAction, HygienePolicy, HygieneReport, HygieneRunner, HygieneStore, ThreadSummarizer, HYGIENE_LEASE,
 };
 
 #[cfg(feature = "mongodb")]
-pub use praxis_persist::MongoPersistenceClient;
+pub use praxis_persist::{MongoPersistenceClient, ReadConsistency};
 
 pub use praxis_context::{
-    ContextStrategy, ContextWindow, DefaultContextStrategy,
+    ContextStrategy, ContextWindow, DefaultContextStrategy, IndexedHistoryStrategy, FetchMessagesTool,
+    ReadAttachmentTool, TiktokenTokenizer, ContextNormalization, normalize_for_model,
+    SummarizationTrigger, SummaryWindow, OverflowTrigger, CadenceTrigger,
+    CompositeContextStrategy, ContextStage, ContextStageConfig, ContextDraft, StageContext, StageUsage,
+    PinnedStage, SummaryStage, RetrievalStage, RecencyStage,
 };
 
 #[cfg(feature = "observability")]
 pub use praxis_observability::{
-    Observer, LangfuseObserver, NodeObservation, NodeObservationData,
+    Observer, NodeObservation, NodeObservationData,
     LangfuseMessage, ToolCallInfo, ToolResultInfo, TokenUsage,
 };
 
+#[cfg(feature = "langfuse")]
+pub use praxis_observability::{langfuse::LangfuseClient, LangfuseObserver};
+
@@ -0,0 +1,69 @@
+//! `doctor` reports every configured dependency, failing only on the
+//! critical ones
+
+use praxis::{
+    doctor, CheckStatus, DoctorConfig, InMemoryPersistenceClient, McpServerTarget, MockLLMClient, PersistenceClient,
+    StdioServerConfig,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn statuses(report: &praxis::DoctorReport) -> Vec<(&str, CheckStatus)> {
+    report.checks.iter().map(|check| (check.name.as_str(), check.status)).collect()
+}
+
+#[tokio::test]
+async fn test_healthy_configuration_passes_without_spending() {
+    let persistence = Arc::new(InMemoryPersistenceClient::new());
+    let llm = Arc::new(MockLLMClient::new().then_message("p"));
+    let config = DoctorConfig::new()
+        .with_persistence(persistence.clone())
+        .with_provider("openai", llm.clone(), "gpt-4o-mini")
+        .with_model("gtp-4o");
+
+    let report = doctor(config).await;
+    assert_eq!(
+        statuses(&report),
+        [
+            ("persistence", CheckStatus::Ok),
+            ("llm:openai", CheckStatus::Skipped),
+            ("model:gpt-4o-mini", CheckStatus::Ok),
+            ("model:gtp-4o", CheckStatus::Warn),
+        ]
+    );
+    assert!(report.passed());
+    // Nothing spent, nothing left behind
+    assert_eq!(llm.remaining(), 1);
+    assert!(persistence.list_threads("praxis-doctor", None, None).await.unwrap().is_empty());
+
+    let table = report.to_table();
+    assert!(table.starts_with("CHECK"), "{}", table);
+    assert!(table.contains("-> Check the name for typos"), "{}", table);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["checks"][0]["status"], "ok");
+    assert!(json["checks"][0]["latency_ms"].is_u64());
+}
+
+#[tokio::test]
+async fn test_dead_dependencies_fail_with_hints() {
+    let llm = Arc::new(MockLLMClient::new().then_error("401 invalid api key"));
+    let config = DoctorConfig::new()
+        .with_mcp_server(McpServerTarget::Stdio(StdioServerConfig::new("search", "praxis-no-such-server")))
+        .with_provider("openai", llm.clone(), "gpt-4o-mini")
+        .spending(true)
+        .with_timeout(Duration::from_secs(5));
+
+    let report = doctor(config).await;
+    assert_eq!(
+        statuses(&report),
+        [("mcp:search", CheckStatus::Fail), ("llm:openai", CheckStatus::Fail), ("model:gpt-4o-mini", CheckStatus::Ok)]
+    );
+    assert!(!report.passed());
+    assert_eq!(report.critical_failures().count(), 2);
+    assert_eq!(llm.chat_requests()[0].options.max_tokens, Some(1));
+
+    let llm_check = &report.checks[1];
+    assert!(llm_check.detail.contains("invalid api key"), "{}", llm_check.detail);
+    assert_eq!(llm_check.hint.as_deref(), Some("Check the openai API key and that the account can use gpt-4o-mini"));
+}
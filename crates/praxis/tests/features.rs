@@ -0,0 +1,16 @@
+//! Facade features are enough on their own: no sub-crate feature needs to
+//! be enabled next to them
+
+#[cfg(feature = "mongodb")]
+#[test]
+fn test_mongodb_feature_exports_client() {
+    let name = std::any::type_name::<praxis::MongoPersistenceClient>();
+    assert!(name.ends_with("MongoPersistenceClient"));
+}
+
+#[cfg(feature = "langfuse")]
+#[test]
+fn test_langfuse_feature_exports_observer() {
+    fn observer<T: praxis::Observer>() {}
+    observer::<praxis::LangfuseObserver>();
+}
@@ -0,0 +1,11 @@
+//! Compile tests for embedders: the public traits stay usable as
+//! `Arc<dyn Trait>` and the usual wrapper patterns keep compiling
+
+#[test]
+fn test_public_traits_are_object_safe() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/object_safety.rs");
+    cases.pass("tests/ui/wrappers.rs");
+    #[cfg(feature = "observability")]
+    cases.pass("tests/ui/observer.rs");
+}
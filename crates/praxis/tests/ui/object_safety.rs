@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use praxis::{ChatClient, ContextStrategy, LLMClient, PersistenceClient, ReasoningClient};
+
+/// Trait objects can cross threads and live in spawned tasks
+fn shareable<T: ?Sized + Send + Sync + 'static>() {}
+
+fn main() {
+    shareable::<dyn ChatClient>();
+    shareable::<dyn ReasoningClient>();
+    shareable::<dyn LLMClient>();
+    shareable::<dyn PersistenceClient>();
+    shareable::<dyn ContextStrategy>();
+
+    // An `Arc` of a trait object is itself an implementation
+    fn llm<T: LLMClient>() {}
+    fn persist<T: PersistenceClient>() {}
+    fn strategy<T: ContextStrategy>() {}
+    llm::<Arc<dyn LLMClient>>();
+    persist::<Arc<dyn PersistenceClient>>();
+    strategy::<Arc<dyn ContextStrategy>>();
+
+    // ... and coerces back into one
+    let _: Option<Arc<dyn PersistenceClient>> = None::<Arc<Arc<dyn PersistenceClient>>>.map(|p| p as _);
+}
@@ -0,0 +1,8 @@
+use std::sync::Arc;
+
+use praxis::Observer;
+
+fn main() {
+    fn observer<T: Observer + Send + Sync + 'static>() {}
+    observer::<Arc<dyn Observer>>();
+}
@@ -0,0 +1,79 @@
+//! A composition layer written once, generic over the wrapped client,
+//! works for concrete clients and trait objects alike
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::Stream;
+use praxis::{
+    ChatClient, ChatRequest, ChatResponse, ContextStrategy, ContextWindow, DBMessage, LLMClient,
+    LLMStreamEvent, PersistenceClient, ReasoningClient, ResponseOutput, ResponseRequest,
+};
+use std::pin::Pin;
+
+/// Counts chat calls, forwards everything
+struct Counting<C> {
+    inner: C,
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl<C: ChatClient> ChatClient for Counting<C> {
+    async fn chat(&self, request: ChatRequest) -> anyhow::Result<ChatResponse> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.inner.chat(request).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = anyhow::Result<LLMStreamEvent>> + Send>>> {
+        self.inner.chat_stream(request).await
+    }
+}
+
+#[async_trait]
+impl<C: ReasoningClient> ReasoningClient for Counting<C> {
+    async fn reason(&self, request: ResponseRequest) -> anyhow::Result<ResponseOutput> {
+        self.inner.reason(request).await
+    }
+
+    async fn reason_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = anyhow::Result<LLMStreamEvent>> + Send>>> {
+        self.inner.reason_stream(request).await
+    }
+}
+
+impl<C: LLMClient> LLMClient for Counting<C> {}
+
+/// Reads the thread before delegating to the wrapped strategy
+struct Prefetching<S> {
+    inner: S,
+}
+
+#[async_trait]
+impl<S: ContextStrategy> ContextStrategy for Prefetching<S> {
+    async fn get_context_window(
+        &self,
+        thread_id: &str,
+        persist_client: &Arc<dyn PersistenceClient>,
+        exclude_message_id: Option<&str>,
+        model: &str,
+    ) -> anyhow::Result<ContextWindow> {
+        let _: Vec<DBMessage> = persist_client.get_messages(thread_id).await?;
+        self.inner.get_context_window(thread_id, persist_client, exclude_message_id, model).await
+    }
+}
+
+fn main() {
+    fn wrap(client: Arc<dyn LLMClient>) -> Arc<dyn LLMClient> {
+        Arc::new(Counting { inner: client, calls: AtomicUsize::new(0) })
+    }
+    fn prefetch(strategy: Arc<dyn ContextStrategy>) -> Arc<dyn ContextStrategy> {
+        Arc::new(Prefetching { inner: strategy })
+    }
+    let _ = (wrap, prefetch);
+}
@@ -0,0 +1,78 @@
+//! Stdio MCP server with read-only tools over one directory of notes
+//!
+//! Usage: `praxis-example-mcp-server <notes-dir>`
+//!
+//! `list_notes` names the files in the directory and `read_note` returns
+//! one of them. Names are plain file names: anything with a path separator
+//! or `..` is refused, so nothing outside the directory can be read.
+
+use rmcp::handler::server::{router::tool::ToolRouter, wrapper::Parameters};
+use rmcp::model::{CallToolResult, Content, ServerCapabilities, ServerInfo};
+use rmcp::{tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler, ServiceExt};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ReadNoteRequest {
+    /// File name, as `list_notes` returns it
+    name: String,
+}
+
+#[derive(Clone)]
+struct Notes {
+    dir: PathBuf,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl Notes {
+    fn new(dir: PathBuf) -> Self {
+        Self { dir, tool_router: Self::tool_router() }
+    }
+
+    #[tool(description = "List the user's notes, one file name per line")]
+    async fn list_notes(&self) -> Result<CallToolResult, McpError> {
+        let entries = std::fs::read_dir(&self.dir).map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        Ok(CallToolResult::success(vec![Content::text(names.join("\n"))]))
+    }
+
+    #[tool(description = "Read one of the user's notes by file name")]
+    async fn read_note(&self, Parameters(request): Parameters<ReadNoteRequest>) -> Result<CallToolResult, McpError> {
+        let name = request.name;
+        if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+            return Ok(CallToolResult::error(vec![Content::text(format!("'{}' is not a note name", name))]));
+        }
+        match std::fs::read_to_string(self.dir.join(&name)) {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err(_) => Ok(CallToolResult::error(vec![Content::text(format!("No note named '{}'", name))])),
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for Notes {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            instructions: Some("Read-only access to the user's notes".into()),
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let dir = std::env::args()
+        .nth(1)
+        .ok_or_else(|| anyhow::anyhow!("usage: praxis-example-mcp-server <notes-dir>"))?;
+    let service = Notes::new(PathBuf::from(dir)).serve(rmcp::transport::stdio()).await?;
+    service.waiting().await?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Local agent: the whole ReAct loop on one machine, with no keys
+//!
+//! ```text
+//! local-agent [--mock] [--thread <id>] [--data-dir <dir>] [--notes <dir>]
+//!             [--max-context-tokens <n>] <message>
+//! ```
+//!
+//! - The model is Ollama's OpenAI-compatible API (`OLLAMA_BASE_URL`,
+//!   default `http://localhost:11434/v1`; `LOCAL_AGENT_MODEL`, default
+//!   `llama3.2`), or with `--mock` a scripted `MockLLMClient`.
+//! - Tools come from `praxis-example-mcp-server`, spawned over stdio and
+//!   serving the notes in `--notes`.
+//! - Threads are saved to `<data-dir>/threads.json`. Each run prints its
+//!   thread id; pass it back with `--thread` to continue after a restart.
+//! - Once the history outgrows `--max-context-tokens`, it is summarized
+//!   and later runs start from the summary.
+
+mod store;
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::Stream;
+use praxis::{
+    ChatClient, ChatRequest, ChatResponse, ContentEncoding, ContextStrategy, DBMessage, DefaultContextStrategy,
+    GraphBuilder, GraphInput, LLMClient, LLMConfig, LLMStreamEvent, MCPClient, MCPToolExecutor, Message, MessageRole,
+    MessageType, MockLLMClient, OpenAIClient, PersistenceClient, PersistenceContext, StreamEvent, ThreadMetadata,
+};
+use std::io::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::store::FileStore;
+
+const USER_ID: &str = "local";
+const DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+const DEFAULT_MODEL: &str = "llama3.2";
+const DEFAULT_MAX_CONTEXT_TOKENS: usize = 4_000;
+
+struct Args {
+    mock: bool,
+    thread: Option<String>,
+    data_dir: PathBuf,
+    notes: PathBuf,
+    max_context_tokens: usize,
+    message: String,
+}
+
+impl Args {
+    fn parse() -> Result<Self> {
+        let mut args = Self {
+            mock: false,
+            thread: None,
+            data_dir: PathBuf::from(".local-agent"),
+            notes: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("notes"),
+            max_context_tokens: DEFAULT_MAX_CONTEXT_TOKENS,
+            message: String::new(),
+        };
+        let mut words = Vec::new();
+        let mut argv = std::env::args().skip(1);
+        while let Some(arg) = argv.next() {
+            let mut value = || argv.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+            match arg.as_str() {
+                "--mock" => args.mock = true,
+                "--thread" => args.thread = Some(value()?),
+                "--data-dir" => args.data_dir = value()?.into(),
+                "--notes" => args.notes = value()?.into(),
+                "--max-context-tokens" => args.max_context_tokens = value()?.parse().context("--max-context-tokens")?,
+                _ if arg.starts_with("--") => bail!("unknown option {}", arg),
+                _ => words.push(arg),
+            }
+        }
+        if words.is_empty() {
+            bail!("usage: local-agent [--mock] [--thread <id>] [--data-dir <dir>] [--notes <dir>] [--max-context-tokens <n>] <message>");
+        }
+        args.message = words.join(" ");
+        Ok(args)
+    }
+}
+
+/// Sends summary requests to `model`
+///
+/// `DefaultContextStrategy` asks for its summaries by an OpenAI model name,
+/// which a local server doesn't have.
+struct OnModel {
+    client: Arc<dyn LLMClient>,
+    model: String,
+}
+
+#[async_trait]
+impl ChatClient for OnModel {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        self.client.chat(ChatRequest { model: self.model.clone(), ..request }).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<LLMStreamEvent>> + Send>>> {
+        self.client.chat_stream(ChatRequest { model: self.model.clone(), ..request }).await
+    }
+}
+
+/// A model turn streamed word by word, like a provider would
+fn streamed(text: &str) -> Vec<LLMStreamEvent> {
+    text.split_inclusive(' ')
+        .map(|word| LLMStreamEvent::Message { content: word.to_string(), choice_index: None, logprobs: None })
+        .chain(std::iter::once(LLMStreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None }))
+        .collect()
+}
+
+/// Clients for `--mock`: the run lists the notes, reads one and answers;
+/// summaries come from their own client so they can't take the run's turns
+fn mock_clients() -> (Arc<dyn LLMClient>, Arc<dyn ChatClient>) {
+    let llm = MockLLMClient::new()
+        .then_tool_call("call_1", "list_notes", "{}")
+        .then_tool_call("call_2", "read_note", r#"{"name": "trip.md"}"#)
+        .then_stream(streamed("Your flight lands on Friday at 09:40, terminal 1."));
+    let summarizer = MockLLMClient::new().then_message("The user is planning a Lisbon trip; the flight lands Friday 09:40.");
+    (Arc::new(llm), Arc::new(summarizer))
+}
+
+fn user_message(thread_id: &str, content: &str) -> DBMessage {
+    DBMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        thread_id: thread_id.to_string(),
+        user_id: USER_ID.to_string(),
+        role: MessageRole::User,
+        message_type: MessageType::Message,
+        content: content.to_string(),
+        tool_call_id: None,
+        tool_name: None,
+        tool_server: None,
+        verification: None,
+        arguments: None,
+        reasoning_id: None,
+        created_at: Utc::now(),
+        duration_ms: None,
+        agent: None,
+        content_encoding: ContentEncoding::None,
+        content_sha256: None,
+        token_count: None,
+        summary: None,
+        iteration: None,
+        usage: None,
+        citations: Vec::new(),
+    }
+}
+
+// One thread, so the graph's background saves run in order with `main`
+// (see the end of `main`)
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let args = Args::parse()?;
+
+    std::fs::create_dir_all(&args.data_dir)?;
+    let persist: Arc<dyn PersistenceClient> = Arc::new(FileStore::open(args.data_dir.join("threads.json"))?);
+
+    // The bundled MCP server is built next to this binary
+    let server = std::env::current_exe()?.with_file_name("praxis-example-mcp-server");
+    let mcp_executor = Arc::new(MCPToolExecutor::new());
+    let notes = MCPClient::new_stdio("notes", &server, [&args.notes])
+        .await
+        .with_context(|| format!("cannot start {} (build it with `cargo build`)", server.display()))?;
+    mcp_executor.add_server(notes).await?;
+
+    let model = std::env::var("LOCAL_AGENT_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+    let (llm_client, summarizer) = if args.mock {
+        mock_clients()
+    } else {
+        let base_url = std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let client: Arc<dyn LLMClient> = Arc::new(OpenAIClient::with_base_url("", base_url)?);
+        let summarizer = OnModel { client: Arc::clone(&client), model: model.clone() };
+        (client, Arc::new(summarizer) as Arc<dyn ChatClient>)
+    };
+
+    let graph = GraphBuilder::new()
+        .llm_client(llm_client)
+        .mcp_executor(Arc::clone(&mcp_executor))
+        .with_persistence(Arc::clone(&persist))
+        .build()?;
+    let context = DefaultContextStrategy::new(args.max_context_tokens, summarizer);
+
+    let thread = match &args.thread {
+        Some(id) => persist.get_thread(id).await?.ok_or_else(|| anyhow!("no thread {} in {}", id, args.data_dir.display()))?,
+        None => persist.create_thread(USER_ID, ThreadMetadata::default()).await?,
+    };
+    println!("thread: {}", thread.id);
+    if let Some(summary) = &thread.summary {
+        println!("summary: {}", summary.text);
+    }
+
+    // Earlier turns (after the summary, if there is one), then this one
+    let window = context.get_context_window(&thread.id, &persist, None, &model).await?;
+    persist.save_message(user_message(&thread.id, &args.message)).await?;
+    let mut messages = vec![Message::system(window.system_prompt)];
+    messages.extend(window.messages);
+    messages.push(Message::human(args.message.clone()));
+
+    let input = GraphInput::new(thread.id.clone(), messages, LLMConfig::new(&model));
+    let persistence = PersistenceContext { thread_id: thread.id.clone(), user_id: USER_ID.to_string() };
+    let mut events = graph.spawn_run(input, Some(persistence));
+
+    let mut failed = None;
+    let mut stdout = std::io::stdout();
+    while let Some(event) = events.recv().await {
+        match event {
+            StreamEvent::Message { content, .. } => {
+                print!("{}", content);
+                stdout.flush()?;
+            }
+            StreamEvent::ToolCallComplete { name, arguments, .. } => println!("[tool] {} {}", name, arguments),
+            StreamEvent::ToolResult { result, is_error, .. } => {
+                let label = if is_error { "error" } else { "result" };
+                println!("[{}] {}", label, result.lines().next().unwrap_or_default());
+            }
+            StreamEvent::Error { message, .. } => failed = Some(message),
+            StreamEvent::EndStream { .. } => println!(),
+            _ => {}
+        }
+    }
+
+    // The graph saves each message in a task of its own; let the ones it
+    // spawned run before the process exits
+    tokio::task::yield_now().await;
+    persist.flush(&thread.id).await?;
+    mcp_executor.shutdown_all().await?;
+
+    match failed {
+        Some(message) => bail!("run failed: {}", message),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,182 @@
+//! `PersistenceClient` backed by one JSON file
+//!
+//! Threads and messages are kept in memory and the whole file is rewritten
+//! after every change, which is plenty for one person's threads and keeps
+//! the example free of a database. Attachments, conversation keys and
+//! migrations use the trait's defaults (unsupported).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use praxis::{DBMessage, HistoryIndex, PersistError, PersistenceClient, SummaryTrigger, Thread, ThreadMetadata, ThreadSummary};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+type Result<T> = std::result::Result<T, PersistError>;
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stored {
+    threads: Vec<Thread>,
+    messages: Vec<DBMessage>,
+}
+
+impl Stored {
+    fn thread_mut(&mut self, thread_id: &str) -> Result<&mut Thread> {
+        self.threads
+            .iter_mut()
+            .find(|thread| thread.id == thread_id)
+            .ok_or_else(|| PersistError::ThreadNotFound(thread_id.to_string()))
+    }
+}
+
+pub struct FileStore {
+    path: PathBuf,
+    stored: Mutex<Stored>,
+}
+
+impl FileStore {
+    /// Open the store at `path`, starting empty if the file doesn't exist
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let stored = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Stored::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, stored: Mutex::new(stored) })
+    }
+
+    fn stored(&self) -> MutexGuard<'_, Stored> {
+        self.stored.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Apply `change` and write the file, through a temporary file so a
+    /// crash never leaves it half written
+    fn update<T>(&self, change: impl FnOnce(&mut Stored) -> Result<T>) -> Result<T> {
+        let mut stored = self.stored();
+        let value = change(&mut stored)?;
+        let json = serde_json::to_vec_pretty(&*stored).map_err(|e| PersistError::Internal(e.to_string()))?;
+        let temp = self.path.with_extension("json.tmp");
+        std::fs::write(&temp, json)
+            .and_then(|_| std::fs::rename(&temp, &self.path))
+            .map_err(|e| PersistError::Other(format!("cannot write {}: {}", self.path.display(), e)))?;
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl PersistenceClient for FileStore {
+    async fn save_message(&self, message: DBMessage) -> Result<()> {
+        self.update(|stored| {
+            stored.messages.push(message);
+            Ok(())
+        })
+    }
+
+    async fn get_messages(&self, thread_id: &str) -> Result<Vec<DBMessage>> {
+        let mut messages: Vec<DBMessage> = self
+            .stored()
+            .messages
+            .iter()
+            .filter(|message| message.thread_id == thread_id)
+            .cloned()
+            .collect();
+        messages.sort_by_key(|message| message.created_at);
+        Ok(messages)
+    }
+
+    async fn get_messages_after(&self, thread_id: &str, after: DateTime<Utc>) -> Result<Vec<DBMessage>> {
+        let mut messages = self.get_messages(thread_id).await?;
+        messages.retain(|message| message.created_at > after);
+        Ok(messages)
+    }
+
+    async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Result<Thread> {
+        let now = Utc::now();
+        let thread = Thread {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata,
+            last_summary_update: now,
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        };
+        self.update(|stored| {
+            stored.threads.push(thread.clone());
+            Ok(thread)
+        })
+    }
+
+    async fn get_thread(&self, thread_id: &str) -> Result<Option<Thread>> {
+        Ok(self.stored().threads.iter().find(|thread| thread.id == thread_id).cloned())
+    }
+
+    async fn save_thread_summary(
+        &self,
+        thread_id: &str,
+        summary: String,
+        generated_at: DateTime<Utc>,
+        trigger: SummaryTrigger,
+    ) -> Result<()> {
+        self.update(|stored| {
+            let messages_count = stored.messages.iter().filter(|message| message.thread_id == thread_id).count();
+            let thread = stored.thread_mut(thread_id)?;
+            thread.summary = Some(ThreadSummary {
+                text: summary,
+                generated_at,
+                total_tokens_before_summary: 0,
+                messages_count,
+                trigger,
+            });
+            thread.last_summary_update = generated_at;
+            thread.updated_at = Utc::now();
+            Ok(())
+        })
+    }
+
+    async fn update_thread_metadata(&self, thread_id: &str, metadata: ThreadMetadata) -> Result<()> {
+        self.update(|stored| {
+            let thread = stored.thread_mut(thread_id)?;
+            thread.metadata = metadata;
+            thread.updated_at = Utc::now();
+            Ok(())
+        })
+    }
+
+    async fn save_thread_index(&self, thread_id: &str, index: HistoryIndex) -> Result<()> {
+        self.update(|stored| {
+            stored.thread_mut(thread_id)?.history_index = Some(index);
+            Ok(())
+        })
+    }
+
+    async fn delete_thread(&self, thread_id: &str, user_id: &str) -> Result<()> {
+        self.update(|stored| {
+            let before = stored.threads.len();
+            stored.threads.retain(|thread| !(thread.id == thread_id && thread.user_id == user_id));
+            if stored.threads.len() == before {
+                return Err(PersistError::ThreadNotFound(thread_id.to_string()));
+            }
+            stored.messages.retain(|message| message.thread_id != thread_id);
+            Ok(())
+        })
+    }
+
+    async fn list_threads(&self, user_id: &str, limit: Option<i64>, skip: Option<i64>) -> Result<Vec<Thread>> {
+        let mut threads: Vec<Thread> = self
+            .stored()
+            .threads
+            .iter()
+            .filter(|thread| thread.user_id == user_id)
+            .cloned()
+            .collect();
+        threads.sort_by_key(|thread| std::cmp::Reverse(thread.updated_at));
+        let skip = skip.unwrap_or(0).max(0) as usize;
+        let limit = limit.map_or(usize::MAX, |limit| limit.max(0) as usize);
+        Ok(threads.into_iter().skip(skip).take(limit).collect())
+    }
+}
@@ -0,0 +1,120 @@
+//! The local agent end to end with `--mock`: real MCP server, real store,
+//! scripted model
+
+use praxis::MCPClient;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn data_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("local-agent-{}", uuid::Uuid::new_v4()))
+}
+
+/// Run the agent; its stdout, failing the test if it exits with an error
+fn agent(data_dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_local-agent"))
+        .arg("--mock")
+        .arg("--data-dir")
+        .arg(data_dir)
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+fn thread_id(stdout: &str) -> &str {
+    stdout.lines().find_map(|line| line.strip_prefix("thread: ")).unwrap()
+}
+
+fn stored(data_dir: &Path) -> Value {
+    serde_json::from_slice(&std::fs::read(data_dir.join("threads.json")).unwrap()).unwrap()
+}
+
+fn message_types(stored: &Value) -> Vec<&str> {
+    stored["messages"].as_array().unwrap().iter().map(|message| message["message_type"].as_str().unwrap()).collect()
+}
+
+#[test]
+fn test_run_calls_the_notes_server_and_saves_the_thread() {
+    let dir = data_dir();
+    let stdout = agent(&dir, &["When does my flight land?"]);
+
+    assert!(stdout.contains("[tool] list_notes {}"), "{}", stdout);
+    assert!(stdout.contains("[tool] read_note"), "{}", stdout);
+    assert!(stdout.contains("Flight lands Friday 09:40"), "{}", stdout);
+    assert!(stdout.contains("Your flight lands on Friday at 09:40, terminal 1."), "{}", stdout);
+
+    let stored = stored(&dir);
+    assert_eq!(stored["threads"][0]["id"], thread_id(&stdout));
+    assert_eq!(
+        message_types(&stored),
+        ["message", "tool_call", "tool_result", "tool_call", "tool_result", "message"]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_resumed_thread_outgrowing_the_context_is_summarized() {
+    let dir = data_dir();
+    let first = agent(&dir, &["When does my flight land?"]);
+    let thread = thread_id(&first).to_string();
+
+    // A restart: a new process picks the thread up by id, and a tiny
+    // context forces a summary of what the first run said
+    let second = agent(&dir, &["--thread", &thread, "--max-context-tokens", "10", "And the hotel?"]);
+    assert_eq!(thread_id(&second), thread);
+    let stored = stored(&dir);
+    assert_eq!(stored["threads"].as_array().unwrap().len(), 1);
+    assert_eq!(message_types(&stored).len(), 12);
+    let summary = &stored["threads"][0]["summary"];
+    assert_eq!(summary["trigger"], "overflow");
+    assert_eq!(summary["messages_count"], 7);
+
+    let third = agent(&dir, &["--thread", &thread, "Thanks"]);
+    assert!(
+        third.contains("summary: The user is planning a Lisbon trip; the flight lands Friday 09:40."),
+        "{}",
+        third
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_unknown_thread_is_an_error() {
+    let dir = data_dir();
+    let output = Command::new(env!("CARGO_BIN_EXE_local-agent"))
+        .args(["--mock", "--thread", "missing", "--data-dir"])
+        .arg(&dir)
+        .arg("Hi")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no thread missing"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_notes_server_reads_only_its_directory() {
+    let notes = Path::new(env!("CARGO_MANIFEST_DIR")).join("notes");
+    let server = MCPClient::new_stdio("notes", env!("CARGO_BIN_EXE_praxis-example-mcp-server"), [&notes]).await.unwrap();
+
+    let mut tools: Vec<String> = server.list_tools().await.unwrap().into_iter().map(|tool| tool.name).collect();
+    tools.sort();
+    assert_eq!(tools, ["list_notes", "read_note"]);
+
+    let note = server.call_tool("read_note", json!({ "name": "garden.md" })).await.unwrap();
+    assert!(note[0].to_string().contains("Tomatoes went in on April 12"));
+
+    for name in ["../Cargo.toml", "/etc/passwd", ".."] {
+        let refused = server.call_tool("read_note", json!({ "name": name })).await;
+        let text = match refused {
+            Ok(responses) => responses[0].to_string(),
+            Err(e) => e.to_string(),
+        };
+        assert!(text.contains("is not a note name"), "{}: {}", name, text);
+    }
+    server.shutdown().await.unwrap();
+}
@@ -0,0 +1,102 @@
+//! Caller identity derived from request headers
+//!
+//! The API has no user authentication of its own; it trusts the gateway in
+//! front of it for the tenant header. The admin scope is granted only to
+//! requests carrying `Authorization: Bearer <ADMIN_TOKEN>`.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap},
+};
+use std::sync::Arc;
+
+use crate::{error::ApiError, state::AppState};
+
+/// Header naming the caller's tenant
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+pub const ADMIN_SCOPE: &str = "admin";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthContext {
+    pub tenant_id: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl AuthContext {
+    /// Build from request headers; `admin_token` empty disables the admin scope
+    pub fn from_headers(headers: &HeaderMap, admin_token: &str) -> Self {
+        let tenant_id = headers
+            .get(TENANT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|tenant| !tenant.is_empty())
+            .map(String::from);
+
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        let mut scopes = Vec::new();
+        if !admin_token.is_empty() && bearer.is_some_and(|token| constant_time_eq(token, admin_token)) {
+            scopes.push(ADMIN_SCOPE.to_string());
+        }
+
+        Self { tenant_id, scopes }
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    pub fn require_scope(&self, scope: &str) -> Result<(), ApiError> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden(format!("requires the '{}' scope", scope)))
+        }
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AuthContext {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers, &state.config.admin_token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_admin_scope_requires_matching_token() {
+        let admin = headers(&[("authorization", "Bearer s3cret"), (TENANT_HEADER, "acme")]);
+        let ctx = AuthContext::from_headers(&admin, "s3cret");
+        assert!(ctx.has_scope(ADMIN_SCOPE));
+        assert_eq!(ctx.tenant_id.as_deref(), Some("acme"));
+
+        let wrong = headers(&[("authorization", "Bearer nope")]);
+        assert!(AuthContext::from_headers(&wrong, "s3cret").require_scope(ADMIN_SCOPE).is_err());
+
+        // No configured token: nobody is admin, not even an empty bearer
+        let empty = headers(&[("authorization", "Bearer ")]);
+        assert!(!AuthContext::from_headers(&empty, "").has_scope(ADMIN_SCOPE));
+    }
+}
@@ -0,0 +1,451 @@
+//! Maintenance commands that run instead of the server
+//!
+//! ```text
+//! praxis-api threads reindex [--model <model>] [<thread_id>...]
+//! praxis-api threads export [--anonymize] <thread_id>...
+//! praxis-api eval run [--model <model>] [--judge-model <model>] [--concurrency <n>] [--json] <suite>...
+//! praxis-api graph validate <spec>
+//! praxis-api db migrate [--dry-run]
+//! praxis-api db hygiene
+//! praxis-api doctor [--llm] [--model <model>] [--json]
+//! praxis-api [--strict]
+//! ```
+//!
+//! `threads reindex` backfills `content_sha256` and `token_count` on
+//! messages saved before they were recorded. Without thread ids it walks
+//! every thread that still has uncounted messages.
+//!
+//! `threads export` prints one JSON export per line. `--anonymize` applies
+//! the `[export]` anonymizer settings.
+//!
+//! `eval run` sends each suite through a graph built from the configured
+//! LLM client, with only the suite's mock tools, and prints the reports
+//! (`--json` for machine-readable output). It fails if any case failed.
+//!
+//! `db migrate` brings stored threads and messages up to the current
+//! schema version and prints what each migration changed. `--dry-run`
+//! only counts the documents each one would change.
+//!
+//! `db hygiene` runs one pass of the `[hygiene]` policy over idle threads,
+//! whether or not the server runs it in the background, and prints what it
+//! did. It fails if a server replica is in the middle of a run.
+//!
+//! `doctor` checks every configured dependency: MongoDB (write, read and
+//! delete a probe thread), each MCP server (list its tools), the configured
+//! model names and the observer's credentials. `--llm` also sends a 1-token
+//! request to `--model`, which costs money. It fails if a critical check
+//! failed.
+//!
+//! `--strict` runs the same checks (without `--llm`) before serving and
+//! refuses to start if a critical one fails; `server.self_check` runs them
+//! and only logs the result.
+//!
+//! `graph validate` checks a graph spec file as the server would load it,
+//! with the same (empty) set of custom nodes.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use praxis::{
+    anonymize_thread, AnonymizerConfig, ContentTokenizer, DoctorConfig, DoctorReport, EvalReport, DEFAULT_EVAL_CONCURRENCY, EvalRunner,
+    EvalSuite, Graph, GraphConfig, GraphSpec, HygienePolicy, HygieneReport, HygieneRunner, LLMClient, MCPToolExecutor, McpServerTarget,
+    MigrationReport, MongoPersistenceClient, NodeRegistry, PersistenceClient, ThreadExport,
+};
+
+use crate::config::Config;
+
+/// Tokenizer used when `--model` is not given
+pub const DEFAULT_REINDEX_MODEL: &str = "gpt-4o-mini";
+
+/// Model evaluated when `--model` is not given
+pub const DEFAULT_EVAL_MODEL: &str = "gpt-4o-mini";
+
+/// Model the doctor's LLM check calls when `--model` is not given
+pub const DEFAULT_DOCTOR_MODEL: &str = "gpt-4o-mini";
+
+pub const USAGE: &str = "usage: praxis-api [threads reindex [--model <model>] [<thread_id>...]]
+       praxis-api threads export [--anonymize] <thread_id>...
+       praxis-api eval run [--model <model>] [--judge-model <model>] [--concurrency <n>] [--json] <suite>...
+       praxis-api graph validate <spec>
+       praxis-api db migrate [--dry-run]
+       praxis-api db hygiene
+       praxis-api doctor [--llm] [--model <model>] [--json]
+       praxis-api [--strict]";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// `strict`: refuse to start when the self-check fails
+    Serve { strict: bool },
+    Reindex { model: String, thread_ids: Vec<String> },
+    Export { anonymize: bool, thread_ids: Vec<String> },
+    Eval(EvalArgs),
+    ValidateGraph { path: String },
+    Migrate { dry_run: bool },
+    Hygiene,
+    Doctor(DoctorArgs),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalArgs {
+    pub suites: Vec<String>,
+    pub model: String,
+    /// Grades `llm_judge` cases (default: `model`)
+    pub judge_model: Option<String>,
+    pub concurrency: usize,
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoctorArgs {
+    /// Make the (paid) 1-token LLM call
+    pub llm: bool,
+    pub model: String,
+    pub json: bool,
+}
+
+impl Command {
+    /// Parse the arguments after the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        match (args.next().as_deref(), args.next().as_deref()) {
+            (None, _) => Ok(Self::Serve { strict: false }),
+            (Some("--strict"), None) => Ok(Self::Serve { strict: true }),
+            (Some("threads"), Some("reindex")) => {
+                let mut model = DEFAULT_REINDEX_MODEL.to_string();
+                let mut thread_ids = Vec::new();
+                while let Some(arg) = args.next() {
+                    if arg == "--model" {
+                        model = args.next().ok_or_else(|| format!("--model needs a value\n{}", USAGE))?;
+                    } else if arg.starts_with('-') {
+                        return Err(format!("unknown option '{}'\n{}", arg, USAGE));
+                    } else {
+                        thread_ids.push(arg);
+                    }
+                }
+                Ok(Self::Reindex { model, thread_ids })
+            }
+            (Some("threads"), Some("export")) => {
+                let mut anonymize = false;
+                let mut thread_ids = Vec::new();
+                for arg in args {
+                    if arg == "--anonymize" {
+                        anonymize = true;
+                    } else if arg.starts_with('-') {
+                        return Err(format!("unknown option '{}'\n{}", arg, USAGE));
+                    } else {
+                        thread_ids.push(arg);
+                    }
+                }
+                if thread_ids.is_empty() {
+                    return Err(format!("threads export needs a thread id\n{}", USAGE));
+                }
+                Ok(Self::Export { anonymize, thread_ids })
+            }
+            (Some("eval"), Some("run")) => {
+                let mut eval = EvalArgs {
+                    suites: Vec::new(),
+                    model: DEFAULT_EVAL_MODEL.to_string(),
+                    judge_model: None,
+                    concurrency: DEFAULT_EVAL_CONCURRENCY,
+                    json: false,
+                };
+                while let Some(arg) = args.next() {
+                    let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value\n{}", name, USAGE));
+                    match arg.as_str() {
+                        "--model" => eval.model = value("--model")?,
+                        "--judge-model" => eval.judge_model = Some(value("--judge-model")?),
+                        "--concurrency" => {
+                            eval.concurrency = value("--concurrency")?
+                                .parse()
+                                .map_err(|_| format!("--concurrency needs a number\n{}", USAGE))?
+                        }
+                        "--json" => eval.json = true,
+                        _ if arg.starts_with('-') => return Err(format!("unknown option '{}'\n{}", arg, USAGE)),
+                        _ => eval.suites.push(arg),
+                    }
+                }
+                if eval.suites.is_empty() {
+                    return Err(format!("eval run needs a suite file\n{}", USAGE));
+                }
+                Ok(Self::Eval(eval))
+            }
+            (Some("graph"), Some("validate")) => match (args.next(), args.next()) {
+                (Some(path), None) if !path.starts_with('-') => Ok(Self::ValidateGraph { path }),
+                _ => Err(format!("graph validate needs one spec file\n{}", USAGE)),
+            },
+            (Some("db"), Some("migrate")) => match (args.next().as_deref(), args.next()) {
+                (None, _) => Ok(Self::Migrate { dry_run: false }),
+                (Some("--dry-run"), None) => Ok(Self::Migrate { dry_run: true }),
+                _ => Err(format!("db migrate only takes --dry-run\n{}", USAGE)),
+            },
+            (Some("db"), Some("hygiene")) => match args.next() {
+                None => Ok(Self::Hygiene),
+                Some(_) => Err(format!("db hygiene takes no arguments\n{}", USAGE)),
+            },
+            (Some("doctor"), first) => {
+                let mut doctor = DoctorArgs { llm: false, model: DEFAULT_DOCTOR_MODEL.to_string(), json: false };
+                let mut args = first.map(str::to_string).into_iter().chain(args);
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--llm" => doctor.llm = true,
+                        "--model" => {
+                            doctor.model = args.next().ok_or_else(|| format!("--model needs a value\n{}", USAGE))?
+                        }
+                        "--json" => doctor.json = true,
+                        _ => return Err(format!("unknown argument '{}'\n{}", arg, USAGE)),
+                    }
+                }
+                Ok(Self::Doctor(doctor))
+            }
+            _ => Err(USAGE.to_string()),
+        }
+    }
+}
+
+/// Backfill message stats, returning the number of messages updated
+pub async fn reindex(
+    client: &MongoPersistenceClient,
+    tokenizer: &dyn ContentTokenizer,
+    model: &str,
+    thread_ids: Vec<String>,
+) -> anyhow::Result<usize> {
+    let thread_ids = if thread_ids.is_empty() {
+        client.threads_needing_reindex().await?
+    } else {
+        thread_ids
+    };
+
+    let mut updated = 0;
+    for thread_id in &thread_ids {
+        let count = client.reindex_thread(thread_id, tokenizer, model).await?;
+        tracing::info!("Reindexed {} messages in thread {}", count, thread_id);
+        updated += count;
+    }
+    Ok(updated)
+}
+
+/// Write one JSON export per thread to `out`, returning how many were written
+///
+/// Fails on the first thread that doesn't exist.
+pub async fn export(
+    client: &dyn PersistenceClient,
+    anonymizer: Option<&AnonymizerConfig>,
+    thread_ids: Vec<String>,
+    out: &mut impl Write,
+) -> anyhow::Result<usize> {
+    for thread_id in &thread_ids {
+        let export = ThreadExport::load(client, thread_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("thread {} not found", thread_id))?;
+        let export = match anonymizer {
+            Some(config) => anonymize_thread(export, config),
+            None => export,
+        };
+        serde_json::to_writer(&mut *out, &export)?;
+        writeln!(out)?;
+    }
+    Ok(thread_ids.len())
+}
+
+/// Run eval suites and print their reports, returning whether every case passed
+pub async fn eval(
+    llm_client: Arc<dyn LLMClient>,
+    graph_config: GraphConfig,
+    args: EvalArgs,
+    out: &mut impl Write,
+) -> anyhow::Result<bool> {
+    // Only the suites' mock tools: no MCP servers
+    let graph = Graph::builder()
+        .llm_client(llm_client.clone())
+        .reasoning_client(llm_client.clone() as Arc<dyn praxis::ReasoningClient>)
+        .mcp_executor(Arc::new(MCPToolExecutor::new()))
+        .with_config(graph_config)
+        .build()?;
+    let judge_model = args.judge_model.unwrap_or_else(|| args.model.clone());
+    let runner = EvalRunner::new(Arc::new(graph), args.model)
+        .with_concurrency(args.concurrency)
+        .with_judge(llm_client, judge_model);
+
+    let mut reports: Vec<EvalReport> = Vec::new();
+    for path in &args.suites {
+        let suite = EvalSuite::load(path)?;
+        reports.push(runner.run(&suite).await);
+    }
+
+    if args.json {
+        serde_json::to_writer_pretty(&mut *out, &reports)?;
+        writeln!(out)?;
+    } else {
+        for report in &reports {
+            writeln!(out, "{}\n", report)?;
+        }
+    }
+    Ok(reports.iter().all(|report| report.passed == report.total))
+}
+
+/// Run schema migrations (only count with `dry_run`) and print the report
+pub async fn migrate(client: &dyn PersistenceClient, dry_run: bool, out: &mut impl Write) -> anyhow::Result<MigrationReport> {
+    let report = client.migrate(dry_run).await?;
+    writeln!(out, "{}", report)?;
+    Ok(report)
+}
+
+/// Run one hygiene pass and print the report
+pub async fn hygiene(runner: &HygieneRunner, policy: &HygienePolicy, out: &mut impl Write) -> anyhow::Result<HygieneReport> {
+    let report = runner
+        .run_hygiene(policy)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("another hygiene run holds the lease; try again once it finishes"))?;
+    writeln!(out, "{}", report)?;
+    Ok(report)
+}
+
+/// What `doctor` checks for `config`: MongoDB, the MCP servers, the OpenAI
+/// client with `model`, the routed and summary models, and Langfuse when it
+/// is the enabled observer
+pub fn doctor_config(
+    config: &Config,
+    persistence: Arc<dyn PersistenceClient>,
+    llm_client: Arc<dyn LLMClient>,
+    model: &str,
+) -> DoctorConfig {
+    let mut doctor = DoctorConfig::new().with_persistence(persistence).with_provider("openai", llm_client, model);
+    let stdio = config.mcp.stdio.iter().cloned().map(McpServerTarget::Stdio);
+    for target in config.mcp.server_targets().into_iter().chain(stdio) {
+        doctor = doctor.with_mcp_server(target);
+    }
+    let routed = config.routing.tenants.values().filter_map(|llm| llm.model.clone());
+    for model in routed.chain(config.graph.reasoning_summary_model.clone()) {
+        doctor = doctor.with_model(model);
+    }
+    #[cfg(feature = "observability")]
+    if config.observability.enabled && config.observability.provider == "langfuse" {
+        let langfuse = &config.observability.langfuse;
+        match praxis::LangfuseClient::new(langfuse.public_key.clone(), langfuse.secret_key.clone(), langfuse.host.clone()) {
+            Ok(client) => doctor = doctor.with_langfuse(client),
+            Err(e) => tracing::warn!("Failed to create Langfuse client: {}", e),
+        }
+    }
+    doctor
+}
+
+/// Run the checks and print the report
+pub async fn doctor(config: DoctorConfig, json: bool, out: &mut impl Write) -> anyhow::Result<DoctorReport> {
+    let report = praxis::doctor(config).await;
+    if json {
+        serde_json::to_writer_pretty(&mut *out, &report)?;
+        writeln!(out)?;
+    } else {
+        write!(out, "{}", report.to_table())?;
+    }
+    Ok(report)
+}
+
+/// Check the graph spec at `path` and describe it on `out`
+///
+/// `graph_config` is the `[graph]` config the spec's budgets apply over.
+pub fn validate_graph(path: &str, graph_config: GraphConfig, out: &mut impl Write) -> anyhow::Result<()> {
+    let spec = GraphSpec::load(path)?;
+    spec.validate(&NodeRegistry::new())?;
+    spec.apply_to(graph_config)
+        .validate()
+        .map_err(|e| anyhow::anyhow!("budgets: {}", e))?;
+
+    writeln!(out, "{}: ok", path)?;
+    writeln!(out, "  pipeline: {}", spec.pipeline.join(" -> "))?;
+    if let Some(rounds) = spec.router.max_tool_rounds {
+        writeln!(out, "  max tool rounds: {}", rounds)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(&[]), Ok(Command::Serve { strict: false }));
+        assert_eq!(parse(&["--strict"]), Ok(Command::Serve { strict: true }));
+        assert_eq!(
+            parse(&["threads", "reindex"]),
+            Ok(Command::Reindex { model: DEFAULT_REINDEX_MODEL.to_string(), thread_ids: vec![] })
+        );
+        assert_eq!(
+            parse(&["threads", "reindex", "--model", "gpt-5", "t1", "t2"]),
+            Ok(Command::Reindex {
+                model: "gpt-5".to_string(),
+                thread_ids: vec!["t1".to_string(), "t2".to_string()],
+            })
+        );
+        assert!(parse(&["threads", "reindex", "--model"]).is_err());
+        assert_eq!(
+            parse(&["threads", "export", "t1", "--anonymize"]),
+            Ok(Command::Export { anonymize: true, thread_ids: vec!["t1".to_string()] })
+        );
+        assert!(parse(&["threads", "export", "--anonymize"]).is_err());
+        assert_eq!(
+            parse(&["eval", "run", "--concurrency", "2", "--json", "a.yaml", "b.yaml"]),
+            Ok(Command::Eval(EvalArgs {
+                suites: vec!["a.yaml".to_string(), "b.yaml".to_string()],
+                model: DEFAULT_EVAL_MODEL.to_string(),
+                judge_model: None,
+                concurrency: 2,
+                json: true,
+            }))
+        );
+        assert!(parse(&["eval", "run", "--concurrency", "many", "a.yaml"]).is_err());
+        assert!(parse(&["eval", "run"]).is_err());
+        assert_eq!(
+            parse(&["graph", "validate", "graph.toml"]),
+            Ok(Command::ValidateGraph { path: "graph.toml".to_string() })
+        );
+        assert!(parse(&["graph", "validate"]).is_err());
+        assert!(parse(&["graph", "validate", "a.toml", "b.toml"]).is_err());
+        assert_eq!(parse(&["db", "migrate"]), Ok(Command::Migrate { dry_run: false }));
+        assert_eq!(parse(&["db", "migrate", "--dry-run"]), Ok(Command::Migrate { dry_run: true }));
+        assert!(parse(&["db", "migrate", "--force"]).is_err());
+        assert_eq!(parse(&["db", "hygiene"]), Ok(Command::Hygiene));
+        assert!(parse(&["db", "hygiene", "--dry-run"]).is_err());
+        assert_eq!(
+            parse(&["doctor"]),
+            Ok(Command::Doctor(DoctorArgs { llm: false, model: DEFAULT_DOCTOR_MODEL.to_string(), json: false }))
+        );
+        assert_eq!(
+            parse(&["doctor", "--llm", "--model", "gpt-5", "--json"]),
+            Ok(Command::Doctor(DoctorArgs { llm: true, model: "gpt-5".to_string(), json: true }))
+        );
+        assert!(parse(&["doctor", "--model"]).is_err());
+        assert!(parse(&["--strict", "doctor"]).is_err());
+        assert!(parse(&["threads"]).is_err());
+        assert!(parse(&["serve"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_prints_the_report() {
+        let mut out = Vec::new();
+        let report = migrate(&praxis::InMemoryPersistenceClient::new(), true, &mut out).await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!(String::from_utf8(out).unwrap(), "no migrations\n");
+    }
+
+    #[tokio::test]
+    async fn test_hygiene_prints_the_report_unless_the_lease_is_held() {
+        use praxis::HygieneStore;
+
+        let store = Arc::new(praxis::InMemoryPersistenceClient::new());
+        let runner = HygieneRunner::new(store.clone(), "cli");
+        let mut out = Vec::new();
+        let report = hygiene(&runner, &HygienePolicy::default(), &mut out).await.unwrap();
+        assert_eq!(report, HygieneReport::default());
+        assert!(String::from_utf8(out).unwrap().starts_with("idle threads: 0\n"));
+
+        let lease = std::time::Duration::from_secs(60);
+        assert!(store.acquire_lease(praxis::HYGIENE_LEASE, "server", lease).await.unwrap());
+        assert!(hygiene(&runner, &HygienePolicy::default(), &mut Vec::new()).await.is_err());
+    }
+}
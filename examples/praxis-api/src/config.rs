@@ -1,6 +1,12 @@
 use config::{Config as ConfigLoader, ConfigError, Environment, File};
+use praxis::{
+    AnonymizerConfig, CompressionConfig, GraphConfig, LLMConfigOverride, McpServerTarget, PersistencePolicy, ReadConsistency, RetryPolicy,
+    RulesAuthorizer, StdioServerConfig, ToolConfig,
+};
+use std::collections::HashMap;
 use serde::Deserialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -8,15 +14,52 @@ pub struct Config {
     pub cors: CorsConfig,
     pub mongodb: MongoDbConfig,
     pub mcp: McpConfig,
+    #[serde(default)]
+    pub llm: LlmConfig,
+    #[serde(default)]
+    pub stream: StreamConfig,
     pub logging: LoggingConfig,
     #[serde(default)]
     pub observability: ObservabilityConfig,
+    #[serde(default)]
+    pub graph: GraphConfig,
+    #[serde(default)]
+    pub graph_spec: GraphSpecConfig,
+    #[serde(default)]
+    pub persistence: PersistencePolicy,
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub context: ContextConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub outbox: OutboxConfig,
+    #[serde(default)]
+    pub hygiene: HygieneConfig,
+    #[serde(default)]
+    pub attachments: AttachmentsConfig,
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    /// Tool call authorization rules (default: every tool allowed)
+    #[serde(default)]
+    pub authorization: RulesAuthorizer,
     
     // Secrets (from ENV only)
     #[serde(default)]
     pub mongodb_uri: String,
     #[serde(default)]
     pub openai_api_key: String,
+    /// `OpenAI-Organization` / `OpenAI-Project` scoping (unset = key default)
+    #[serde(default)]
+    pub openai_organization: Option<String>,
+    #[serde(default)]
+    pub openai_project: Option<String>,
+    /// Bearer token granting the admin scope (unset = no admin access)
+    #[serde(default)]
+    pub admin_token: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -25,6 +68,10 @@ pub struct ServerConfig {
     pub port: u16,
     #[serde(default)]
     pub workers: usize,
+    /// Run the doctor's checks (without provider calls) before serving and
+    /// log what fails; `--strict` refuses to start on critical failures
+    #[serde(default)]
+    pub self_check: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -38,13 +85,320 @@ pub struct MongoDbConfig {
     pub database: String,
     pub pool_size: u32,
     pub timeout_ms: u64,
+    /// zstd compression of large message content at rest
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// `read_your_writes` pins reads to the primary (needed with secondary reads)
+    #[serde(default)]
+    pub read_consistency: ReadConsistency,
+    /// Run schema migrations before serving (off: run `db migrate` by hand)
+    #[serde(default)]
+    pub migrate_on_startup: bool,
+}
+
+
+
+/// Server-side model routing (see `routing::DefaultModelRouter`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RoutingConfig {
+    /// LLM settings forced for every thread of a tenant, keyed by tenant id
+    #[serde(default)]
+    pub tenants: HashMap<String, LLMConfigOverride>,
+}
+
+/// Run admission (see `scheduler::RunScheduler`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Runs executing at once across tenants (0 = no limit)
+    pub max_concurrent_runs: usize,
+    /// Weight of tenants without an entry in `tenants`
+    pub default_weight: u32,
+    /// Share of the run slots per tenant, keyed by tenant id
+    pub tenants: HashMap<String, TenantShare>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_runs: 0,
+            default_weight: 1,
+            tenants: HashMap::new(),
+        }
+    }
 }
 
+impl SchedulerConfig {
+    /// Weight (at least 1) and own concurrency cap of `tenant`
+    pub fn share(&self, tenant: &str) -> (u32, Option<usize>) {
+        let share = self.tenants.get(tenant);
+        let weight = share.and_then(|share| share.weight).unwrap_or(self.default_weight);
+        (weight.max(1), share.and_then(|share| share.max_concurrent))
+    }
+}
+
+/// When threads get summarized, on top of the context budget overflowing,
+/// and how the context window is assembled
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ContextConfig {
+    /// Summarize every N assistant answers (unset = no turn cadence)
+    pub summarize_every_turns: Option<usize>,
+    /// Summarize when a thread resumes after this many idle minutes
+    /// (unset = no idle cadence)
+    pub summarize_after_idle_minutes: Option<u32>,
+    /// Build the window from these stages, in order, each with its share of
+    /// the budget (empty = summary plus everything after it)
+    pub stages: Vec<praxis::ContextStageConfig>,
+}
+
+impl ContextConfig {
+    /// The configured cadence, if any is set
+    pub fn cadence(&self) -> Option<praxis::CadenceTrigger> {
+        if self.summarize_every_turns.is_none() && self.summarize_after_idle_minutes.is_none() {
+            return None;
+        }
+        let mut cadence = praxis::CadenceTrigger::new();
+        if let Some(turns) = self.summarize_every_turns {
+            cadence = cadence.with_every_turns(turns);
+        }
+        if let Some(minutes) = self.summarize_after_idle_minutes {
+            cadence = cadence.with_after_idle(chrono::Duration::minutes(minutes.into()));
+        }
+        Some(cadence)
+    }
+}
 
+/// A tenant's `[scheduler.tenants.<id>]` entry
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TenantShare {
+    /// Runs started per round-robin turn while the tenant has runs queued
+    /// (unset = `default_weight`)
+    #[serde(default)]
+    pub weight: Option<u32>,
+    /// Runs of this tenant executing at once (unset = only the global limit)
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+}
+
+/// Limits applied to the shared LLM client
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// Provider requests in flight across all runs; others queue for a slot
+    pub max_concurrent_requests: usize,
+    /// Resend provider requests that hit a 429 or a transient 5xx (unset = send once)
+    pub retry: Option<RetryPolicy>,
+    /// Wait for a provider response; streams only need to start in time
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    /// Abort a provider stream after this long without an event
+    pub stream_idle_timeout_secs: u64,
+    /// Calls per provider that `/health/llm` stats cover
+    pub stats_window: usize,
+    /// Record every LLM call, one JSONL file per run, for offline replay
+    /// with `ReplayClient` (unset = off; recordings hold full prompts)
+    pub record_dir: Option<std::path::PathBuf>,
+    /// Extra headers sent with every OpenAI request (`[llm.headers]`)
+    pub headers: HashMap<String, String>,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 32,
+            retry: None,
+            timeout_secs: 120,
+            connect_timeout_secs: 10,
+            stream_idle_timeout_secs: 60,
+            stats_window: 200,
+            record_dir: None,
+            headers: HashMap::new(),
+        }
+    }
+}
+
+/// Extras sent on the message SSE stream
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StreamConfig {
+    /// Lead the stream with a `thread_info` event (costs one message count read)
+    pub thread_info: bool,
+    /// Seconds between keep-alive comments while the stream is otherwise
+    /// quiet; 0 sends none
+    pub keep_alive_secs: u64,
+    /// How long `?wire=compact` streams hold message deltas to send them as
+    /// one event (the verbose stream sends each delta as it arrives)
+    pub compact_window_ms: u64,
+    /// Seconds a finished stream can still be resumed with `Last-Event-ID`
+    pub replay_retention_secs: u64,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self { thread_info: true, keep_alive_secs: 15, compact_window_ms: 250, replay_retention_secs: 300 }
+    }
+}
+
+/// Anonymization of thread exports (`?anonymize=true`, `threads export --anonymize`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// Keys the pseudonyms; exports with the same seed are identical
+    pub seed: u64,
+    /// Tools whose results are left out of anonymized exports
+    pub sensitive_tools: Vec<String>,
+    /// Keys dropped on top of the defaults (`password`, `token`, ...)
+    pub blocked_keys: Vec<String>,
+}
+
+impl ExportConfig {
+    pub fn anonymizer(&self) -> AnonymizerConfig {
+        let config = AnonymizerConfig::new().with_seed(self.seed);
+        let config = self.sensitive_tools.iter().fold(config, |c, tool| c.with_sensitive_tool(tool.clone()));
+        self.blocked_keys.iter().fold(config, |c, key| c.with_blocked_key(key.clone()))
+    }
+}
+
+/// Files uploaded with `POST /threads/{id}/attachments`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AttachmentsConfig {
+    /// Largest accepted file, in bytes
+    pub max_bytes: usize,
+    /// Accepted mime types; `type/*` accepts a whole family
+    pub allowed_mime_types: Vec<String>,
+}
+
+impl Default for AttachmentsConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            allowed_mime_types: ["text/*", "application/json", "application/pdf", "image/png", "image/jpeg"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl AttachmentsConfig {
+    /// Whether `mime_type` (parameters like `charset` ignored) is allowed
+    pub fn allows(&self, mime_type: &str) -> bool {
+        let essence = mime_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        self.allowed_mime_types.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(family) => essence.strip_prefix(family).is_some_and(|rest| rest.starts_with('/')),
+            None => essence == allowed.to_ascii_lowercase(),
+        })
+    }
+}
+
+/// Cost estimates of runs (see `praxis::CostCalculator`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PricingConfig {
+    /// Report `cost_usd` on `done` events
+    pub enabled: bool,
+    /// USD per 1M tokens by model-name prefix, over the built-in table
+    pub models: HashMap<String, praxis::ModelPrice>,
+}
+
+impl PricingConfig {
+    /// The calculator for the graph (None when disabled)
+    pub fn calculator(&self) -> Option<praxis::CostCalculator> {
+        self.enabled.then(|| praxis::CostCalculator::new().with_prices(self.models.clone()))
+    }
+}
+
+/// Declarative graph definition (see `praxis::GraphSpec`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GraphSpecConfig {
+    /// Spec file shaping the graph; its budgets override `[graph]` (unset = default graph)
+    pub path: Option<PathBuf>,
+}
+
+/// Webhooks, delivered through the persistence outbox (see `webhooks`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OutboxConfig {
+    /// Receives a `run.completed` POST after every run (unset = no webhooks)
+    pub webhook_url: Option<String>,
+    /// Wait between polls for due entries
+    pub poll_interval_ms: u64,
+    /// How long a replica holds an entry while delivering it
+    pub lease_secs: u64,
+    /// Delivery attempts before an entry is marked failed
+    pub max_attempts: u32,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            poll_interval_ms: 1000,
+            lease_secs: 60,
+            max_attempts: 8,
+        }
+    }
+}
+
+/// Background hygiene of idle threads (see `praxis::HygieneRunner`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HygieneConfig {
+    /// Run on every replica; a lease keeps runs from overlapping
+    pub enabled: bool,
+    /// Wait between runs
+    pub interval_secs: u64,
+    /// How long a replica holds the run lease between threads
+    pub lease_secs: u64,
+    /// Which threads are processed and what is done to them
+    #[serde(flatten)]
+    pub policy: praxis::HygienePolicy,
+}
+
+impl Default for HygieneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 3600,
+            lease_secs: 600,
+            policy: praxis::HygienePolicy::default(),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct McpConfig {
     pub servers: String,
+    /// Stdio servers, each optionally run as a warm pool (`[[mcp.stdio]]`)
+    #[serde(default)]
+    pub stdio: Vec<StdioServerConfig>,
+    /// Per-tool settings by tool name (`[mcp.tools.<name>]`)
+    #[serde(default)]
+    pub tools: HashMap<String, ToolConfig>,
+}
+
+impl McpConfig {
+    /// The servers of `servers`, named `mcp-server-<index>`
+    ///
+    /// `stdio:<command> [args...]` spawns a local server, anything else is an HTTP URL.
+    pub fn server_targets(&self) -> Vec<McpServerTarget> {
+        self.servers
+            .split(',')
+            .enumerate()
+            .map(|(idx, url)| (format!("mcp-server-{}", idx), url.trim()))
+            .filter(|(_, url)| !url.is_empty())
+            .map(|(name, url)| match url.strip_prefix("stdio:") {
+                Some(command_line) => {
+                    let mut parts = command_line.split_whitespace();
+                    let command = parts.next().unwrap_or_default();
+                    McpServerTarget::Stdio(StdioServerConfig::new(name, command).with_args(parts))
+                }
+                None => McpServerTarget::Http { name, url: url.to_string() },
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -169,6 +523,15 @@ impl Config {
             .map_err(|_| ConfigError::Message("MONGODB_URI environment variable is required".to_string()))?;
         cfg.openai_api_key = std::env::var("OPENAI_API_KEY")
             .map_err(|_| ConfigError::Message("OPENAI_API_KEY environment variable is required".to_string()))?;
+        cfg.openai_organization = std::env::var("OPENAI_ORGANIZATION").ok().filter(|v| !v.is_empty());
+        cfg.openai_project = std::env::var("OPENAI_PROJECT").ok().filter(|v| !v.is_empty());
+        cfg.admin_token = std::env::var("ADMIN_TOKEN").unwrap_or_default();
+        if let Ok(path) = std::env::var("GRAPH_SPEC_PATH") {
+            cfg.graph_spec.path = Some(PathBuf::from(path)).filter(|path| !path.as_os_str().is_empty());
+        }
+        if let Ok(url) = std::env::var("OUTBOX_WEBHOOK_URL") {
+            cfg.outbox.webhook_url = Some(url).filter(|url| !url.is_empty());
+        }
         
         if let Ok(enabled) = std::env::var("OBSERVABILITY_ENABLED") {
             cfg.observability.enabled = enabled.to_lowercase() == "true" || enabled == "1";
@@ -183,6 +546,11 @@ impl Config {
             cfg.observability.langfuse.secret_key = secret_key;
         }
         
+        apply_graph_env_overrides(&mut cfg.graph)?;
+        cfg.graph
+            .validate()
+            .map_err(|e| ConfigError::Message(format!("Invalid [graph] config: graph.{}", e)))?;
+        
         Ok(cfg)
     }
     
@@ -196,6 +564,44 @@ impl Config {
     }
 }
 
+/// Apply `GRAPH_*` environment overrides to the `[graph]` section
+fn apply_graph_env_overrides(graph: &mut GraphConfig) -> Result<(), ConfigError> {
+    if let Some(max_iterations) = parse_graph_env("GRAPH_MAX_ITERATIONS", "max_iterations")? {
+        graph.max_iterations = max_iterations;
+    }
+    if let Some(secs) = parse_graph_env::<u64>("GRAPH_EXECUTION_TIMEOUT_SECS", "execution_timeout_secs")? {
+        graph.execution_timeout = std::time::Duration::from_secs(secs);
+    }
+    if let Some(enabled) = parse_graph_env("GRAPH_ENABLE_CANCELLATION", "enable_cancellation")? {
+        graph.enable_cancellation = enabled;
+    }
+    if let Some(capacity) = parse_graph_env("GRAPH_EVENT_CHANNEL_CAPACITY", "event_channel_capacity")? {
+        graph.event_channel_capacity = capacity;
+    }
+    if let Some(raw) = parse_graph_env("GRAPH_SEND_RAW_END_USER_ID", "send_raw_end_user_id")? {
+        graph.send_raw_end_user_id = raw;
+    }
+    if let Some(retries) = parse_graph_env("GRAPH_RATE_LIMIT_RETRIES", "rate_limit_retries")? {
+        graph.rate_limit_retries = retries;
+    }
+    if let Ok(model) = std::env::var("GRAPH_REASONING_SUMMARY_MODEL") {
+        graph.reasoning_summary_model = Some(model).filter(|m| !m.trim().is_empty());
+    }
+    Ok(())
+}
+
+fn parse_graph_env<T: FromStr>(var: &str, field: &str) -> Result<Option<T>, ConfigError> {
+    match std::env::var(var) {
+        Ok(value) => value.trim().parse().map(Some).map_err(|_| {
+            ConfigError::Message(format!(
+                "Invalid value '{}' for graph.{} (from {})",
+                value, field, var
+            ))
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,6 +640,277 @@ mod tests {
         let config: Config = toml::from_str(toml).unwrap();
         assert_eq!(config.server.port, 3000);
         assert_eq!(config.mongodb.database, "test");
+        assert_eq!(config.graph, GraphConfig::default());
+    }
+
+    #[test]
+    fn test_partial_graph_section_fills_defaults() {
+        let toml = r#"
+            [server]
+            host = "127.0.0.1"
+            port = 3000
+
+            [cors]
+            enabled = false
+            origins = []
+
+            [mongodb]
+            database = "test"
+            pool_size = 5
+            timeout_ms = 3000
+
+            [mcp]
+            servers = ""
+
+            [logging]
+            level = "info"
+            format = "pretty"
+
+            [graph]
+            max_iterations = 12
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let defaults = GraphConfig::default();
+        assert_eq!(config.graph.max_iterations, 12);
+        assert_eq!(config.graph.execution_timeout, defaults.execution_timeout);
+        assert_eq!(config.graph.event_channel_capacity, defaults.event_channel_capacity);
+        assert_eq!(config.graph.enable_cancellation, defaults.enable_cancellation);
+    }
+
+    #[test]
+    fn test_persistence_section_maps_to_policy() {
+        let toml = r#"
+            [server]
+            host = "127.0.0.1"
+            port = 3000
+
+            [cors]
+            enabled = false
+            origins = []
+
+            [mongodb]
+            database = "test"
+            pool_size = 5
+            timeout_ms = 3000
+
+            [mcp]
+            servers = ""
+
+            [logging]
+            level = "info"
+            format = "pretty"
+
+            [persistence]
+            save_reasoning = false
+            content_max_bytes = 1024
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        assert!(!config.persistence.save_reasoning);
+        assert!(config.persistence.save_tool_results);
+        assert_eq!(config.persistence.content_max_bytes, Some(1024));
     }
-}
 
+    #[test]
+    fn test_hygiene_section_maps_to_policy() {
+        let toml = r#"
+            [server]
+            host = "127.0.0.1"
+            port = 3000
+
+            [cors]
+            enabled = false
+            origins = []
+
+            [mongodb]
+            database = "test"
+            pool_size = 5
+            timeout_ms = 3000
+
+            [mcp]
+            servers = ""
+
+            [logging]
+            level = "info"
+            format = "pretty"
+
+            [hygiene]
+            enabled = true
+            idle_days = 30
+            actions = ["compress_tool_results", "archive"]
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        assert!(config.hygiene.enabled);
+        assert_eq!(config.hygiene.interval_secs, 3600);
+        assert_eq!(
+            config.hygiene.policy,
+            praxis::HygienePolicy::new()
+                .with_idle_days(30)
+                .with_actions([praxis::HygieneAction::CompressToolResults, praxis::HygieneAction::Archive])
+        );
+    }
+
+    #[test]
+    fn test_pricing_section_extends_built_in_prices() {
+        let toml = r#"
+            [server]
+            host = "127.0.0.1"
+            port = 3000
+
+            [cors]
+            enabled = false
+            origins = []
+
+            [mongodb]
+            database = "test"
+            pool_size = 5
+            timeout_ms = 3000
+
+            [mcp]
+            servers = ""
+
+            [logging]
+            level = "info"
+            format = "pretty"
+
+            [pricing]
+            enabled = true
+
+            [pricing.models]
+            "acme-gpt" = { input = 1.0, output = 4.0 }
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let calculator = config.pricing.calculator().unwrap();
+        assert_eq!(calculator.price("acme-gpt-large"), Some(&praxis::ModelPrice::new(1.0, 4.0)));
+        assert!(calculator.price("gpt-4o").is_some());
+        assert!(PricingConfig::default().calculator().is_none());
+    }
+
+    #[test]
+    fn test_mcp_stdio_servers_with_pool() {
+        let toml = r#"
+            [server]
+            host = "127.0.0.1"
+            port = 3000
+
+            [cors]
+            enabled = false
+            origins = []
+
+            [mongodb]
+            database = "test"
+            pool_size = 5
+            timeout_ms = 3000
+
+            [mcp]
+            servers = ""
+
+            [[mcp.stdio]]
+            name = "search"
+            command = "python"
+            args = ["-m", "search_server"]
+            pool = { min = 1, max = 4 }
+
+            [[mcp.stdio]]
+            name = "files"
+            command = "mcp-files"
+
+            [logging]
+            level = "info"
+            format = "pretty"
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let [search, files] = &config.mcp.stdio[..] else {
+            panic!("expected two stdio servers");
+        };
+        let pool = search.pool.as_ref().unwrap();
+        assert_eq!((pool.min, pool.max, pool.idle_timeout_secs), (1, 4, 300));
+        assert!(files.pool.is_none() && files.args.is_empty());
+    }
+
+    #[test]
+    fn test_authorization_rules_authorize_by_role() {
+        use praxis::{Decision, RunContext, ToolAuthorizer};
+
+        let toml = r#"
+            [server]
+            host = "127.0.0.1"
+            port = 3000
+
+            [cors]
+            enabled = false
+            origins = []
+
+            [mongodb]
+            database = "test"
+            pool_size = 5
+            timeout_ms = 3000
+
+            [mcp]
+            servers = ""
+
+            [logging]
+            level = "info"
+            format = "pretty"
+
+            [[authorization.rules]]
+            tool = "delete_*"
+            roles = ["admin"]
+            effect = "allow"
+
+            [[authorization.rules]]
+            tool = "delete_*"
+            effect = "deny"
+            reason = "admins only"
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let admin = RunContext { roles: vec!["admin".to_string()], ..Default::default() };
+        let args = serde_json::json!({});
+
+        assert_eq!(config.authorization.authorize(&admin, "delete_record", &args), Decision::Allow);
+        assert_eq!(
+            config.authorization.authorize(&RunContext::default(), "delete_record", &args),
+            Decision::deny("admins only")
+        );
+        assert!(config.authorization.authorize(&RunContext::default(), "search", &args).is_allowed());
+    }
+
+    #[test]
+    fn test_attachment_mime_allowlist() {
+        let config = AttachmentsConfig::default();
+        assert!(config.allows("text/plain"));
+        assert!(config.allows("text/csv; charset=utf-8"));
+        assert!(config.allows("Application/JSON"));
+        assert!(!config.allows("textual/plain"));
+        assert!(!config.allows("image/gif"));
+        assert!(!config.allows("application/octet-stream"));
+    }
+
+    #[test]
+    fn test_scheduler_tenant_shares() {
+        let config: SchedulerConfig = toml::from_str(
+            r#"
+            max_concurrent_runs = 16
+            default_weight = 2
+
+            [tenants.acme]
+            weight = 5
+            max_concurrent = 8
+
+            [tenants.small]
+            max_concurrent = 1
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.max_concurrent_runs, 16);
+        assert_eq!(config.share("acme"), (5, Some(8)));
+        assert_eq!(config.share("small"), (2, Some(1)));
+        assert_eq!(config.share("unknown"), (2, None));
+    }
+}
@@ -1,79 +1,370 @@
+//! Error responses
+//!
+//! Every non-SSE error is returned as an RFC 7807 `application/problem+json`
+//! body. The SSE `error` event carries the same `Problem` shape, so clients
+//! handle both with one code path. `ERROR_CODES` lists every `code` the API
+//! can emit. A provider failure is reported with its `LLMError` code when
+//! the catalog has it, and as `provider_error` otherwise.
+
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use praxis::{GraphErrorKind, LLMError};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Prefix of the problem `type` URI; the error code is appended
+pub const PROBLEM_TYPE_PREFIX: &str = "urn:praxis:error:";
+
+/// One entry of the error code catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ErrorCodeInfo {
+    pub code: &'static str,
+    pub status: u16,
+    pub title: &'static str,
+    pub retryable: bool,
+}
+
+const fn graph_code(kind: GraphErrorKind, status: u16, title: &'static str) -> ErrorCodeInfo {
+    ErrorCodeInfo {
+        code: kind.code(),
+        status,
+        title,
+        retryable: kind.is_retryable(),
+    }
+}
+
+/// Every error code the API emits, in HTTP problems and SSE `error` events
+pub const ERROR_CODES: &[ErrorCodeInfo] = &[
+    ErrorCodeInfo { code: "thread_not_found", status: 404, title: "Thread not found", retryable: false },
+    ErrorCodeInfo { code: "message_not_found", status: 404, title: "Message not found", retryable: false },
+    ErrorCodeInfo { code: "stream_not_found", status: 404, title: "Stream not found", retryable: false },
+    ErrorCodeInfo { code: "route_not_found", status: 404, title: "Route not found", retryable: false },
+    ErrorCodeInfo { code: "bad_request", status: 400, title: "Invalid request", retryable: false },
+    ErrorCodeInfo { code: "forbidden", status: 403, title: "Forbidden", retryable: false },
+    ErrorCodeInfo { code: "payload_too_large", status: 413, title: "Payload too large", retryable: false },
+    ErrorCodeInfo { code: "unsupported_media_type", status: 415, title: "Unsupported media type", retryable: false },
+    ErrorCodeInfo { code: "invalid_messages", status: 422, title: "Invalid messages", retryable: false },
+    ErrorCodeInfo { code: "conversation_key_conflict", status: 409, title: "Conversation key conflict", retryable: false },
+    ErrorCodeInfo { code: "database_error", status: 503, title: "Database error", retryable: true },
+    ErrorCodeInfo { code: "storage_error", status: 503, title: "Storage error", retryable: true },
+    ErrorCodeInfo { code: "configuration_error", status: 500, title: "Configuration error", retryable: false },
+    graph_code(GraphErrorKind::ProviderError, 502, "LLM provider error"),
+    graph_code(GraphErrorKind::MaxIterations, 500, "Maximum iterations reached"),
+    graph_code(GraphErrorKind::ExecutionTimeout, 504, "Execution timed out"),
+    graph_code(GraphErrorKind::Cancelled, 409, "Execution cancelled"),
+    graph_code(GraphErrorKind::ContentFiltered, 422, "Answer stopped by the content filter"),
+    graph_code(GraphErrorKind::ModerationBlocked, 422, "Flagged by moderation"),
+    graph_code(GraphErrorKind::InternalError, 500, "Internal server error"),
+    ErrorCodeInfo { code: "rate_limited", status: 429, title: "LLM provider rate limit reached", retryable: true },
+    ErrorCodeInfo { code: "auth_failed", status: 502, title: "LLM provider rejected the credentials", retryable: false },
+    ErrorCodeInfo { code: "context_length_exceeded", status: 400, title: "Conversation too long for the model", retryable: false },
+    ErrorCodeInfo { code: "invalid_request", status: 502, title: "LLM provider rejected the request", retryable: false },
+    ErrorCodeInfo { code: "transport_error", status: 502, title: "LLM provider unreachable", retryable: true },
+    ErrorCodeInfo { code: "parse_error", status: 502, title: "Unreadable LLM provider response", retryable: false },
+    ErrorCodeInfo { code: "validation_failed", status: 422, title: "LLM request failed validation", retryable: false },
+];
+
+/// Catalog code for a graph error: the provider's `LLMError` code if listed, else the kind's
+fn graph_error_code(kind: GraphErrorKind, error_code: Option<&str>) -> &str {
+    error_code
+        .filter(|code| ERROR_CODES.iter().any(|info| info.code == *code))
+        .unwrap_or(kind.code())
+}
+
+/// Catalog entry for a code (falls back to `internal_error`)
+pub fn error_code_info(code: &str) -> &'static ErrorCodeInfo {
+    ERROR_CODES
+        .iter()
+        .find(|info| info.code == code)
+        .or_else(|| ERROR_CODES.iter().find(|info| info.code == GraphErrorKind::InternalError.code()))
+        .expect("internal_error is in the catalog")
+}
+
+/// RFC 7807 problem details with `code` and `retryable` extensions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub code: String,
+    pub retryable: bool,
+}
+
+impl Problem {
+    /// Build a problem from a catalog code
+    pub fn new(code: &str, detail: Option<String>) -> Self {
+        let info = error_code_info(code);
+        Self {
+            type_uri: format!("{}{}", PROBLEM_TYPE_PREFIX, info.code),
+            title: info.title.to_string(),
+            status: info.status,
+            detail,
+            code: info.code.to_string(),
+            retryable: info.retryable,
+        }
+    }
+
+    /// Problem for a graph error (also used for SSE `error` events)
+    ///
+    /// `error_code` is the event's `LLMError` code, if the provider failed.
+    pub fn from_graph_error(kind: GraphErrorKind, error_code: Option<&str>, detail: impl Into<String>) -> Self {
+        Self::new(graph_error_code(kind, error_code), Some(detail.into()))
+    }
+}
+
+/// The payload of the SSE `error` event
+impl From<Problem> for praxis::wire::Problem {
+    fn from(problem: Problem) -> Self {
+        Self {
+            type_uri: problem.type_uri,
+            title: problem.title,
+            status: problem.status,
+            detail: problem.detail,
+            code: problem.code,
+            retryable: problem.retryable,
+        }
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (
+            status,
+            [(header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE)],
+            Json(self),
+        )
+            .into_response()
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ApiError {
     #[error("Thread not found: {0}")]
     ThreadNotFound(String),
-    
+
     #[error("Message not found: {0}")]
     MessageNotFound(String),
-    
+
+    #[error("Stream not found: {0}")]
+    StreamNotFound(String),
+
+    #[error("No route for {0}")]
+    RouteNotFound(String),
+
     #[error("Invalid request: {0}")]
     BadRequest(String),
-    
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
     #[error("Database error: {0}")]
     Database(#[from] mongodb::error::Error),
-    
+
     #[error("BSON error: {0}")]
     Bson(#[from] bson::de::Error),
-    
+
     #[error("Persistence error: {0}")]
     Persist(#[from] praxis::PersistError),
-    
+
     #[error("Graph execution error: {0}")]
     Graph(#[from] anyhow::Error),
-    
+
     #[error("Configuration error: {0}")]
     Config(String),
-    
+
     #[error("Internal server error")]
     Internal,
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status, message) = match self {
-            ApiError::ThreadNotFound(_) | ApiError::MessageNotFound(_) => {
-                (StatusCode::NOT_FOUND, self.to_string())
+impl ApiError {
+    /// Problem details for this error; server-side causes are logged, not exposed
+    pub fn to_problem(&self) -> Problem {
+        match self {
+            ApiError::ThreadNotFound(_) => Problem::new("thread_not_found", Some(self.to_string())),
+            ApiError::MessageNotFound(_) => Problem::new("message_not_found", Some(self.to_string())),
+            ApiError::StreamNotFound(_) => Problem::new("stream_not_found", Some(self.to_string())),
+            ApiError::RouteNotFound(_) => Problem::new("route_not_found", Some(self.to_string())),
+            ApiError::BadRequest(_) => Problem::new("bad_request", Some(self.to_string())),
+            ApiError::Forbidden(_) => Problem::new("forbidden", Some(self.to_string())),
+            ApiError::PayloadTooLarge(_) => Problem::new("payload_too_large", Some(self.to_string())),
+            ApiError::UnsupportedMediaType(_) => Problem::new("unsupported_media_type", Some(self.to_string())),
+            ApiError::Persist(praxis::PersistError::InvalidMessage { index, reason }) => Problem::new(
+                "invalid_messages",
+                Some(format!("Invalid messages: messages[{}]: {}", index, reason)),
+            ),
+            ApiError::Persist(e @ praxis::PersistError::ConversationKeyConflict(_)) => {
+                Problem::new("conversation_key_conflict", Some(e.to_string()))
             }
-            ApiError::BadRequest(_) => {
-                (StatusCode::BAD_REQUEST, self.to_string())
-            }
-            ApiError::Database(ref e) => {
+            ApiError::Database(e) => {
                 tracing::error!("Database error: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
+                Problem::new("database_error", None)
             }
-            ApiError::Persist(ref e) => {
+            ApiError::Persist(e) => {
                 tracing::error!("Persistence error: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Storage error".to_string())
+                Problem::new("storage_error", None)
             }
-            ApiError::Graph(ref e) => {
+            ApiError::Graph(e) => {
                 tracing::error!("Graph error: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Processing error".to_string())
+                let error_code = LLMError::of(e).map(LLMError::code);
+                Problem::new(graph_error_code(GraphErrorKind::of(e), error_code), None)
             }
-            ApiError::Config(ref msg) => {
+            ApiError::Config(msg) => {
                 tracing::error!("Config error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Configuration error".to_string())
+                Problem::new("configuration_error", None)
             }
             ApiError::Bson(_) | ApiError::Internal => {
                 tracing::error!("Internal error: {}", self);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+                Problem::new(GraphErrorKind::InternalError.code(), None)
             }
-        };
-        
-        let body = Json(json!({
-            "error": message
-        }));
-        
-        (status, body).into_response()
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        self.to_problem().into_response()
     }
 }
 
 pub type ApiResult<T> = Result<T, ApiError>;
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use praxis::GraphError;
+
+    async fn response_parts(error: ApiError) -> (StatusCode, String, Problem) {
+        let response = error.into_response();
+        let status = response.status();
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn assert_problem(error: ApiError, status: u16, code: &str, retryable: bool, detail: Option<&str>) {
+        let (actual_status, content_type, problem) = response_parts(error).await;
+
+        assert_eq!(actual_status.as_u16(), status, "{}", code);
+        assert_eq!(content_type, PROBLEM_CONTENT_TYPE);
+        assert_eq!(problem.status, status);
+        assert_eq!(problem.code, code);
+        assert_eq!(problem.type_uri, format!("urn:praxis:error:{}", code));
+        assert_eq!(problem.retryable, retryable);
+        assert_eq!(problem.detail.as_deref(), detail);
+        assert!(!problem.title.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_client_errors() {
+        assert_problem(ApiError::ThreadNotFound("t1".into()), 404, "thread_not_found", false, Some("Thread not found: t1")).await;
+        assert_problem(ApiError::MessageNotFound("m1".into()), 404, "message_not_found", false, Some("Message not found: m1")).await;
+        assert_problem(ApiError::StreamNotFound("s1".into()), 404, "stream_not_found", false, Some("Stream not found: s1")).await;
+        assert_problem(ApiError::RouteNotFound("/nope".into()), 404, "route_not_found", false, Some("No route for /nope")).await;
+        assert_problem(ApiError::BadRequest("bad".into()), 400, "bad_request", false, Some("Invalid request: bad")).await;
+        assert_problem(ApiError::Forbidden("admin only".into()), 403, "forbidden", false, Some("Forbidden: admin only")).await;
+        assert_problem(ApiError::PayloadTooLarge("max 10 bytes".into()), 413, "payload_too_large", false, Some("Payload too large: max 10 bytes")).await;
+        assert_problem(
+            ApiError::UnsupportedMediaType("image/gif".into()),
+            415,
+            "unsupported_media_type",
+            false,
+            Some("Unsupported media type: image/gif"),
+        )
+        .await;
+        let invalid = praxis::PersistError::InvalidMessage { index: 2, reason: "tool result 'c1' has no matching tool call".into() };
+        assert_problem(
+            ApiError::Persist(invalid),
+            422,
+            "invalid_messages",
+            false,
+            Some("Invalid messages: messages[2]: tool result 'c1' has no matching tool call"),
+        )
+        .await;
+        let conflict = praxis::PersistError::ConversationKeyConflict("slack:C123".into());
+        assert_problem(
+            ApiError::Persist(conflict),
+            409,
+            "conversation_key_conflict",
+            false,
+            Some("Conversation key slack:C123 belongs to another user's thread"),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_hide_internal_details() {
+        let database = mongodb::error::Error::from(std::io::Error::other("socket closed"));
+        assert_problem(ApiError::Database(database), 503, "database_error", true, None).await;
+
+        let persist = praxis::PersistError::Connection("refused".into());
+        assert_problem(ApiError::Persist(persist), 503, "storage_error", true, None).await;
+
+        assert_problem(ApiError::Config("missing key".into()), 500, "configuration_error", false, None).await;
+        assert_problem(ApiError::Internal, 500, "internal_error", false, None).await;
+
+        let bson = bson::from_document::<String>(bson::doc! {}).unwrap_err();
+        assert_problem(ApiError::Bson(bson), 500, "internal_error", false, None).await;
+    }
+
+    #[tokio::test]
+    async fn test_graph_errors_use_graph_codes() {
+        let provider = GraphError::provider(anyhow::anyhow!("upstream 503"));
+        assert_problem(ApiError::Graph(provider), 502, "provider_error", true, None).await;
+        assert_problem(ApiError::Graph(anyhow::anyhow!("boom")), 500, "internal_error", false, None).await;
+    }
+
+    #[tokio::test]
+    async fn test_provider_errors_use_llm_error_codes() {
+        let limited = praxis::RateLimited { retry_after: None, message: "slow down".into() };
+        let provider = GraphError::provider(LLMError::from(limited).into());
+        assert_problem(ApiError::Graph(provider), 429, "rate_limited", true, None).await;
+
+        let context = LLMError::ContextLengthExceeded { max: Some(8192), requested: Some(9000), message: "too long".into() };
+        assert_problem(ApiError::Graph(GraphError::provider(context.into())), 400, "context_length_exceeded", false, None).await;
+
+        // Codes the catalog doesn't list stay provider errors
+        let upstream = LLMError::Api { status: 500, message: "oops".into() };
+        let problem = Problem::from_graph_error(GraphErrorKind::ProviderError, Some(upstream.code()), "oops");
+        assert_eq!(problem.code, "provider_error");
+    }
+
+    #[test]
+    fn test_catalog_covers_graph_error_kinds() {
+        for kind in GraphErrorKind::ALL {
+            let info = error_code_info(kind.code());
+            assert_eq!(info.code, kind.code());
+            assert_eq!(info.retryable, kind.is_retryable());
+        }
+
+        let mut codes: Vec<_> = ERROR_CODES.iter().map(|info| info.code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), ERROR_CODES.len(), "duplicate error codes");
+    }
+
+    #[test]
+    fn test_sse_problem_shape() {
+        let problem = Problem::from_graph_error(GraphErrorKind::MaxIterations, None, "Max iterations (50) reached");
+        let json = serde_json::to_value(&problem).unwrap();
+
+        assert_eq!(json["type"], "urn:praxis:error:max_iterations");
+        assert_eq!(json["code"], "max_iterations");
+        assert_eq!(json["retryable"], false);
+        assert_eq!(json["detail"], "Max iterations (50) reached");
+    }
+}
@@ -0,0 +1,378 @@
+//! Compact wire format of the message stream (`?wire=compact`)
+//!
+//! Event names are the verbose ones; payloads shrink three ways:
+//!
+//! - the frequent events (`message`, `reasoning`, `tool_call`, `tool_result`,
+//!   `activity`, `usage`, `thinking`) use the short keys below
+//! - unset and empty fields are left out, for every event
+//! - message and reasoning deltas are coalesced over `stream.compact_window_ms`
+//!
+//! | Event | Keys |
+//! | --- | --- |
+//! | `message`, `reasoning` | `c` content |
+//! | `tool_call` | `n` name, `args` arguments |
+//! | `tool_result` | `id` tool call id, `r` result, `err` (only when true), `ms` duration |
+//! | `activity` | `id` tool call id, `t` text |
+//! | `usage` | `in`, `out`, `tot`, `rsn` reasoning tokens |
+//! | `thinking` | `ms` elapsed |
+//!
+//! Agent-produced events add `a` (agent) and `p` (provenance as
+//! `[node, iteration]`) when set.
+
+use futures::stream::{Stream, StreamExt};
+use praxis::{EventBatcher, Provenance, ReasoningKind, StreamEvent as GraphStreamEvent, ToolVerification};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::handlers::stream::event_payload;
+
+/// Payload format of the message stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireFormat {
+    #[default]
+    Verbose,
+    Compact,
+}
+
+/// Agent and provenance, shared by agent-produced events
+#[derive(Serialize)]
+struct Origin {
+    #[serde(rename = "a", skip_serializing_if = "Option::is_none")]
+    agent: Option<String>,
+    #[serde(rename = "p", skip_serializing_if = "Option::is_none")]
+    provenance: Option<(praxis::NodeType, u32)>,
+}
+
+impl Origin {
+    fn new(agent: Option<String>, provenance: Option<Provenance>) -> Self {
+        Self { agent, provenance: provenance.map(|p| (p.node, p.iteration)) }
+    }
+}
+
+#[derive(Serialize)]
+struct Delta {
+    c: String,
+    /// Set for reasoning summaries only
+    #[serde(rename = "k", skip_serializing_if = "Option::is_none")]
+    kind: Option<ReasoningKind>,
+    #[serde(flatten)]
+    origin: Origin,
+}
+
+#[derive(Serialize)]
+struct ToolCall {
+    #[serde(rename = "n", skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<String>,
+    #[serde(flatten)]
+    origin: Origin,
+}
+
+#[derive(Serialize)]
+struct ToolResult {
+    id: String,
+    r: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    err: bool,
+    ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    srv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vfy: Option<ToolVerification>,
+    #[serde(flatten)]
+    origin: Origin,
+}
+
+#[derive(Serialize)]
+struct Activity {
+    id: String,
+    t: String,
+    #[serde(flatten)]
+    origin: Origin,
+}
+
+#[derive(Serialize)]
+struct Usage {
+    #[serde(rename = "in")]
+    input: u32,
+    out: u32,
+    tot: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rsn: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cch: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Thinking {
+    ms: u64,
+}
+
+/// SSE event name and compact payload for a graph event
+pub fn compact_payload(event: GraphStreamEvent) -> (String, serde_json::Value) {
+    let compact = match event {
+        GraphStreamEvent::Message { content, agent, provenance } => {
+            ("message", serde_json::to_value(Delta { c: content, kind: None, origin: Origin::new(agent, provenance) }))
+        }
+        GraphStreamEvent::Reasoning { content, kind, agent, provenance } => {
+            let kind = Some(kind).filter(|kind| !kind.is_raw());
+            ("reasoning", serde_json::to_value(Delta { c: content, kind, origin: Origin::new(agent, provenance) }))
+        }
+        GraphStreamEvent::ToolCall { name, arguments, agent, provenance, .. } => (
+            "tool_call",
+            serde_json::to_value(ToolCall { name, args: arguments, origin: Origin::new(agent, provenance) }),
+        ),
+        GraphStreamEvent::ToolResult {
+            tool_call_id,
+            result,
+            is_error,
+            duration_ms,
+            tool_server,
+            verification,
+            agent,
+            provenance,
+        } => (
+            "tool_result",
+            serde_json::to_value(ToolResult {
+                id: tool_call_id,
+                r: result,
+                err: is_error,
+                ms: duration_ms,
+                srv: tool_server,
+                vfy: verification,
+                origin: Origin::new(agent, provenance),
+            }),
+        ),
+        GraphStreamEvent::Activity { tool_call_id, text, agent, provenance } => (
+            "activity",
+            serde_json::to_value(Activity { id: tool_call_id, t: text, origin: Origin::new(agent, provenance) }),
+        ),
+        GraphStreamEvent::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens } => (
+            "usage",
+            serde_json::to_value(Usage {
+                input: input_tokens,
+                out: output_tokens,
+                tot: total_tokens,
+                rsn: reasoning_tokens,
+                cch: cached_tokens,
+            }),
+        ),
+        GraphStreamEvent::Thinking { elapsed_ms } => ("thinking", serde_json::to_value(Thinking { ms: elapsed_ms })),
+        other => {
+            let (name, mut data) = event_payload(other);
+            strip_unset(&mut data);
+            return (name, data);
+        }
+    };
+    (compact.0.to_string(), compact.1.unwrap_or_default())
+}
+
+/// Drop null, empty-string, empty-array and empty-object fields, at any depth
+fn strip_unset(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            fields.values_mut().for_each(strip_unset);
+            fields.retain(|_, field| match field {
+                serde_json::Value::Null => false,
+                serde_json::Value::String(s) => !s.is_empty(),
+                serde_json::Value::Array(items) => !items.is_empty(),
+                serde_json::Value::Object(inner) => !inner.is_empty(),
+                _ => true,
+            });
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_unset),
+        _ => {}
+    }
+}
+
+/// Hold message and reasoning deltas for up to `window`, sending each run of
+/// them as one event
+///
+/// Any other event first sends what is held, then goes out right away, so
+/// tool calls and the end of the run aren't delayed.
+pub fn coalesce<S>(events: S, window: Duration) -> impl Stream<Item = GraphStreamEvent>
+where
+    S: Stream<Item = GraphStreamEvent> + Send + 'static,
+{
+    async_stream::stream! {
+        let mut events = Box::pin(events);
+        let mut batcher = EventBatcher::new(window.as_millis().max(1) as u64);
+        // The first tick would be immediate
+        batcher.ticker().reset();
+        loop {
+            tokio::select! {
+                biased;
+                event = events.next() => match event {
+                    Some(event) if is_delta(&event) => batcher.push(event),
+                    Some(event) => {
+                        for held in merge_deltas(batcher.take()) {
+                            yield held;
+                        }
+                        yield event;
+                    }
+                    None => break,
+                },
+                _ = batcher.ticker().tick() => {
+                    for held in merge_deltas(batcher.take()) {
+                        yield held;
+                    }
+                }
+            }
+        }
+        for held in merge_deltas(batcher.take()) {
+            yield held;
+        }
+    }
+}
+
+fn is_delta(event: &GraphStreamEvent) -> bool {
+    matches!(event, GraphStreamEvent::Message { .. } | GraphStreamEvent::Reasoning { .. })
+}
+
+/// Join consecutive deltas of the same kind, agent and provenance
+/// (reasoning summaries and raw reasoning stay apart)
+fn merge_deltas(deltas: Vec<GraphStreamEvent>) -> Vec<GraphStreamEvent> {
+    let mut merged: Vec<GraphStreamEvent> = Vec::new();
+    for delta in deltas {
+        match (merged.last_mut(), delta) {
+            (
+                Some(GraphStreamEvent::Message { content, agent, provenance }),
+                GraphStreamEvent::Message { content: more, agent: next_agent, provenance: next_provenance },
+            ) if *agent == next_agent && *provenance == next_provenance => content.push_str(&more),
+            (
+                Some(GraphStreamEvent::Reasoning { content, kind, agent, provenance }),
+                GraphStreamEvent::Reasoning { content: more, kind: next_kind, agent: next_agent, provenance: next_provenance },
+            ) if *kind == next_kind && *agent == next_agent && *provenance == next_provenance => content.push_str(&more),
+            (_, delta) => merged.push(delta),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use praxis::NodeType;
+
+    fn provenance(node: NodeType, iteration: u32) -> Option<Provenance> {
+        Some(Provenance { node, iteration })
+    }
+
+    fn message(content: &str) -> GraphStreamEvent {
+        GraphStreamEvent::Message { content: content.to_string(), agent: None, provenance: provenance(NodeType::LLM, 1) }
+    }
+
+    /// A tool call followed by a 60-delta answer, as the graph emits it
+    fn fixture_run() -> Vec<GraphStreamEvent> {
+        let mut events = vec![
+            GraphStreamEvent::InitStream {
+                run_id: "run-1".to_string(),
+                conversation_id: "thread-1".to_string(),
+                timestamp: 0,
+                llm_config: Some(praxis::LLMConfig::new("gpt-4o-mini")),
+                variables: Default::default(),
+                option_adjustments: Vec::new(),
+            },
+            GraphStreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("weather".to_string()),
+                arguments: Some(r#"{"city":"Lisbon"}"#.to_string()),
+                agent: None,
+                provenance: provenance(NodeType::LLM, 0),
+            },
+            GraphStreamEvent::ToolResult {
+                tool_call_id: "call_1".to_string(),
+                result: "18C, clear".to_string(),
+                is_error: false,
+                duration_ms: 120,
+                tool_server: None,
+                verification: None,
+                agent: None,
+                provenance: provenance(NodeType::Tool, 0),
+            },
+        ];
+        let answer = "It is 18 degrees and clear in Lisbon right now, a good afternoon for a walk by the river.";
+        let words: Vec<&str> = answer.split_inclusive(' ').collect();
+        events.extend(words.iter().cycle().take(60).map(|word| message(word)));
+        events.push(GraphStreamEvent::Usage { input_tokens: 230, output_tokens: 60, total_tokens: 290, reasoning_tokens: None, cached_tokens: None });
+        events.push(GraphStreamEvent::Done { finish_reason: Some("stop".to_string()), cost_usd: None });
+        events
+    }
+
+    /// Bytes of `events` as SSE frames
+    fn wire_bytes(events: Vec<GraphStreamEvent>, payload: fn(GraphStreamEvent) -> (String, serde_json::Value)) -> usize {
+        events
+            .into_iter()
+            .map(|event| {
+                let (name, data) = payload(event);
+                format!("event: {}\ndata: {}\n\n", name, data).len()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_compact_payloads_use_short_keys_and_skip_unset_fields() {
+        let (name, data) = compact_payload(message("Hi"));
+        assert_eq!(name, "message");
+        assert_eq!(data, serde_json::json!({ "c": "Hi", "p": ["llm", 1] }));
+
+        let (_, data) = compact_payload(GraphStreamEvent::Reasoning {
+            content: "Checking".to_string(),
+            kind: ReasoningKind::Summary,
+            agent: None,
+            provenance: None,
+        });
+        assert_eq!(data, serde_json::json!({ "c": "Checking", "k": "summary" }));
+
+        let (_, data) = compact_payload(GraphStreamEvent::ToolResult {
+            tool_call_id: "call_1".to_string(),
+            result: "ok".to_string(),
+            is_error: false,
+            duration_ms: 5,
+            tool_server: None,
+            verification: None,
+            agent: Some("researcher".to_string()),
+            provenance: None,
+        });
+        assert_eq!(data, serde_json::json!({ "id": "call_1", "r": "ok", "ms": 5, "a": "researcher" }));
+
+        // Other events keep their verbose keys, minus the unset ones
+        let (name, data) = compact_payload(GraphStreamEvent::Handoff { from: "main".to_string(), to: "researcher".to_string(), reason: None });
+        assert_eq!(name, "handoff");
+        assert_eq!(data, serde_json::json!({ "from": "main", "to": "researcher" }));
+    }
+
+    #[tokio::test]
+    async fn test_deltas_coalesce_until_another_event() {
+        let events = futures::stream::iter(vec![
+            message("It "),
+            message("is "),
+            GraphStreamEvent::Thinking { elapsed_ms: 10 },
+            message("sunny"),
+            GraphStreamEvent::Message { content: "!".to_string(), agent: Some("other".to_string()), provenance: None },
+        ]);
+        let out: Vec<_> = coalesce(events, Duration::from_secs(60)).collect().await;
+
+        let contents: Vec<_> = out
+            .iter()
+            .map(|event| match event {
+                GraphStreamEvent::Message { content, .. } => content.as_str(),
+                _ => "<thinking>",
+            })
+            .collect();
+        assert_eq!(contents, vec!["It is ", "<thinking>", "sunny", "!"]);
+    }
+
+    #[tokio::test]
+    async fn test_compact_wire_is_much_smaller_for_a_fixture_run() {
+        let verbose = wire_bytes(fixture_run(), event_payload);
+        let coalesced: Vec<_> = coalesce(futures::stream::iter(fixture_run()), Duration::from_secs(60)).collect().await;
+        let compact = wire_bytes(coalesced, compact_payload);
+
+        let reduction = 100 - compact * 100 / verbose;
+        assert!(reduction >= 60, "only {}% smaller", reduction);
+    }
+}
@@ -0,0 +1,419 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use praxis::{AttachmentRef, DBMessage, MessageRole, MessageType, NodeType, Provenance, ReasoningKind, StreamEvent as GraphStreamEvent};
+use crate::{error::{ApiError, ApiResult}, extract::ApiQuery, handlers::stream::event_payload, state::AppState};
+use praxis::wire::StreamEvent as WireEvent;
+
+/// What `limit` counts when paging through history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageBy {
+    /// A user message and everything the assistant produced for it
+    #[default]
+    Run,
+    /// Single stored messages
+    Item,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOrder {
+    #[default]
+    OldestFirst,
+    /// Latest page first; items within a run stay in the order they happened
+    NewestFirst,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HydrateQuery {
+    #[serde(default)]
+    pub page_by: PageBy,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub order: HistoryOrder,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+/// Runs or items per page at most
+const MAX_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Completed,
+    /// A tool call with no stored result (the run was interrupted)
+    Incomplete,
+}
+
+/// One stored message as the SSE event the live stream would have sent
+#[derive(Debug, Serialize)]
+pub struct HydratedItem {
+    pub message_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub status: ItemStatus,
+    /// SSE event name (`message`, `tool_call`, ...), plus `user_message`
+    /// for the user's own turns, which the live stream never echoes
+    pub event: String,
+    /// Same payload as the live event of that name
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HydrateResponse {
+    pub thread_id: String,
+    pub items: Vec<HydratedItem>,
+    /// Pass as `cursor` for the next page; absent on the last one
+    pub next_cursor: Option<String>,
+    /// Files uploaded to the thread, on every page
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<AttachmentRef>,
+}
+
+/// Thread history in the live stream's event schema
+#[utoipa::path(
+    get,
+    path = "/threads/{thread_id}/hydrate",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID"),
+        ("page_by" = Option<String>, Query, description = "`run` (default) or `item`"),
+        ("limit" = Option<usize>, Query, description = "Runs or items per page (default: 20, max: 100)"),
+        ("order" = Option<String>, Query, description = "`oldest_first` (default) or `newest_first`"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page")
+    ),
+    responses(
+        (status = 200, description = "History as stream events", body = HydrateResponse),
+        (status = 404, description = "Thread not found")
+    ),
+    tag = "messages"
+)]
+pub async fn hydrate_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    ApiQuery(query): ApiQuery<HydrateQuery>,
+) -> ApiResult<Json<HydrateResponse>> {
+    if state.persist.get_thread(&thread_id).await?.is_none() {
+        return Err(ApiError::ThreadNotFound(thread_id));
+    }
+
+    // Include turns a write-behind client still has queued
+    state.persist.flush(&thread_id).await?;
+    let messages = state.persist.get_messages(&thread_id).await?;
+    let attachments = state.persist.list_attachments(&thread_id).await?;
+
+    let (page, next_cursor) = paginate(messages, &query);
+    Ok(Json(HydrateResponse {
+        thread_id,
+        items: hydrate(page),
+        next_cursor,
+        attachments,
+    }))
+}
+
+/// Pick one page of messages, returning it in display order
+fn paginate(messages: Vec<DBMessage>, query: &HydrateQuery) -> (Vec<DBMessage>, Option<String>) {
+    let mut units = group(messages, query.page_by);
+    if query.order == HistoryOrder::NewestFirst {
+        units.reverse();
+    }
+
+    // The cursor names the first message of the last unit already returned
+    let start = query
+        .cursor
+        .as_ref()
+        .and_then(|cursor| units.iter().position(|unit| unit[0].id == *cursor))
+        .map_or(0, |position| position + 1);
+    let limit = query.limit.clamp(1, MAX_LIMIT);
+    let end = (start + limit).min(units.len());
+
+    let next_cursor = (end < units.len()).then(|| units[end - 1][0].id.clone());
+    let page = units.drain(start.min(end)..end).flatten().collect();
+    (page, next_cursor)
+}
+
+/// Split chronological messages into runs (a user message opens one) or
+/// single items
+fn group(messages: Vec<DBMessage>, page_by: PageBy) -> Vec<Vec<DBMessage>> {
+    let mut units: Vec<Vec<DBMessage>> = Vec::new();
+    for message in messages {
+        let opens_run = message.role == MessageRole::User && message.message_type == MessageType::Message;
+        match units.last_mut() {
+            Some(run) if page_by == PageBy::Run && !opens_run => run.push(message),
+            _ => units.push(vec![message]),
+        }
+    }
+    units
+}
+
+/// Convert stored messages to the events the live stream sends for them
+fn hydrate(messages: Vec<DBMessage>) -> Vec<HydratedItem> {
+    let answered: HashSet<String> = messages
+        .iter()
+        .filter(|m| m.message_type == MessageType::ToolResult)
+        .filter_map(|m| m.tool_call_id.clone())
+        .collect();
+
+    let mut items = Vec::with_capacity(messages.len());
+    for message in messages {
+        let status = match (&message.message_type, &message.tool_call_id) {
+            (MessageType::ToolCall, Some(id)) if !answered.contains(id) => ItemStatus::Incomplete,
+            _ => ItemStatus::Completed,
+        };
+        for (event, data) in message_events(&message) {
+            items.push(HydratedItem {
+                message_id: message.id.clone(),
+                created_at: message.created_at,
+                status,
+                event,
+                data,
+            });
+        }
+    }
+    items
+}
+
+/// The live events a stored message stands for
+///
+/// Reasoning rows with a summary yield `reasoning` then `reasoning_summary`,
+/// tool call rows `tool_call` then `tool_call_complete` (and `activity` when
+/// a line is stored), and answers with citations `message` then
+/// `citations`, as the live stream sends them.
+fn message_events(message: &DBMessage) -> Vec<(String, serde_json::Value)> {
+    let agent = message.agent.clone();
+    let node = if message.message_type == MessageType::ToolResult { NodeType::Tool } else { NodeType::LLM };
+    let provenance = message.iteration.map(|iteration| Provenance { node, iteration });
+    let events = match (message.role, message.message_type) {
+        (MessageRole::User, MessageType::Message) => {
+            return vec![WireEvent::UserMessage { content: message.content.clone() }.into_parts()];
+        }
+        (_, MessageType::Message) => {
+            let mut events = vec![GraphStreamEvent::Message {
+                content: message.content.clone(),
+                agent: agent.clone(),
+                provenance,
+            }];
+            if !message.citations.is_empty() {
+                events.push(GraphStreamEvent::Citations { citations: message.citations.clone(), agent, provenance });
+            }
+            events
+        }
+        (_, MessageType::Reasoning) => {
+            let mut events = vec![GraphStreamEvent::Reasoning {
+                content: message.content.clone(),
+                kind: ReasoningKind::Raw,
+                agent: agent.clone(),
+                provenance,
+            }];
+            if let (Some(item_id), Some(text)) = (&message.reasoning_id, &message.summary) {
+                events.push(GraphStreamEvent::ReasoningSummary {
+                    item_id: item_id.clone(),
+                    text: text.clone(),
+                    agent,
+                    provenance,
+                });
+            }
+            events
+        }
+        (_, MessageType::ToolCall) => {
+            let mut events = vec![GraphStreamEvent::ToolCall {
+                index: 0,
+                id: message.tool_call_id.clone(),
+                name: message.tool_name.clone(),
+                arguments: message.arguments.as_ref().map(|arguments| arguments.to_string()),
+                agent: agent.clone(),
+                provenance,
+            }];
+            if let (Some(id), Some(name)) = (&message.tool_call_id, &message.tool_name) {
+                events.push(GraphStreamEvent::ToolCallComplete {
+                    id: id.clone(),
+                    name: name.clone(),
+                    arguments: message.arguments.as_ref().map(|arguments| arguments.to_string()).unwrap_or_default(),
+                    agent: agent.clone(),
+                    provenance,
+                });
+            }
+            if let (Some(tool_call_id), Some(text)) = (&message.tool_call_id, &message.summary) {
+                events.push(GraphStreamEvent::Activity {
+                    tool_call_id: tool_call_id.clone(),
+                    text: text.clone(),
+                    agent,
+                    provenance,
+                });
+            }
+            events
+        }
+        (_, MessageType::ToolResult) => vec![GraphStreamEvent::ToolResult {
+            tool_call_id: message.tool_call_id.clone().unwrap_or_default(),
+            result: message.content.clone(),
+            is_error: message.verification.as_ref().is_some_and(|verification| !verification.passed),
+            duration_ms: message.duration_ms.unwrap_or(0),
+            tool_server: message.tool_server.clone(),
+            verification: message.verification.clone(),
+            agent,
+            provenance,
+        }],
+    };
+    events.into_iter().map(event_payload).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    /// A two-run thread: reasoning, an answered tool call and an answer,
+    /// then a run interrupted during its tool call. Assistant rows carry
+    /// the loop iteration that produced them.
+    fn fixture() -> Vec<DBMessage> {
+        let row = |n: u32, role: MessageRole, message_type: MessageType, content: &str| DBMessage {
+            id: format!("m-{}", n),
+            thread_id: "t-1".to_string(),
+            user_id: "u-1".to_string(),
+            role,
+            message_type,
+            content: content.to_string(),
+            created_at: Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, n).unwrap(),
+            agent: (role == MessageRole::Assistant).then(|| "main".to_string()),
+            iteration: (role == MessageRole::Assistant).then_some(0),
+            ..Default::default()
+        };
+        let call = |n: u32, id: &str| DBMessage {
+            tool_call_id: Some(id.to_string()),
+            tool_name: Some("weather".to_string()),
+            arguments: Some(serde_json::json!({ "city": "Lisbon" })),
+            ..row(n, MessageRole::Assistant, MessageType::ToolCall, "")
+        };
+
+        vec![
+            row(1, MessageRole::User, MessageType::Message, "Will it rain?"),
+            DBMessage {
+                reasoning_id: Some("rs_1".to_string()),
+                summary: Some("Checking the forecast".to_string()),
+                ..row(2, MessageRole::Assistant, MessageType::Reasoning, "Need the forecast.")
+            },
+            call(3, "call_1"),
+            DBMessage {
+                tool_call_id: Some("call_1".to_string()),
+                tool_server: Some("weather".to_string()),
+                duration_ms: Some(120),
+                iteration: Some(1),
+                ..row(4, MessageRole::Assistant, MessageType::ToolResult, "22C, sunny")
+            },
+            DBMessage { iteration: Some(2), ..row(5, MessageRole::Assistant, MessageType::Message, "No rain today.") },
+            row(6, MessageRole::User, MessageType::Message, "And tomorrow?"),
+            call(7, "call_2"),
+        ]
+    }
+
+    fn query(page_by: PageBy, limit: usize, order: HistoryOrder, cursor: Option<&str>) -> HydrateQuery {
+        HydrateQuery { page_by, limit, order, cursor: cursor.map(String::from) }
+    }
+
+    fn ids(page: &[DBMessage]) -> Vec<&str> {
+        page.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_hydrated_thread_matches_golden_file() {
+        let items = hydrate(fixture());
+        let actual = serde_json::to_value(&items).unwrap();
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/golden/hydrate_thread.json")).unwrap();
+        assert_eq!(actual, golden, "{}", serde_json::to_string_pretty(&actual).unwrap());
+    }
+
+    #[test]
+    fn test_hydrated_events_use_the_live_schema() {
+        // What the live stream sends while producing the first run
+        let at = |node, iteration| Some(Provenance { node, iteration });
+        let live = vec![
+            GraphStreamEvent::Reasoning {
+                content: "Need the forecast.".to_string(),
+                kind: ReasoningKind::Raw,
+                agent: Some("main".to_string()),
+                provenance: at(NodeType::LLM, 0),
+            },
+            GraphStreamEvent::ReasoningSummary {
+                item_id: "rs_1".to_string(),
+                text: "Checking the forecast".to_string(),
+                agent: Some("main".to_string()),
+                provenance: at(NodeType::LLM, 0),
+            },
+            GraphStreamEvent::ToolCall {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("weather".to_string()),
+                arguments: Some(r#"{"city":"Lisbon"}"#.to_string()),
+                agent: Some("main".to_string()),
+                provenance: at(NodeType::LLM, 0),
+            },
+            GraphStreamEvent::ToolCallComplete {
+                id: "call_1".to_string(),
+                name: "weather".to_string(),
+                arguments: r#"{"city":"Lisbon"}"#.to_string(),
+                agent: Some("main".to_string()),
+                provenance: at(NodeType::LLM, 0),
+            },
+            GraphStreamEvent::ToolResult {
+                tool_call_id: "call_1".to_string(),
+                result: "22C, sunny".to_string(),
+                is_error: false,
+                duration_ms: 120,
+                tool_server: Some("weather".to_string()),
+                verification: None,
+                agent: Some("main".to_string()),
+                provenance: at(NodeType::Tool, 1),
+            },
+            GraphStreamEvent::Message {
+                content: "No rain today.".to_string(),
+                agent: Some("main".to_string()),
+                provenance: at(NodeType::LLM, 2),
+            },
+        ];
+        let live: Vec<(String, serde_json::Value)> =
+            live.into_iter().map(event_payload).collect();
+
+        let hydrated: Vec<(String, serde_json::Value)> =
+            hydrate(fixture()).into_iter().skip(1).take(6).map(|item| (item.event, item.data)).collect();
+        assert_eq!(hydrated, live);
+    }
+
+    #[test]
+    fn test_pages_by_run_and_item() {
+        let (page, cursor) = paginate(fixture(), &query(PageBy::Run, 1, HistoryOrder::OldestFirst, None));
+        assert_eq!(ids(&page), vec!["m-1", "m-2", "m-3", "m-4", "m-5"]);
+        assert_eq!(cursor.as_deref(), Some("m-1"));
+
+        let (page, cursor) = paginate(fixture(), &query(PageBy::Run, 1, HistoryOrder::OldestFirst, Some("m-1")));
+        assert_eq!(ids(&page), vec!["m-6", "m-7"]);
+        assert_eq!(cursor, None);
+
+        let (page, cursor) = paginate(fixture(), &query(PageBy::Item, 3, HistoryOrder::OldestFirst, Some("m-2")));
+        assert_eq!(ids(&page), vec!["m-3", "m-4", "m-5"]);
+        assert_eq!(cursor.as_deref(), Some("m-5"));
+    }
+
+    #[test]
+    fn test_newest_first_keeps_runs_in_order() {
+        let (page, cursor) = paginate(fixture(), &query(PageBy::Run, 1, HistoryOrder::NewestFirst, None));
+        assert_eq!(ids(&page), vec!["m-6", "m-7"]);
+        assert_eq!(cursor.as_deref(), Some("m-6"));
+
+        let (page, _) = paginate(fixture(), &query(PageBy::Run, 1, HistoryOrder::NewestFirst, Some("m-6")));
+        assert_eq!(ids(&page), vec!["m-1", "m-2", "m-3", "m-4", "m-5"]);
+
+        let (page, _) = paginate(fixture(), &query(PageBy::Item, 2, HistoryOrder::NewestFirst, None));
+        assert_eq!(ids(&page), vec!["m-7", "m-6"]);
+    }
+}
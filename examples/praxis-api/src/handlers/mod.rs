@@ -1,2 +1,3 @@
 pub mod stream;
-
+pub mod compact;
+pub mod hydrate;
@@ -1,7 +1,7 @@
 use axum::{
     extract::{Path, State},
-    response::sse::{Event, Sse},
-    Json,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
 };
 use futures::stream::{Stream, StreamExt};
 use serde::Deserialize;
@@ -10,8 +10,13 @@ use std::sync::Arc;
 use chrono::Utc;
 
 use tokio_stream::wrappers::ReceiverStream;
-use praxis::{StreamEvent as GraphStreamEvent, GraphInput, Message as LLMMessage, Content, DBMessage, MessageRole, MessageType, PersistenceContext, LLMConfig};
-use crate::{error::{ApiError, ApiResult}, state::AppState};
+use praxis::{StreamEvent as GraphStreamEvent, GraphInput, Message as LLMMessage, Content, ContentEncoding, DBMessage, MessageRole, MessageType, PersistenceContext, ReadAttachmentTool, LocalTool, ReasoningEffort, SummaryMode, Thread, ToolChoice, AudioOptions};
+use crate::{auth::AuthContext, error::{ApiError, ApiResult, Problem}, extract::{ApiJson, ApiQuery}, state::AppState};
+use crate::handlers::compact::{coalesce, compact_payload, WireFormat};
+use crate::replay::{parse_event_id, StreamBuffer};
+use crate::scheduler::{Admission, QueueUpdate, DEFAULT_TENANT};
+use praxis::{ModerationStage, NodeType, Provenance};
+use praxis::wire::{Provenance as WireProvenance, RunInfo, StreamEvent as WireEvent, ThreadInfo};
 
 #[derive(Debug, Deserialize)]
 pub struct SendMessageRequest {
@@ -25,14 +30,46 @@ pub struct SendMessageRequest {
 pub struct RequestLLMConfig {
     pub model: String,
     
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reasoning_effort: Option<String>,
+    /// `"minimal"`, `"low"`, `"medium"` or `"high"`
+    #[serde(default)]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    
+    /// `"auto"` or `"detailed"`
+    #[serde(default)]
+    pub reasoning_summary: Option<SummaryMode>,
     
     #[serde(default = "default_temperature")]
     pub temperature: f32,
     
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+    
+    /// Tool choice for the run's first LLM call (`"required"`, or
+    /// `{"type": "function", "function": {"name": "..."}}`); replaces
+    /// `graph.tool_choice`
+    #[serde(default)]
+    pub tool_choice: Option<ToolChoice>,
+    
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    
+    /// Sent on every LLM call of the run, for reproducible outputs
+    #[serde(default)]
+    pub seed: Option<u64>,
+    
+    /// Sequences that end generation
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+    
+    /// `{"voice": "alloy", "format": "pcm16"}` for spoken answers from
+    /// audio models, streamed as `audio` events
+    #[serde(default)]
+    pub audio: Option<AudioOptions>,
+    
+    /// Raw fields added to the provider request body, for parameters the
+    /// server doesn't know yet; fields it already sends win
+    #[serde(default)]
+    pub extra_body: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 fn default_temperature() -> f32 {
@@ -43,10 +80,41 @@ fn default_max_tokens() -> u32 {
     8000
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamQuery {
+    /// `verbose` (default) or `compact`: short keys, no unset fields, coalesced deltas
+    #[serde(default)]
+    pub wire: WireFormat,
+}
+
+/// Characters of the summary shown in `summary_preview`
+const SUMMARY_PREVIEW_CHARS: usize = 160;
+
+/// Sidebar entry for the thread, sent as the `thread_info` SSE event
+pub fn thread_info(thread: &Thread, message_count: u64) -> ThreadInfo {
+    let summary_preview = thread.summary.as_ref().map(|summary| {
+        let text = summary.text.trim();
+        match text.char_indices().nth(SUMMARY_PREVIEW_CHARS) {
+            Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+            None => text.to_string(),
+        }
+    });
+    ThreadInfo {
+        thread_id: thread.id.clone(),
+        title: thread.metadata.title.clone(),
+        summary_preview,
+        created_at: thread.created_at,
+        message_count,
+    }
+}
+
 /// Send a message and stream the response using Server-Sent Events
 #[utoipa::path(
     post,
     path = "/threads/{thread_id}/messages",
+    params(
+        ("wire" = Option<String>, Query, description = "`verbose` (default) or `compact`"),
+    ),
     request_body = SendMessageRequest,
     responses(
         (status = 200, description = "Streaming response", content_type = "text/event-stream"),
@@ -57,135 +125,711 @@ fn default_max_tokens() -> u32 {
 pub async fn send_message_stream(
     State(state): State<Arc<AppState>>,
     Path(thread_id): Path<String>,
-    Json(req): Json<SendMessageRequest>,
+    ApiQuery(query): ApiQuery<StreamQuery>,
+    auth: AuthContext,
+    ApiJson(req): ApiJson<SendMessageRequest>,
 ) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let conversation_id = thread_id.clone();
+    stream_to_thread(state, thread_id, conversation_id, query, auth, req).await
+}
+
+/// Run a message on a stored thread; `conversation_id` is what the graph
+/// and its traces see, `thread_id` is where messages are saved
+async fn stream_to_thread(
+    state: Arc<AppState>,
+    thread_id: String,
+    conversation_id: String,
+    query: StreamQuery,
+    auth: AuthContext,
+    req: SendMessageRequest,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let received_at = std::time::Instant::now();
+
     // 1. Check if thread exists
-    let _thread = state
+    let mut thread = state
         .persist
         .get_thread(&thread_id)
         .await?
         .ok_or_else(|| ApiError::ThreadNotFound(thread_id.clone()))?;
     
-    // 2. Save user message to database
-    let user_message = DBMessage {
-        id: uuid::Uuid::new_v4().to_string(),
+    // 2. Route the requested LLM config through server-side overrides
+    let routing = state.model_router.route(&req.llm_config, &thread, &auth);
+    if !routing.overrides.is_empty() {
+        tracing::info!(
+            "Routed thread {} to model {} (requested {}, overrides: {:?})",
+            thread_id,
+            routing.config.model,
+            routing.requested_model,
+            routing.overrides
+        );
+    }
+    
+    // The first run pins the thread to the current MCP tools; later runs
+    // keep them until the thread is repinned
+    let tool_profile = match thread.metadata.tool_profile.clone() {
+        Some(profile) => profile,
+        None => crate::routes::threads::pin_tool_profile(&state, &mut thread).await?,
+    };
+    
+    // 3. Build the user message (stored subject to the persistence policy)
+    let user_message = user_message(&thread_id, &req);
+    
+    // 4. Get context using strategy (BEFORE Graph execution) while the user
+    // message is saved. The new message is appended below and kept out of
+    // the window, so the window only needs earlier turns, which a
+    // write-behind client may still be holding.
+    state.persist.flush(&thread_id).await?;
+    let user_message_id = user_message.id.clone();
+    let save_user_message = async {
+        if let Some(mut user_message) = state.config.persistence.apply(user_message) {
+            user_message.fingerprint(Some(state.content_tokenizer.as_ref()), &routing.config.model);
+            state.persist.save_message(user_message).await?;
+            state.persist.flush(&thread_id).await?;
+        }
+        Ok::<_, ApiError>(())
+    };
+    let (saved, context_window) = tokio::join!(
+        save_user_message,
+        state.context_strategy.get_context_window(&thread_id, &state.persist, Some(&user_message_id), &routing.config.model),
+    );
+    // A lost user message ends the request before the provider is called
+    saved?;
+    let context_window = context_window?;
+    
+    // Files uploaded to the thread are readable through `read_attachment`
+    let attachments = state.persist.list_attachments(&thread_id).await?;
+    let attachment_tool = (!attachments.is_empty()).then(|| {
+        Arc::new(ReadAttachmentTool::new(thread_id.clone(), Arc::clone(&state.persist), attachments)) as Arc<dyn LocalTool>
+    });
+    if !context_window.normalizations.is_empty() {
+        tracing::info!(
+            "Normalized thread {} history for {}: {:?}",
+            thread_id,
+            routing.config.model,
+            context_window.normalizations
+        );
+    }
+    
+    // 5. Build full message history
+    let mut messages = instructions(context_window.system_prompt, context_window.developer_prompt);
+    messages.extend(context_window.messages);
+    messages.push(LLMMessage::human(req.content.clone()));
+    
+    let graph_input = GraphInput::new(
+        conversation_id,
+        messages,
+        routing.config.clone(),
+    )
+    .with_variable("routing", serde_json::to_value(&routing).map_err(|_| ApiError::Internal)?)
+    .with_variable(
+        "context_normalizations",
+        serde_json::to_value(&context_window.normalizations).map_err(|_| ApiError::Internal)?,
+    )
+    .with_run_tools(context_window.tools)
+    .with_run_tools(attachment_tool)
+    .with_pinned_tools(tool_profile.tools)
+    .with_roles(auth.scopes.clone());
+    let graph_input = match &auth.tenant_id {
+        Some(tenant) => graph_input.with_tenant(tenant.clone()),
+        None => graph_input,
+    };
+    
+    // Events go to the stream's replay buffer, led by the sidebar data for
+    // clients that open the stream right after creating the thread
+    let writer = state.stream_buffers.open(&thread_id);
+    if state.config.stream.thread_info {
+        let message_count = state.persist.count_messages(&thread_id).await?;
+        let (name, data) = WireEvent::ThreadInfo(thread_info(&thread, message_count)).into_parts();
+        writer.push(name, data);
+    }
+    
+    // 6. Wait for a run slot, then spawn Graph with PersistenceContext.
+    // The permit is held until the run's events stop.
+    let tenant = auth.tenant_id.clone().unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let admission = state.run_scheduler.enqueue(&tenant);
+    let graph = Arc::clone(&state.graph);
+    let persistence_context = PersistenceContext {
         thread_id: thread_id.clone(),
         user_id: req.user_id.clone(),
+    };
+    let wire = query.wire;
+    let compact_window = std::time::Duration::from_millis(state.config.stream.compact_window_ms);
+    let event_stream = async_stream::stream! {
+        let permit = match admission {
+            Admission::Started(permit) => permit,
+            Admission::Queued(mut queued) => {
+                yield RunEvent::Queued(queued.position());
+                loop {
+                    match queued.next().await {
+                        QueueUpdate::Position(position) => yield RunEvent::Queued(position),
+                        QueueUpdate::Started(permit) => break permit,
+                    }
+                }
+            }
+        };
+        if !permit.waited.is_zero() {
+            tracing::info!(tenant = %tenant, wait_ms = permit.waited.as_millis() as u64, "Run left the queue");
+        }
+        // 7. Convert Receiver to Stream for SSE
+        let events = ReceiverStream::new(graph.spawn_run(graph_input, Some(persistence_context)));
+        let mut events = match wire {
+            WireFormat::Verbose => events.boxed(),
+            WireFormat::Compact => coalesce(events, compact_window).boxed(),
+        };
+        while let Some(event) = events.next().await {
+            yield RunEvent::Graph(Box::new(event));
+        }
+        drop(permit);
+    };
+    
+    // 8. Convert Graph events to SSE payloads (Graph handles persistence
+    // automatically); the end of the run queues its webhook
+    let outbox = state.outbox.clone();
+    let user_id = req.user_id.clone();
+    let mut first_token = true;
+    let payloads = event_stream.map(move |event| {
+        let event = match event {
+            RunEvent::Queued(position) => return WireEvent::Queued { position }.into_parts(),
+            RunEvent::Graph(event) => *event,
+        };
+        // Time to first token, measured from the request
+        if first_token && matches!(
+            event,
+            GraphStreamEvent::Message { .. }
+                | GraphStreamEvent::Audio { .. }
+                | GraphStreamEvent::Reasoning { .. }
+                | GraphStreamEvent::ToolCall { .. }
+        ) {
+            first_token = false;
+            tracing::info!(thread_id = %thread_id, ttft_ms = received_at.elapsed().as_millis() as u64, "First token");
+        }
+        if let (Some(outbox), GraphStreamEvent::EndStream { status, total_duration_ms, .. }) = (&outbox, &event) {
+            let entry = crate::webhooks::run_completed(&thread_id, &user_id, status, *total_duration_ms);
+            let (outbox, thread_id) = (Arc::clone(outbox), thread_id.clone());
+            tokio::spawn(async move {
+                if let Err(e) = crate::webhooks::enqueue_after_flush(outbox.as_ref(), &thread_id, entry).await {
+                    tracing::error!("Failed to queue run webhook for thread {}: {}", thread_id, e);
+                }
+            });
+        }
+        match wire {
+            WireFormat::Verbose => event_payload(event),
+            WireFormat::Compact => compact_payload(event),
+        }
+    });
+    
+    // 9. The run feeds the buffer, not the connection, so it keeps going
+    // when the client drops and reconnects through `/streams/{id}`
+    let buffer = Arc::clone(writer.buffer());
+    tokio::spawn(async move {
+        let mut payloads = std::pin::pin!(payloads);
+        while let Some((name, data)) = payloads.next().await {
+            writer.push(name, data);
+        }
+    });
+    Ok(replay(&state, buffer, 0))
+}
+
+/// Resume a message stream after a dropped connection
+///
+/// Sends the events after `Last-Event-ID` (all of them without the header),
+/// then follows the run. Streams can be resumed until
+/// `[stream] replay_retention_secs` after their run ends.
+#[utoipa::path(
+    get,
+    path = "/streams/{stream_id}",
+    params(
+        ("stream_id" = String, Path, description = "Stream id, the part of each event id before the `:`"),
+        ("Last-Event-ID" = Option<String>, Header, description = "Id of the last event received"),
+    ),
+    responses(
+        (status = 200, description = "Streaming response", content_type = "text/event-stream"),
+        (status = 400, description = "Last-Event-ID is not an event of this stream"),
+        (status = 404, description = "Unknown or expired stream")
+    ),
+    tag = "messages"
+)]
+pub async fn resume_message_stream(
+    State(state): State<Arc<AppState>>,
+    Path(stream_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let buffer = state
+        .stream_buffers
+        .get(&stream_id)
+        .ok_or_else(|| ApiError::StreamNotFound(stream_id.clone()))?;
+    let after = match headers.get("last-event-id") {
+        None => 0,
+        Some(id) => id
+            .to_str()
+            .ok()
+            .and_then(|id| parse_event_id(&stream_id, id))
+            .ok_or_else(|| ApiError::BadRequest(format!("Last-Event-ID is not an event of stream {}", stream_id)))?,
+    };
+    Ok(replay(&state, buffer, after))
+}
+
+/// SSE response with the buffer's events after `seq`, each with its id
+fn replay(state: &AppState, buffer: Arc<StreamBuffer>, seq: u64) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = buffer.events_after(seq).map(move |event| {
+        let sse = Event::default().event(event.name).json_data(event.data).unwrap();
+        Ok::<Event, Infallible>(sse.id(buffer.event_id(event.seq)))
+    });
+    let sse = Sse::new(events);
+    match state.config.stream.keep_alive_secs {
+        0 => sse,
+        secs => sse.keep_alive(KeepAlive::new().interval(std::time::Duration::from_secs(secs))),
+    }
+}
+
+/// Send a message to the thread mapped to an external conversation key
+///
+/// The thread is created for `user_id` on first use; later calls with the
+/// same key reach the same thread and stream like the thread endpoint.
+#[utoipa::path(
+    post,
+    path = "/conversations/{key}/messages",
+    params(
+        ("wire" = Option<String>, Query, description = "`verbose` (default) or `compact`"),
+    ),
+    request_body = SendMessageRequest,
+    responses(
+        (status = 200, description = "Streaming response", content_type = "text/event-stream"),
+        (status = 409, description = "Conversation key belongs to another user's thread")
+    ),
+    tag = "messages"
+)]
+pub async fn send_conversation_message_stream(
+    State(state): State<Arc<AppState>>,
+    Path(key): Path<String>,
+    ApiQuery(query): ApiQuery<StreamQuery>,
+    auth: AuthContext,
+    ApiJson(req): ApiJson<SendMessageRequest>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let thread = state.persist.get_or_create_thread_for_conversation(&key, &req.user_id).await?;
+    stream_to_thread(state, thread.id, key, query, auth, req).await
+}
+
+/// What the message stream sends: queue updates until the run starts,
+/// then the run's events
+enum RunEvent {
+    /// Position in the tenant's run queue, sent as the `queued` SSE event
+    Queued(usize),
+    Graph(Box<GraphStreamEvent>),
+}
+
+/// The run's user message
+///
+/// Its id is an ObjectId: the Mongo backend stores messages under ObjectIds
+/// and replaces any other id, so the id passed to `get_context_window` to
+/// leave this message out would never match the stored one.
+fn user_message(thread_id: &str, req: &SendMessageRequest) -> DBMessage {
+    DBMessage {
+        id: bson::oid::ObjectId::new().to_hex(),
+        thread_id: thread_id.to_string(),
+        user_id: req.user_id.clone(),
         role: MessageRole::User,
         message_type: MessageType::Message,
         content: req.content.clone(),
         tool_call_id: None,
         tool_name: None,
+        tool_server: None,
+        verification: None,
         arguments: None,
         reasoning_id: None,
         created_at: Utc::now(),
         duration_ms: None,
-    };
-    
-    state.persist.save_message(user_message).await?;
-    
-    // 3. Get context using strategy (BEFORE Graph execution)
-    let context_window = state.context_strategy
-        .get_context_window(&thread_id, Arc::clone(&state.persist))
-        .await?;
-    
-    // 4. Build full message history
-    let mut messages = vec![
-        LLMMessage::System {
-            content: Content::text(context_window.system_prompt),
-            name: None,
+        agent: None,
+        content_encoding: ContentEncoding::None,
+        content_sha256: None,
+        token_count: None,
+        summary: None,
+        iteration: None,
+        usage: None,
+        citations: Vec::new(),
+    }
+}
+
+/// The messages a run starts with: the server's system prompt, then the
+/// thread's developer prompt
+///
+/// Provider clients send the developer message as a system message to
+/// models without the developer role.
+fn instructions(system_prompt: String, developer_prompt: Option<String>) -> Vec<LLMMessage> {
+    std::iter::once(LLMMessage::system(system_prompt))
+        .chain(developer_prompt.map(LLMMessage::developer))
+        .collect()
+}
+
+/// SSE event name and payload for a graph event
+pub fn event_payload(event: GraphStreamEvent) -> (String, serde_json::Value) {
+    wire_event(event).into_parts()
+}
+
+/// The wire event for a graph event
+///
+/// The live stream and `/threads/:id/hydrate` both go through this, so
+/// history renders with the same schema as a running turn. The schema is
+/// `praxis::wire::StreamEvent`, the type `praxis-client` parses the stream into.
+pub fn wire_event(event: GraphStreamEvent) -> WireEvent {
+    match event {
+        GraphStreamEvent::InitStream { run_id, conversation_id, llm_config, variables, option_adjustments, .. } => {
+            WireEvent::Init(RunInfo {
+                run_id,
+                conversation_id,
+                llm_config: serde_json::to_value(llm_config).ok(),
+                routing: variables.get("routing").cloned(),
+                context_normalizations: variables.get("context_normalizations").cloned(),
+                option_adjustments,
+            })
         }
-    ];
-    messages.extend(context_window.messages);
-    messages.push(LLMMessage::Human {
-        content: Content::text(req.content.clone()),
-        name: None,
-    });
-    
-    // 5. Create GraphInput with dynamic LLM config from request
-    let llm_config = LLMConfig {
-        model: req.llm_config.model.clone(),
-        provider: praxis::Provider::OpenAI,
-        temperature: Some(req.llm_config.temperature),
-        max_tokens: Some(req.llm_config.max_tokens),
-        reasoning_effort: req.llm_config.reasoning_effort.clone(),
+        GraphStreamEvent::Message { content, agent, provenance } => {
+            WireEvent::Message { content, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::Audio { data_base64, agent, provenance } => {
+            WireEvent::Audio { data_base64, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::ToolCall { name, arguments, agent, provenance, .. } => {
+            WireEvent::ToolCall { name, arguments, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::ToolCallComplete { id, name, arguments, agent, provenance } => {
+            WireEvent::ToolCallComplete { id, name, arguments, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::Activity { tool_call_id, text, agent, provenance } => {
+            WireEvent::Activity { tool_call_id, text, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::Citations { citations, agent, provenance } => {
+            WireEvent::Citations { citations, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::ToolResult { result, tool_server, verification, agent, provenance, .. } => WireEvent::ToolResult {
+            result,
+            tool_server,
+            verification,
+            agent,
+            provenance: wire_provenance(provenance),
+        },
+        GraphStreamEvent::Reasoning { content, kind, agent, provenance } => {
+            WireEvent::Reasoning { content, kind, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::ReasoningSummary { item_id, text, agent, provenance } => {
+            WireEvent::ReasoningSummary { item_id, text, agent, provenance: wire_provenance(provenance) }
+        }
+        GraphStreamEvent::Handoff { from, to, reason } => WireEvent::Handoff { from, to, reason },
+        GraphStreamEvent::Retrying { reason, in_seconds, attempt } => WireEvent::Retrying { reason, in_seconds, attempt },
+        GraphStreamEvent::Thinking { elapsed_ms } => WireEvent::Thinking { elapsed_ms },
+        GraphStreamEvent::ResponseMetadata { metadata } => WireEvent::ResponseMetadata(metadata),
+        GraphStreamEvent::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens } => {
+            WireEvent::Usage { input_tokens, output_tokens, total_tokens, reasoning_tokens, cached_tokens }
+        }
+        GraphStreamEvent::Done { finish_reason, cost_usd } => {
+            WireEvent::Done { status: "completed".to_string(), finish_reason, cost_usd }
+        }
+        GraphStreamEvent::Warning { code, message, .. } => WireEvent::Warning { code, message },
+        GraphStreamEvent::ModerationWarning { stage, categories } => {
+            let stage = match stage {
+                ModerationStage::Input => "input",
+                ModerationStage::Output => "output",
+            };
+            WireEvent::ModerationWarning { stage: stage.to_string(), categories }
+        }
+        GraphStreamEvent::Error { message, kind, error_code, .. } => {
+            WireEvent::Error(Problem::from_graph_error(kind, error_code.as_deref(), message).into())
+        }
+        // Other event types (EndStream)
+        _ => WireEvent::Info {},
+    }
+}
+
+fn wire_provenance(provenance: Option<Provenance>) -> Option<WireProvenance> {
+    provenance.map(|provenance| WireProvenance {
+        node: match provenance.node {
+            NodeType::LLM => "llm",
+            NodeType::Tool => "tool",
+        }
+        .to_string(),
+        iteration: provenance.iteration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use praxis::{
+        ChatClient, ChatRequest, ChatResponse, DefaultContextStrategy, Graph, HistoryIndex,
+        InMemoryPersistenceClient, LLMClient, LLMStreamEvent, MCPToolExecutor, PersistError,
+        PersistenceClient, ReasoningClient, ResponseOutput, ResponseRequest, SummaryTrigger, ThreadMetadata,
+        ThreadSummary,
     };
-    
-    let graph_input = GraphInput::new(
-        thread_id.clone(),
-        messages,
-        llm_config,
-    );
-    
-    // 6. Spawn Graph with PersistenceContext
-    let event_receiver = state.graph.spawn_run(
-        graph_input,
-        Some(PersistenceContext {
-            thread_id: thread_id.clone(),
-            user_id: req.user_id.clone(),
-        }),
-    );
-    
-    // 7. Convert Receiver to Stream for SSE
-    let event_stream = ReceiverStream::new(event_receiver);
-    
-    // 8. Convert Graph events to SSE events (Graph handles persistence automatically)
-    let sse_stream = event_stream.map(move |event| {
-        let sse_event = match event {
-            GraphStreamEvent::Message { content, .. } => {
-                Event::default()
-                    .event("message")
-                    .json_data(serde_json::json!({
-                        "content": content
-                    }))
-            },
-            GraphStreamEvent::ToolCall { name, arguments, .. } => {
-                Event::default()
-                    .event("tool_call")
-                    .json_data(serde_json::json!({
-                        "name": name,
-                        "arguments": arguments
-                    }))
-            },
-            GraphStreamEvent::ToolResult { result, .. } => {
-                Event::default()
-                    .event("tool_result")
-                    .json_data(serde_json::json!({
-                        "result": result
-                    }))
-            },
-            GraphStreamEvent::Reasoning { content, .. } => {
-                Event::default()
-                    .event("reasoning")
-                    .json_data(serde_json::json!({
-                        "content": content
-                    }))
-            },
-            GraphStreamEvent::Done { .. } => {
-                Event::default()
-                    .event("done")
-                    .json_data(serde_json::json!({
-                        "status": "completed"
-                    }))
-            },
-            GraphStreamEvent::Error { message, .. } => {
-                Event::default()
-                    .event("error")
-                    .json_data(serde_json::json!({
-                        "error": message
-                    }))
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    fn thread(summary: Option<&str>) -> Thread {
+        let now = Utc::now();
+        Thread {
+            id: "t-1".to_string(),
+            user_id: "u-1".to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata: ThreadMetadata {
+                title: Some("Trip planning".to_string()),
+                ..Default::default()
             },
-            _ => {
-                // Handle other event types (InitStream, EndStream)
-                Event::default()
-                    .event("info")
-                    .json_data(serde_json::json!({}))
+            last_summary_update: now,
+            summary: summary.map(|text| ThreadSummary {
+                text: text.to_string(),
+                generated_at: now,
+                total_tokens_before_summary: 0,
+                messages_count: 0,
+                trigger: SummaryTrigger::Overflow,
+            }),
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        }
+    }
+
+    #[test]
+    fn test_thread_info_previews_long_summaries() {
+        let info = thread_info(&thread(None), 3);
+        assert_eq!(info.title.as_deref(), Some("Trip planning"));
+        assert_eq!(info.summary_preview, None);
+        assert_eq!(info.message_count, 3);
+
+        let short = thread_info(&thread(Some(" Booked flights. ")), 1);
+        assert_eq!(short.summary_preview.as_deref(), Some("Booked flights."));
+
+        let long = "é".repeat(SUMMARY_PREVIEW_CHARS + 10);
+        let preview = thread_info(&thread(Some(&long)), 1).summary_preview.unwrap();
+        assert_eq!(preview.chars().count(), SUMMARY_PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+    }
+
+    /// In-memory persistence whose message saves can be made slow or to
+    /// fail, logging when saves and history reads happen
+    #[derive(Default)]
+    struct StubPersistence {
+        inner: Arc<InMemoryPersistenceClient>,
+        fail_saves: bool,
+        save_delay: Duration,
+        log: Mutex<Vec<&'static str>>,
+    }
+
+    #[async_trait]
+    impl PersistenceClient for StubPersistence {
+        async fn save_message(&self, message: DBMessage) -> Result<(), PersistError> {
+            self.log.lock().unwrap().push("save started");
+            tokio::time::sleep(self.save_delay).await;
+            if self.fail_saves {
+                return Err(PersistError::Other("disk full".to_string()));
+            }
+            self.inner.save_message(message).await?;
+            self.log.lock().unwrap().push("save finished");
+            Ok(())
+        }
+
+        async fn get_messages(&self, thread_id: &str) -> Result<Vec<DBMessage>, PersistError> {
+            self.inner.get_messages(thread_id).await
+        }
+
+        async fn get_messages_after(
+            &self,
+            thread_id: &str,
+            after: chrono::DateTime<Utc>,
+        ) -> Result<Vec<DBMessage>, PersistError> {
+            self.log.lock().unwrap().push("history read");
+            self.inner.get_messages_after(thread_id, after).await
+        }
+
+        async fn create_thread(&self, user_id: &str, metadata: ThreadMetadata) -> Result<Thread, PersistError> {
+            self.inner.create_thread(user_id, metadata).await
+        }
+
+        async fn get_thread(&self, thread_id: &str) -> Result<Option<Thread>, PersistError> {
+            self.inner.get_thread(thread_id).await
+        }
+
+        async fn save_thread_summary(
+            &self,
+            thread_id: &str,
+            summary: String,
+            generated_at: chrono::DateTime<Utc>,
+            trigger: SummaryTrigger,
+        ) -> Result<(), PersistError> {
+            self.inner.save_thread_summary(thread_id, summary, generated_at, trigger).await
+        }
+
+        async fn update_thread_metadata(&self, thread_id: &str, metadata: ThreadMetadata) -> Result<(), PersistError> {
+            self.inner.update_thread_metadata(thread_id, metadata).await
+        }
+
+        async fn save_thread_index(&self, thread_id: &str, index: HistoryIndex) -> Result<(), PersistError> {
+            self.inner.save_thread_index(thread_id, index).await
+        }
+
+        async fn delete_thread(&self, thread_id: &str, user_id: &str) -> Result<(), PersistError> {
+            self.inner.delete_thread(thread_id, user_id).await
+        }
+
+        async fn list_threads(
+            &self,
+            user_id: &str,
+            limit: Option<i64>,
+            skip: Option<i64>,
+        ) -> Result<Vec<Thread>, PersistError> {
+            self.inner.list_threads(user_id, limit, skip).await
+        }
+    }
+
+    /// Answers every stream with "Hi", noting whether the user message was
+    /// stored by the time the provider was called
+    struct ObservingLLM {
+        persist: Arc<InMemoryPersistenceClient>,
+        thread_id: String,
+        calls: Mutex<Vec<bool>>,
+    }
+
+    #[async_trait]
+    impl ChatClient for ObservingLLM {
+        async fn chat(&self, _request: ChatRequest) -> anyhow::Result<ChatResponse> {
+            anyhow::bail!("not scripted")
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> anyhow::Result<std::pin::Pin<Box<dyn Stream<Item = anyhow::Result<LLMStreamEvent>> + Send>>> {
+            let messages = self.persist.get_messages(&self.thread_id).await?;
+            let user_message_stored = messages.iter().any(|m| m.role == MessageRole::User);
+            self.calls.lock().unwrap().push(user_message_stored);
+            Ok(Box::pin(futures::stream::iter(vec![
+                Ok(LLMStreamEvent::Message { content: "Hi".to_string(), choice_index: None, logprobs: None }),
+                Ok(LLMStreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None }),
+            ])))
+        }
+    }
+
+    #[async_trait]
+    impl ReasoningClient for ObservingLLM {
+        async fn reason(&self, _request: ResponseRequest) -> anyhow::Result<ResponseOutput> {
+            anyhow::bail!("not scripted")
+        }
+
+        async fn reason_stream(
+            &self,
+            _request: ResponseRequest,
+        ) -> anyhow::Result<std::pin::Pin<Box<dyn Stream<Item = anyhow::Result<LLMStreamEvent>> + Send>>> {
+            anyhow::bail!("not scripted")
+        }
+    }
+
+    impl LLMClient for ObservingLLM {}
+
+    async fn send(persistence: StubPersistence) -> (ApiResult<()>, Arc<ObservingLLM>, Arc<StubPersistence>) {
+        let persistence = Arc::new(persistence);
+        let memory = Arc::clone(&persistence.inner);
+        let thread = memory.create_thread("user-1", ThreadMetadata::default()).await.unwrap();
+        let llm = Arc::new(ObservingLLM {
+            persist: memory,
+            thread_id: thread.id.clone(),
+            calls: Mutex::new(Vec::new()),
+        });
+        let mcp_executor = Arc::new(MCPToolExecutor::new());
+        let graph = Graph::builder()
+            .llm_client(llm.clone())
+            .mcp_executor(mcp_executor.clone())
+            .build()
+            .unwrap();
+        let config: crate::config::Config = toml::from_str(include_str!("../../config/default.toml")).unwrap();
+        let state = Arc::new(AppState::new(
+            config,
+            persistence.clone(),
+            Arc::new(DefaultContextStrategy::new(100_000, llm.clone())),
+            llm.clone(),
+            mcp_executor,
+            graph,
+        ));
+        let req = SendMessageRequest {
+            user_id: "user-1".to_string(),
+            content: "hello".to_string(),
+            llm_config: RequestLLMConfig {
+                model: "gpt-4o".to_string(),
+                reasoning_effort: None,
+                reasoning_summary: None,
+                temperature: 0.7,
+                max_tokens: 100,
+                tool_choice: None,
+                top_p: None,
+                seed: None,
+                stop: None,
+                audio: None,
+                extra_body: None,
             },
         };
-        
-        Ok::<Event, Infallible>(sse_event.unwrap())
-    });
-    
-    Ok(Sse::new(sse_stream))
-}
+        let auth = AuthContext { tenant_id: None, scopes: Vec::new() };
+        let result = match send_message_stream(State(state), Path(thread.id), ApiQuery(StreamQuery::default()), auth, ApiJson(req)).await {
+            // Drain the stream so the run reaches the provider
+            Ok(sse) => {
+                let body = axum::response::IntoResponse::into_response(sse).into_body();
+                axum::body::to_bytes(body, usize::MAX).await.unwrap();
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        (result, llm, persistence)
+    }
+
+    #[test]
+    fn test_instructions_put_the_developer_prompt_after_the_system_prompt() {
+        let messages = instructions("Be helpful.".to_string(), Some("Answer in French.".to_string()));
+        let roles: Vec<&str> = messages.iter().map(LLMMessage::role).collect();
+        assert_eq!(roles, ["system", "developer"]);
+        assert_eq!(messages[1].text(), Some("Answer in French."));
+
+        assert_eq!(instructions("Be helpful.".to_string(), None).len(), 1);
+    }
+
+    #[test]
+    fn test_user_message_id_is_an_object_id() {
+        // The Mongo backend keeps ObjectId ids and replaces any other, so
+        // only these match the stored message when it is left out of the
+        // context window
+        let req: SendMessageRequest = serde_json::from_value(serde_json::json!({
+            "user_id": "user-1",
+            "content": "hello",
+            "llm_config": { "model": "gpt-4o" },
+        }))
+        .unwrap();
+        let message = user_message("t-1", &req);
+        assert!(bson::oid::ObjectId::parse_str(&message.id).is_ok(), "{}", message.id);
+        assert_eq!(message.role, MessageRole::User);
+    }
+
+    #[tokio::test]
+    async fn test_user_message_is_stored_before_the_provider_call() {
+        let (result, llm, _) = send(StubPersistence::default()).await;
 
+        assert!(result.is_ok());
+        assert_eq!(*llm.calls.lock().unwrap(), vec![true]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_user_message_save_aborts_before_the_provider_call() {
+        let (result, llm, _) = send(StubPersistence { fail_saves: true, ..Default::default() }).await;
+
+        assert!(matches!(result, Err(ApiError::Persist(_))));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(llm.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_context_window_is_built_while_the_user_message_is_saved() {
+        let save_delay = Duration::from_millis(200);
+        let (result, llm, persistence) = send(StubPersistence { save_delay, ..Default::default() }).await;
+
+        assert!(result.is_ok());
+        assert_eq!(*llm.calls.lock().unwrap(), vec![true]);
+        let log = persistence.log.lock().unwrap().clone();
+        assert_eq!(log, vec!["save started", "history read", "save finished"]);
+    }
+}
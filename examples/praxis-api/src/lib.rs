@@ -1,7 +1,14 @@
+pub mod auth;
+pub mod cli;
 pub mod config;
 pub mod state;
 pub mod error;
+pub mod extract;
 pub mod routes;
+pub mod replay;
+pub mod routing;
+pub mod scheduler;
 pub mod handlers;
 pub mod middleware;
+pub mod webhooks;
 
@@ -1,6 +1,7 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
 use std::sync::Arc;
@@ -13,13 +14,19 @@ use tower_http::{
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 use praxis_api::{
+    cli::{self, Command},
     config::Config,
+    error::ApiError,
     middleware::logging,
-    routes::{health, messages, threads},
-    handlers::stream,
+    routes::{attachments, health, messages, threads},
+    handlers::{hydrate, stream},
     state::AppState,
+    webhooks,
+};
+use praxis::{
+    CheckStatus, ConcurrencyLimitedClient, HygieneRunner, OpenAIClient, OpenAIConfig, MCPToolExecutor, MongoPersistenceClient, OutboxDispatcher,
+    OutboxStore, StatsRecordingClient, StdioServerPool, TiktokenTokenizer,
 };
-use praxis::{OpenAIClient, MCPClient, MCPToolExecutor, MongoPersistenceClient};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -33,52 +40,180 @@ async fn main() -> anyhow::Result<()> {
     // Initialize logging
     init_logging(&config);
     
+    // Context strategy uses a default max_tokens for managing context window
+    // Actual max_tokens per request is sent via SendMessageRequest
+    const DEFAULT_CONTEXT_MAX_TOKENS: usize = 8000;
+    
+    let command = Command::parse(std::env::args().skip(1)).map_err(|usage| anyhow::anyhow!(usage))?;
+    if let Command::ValidateGraph { path } = &command {
+        return cli::validate_graph(path, config.graph.clone(), &mut std::io::stdout().lock());
+    }
+    if let Command::Reindex { model, thread_ids } = command {
+        let mongo_client = MongoPersistenceClient::connect(&config.mongodb_uri, &config.mongodb.database).await?;
+        let updated = cli::reindex(&mongo_client, &TiktokenTokenizer, &model, thread_ids).await?;
+        tracing::info!("Reindex complete: {} messages updated", updated);
+        return Ok(());
+    }
+    if let Command::Migrate { dry_run } = command {
+        let mongo_client = MongoPersistenceClient::connect(&config.mongodb_uri, &config.mongodb.database).await?;
+        cli::migrate(&mongo_client, dry_run, &mut std::io::stdout().lock()).await?;
+        return Ok(());
+    }
+    if let Command::Export { anonymize, thread_ids } = command {
+        let mongo_client = MongoPersistenceClient::connect(&config.mongodb_uri, &config.mongodb.database).await?;
+        let anonymizer = anonymize.then(|| config.export.anonymizer());
+        let exported = cli::export(&mongo_client, anonymizer.as_ref(), thread_ids, &mut std::io::stdout().lock()).await?;
+        tracing::info!("Export complete: {} threads", exported);
+        return Ok(());
+    }
+    
     tracing::info!("Starting Praxis API server");
     tracing::info!("Config loaded: {}:{}", config.server.host, config.server.port);
     
     // Initialize LLM client
     tracing::info!("Initializing LLM client");
-    let llm_client: Arc<dyn praxis::LLMClient> = Arc::new(OpenAIClient::new(config.openai_api_key.clone())?);
+    let mut openai_config = OpenAIConfig::new(config.openai_api_key.clone())
+        .with_timeout(std::time::Duration::from_secs(config.llm.timeout_secs))
+        .with_connect_timeout(std::time::Duration::from_secs(config.llm.connect_timeout_secs))
+        .with_stream_idle_timeout(std::time::Duration::from_secs(config.llm.stream_idle_timeout_secs));
+    if let Some(organization) = &config.openai_organization {
+        openai_config = openai_config.with_organization(organization.clone());
+    }
+    if let Some(project) = &config.openai_project {
+        openai_config = openai_config.with_project(project.clone());
+    }
+    if let Some(retry) = &config.llm.retry {
+        openai_config = openai_config.with_retry_policy(retry.clone());
+    }
+    if !config.llm.headers.is_empty() {
+        let headers = reqwest::header::HeaderMap::try_from(&config.llm.headers)
+            .map_err(|e| anyhow::anyhow!("Invalid [llm.headers]: {}", e))?;
+        openai_config = openai_config.with_default_headers(headers);
+    }
+    let openai_client = Arc::new(
+        StatsRecordingClient::new(Arc::new(OpenAIClient::from_config(openai_config)?), "openai")
+            .with_window(config.llm.stats_window),
+    );
+    if let Some(dir) = &config.llm.record_dir {
+        tracing::warn!("Recording LLM calls to {}", dir.display());
+    }
+    let llm_client: Arc<dyn praxis::LLMClient> = Arc::new(ConcurrencyLimitedClient::new(
+        praxis::ClientFactory::with_record_dir(openai_client.clone(), config.llm.record_dir.as_deref()),
+        config.llm.max_concurrent_requests,
+    ));
+    
+    if let Command::Eval(args) = command {
+        let passed = cli::eval(llm_client, config.graph.clone(), args, &mut std::io::stdout().lock()).await?;
+        anyhow::ensure!(passed, "some eval cases failed");
+        return Ok(());
+    }
+    if let Command::Hygiene = command {
+        let mongo_client = MongoPersistenceClient::connect(&config.mongodb_uri, &config.mongodb.database).await?;
+        let summarizer = praxis::DefaultContextStrategy::new(DEFAULT_CONTEXT_MAX_TOKENS, llm_client.clone());
+        let runner = HygieneRunner::new(Arc::new(mongo_client), format!("praxis-cli-{}", uuid::Uuid::new_v4()))
+            .with_summarizer(Arc::new(summarizer))
+            .with_lease(std::time::Duration::from_secs(config.hygiene.lease_secs));
+        cli::hygiene(&runner, &config.hygiene.policy, &mut std::io::stdout().lock()).await?;
+        return Ok(());
+    }
+    
+    if let Command::Doctor(args) = command {
+        let mongo_client = MongoPersistenceClient::connect(&config.mongodb_uri, &config.mongodb.database).await?;
+        let doctor = cli::doctor_config(&config, Arc::new(mongo_client), llm_client, &args.model).spending(args.llm);
+        let report = cli::doctor(doctor, args.json, &mut std::io::stdout().lock()).await?;
+        anyhow::ensure!(report.passed(), "some critical checks failed");
+        return Ok(());
+    }
+    let strict = matches!(command, Command::Serve { strict: true });
+    if strict || config.server.self_check {
+        tracing::info!("Running startup self-check");
+        let mongo_client = MongoPersistenceClient::connect(&config.mongodb_uri, &config.mongodb.database).await?;
+        let doctor = cli::doctor_config(&config, Arc::new(mongo_client), llm_client.clone(), cli::DEFAULT_DOCTOR_MODEL);
+        let report = praxis::doctor(doctor).await;
+        for check in report.checks.iter().filter(|check| matches!(check.status, CheckStatus::Fail | CheckStatus::Warn)) {
+            tracing::warn!(
+                "Self-check {} {:?}: {} ({})",
+                check.name,
+                check.status,
+                check.detail,
+                check.hint.as_deref().unwrap_or_default()
+            );
+        }
+        if strict && !report.passed() {
+            let failed: Vec<&str> = report.critical_failures().map(|check| check.name.as_str()).collect();
+            anyhow::bail!("Startup self-check failed: {}", failed.join(", "));
+        }
+    }
     
     // Initialize MCP executor and connect to servers
     tracing::info!("Connecting to MCP servers");
     let mcp_executor = MCPToolExecutor::new();
-    for (idx, url) in config.mcp.servers.split(',').enumerate() {
-        let url = url.trim();
-        if !url.is_empty() {
-            match MCPClient::new_http(&format!("mcp-server-{}", idx), url).await {
-                Ok(client) => {
-                    mcp_executor.add_server(client).await?;
-                    tracing::info!("Connected to MCP server: {}", url);
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to connect to MCP server {}: {}", url, e);
-                }
+    for target in config.mcp.server_targets() {
+        match target.connect().await {
+            Ok(client) => {
+                mcp_executor.add_server(client).await?;
+                tracing::info!("Connected to MCP server: {}", target.name());
             }
+            Err(e) => {
+                tracing::warn!("Failed to connect to MCP server {}: {}", target.name(), e);
+            }
+        }
+    }
+    for server in &config.mcp.stdio {
+        let connected = async {
+            match server.pool {
+                Some(_) => mcp_executor.add_pool(StdioServerPool::start(server.clone()).await?).await,
+                None => mcp_executor.add_server(server.spawn().await?).await,
+            }
+        }
+        .await;
+        match connected {
+            Ok(()) => tracing::info!("Started MCP server: {}", server.name),
+            Err(e) => tracing::warn!("Failed to start MCP server {}: {}", server.name, e),
         }
     }
+    for (tool, tool_config) in &config.mcp.tools {
+        mcp_executor.configure_tool(tool.clone(), tool_config.clone()).await;
+    }
+    mcp_executor.validate_tool_configs().await?;
     
     // Initialize persistence client (MongoDB)
     tracing::info!("Connecting to MongoDB");
     let mongo_client = MongoPersistenceClient::connect(
         &config.mongodb_uri,
         &config.mongodb.database,
-    ).await?;
-    let persist_client: Arc<dyn praxis::PersistenceClient> = Arc::new(mongo_client);
+    ).await?
+    .with_compression(config.mongodb.compression.clone())
+    .with_read_consistency(config.mongodb.read_consistency);
+    let mongo_client = Arc::new(mongo_client);
+    let persist_client: Arc<dyn praxis::PersistenceClient> = mongo_client.clone();
     
     tracing::info!("MongoDB connected");
+    if config.mongodb.migrate_on_startup {
+        let report = persist_client.migrate(false).await?;
+        tracing::info!("Schema migrations done: {} documents migrated", report.documents());
+    }
     
     // Create context strategy
     tracing::info!("Initializing context strategy");
-    // Context strategy uses a default max_tokens for managing context window
-    // Actual max_tokens per request is sent via SendMessageRequest
-    const DEFAULT_CONTEXT_MAX_TOKENS: usize = 8000;
-    let context_strategy: Arc<dyn praxis::ContextStrategy> = Arc::new(
-        praxis::DefaultContextStrategy::new(
-            DEFAULT_CONTEXT_MAX_TOKENS,
-            llm_client.clone(),
-        )
+    let mut default_strategy = praxis::DefaultContextStrategy::new(
+        DEFAULT_CONTEXT_MAX_TOKENS,
+        llm_client.clone(),
     );
+    if let Some(cadence) = config.context.cadence() {
+        tracing::info!("Summarizing threads on a cadence: {:?}", cadence);
+        default_strategy = default_strategy.with_trigger(Arc::new(cadence));
+    }
+    let default_strategy = Arc::new(default_strategy);
+    let context_strategy: Arc<dyn praxis::ContextStrategy> = if config.context.stages.is_empty() {
+        default_strategy.clone()
+    } else {
+        let composite = praxis::CompositeContextStrategy::from_config(DEFAULT_CONTEXT_MAX_TOKENS, &config.context.stages)
+            .with_summarizer(default_strategy.clone());
+        composite.validate()?;
+        tracing::info!("Building context windows from stages: {:?}", config.context.stages);
+        Arc::new(composite)
+    };
     
     // Wrap mcp_executor in Arc for sharing
     let mcp_executor = Arc::new(mcp_executor);
@@ -122,13 +257,28 @@ async fn main() -> anyhow::Result<()> {
     
     // Create graph with persistence and observability
     tracing::info!("Initializing Graph orchestrator with persistence");
+    let graph_builder = match &config.graph_spec.path {
+        Some(path) => {
+            tracing::info!("Loading graph spec from {}", path.display());
+            praxis::GraphBuilder::from_spec(path, &praxis::NodeRegistry::new())?
+        }
+        None => praxis::Graph::builder(),
+    };
+    let graph_builder = match config.pricing.calculator() {
+        Some(calculator) => graph_builder.with_cost_calculator(calculator),
+        None => graph_builder,
+    };
     #[cfg(feature = "observability")]
     let graph = {
-        let mut builder = praxis::Graph::builder()
+        let mut builder = graph_builder
             .llm_client(llm_client.clone())
             .reasoning_client(llm_client.clone() as Arc<dyn praxis::ReasoningClient>) // OpenAIClient implements both ChatClient and ReasoningClient
             .mcp_executor(Arc::clone(&mcp_executor))
-            .with_persistence(persist_client.clone());
+            .with_config(config.graph.clone())
+            .with_persistence(persist_client.clone())
+            .with_persistence_policy(config.persistence.clone())
+            .with_content_tokenizer(Arc::new(TiktokenTokenizer))
+            .with_tool_authorizer(Arc::new(config.authorization.clone()));
         
         if let Some(obs) = observer {
             builder = builder.with_observer(obs);
@@ -138,22 +288,57 @@ async fn main() -> anyhow::Result<()> {
     };
     
     #[cfg(not(feature = "observability"))]
-    let graph = praxis::Graph::builder()
+    let graph = graph_builder
         .llm_client(llm_client.clone())
         .reasoning_client(llm_client.clone() as Arc<dyn praxis::ReasoningClient>) // OpenAIClient implements both ChatClient and ReasoningClient
         .mcp_executor(Arc::clone(&mcp_executor))
+        .with_config(config.graph.clone())
         .with_persistence(persist_client.clone())
+        .with_persistence_policy(config.persistence.clone())
+        .with_content_tokenizer(Arc::new(TiktokenTokenizer))
+        .with_tool_authorizer(Arc::new(config.authorization.clone()))
         .build()?;
     
     // Create application state
-    let state = Arc::new(AppState::new(
+    let mut state = AppState::new(
         config.clone(),
         persist_client,
         context_strategy,
         llm_client,
         mcp_executor,
         graph,
-    ));
+    )
+    .with_llm_provider(openai_client);
+    
+    let replica = format!("praxis-api-{}", uuid::Uuid::new_v4());
+    
+    // Deliver webhooks from the outbox; every replica runs a dispatcher and
+    // leases keep them from sending the same entry
+    let dispatcher = match &config.outbox.webhook_url {
+        Some(url) => {
+            tracing::info!("Delivering run webhooks to {}", url);
+            let outbox: Arc<dyn OutboxStore> = mongo_client.clone();
+            state = state.with_outbox(outbox.clone());
+            let dispatcher = OutboxDispatcher::new(outbox, replica.clone())
+                .with_handler(webhooks::WEBHOOK_KIND, Arc::new(webhooks::WebhookHandler::new(url.clone())))
+                .with_poll_interval(std::time::Duration::from_millis(config.outbox.poll_interval_ms))
+                .with_lease(std::time::Duration::from_secs(config.outbox.lease_secs))
+                .with_max_attempts(config.outbox.max_attempts);
+            Some(dispatcher.spawn())
+        }
+        None => None,
+    };
+    
+    // Hygiene of idle threads, also run on every replica; the run lease lets
+    // one of them at a time through
+    let hygiene = config.hygiene.enabled.then(|| {
+        tracing::info!("Running thread hygiene every {}s", config.hygiene.interval_secs);
+        HygieneRunner::new(mongo_client.clone(), replica.clone())
+            .with_summarizer(default_strategy.clone())
+            .with_lease(std::time::Duration::from_secs(config.hygiene.lease_secs))
+            .spawn(config.hygiene.policy.clone(), std::time::Duration::from_secs(config.hygiene.interval_secs))
+    });
+    let state = Arc::new(state);
     
     // Build router
     let app = build_router(state.clone());
@@ -166,42 +351,116 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Health check: http://{}/health", addr);
     tracing::info!("API docs: http://{}/api/docs", addr);
     
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    
+    if let Some(dispatcher) = dispatcher {
+        dispatcher.abort();
+    }
+    if let Some(hygiene) = hygiene {
+        hygiene.abort();
+    }
+    
+    // Stop stdio MCP servers so no child processes outlive the API
+    tracing::info!("Shutting down MCP servers");
+    if let Err(e) = state.mcp_executor.shutdown_all().await {
+        tracing::warn!("{}", e);
+    }
     
     Ok(())
 }
 
+/// Resolves on Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    
+    tracing::info!("Shutdown signal received");
+}
+
 fn build_router(state: Arc<AppState>) -> Router {
     // API routes
     let api_routes = Router::new()
         // Health
         .route("/health", get(health::health_check))
+        .route("/health/llm", get(health::llm_health))
+        .route("/health/runs", get(health::run_queue_health))
         // Threads
         .route("/threads", post(threads::create_thread))
         .route("/threads", get(threads::list_threads))
         .route("/threads/:thread_id", get(threads::get_thread))
+        .route("/threads/:thread_id", patch(threads::update_thread))
         .route("/threads/:thread_id", delete(threads::delete_thread))
+        .route("/threads/:thread_id/export", get(threads::export_thread))
+        .route("/threads/:thread_id/summarize", post(threads::summarize_thread))
+        .route(
+            "/threads/:thread_id/attachments",
+            post(attachments::upload_attachment)
+                .layer(DefaultBodyLimit::max(attachments::upload_body_limit(&state.config.attachments))),
+        )
+        .route("/threads/:thread_id/attachments", get(attachments::list_attachments))
+        // Routes can't escape ':', so `tools:repin` is `tools` + a `:method`
+        // parameter the handler checks
+        .route("/threads/:thread_id/tools:method", post(threads::repin_tools))
         // Messages
         .route("/threads/:thread_id/messages", get(messages::list_messages))
-        .route("/threads/:thread_id/messages", post(stream::send_message_stream));
+        .route("/threads/:thread_id/messages", post(stream::send_message_stream))
+        .route("/conversations/:key/messages", post(stream::send_conversation_message_stream))
+        .route("/streams/:stream_id", get(stream::resume_message_stream))
+        .route("/threads/:thread_id/hydrate", get(hydrate::hydrate_thread));
     
     // Build full router with middleware
     Router::new()
         .nest("/", api_routes)
+        .fallback(route_not_found)
         .layer(middleware::from_fn(logging::log_request))
         .layer(TimeoutLayer::new(std::time::Duration::from_secs(300)))
+        // The default predicate skips `text/event-stream`, so message streams
+        // go out uncompressed (gzip would hold back deltas until a block
+        // fills); `?wire=compact` is how streams get smaller
         .layer(CompressionLayer::new())
         .layer(build_cors_layer(&state.config))
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
+async fn route_not_found(uri: axum::http::Uri) -> ApiError {
+    ApiError::RouteNotFound(uri.path().to_string())
+}
+
 fn build_cors_layer(config: &Config) -> CorsLayer {
     if config.cors.enabled {
         let cors = CorsLayer::new()
             .allow_methods([
                 axum::http::Method::GET,
                 axum::http::Method::POST,
+                axum::http::Method::PATCH,
                 axum::http::Method::DELETE,
                 axum::http::Method::OPTIONS,
             ])
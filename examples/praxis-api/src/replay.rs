@@ -0,0 +1,201 @@
+//! Replay of message streams for clients reconnecting with `Last-Event-ID`
+//!
+//! A run doesn't depend on the connection that started it: its SSE events
+//! go into a `StreamBuffer`, numbered from 1, and every connection reads the
+//! buffer from where it left off. Each event's SSE `id` is
+//! `<stream_id>:<seq>`, so after a dropped connection
+//! `GET /streams/{stream_id}` with the last id the client saw sends the
+//! events it missed and then follows the run. A buffer is dropped
+//! `[stream] replay_retention_secs` after its run's events end.
+
+use futures::Stream;
+use praxis::wire;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// One event of a buffered stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferedEvent {
+    /// Position in the stream, from 1
+    pub seq: u64,
+    pub name: String,
+    pub data: Value,
+}
+
+/// Events a message stream has sent so far
+pub struct StreamBuffer {
+    pub stream_id: String,
+    pub thread_id: String,
+    events: Mutex<Vec<(String, Value)>>,
+    /// Number of buffered events; closed once the run's events end
+    len: watch::Receiver<u64>,
+    ended_at: Mutex<Option<Instant>>,
+}
+
+impl StreamBuffer {
+    /// SSE `id` of the event at `seq`
+    pub fn event_id(&self, seq: u64) -> String {
+        wire::event_id(&self.stream_id, seq)
+    }
+
+    /// Events after `seq`, then each new one until the run's events end
+    pub fn events_after(self: &Arc<Self>, seq: u64) -> impl Stream<Item = BufferedEvent> + Send + 'static {
+        let buffer = Arc::clone(self);
+        async_stream::stream! {
+            let mut len = buffer.len.clone();
+            let mut next = seq;
+            loop {
+                len.borrow_and_update();
+                for event in buffer.read_from(next) {
+                    next = event.seq;
+                    yield event;
+                }
+                if len.changed().await.is_err() {
+                    // Ended: what was pushed before the end is all there is
+                    for event in buffer.read_from(next) {
+                        yield event;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    fn read_from(&self, seq: u64) -> Vec<BufferedEvent> {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .enumerate()
+            .skip(seq as usize)
+            .map(|(index, (name, data))| BufferedEvent { seq: index as u64 + 1, name: name.clone(), data: data.clone() })
+            .collect()
+    }
+
+    fn expired(&self, retention: Duration) -> bool {
+        self.ended_at.lock().unwrap().is_some_and(|ended| ended.elapsed() >= retention)
+    }
+}
+
+/// Appends to a `StreamBuffer`; dropping it ends the stream
+pub struct StreamWriter {
+    buffer: Arc<StreamBuffer>,
+    len: watch::Sender<u64>,
+}
+
+impl StreamWriter {
+    pub fn buffer(&self) -> &Arc<StreamBuffer> {
+        &self.buffer
+    }
+
+    pub fn push(&self, name: String, data: Value) {
+        let mut events = self.buffer.events.lock().unwrap();
+        events.push((name, data));
+        self.len.send_replace(events.len() as u64);
+    }
+}
+
+impl Drop for StreamWriter {
+    fn drop(&mut self) {
+        *self.buffer.ended_at.lock().unwrap() = Some(Instant::now());
+    }
+}
+
+/// Buffers of the streams that can still be resumed, by stream id
+pub struct StreamBuffers {
+    streams: Mutex<HashMap<String, Arc<StreamBuffer>>>,
+    retention: Duration,
+}
+
+impl StreamBuffers {
+    /// Keep each buffer for `retention` after its run's events end
+    pub fn new(retention: Duration) -> Self {
+        Self { streams: Mutex::new(HashMap::new()), retention }
+    }
+
+    /// Start buffering a new stream of `thread_id`, dropping expired ones
+    pub fn open(&self, thread_id: &str) -> StreamWriter {
+        let (len_tx, len_rx) = watch::channel(0);
+        let buffer = Arc::new(StreamBuffer {
+            stream_id: uuid::Uuid::new_v4().simple().to_string(),
+            thread_id: thread_id.to_string(),
+            events: Mutex::new(Vec::new()),
+            len: len_rx,
+            ended_at: Mutex::new(None),
+        });
+        let mut streams = self.streams.lock().unwrap();
+        streams.retain(|_, stream| !stream.expired(self.retention));
+        streams.insert(buffer.stream_id.clone(), Arc::clone(&buffer));
+        StreamWriter { buffer, len: len_tx }
+    }
+
+    pub fn get(&self, stream_id: &str) -> Option<Arc<StreamBuffer>> {
+        let streams = self.streams.lock().unwrap();
+        streams.get(stream_id).filter(|stream| !stream.expired(self.retention)).cloned()
+    }
+}
+
+/// Sequence number in a `Last-Event-ID` of the stream `stream_id`
+pub fn parse_event_id(stream_id: &str, event_id: &str) -> Option<u64> {
+    wire::parse_event_id(event_id).filter(|(id, _)| *id == stream_id).map(|(_, seq)| seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn push(writer: &StreamWriter, content: &str) {
+        writer.push("message".to_string(), serde_json::json!({ "content": content }));
+    }
+
+    fn contents(events: &[BufferedEvent]) -> Vec<(u64, &str)> {
+        events.iter().map(|event| (event.seq, event.data["content"].as_str().unwrap())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_reader_gets_the_events_after_its_last_one_then_follows() {
+        let streams = StreamBuffers::new(Duration::from_secs(60));
+        let writer = streams.open("t-1");
+        push(&writer, "a");
+        push(&writer, "b");
+        push(&writer, "c");
+
+        let buffer = streams.get(&writer.buffer().stream_id).unwrap();
+        let mut reader = Box::pin(buffer.events_after(1));
+        let replayed = vec![reader.next().await.unwrap(), reader.next().await.unwrap()];
+        assert_eq!(contents(&replayed), [(2, "b"), (3, "c")]);
+
+        push(&writer, "d");
+        drop(writer);
+        let rest: Vec<_> = reader.collect().await;
+        assert_eq!(contents(&rest), [(4, "d")]);
+    }
+
+    #[tokio::test]
+    async fn test_ended_streams_replay_until_they_expire() {
+        let streams = StreamBuffers::new(Duration::from_millis(50));
+        let writer = streams.open("t-1");
+        let stream_id = writer.buffer().stream_id.clone();
+        push(&writer, "a");
+        drop(writer);
+
+        let events: Vec<_> = streams.get(&stream_id).unwrap().events_after(0).collect().await;
+        assert_eq!(contents(&events), [(1, "a")]);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(streams.get(&stream_id).is_none());
+        streams.open("t-2");
+        assert_eq!(streams.streams.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_event_ids_name_their_stream() {
+        assert_eq!(parse_event_id("abc", "abc:7"), Some(7));
+        assert_eq!(parse_event_id("abc", "xyz:7"), None);
+        assert_eq!(parse_event_id("abc", "abc:seven"), None);
+        assert_eq!(parse_event_id("abc", "7"), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! Files uploaded to a thread
+//!
+//! Uploads are `multipart/form-data` with one file part, parsed by axum's
+//! `Multipart`. The file is read chunk by chunk and rejected as soon as it
+//! passes `[attachments] max_bytes`, so an oversized upload is never
+//! buffered whole. Stored files are offered to the model through a
+//! `read_attachment` tool on the thread's runs (see `handlers::stream`).
+
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use praxis::AttachmentRef;
+use crate::{config::AttachmentsConfig, error::{ApiError, ApiResult}, extract::ApiMultipart, state::AppState};
+
+/// Room for the multipart boundaries and part headers around the file
+const MULTIPART_OVERHEAD: usize = 16 * 1024;
+
+/// Longest accepted file name, in bytes
+const MAX_NAME_BYTES: usize = 255;
+
+#[derive(Debug, Serialize)]
+pub struct ListAttachmentsResponse {
+    pub attachments: Vec<AttachmentRef>,
+}
+
+/// The file part of an upload
+#[derive(Debug, PartialEq)]
+struct FilePart {
+    name: String,
+    mime_type: String,
+    bytes: Vec<u8>,
+}
+
+/// Upload a file to a thread
+///
+/// Replaces an earlier attachment of the same name.
+#[utoipa::path(
+    post,
+    path = "/threads/{thread_id}/attachments",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    request_body(content = String, content_type = "multipart/form-data", description = "One file part"),
+    responses(
+        (status = 201, description = "Stored attachment", body = AttachmentRef),
+        (status = 400, description = "Malformed multipart body or no file part"),
+        (status = 404, description = "Thread not found"),
+        (status = 413, description = "File larger than `[attachments] max_bytes`"),
+        (status = 415, description = "Not multipart, or a file type outside `allowed_mime_types`")
+    ),
+    tag = "threads"
+)]
+pub async fn upload_attachment(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    ApiMultipart(multipart): ApiMultipart,
+) -> ApiResult<(StatusCode, Json<AttachmentRef>)> {
+    if state.persist.get_thread(&thread_id).await?.is_none() {
+        return Err(ApiError::ThreadNotFound(thread_id));
+    }
+
+    let config = &state.config.attachments;
+    let file = read_file_part(multipart, config.max_bytes).await?;
+    check_upload(config, &file)?;
+
+    let attachment = state
+        .persist
+        .save_attachment(&thread_id, &file.name, &file.mime_type, file.bytes)
+        .await?;
+    tracing::info!(
+        "Stored attachment {} ({}, {} bytes) on thread {}",
+        attachment.name,
+        attachment.mime_type,
+        attachment.size,
+        thread_id
+    );
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+/// List a thread's attachments
+#[utoipa::path(
+    get,
+    path = "/threads/{thread_id}/attachments",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    responses(
+        (status = 200, description = "Attachments, oldest first", body = ListAttachmentsResponse),
+        (status = 404, description = "Thread not found")
+    ),
+    tag = "threads"
+)]
+pub async fn list_attachments(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+) -> ApiResult<Json<ListAttachmentsResponse>> {
+    if state.persist.get_thread(&thread_id).await?.is_none() {
+        return Err(ApiError::ThreadNotFound(thread_id));
+    }
+    let attachments = state.persist.list_attachments(&thread_id).await?;
+    Ok(Json(ListAttachmentsResponse { attachments }))
+}
+
+/// Size and type checks against `[attachments]`
+fn check_upload(config: &AttachmentsConfig, file: &FilePart) -> ApiResult<()> {
+    if file.bytes.len() > config.max_bytes {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "'{}' is {} bytes, the limit is {}",
+            file.name,
+            file.bytes.len(),
+            config.max_bytes
+        )));
+    }
+    if !config.allows(&file.mime_type) {
+        return Err(ApiError::UnsupportedMediaType(format!("{} is not an allowed attachment type", file.mime_type)));
+    }
+    Ok(())
+}
+
+/// Largest upload body for `config`, set as the route's body limit: the
+/// file plus room for the other parts
+pub fn upload_body_limit(config: &AttachmentsConfig) -> usize {
+    config.max_bytes + MULTIPART_OVERHEAD
+}
+
+/// The first part of the upload that carries a file name, failing once the
+/// file passes `max_bytes`
+async fn read_file_part(mut multipart: Multipart, max_bytes: usize) -> ApiResult<FilePart> {
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let Some(name) = field.file_name().map(clean_file_name).transpose()? else {
+            continue;
+        };
+        let mime_type = field.content_type().unwrap_or("application/octet-stream").trim().to_string();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(ApiError::PayloadTooLarge(format!("attachments are limited to {} bytes", max_bytes)));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        return Ok(FilePart { name, mime_type, bytes });
+    }
+    Err(ApiError::BadRequest("no file part in the upload".to_string()))
+}
+
+/// A body over the route's limit is a 413; anything else is malformed
+fn multipart_error(error: MultipartError) -> ApiError {
+    match error.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => ApiError::PayloadTooLarge(error.body_text()),
+        _ => ApiError::BadRequest(format!("malformed multipart body: {}", error.body_text())),
+    }
+}
+
+/// Last path segment of an uploaded file name (some clients send full paths)
+fn clean_file_name(name: &str) -> ApiResult<String> {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(ApiError::BadRequest("the file part needs a file name".to_string()));
+    }
+    if name.len() > MAX_NAME_BYTES {
+        return Err(ApiError::BadRequest(format!("file names are limited to {} bytes", MAX_NAME_BYTES)));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::{FromRequest, Request}, http::header};
+
+    const BOUNDARY: &str = "----praxis1234";
+
+    fn request(content_type: &str, body: Vec<u8>) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    fn multipart_request(parts: &[(&str, &str)]) -> Request {
+        let mut body = Vec::new();
+        for (headers, content) in parts {
+            body.extend_from_slice(format!("--{}\r\n{}\r\n\r\n{}\r\n", BOUNDARY, headers, content).as_bytes());
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        request(&format!("multipart/form-data; boundary={}", BOUNDARY), body)
+    }
+
+    async fn upload(parts: &[(&str, &str)], max_bytes: usize) -> ApiResult<FilePart> {
+        let ApiMultipart(multipart) = ApiMultipart::from_request(multipart_request(parts), &()).await?;
+        read_file_part(multipart, max_bytes).await
+    }
+
+    #[tokio::test]
+    async fn test_file_part_is_found_after_other_fields() {
+        let file = upload(
+            &[
+                ("Content-Disposition: form-data; name=\"note\"", "ignored"),
+                (
+                    "Content-Disposition: form-data; name=\"file\"; filename=\"report.csv\"\r\nContent-Type: text/csv",
+                    "a,b\r\n1,2",
+                ),
+            ],
+            100,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(file.name, "report.csv");
+        assert_eq!(file.mime_type, "text/csv");
+        assert_eq!(file.bytes, b"a,b\r\n1,2");
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_part_or_type() {
+        let fields_only = upload(&[("Content-Disposition: form-data; name=\"note\"", "hi")], 100).await;
+        assert!(matches!(fields_only, Err(ApiError::BadRequest(_))));
+
+        let untyped = upload(&[("Content-Disposition: form-data; name=\"file\"; filename=\"blob\"", "x")], 100).await;
+        assert_eq!(untyped.unwrap().mime_type, "application/octet-stream");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_or_non_multipart_bodies() {
+        let json = ApiMultipart::from_request(request("application/json", b"{}".to_vec()), &()).await;
+        assert!(matches!(json, Err(ApiError::UnsupportedMediaType(_))));
+
+        let content_type = format!("multipart/form-data; boundary={}", BOUNDARY);
+        let ApiMultipart(truncated) =
+            ApiMultipart::from_request(request(&content_type, format!("--{}\r\nContent-Disp", BOUNDARY).into_bytes()), &())
+                .await
+                .unwrap();
+        assert!(matches!(read_file_part(truncated, 100).await, Err(ApiError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_file_read_stops_at_the_limit() {
+        let part = [("Content-Disposition: form-data; name=\"file\"; filename=\"f.txt\"", "12345678901")];
+        assert!(matches!(upload(&part, 10).await, Err(ApiError::PayloadTooLarge(_))));
+        assert_eq!(upload(&part, 11).await.unwrap().bytes, b"12345678901");
+    }
+
+    #[tokio::test]
+    async fn test_body_over_the_route_limit_is_a_413() {
+        async fn handler(ApiMultipart(multipart): ApiMultipart) -> ApiResult<StatusCode> {
+            read_file_part(multipart, 1000).await.map(|_| StatusCode::CREATED)
+        }
+        let mut router =
+            axum::Router::new().route("/", axum::routing::post(handler).layer(axum::extract::DefaultBodyLimit::max(100)));
+        let content = "x".repeat(500);
+        let part = [("Content-Disposition: form-data; name=\"file\"; filename=\"f.txt\"", content.as_str())];
+
+        let response = tower::Service::call(&mut router, multipart_request(&part)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    fn file(mime_type: &str, size: usize) -> FilePart {
+        FilePart { name: "f".to_string(), mime_type: mime_type.to_string(), bytes: vec![b'x'; size] }
+    }
+
+    #[test]
+    fn test_size_limit_and_mime_allowlist() {
+        let config = AttachmentsConfig { max_bytes: 10, ..Default::default() };
+
+        assert!(check_upload(&config, &file("text/plain", 10)).is_ok());
+        assert!(matches!(check_upload(&config, &file("text/plain", 11)), Err(ApiError::PayloadTooLarge(_))));
+        assert!(matches!(check_upload(&config, &file("image/gif", 1)), Err(ApiError::UnsupportedMediaType(_))));
+        assert!(matches!(
+            check_upload(&config, &file("application/octet-stream", 1)),
+            Err(ApiError::UnsupportedMediaType(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_names_are_cleaned() {
+        assert_eq!(clean_file_name("../../etc/passwd").unwrap(), "passwd");
+        assert_eq!(clean_file_name("C:\\docs\\report.csv").unwrap(), "report.csv");
+        assert!(clean_file_name("dir/").is_err());
+        assert!(clean_file_name(&"a".repeat(300)).is_err());
+    }
+}
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use crate::{error::ApiResult, state::AppState};
+use praxis::ProviderStats;
+
+use crate::{error::ApiResult, scheduler::SchedulerStats, state::AppState};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
@@ -44,6 +46,50 @@ pub async fn health_check(
     }))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LlmHealthResponse {
+    pub providers: Vec<ProviderStats>,
+}
+
+/// LLM provider health
+///
+/// Rolling stats over each configured provider's recent calls (success
+/// rate, p50/p95 latency, rate-limit headroom, last error), kept in memory
+/// since startup
+#[utoipa::path(
+    get,
+    path = "/health/llm",
+    responses(
+        (status = 200, description = "Recent provider stats", body = LlmHealthResponse)
+    ),
+    tag = "health"
+)]
+pub async fn llm_health(
+    State(state): State<Arc<AppState>>,
+) -> Json<LlmHealthResponse> {
+    Json(LlmHealthResponse {
+        providers: state.llm_providers.iter().map(|provider| provider.stats()).collect(),
+    })
+}
+
+/// Run queue health
+///
+/// Runs executing and queued per tenant, with each tenant's weight, cap and
+/// queue wait times
+#[utoipa::path(
+    get,
+    path = "/health/runs",
+    responses(
+        (status = 200, description = "Run queue per tenant", body = SchedulerStats)
+    ),
+    tag = "health"
+)]
+pub async fn run_queue_health(
+    State(state): State<Arc<AppState>>,
+) -> Json<SchedulerStats> {
+    Json(state.run_scheduler.stats())
+}
+
 async fn check_mongodb(state: &AppState) -> ApiResult<()> {
     // Try to list threads (lightweight operation)
     let _ = state.persist.list_threads("_health_check", Some(1), None).await?;
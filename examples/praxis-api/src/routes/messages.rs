@@ -1,12 +1,12 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use praxis::{DBMessage, MessageRole, MessageType};
-use crate::{error::{ApiError, ApiResult}, state::AppState};
+use praxis::{Citation, DBMessage, MessageRole, MessageType, ToolVerification};
+use crate::{error::{ApiError, ApiResult}, extract::ApiQuery, state::AppState};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MessageResponse {
@@ -16,6 +16,24 @@ pub struct MessageResponse {
     pub message_type: MessageType,
     pub content: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// SHA-256 of the text sent to the model, for spotting prompt drift
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_sha256: Option<String>,
+    /// Tokens per the model's tokenizer when the message was saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<u32>,
+    /// Reasoning rows: short summary for collapsed display
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Tool results: MCP server that ran the call (`_local` for a local tool)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_server: Option<String>,
+    /// Tool results: read call that checked a write tool's effect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ToolVerification>,
+    /// Answers: tool results cited by `[n]` markers in `content`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,7 +71,7 @@ pub struct ListMessagesResponse {
 pub async fn list_messages(
     State(state): State<Arc<AppState>>,
     Path(thread_id): Path<String>,
-    Query(query): Query<ListMessagesQuery>,
+    ApiQuery(query): ApiQuery<ListMessagesQuery>,
 ) -> ApiResult<Json<ListMessagesResponse>> {
     // Check if thread exists
     let thread = state
@@ -109,6 +127,12 @@ fn message_to_response(message: DBMessage) -> MessageResponse {
         message_type: message.message_type,
         content: message.content,
         created_at: message.created_at,
+        content_sha256: message.content_sha256,
+        token_count: message.token_count,
+        summary: message.summary,
+        tool_server: message.tool_server,
+        verification: message.verification,
+        citations: message.citations,
     }
 }
 
@@ -1,4 +1,5 @@
 pub mod health;
 pub mod threads;
 pub mod messages;
+pub mod attachments;
 
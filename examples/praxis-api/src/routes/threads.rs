@@ -1,19 +1,99 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use praxis::{ThreadMetadata, Thread, ThreadSummary};
-use crate::{error::{ApiError, ApiResult}, state::AppState};
+use praxis::{
+    anonymize_thread, check_message_invariants, DBMessage, LLMConfigOverride, MessageRole, MessageType, PersistError, ThreadMetadata,
+    SummaryTrigger, Thread, ThreadExport, ThreadSummary, ToolProfile,
+};
+use crate::{auth::{AuthContext, ADMIN_SCOPE}, error::{ApiError, ApiResult}, extract::{ApiJson, ApiQuery}, state::AppState};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateThreadRequest {
     pub user_id: String,
     #[serde(default)]
     pub metadata: ThreadMetadata,
+    /// Conversation to seed the thread with, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<SeedMessage>,
+}
+
+/// One message of a `CreateThreadRequest` seed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeedMessage {
+    pub role: MessageRole,
+    #[serde(default)]
+    pub content: String,
+    #[serde(rename = "type", alias = "message_type", default = "default_message_type")]
+    pub message_type: MessageType,
+    /// Defaults to 1ms after the previous message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Value>,
+}
+
+fn default_message_type() -> MessageType {
+    MessageType::Message
+}
+
+/// Convert a seed to stored messages and check it can be replayed
+///
+/// Missing timestamps continue 1ms after the previous message; a seed with
+/// no timestamps at all ends just before now.
+fn seed_messages(seed: Vec<SeedMessage>) -> Result<Vec<DBMessage>, PersistError> {
+    let step = chrono::Duration::milliseconds(1);
+    let mut previous = chrono::Utc::now() - step * seed.len() as i32;
+    let messages: Vec<DBMessage> = seed
+        .into_iter()
+        .map(|seed| {
+            let created_at = seed.created_at.unwrap_or(previous + step);
+            previous = created_at;
+            DBMessage {
+                role: seed.role,
+                message_type: seed.message_type,
+                content: seed.content,
+                tool_call_id: seed.tool_call_id,
+                tool_name: seed.tool_name,
+                arguments: seed.arguments,
+                created_at,
+                ..Default::default()
+            }
+        })
+        .collect();
+    check_message_invariants(&messages)?;
+    Ok(messages)
+}
+
+/// Partial thread update; omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateThreadRequest {
+    pub title: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// `null` clears the override. Requires the admin scope.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub model_override: Option<Option<LLMConfigOverride>>,
+    /// Instructions for the thread's runs, sent as a developer message;
+    /// `null` clears them
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub developer_prompt: Option<Option<String>>,
+}
+
+/// Distinguishes an explicit `null` (`Some(None)`) from an absent field (`None`)
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,6 +105,9 @@ pub struct ThreadResponse {
     pub metadata: ThreadMetadata,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<ThreadSummaryResponse>,
+    /// Set once a hygiene run archived the idle thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,6 +116,8 @@ pub struct ThreadSummaryResponse {
     pub generated_at: chrono::DateTime<chrono::Utc>,
     pub total_tokens_before_summary: usize,
     pub messages_count: usize,
+    /// `overflow`, `cadence` or `manual`
+    pub trigger: SummaryTrigger,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +131,24 @@ fn default_limit() -> i64 {
     20
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportThreadQuery {
+    /// Pseudonymize personal data per the `[export]` config
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+/// Outcome of moving a thread to the current MCP tools
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepinToolsResponse {
+    /// Version of the new profile
+    pub version: String,
+    /// Tools of the old profile the servers no longer provide
+    pub removed: Vec<String>,
+    /// Tools the thread can use now that it couldn't before
+    pub added: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ListThreadsResponse {
     pub threads: Vec<ThreadResponse>,
@@ -59,18 +162,30 @@ pub struct ListThreadsResponse {
     request_body = CreateThreadRequest,
     responses(
         (status = 201, description = "Thread created", body = ThreadResponse),
-        (status = 400, description = "Invalid request")
+        (status = 400, description = "Invalid request"),
+        (status = 422, description = "Seed messages out of order or with unpaired tool calls")
     ),
     tag = "threads"
 )]
 pub async fn create_thread(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<CreateThreadRequest>,
+    auth: AuthContext,
+    ApiJson(req): ApiJson<CreateThreadRequest>,
 ) -> ApiResult<(StatusCode, Json<ThreadResponse>)> {
-    let thread = state
-        .persist
-        .create_thread(&req.user_id, req.metadata)
-        .await?;
+    if req.metadata.model_override.is_some() {
+        auth.require_scope(ADMIN_SCOPE)?;
+        check_model_override(req.metadata.model_override.as_ref())?;
+    }
+    
+    let thread = if req.messages.is_empty() {
+        state.persist.create_thread(&req.user_id, req.metadata).await?
+    } else {
+        let messages = seed_messages(req.messages)?;
+        state
+            .persist
+            .create_thread_with_messages(&req.user_id, req.metadata, messages)
+            .await?
+    };
     
     Ok((StatusCode::CREATED, Json(thread_to_response(thread))))
 }
@@ -91,7 +206,7 @@ pub async fn create_thread(
 )]
 pub async fn list_threads(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ListThreadsQuery>,
+    ApiQuery(query): ApiQuery<ListThreadsQuery>,
 ) -> ApiResult<Json<ListThreadsResponse>> {
     let limit = query.limit.min(100); // Cap at 100
     
@@ -138,6 +253,185 @@ pub async fn get_thread(
     Ok(Json(thread_to_response(thread)))
 }
 
+/// Update thread metadata
+#[utoipa::path(
+    patch,
+    path = "/threads/{thread_id}",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    request_body = UpdateThreadRequest,
+    responses(
+        (status = 200, description = "Updated thread", body = ThreadResponse),
+        (status = 400, description = "model_override with an unknown reasoning_effort"),
+        (status = 403, description = "model_override set without the admin scope"),
+        (status = 404, description = "Thread not found")
+    ),
+    tag = "threads"
+)]
+pub async fn update_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    auth: AuthContext,
+    ApiJson(req): ApiJson<UpdateThreadRequest>,
+) -> ApiResult<Json<ThreadResponse>> {
+    if let Some(model_override) = &req.model_override {
+        auth.require_scope(ADMIN_SCOPE)?;
+        check_model_override(model_override.as_ref())?;
+    }
+    
+    let mut thread = state
+        .persist
+        .get_thread(&thread_id)
+        .await?
+        .ok_or_else(|| ApiError::ThreadNotFound(thread_id.clone()))?;
+    
+    if let Some(title) = req.title {
+        thread.metadata.title = Some(title);
+    }
+    if let Some(tags) = req.tags {
+        thread.metadata.tags = tags;
+    }
+    if let Some(model_override) = req.model_override {
+        thread.metadata.model_override = model_override;
+    }
+    if let Some(developer_prompt) = req.developer_prompt {
+        thread.metadata.developer_prompt = developer_prompt;
+    }
+    
+    state
+        .persist
+        .update_thread_metadata(&thread_id, thread.metadata.clone())
+        .await?;
+    
+    Ok(Json(thread_to_response(thread)))
+}
+
+/// Export a thread with all its messages, optionally anonymized
+#[utoipa::path(
+    get,
+    path = "/threads/{thread_id}/export",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID"),
+        ("anonymize" = Option<bool>, Query, description = "Pseudonymize personal data (default: false)")
+    ),
+    responses(
+        (status = 200, description = "Thread and messages, oldest first", body = ThreadExport),
+        (status = 404, description = "Thread not found")
+    ),
+    tag = "threads"
+)]
+pub async fn export_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    ApiQuery(query): ApiQuery<ExportThreadQuery>,
+) -> ApiResult<Json<ThreadExport>> {
+    let export = ThreadExport::load(state.persist.as_ref(), &thread_id)
+        .await?
+        .ok_or_else(|| ApiError::ThreadNotFound(thread_id))?;
+    
+    if query.anonymize {
+        return Ok(Json(anonymize_thread(export, &state.config.export.anonymizer())));
+    }
+    Ok(Json(export))
+}
+
+/// Pin a thread to the tools the MCP servers offer now
+///
+/// Replaces any earlier profile; returns the new one.
+pub(crate) async fn pin_tool_profile(state: &AppState, thread: &mut Thread) -> ApiResult<ToolProfile> {
+    let profile = ToolProfile::new(state.mcp_executor.mcp_tools().await?, chrono::Utc::now());
+    thread.metadata.tool_profile = Some(profile.clone());
+    state
+        .persist
+        .update_thread_metadata(&thread.id, thread.metadata.clone())
+        .await?;
+    Ok(profile)
+}
+
+/// Move a thread to the current MCP tool profile
+#[utoipa::path(
+    post,
+    path = "/threads/{thread_id}/tools:repin",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    responses(
+        (status = 200, description = "New profile version and the tools that changed", body = RepinToolsResponse),
+        (status = 404, description = "Thread not found")
+    ),
+    tag = "threads"
+)]
+pub async fn repin_tools(
+    State(state): State<Arc<AppState>>,
+    Path((thread_id, method)): Path<(String, String)>,
+) -> ApiResult<Json<RepinToolsResponse>> {
+    if method != ":repin" {
+        return Err(ApiError::RouteNotFound(format!("/threads/{}/tools{}", thread_id, method)));
+    }
+    
+    let mut thread = state
+        .persist
+        .get_thread(&thread_id)
+        .await?
+        .ok_or_else(|| ApiError::ThreadNotFound(thread_id.clone()))?;
+    
+    let previous = thread.metadata.tool_profile.take();
+    let profile = pin_tool_profile(&state, &mut thread).await?;
+    let (removed, added) = match previous {
+        Some(previous) => previous.diff(&profile.tools),
+        None => (Vec::new(), profile.tools.iter().map(|t| t.function.name.clone()).collect()),
+    };
+    tracing::info!(
+        "Repinned thread {} to tool profile {} (removed: {:?}, added: {:?})",
+        thread_id,
+        profile.version,
+        removed,
+        added
+    );
+    
+    Ok(Json(RepinToolsResponse { version: profile.version, removed, added }))
+}
+
+/// Summarize a thread's history now, whatever the summary cadence says
+///
+/// Waits for the summary. A thread with nothing said since its last summary
+/// is returned unchanged.
+#[utoipa::path(
+    post,
+    path = "/threads/{thread_id}/summarize",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    responses(
+        (status = 200, description = "Thread with its new summary", body = ThreadResponse),
+        (status = 403, description = "Caller lacks the admin scope"),
+        (status = 404, description = "Thread not found")
+    ),
+    tag = "threads"
+)]
+pub async fn summarize_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    auth: AuthContext,
+) -> ApiResult<Json<ThreadResponse>> {
+    auth.require_scope(ADMIN_SCOPE)?;
+    
+    if state.persist.get_thread(&thread_id).await?.is_none() {
+        return Err(ApiError::ThreadNotFound(thread_id));
+    }
+    if let Some(summary) = state.context_strategy.summarize_now(&thread_id, &state.persist).await? {
+        tracing::info!("Summarized thread {} on request ({} messages)", thread_id, summary.messages_count);
+    }
+    
+    let thread = state
+        .persist
+        .get_thread(&thread_id)
+        .await?
+        .ok_or_else(|| ApiError::ThreadNotFound(thread_id))?;
+    Ok(Json(thread_to_response(thread)))
+}
+
 /// Delete a thread
 #[utoipa::path(
     delete,
@@ -173,6 +467,16 @@ pub async fn delete_thread(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Reject overrides the router could not apply
+fn check_model_override(model_override: Option<&LLMConfigOverride>) -> ApiResult<()> {
+    if let Some(effort) = model_override.and_then(|o| o.reasoning_effort.as_deref()) {
+        effort
+            .parse::<praxis::ReasoningEffort>()
+            .map_err(|e| ApiError::BadRequest(format!("model_override.reasoning_effort: {}", e)))?;
+    }
+    Ok(())
+}
+
 fn thread_to_response(thread: Thread) -> ThreadResponse {
     ThreadResponse {
         thread_id: thread.id,
@@ -181,6 +485,7 @@ fn thread_to_response(thread: Thread) -> ThreadResponse {
         updated_at: thread.updated_at,
         metadata: thread.metadata,
         summary: thread.summary.map(summary_to_response),
+        archived_at: thread.archived_at,
     }
 }
 
@@ -190,6 +495,105 @@ fn summary_to_response(summary: ThreadSummary) -> ThreadSummaryResponse {
         generated_at: summary.generated_at,
         total_tokens_before_summary: summary.total_tokens_before_summary,
         messages_count: summary.messages_count,
+        trigger: summary.trigger,
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_request_distinguishes_null_from_absent() {
+        let absent: UpdateThreadRequest = serde_json::from_str(r#"{"title": "t"}"#).unwrap();
+        assert!(absent.model_override.is_none());
+        assert!(absent.developer_prompt.is_none());
+
+        let cleared: UpdateThreadRequest = serde_json::from_str(r#"{"model_override": null}"#).unwrap();
+        assert_eq!(cleared.model_override, Some(None));
+
+        let set: UpdateThreadRequest = serde_json::from_str(r#"{"model_override": {"model": "beta"}}"#).unwrap();
+        assert_eq!(set.model_override.unwrap().unwrap().model.as_deref(), Some("beta"));
+
+        let cleared: UpdateThreadRequest = serde_json::from_str(r#"{"developer_prompt": null}"#).unwrap();
+        assert_eq!(cleared.developer_prompt, Some(None));
+    }
+
+    #[test]
+    fn test_model_override_rejects_unknown_reasoning_effort() {
+        let with_effort = |effort: &str| LLMConfigOverride {
+            reasoning_effort: Some(effort.to_string()),
+            ..Default::default()
+        };
+
+        assert!(check_model_override(None).is_ok());
+        assert!(check_model_override(Some(&LLMConfigOverride::default())).is_ok());
+        assert!(check_model_override(Some(&with_effort("minimal"))).is_ok());
+
+        let error = check_model_override(Some(&with_effort("extreme"))).unwrap_err();
+        assert!(matches!(&error, ApiError::BadRequest(message) if message.contains("'extreme'")), "{}", error);
+    }
+
+    fn seed(json: serde_json::Value) -> Vec<SeedMessage> {
+        serde_json::from_value::<CreateThreadRequest>(serde_json::json!({ "user_id": "u1", "messages": json }))
+            .unwrap()
+            .messages
+    }
+
+    async fn to_problem(error: PersistError) -> (StatusCode, crate::error::Problem) {
+        let response = axum::response::IntoResponse::into_response(ApiError::from(error));
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn test_seed_fills_timestamps_in_order() {
+        let messages = seed_messages(seed(serde_json::json!([
+            { "role": "user", "content": "weather?" },
+            { "role": "assistant", "type": "tool_call", "tool_call_id": "c1", "tool_name": "weather", "arguments": {} },
+            { "role": "assistant", "type": "tool_result", "tool_call_id": "c1", "content": "sunny" },
+            { "role": "assistant", "content": "It is sunny." }
+        ])))
+        .unwrap();
+
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1].message_type, MessageType::ToolCall);
+        assert!(messages.windows(2).all(|pair| pair[0].created_at < pair[1].created_at));
+        assert!(messages[3].created_at <= chrono::Utc::now());
+    }
+
+    #[tokio::test]
+    async fn test_orphan_tool_result_is_422_with_index() {
+        let error = seed_messages(seed(serde_json::json!([
+            { "role": "user", "content": "hi" },
+            { "role": "assistant", "type": "tool_result", "tool_call_id": "c9", "content": "42" }
+        ])))
+        .unwrap_err();
+
+        let (status, problem) = to_problem(error).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem.code, "invalid_messages");
+        assert!(problem.detail.unwrap().contains("messages[1]"));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_tool_call_and_backwards_time_are_422() {
+        let unanswered = seed_messages(seed(serde_json::json!([
+            { "role": "assistant", "type": "tool_call", "tool_call_id": "c1", "tool_name": "weather" },
+            { "role": "assistant", "content": "done" }
+        ])))
+        .unwrap_err();
+        let (status, problem) = to_problem(unanswered).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(problem.detail.unwrap().contains("messages[0]"));
+
+        let backwards = seed_messages(seed(serde_json::json!([
+            { "role": "user", "content": "a", "created_at": "2025-01-08T12:00:00Z" },
+            { "role": "assistant", "content": "b", "created_at": "2025-01-08T11:00:00Z" }
+        ])))
+        .unwrap_err();
+        let (_, problem) = to_problem(backwards).await;
+        assert!(problem.detail.unwrap().contains("messages[1]"));
+    }
+}
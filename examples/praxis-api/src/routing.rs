@@ -0,0 +1,261 @@
+//! Server-side model routing
+//!
+//! Decides the `LLMConfig` of a run from what the client asked for plus any
+//! server-side overrides. The decision is echoed to the client in the `init`
+//! SSE event and recorded on the run, so a model other than the requested
+//! one can always be traced back to the layer that chose it.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use praxis::{LLMConfig, LLMConfigOverride, Provider, ReasoningEffort, Thread};
+use crate::{auth::AuthContext, handlers::stream::RequestLLMConfig};
+
+/// Where a routed setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingSource {
+    /// `ThreadMetadata::model_override`
+    Thread,
+    /// `[routing.tenants.<id>]` config
+    Tenant,
+}
+
+/// Routed LLM settings plus the overrides that produced them
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingDecision {
+    pub config: LLMConfig,
+    /// Model named in the client request
+    pub requested_model: String,
+    /// Settings not taken from the request, by field name
+    pub overrides: BTreeMap<&'static str, RoutingSource>,
+}
+
+/// Chooses the LLM settings of a run
+pub trait ModelRouter: Send + Sync {
+    fn route(&self, requested: &RequestLLMConfig, thread: &Thread, user: &AuthContext) -> RoutingDecision;
+}
+
+/// Per setting: thread override, then tenant config, then the request
+#[derive(Debug, Clone, Default)]
+pub struct DefaultModelRouter {
+    tenants: HashMap<String, LLMConfigOverride>,
+}
+
+impl DefaultModelRouter {
+    pub fn new(tenants: HashMap<String, LLMConfigOverride>) -> Self {
+        Self { tenants }
+    }
+}
+
+impl ModelRouter for DefaultModelRouter {
+    fn route(&self, requested: &RequestLLMConfig, thread: &Thread, user: &AuthContext) -> RoutingDecision {
+        let layers: Vec<(RoutingSource, &LLMConfigOverride)> = [
+            thread.metadata.model_override.as_ref().map(|o| (RoutingSource::Thread, o)),
+            user.tenant_id
+                .as_ref()
+                .and_then(|tenant| self.tenants.get(tenant))
+                .map(|o| (RoutingSource::Tenant, o)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mut overrides = BTreeMap::new();
+        let config = LLMConfig {
+            model: layered(&layers, &mut overrides, "model", |o| o.model.clone())
+                .unwrap_or_else(|| requested.model.clone()),
+            provider: Provider::OpenAI,
+            temperature: Some(
+                layered(&layers, &mut overrides, "temperature", |o| o.temperature)
+                    .unwrap_or(requested.temperature),
+            ),
+            max_tokens: Some(
+                layered(&layers, &mut overrides, "max_tokens", |o| o.max_tokens)
+                    .unwrap_or(requested.max_tokens),
+            ),
+            // Overrides store the effort as text; an unknown level is skipped
+            // so the next layer, or the request, decides
+            reasoning_effort: layered(&layers, &mut overrides, "reasoning_effort", |o| {
+                o.reasoning_effort.as_deref().and_then(parse_effort)
+            })
+            .or(requested.reasoning_effort),
+            reasoning_summary: requested.reasoning_summary,
+            tool_choice: requested.tool_choice.clone(),
+            top_p: requested.top_p,
+            seed: requested.seed,
+            stop: requested.stop.clone(),
+            audio: requested.audio.clone(),
+            extra_body: requested.extra_body.clone(),
+        };
+
+        RoutingDecision {
+            config,
+            requested_model: requested.model.clone(),
+            overrides,
+        }
+    }
+}
+
+/// Stored effort override, or `None` with a warning when it is not a known level
+fn parse_effort(effort: &str) -> Option<ReasoningEffort> {
+    match effort.parse() {
+        Ok(effort) => Some(effort),
+        Err(e) => {
+            tracing::warn!("Ignoring reasoning_effort override: {}", e);
+            None
+        }
+    }
+}
+
+/// First layer that sets `field`, recording it in `overrides`
+fn layered<T>(
+    layers: &[(RoutingSource, &LLMConfigOverride)],
+    overrides: &mut BTreeMap<&'static str, RoutingSource>,
+    field: &'static str,
+    get: impl Fn(&LLMConfigOverride) -> Option<T>,
+) -> Option<T> {
+    let (source, value) = layers
+        .iter()
+        .find_map(|(source, layer)| get(layer).map(|value| (*source, value)))?;
+    overrides.insert(field, source);
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use praxis::ThreadMetadata;
+
+    fn request() -> RequestLLMConfig {
+        RequestLLMConfig {
+            model: "gpt-4o-mini".to_string(),
+            reasoning_effort: None,
+            reasoning_summary: None,
+            temperature: 0.7,
+            max_tokens: 8000,
+            tool_choice: None,
+            top_p: None,
+            seed: Some(7),
+            stop: None,
+            audio: None,
+            extra_body: None,
+        }
+    }
+
+    fn thread(model_override: Option<LLMConfigOverride>) -> Thread {
+        let now = Utc::now();
+        Thread {
+            id: "t1".to_string(),
+            user_id: "u1".to_string(),
+            created_at: now,
+            updated_at: now,
+            metadata: ThreadMetadata { model_override, ..Default::default() },
+            last_summary_update: now,
+            summary: None,
+            history_index: None,
+            archived_at: None,
+            conversation_key: None,
+        }
+    }
+
+    fn tenant_user() -> AuthContext {
+        AuthContext { tenant_id: Some("acme".to_string()), scopes: vec![] }
+    }
+
+    fn router() -> DefaultModelRouter {
+        DefaultModelRouter::new(HashMap::from([(
+            "acme".to_string(),
+            LLMConfigOverride {
+                model: Some("tenant-model".to_string()),
+                max_tokens: Some(2000),
+                ..Default::default()
+            },
+        )]))
+    }
+
+    #[test]
+    fn test_request_used_without_overrides() {
+        let decision = router().route(&request(), &thread(None), &AuthContext::default());
+
+        assert_eq!(decision.config.model, "gpt-4o-mini");
+        assert_eq!(decision.config.max_tokens, Some(8000));
+        assert_eq!(decision.config.seed, Some(7));
+        assert!(decision.overrides.is_empty());
+    }
+
+    #[test]
+    fn test_tenant_overrides_request() {
+        let decision = router().route(&request(), &thread(None), &tenant_user());
+
+        assert_eq!(decision.config.model, "tenant-model");
+        assert_eq!(decision.config.max_tokens, Some(2000));
+        assert_eq!(decision.config.temperature, Some(0.7));
+        assert_eq!(decision.requested_model, "gpt-4o-mini");
+        assert_eq!(decision.overrides["model"], RoutingSource::Tenant);
+        assert!(!decision.overrides.contains_key("temperature"));
+    }
+
+    #[test]
+    fn test_thread_overrides_tenant_per_field() {
+        let pinned = LLMConfigOverride {
+            model: Some("beta-model".to_string()),
+            reasoning_effort: Some("minimal".to_string()),
+            ..Default::default()
+        };
+        let decision = router().route(&request(), &thread(Some(pinned)), &tenant_user());
+
+        assert_eq!(decision.config.model, "beta-model");
+        assert_eq!(decision.config.reasoning_effort, Some(ReasoningEffort::Minimal));
+        // Not set on the thread, so the tenant value still applies
+        assert_eq!(decision.config.max_tokens, Some(2000));
+
+        let sources: Vec<_> = decision.overrides.into_iter().collect();
+        assert_eq!(
+            sources,
+            [
+                ("max_tokens", RoutingSource::Tenant),
+                ("model", RoutingSource::Thread),
+                ("reasoning_effort", RoutingSource::Thread),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_reasoning_effort_override_is_skipped() {
+        let stale = LLMConfigOverride {
+            reasoning_effort: Some("extreme".to_string()),
+            ..Default::default()
+        };
+        let mut requested = request();
+        requested.reasoning_effort = Some(ReasoningEffort::High);
+        let decision = router().route(&requested, &thread(Some(stale)), &AuthContext::default());
+
+        assert_eq!(decision.config.reasoning_effort, Some(ReasoningEffort::High));
+        assert!(!decision.overrides.contains_key("reasoning_effort"));
+    }
+
+    #[test]
+    fn test_unknown_tenant_falls_through_to_request() {
+        let user = AuthContext { tenant_id: Some("other".to_string()), scopes: vec![] };
+        let decision = router().route(&request(), &thread(None), &user);
+
+        assert_eq!(decision.config.model, "gpt-4o-mini");
+        assert!(decision.overrides.is_empty());
+    }
+
+    #[test]
+    fn test_request_reasoning_settings_pass_through() {
+        let requested: RequestLLMConfig = serde_json::from_value(serde_json::json!({
+            "model": "gpt-5",
+            "reasoning_effort": "minimal",
+            "reasoning_summary": "detailed",
+        }))
+        .unwrap();
+        let decision = router().route(&requested, &thread(None), &AuthContext::default());
+
+        assert_eq!(decision.config.reasoning_effort, Some(ReasoningEffort::Minimal));
+        assert_eq!(decision.config.reasoning_summary, Some(praxis::SummaryMode::Detailed));
+    }
+}
@@ -0,0 +1,501 @@
+//! Run admission with per-tenant weighted fair queuing
+//!
+//! `RunScheduler` caps the runs executing at once. Past the cap a new run
+//! waits in its tenant's queue, and freed slots go to tenants in deficit
+//! round-robin order instead of arrival order: on its turn a tenant is
+//! credited its weight and starts one queued run per credit. Under
+//! contention tenants get slots in proportion to their weights, however
+//! many runs each one has queued, so one tenant's burst delays the others
+//! by at most a turn. A tenant can also be held to its own concurrency cap.
+//!
+//! Queue positions are counted within the tenant's own queue: a run's
+//! position is the number of that tenant's runs ahead of it, plus one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, watch};
+
+use crate::config::SchedulerConfig;
+
+/// Queue of runs without a tenant
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Slot of a started run; dropping it frees the slot for the next queued run
+pub struct RunPermit {
+    scheduler: Arc<RunScheduler>,
+    tenant: String,
+    /// Time spent queued before the run started
+    pub waited: Duration,
+}
+
+impl Drop for RunPermit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.tenant);
+    }
+}
+
+/// Outcome of `RunScheduler::enqueue`
+pub enum Admission {
+    /// A slot was free; the run starts right away
+    Started(RunPermit),
+    Queued(QueuedRun),
+}
+
+/// Change seen by a queued run
+pub enum QueueUpdate {
+    /// New position in the tenant's queue
+    Position(usize),
+    Started(RunPermit),
+}
+
+/// Run waiting for a slot; dropping it leaves the queue
+pub struct QueuedRun {
+    scheduler: Arc<RunScheduler>,
+    tenant: String,
+    id: u64,
+    position: watch::Receiver<usize>,
+    admit: oneshot::Receiver<RunPermit>,
+}
+
+impl QueuedRun {
+    /// Current position in the tenant's queue (1 = next of its runs to start)
+    pub fn position(&self) -> usize {
+        *self.position.borrow()
+    }
+
+    /// Wait until the run moves up in the queue or starts
+    pub async fn next(&mut self) -> QueueUpdate {
+        tokio::select! {
+            biased;
+            permit = &mut self.admit => {
+                QueueUpdate::Started(permit.expect("queued runs are only removed to start or by their QueuedRun"))
+            }
+            Ok(()) = self.position.changed() => QueueUpdate::Position(*self.position.borrow_and_update()),
+        }
+    }
+}
+
+impl Drop for QueuedRun {
+    fn drop(&mut self) {
+        // A permit already sent is dropped with `admit`, after the lock is released
+        self.scheduler.cancel(&self.tenant, self.id);
+    }
+}
+
+/// Queue and wait times of one tenant, served by `/health/runs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenantQueueStats {
+    pub tenant: String,
+    pub weight: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    pub running: usize,
+    /// Queue depth
+    pub queued: usize,
+    /// Runs started since the server started
+    pub started: u64,
+    /// Mean time started runs spent queued (None before the first run)
+    pub avg_wait_ms: Option<u64>,
+    /// How long the run at the head of the queue has been waiting
+    pub oldest_wait_ms: Option<u64>,
+}
+
+/// Scheduler snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerStats {
+    /// 0 = no limit
+    pub max_concurrent_runs: usize,
+    pub running: usize,
+    pub queued: usize,
+    pub tenants: Vec<TenantQueueStats>,
+}
+
+struct Waiter {
+    id: u64,
+    enqueued_at: Instant,
+    position: watch::Sender<usize>,
+    admit: oneshot::Sender<RunPermit>,
+}
+
+struct TenantQueue {
+    weight: u32,
+    max_concurrent: Option<usize>,
+    running: usize,
+    /// Runs the tenant may still start this turn
+    deficit: u32,
+    waiting: VecDeque<Waiter>,
+    started: u64,
+    total_wait: Duration,
+}
+
+impl TenantQueue {
+    fn has_room(&self) -> bool {
+        self.max_concurrent.is_none_or(|max| self.running < max)
+    }
+
+    fn renumber(&self) {
+        for (index, waiter) in self.waiting.iter().enumerate() {
+            waiter.position.send_if_modified(|position| {
+                let changed = *position != index + 1;
+                *position = index + 1;
+                changed
+            });
+        }
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    running: usize,
+    tenants: HashMap<String, TenantQueue>,
+    /// Tenants with queued runs in round-robin order; the front one has the turn
+    active: VecDeque<String>,
+    /// The front tenant hasn't been credited for its turn yet
+    new_turn: bool,
+    next_id: u64,
+}
+
+impl QueueState {
+    /// Tenant whose run starts next, charged one credit
+    fn next_tenant(&mut self) -> Option<String> {
+        // Every tenant with room is credited at least 1 on its turn, so a
+        // full rotation without a pick means all of them are at their cap
+        for _ in 0..=self.active.len() {
+            let tenant_id = self.active.front()?;
+            let tenant = self.tenants.get_mut(tenant_id).expect("active tenants have a queue");
+            if std::mem::take(&mut self.new_turn) {
+                // Credit isn't banked while the tenant can't use it
+                tenant.deficit = if tenant.has_room() { tenant.deficit + tenant.weight } else { 0 };
+            }
+            if tenant.deficit > 0 && tenant.has_room() {
+                tenant.deficit -= 1;
+                return Some(tenant_id.clone());
+            }
+            self.active.rotate_left(1);
+            self.new_turn = true;
+        }
+        None
+    }
+
+    /// Take queued runs off their queues while slots are free
+    fn dispatch(&mut self, max_running: usize) -> Vec<(String, Waiter)> {
+        let mut started = Vec::new();
+        while max_running == 0 || self.running < max_running {
+            let Some(tenant_id) = self.next_tenant() else { break };
+            let tenant = self.tenants.get_mut(&tenant_id).expect("active tenants have a queue");
+            let waiter = tenant.waiting.pop_front().expect("active tenants have queued runs");
+            tenant.running += 1;
+            tenant.started += 1;
+            tenant.total_wait += waiter.enqueued_at.elapsed();
+            tenant.renumber();
+            if tenant.waiting.is_empty() {
+                // `next_tenant` only picks the front tenant
+                tenant.deficit = 0;
+                self.active.pop_front();
+                self.new_turn = true;
+            }
+            self.running += 1;
+            started.push((tenant_id, waiter));
+        }
+        started
+    }
+}
+
+/// Admits runs under a global limit, sharing slots across tenants by weight
+pub struct RunScheduler {
+    config: SchedulerConfig,
+    state: Mutex<QueueState>,
+}
+
+impl RunScheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(QueueState { new_turn: true, ..Default::default() }),
+        }
+    }
+
+    /// Queue a run of `tenant`, starting it at once if a slot is free
+    pub fn enqueue(self: &Arc<Self>, tenant: &str) -> Admission {
+        let (admit_tx, mut admit_rx) = oneshot::channel();
+        let (id, position_rx, started) = {
+            let mut state = self.lock();
+            let id = state.next_id;
+            state.next_id += 1;
+
+            let (weight, max_concurrent) = self.config.share(tenant);
+            let queue = state.tenants.entry(tenant.to_string()).or_insert_with(|| TenantQueue {
+                weight,
+                max_concurrent,
+                running: 0,
+                deficit: 0,
+                waiting: VecDeque::new(),
+                started: 0,
+                total_wait: Duration::ZERO,
+            });
+            let (position_tx, position_rx) = watch::channel(queue.waiting.len() + 1);
+            queue.waiting.push_back(Waiter {
+                id,
+                enqueued_at: Instant::now(),
+                position: position_tx,
+                admit: admit_tx,
+            });
+            if queue.waiting.len() == 1 {
+                state.active.push_back(tenant.to_string());
+                if state.active.len() == 1 {
+                    state.new_turn = true;
+                }
+            }
+            (id, position_rx, state.dispatch(self.config.max_concurrent_runs))
+        };
+        self.admit(started);
+
+        match admit_rx.try_recv() {
+            Ok(permit) => Admission::Started(permit),
+            Err(_) => Admission::Queued(QueuedRun {
+                scheduler: Arc::clone(self),
+                tenant: tenant.to_string(),
+                id,
+                position: position_rx,
+                admit: admit_rx,
+            }),
+        }
+    }
+
+    /// Per-tenant queue depth and wait times, tenants sorted by id
+    pub fn stats(&self) -> SchedulerStats {
+        let state = self.lock();
+        let mut tenants: Vec<TenantQueueStats> = state
+            .tenants
+            .iter()
+            .map(|(tenant, queue)| TenantQueueStats {
+                tenant: tenant.clone(),
+                weight: queue.weight,
+                max_concurrent: queue.max_concurrent,
+                running: queue.running,
+                queued: queue.waiting.len(),
+                started: queue.started,
+                avg_wait_ms: (queue.started > 0).then(|| (queue.total_wait.as_millis() / queue.started as u128) as u64),
+                oldest_wait_ms: queue.waiting.front().map(|waiter| waiter.enqueued_at.elapsed().as_millis() as u64),
+            })
+            .collect();
+        tenants.sort_by(|a, b| a.tenant.cmp(&b.tenant));
+        SchedulerStats {
+            max_concurrent_runs: self.config.max_concurrent_runs,
+            running: state.running,
+            queued: tenants.iter().map(|tenant| tenant.queued).sum(),
+            tenants,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Hand permits to the runs taken off the queue, outside the lock: a
+    /// run that left in the meantime drops its permit, which releases again
+    fn admit(self: &Arc<Self>, started: Vec<(String, Waiter)>) {
+        for (tenant, waiter) in started {
+            let permit = RunPermit {
+                scheduler: Arc::clone(self),
+                tenant,
+                waited: waiter.enqueued_at.elapsed(),
+            };
+            let _ = waiter.admit.send(permit);
+        }
+    }
+
+    fn release(self: &Arc<Self>, tenant: &str) {
+        let started = {
+            let mut state = self.lock();
+            state.running -= 1;
+            if let Some(queue) = state.tenants.get_mut(tenant) {
+                queue.running -= 1;
+            }
+            state.dispatch(self.config.max_concurrent_runs)
+        };
+        self.admit(started);
+    }
+
+    fn cancel(&self, tenant: &str, id: u64) {
+        let mut state = self.lock();
+        let Some(queue) = state.tenants.get_mut(tenant) else { return };
+        let Some(index) = queue.waiting.iter().position(|waiter| waiter.id == id) else { return };
+        queue.waiting.remove(index);
+        queue.renumber();
+        if queue.waiting.is_empty() {
+            queue.deficit = 0;
+            if state.active.front().is_some_and(|front| front == tenant) {
+                state.new_turn = true;
+            }
+            state.active.retain(|active| active != tenant);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TenantShare;
+
+    fn new_scheduler(max_concurrent_runs: usize, shares: &[(&str, u32, Option<usize>)]) -> Arc<RunScheduler> {
+        let tenants = shares
+            .iter()
+            .map(|(tenant, weight, max_concurrent)| {
+                let share = TenantShare { weight: Some(*weight), max_concurrent: *max_concurrent };
+                (tenant.to_string(), share)
+            })
+            .collect();
+        Arc::new(RunScheduler::new(SchedulerConfig { max_concurrent_runs, tenants, ..Default::default() }))
+    }
+
+    fn started(admission: Admission) -> RunPermit {
+        match admission {
+            Admission::Started(permit) => permit,
+            Admission::Queued(_) => panic!("expected the run to start"),
+        }
+    }
+
+    fn queued(admission: Admission) -> QueuedRun {
+        match admission {
+            Admission::Queued(run) => run,
+            Admission::Started(_) => panic!("expected the run to queue"),
+        }
+    }
+
+    /// Finish the running run and return the tenant and permit of the queued
+    /// run that took its slot
+    fn finish(permit: RunPermit, queue: &mut Vec<(&'static str, QueuedRun)>) -> (&'static str, RunPermit) {
+        drop(permit);
+        let index = queue
+            .iter_mut()
+            .position(|(_, run)| !run.admit.is_empty())
+            .expect("a queued run takes the freed slot");
+        let (tenant, mut run) = queue.remove(index);
+        (tenant, run.admit.try_recv().unwrap())
+    }
+
+    #[test]
+    fn test_runs_start_until_the_limit_then_queue() {
+        let scheduler = new_scheduler(2, &[]);
+        let _first = started(scheduler.enqueue("acme"));
+        let _second = started(scheduler.enqueue("beta"));
+        let third = queued(scheduler.enqueue("acme"));
+        assert_eq!(third.position(), 1);
+
+        let stats = scheduler.stats();
+        assert_eq!((stats.running, stats.queued), (2, 1));
+
+        // No limit: nothing ever queues
+        let unlimited = new_scheduler(0, &[]);
+        let permits: Vec<_> = (0..100).map(|_| started(unlimited.enqueue("acme"))).collect();
+        assert_eq!(unlimited.stats().running, permits.len());
+    }
+
+    #[test]
+    fn test_slots_are_shared_by_weight() {
+        let scheduler = new_scheduler(1, &[("acme", 3, None), ("beta", 1, None)]);
+        let mut permit = started(scheduler.enqueue("acme"));
+        // acme's burst is queued before any of beta's runs
+        let mut queue: Vec<(&str, QueuedRun)> = (0..12).map(|_| ("acme", queued(scheduler.enqueue("acme")))).collect();
+        queue.extend((0..12).map(|_| ("beta", queued(scheduler.enqueue("beta")))));
+
+        let mut order = Vec::new();
+        for _ in 0..8 {
+            let (tenant, next) = finish(permit, &mut queue);
+            order.push(tenant);
+            permit = next;
+        }
+        assert_eq!(order, ["acme", "acme", "acme", "beta", "acme", "acme", "acme", "beta"]);
+    }
+
+    #[test]
+    fn test_burst_does_not_starve_another_tenant() {
+        let scheduler = new_scheduler(2, &[]);
+        let mut permits = vec![started(scheduler.enqueue("acme")), started(scheduler.enqueue("acme"))];
+        let mut queue: Vec<(&str, QueuedRun)> = (0..50).map(|_| ("acme", queued(scheduler.enqueue("acme")))).collect();
+        queue.push(("beta", queued(scheduler.enqueue("beta"))));
+
+        // Positions are per tenant: beta's run is first in its own queue
+        assert_eq!(queue[49].1.position(), 50);
+        assert_eq!(queue[50].1.position(), 1);
+
+        // With equal weights beta gets one of the next two slots, where
+        // FIFO would make it wait for all 50 of acme's runs
+        let mut starts = Vec::new();
+        for _ in 0..2 {
+            let (tenant, permit) = finish(permits.remove(0), &mut queue);
+            starts.push(tenant);
+            permits.push(permit);
+        }
+        assert!(starts.contains(&"beta"), "{:?}", starts);
+        assert_eq!(queue[0].1.position(), 1);
+    }
+
+    #[test]
+    fn test_tenant_cap_holds_its_runs_back() {
+        let scheduler = new_scheduler(4, &[("acme", 1, Some(1))]);
+        let acme = started(scheduler.enqueue("acme"));
+        let mut waiting = queued(scheduler.enqueue("acme"));
+        // Free slots still go to other tenants
+        let _beta = started(scheduler.enqueue("beta"));
+
+        drop(acme);
+        assert!(waiting.admit.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_leaving_the_queue_moves_later_runs_up() {
+        let scheduler = new_scheduler(1, &[]);
+        let permit = started(scheduler.enqueue("acme"));
+        let first = queued(scheduler.enqueue("acme"));
+        let mut second = queued(scheduler.enqueue("acme"));
+        let third = queued(scheduler.enqueue("acme"));
+        assert_eq!((second.position(), third.position()), (2, 3));
+
+        drop(first);
+        assert_eq!((second.position(), third.position()), (1, 2));
+        assert_eq!(scheduler.stats().tenants[0].queued, 2);
+
+        drop(permit);
+        assert!(second.admit.try_recv().is_ok());
+        assert_eq!(third.position(), 1);
+    }
+
+    #[test]
+    fn test_stats_report_depth_and_wait_per_tenant() {
+        let scheduler = new_scheduler(1, &[("acme", 2, Some(4))]);
+        let permit = started(scheduler.enqueue("acme"));
+        let mut beta = queued(scheduler.enqueue("beta"));
+        std::thread::sleep(Duration::from_millis(20));
+
+        let stats = scheduler.stats();
+        let [acme_stats, beta_stats] = &stats.tenants[..] else { panic!("expected two tenants") };
+        assert_eq!((acme_stats.weight, acme_stats.max_concurrent, acme_stats.running), (2, Some(4), 1));
+        assert_eq!((acme_stats.started, acme_stats.avg_wait_ms), (1, Some(0)));
+        assert_eq!((beta_stats.weight, beta_stats.queued, beta_stats.avg_wait_ms), (1, 1, None));
+        assert!(beta_stats.oldest_wait_ms.unwrap() >= 20);
+
+        drop(permit);
+        let beta_permit = beta.admit.try_recv().unwrap();
+        assert!(beta_permit.waited >= Duration::from_millis(20));
+        let beta_stats = &scheduler.stats().tenants[1];
+        assert_eq!((beta_stats.queued, beta_stats.running, beta_stats.oldest_wait_ms), (0, 1, None));
+        assert!(beta_stats.avg_wait_ms.unwrap() >= 20);
+    }
+
+    #[tokio::test]
+    async fn test_queued_run_sees_position_changes_then_starts() {
+        let scheduler = new_scheduler(1, &[]);
+        let permit = started(scheduler.enqueue("acme"));
+        let ahead = queued(scheduler.enqueue("acme"));
+        let mut run = queued(scheduler.enqueue("acme"));
+
+        drop(ahead);
+        assert!(matches!(run.next().await, QueueUpdate::Position(1)));
+        drop(permit);
+        assert!(matches!(run.next().await, QueueUpdate::Started(_)));
+    }
+}
@@ -1,6 +1,9 @@
 use std::sync::Arc;
-use praxis::{LLMClient, MCPToolExecutor, PersistenceClient, ContextStrategy, Graph};
+use praxis::{LLMClient, MCPToolExecutor, OutboxStore, PersistenceClient, ContextStrategy, ContentTokenizer, Graph, StatsRecordingClient, TiktokenTokenizer};
 use crate::config::Config;
+use crate::replay::StreamBuffers;
+use crate::routing::{DefaultModelRouter, ModelRouter};
+use crate::scheduler::RunScheduler;
 
 /// Shared application state passed to all handlers
 /// 
@@ -14,6 +17,17 @@ pub struct AppState {
     pub llm_client: Arc<dyn LLMClient>,
     pub mcp_executor: Arc<MCPToolExecutor>,
     pub graph: Arc<Graph>,
+    pub model_router: Arc<dyn ModelRouter>,
+    /// Token counts recorded on messages the API saves itself
+    pub content_tokenizer: Arc<dyn ContentTokenizer>,
+    /// Where run side effects are queued (unset = no webhooks)
+    pub outbox: Option<Arc<dyn OutboxStore>>,
+    /// Configured providers, reported by `/health/llm`
+    pub llm_providers: Vec<Arc<StatsRecordingClient>>,
+    /// Admits runs under `[scheduler]` limits
+    pub run_scheduler: Arc<RunScheduler>,
+    /// Events of recent message streams, for `Last-Event-ID` resumes
+    pub stream_buffers: Arc<StreamBuffers>,
 }
 
 impl AppState {
@@ -25,6 +39,11 @@ impl AppState {
         mcp_executor: Arc<MCPToolExecutor>,
         graph: Graph,
     ) -> Self {
+        let model_router = Arc::new(DefaultModelRouter::new(config.routing.tenants.clone()));
+        let run_scheduler = Arc::new(RunScheduler::new(config.scheduler.clone()));
+        let stream_buffers = Arc::new(StreamBuffers::new(std::time::Duration::from_secs(
+            config.stream.replay_retention_secs,
+        )));
         Self {
             config: Arc::new(config),
             persist,
@@ -32,7 +51,31 @@ impl AppState {
             llm_client,
             mcp_executor,
             graph: Arc::new(graph),
+            model_router,
+            content_tokenizer: Arc::new(TiktokenTokenizer),
+            outbox: None,
+            llm_providers: Vec::new(),
+            run_scheduler,
+            stream_buffers,
         }
     }
+    
+    /// Replace the default thread → tenant → request routing
+    pub fn with_model_router(mut self, router: Arc<dyn ModelRouter>) -> Self {
+        self.model_router = router;
+        self
+    }
+    
+    /// Report `provider`'s recent calls on `/health/llm`
+    pub fn with_llm_provider(mut self, provider: Arc<StatsRecordingClient>) -> Self {
+        self.llm_providers.push(provider);
+        self
+    }
+    
+    /// Queue a `run.completed` webhook in `outbox` after every run
+    pub fn with_outbox(mut self, outbox: Arc<dyn OutboxStore>) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
 }
 
@@ -0,0 +1,86 @@
+//! `run.completed` webhooks
+//!
+//! Runs don't call the webhook directly: the handler stores an outbox entry
+//! once the run's messages are written, and the `OutboxDispatcher` started
+//! by `main` POSTs it. A crash after the run therefore delays the webhook
+//! instead of dropping it.
+
+use async_trait::async_trait;
+use praxis::{OutboxEntry, OutboxHandler, OutboxStore, PersistError};
+
+/// Outbox kind of webhook entries
+pub const WEBHOOK_KIND: &str = "webhook";
+
+/// Delivers webhook entries to one URL
+pub struct WebhookHandler {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookHandler {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl OutboxHandler for WebhookHandler {
+    /// POSTs the payload; the entry id goes in `Idempotency-Key` since a
+    /// delivery can be repeated after a lease expires
+    async fn deliver(&self, entry: &OutboxEntry) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header("Idempotency-Key", &entry.id)
+            .json(&entry.payload)
+            .send()
+            .await?;
+        anyhow::ensure!(response.status().is_success(), "webhook returned {}", response.status());
+        Ok(())
+    }
+}
+
+/// Webhook entry announcing the end of a run
+pub fn run_completed(thread_id: &str, user_id: &str, status: &str, total_duration_ms: u64) -> OutboxEntry {
+    OutboxEntry::new(
+        WEBHOOK_KIND,
+        serde_json::json!({
+            "event": "run.completed",
+            "thread_id": thread_id,
+            "user_id": user_id,
+            "status": status,
+            "total_duration_ms": total_duration_ms,
+        }),
+    )
+}
+
+/// Store `entry` once the thread's queued message writes have landed, so
+/// the webhook never announces messages that aren't readable yet
+pub async fn enqueue_after_flush(outbox: &dyn OutboxStore, thread_id: &str, entry: OutboxEntry) -> Result<(), PersistError> {
+    outbox.flush(thread_id).await?;
+    outbox.save_messages_with_outbox(Vec::new(), vec![entry]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use praxis::{InMemoryPersistenceClient, OutboxStatus};
+
+    #[tokio::test]
+    async fn test_run_completed_is_stored_pending() {
+        let outbox = InMemoryPersistenceClient::new();
+        let entry = run_completed("t-1", "u-1", "success", 1200);
+        let id = entry.id.clone();
+
+        enqueue_after_flush(&outbox, "t-1", entry).await.unwrap();
+
+        let stored = outbox.get_outbox_entry(&id).await.unwrap().unwrap();
+        assert_eq!(stored.kind, WEBHOOK_KIND);
+        assert_eq!(stored.status, OutboxStatus::Pending);
+        assert_eq!(stored.payload["event"], "run.completed");
+        assert_eq!(stored.payload["thread_id"], "t-1");
+    }
+}
@@ -0,0 +1,280 @@
+//! `praxis-client` against the API's handlers, served in-process
+//!
+//! Every event the server sends must parse as a known `StreamEvent`, so a
+//! change to the wire format fails here until the client follows it.
+
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::extract::Request;
+use axum::handler::Handler;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::Router;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use tower_http::compression::CompressionLayer;
+
+use praxis::{
+    ChatClient, ChatRequest, ChatResponse, DefaultContextStrategy, Graph, InMemoryPersistenceClient, LLMClient,
+    LLMStreamEvent, MCPToolExecutor, ReasoningClient, ResponseOutput, ResponseRequest,
+};
+use praxis_api::{
+    config::Config,
+    handlers::{hydrate, stream},
+    routes::{messages, threads},
+    state::AppState,
+};
+use praxis_client::{ApiError, CreateThreadRequest, PraxisClient, SendMessageRequest, StreamEvent};
+
+/// Answers every stream with "Hello there" and its token usage
+struct GreetingLLM;
+
+#[async_trait]
+impl ChatClient for GreetingLLM {
+    async fn chat(&self, _request: ChatRequest) -> anyhow::Result<ChatResponse> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn chat_stream(
+        &self,
+        _request: ChatRequest,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = anyhow::Result<LLMStreamEvent>> + Send>>> {
+        Ok(Box::pin(futures::stream::iter(vec![
+            Ok(LLMStreamEvent::Message { content: "Hello".to_string(), choice_index: None, logprobs: None }),
+            Ok(LLMStreamEvent::Message { content: " there".to_string(), choice_index: None, logprobs: None }),
+            Ok(LLMStreamEvent::Usage { input_tokens: 12, output_tokens: 2, total_tokens: 14, reasoning_tokens: None, cached_tokens: None }),
+            Ok(LLMStreamEvent::Done { finish_reason: Some("stop".to_string()), choice_index: None }),
+        ])))
+    }
+}
+
+#[async_trait]
+impl ReasoningClient for GreetingLLM {
+    async fn reason(&self, _request: ResponseRequest) -> anyhow::Result<ResponseOutput> {
+        anyhow::bail!("not scripted")
+    }
+
+    async fn reason_stream(
+        &self,
+        _request: ResponseRequest,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = anyhow::Result<LLMStreamEvent>> + Send>>> {
+        anyhow::bail!("not scripted")
+    }
+}
+
+impl LLMClient for GreetingLLM {}
+
+/// Serve the thread and message routes on a local port, behind the
+/// compression layer the API uses
+async fn serve() -> PraxisClient {
+    PraxisClient::new(format!("http://{}/", serve_at().await), "")
+}
+
+/// Address of a fresh in-process API
+async fn serve_at() -> std::net::SocketAddr {
+    serve_with(false).await
+}
+
+/// Cut the connection of a new message stream after its first two events
+async fn drop_after_two_events(request: Request, next: Next) -> Response {
+    let (parts, body) = next.run(request).await.into_parts();
+    Response::from_parts(parts, Body::from_stream(body.into_data_stream().take(2)))
+}
+
+/// Address of a fresh in-process API; with `drop_streams`, every message
+/// stream loses its connection early and has to be resumed
+async fn serve_with(drop_streams: bool) -> std::net::SocketAddr {
+    let llm = Arc::new(GreetingLLM);
+    let persist = Arc::new(InMemoryPersistenceClient::new());
+    let mcp_executor = Arc::new(MCPToolExecutor::new());
+    let graph = Graph::builder()
+        .llm_client(llm.clone())
+        .mcp_executor(mcp_executor.clone())
+        .with_persistence(persist.clone())
+        .build()
+        .unwrap();
+    let config: Config = toml::from_str(include_str!("../config/default.toml")).unwrap();
+    let state = Arc::new(AppState::new(
+        config,
+        persist,
+        Arc::new(DefaultContextStrategy::new(100_000, llm.clone())),
+        llm,
+        mcp_executor,
+        graph,
+    ));
+
+    let router = Router::new()
+        .route("/threads", post(threads::create_thread).get(threads::list_threads))
+        .route("/threads/:thread_id", get(threads::get_thread).delete(threads::delete_thread))
+        .route(
+            "/threads/:thread_id/messages",
+            match drop_streams {
+                false => get(messages::list_messages).post(stream::send_message_stream),
+                true => post(stream::send_message_stream.layer(middleware::from_fn(drop_after_two_events))),
+            },
+        )
+        .route("/streams/:stream_id", get(stream::resume_message_stream))
+        .route("/threads/:thread_id/hydrate", get(hydrate::hydrate_thread))
+        .layer(CompressionLayer::new())
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    address
+}
+
+#[tokio::test]
+async fn test_message_stream_parses_into_typed_events() {
+    let client = serve().await;
+    let thread = client
+        .create_thread(&CreateThreadRequest::new("user-1").with_title("Greetings"))
+        .await
+        .unwrap();
+
+    let request = SendMessageRequest::new("user-1", "Hi!", "gpt-4o-mini");
+    let events: Vec<StreamEvent> = client
+        .send_message_stream(&thread.thread_id, &request)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    let unknown: Vec<_> = events.iter().filter(|event| matches!(event, StreamEvent::Unknown { .. })).collect();
+    assert!(unknown.is_empty(), "events the client doesn't know: {:?}", unknown);
+
+    let StreamEvent::ThreadInfo(info) = &events[0] else {
+        panic!("expected thread_info first, got {:?}", events[0]);
+    };
+    assert_eq!(info.title.as_deref(), Some("Greetings"));
+    assert_eq!(info.message_count, 1);
+    assert!(matches!(&events[1], StreamEvent::Init(run) if run.conversation_id == thread.thread_id));
+
+    let answer: String = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Message { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(answer, "Hello there");
+    assert!(events.iter().any(|event| matches!(event, StreamEvent::Usage { total_tokens: 14, .. })));
+    assert!(events.contains(&StreamEvent::Done {
+        status: "completed".to_string(),
+        finish_reason: Some("stop".to_string()),
+        cost_usd: None,
+    }));
+}
+
+#[tokio::test]
+async fn test_history_reads_back_through_the_client() {
+    let client = serve().await;
+    let thread = client.create_thread(&CreateThreadRequest::new("user-1")).await.unwrap();
+    let request = SendMessageRequest::new("user-1", "Hi!", "gpt-4o-mini");
+    let _: Vec<_> = client.send_message_stream(&thread.thread_id, &request).await.unwrap().collect().await;
+
+    let page = client.hydrate_thread(&thread.thread_id, None).await.unwrap();
+    let events: Vec<StreamEvent> = page.items.into_iter().map(|item| item.event).collect();
+    assert_eq!(events[0], StreamEvent::UserMessage { content: "Hi!".to_string() });
+    assert!(matches!(&events[1], StreamEvent::Message { content, .. } if content == "Hello there"));
+
+    let messages = client.list_messages(&thread.thread_id, None, None).await.unwrap();
+    assert_eq!(messages.messages.len(), 2);
+    let threads = client.list_threads("user-1", Some(10)).await.unwrap();
+    assert_eq!(threads.threads.len(), 1);
+
+    client.delete_thread(&thread.thread_id).await.unwrap();
+    let error = client.get_thread(&thread.thread_id).await.unwrap_err();
+    let error = error.downcast_ref::<ApiError>().expect("an API error");
+    assert_eq!(error.status, 404);
+    assert_eq!(error.problem.as_ref().map(|problem| problem.code.as_str()), Some("thread_not_found"));
+}
+
+#[tokio::test]
+async fn test_compact_stream_is_not_gzipped_and_coalesces_deltas() {
+    let address = serve_at().await;
+    let client = PraxisClient::new(format!("http://{}/", address), "");
+    let thread = client.create_thread(&CreateThreadRequest::new("user-1")).await.unwrap();
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/threads/{}/messages?wire=compact", address, thread.thread_id))
+        .header("accept-encoding", "gzip")
+        .json(&SendMessageRequest::new("user-1", "Hi!", "gpt-4o-mini"))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get("content-encoding").is_none());
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let body = response.text().await.unwrap();
+    assert!(body.contains("event: message\ndata: {\"c\":\"Hello there\""), "{}", body);
+    assert!(body.contains(r#"{"in":12,"out":2,"tot":14}"#), "{}", body);
+}
+
+#[tokio::test]
+async fn test_dropped_stream_resumes_after_the_last_event() {
+    let client = PraxisClient::new(format!("http://{}/", serve_with(true).await), "");
+    let thread = client.create_thread(&CreateThreadRequest::new("user-1")).await.unwrap();
+
+    let request = SendMessageRequest::new("user-1", "Hi!", "gpt-4o-mini");
+    let events: Vec<StreamEvent> = client
+        .send_message_stream(&thread.thread_id, &request)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    // Nothing is lost or sent twice across the reconnect
+    assert!(matches!(events[0], StreamEvent::ThreadInfo(_)));
+    assert_eq!(events.iter().filter(|event| matches!(event, StreamEvent::ThreadInfo(_))).count(), 1);
+    assert_eq!(events.iter().filter(|event| matches!(event, StreamEvent::Init(_))).count(), 1);
+    let answer: String = events
+        .iter()
+        .filter_map(|event| match event {
+            StreamEvent::Message { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(answer, "Hello there");
+    assert_eq!(events.last(), Some(&StreamEvent::Info {}));
+}
+
+#[tokio::test]
+async fn test_resume_rejects_unknown_streams_and_foreign_event_ids() {
+    let address = serve_at().await;
+    let http = reqwest::Client::new();
+
+    let response = http.get(format!("http://{}/streams/nope", address)).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+    let problem: praxis_client::Problem = response.json().await.unwrap();
+    assert_eq!(problem.code, "stream_not_found");
+
+    // A finished stream replays in full, and only takes its own event ids
+    let response = http
+        .post(format!("http://{}/threads/{}/messages", address, serve_thread(address).await))
+        .json(&SendMessageRequest::new("user-1", "Hi!", "gpt-4o-mini"))
+        .send()
+        .await
+        .unwrap();
+    let body = response.text().await.unwrap();
+    let first_id = body.lines().find_map(|line| line.strip_prefix("id: ")).unwrap().to_string();
+    let (stream_id, _) = first_id.rsplit_once(':').unwrap();
+
+    let replay = http.get(format!("http://{}/streams/{}", address, stream_id)).send().await.unwrap();
+    assert_eq!(replay.text().await.unwrap(), body);
+    let foreign = http
+        .get(format!("http://{}/streams/{}", address, stream_id))
+        .header("last-event-id", "another-stream:1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(foreign.status(), 400);
+}
+
+async fn serve_thread(address: std::net::SocketAddr) -> String {
+    let client = PraxisClient::new(format!("http://{}/", address), "");
+    client.create_thread(&CreateThreadRequest::new("user-1")).await.unwrap().thread_id
+}
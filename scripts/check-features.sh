@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Check the facade crate with each of its features on its own, then all together.
+#
+# Uses cargo-hack when installed (`cargo install cargo-hack`), otherwise
+# loops over the features listed in crates/praxis/Cargo.toml.
+set -euo pipefail
+
+cd "$(dirname "$0")/../crates/praxis"
+
+if cargo hack --version >/dev/null 2>&1; then
+    cargo hack check --each-feature --no-dev-deps "$@"
+    cargo check --all-features "$@"
+    exit 0
+fi
+
+features=$(sed -n '/^\[features\]/,/^\[/p' Cargo.toml | grep -oE '^[a-z][a-z0-9_-]*' | grep -v '^default$')
+
+echo "checking: no default features"
+cargo check --no-default-features "$@"
+for feature in $features; do
+    echo "checking: $feature"
+    cargo check --no-default-features --features "$feature" "$@"
+done
+echo "checking: all features"
+cargo check --all-features "$@"